@@ -0,0 +1,252 @@
+//! Command history: lines entered in PTY sessions, stored per worktree in
+//! `.command-history.jsonl` (the workspace root for main-workspace terminals) so a build
+//! command from weeks ago can be found and re-run.
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{get_window_workspace_config, path_location};
+use crate::state::PTY_MANAGER;
+use crate::types::{CommandHistoryEntry, WorkspaceConfig};
+use crate::utils::normalize_path;
+
+const HISTORY_FILE: &str = ".command-history.jsonl";
+
+/// Max history file size (512 KB); compacted to the newest half when exceeded
+const HISTORY_FILE_CAP: u64 = 512 * 1024;
+
+/// Commands longer than this are pastes or heredocs, not worth keeping
+const MAX_COMMAND_LEN: usize = 2000;
+
+/// Input typed so far on the current line of a session.
+#[derive(Default)]
+struct LineBuffer {
+    line: String,
+    /// The line was edited with cursor keys, tab completion or shell history, so what the
+    /// shell runs is unknown; it is not recorded.
+    unreliable: bool,
+}
+
+static LINE_BUFFERS: Lazy<Mutex<HashMap<String, LineBuffer>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// One stored line of the history file.
+#[derive(Serialize, Deserialize)]
+struct StoredCommand {
+    command: String,
+    cwd: String,
+    timestamp: String,
+}
+
+/// Feed terminal input; completed lines are saved to the history of the session's worktree.
+pub(crate) fn record_input(window_label: &str, pty_session_id: &str, data: &str) {
+    let commands = {
+        let Ok(mut buffers) = LINE_BUFFERS.lock() else {
+            return;
+        };
+        let buffer = buffers.entry(pty_session_id.to_string()).or_default();
+        feed(buffer, data)
+    };
+    if commands.is_empty() {
+        return;
+    }
+    let Some(cwd) = PTY_MANAGER
+        .lock()
+        .ok()
+        .and_then(|m| m.session_cwd(pty_session_id))
+    else {
+        return;
+    };
+    let Some((workspace_path, config)) = get_window_workspace_config(window_label) else {
+        return;
+    };
+    let path = history_dir_for_cwd(&workspace_path, &config, &cwd).join(HISTORY_FILE);
+    for command in commands {
+        if let Err(e) = append(&path, &command, &cwd) {
+            log::warn!("[history] Failed to write {}: {}", path.display(), e);
+            return;
+        }
+    }
+}
+
+/// Forget the partial line of a closed session.
+pub(crate) fn forget_session(pty_session_id: &str) {
+    if let Ok(mut buffers) = LINE_BUFFERS.lock() {
+        buffers.remove(pty_session_id);
+    }
+}
+
+/// Apply input to the line buffer and return the lines completed by Enter.
+fn feed(buffer: &mut LineBuffer, data: &str) -> Vec<String> {
+    // Bracketed paste markers wrap pasted text; the text itself is plain input
+    let data = data.replace("\x1b[200~", "").replace("\x1b[201~", "");
+    let mut commands = vec![];
+    let mut chars = data.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\r' | '\n' => {
+                let line = std::mem::take(&mut buffer.line);
+                let command = line.trim();
+                if !buffer.unreliable && !command.is_empty() && command.len() <= MAX_COMMAND_LEN {
+                    commands.push(command.to_string());
+                }
+                buffer.unreliable = false;
+            }
+            // Backspace
+            '\x7f' | '\x08' => {
+                buffer.line.pop();
+            }
+            // Ctrl-C / Ctrl-U discard the line
+            '\x03' | '\x15' => {
+                buffer.line.clear();
+                buffer.unreliable = false;
+            }
+            // Escape sequences (arrows, history) and tab completion change the line in ways
+            // we can't follow
+            '\x1b' => {
+                buffer.unreliable = true;
+                if chars.peek() == Some(&'[') || chars.peek() == Some(&'O') {
+                    chars.next();
+                    while let Some(&next) = chars.peek() {
+                        chars.next();
+                        if next.is_ascii_alphabetic() || next == '~' {
+                            break;
+                        }
+                    }
+                }
+            }
+            '\t' => buffer.unreliable = true,
+            c if c.is_control() => {}
+            c => buffer.line.push(c),
+        }
+    }
+    commands
+}
+
+fn append(path: &Path, command: &str, cwd: &str) -> std::io::Result<()> {
+    let entry = StoredCommand {
+        command: command.to_string(),
+        cwd: normalize_path(cwd),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    };
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+    if file.metadata()?.len() > HISTORY_FILE_CAP {
+        let content = fs::read_to_string(path)?;
+        let lines: Vec<&str> = content.lines().collect();
+        let keep = &lines[lines.len() / 2..];
+        fs::write(path, format!("{}\n", keep.join("\n")))?;
+    }
+    Ok(())
+}
+
+/// Where the history of a terminal at `cwd` is kept.
+fn history_dir_for_cwd(workspace_path: &str, config: &WorkspaceConfig, cwd: &str) -> PathBuf {
+    match path_location(workspace_path, config, cwd).0 {
+        Some(worktree) => Path::new(workspace_path)
+            .join(&config.worktrees_dir)
+            .join(worktree),
+        None => PathBuf::from(workspace_path),
+    }
+}
+
+/// History of a worktree (the main workspace when `worktree_name` is `None`), one entry per
+/// distinct command. Without a query the most recent come first; with one, entries are
+/// fuzzy-matched and ranked by match quality, then recency.
+pub(crate) fn command_history(
+    workspace_path: &str,
+    config: &WorkspaceConfig,
+    worktree_name: Option<&str>,
+    query: Option<&str>,
+    limit: usize,
+) -> Result<Vec<CommandHistoryEntry>, String> {
+    let dir = match worktree_name {
+        Some(name) => {
+            if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+                return Err(format!("Invalid worktree name: {}", name));
+            }
+            let dir = Path::new(workspace_path)
+                .join(&config.worktrees_dir)
+                .join(name);
+            if !dir.is_dir() {
+                return Err(format!("Worktree not found: {}", name));
+            }
+            dir
+        }
+        None => PathBuf::from(workspace_path),
+    };
+    let content = fs::read_to_string(dir.join(HISTORY_FILE)).unwrap_or_default();
+
+    // Newest occurrence of each command, with how often it ran
+    let mut entries: Vec<CommandHistoryEntry> = vec![];
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for stored in content
+        .lines()
+        .filter_map(|l| serde_json::from_str::<StoredCommand>(l).ok())
+    {
+        match index.get(&stored.command) {
+            Some(&i) => {
+                let entry = &mut entries[i];
+                entry.count += 1;
+                entry.cwd = stored.cwd;
+                entry.last_run_at = stored.timestamp;
+            }
+            None => {
+                index.insert(stored.command.clone(), entries.len());
+                entries.push(CommandHistoryEntry {
+                    command: stored.command,
+                    cwd: stored.cwd,
+                    worktree_name: worktree_name.map(str::to_string),
+                    last_run_at: stored.timestamp,
+                    count: 1,
+                });
+            }
+        }
+    }
+    entries.sort_by(|a, b| b.last_run_at.cmp(&a.last_run_at));
+
+    let query = query.map(str::trim).filter(|q| !q.is_empty());
+    if let Some(query) = query {
+        let mut scored: Vec<(i64, CommandHistoryEntry)> = entries
+            .into_iter()
+            .filter_map(|e| Some((fuzzy_score(query, &e.command)?, e)))
+            .collect();
+        // Stable sort keeps recency order among equal scores
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        entries = scored.into_iter().map(|(_, e)| e).collect();
+    }
+    entries.truncate(limit);
+    Ok(entries)
+}
+
+/// Case-insensitive match score of `query` in `candidate`: substrings beat scattered
+/// subsequences, earlier and tighter matches beat later and looser ones. `None` when the
+/// query's characters don't all appear in order.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let query: Vec<char> = query.to_lowercase().chars().collect();
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    if let Some(pos) = candidate
+        .windows(query.len())
+        .position(|w| w == query.as_slice())
+    {
+        return Some(10_000 - pos as i64);
+    }
+    let mut score = 0;
+    let mut last: Option<usize> = None;
+    let mut rest = candidate.iter().enumerate();
+    for q in &query {
+        let (i, _) = rest.find(|(_, c)| *c == q)?;
+        score -= match last {
+            Some(prev) => (i - prev - 1) as i64,
+            None => i as i64,
+        };
+        last = Some(i);
+    }
+    Some(score)
+}
@@ -0,0 +1,696 @@
+use std::path::PathBuf;
+
+use crate::config::get_window_workspace_config;
+use crate::state::{SHARE_STATE, WORKTREE_LOCKS};
+use crate::types::{ActionArg, ActionInfo};
+
+// ==================== 命令面板：操作注册表 ====================
+//
+// 前端命令面板和后续的 CLI 都从这里生成。新增操作时只需在 ACTIONS 中追加一项，
+// `id` 必须与 Tauri 命令名 / `/api/<id>` 路由名保持一致。
+
+/// 操作的作用范围，决定启用条件
+#[derive(Clone, Copy, PartialEq)]
+enum Scope {
+    /// 任何时候可用
+    Global,
+    /// 需要当前窗口已选择 workspace
+    Workspace,
+    /// 需要选中一个未归档、且未被其他窗口锁定的 worktree
+    Worktree,
+    /// 需要选中一个已归档的 worktree
+    ArchivedWorktree,
+    /// 需要选中项目（worktree 或主工作区中的项目）
+    Project,
+    /// 分享未开启且已选择 workspace 时可用
+    ShareInactive,
+    /// 分享开启时可用
+    ShareActive,
+}
+
+struct ArgSpec {
+    name: &'static str,
+    kind: &'static str,
+    required: bool,
+    description: &'static str,
+}
+
+struct ActionSpec {
+    id: &'static str,
+    title: &'static str,
+    category: &'static str,
+    scope: Scope,
+    args: &'static [ArgSpec],
+}
+
+const fn arg(
+    name: &'static str,
+    kind: &'static str,
+    required: bool,
+    description: &'static str,
+) -> ArgSpec {
+    ArgSpec {
+        name,
+        kind,
+        required,
+        description,
+    }
+}
+
+const ARG_NAME: ArgSpec = arg("name", "string", true, "Worktree 名称");
+const ARG_PATH: ArgSpec = arg("path", "path", true, "项目路径（支持 workspace 相对路径）");
+
+const ACTIONS: &[ActionSpec] = &[
+    // Workspace
+    ActionSpec {
+        id: "switch_workspace",
+        title: "切换 Workspace",
+        category: "workspace",
+        scope: Scope::Global,
+        args: &[arg("path", "path", true, "Workspace 路径")],
+    },
+    ActionSpec {
+        id: "add_workspace",
+        title: "导入已有 Workspace",
+        category: "workspace",
+        scope: Scope::Global,
+        args: &[
+            arg("name", "string", true, "Workspace 名称"),
+            arg("path", "path", true, "Workspace 路径"),
+        ],
+    },
+    ActionSpec {
+        id: "create_workspace",
+        title: "新建 Workspace",
+        category: "workspace",
+        scope: Scope::Global,
+        args: &[
+            arg("name", "string", true, "Workspace 名称"),
+            arg("path", "path", true, "父目录"),
+        ],
+    },
+    ActionSpec {
+        id: "validate_workspace_config",
+        title: "检查 Workspace 配置",
+        category: "workspace",
+        scope: Scope::Workspace,
+        args: &[],
+    },
+    ActionSpec {
+        id: "clone_project",
+        title: "克隆项目",
+        category: "workspace",
+        scope: Scope::Workspace,
+        args: &[arg("request", "object", true, "CloneProjectRequest")],
+    },
+    ActionSpec {
+        id: "get_disk_usage",
+        title: "查看磁盘占用",
+        category: "workspace",
+        scope: Scope::Workspace,
+        args: &[],
+    },
+    ActionSpec {
+        id: "export_workspace_manifest",
+        title: "导出 Workspace 清单",
+        category: "workspace",
+        scope: Scope::Workspace,
+        args: &[],
+    },
+    ActionSpec {
+        id: "import_workspace_manifest",
+        title: "导入 Workspace 清单",
+        category: "workspace",
+        scope: Scope::Workspace,
+        args: &[arg("manifest", "object", true, "WorkspaceManifest")],
+    },
+    // Worktree
+    ActionSpec {
+        id: "create_worktree",
+        title: "新建 Worktree",
+        category: "worktree",
+        scope: Scope::Workspace,
+        args: &[arg("request", "object", true, "CreateWorktreeRequest")],
+    },
+    ActionSpec {
+        id: "add_project_to_worktree",
+        title: "向 Worktree 添加项目",
+        category: "worktree",
+        scope: Scope::Worktree,
+        args: &[arg("request", "object", true, "AddProjectToWorktreeRequest")],
+    },
+    ActionSpec {
+        id: "get_worktree",
+        title: "获取 Worktree 状态",
+        category: "worktree",
+        scope: Scope::Workspace,
+        args: &[ARG_NAME],
+    },
+    ActionSpec {
+        id: "get_worktree_status",
+        title: "获取 Worktree 项目的 Git 状态",
+        category: "worktree",
+        scope: Scope::Workspace,
+        args: &[ARG_NAME, arg("project", "string", false, "只扫描该项目")],
+    },
+    ActionSpec {
+        id: "check_worktree_status",
+        title: "检查 Worktree 状态",
+        category: "worktree",
+        scope: Scope::Worktree,
+        args: &[ARG_NAME],
+    },
+    ActionSpec {
+        id: "set_worktree_meta",
+        title: "编辑 Worktree 描述",
+        category: "worktree",
+        scope: Scope::Worktree,
+        args: &[
+            ARG_NAME,
+            arg("description", "string", true, "描述"),
+            arg("ticketUrl", "string", true, "工单链接"),
+        ],
+    },
+    ActionSpec {
+        id: "get_worktree_ports",
+        title: "查看 Worktree 端口",
+        category: "worktree",
+        scope: Scope::Worktree,
+        args: &[ARG_NAME],
+    },
+    ActionSpec {
+        id: "set_worktree_labels",
+        title: "设置 Worktree 标签",
+        category: "worktree",
+        scope: Scope::Worktree,
+        args: &[ARG_NAME, arg("labels", "array", true, "标签列表")],
+    },
+    ActionSpec {
+        id: "set_worktree_env",
+        title: "设置 Worktree 终端环境变量",
+        category: "worktree",
+        scope: Scope::Worktree,
+        args: &[ARG_NAME, arg("env", "object", true, "变量名 → 值，替换全部已有变量；新开的终端生效")],
+    },
+    ActionSpec {
+        id: "archive_worktree",
+        title: "归档 Worktree",
+        category: "worktree",
+        scope: Scope::Worktree,
+        args: &[ARG_NAME],
+    },
+    ActionSpec {
+        id: "kill_worktree_processes",
+        title: "结束 Worktree 中的所有进程",
+        category: "worktree",
+        scope: Scope::Worktree,
+        args: &[arg("path", "path", true, "Worktree 路径（支持 workspace 相对路径）")],
+    },
+    ActionSpec {
+        id: "archive_worktrees",
+        title: "批量归档 Worktree",
+        category: "worktree",
+        scope: Scope::Workspace,
+        args: &[arg("names", "array", true, "Worktree 名称列表，未通过检查的会跳过")],
+    },
+    ActionSpec {
+        id: "restore_worktree",
+        title: "恢复 Worktree",
+        category: "worktree",
+        scope: Scope::ArchivedWorktree,
+        args: &[ARG_NAME],
+    },
+    ActionSpec {
+        id: "delete_archived_worktree",
+        title: "永久删除已归档 Worktree",
+        category: "worktree",
+        scope: Scope::ArchivedWorktree,
+        args: &[
+            ARG_NAME,
+            arg("deleteRemote", "boolean", false, "同时删除远程分支"),
+        ],
+    },
+    ActionSpec {
+        id: "rename_worktree_branch",
+        title: "重命名 Worktree 及分支",
+        category: "worktree",
+        scope: Scope::Worktree,
+        args: &[
+            arg("worktreeName", "string", true, "Worktree 名称"),
+            arg("newName", "string", true, "新名称（同时作为分支名）"),
+            arg("push", "boolean", false, "推送新分支并删除远程旧分支"),
+        ],
+    },
+    ActionSpec {
+        id: "sync_links",
+        title: "为所有 Worktree 补建软链接",
+        category: "worktree",
+        scope: Scope::Workspace,
+        args: &[],
+    },
+    ActionSpec {
+        id: "refresh_workspace",
+        title: "刷新工作区状态",
+        category: "worktree",
+        scope: Scope::Workspace,
+        args: &[],
+    },
+    ActionSpec {
+        id: "repair_worktrees",
+        title: "清理并修复 Worktree 登记",
+        category: "worktree",
+        scope: Scope::Workspace,
+        args: &[],
+    },
+    ActionSpec {
+        id: "get_sync_advice",
+        title: "同步建议",
+        category: "worktree",
+        scope: Scope::Worktree,
+        args: &[arg("worktreeName", "string", true, "Worktree 名称")],
+    },
+    ActionSpec {
+        id: "sync_safe_projects",
+        title: "同步可安全同步的项目",
+        category: "worktree",
+        scope: Scope::Worktree,
+        args: &[arg("worktreeName", "string", true, "Worktree 名称")],
+    },
+    ActionSpec {
+        id: "deploy_to_main",
+        title: "部署到主工作区",
+        category: "worktree",
+        scope: Scope::Worktree,
+        args: &[
+            arg("worktreeName", "string", true, "Worktree 名称"),
+            arg("dirtyAck", "string", false, "已确认的未提交修改指纹"),
+        ],
+    },
+    ActionSpec {
+        id: "exit_main_occupation",
+        title: "退出主工作区占用",
+        category: "worktree",
+        scope: Scope::Workspace,
+        args: &[arg("force", "boolean", false, "忽略未提交修改")],
+    },
+    ActionSpec {
+        id: "run_quick_command",
+        title: "执行终端快捷命令",
+        category: "worktree",
+        scope: Scope::Worktree,
+        args: &[
+            arg("worktreeName", "string", true, "Worktree 名称"),
+            arg("label", "string", true, "快捷命令名称（workspace 或项目配置 quick_commands）"),
+            arg("project", "string", false, "同名命令所属项目，用于区分项目级命令"),
+            arg("newSession", "boolean", false, "新开终端而不是复用默认终端"),
+        ],
+    },
+    ActionSpec {
+        id: "list_quick_commands",
+        title: "列出终端快捷命令",
+        category: "worktree",
+        scope: Scope::Workspace,
+        args: &[],
+    },
+    ActionSpec {
+        id: "list_running_processes",
+        title: "列出终端中运行的进程",
+        category: "worktree",
+        scope: Scope::Workspace,
+        args: &[arg("worktreeName", "string", false, "只列出该 worktree 的进程")],
+    },
+    ActionSpec {
+        id: "list_recordings",
+        title: "列出终端录制",
+        category: "worktree",
+        scope: Scope::Workspace,
+        args: &[arg("worktreeName", "string", false, "Worktree 名称，省略时列出主工作区的录制")],
+    },
+    ActionSpec {
+        id: "read_recording",
+        title: "读取终端录制",
+        category: "worktree",
+        scope: Scope::Workspace,
+        args: &[
+            arg("worktreeName", "string", false, "Worktree 名称，省略时为主工作区"),
+            arg("fileName", "string", true, "录制文件名（.cast）"),
+        ],
+    },
+    ActionSpec {
+        id: "get_command_history",
+        title: "查询终端命令历史",
+        category: "worktree",
+        scope: Scope::Workspace,
+        args: &[
+            arg("worktreeName", "string", false, "Worktree 名称，省略时为主工作区"),
+            arg("query", "string", false, "模糊搜索关键字"),
+            arg("limit", "number", false, "最多返回条数，默认 200"),
+        ],
+    },
+    ActionSpec {
+        id: "run_task",
+        title: "运行项目任务",
+        category: "worktree",
+        scope: Scope::Worktree,
+        args: &[
+            arg("worktreeName", "string", true, "Worktree 名称"),
+            arg("project", "string", true, "项目名称"),
+            arg("task", "string", true, "任务名称（项目配置 tasks 中的键）"),
+        ],
+    },
+    ActionSpec {
+        id: "open_terminal_preset",
+        title: "打开终端预设",
+        category: "worktree",
+        scope: Scope::Worktree,
+        args: &[
+            arg("worktreeName", "string", true, "Worktree 名称"),
+            arg("project", "string", true, "项目名称"),
+            arg("preset", "string", true, "预设名称（项目配置 terminal_presets 中的 name）"),
+        ],
+    },
+    ActionSpec {
+        id: "compose_status",
+        title: "查看 Docker Compose 状态",
+        category: "worktree",
+        scope: Scope::Worktree,
+        args: &[ARG_NAME, arg("project", "string", true, "项目名称")],
+    },
+    ActionSpec {
+        id: "compose_up",
+        title: "启动 Docker Compose 服务",
+        category: "worktree",
+        scope: Scope::Worktree,
+        args: &[ARG_NAME, arg("project", "string", true, "项目名称")],
+    },
+    ActionSpec {
+        id: "compose_down",
+        title: "停止 Docker Compose 服务",
+        category: "worktree",
+        scope: Scope::Worktree,
+        args: &[ARG_NAME, arg("project", "string", true, "项目名称")],
+    },
+    // Git
+    ActionSpec {
+        id: "switch_branch",
+        title: "切换分支",
+        category: "git",
+        scope: Scope::Project,
+        args: &[arg("request", "object", true, "SwitchBranchRequest")],
+    },
+    ActionSpec {
+        id: "fetch_all_projects",
+        title: "并发拉取所有项目远程",
+        category: "git",
+        scope: Scope::Workspace,
+        args: &[],
+    },
+    ActionSpec {
+        id: "fetch_project_remote",
+        title: "拉取远程",
+        category: "git",
+        scope: Scope::Project,
+        args: &[ARG_PATH],
+    },
+    ActionSpec {
+        id: "sync_with_base_branch",
+        title: "同步 Base 分支",
+        category: "git",
+        scope: Scope::Project,
+        args: &[ARG_PATH, arg("baseBranch", "string", true, "Base 分支")],
+    },
+    ActionSpec {
+        id: "amend_commit",
+        title: "修改最近一次提交",
+        category: "git",
+        scope: Scope::Project,
+        args: &[
+            ARG_PATH,
+            arg("newMessage", "string", false, "新的提交信息，为空则保留原信息"),
+            arg("includeStaged", "boolean", false, "同时带入已暂存的修改"),
+        ],
+    },
+    ActionSpec {
+        id: "push_to_remote",
+        title: "推送到远程",
+        category: "git",
+        scope: Scope::Project,
+        args: &[ARG_PATH],
+    },
+    ActionSpec {
+        id: "merge_to_test_branch",
+        title: "合并到测试分支",
+        category: "git",
+        scope: Scope::Project,
+        args: &[ARG_PATH, arg("testBranch", "string", true, "测试分支")],
+    },
+    ActionSpec {
+        id: "merge_to_base_branch",
+        title: "合并到 Base 分支",
+        category: "git",
+        scope: Scope::Project,
+        args: &[ARG_PATH, arg("baseBranch", "string", true, "Base 分支")],
+    },
+    ActionSpec {
+        id: "cherry_pick",
+        title: "Cherry-pick 提交",
+        category: "git",
+        scope: Scope::Project,
+        args: &[ARG_PATH, arg("commitShas", "string[]", true, "提交 SHA 列表")],
+    },
+    ActionSpec {
+        id: "continue_merge",
+        title: "继续合并（冲突已解决）",
+        category: "git",
+        scope: Scope::Project,
+        args: &[ARG_PATH],
+    },
+    ActionSpec {
+        id: "abort_merge",
+        title: "中止合并",
+        category: "git",
+        scope: Scope::Project,
+        args: &[ARG_PATH],
+    },
+    ActionSpec {
+        id: "normalize_line_endings",
+        title: "修复换行符差异",
+        category: "git",
+        scope: Scope::Project,
+        args: &[ARG_PATH],
+    },
+    ActionSpec {
+        id: "discard_changes",
+        title: "丢弃未提交的修改",
+        category: "git",
+        scope: Scope::Project,
+        args: &[
+            ARG_PATH,
+            arg("files", "string[]", true, "文件或目录列表，为空表示全部已跟踪的修改"),
+            arg("dryRun", "boolean", false, "仅返回将被还原的文件"),
+        ],
+    },
+    ActionSpec {
+        id: "clean_untracked",
+        title: "清理未跟踪文件",
+        category: "git",
+        scope: Scope::Project,
+        args: &[
+            ARG_PATH,
+            arg("paths", "string[]", true, "文件或目录列表，为空表示整个项目"),
+            arg("dryRun", "boolean", false, "仅返回将被删除的文件"),
+        ],
+    },
+    ActionSpec {
+        id: "create_pull_request",
+        title: "创建 PR",
+        category: "git",
+        scope: Scope::Project,
+        args: &[
+            ARG_PATH,
+            arg("baseBranch", "string", true, "目标分支"),
+            arg("title", "string", true, "标题"),
+            arg("body", "string", false, "描述"),
+        ],
+    },
+    ActionSpec {
+        id: "export_diff_zip",
+        title: "导出变更 ZIP",
+        category: "git",
+        scope: Scope::Project,
+        args: &[ARG_PATH],
+    },
+    // 工具
+    ActionSpec {
+        id: "open_in_terminal",
+        title: "在终端中打开",
+        category: "tools",
+        scope: Scope::Workspace,
+        args: &[arg("path", "path", true, "目录")],
+    },
+    ActionSpec {
+        id: "open_in_editor",
+        title: "在编辑器中打开",
+        category: "tools",
+        scope: Scope::Workspace,
+        args: &[arg("request", "object", true, "OpenEditorRequest")],
+    },
+    ActionSpec {
+        id: "open_in_devcontainer",
+        title: "在 Dev Container 中打开",
+        category: "tools",
+        scope: Scope::Project,
+        args: &[ARG_PATH],
+    },
+    ActionSpec {
+        id: "reveal_in_finder",
+        title: "在文件管理器中显示",
+        category: "tools",
+        scope: Scope::Workspace,
+        args: &[arg("path", "path", true, "目录")],
+    },
+    ActionSpec {
+        id: "open_log_dir",
+        title: "打开日志目录",
+        category: "tools",
+        scope: Scope::Global,
+        args: &[],
+    },
+    // 分享
+    ActionSpec {
+        id: "start_sharing",
+        title: "开始分享",
+        category: "share",
+        scope: Scope::ShareInactive,
+        args: &[
+            arg("port", "number", true, "端口"),
+            arg("password", "string", true, "访问密码"),
+        ],
+    },
+    ActionSpec {
+        id: "stop_sharing",
+        title: "停止分享",
+        category: "share",
+        scope: Scope::ShareActive,
+        args: &[],
+    },
+    ActionSpec {
+        id: "start_ngrok_tunnel",
+        title: "启动 ngrok 隧道",
+        category: "share",
+        scope: Scope::ShareActive,
+        args: &[],
+    },
+    ActionSpec {
+        id: "stop_ngrok_tunnel",
+        title: "停止 ngrok 隧道",
+        category: "share",
+        scope: Scope::ShareActive,
+        args: &[],
+    },
+];
+
+/// 根据当前窗口的选择状态计算某个作用范围是否可用，不可用时返回原因
+fn scope_disabled_reason(
+    scope: Scope,
+    window_label: &str,
+    workspace: Option<&(String, crate::types::WorkspaceConfig)>,
+    worktree_name: Option<&str>,
+    project_name: Option<&str>,
+    share_active: bool,
+) -> Option<String> {
+    match scope {
+        Scope::Global => None,
+        Scope::ShareInactive if share_active => Some("分享已开启".to_string()),
+        Scope::ShareActive if !share_active => Some("分享未开启".to_string()),
+        Scope::ShareActive => None,
+        _ if workspace.is_none() => Some("未选择 Workspace".to_string()),
+        Scope::Workspace | Scope::ShareInactive => None,
+        Scope::Project if project_name.is_none() => Some("未选择项目".to_string()),
+        Scope::Project => None,
+        Scope::Worktree | Scope::ArchivedWorktree => {
+            let Some(name) = worktree_name else {
+                return Some("未选择 Worktree".to_string());
+            };
+            let is_archived = name.ends_with(".archive");
+            if scope == Scope::ArchivedWorktree {
+                return (!is_archived).then(|| "Worktree 未归档".to_string());
+            }
+            if is_archived {
+                return Some("Worktree 已归档".to_string());
+            }
+            let (workspace_path, config) = workspace?;
+            let wt_path = PathBuf::from(workspace_path)
+                .join(&config.worktrees_dir)
+                .join(name);
+            if !wt_path.exists() {
+                return Some(format!("Worktree \"{}\" 不存在", name));
+            }
+            let locks = WORKTREE_LOCKS.lock().unwrap();
+            match locks.get(&(workspace_path.clone(), name.to_string())) {
+                Some(owner) if owner != window_label => {
+                    Some(format!("Worktree \"{}\" 已在其他窗口中打开", name))
+                }
+                _ => None,
+            }
+        }
+    }
+}
+
+fn action_info(spec: &ActionSpec, disabled_reason: Option<String>) -> ActionInfo {
+    ActionInfo {
+        id: spec.id.to_string(),
+        title: spec.title.to_string(),
+        category: spec.category.to_string(),
+        args: spec
+            .args
+            .iter()
+            .map(|a| ActionArg {
+                name: a.name.to_string(),
+                kind: a.kind.to_string(),
+                required: a.required,
+                description: a.description.to_string(),
+            })
+            .collect(),
+        enabled: disabled_reason.is_none(),
+        disabled_reason,
+    }
+}
+
+pub fn list_actions_impl(
+    window_label: &str,
+    worktree_name: Option<String>,
+    project_name: Option<String>,
+) -> Vec<ActionInfo> {
+    let workspace = get_window_workspace_config(window_label);
+    let share_active = SHARE_STATE.lock().unwrap().active;
+
+    ACTIONS
+        .iter()
+        .map(|spec| {
+            let disabled_reason = scope_disabled_reason(
+                spec.scope,
+                window_label,
+                workspace.as_ref(),
+                worktree_name.as_deref(),
+                project_name.as_deref(),
+                share_active,
+            );
+            action_info(spec, disabled_reason)
+        })
+        .collect()
+}
+
+/// 全部操作的说明（不检查可用性），供 OpenAPI 文档使用
+pub(crate) fn action_docs() -> Vec<ActionInfo> {
+    ACTIONS.iter().map(|spec| action_info(spec, None)).collect()
+}
+
+#[tauri::command]
+pub(crate) fn list_actions(
+    window: tauri::Window,
+    worktree_name: Option<String>,
+    project_name: Option<String>,
+) -> Vec<ActionInfo> {
+    list_actions_impl(window.label(), worktree_name, project_name)
+}
@@ -1,15 +1,31 @@
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
-use crate::config::{get_window_workspace_config, save_workspace_config_internal};
+use crate::config::{
+    get_window_workspace_config, merge_strategy_for_path, remote_for_path, resolve_workspace_path,
+    save_workspace_config_internal,
+};
 use crate::git_ops;
-use crate::types::{CloneProjectRequest, ProjectConfig, SwitchBranchRequest};
-use crate::utils::{normalize_path, parse_repo_url};
+use crate::notifier;
+use crate::oplog::{self, LoggedCommand};
+use crate::types::{default_remote, CloneProjectRequest, ProjectConfig, SwitchBranchRequest};
+use crate::utils::{begin_git_operation, normalize_path, parse_repo_url};
 
 // ==================== Tauri 命令：Git 操作 ====================
 
 #[tauri::command]
-pub(crate) fn switch_branch(request: SwitchBranchRequest) -> Result<(), String> {
+pub(crate) fn switch_branch(
+    window: tauri::Window,
+    mut request: SwitchBranchRequest,
+) -> Result<(), String> {
+    request.project_path = resolve_workspace_path(window.label(), &request.project_path)?;
+    crate::commands::worktree::guard_main_project_switch(
+        window.label(),
+        &request.project_path,
+        request.dirty_ack.as_deref(),
+    )?;
+    let _guard = begin_git_operation("switch_branch")?;
+    let remote = remote_for_path(window.label(), &request.project_path);
     log::info!(
         "[git] Switching branch: path='{}', target='{}'",
         request.project_path, request.branch
@@ -25,11 +41,11 @@ pub(crate) fn switch_branch(request: SwitchBranchRequest) -> Result<(), String>
     }
 
     // Step 1: Fetch to ensure we have latest refs
-    log::info!("[git] Step 1/3: git fetch origin");
+    log::info!("[git] Step 1/3: git fetch {}", remote);
     let fetch_output = Command::new("git")
-        .args(["fetch", "origin"])
+        .args(["fetch", &remote])
         .current_dir(&path)
-        .output()
+        .logged_output()
         .map_err(|e| format!("Failed to fetch: {}", e))?;
 
     if !fetch_output.status.success() {
@@ -39,7 +55,7 @@ pub(crate) fn switch_branch(request: SwitchBranchRequest) -> Result<(), String>
             String::from_utf8_lossy(&fetch_output.stderr)
         );
     } else {
-        log::info!("[git] Step 1/3: git fetch origin succeeded");
+        log::info!("[git] Step 1/3: git fetch {} succeeded", remote);
     }
 
     // Step 2: Checkout the branch
@@ -47,7 +63,7 @@ pub(crate) fn switch_branch(request: SwitchBranchRequest) -> Result<(), String>
     let checkout_output = Command::new("git")
         .args(["checkout", &request.branch])
         .current_dir(&path)
-        .output()
+        .logged_output()
         .map_err(|e| format!("Failed to checkout: {}", e))?;
 
     if !checkout_output.status.success() {
@@ -58,18 +74,22 @@ pub(crate) fn switch_branch(request: SwitchBranchRequest) -> Result<(), String>
     log::info!("[git] Step 2/3: git checkout {} succeeded", request.branch);
 
     // Step 3: Pull latest changes
-    log::info!("[git] Step 3/3: git pull origin {}", request.branch);
+    log::info!("[git] Step 3/3: git pull {} {}", remote, request.branch);
     let pull_output = Command::new("git")
-        .args(["pull", "origin", &request.branch])
+        .args(["pull", &remote, &request.branch])
         .current_dir(&path)
-        .output()
+        .logged_output()
         .map_err(|e| format!("Failed to pull: {}", e))?;
 
     if !pull_output.status.success() {
         let stderr = String::from_utf8_lossy(&pull_output.stderr);
         log::warn!("[git] Step 3/3: git pull failed (non-critical): {}", stderr);
     } else {
-        log::info!("[git] Step 3/3: git pull origin {} succeeded", request.branch);
+        log::info!(
+            "[git] Step 3/3: git pull {} {} succeeded",
+            remote,
+            request.branch
+        );
     }
 
     log::info!(
@@ -80,6 +100,7 @@ pub(crate) fn switch_branch(request: SwitchBranchRequest) -> Result<(), String>
 }
 
 pub fn clone_project_impl(window_label: &str, request: CloneProjectRequest) -> Result<(), String> {
+    let _guard = begin_git_operation("clone_project")?;
     let (workspace_path, mut config) =
         get_window_workspace_config(window_label).ok_or("No workspace selected")?;
 
@@ -112,7 +133,8 @@ pub fn clone_project_impl(window_label: &str, request: CloneProjectRequest) -> R
     log::info!("[git] Step 1/3: git clone to {}", target_path.display());
     let clone_output = Command::new("git")
         .args(["clone", &git_url, target_path.to_str().unwrap()])
-        .output()
+        .env("GIT_LFS_SKIP_SMUDGE", "1")
+        .logged_output()
         .map_err(|e| format!("Failed to clone repository: {}", e))?;
 
     if !clone_output.status.success() {
@@ -127,7 +149,8 @@ pub fn clone_project_impl(window_label: &str, request: CloneProjectRequest) -> R
     let checkout_output = Command::new("git")
         .args(["checkout", &request.base_branch])
         .current_dir(&target_path)
-        .output()
+        .env("GIT_LFS_SKIP_SMUDGE", "1")
+        .logged_output()
         .map_err(|e| format!("Failed to checkout base branch: {}", e))?;
 
     if !checkout_output.status.success() {
@@ -139,15 +162,28 @@ pub fn clone_project_impl(window_label: &str, request: CloneProjectRequest) -> R
         log::info!("[git] Step 2/3: Checked out base branch '{}'", request.base_branch);
     }
 
-    // Step 3: Add project to config
-    log::info!("[git] Step 3/3: Adding project '{}' to workspace config", request.name);
-    config.projects.push(ProjectConfig {
+    let project = ProjectConfig {
         name: request.name.clone(),
         base_branch: request.base_branch,
         test_branch: request.test_branch,
         merge_strategy: request.merge_strategy,
         linked_folders: request.linked_folders,
-    });
+        copy_rules: vec![],
+        status_excludes: vec![],
+        skip_lfs: request.skip_lfs,
+        sparse_paths: vec![],
+        remote: default_remote(),
+        quick_commands: vec![],
+        tasks: Default::default(),
+        terminal_presets: vec![],
+        shell: None,
+        env: Default::default(),
+    };
+    crate::commands::worktree::sync_lfs_objects(&target_path, &project);
+
+    // Step 3: Add project to config
+    log::info!("[git] Step 3/3: Adding project '{}' to workspace config", request.name);
+    config.projects.push(project);
 
     save_workspace_config_internal(&workspace_path, &config)?;
 
@@ -164,74 +200,306 @@ pub(crate) fn clone_project(
 }
 
 // ==================== Tauri 命令：Git 高级操作 ====================
+//
+// `path` 可以是绝对路径，也可以是相对 workspace 根目录的路径（见 resolve_workspace_path）
+
+#[tauri::command]
+pub(crate) fn sync_with_base_branch(
+    window: tauri::Window,
+    path: String,
+    base_branch: String,
+) -> Result<String, String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    let strategy = merge_strategy_for_path(window.label(), &resolved);
+    let remote = remote_for_path(window.label(), &resolved);
+    git_ops::sync_with_base_branch(Path::new(&resolved), &remote, &base_branch, strategy)
+}
+
+#[tauri::command]
+pub(crate) fn push_to_remote(window: tauri::Window, path: String) -> Result<String, String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    let remote = remote_for_path(window.label(), &resolved);
+    git_ops::push_to_remote(Path::new(&resolved), &remote)
+}
+
+#[tauri::command]
+pub(crate) fn amend_commit(
+    window: tauri::Window,
+    path: String,
+    new_message: Option<String>,
+    include_staged: Option<bool>,
+) -> Result<String, String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    let remote = remote_for_path(window.label(), &resolved);
+    git_ops::amend_commit(
+        Path::new(&resolved),
+        &remote,
+        new_message.as_deref().unwrap_or(""),
+        include_staged.unwrap_or(false),
+    )
+}
+
+#[tauri::command]
+pub(crate) fn merge_to_test_branch(
+    window: tauri::Window,
+    path: String,
+    test_branch: String,
+    keep_conflicts: Option<bool>,
+) -> Result<String, String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    let remote = remote_for_path(window.label(), &resolved);
+    merge_to_test_branch_internal(
+        Path::new(&resolved),
+        &remote,
+        &test_branch,
+        keep_conflicts.unwrap_or(false),
+    )
+}
+
+/// 合并到测试分支并发送 merge_to_test_* 频道通知（Tauri 命令和 HTTP 共用）
+pub fn merge_to_test_branch_internal(
+    path: &Path,
+    remote: &str,
+    test_branch: &str,
+    keep_conflicts: bool,
+) -> Result<String, String> {
+    // 合并过程中会切换分支，先记下当前分支
+    let branch = git2::Repository::open(path).ok().and_then(|repo| {
+        let head = repo.head().ok()?;
+        head.shorthand().map(str::to_string)
+    });
+    let result = git_ops::merge_to_test_branch(path, remote, test_branch, keep_conflicts);
+    notifier::notify_merge_to_test(path, branch.as_deref(), test_branch, &result);
+    result
+}
+
+#[tauri::command]
+pub(crate) fn merge_to_base_branch(
+    window: tauri::Window,
+    path: String,
+    base_branch: String,
+) -> Result<String, String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    let strategy = merge_strategy_for_path(window.label(), &resolved);
+    let remote = remote_for_path(window.label(), &resolved);
+    git_ops::merge_to_base_branch(Path::new(&resolved), &remote, &base_branch, strategy)
+}
+
+#[tauri::command]
+pub(crate) async fn cherry_pick(
+    window: tauri::Window,
+    path: String,
+    commit_shas: Vec<String>,
+) -> Result<String, String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    tokio::task::spawn_blocking(move || {
+        git_ops::cherry_pick_commits(Path::new(&resolved), &commit_shas)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+    .and_then(|r| r)
+}
+
+#[tauri::command]
+pub(crate) fn list_merge_conflicts(
+    window: tauri::Window,
+    path: String,
+) -> Result<git_ops::MergeConflictState, String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    git_ops::list_merge_conflicts(Path::new(&resolved))
+}
 
 #[tauri::command]
-pub(crate) fn sync_with_base_branch(path: String, base_branch: String) -> Result<String, String> {
-    let normalized = normalize_path(&path);
-    git_ops::sync_with_base_branch(Path::new(&normalized), &base_branch)
+pub(crate) fn resolve_conflict(
+    window: tauri::Window,
+    path: String,
+    file: String,
+    resolution: String,
+) -> Result<(), String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    git_ops::resolve_conflict(Path::new(&resolved), &file, &resolution)
 }
 
 #[tauri::command]
-pub(crate) fn push_to_remote(path: String) -> Result<String, String> {
-    let normalized = normalize_path(&path);
-    git_ops::push_to_remote(Path::new(&normalized))
+pub(crate) async fn continue_merge(window: tauri::Window, path: String) -> Result<String, String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    tokio::task::spawn_blocking(move || git_ops::continue_merge(Path::new(&resolved)))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r)
+}
+
+#[tauri::command]
+pub(crate) fn abort_merge(window: tauri::Window, path: String) -> Result<String, String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    git_ops::abort_merge(Path::new(&resolved))
 }
 
 #[tauri::command]
-pub(crate) fn merge_to_test_branch(path: String, test_branch: String) -> Result<String, String> {
-    let normalized = normalize_path(&path);
-    git_ops::merge_to_test_branch(Path::new(&normalized), &test_branch)
+pub(crate) async fn normalize_line_endings(
+    window: tauri::Window,
+    path: String,
+) -> Result<usize, String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    tokio::task::spawn_blocking(move || git_ops::normalize_line_endings(Path::new(&resolved)))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub(crate) async fn discard_changes(
+    window: tauri::Window,
+    path: String,
+    files: Vec<String>,
+    dry_run: Option<bool>,
+) -> Result<Vec<String>, String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    tokio::task::spawn_blocking(move || {
+        git_ops::discard_changes(Path::new(&resolved), &files, dry_run.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub(crate) async fn clean_untracked(
+    window: tauri::Window,
+    path: String,
+    paths: Vec<String>,
+    dry_run: Option<bool>,
+) -> Result<Vec<String>, String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    tokio::task::spawn_blocking(move || {
+        git_ops::clean_untracked(Path::new(&resolved), &paths, dry_run.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
 }
 
 #[tauri::command]
-pub(crate) fn merge_to_base_branch(path: String, base_branch: String) -> Result<String, String> {
-    let normalized = normalize_path(&path);
-    git_ops::merge_to_base_branch(Path::new(&normalized), &base_branch)
+pub(crate) fn get_branch_diff_stats(
+    window: tauri::Window,
+    path: String,
+    base_branch: String,
+) -> git_ops::BranchDiffStats {
+    let resolved =
+        resolve_workspace_path(window.label(), &path).unwrap_or_else(|_| normalize_path(&path));
+    let remote = remote_for_path(window.label(), &resolved);
+    git_ops::get_branch_diff_stats(Path::new(&resolved), &remote, &base_branch)
 }
 
 #[tauri::command]
-pub(crate) fn get_branch_diff_stats(path: String, base_branch: String) -> git_ops::BranchDiffStats {
-    let normalized = normalize_path(&path);
-    git_ops::get_branch_diff_stats(Path::new(&normalized), &base_branch)
+pub(crate) async fn get_file_diff(
+    window: tauri::Window,
+    path: String,
+    file: String,
+    base_ref: Option<String>,
+) -> Result<git_ops::FileDiff, String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    tokio::task::spawn_blocking(move || {
+        git_ops::get_file_diff(Path::new(&resolved), &file, base_ref.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+    .and_then(|r| r)
 }
 
 #[tauri::command]
 pub(crate) fn create_pull_request(
+    window: tauri::Window,
     path: String,
     base_branch: String,
     title: String,
     body: String,
 ) -> Result<String, String> {
-    let normalized = normalize_path(&path);
-    git_ops::create_pull_request(Path::new(&normalized), &base_branch, &title, &body)
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    let remote = remote_for_path(window.label(), &resolved);
+    git_ops::create_pull_request(Path::new(&resolved), &remote, &base_branch, &title, &body)
 }
 
+/// 分支 head commit 在 GitHub / GitLab 上的 CI 状态（按 commit 缓存）
 #[tauri::command]
-pub(crate) async fn fetch_project_remote(path: String) -> Result<(), String> {
-    let normalized = normalize_path(&path);
-    tokio::task::spawn_blocking(move || git_ops::fetch_remote(Path::new(&normalized)))
+pub(crate) async fn get_ci_status(
+    window: tauri::Window,
+    path: String,
+    branch: String,
+) -> Result<git_ops::CiStatus, String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    let remote = remote_for_path(window.label(), &resolved);
+    tokio::task::spawn_blocking(move || {
+        git_ops::get_ci_status(Path::new(&resolved), &remote, &branch)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub(crate) async fn fetch_project_remote(window: tauri::Window, path: String) -> Result<(), String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    let remote = remote_for_path(window.label(), &resolved);
+    tokio::task::spawn_blocking(move || git_ops::fetch_remote(Path::new(&resolved), &remote))
         .await
         .map_err(|e| format!("Task join error: {}", e))?
 }
 
 #[tauri::command]
 pub(crate) fn check_remote_branch_exists(
+    window: tauri::Window,
     path: String,
     branch_name: String,
 ) -> Result<bool, String> {
-    let normalized = normalize_path(&path);
-    git_ops::check_remote_branch_exists(Path::new(&normalized), &branch_name)
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    let remote = remote_for_path(window.label(), &resolved);
+    git_ops::check_remote_branch_exists(Path::new(&resolved), &remote, &branch_name)
+}
+
+#[tauri::command]
+pub(crate) fn get_remote_branches(window: tauri::Window, path: String) -> Result<Vec<String>, String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    let remote = remote_for_path(window.label(), &resolved);
+    git_ops::get_remote_branches(Path::new(&resolved), &remote)
+}
+
+/// 将相对基础分支变更的文件打包为 zip 并写入 `output_path`（由前端保存对话框选择）
+#[tauri::command]
+pub(crate) async fn export_diff_zip(
+    window: tauri::Window,
+    path: String,
+    base_branch: String,
+    output_path: String,
+) -> Result<git_ops::DiffArchiveSummary, String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    let remote = remote_for_path(window.label(), &resolved);
+    tokio::task::spawn_blocking(move || {
+        let (bytes, summary) =
+            git_ops::build_diff_archive(Path::new(&resolved), &remote, &base_branch)?;
+        std::fs::write(&output_path, bytes)
+            .map_err(|e| format!("Failed to write {}: {}", output_path, e))?;
+        log::info!("[git] Diff archive saved to {}", output_path);
+        Ok(summary)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+// ==================== 操作日志 ====================
+
+/// 获取某次操作的完整命令记录（命令、退出码、stdout/stderr）
+#[tauri::command]
+pub(crate) fn get_operation_log(id: String) -> Result<oplog::OperationLog, String> {
+    oplog::get_operation_log_inner(&id)
 }
 
+/// 列出最近的操作（最新在前）
 #[tauri::command]
-pub(crate) fn get_remote_branches(path: String) -> Result<Vec<String>, String> {
-    let normalized = normalize_path(&path);
-    git_ops::get_remote_branches(Path::new(&normalized))
+pub(crate) fn list_operation_logs() -> Vec<oplog::OperationLogSummary> {
+    oplog::list_operation_logs_inner()
 }
 
 // ==================== HTTP Server 共享接口 ====================
 
-pub fn switch_branch_internal(request: &SwitchBranchRequest) -> Result<(), String> {
+pub fn switch_branch_internal(request: &SwitchBranchRequest, remote: &str) -> Result<(), String> {
+    let _guard = begin_git_operation("switch_branch")?;
     log::info!(
         "[git] switch_branch_internal: path='{}', target='{}'",
         request.project_path, request.branch
@@ -244,27 +512,27 @@ pub fn switch_branch_internal(request: &SwitchBranchRequest) -> Result<(), Strin
             request.project_path
         ));
     }
-    log::info!("[git] Step 1/3: git fetch origin");
+    log::info!("[git] Step 1/3: git fetch {}", remote);
     let _ = Command::new("git")
-        .args(["fetch", "origin"])
+        .args(["fetch", remote])
         .current_dir(&path)
-        .output();
+        .logged_output();
     log::info!("[git] Step 2/3: git checkout {}", request.branch);
     let checkout_output = Command::new("git")
         .args(["checkout", &request.branch])
         .current_dir(&path)
-        .output()
+        .logged_output()
         .map_err(|e| format!("Failed to checkout: {}", e))?;
     if !checkout_output.status.success() {
         let stderr = String::from_utf8_lossy(&checkout_output.stderr);
         log::error!("[git] Step 2/3 FAILED: git checkout {}: {}", request.branch, stderr);
         return Err(format!("Failed to checkout {}: {}", request.branch, stderr));
     }
-    log::info!("[git] Step 3/3: git pull origin {}", request.branch);
+    log::info!("[git] Step 3/3: git pull {} {}", remote, request.branch);
     let _ = Command::new("git")
-        .args(["pull", "origin", &request.branch])
+        .args(["pull", remote, &request.branch])
         .current_dir(&path)
-        .output();
+        .logged_output();
     log::info!("[git] Successfully switched to branch '{}'", request.branch);
     Ok(())
 }
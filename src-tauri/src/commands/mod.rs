@@ -1,6 +1,8 @@
+pub(crate) mod actions;
 pub(crate) mod git;
 pub(crate) mod pty;
 pub(crate) mod sharing;
+pub(crate) mod shutdown;
 pub(crate) mod system;
 pub(crate) mod voice;
 pub(crate) mod window;
@@ -1,12 +1,100 @@
-use crate::state::PTY_MANAGER;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::command_history::{command_history, forget_session, record_input};
+use crate::commands::window::{note_terminal_activity, register_terminal_tab};
+use crate::commands::worktree::read_worktree_meta;
+use crate::config::{
+    get_pty_sessions_path, get_scrollback_dir, get_window_workspace_config, load_global_config,
+    path_location, resolve_workspace_path, save_global_config_internal, shell_for_path,
+};
+use crate::recordings::{
+    list_recordings as list_recordings_in, recording_file_name, recording_info, recording_path,
+    recordings_dir, recordings_dir_for_cwd,
+};
+use crate::state::{PTY_MANAGER, TERMINAL_STATES};
+use crate::types::{
+    CommandHistoryEntry, OutputSearchResult, PtyLimits, QuickCommand, QuickCommandRun,
+    RestoredTerminal, RunningProcess, SshHost, TerminalRecording, TerminalState, WorkspaceConfig,
+};
+use crate::utils::normalize_path;
+
+const DEFAULT_SCROLLBACK_LINES: usize = 1000;
+
+const DEFAULT_SEARCH_MATCHES: usize = 200;
+
+const DEFAULT_COMMAND_HISTORY_LIMIT: usize = 200;
+
+/// 空闲终端检查间隔
+const IDLE_CHECK_INTERVAL_SECS: u64 = 60;
+
+/// 环境变量名：字母或下划线开头，只含字母、数字、下划线
+pub(crate) fn is_valid_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// 新终端的环境变量，后面的覆盖前面的：
+/// 内置变量（WORKTREE_NAME、WORKTREE_PATH）→ 端口（PORT、ALLOCATED_PORT 等）→ 项目配置 env → worktree 元数据 env
+pub(crate) fn terminal_env(window_label: &str, cwd: &str) -> Vec<(String, String)> {
+    let Some((workspace_path, config)) = get_window_workspace_config(window_label) else {
+        return vec![];
+    };
+    let (worktree_name, project) = path_location(&workspace_path, &config, cwd);
+    let worktree_path = worktree_name.as_ref().map(|name| {
+        Path::new(&workspace_path)
+            .join(&config.worktrees_dir)
+            .join(name)
+    });
+
+    let mut env = vec![];
+    if let (Some(name), Some(path)) = (&worktree_name, &worktree_path) {
+        env.push(("WORKTREE_NAME".to_string(), name.clone()));
+        env.push((
+            "WORKTREE_PATH".to_string(),
+            normalize_path(&path.to_string_lossy()),
+        ));
+        env.extend(crate::ports::terminal_port_env(
+            &config,
+            &workspace_path,
+            name,
+            project.as_deref(),
+        ));
+    }
+    if let Some(project) = config
+        .projects
+        .iter()
+        .find(|p| Some(&p.name) == project.as_ref())
+    {
+        env.extend(project.env.clone());
+    }
+    if let Some(meta) = worktree_path.as_deref().and_then(read_worktree_meta) {
+        env.extend(meta.env);
+    }
+    env.retain(|(key, _)| {
+        let valid = is_valid_env_name(key);
+        if !valid {
+            log::warn!("[pty] Ignoring invalid environment variable name '{}'", key);
+        }
+        valid
+    });
+    env
+}
 
 #[tauri::command]
 pub(crate) fn pty_create(
+    window: tauri::Window,
     session_id: String,
     cwd: String,
     cols: u16,
     rows: u16,
 ) -> Result<(), String> {
+    let cwd = resolve_workspace_path(window.label(), &cwd)?;
+    let env = terminal_env(window.label(), &cwd);
+    let shell = shell_for_path(window.label(), &cwd);
     log::info!(
         "[pty] Creating session: id={}, cwd={}, cols={}, rows={}",
         session_id,
@@ -17,7 +105,7 @@ pub(crate) fn pty_create(
     let mut manager = PTY_MANAGER
         .lock()
         .map_err(|e| format!("Lock error: {}", e))?;
-    let result = manager.create_session(&session_id, &cwd, cols, rows);
+    let result = manager.create_session(&session_id, &cwd, cols, rows, &env, shell.as_ref());
     match &result {
         Ok(()) => log::info!("[pty] Session created: {}", session_id),
         Err(e) => log::error!("[pty] Failed to create session {}: {}", session_id, e),
@@ -25,12 +113,72 @@ pub(crate) fn pty_create(
     result
 }
 
-#[tauri::command]
-pub(crate) fn pty_write(session_id: String, data: String) -> Result<(), String> {
-    let manager = PTY_MANAGER
+/// 在已配置的 SSH 主机上打开终端。host 按名称或主机名匹配 GlobalConfig.ssh_hosts，
+/// 不接受未配置的主机；user / cwd 覆盖主机配置中的默认值
+pub fn pty_create_ssh_impl(
+    session_id: &str,
+    host: &str,
+    user: Option<String>,
+    cwd: Option<String>,
+    cols: u16,
+    rows: u16,
+) -> Result<(), String> {
+    let mut target = load_global_config()
+        .ssh_hosts
+        .into_iter()
+        .find(|h| h.name == host || h.host == host)
+        .ok_or_else(|| format!("SSH host not configured: {}", host))?;
+    if let Some(user) = user.filter(|u| !u.is_empty()) {
+        target.user = Some(user);
+    }
+    validate_ssh_host(&target)?;
+    let cwd = cwd.filter(|c| !c.is_empty()).or_else(|| target.cwd.clone());
+    log::info!(
+        "[pty] Creating SSH session: id={}, host={}, cwd={:?}, cols={}, rows={}",
+        session_id,
+        target.host,
+        cwd,
+        cols,
+        rows
+    );
+    let mut manager = PTY_MANAGER
         .lock()
         .map_err(|e| format!("Lock error: {}", e))?;
-    manager.write_to_session(&session_id, &data)
+    let result = manager.create_ssh_session(session_id, &target, cwd.as_deref(), cols, rows);
+    match &result {
+        Ok(()) => log::info!("[pty] SSH session created: {}", session_id),
+        Err(e) => log::error!("[pty] Failed to create SSH session {}: {}", session_id, e),
+    }
+    result
+}
+
+#[tauri::command]
+pub(crate) async fn pty_create_ssh(
+    session_id: String,
+    host: String,
+    user: Option<String>,
+    cwd: Option<String>,
+    cols: u16,
+    rows: u16,
+) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        pty_create_ssh_impl(&session_id, &host, user, cwd, cols, rows)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub(crate) fn pty_write(window: tauri::Window, session_id: String, data: String) -> Result<(), String> {
+    {
+        let manager = PTY_MANAGER
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        manager.write_to_session(&session_id, &data)?;
+    }
+    note_terminal_activity(window.label(), &session_id);
+    record_input(window.label(), &session_id, &data);
+    Ok(())
 }
 
 #[tauri::command]
@@ -56,6 +204,7 @@ pub(crate) fn pty_close(session_id: String) -> Result<(), String> {
         .lock()
         .map_err(|e| format!("Lock error: {}", e))?;
     let result = manager.close_session(&session_id);
+    forget_session(&session_id);
     match &result {
         Ok(()) => log::info!("[pty] Closed session: {}", session_id),
         Err(e) => log::error!("[pty] Failed to close session {}: {}", session_id, e),
@@ -71,6 +220,50 @@ pub(crate) fn pty_exists(session_id: String) -> Result<bool, String> {
     Ok(manager.has_session(&session_id))
 }
 
+/// 终端最近 `lines` 行输出（默认 1000），供重新加载的 xterm 回填历史；会话已不存在时读取持久化文件
+pub fn pty_get_scrollback_impl(session_id: &str, lines: Option<usize>) -> Result<String, String> {
+    let manager = PTY_MANAGER
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    manager.get_scrollback(session_id, lines.unwrap_or(DEFAULT_SCROLLBACK_LINES).max(1))
+}
+
+#[tauri::command]
+pub(crate) fn pty_get_scrollback(
+    session_id: String,
+    lines: Option<usize>,
+) -> Result<String, String> {
+    pty_get_scrollback_impl(&session_id, lines)
+}
+
+/// 在终端保留的输出中搜索（不区分大小写，忽略颜色等控制序列），返回匹配行号，
+/// 网页端据此跳转而无需先拉取全部历史；最多返回最近的 `max_matches` 条（默认 200）
+pub fn pty_search_output_impl(
+    session_id: &str,
+    pattern: &str,
+    max_matches: Option<usize>,
+) -> Result<OutputSearchResult, String> {
+    let manager = PTY_MANAGER
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    manager.search_output(
+        session_id,
+        pattern,
+        max_matches.unwrap_or(DEFAULT_SEARCH_MATCHES).max(1),
+    )
+}
+
+#[tauri::command]
+pub(crate) async fn pty_search_output(
+    session_id: String,
+    pattern: String,
+    max_matches: Option<usize>,
+) -> Result<OutputSearchResult, String> {
+    tokio::task::spawn_blocking(move || pty_search_output_impl(&session_id, &pattern, max_matches))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
 /// Close all PTY sessions whose working directory starts with the given path prefix.
 /// Used internally when archiving/deleting worktrees (see archive_worktree, delete_archived_worktree)
 /// and exposed via the HTTP server for remote access mode.
@@ -88,3 +281,819 @@ pub(crate) fn pty_close_by_path(path_prefix: String) -> Result<Vec<String>, Stri
     );
     Ok(closed)
 }
+
+// ==================== 快捷命令 ====================
+
+/// 与前端终端标签相同的 session id 规则：`pty-<path>`，其中 `/` 与 `#` 替换为 `-`
+fn terminal_session_id(terminal_path: &str) -> String {
+    format!("pty-{}", terminal_path.replace(['/', '#'], "-"))
+}
+
+/// workspace 级快捷命令在前，随后是各项目配置的快捷命令（`project` 指向所属项目）
+fn configured_quick_commands(config: &WorkspaceConfig) -> Vec<QuickCommand> {
+    let mut commands = config.quick_commands.clone();
+    for project in &config.projects {
+        commands.extend(project.quick_commands.iter().map(|c| QuickCommand {
+            project: Some(project.name.clone()),
+            ..c.clone()
+        }));
+    }
+    commands
+}
+
+/// 当前 workspace 可用的全部快捷命令；`remote_only` 时只返回允许远程客户端执行的命令
+pub fn list_quick_commands_impl(
+    window_label: &str,
+    remote_only: bool,
+) -> Result<Vec<QuickCommand>, String> {
+    let (_, config) = get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    Ok(configured_quick_commands(&config)
+        .into_iter()
+        .filter(|c| !remote_only || c.allow_remote)
+        .collect())
+}
+
+#[tauri::command]
+pub(crate) fn list_quick_commands(window: tauri::Window) -> Result<Vec<QuickCommand>, String> {
+    list_quick_commands_impl(window.label(), false)
+}
+
+/// 在 worktree 的终端中执行一条快捷命令。
+/// 同名命令按 `project` 区分（None 优先匹配 workspace 级命令）；`from_remote` 为 true 时
+/// 拒绝未开启 allow_remote 的命令。
+/// `new_session` 为 false 时复用该目录的默认终端（不存在则创建），为 true 时新开一个终端。
+pub fn run_quick_command_impl(
+    window_label: &str,
+    worktree_name: String,
+    label: String,
+    project: Option<String>,
+    new_session: bool,
+    from_remote: bool,
+) -> Result<QuickCommandRun, String> {
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let commands = configured_quick_commands(&config);
+    let quick = commands
+        .iter()
+        .filter(|c| c.label == label)
+        .find(|c| project.is_none() || c.project == project)
+        .ok_or_else(|| format!("快捷命令 \"{}\" 不存在", label))?;
+    if from_remote && !quick.allow_remote {
+        return Err(format!("快捷命令 \"{}\" 不允许远程执行", label));
+    }
+
+    if worktree_name.is_empty()
+        || worktree_name.contains(['/', '\\'])
+        || worktree_name == ".."
+        || worktree_name.ends_with(".archive")
+    {
+        return Err(format!("无效的 worktree: {}", worktree_name));
+    }
+    let worktree_path = std::path::Path::new(&workspace_path)
+        .join(&config.worktrees_dir)
+        .join(&worktree_name);
+    let cwd = match &quick.project {
+        Some(project) => worktree_path.join("projects").join(project),
+        None => worktree_path,
+    };
+    if !cwd.is_dir() {
+        return Err(format!("目录不存在: {}", cwd.display()));
+    }
+    let cwd = cwd.to_string_lossy().to_string();
+
+    log::info!("[pty] Running quick command '{}': {}", label, quick.command);
+    run_in_terminal(window_label, &cwd, &quick.command, new_session)
+}
+
+/// 把命令写入 `cwd` 的终端并回车执行，输出随终端流式返回。
+/// `new_session` 为 false 时复用该目录的默认终端（不存在则创建），为 true 时新开一个终端。
+pub(crate) fn run_in_terminal(
+    window_label: &str,
+    cwd: &str,
+    command: &str,
+    new_session: bool,
+) -> Result<QuickCommandRun, String> {
+    let terminal_path = if new_session {
+        let ts = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+        format!("{}#{}", cwd, ts)
+    } else {
+        cwd.to_string()
+    };
+    let session_id = terminal_session_id(&terminal_path);
+
+    log::info!("[pty] Writing command to session {}: {}", session_id, command);
+    let env = terminal_env(window_label, cwd);
+    let shell = shell_for_path(window_label, cwd);
+    {
+        let mut manager = PTY_MANAGER
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        if !manager.has_session(&session_id) {
+            // 前端打开该标签时会按实际大小 resize
+            manager.create_session(&session_id, cwd, 120, 30, &env, shell.as_ref())?;
+        }
+        // 终端预设可以不带启动命令，只打开终端
+        if !command.is_empty() {
+            manager.write_to_session(&session_id, &format!("{}\r", command))?;
+        }
+    }
+    note_terminal_activity(window_label, &session_id);
+    record_input(window_label, &session_id, &format!("{}\r", command));
+
+    Ok(QuickCommandRun {
+        session_id,
+        terminal_path,
+    })
+}
+
+#[tauri::command]
+pub(crate) fn run_quick_command(
+    window: tauri::Window,
+    worktree_name: String,
+    label: String,
+    project: Option<String>,
+    new_session: Option<bool>,
+) -> Result<QuickCommandRun, String> {
+    run_quick_command_impl(
+        window.label(),
+        worktree_name,
+        label,
+        project,
+        new_session.unwrap_or(false),
+        false,
+    )
+}
+
+// ==================== 项目任务 ====================
+
+/// 在 worktree 中某项目的目录下新开终端运行该项目配置的任务（`ProjectConfig.tasks`），
+/// 每次运行都是独立的终端，长时间运行的任务（如 dev server）互不干扰
+pub fn run_task_impl(
+    window_label: &str,
+    worktree_name: String,
+    project: String,
+    task: String,
+) -> Result<QuickCommandRun, String> {
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let command = config
+        .projects
+        .iter()
+        .find(|p| p.name == project)
+        .ok_or_else(|| format!("项目 \"{}\" 不存在", project))?
+        .tasks
+        .get(&task)
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .ok_or_else(|| format!("项目 \"{}\" 未配置任务 \"{}\"", project, task))?;
+
+    if worktree_name.is_empty()
+        || worktree_name.contains(['/', '\\'])
+        || worktree_name == ".."
+        || worktree_name.ends_with(".archive")
+    {
+        return Err(format!("无效的 worktree: {}", worktree_name));
+    }
+    let cwd = std::path::Path::new(&workspace_path)
+        .join(&config.worktrees_dir)
+        .join(&worktree_name)
+        .join("projects")
+        .join(&project);
+    if !cwd.is_dir() {
+        return Err(format!("目录不存在: {}", cwd.display()));
+    }
+    let cwd = cwd.to_string_lossy().to_string();
+
+    log::info!("[pty] Running task '{}' of project '{}': {}", task, project, command);
+    run_in_terminal(window_label, &cwd, &command, true)
+}
+
+#[tauri::command]
+pub(crate) fn run_task(
+    window: tauri::Window,
+    worktree_name: String,
+    project: String,
+    task: String,
+) -> Result<QuickCommandRun, String> {
+    run_task_impl(window.label(), worktree_name, project, task)
+}
+
+// ==================== 终端预设 ====================
+
+/// 按项目配置的终端预设（`ProjectConfig.terminal_presets`）在 worktree 中新开终端并运行启动命令，
+/// 同时登记到终端状态缓存，所有客户端都以预设名显示该标签
+pub fn open_terminal_preset_impl(
+    window_label: &str,
+    worktree_name: String,
+    project: String,
+    preset: String,
+) -> Result<QuickCommandRun, String> {
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let terminal_preset = config
+        .projects
+        .iter()
+        .find(|p| p.name == project)
+        .ok_or_else(|| format!("项目 \"{}\" 不存在", project))?
+        .terminal_presets
+        .iter()
+        .find(|p| p.name == preset)
+        .cloned()
+        .ok_or_else(|| format!("项目 \"{}\" 未配置终端预设 \"{}\"", project, preset))?;
+
+    if worktree_name.is_empty()
+        || worktree_name.contains(['/', '\\'])
+        || worktree_name == ".."
+        || worktree_name.ends_with(".archive")
+    {
+        return Err(format!("无效的 worktree: {}", worktree_name));
+    }
+    let mut cwd = std::path::Path::new(&workspace_path)
+        .join(&config.worktrees_dir)
+        .join(&worktree_name)
+        .join("projects")
+        .join(&project);
+    if let Some(suffix) = terminal_preset.cwd.as_deref().filter(|s| !s.is_empty()) {
+        let suffix = Path::new(suffix);
+        if !suffix
+            .components()
+            .all(|c| matches!(c, std::path::Component::Normal(_)))
+        {
+            return Err(format!("无效的预设目录: {}", suffix.display()));
+        }
+        cwd = cwd.join(suffix);
+    }
+    if !cwd.is_dir() {
+        return Err(format!("目录不存在: {}", cwd.display()));
+    }
+    let cwd = cwd.to_string_lossy().to_string();
+
+    log::info!(
+        "[pty] Opening terminal preset '{}' of project '{}': {}",
+        preset,
+        project,
+        terminal_preset.command
+    );
+    let run = run_in_terminal(window_label, &cwd, terminal_preset.command.trim(), true)?;
+    register_terminal_tab(
+        &workspace_path,
+        &worktree_name,
+        &run.terminal_path,
+        &terminal_preset.name,
+    );
+    Ok(run)
+}
+
+#[tauri::command]
+pub(crate) fn open_terminal_preset(
+    window: tauri::Window,
+    worktree_name: String,
+    project: String,
+    preset: String,
+) -> Result<QuickCommandRun, String> {
+    open_terminal_preset_impl(window.label(), worktree_name, project, preset)
+}
+
+// ==================== 运行中的进程 ====================
+
+/// 当前 workspace 各终端中运行的子进程（dev server、任务等）；
+/// 指定 `worktree_name` 时只返回该 worktree 的进程
+pub fn list_running_processes_impl(
+    window_label: &str,
+    worktree_name: Option<String>,
+) -> Result<Vec<RunningProcess>, String> {
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    Ok(crate::process_tracker::running_processes(
+        &workspace_path,
+        &config.worktrees_dir,
+        worktree_name.as_deref(),
+    ))
+}
+
+#[tauri::command]
+pub(crate) async fn list_running_processes(
+    window: tauri::Window,
+    worktree_name: Option<String>,
+) -> Result<Vec<RunningProcess>, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || list_running_processes_impl(&label, worktree_name))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+// ==================== 终端录制 ====================
+
+/// 开始录制终端会话，.cast 文件写入终端所在 worktree 的 .recordings 目录（主工作区终端写入 workspace 根目录）
+pub fn pty_start_recording_impl(
+    window_label: &str,
+    session_id: &str,
+    title: Option<String>,
+) -> Result<TerminalRecording, String> {
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let manager = PTY_MANAGER
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let cwd = manager
+        .session_cwd(session_id)
+        .ok_or_else(|| "Session not found".to_string())?;
+    let (dir, worktree_name) = recordings_dir_for_cwd(&workspace_path, &config, &cwd);
+    let title = title
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+    let path = dir.join(recording_file_name(title.as_deref()));
+    manager.start_recording(session_id, &path, title.as_deref())?;
+    log::info!(
+        "[pty] Recording session {} to {}",
+        session_id,
+        path.display()
+    );
+    recording_info(
+        &path,
+        worktree_name.as_deref(),
+        Some(session_id.to_string()),
+    )
+}
+
+/// 停止录制，返回录制文件信息
+pub fn pty_stop_recording_impl(
+    window_label: &str,
+    session_id: &str,
+) -> Result<TerminalRecording, String> {
+    let (path, cwd) = {
+        let manager = PTY_MANAGER
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        let path = manager.stop_recording(session_id)?;
+        (path, manager.session_cwd(session_id).unwrap_or_default())
+    };
+    log::info!(
+        "[pty] Stopped recording session {}: {}",
+        session_id,
+        path.display()
+    );
+    let worktree_name = get_window_workspace_config(window_label)
+        .and_then(|(ws, config)| recordings_dir_for_cwd(&ws, &config, &cwd).1);
+    recording_info(&path, worktree_name.as_deref(), None)
+}
+
+/// 终端会话当前的录制；未在录制时返回 None
+pub fn pty_get_recording_impl(
+    window_label: &str,
+    session_id: &str,
+) -> Result<Option<TerminalRecording>, String> {
+    let (path, cwd) = {
+        let manager = PTY_MANAGER
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        let Some((_, path)) = manager
+            .active_recordings()
+            .into_iter()
+            .find(|(id, _)| id == session_id)
+        else {
+            return Ok(None);
+        };
+        (path, manager.session_cwd(session_id).unwrap_or_default())
+    };
+    let worktree_name = get_window_workspace_config(window_label)
+        .and_then(|(ws, config)| recordings_dir_for_cwd(&ws, &config, &cwd).1);
+    recording_info(
+        &path,
+        worktree_name.as_deref(),
+        Some(session_id.to_string()),
+    )
+    .map(Some)
+}
+
+/// 列出 worktree 的终端录制（新的在前）；不指定 `worktree_name` 时列出主工作区的录制
+pub fn list_recordings_impl(
+    window_label: &str,
+    worktree_name: Option<String>,
+) -> Result<Vec<TerminalRecording>, String> {
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let dir = recordings_dir(&workspace_path, &config, worktree_name.as_deref())?;
+    let active = PTY_MANAGER
+        .lock()
+        .map(|m| m.active_recordings())
+        .unwrap_or_default();
+    Ok(list_recordings_in(&dir, worktree_name.as_deref(), &active))
+}
+
+/// 读取录制文件内容（asciicast v2，可直接用 asciinema play 回放）
+pub fn read_recording_impl(
+    window_label: &str,
+    worktree_name: Option<String>,
+    file_name: &str,
+) -> Result<String, String> {
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let dir = recordings_dir(&workspace_path, &config, worktree_name.as_deref())?;
+    let path = recording_path(&dir, file_name)?;
+    std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read recording {}: {}", path.display(), e))
+}
+
+#[tauri::command]
+pub(crate) fn pty_start_recording(
+    window: tauri::Window,
+    session_id: String,
+    title: Option<String>,
+) -> Result<TerminalRecording, String> {
+    pty_start_recording_impl(window.label(), &session_id, title)
+}
+
+#[tauri::command]
+pub(crate) fn pty_stop_recording(
+    window: tauri::Window,
+    session_id: String,
+) -> Result<TerminalRecording, String> {
+    pty_stop_recording_impl(window.label(), &session_id)
+}
+
+#[tauri::command]
+pub(crate) fn pty_get_recording(
+    window: tauri::Window,
+    session_id: String,
+) -> Result<Option<TerminalRecording>, String> {
+    pty_get_recording_impl(window.label(), &session_id)
+}
+
+#[tauri::command]
+pub(crate) async fn list_recordings(
+    window: tauri::Window,
+    worktree_name: Option<String>,
+) -> Result<Vec<TerminalRecording>, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || list_recordings_impl(&label, worktree_name))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub(crate) async fn read_recording(
+    window: tauri::Window,
+    worktree_name: Option<String>,
+    file_name: String,
+) -> Result<String, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || read_recording_impl(&label, worktree_name, &file_name))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+// ==================== 终端输出持久化 ====================
+
+/// 启动时按全局配置开启终端输出持久化
+pub(crate) fn init_scrollback_persistence() {
+    if load_global_config().persist_terminal_scrollback {
+        if let Ok(mut manager) = PTY_MANAGER.lock() {
+            manager.set_scrollback_dir(Some(get_scrollback_dir()));
+        }
+    }
+}
+
+pub(crate) fn get_scrollback_persist_enabled_inner() -> Result<bool, String> {
+    Ok(load_global_config().persist_terminal_scrollback)
+}
+
+/// 关闭时删除已持久化的终端输出
+pub(crate) fn set_scrollback_persist_enabled_inner(enabled: bool) -> Result<(), String> {
+    let mut config = load_global_config();
+    config.persist_terminal_scrollback = enabled;
+    save_global_config_internal(&config)?;
+    PTY_MANAGER
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .set_scrollback_dir(enabled.then(get_scrollback_dir));
+    log::info!("[pty] Scrollback persistence enabled: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn get_scrollback_persist_enabled() -> Result<bool, String> {
+    get_scrollback_persist_enabled_inner()
+}
+
+#[tauri::command]
+pub(crate) fn set_scrollback_persist_enabled(enabled: bool) -> Result<(), String> {
+    set_scrollback_persist_enabled_inner(enabled)
+}
+
+// ==================== 终端会话恢复 ====================
+
+/// 启动时载入上次运行留下的终端会话列表，之后会话的创建/关闭/调整大小都会写回该文件
+pub(crate) fn init_session_registry() {
+    if let Ok(mut manager) = PTY_MANAGER.lock() {
+        manager.set_session_registry(get_pty_sessions_path());
+    }
+}
+
+/// 由 session id 还原终端标签路径：复制的标签为 `<cwd>#<后缀>`
+fn terminal_path_for_session(session_id: &str, cwd: &str) -> String {
+    let base = terminal_session_id(cwd);
+    match session_id
+        .strip_prefix(&base)
+        .and_then(|rest| rest.strip_prefix('-'))
+    {
+        Some(suffix) if !suffix.is_empty() => format!("{}#{}", cwd, suffix),
+        _ => cwd.to_string(),
+    }
+}
+
+/// 在原目录重建上次运行时打开的终端（仅当前窗口工作区内、目录仍存在的），
+/// 并把标签写回终端状态缓存，切换到对应 worktree 时即可看到。未开启该功能时返回空列表
+pub fn pty_restore_sessions_impl(window_label: &str) -> Result<Vec<RestoredTerminal>, String> {
+    if !load_global_config().restore_terminal_sessions {
+        return Ok(vec![]);
+    }
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let snapshots = PTY_MANAGER
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .take_restorable(|s| Path::new(&s.cwd).starts_with(&workspace_path));
+
+    let mut restored = vec![];
+    for snapshot in snapshots {
+        if !Path::new(&snapshot.cwd).is_dir() {
+            continue;
+        }
+        let env = terminal_env(window_label, &snapshot.cwd);
+        let shell = shell_for_path(window_label, &snapshot.cwd);
+        let result = {
+            let mut manager = PTY_MANAGER
+                .lock()
+                .map_err(|e| format!("Lock error: {}", e))?;
+            if manager.has_session(&snapshot.id) {
+                continue;
+            }
+            manager.create_session(
+                &snapshot.id,
+                &snapshot.cwd,
+                snapshot.cols,
+                snapshot.rows,
+                &env,
+                shell.as_ref(),
+            )
+        };
+        if let Err(e) = result {
+            log::warn!("[pty] Failed to restore session {}: {}", snapshot.id, e);
+            continue;
+        }
+        restored.push(RestoredTerminal {
+            terminal_path: terminal_path_for_session(&snapshot.id, &snapshot.cwd),
+            worktree_name: path_location(&workspace_path, &config, &snapshot.cwd).0,
+            session_id: snapshot.id,
+        });
+    }
+
+    if let Ok(mut states) = TERMINAL_STATES.lock() {
+        for terminal in &restored {
+            let key = (
+                workspace_path.clone(),
+                terminal.worktree_name.clone().unwrap_or_default(),
+            );
+            let state = states.entry(key).or_insert_with(|| TerminalState {
+                activated_terminals: vec![],
+                active_terminal_tab: None,
+                terminal_visible: false,
+                client_id: None,
+                tab_labels: Default::default(),
+            });
+            if !state.activated_terminals.contains(&terminal.terminal_path) {
+                state
+                    .activated_terminals
+                    .push(terminal.terminal_path.clone());
+            }
+        }
+    }
+    log::info!(
+        "[pty] Restored {} terminal sessions in {}",
+        restored.len(),
+        workspace_path
+    );
+    Ok(restored)
+}
+
+#[tauri::command]
+pub(crate) async fn pty_restore_sessions(
+    window: tauri::Window,
+) -> Result<Vec<RestoredTerminal>, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || pty_restore_sessions_impl(&label))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+pub(crate) fn get_session_restore_enabled_inner() -> Result<bool, String> {
+    Ok(load_global_config().restore_terminal_sessions)
+}
+
+pub(crate) fn set_session_restore_enabled_inner(enabled: bool) -> Result<(), String> {
+    let mut config = load_global_config();
+    config.restore_terminal_sessions = enabled;
+    save_global_config_internal(&config)?;
+    log::info!("[pty] Terminal session restore enabled: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn get_session_restore_enabled() -> Result<bool, String> {
+    get_session_restore_enabled_inner()
+}
+
+#[tauri::command]
+pub(crate) fn set_session_restore_enabled(enabled: bool) -> Result<(), String> {
+    set_session_restore_enabled_inner(enabled)
+}
+
+// ==================== 终端资源限制 ====================
+
+fn apply_pty_limits(limits: PtyLimits) -> Result<(), String> {
+    let idle_timeout = (limits.idle_timeout_mins > 0)
+        .then(|| Duration::from_secs(limits.idle_timeout_mins as u64 * 60));
+    PTY_MANAGER
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .set_limits(limits.max_sessions as usize, idle_timeout);
+    Ok(())
+}
+
+/// 启动时按全局配置设置终端数上限，并启动后台线程定期关闭空闲终端
+pub(crate) fn start_pty_limits() {
+    if let Err(e) = apply_pty_limits(load_global_config().pty_limits) {
+        log::warn!("[pty] Failed to apply terminal limits: {}", e);
+    }
+    std::thread::spawn(|| loop {
+        std::thread::sleep(Duration::from_secs(IDLE_CHECK_INTERVAL_SECS));
+        let closed = match PTY_MANAGER.lock() {
+            Ok(mut manager) => manager.close_idle_sessions(),
+            Err(_) => continue,
+        };
+        for id in &closed {
+            forget_session(id);
+        }
+        if !closed.is_empty() {
+            log::info!("[pty] Closed idle sessions: {:?}", closed);
+        }
+    });
+}
+
+pub(crate) fn get_pty_limits_inner() -> Result<PtyLimits, String> {
+    Ok(load_global_config().pty_limits)
+}
+
+pub(crate) fn set_pty_limits_inner(limits: PtyLimits) -> Result<(), String> {
+    let mut config = load_global_config();
+    config.pty_limits = limits;
+    save_global_config_internal(&config)?;
+    apply_pty_limits(limits)?;
+    log::info!(
+        "[pty] Terminal limits: max {} sessions, idle timeout {} min",
+        limits.max_sessions,
+        limits.idle_timeout_mins
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn get_pty_limits() -> Result<PtyLimits, String> {
+    get_pty_limits_inner()
+}
+
+#[tauri::command]
+pub(crate) fn set_pty_limits(limits: PtyLimits) -> Result<(), String> {
+    set_pty_limits_inner(limits)
+}
+
+// ==================== SSH 主机 ====================
+
+/// 主机名和用户名会作为 ssh 参数传入，不能以 `-` 开头（否则会被当作选项）
+fn validate_ssh_host(host: &SshHost) -> Result<(), String> {
+    if host.name.trim().is_empty() || host.host.trim().is_empty() {
+        return Err("SSH host needs a name and a host".to_string());
+    }
+    let user = host.user.as_deref().unwrap_or("");
+    if host.host.starts_with('-') || host.host.contains(char::is_whitespace) {
+        return Err(format!("Invalid SSH host: {}", host.host));
+    }
+    if user.starts_with('-') || user.contains(|c: char| c == '@' || c.is_whitespace()) {
+        return Err(format!("Invalid SSH user: {}", user));
+    }
+    Ok(())
+}
+
+pub(crate) fn get_ssh_hosts_inner() -> Result<Vec<SshHost>, String> {
+    Ok(load_global_config().ssh_hosts)
+}
+
+pub(crate) fn set_ssh_hosts_inner(hosts: Vec<SshHost>) -> Result<(), String> {
+    for (i, host) in hosts.iter().enumerate() {
+        validate_ssh_host(host)?;
+        if hosts[..i].iter().any(|h| h.name == host.name) {
+            return Err(format!("Duplicate SSH host name: {}", host.name));
+        }
+    }
+    let mut config = load_global_config();
+    config.ssh_hosts = hosts;
+    save_global_config_internal(&config)?;
+    log::info!(
+        "[pty] SSH hosts updated: {} configured",
+        config.ssh_hosts.len()
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn get_ssh_hosts() -> Result<Vec<SshHost>, String> {
+    get_ssh_hosts_inner()
+}
+
+#[tauri::command]
+pub(crate) fn set_ssh_hosts(hosts: Vec<SshHost>) -> Result<(), String> {
+    set_ssh_hosts_inner(hosts)
+}
+
+/// worktree 的终端命令历史（worktree_name 为 None 时为主工作区），query 不为空时模糊搜索
+pub fn get_command_history_impl(
+    window_label: &str,
+    worktree_name: Option<String>,
+    query: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<CommandHistoryEntry>, String> {
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    command_history(
+        &workspace_path,
+        &config,
+        worktree_name.as_deref(),
+        query.as_deref(),
+        limit.unwrap_or(DEFAULT_COMMAND_HISTORY_LIMIT),
+    )
+}
+
+#[tauri::command]
+pub(crate) async fn get_command_history(
+    window: tauri::Window,
+    worktree_name: Option<String>,
+    query: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<CommandHistoryEntry>, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || {
+        get_command_history_impl(&label, worktree_name, query, limit)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(host: &str, user: Option<&str>) -> SshHost {
+        SshHost {
+            name: "dev".to_string(),
+            host: host.to_string(),
+            user: user.map(str::to_string),
+            port: None,
+            identity_file: None,
+            cwd: None,
+        }
+    }
+
+    #[test]
+    fn ssh_host_accepts_hosts_aliases_and_users() {
+        assert!(validate_ssh_host(&host("dev.example.com", None)).is_ok());
+        assert!(validate_ssh_host(&host("10.0.0.5", Some("deploy"))).is_ok());
+        assert!(validate_ssh_host(&host("my-alias", Some(""))).is_ok());
+    }
+
+    #[test]
+    fn ssh_host_requires_a_name_and_host() {
+        assert!(validate_ssh_host(&host("", None)).is_err());
+        assert!(validate_ssh_host(&host("  ", None)).is_err());
+        let mut unnamed = host("dev.example.com", None);
+        unnamed.name = " ".to_string();
+        assert!(validate_ssh_host(&unnamed).is_err());
+    }
+
+    #[test]
+    fn ssh_host_rejects_option_injection() {
+        for bad in ["-oProxyCommand=sh", "dev example", "dev\texample"] {
+            assert!(validate_ssh_host(&host(bad, None)).is_err(), "{}", bad);
+        }
+        for bad in ["-oProxyCommand=sh", "root@evil", "de v", "de\tv"] {
+            assert!(
+                validate_ssh_host(&host("dev", Some(bad))).is_err(),
+                "{}",
+                bad
+            );
+        }
+    }
+}
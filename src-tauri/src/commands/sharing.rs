@@ -1,18 +1,23 @@
-use ngrok::config::ForwarderBuilder; // trait import: provides listen_and_forward()
-use ngrok::forwarder::Forwarder;
-use ngrok::tunnel::{EndpointInfo, HttpTunnel}; // EndpointInfo trait import: provides url()
-
 use crate::config::{get_window_workspace_path, load_global_config, save_global_config_internal};
 use crate::http_server;
+use crate::notifier;
 use crate::state::{
-    AUTHENTICATED_SESSIONS, CLIENT_NOTIFICATION_BROADCAST, CONNECTED_CLIENTS, SHARE_STATE,
-    TOKIO_RT,
+    API_RATE_LIMITER, AUTHENTICATED_SESSIONS, CLIENT_NOTIFICATION_BROADCAST, CONNECTED_CLIENTS,
+    EXPIRED_SESSIONS, QUARANTINED_SESSIONS, SHARE_ACTIVITY, SHARE_STATE, TOKIO_RT,
 };
 use crate::tls;
-use crate::types::{ConnectedClient, ShareStateInfo};
+use crate::totp;
+use crate::tunnel::{self, TunnelStatus};
+use crate::types::{
+    ApiToken, CloudflareTunnelConfig, ConnectedClient, CustomTlsConfig, FrpTunnelConfig,
+    NotifyEvent, RateLimitConfig, ShareActivity, ShareProfile, ShareRole, ShareStateInfo,
+    TotpEnrollment,
+};
 use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 // ==================== 分享功能命令 ====================
 
@@ -42,11 +47,58 @@ pub(crate) async fn get_last_share_password() -> Result<Option<String>, String>
     Ok(None)
 }
 
+/// All non-loopback IPv4 addresses: private, link-local, CGNAT (Tailscale 100.x), etc.
+fn lan_ip_addrs() -> Vec<IpAddr> {
+    let mut ips: Vec<IpAddr> = local_ip_address::list_afinet_netifas()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(_name, ip)| match ip {
+            IpAddr::V4(v4) if !v4.is_loopback() && !v4.is_unspecified() && !v4.is_multicast() => {
+                Some(ip)
+            }
+            _ => None,
+        })
+        .collect();
+    ips.sort();
+    ips.dedup();
+    ips
+}
+
+/// Addresses the server is reachable at: the bound interface, or every LAN IP when bound to all
+fn reachable_ips(bind_ip: Option<IpAddr>) -> Vec<IpAddr> {
+    match bind_ip {
+        Some(ip) if !ip.is_unspecified() => vec![ip],
+        _ => lan_ip_addrs(),
+    }
+}
+
+/// Share URLs for the given addresses (localhost is served over plain HTTP)
+fn share_urls(ips: &[IpAddr], port: u16) -> Vec<String> {
+    ips.iter()
+        .map(|ip| {
+            let scheme = if ip.is_loopback() { "http" } else { "https" };
+            format!("{}://{}", scheme, SocketAddr::new(*ip, port))
+        })
+        .collect()
+}
+
+fn parse_bind_address(bind_address: Option<&str>) -> Result<Option<IpAddr>, String> {
+    match bind_address.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(addr) => addr
+            .parse()
+            .map(Some)
+            .map_err(|_| format!("无效的监听地址: {}", addr)),
+        None => Ok(None),
+    }
+}
+
 /// Internal function to start LAN sharing. Can be called from Tauri command or from WMS tunnel auto-start.
+/// `profile` supplies bind address, role and allowed worktrees; None keeps the defaults.
 pub async fn start_sharing_internal(
     workspace_path: String,
     port: u16,
     password: String,
+    profile: Option<ShareProfile>,
 ) -> Result<String, String> {
     log::info!(
         "[sharing] Starting LAN sharing: workspace={}, port={}, password_len={}",
@@ -82,52 +134,46 @@ pub async fn start_sharing_internal(
         }
     }
 
+    let bind_ip = parse_bind_address(profile.as_ref().and_then(|p| p.bind_address.as_deref()))?;
+
     // Check if port is available
-    // Bind to 0.0.0.0 to allow LAN access (security handled by password auth)
-    let bind_addr = format!("0.0.0.0:{}", port);
-    if let Err(e) = tokio::net::TcpListener::bind(&bind_addr).await {
-        log::error!("[sharing] Port {} unavailable: {}", port, e);
+    // Bind to 0.0.0.0 by default to allow LAN access (security handled by password auth)
+    let bind_addr = SocketAddr::new(bind_ip.unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED)), port);
+    if let Err(e) = tokio::net::TcpListener::bind(bind_addr).await {
+        log::error!("[sharing] {} unavailable: {}", bind_addr, e);
         return Err(format!("端口 {} 已被占用: {}", port, e));
     }
 
     // Collect all LAN IPs for multi-address display
-    // Include all non-loopback IPv4: private, link-local, CGNAT (Tailscale 100.x), etc.
-    let mut lan_ips: Vec<std::net::IpAddr> = local_ip_address::list_afinet_netifas()
-        .unwrap_or_default()
-        .into_iter()
-        .filter_map(|(_name, ip)| match ip {
-            std::net::IpAddr::V4(v4)
-                if !v4.is_loopback() && !v4.is_unspecified() && !v4.is_multicast() =>
-            {
-                Some(ip)
-            }
-            _ => None,
-        })
-        .collect();
-    lan_ips.sort();
-    lan_ips.dedup();
+    let lan_ips = reachable_ips(bind_ip);
     log::info!(
         "[sharing] Detected {} LAN IPs: {:?}",
         lan_ips.len(),
         lan_ips
     );
 
-    // Generate self-signed TLS certificate for HTTPS (includes all LAN IPs in SAN)
-    let tls_certs = tls::generate_self_signed(&lan_ips)?;
-    log::info!(
-        "[sharing] TLS certificate generated for {} LAN IPs",
-        lan_ips.len()
-    );
-
-    let share_urls: Vec<String> = lan_ips
-        .iter()
-        .map(|ip| format!("https://{}:{}", ip, port))
-        .collect();
+    // Use the configured certificate, or generate a self-signed one for HTTPS (includes all
+    // LAN IPs in SAN)
+    let tls_certs = match load_global_config().custom_tls {
+        Some(custom) => {
+            let certs = tls::load_custom(&custom)?;
+            log::info!("[sharing] Using TLS certificate {}", custom.cert_path);
+            certs
+        }
+        None => {
+            let certs = tls::generate_self_signed(&lan_ips)?;
+            log::info!(
+                "[sharing] TLS certificate generated for {} LAN IPs",
+                lan_ips.len()
+            );
+            certs
+        }
+    };
 
-    let share_url = share_urls
-        .first()
-        .cloned()
-        .unwrap_or_else(|| format!("https://0.0.0.0:{}", port));
+    let share_url = share_urls(&lan_ips, port)
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| format!("https://{}", bind_addr));
 
     // Create shutdown channel
     let (tx, rx) = tokio::sync::watch::channel(false);
@@ -162,6 +208,10 @@ pub async fn start_sharing_internal(
         state.auth_key = Some(auth_key);
         state.auth_salt = Some(salt);
         state.shutdown_tx = Some(tx);
+        state.bind_ip = bind_ip;
+        state.profile_name = profile.as_ref().map(|p| p.name.clone());
+        state.role = profile.as_ref().map(|p| p.role).unwrap_or_default();
+        state.allowed_worktrees = profile.map(|p| p.allowed_worktrees).unwrap_or_default();
     }
 
     // Save port to global config (no longer save password)
@@ -176,16 +226,26 @@ pub async fn start_sharing_internal(
     if let Ok(mut sessions) = AUTHENTICATED_SESSIONS.lock() {
         sessions.clear();
     }
+    if let Ok(mut expired) = EXPIRED_SESSIONS.lock() {
+        expired.clear();
+    }
     log::info!("[sharing] Previous authenticated sessions cleared");
 
+    // Drop idle sessions until sharing stops
+    TOKIO_RT.spawn(expire_idle_sessions_loop(rx.clone()));
+
     // Spawn HTTP (port) + HTTPS (port+1) servers on the shared tokio runtime
-    TOKIO_RT.spawn(http_server::start_server(port, rx, Some(tls_certs)));
+    TOKIO_RT.spawn(http_server::start_server(bind_addr, rx, Some(tls_certs)));
     log::info!(
         "[sharing] HTTP/HTTPS server spawned on port {} for workspace {}",
         port,
         workspace_path
     );
 
+    notifier::notify(
+        NotifyEvent::ShareStarted,
+        format!("🔗 {} 已开始分享: {}", workspace_path, share_url),
+    );
     Ok(share_url)
 }
 
@@ -197,96 +257,143 @@ pub(crate) async fn start_sharing(
 ) -> Result<String, String> {
     let workspace_path =
         get_window_workspace_path(window.label()).ok_or("No workspace selected")?;
-    start_sharing_internal(workspace_path, port, password).await
+    start_sharing_internal(workspace_path, port, password, None).await
 }
 
-pub async fn start_ngrok_tunnel_internal() -> Result<String, String> {
-    log::info!("[ngrok] Starting ngrok tunnel");
+// ==================== 分享配置 ====================
+
+#[tauri::command]
+pub(crate) async fn get_share_profiles() -> Result<Vec<ShareProfile>, String> {
+    Ok(load_global_config().share_profiles)
+}
+
+/// 分享配置的隧道：WMS 或任一已注册的隧道提供方
+fn validate_profile_tunnel(tunnel: Option<&str>) -> Result<(), String> {
+    match tunnel {
+        None | Some("wms") => Ok(()),
+        Some(id) if tunnel::provider(id).is_some() => Ok(()),
+        Some(other) => Err(format!("不支持的隧道类型: {}", other)),
+    }
+}
+
+/// Create or replace (by name) a share profile. Passwords are never stored.
+#[tauri::command]
+pub(crate) async fn save_share_profile(mut profile: ShareProfile) -> Result<(), String> {
+    profile.name = profile.name.trim().to_string();
+    if profile.name.is_empty() {
+        return Err("分享配置名称不能为空".to_string());
+    }
+    let bind_ip = parse_bind_address(profile.bind_address.as_deref())?;
+    profile.bind_address = bind_ip.map(|ip| ip.to_string());
+    profile.allowed_worktrees = profile
+        .allowed_worktrees
+        .iter()
+        .map(|w| w.trim().to_string())
+        .filter(|w| !w.is_empty())
+        .collect();
+    validate_profile_tunnel(profile.tunnel.as_deref())?;
+    // 隧道转发到 localhost，监听特定网卡时无法访问
+    if profile.tunnel.is_some() && bind_ip.is_some_and(|ip| !ip.is_unspecified() && !ip.is_loopback()) {
+        return Err("使用隧道时监听地址只能为空、0.0.0.0 或 127.0.0.1".to_string());
+    }
+
+    let mut config = load_global_config();
+    match config.share_profiles.iter_mut().find(|p| p.name == profile.name) {
+        Some(existing) => *existing = profile,
+        None => config.share_profiles.push(profile),
+    }
+    save_global_config_internal(&config)
+}
+
+#[tauri::command]
+pub(crate) async fn delete_share_profile(name: String) -> Result<(), String> {
+    let mut config = load_global_config();
+    let before = config.share_profiles.len();
+    config.share_profiles.retain(|p| p.name != name);
+    if config.share_profiles.len() == before {
+        return Err(format!("分享配置 \"{}\" 不存在", name));
+    }
+    save_global_config_internal(&config)
+}
+
+/// Start LAN sharing with a saved profile, then open the profile's tunnel if it has one.
+/// Returns the tunnel URL when a tunnel is configured, otherwise the LAN URL.
+#[tauri::command]
+pub(crate) async fn start_sharing_with_profile(
+    window: tauri::Window,
+    name: String,
+    password: String,
+) -> Result<String, String> {
+    let workspace_path =
+        get_window_workspace_path(window.label()).ok_or("No workspace selected")?;
+    let profile = load_global_config()
+        .share_profiles
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| format!("分享配置 \"{}\" 不存在", name))?;
+    log::info!("[sharing] Starting with profile \"{}\"", profile.name);
+
+    let tunnel = profile.tunnel.clone();
+    let lan_url = start_sharing_internal(workspace_path, profile.port, password, Some(profile)).await?;
+    let tunnel_result = match tunnel.as_deref() {
+        Some("wms") => start_wms_tunnel_internal(Some(window)).await,
+        Some(provider_id) => start_tunnel_internal(provider_id).await,
+        None => return Ok(lan_url),
+    };
+    // 隧道失败时保留局域网分享，由用户决定是否停止
+    tunnel_result.map_err(|e| format!("局域网分享已启动，但隧道启动失败: {}", e))
+}
+
+/// Open the tunnel of a registered provider (see `crate::tunnel`) for the active share.
+pub async fn start_tunnel_internal(provider_id: &str) -> Result<String, String> {
+    let provider = tunnel::provider(provider_id)
+        .ok_or_else(|| format!("不支持的隧道类型: {}", provider_id))?;
+    log::info!("[{}] Starting tunnel", provider_id);
     let port = {
         let state = SHARE_STATE
             .lock()
             .map_err(|_| "Internal state error".to_string())?;
         if !state.active {
-            log::warn!("[ngrok] Rejected: LAN sharing not active");
+            log::warn!("[{}] Rejected: LAN sharing not active", provider_id);
             return Err("请先开启分享".to_string());
         }
-        if state.ngrok_url.is_some() {
-            log::warn!("[ngrok] Rejected: ngrok tunnel already running");
-            return Err("ngrok 隧道已在运行".to_string());
-        }
         state.port
     };
-
-    let ngrok_token = load_global_config()
-        .ngrok_token
-        .ok_or("未配置 ngrok token，请先在设置中配置".to_string())?;
-    log::info!("[ngrok] Token configured, forwarding to port {}", port);
-
-    let (url_tx, url_rx) = std::sync::mpsc::channel::<Result<String, String>>();
-
-    let ngrok_handle = TOKIO_RT.spawn(async move {
-        let result = async {
-            log::info!("[ngrok] Connecting to ngrok service...");
-            let session = ngrok::Session::builder()
-                .authtoken(ngrok_token)
-                .connect()
-                .await
-                .map_err(|e| format!("ngrok 连接失败: {}", e))?;
-            log::info!("[ngrok] Session established, creating HTTP tunnel to localhost:{}", port);
-
-            let forwarder = session
-                .http_endpoint()
-                .listen_and_forward(
-                    url::Url::parse(&format!("http://localhost:{}", port))
-                        .map_err(|e| format!("URL 解析失败: {}", e))?,
-                )
-                .await
-                .map_err(|e| format!("ngrok 隧道创建失败: {}", e))?;
-
-            let ngrok_url = forwarder.url().to_string();
-            log::info!("[ngrok] Tunnel created, URL: {}", ngrok_url);
-            Ok::<(String, Forwarder<HttpTunnel>), String>((ngrok_url, forwarder))
+    match provider.status() {
+        TunnelStatus::Running => {
+            log::warn!("[{}] Rejected: tunnel already running", provider_id);
+            return Err(format!("{} 隧道已在运行", provider_id));
         }
-        .await;
+        // 远端关闭后残留的任务句柄，先清理再重新启动
+        TunnelStatus::Closed => provider.stop(),
+        TunnelStatus::Stopped => {}
+    }
 
-        match result {
-            Ok((url, mut forwarder)) => {
-                let _ = url_tx.send(Ok(url));
-                // join() keeps the forwarder actively forwarding traffic
-                let _ = forwarder.join().await;
-                log::info!("[ngrok] Forwarder join() returned, tunnel closed");
-            }
-            Err(e) => {
-                log::error!("[ngrok] Tunnel creation failed: {}", e);
-                let _ = url_tx.send(Err(e));
-            }
+    match provider.start(port).await {
+        Ok(url) => {
+            log::info!("[{}] Tunnel started successfully: {}", provider_id, url);
+            Ok(url)
         }
-    });
-
-    // Wait for the ngrok URL (with timeout)
-    match url_rx.recv_timeout(std::time::Duration::from_secs(30)) {
-        Ok(Ok(ngrok_url)) => {
-            let mut state = SHARE_STATE
-                .lock()
-                .map_err(|_| "Internal state error".to_string())?;
-            state.ngrok_url = Some(ngrok_url.clone());
-            state.ngrok_task = Some(ngrok_handle);
-            log::info!("[ngrok] Tunnel started successfully: {}", ngrok_url);
-            Ok(ngrok_url)
-        }
-        Ok(Err(e)) => {
-            log::error!("[ngrok] Tunnel startup error: {}", e);
-            ngrok_handle.abort();
+        Err(e) => {
+            log::error!("[{}] Tunnel startup error: {}", provider_id, e);
             Err(e)
         }
-        Err(_) => {
-            log::error!("[ngrok] Tunnel startup timed out after 30s");
-            ngrok_handle.abort();
-            Err("ngrok 隧道启动超时".to_string())
-        }
     }
 }
 
+pub fn stop_tunnel_internal(provider_id: &str) -> Result<(), String> {
+    let provider = tunnel::provider(provider_id)
+        .ok_or_else(|| format!("不支持的隧道类型: {}", provider_id))?;
+    log::info!("[{}] Stopping tunnel", provider_id);
+    provider.stop();
+    log::info!("[{}] Tunnel stopped", provider_id);
+    Ok(())
+}
+
+pub async fn start_ngrok_tunnel_internal() -> Result<String, String> {
+    start_tunnel_internal("ngrok").await
+}
+
 #[tauri::command]
 pub(crate) async fn start_ngrok_tunnel() -> Result<String, String> {
     start_ngrok_tunnel_internal().await
@@ -294,21 +401,123 @@ pub(crate) async fn start_ngrok_tunnel() -> Result<String, String> {
 
 #[tauri::command]
 pub(crate) async fn stop_ngrok_tunnel() -> Result<(), String> {
-    log::info!("[ngrok] Stopping ngrok tunnel");
-    let mut state = SHARE_STATE
-        .lock()
-        .map_err(|_| "Internal state error".to_string())?;
-    if let Some(handle) = state.ngrok_task.take() {
-        // abort() is intentional: the ngrok crate's Forwarder does not expose a graceful
-        // shutdown API. Aborting the task triggers its Drop impl, which handles cleanup.
-        handle.abort();
-        log::info!("[ngrok] Tunnel task aborted");
-    } else {
-        log::info!("[ngrok] No active tunnel task to stop");
+    stop_tunnel_internal("ngrok")
+}
+
+// ==================== Cloudflare 隧道 ====================
+
+#[tauri::command]
+pub(crate) fn get_cloudflare_tunnel_config() -> CloudflareTunnelConfig {
+    load_global_config().cloudflare_tunnel
+}
+
+#[tauri::command]
+pub(crate) fn set_cloudflare_tunnel_config(tunnel: CloudflareTunnelConfig) -> Result<(), String> {
+    let non_empty = |v: Option<String>| v.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    let mut config = load_global_config();
+    config.cloudflare_tunnel = CloudflareTunnelConfig {
+        enabled: tunnel.enabled,
+        binary_path: non_empty(tunnel.binary_path),
+        token: non_empty(tunnel.token),
+        hostname: non_empty(tunnel.hostname),
+    };
+    save_global_config_internal(&config)
+}
+
+pub async fn start_cloudflare_tunnel_internal() -> Result<String, String> {
+    start_tunnel_internal("cloudflare").await
+}
+
+#[tauri::command]
+pub(crate) async fn start_cloudflare_tunnel() -> Result<String, String> {
+    start_cloudflare_tunnel_internal().await
+}
+
+#[tauri::command]
+pub(crate) async fn stop_cloudflare_tunnel() -> Result<(), String> {
+    stop_tunnel_internal("cloudflare")
+}
+
+// ==================== frp 隧道 ====================
+
+#[tauri::command]
+pub(crate) fn get_frp_tunnel_config() -> FrpTunnelConfig {
+    load_global_config().frp_tunnel
+}
+
+#[tauri::command]
+pub(crate) fn set_frp_tunnel_config(tunnel: FrpTunnelConfig) -> Result<(), String> {
+    let non_empty = |v: Option<String>| v.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    let mut config = load_global_config();
+    config.frp_tunnel = FrpTunnelConfig {
+        enabled: tunnel.enabled,
+        binary_path: non_empty(tunnel.binary_path),
+        server_addr: tunnel.server_addr.trim().to_string(),
+        server_port: tunnel.server_port,
+        token: non_empty(tunnel.token),
+        remote_port: tunnel.remote_port.filter(|p| *p > 0),
+        custom_domain: non_empty(tunnel.custom_domain),
+        public_url: non_empty(tunnel.public_url),
+    };
+    save_global_config_internal(&config)
+}
+
+pub async fn start_frp_tunnel_internal() -> Result<String, String> {
+    start_tunnel_internal("frp").await
+}
+
+#[tauri::command]
+pub(crate) async fn start_frp_tunnel() -> Result<String, String> {
+    start_frp_tunnel_internal().await
+}
+
+#[tauri::command]
+pub(crate) async fn stop_frp_tunnel() -> Result<(), String> {
+    stop_tunnel_internal("frp")
+}
+
+// ==================== Tailscale ====================
+
+/// 分享服务在 tailnet 内的访问地址（MagicDNS 域名与 tailnet IP），未分享或 Tailscale 未运行时为空
+#[tauri::command]
+pub(crate) async fn get_tailscale_urls() -> Result<Vec<String>, String> {
+    let (port, bind_ip) = {
+        let state = SHARE_STATE
+            .lock()
+            .map_err(|_| "Internal state error".to_string())?;
+        if !state.active {
+            return Ok(vec![]);
+        }
+        (state.port, state.bind_ip.filter(|ip| !ip.is_unspecified()))
+    };
+    let Some(node) = tokio::task::spawn_blocking(tunnel::tailscale_node)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+    else {
+        return Ok(vec![]);
+    };
+    // 监听特定网卡时只有该地址可达
+    let ips: Vec<IpAddr> = node
+        .ips
+        .into_iter()
+        .filter(|ip| bind_ip.is_none_or(|bind| bind == *ip))
+        .collect();
+    let mut urls = vec![];
+    if let Some(name) = node.dns_name.filter(|_| !ips.is_empty()) {
+        urls.push(format!("https://{}:{}", name, port));
     }
-    state.ngrok_url = None;
-    log::info!("[ngrok] Tunnel stopped");
-    Ok(())
+    urls.extend(share_urls(&ips, port));
+    Ok(urls)
+}
+
+#[tauri::command]
+pub(crate) async fn start_tailscale_funnel() -> Result<String, String> {
+    start_tunnel_internal("tailscale").await
+}
+
+#[tauri::command]
+pub(crate) async fn stop_tailscale_funnel() -> Result<(), String> {
+    stop_tunnel_internal("tailscale")
 }
 
 // ==================== WMS 隧道 ====================
@@ -702,7 +911,7 @@ pub fn wms_manual_reconnect_internal() -> Result<(), String> {
 pub fn stop_sharing_internal() -> Result<(), String> {
     log::info!("[sharing] Stopping LAN sharing");
 
-    // Single lock scope: check active, stop ngrok, extract shutdown_tx, and reset state
+    // Single lock scope: check active, stop tunnels, extract shutdown_tx, and reset state
     let shutdown_tx = {
         let mut state = SHARE_STATE
             .lock()
@@ -712,14 +921,9 @@ pub fn stop_sharing_internal() -> Result<(), String> {
             return Err("Not currently sharing".to_string());
         }
 
-        // Stop ngrok tunnel if active
-        // NOTE: abort() is intentional here -- the ngrok crate's Forwarder does not expose
-        // a graceful shutdown API; aborting the task triggers its Drop impl for cleanup.
-        if let Some(handle) = state.ngrok_task.take() {
-            handle.abort();
-            log::info!("[sharing] Stopped ngrok tunnel");
-        }
-        state.ngrok_url = None;
+        // Stop provider tunnels (ngrok, ...) if active
+        tunnel::stop_all();
+        log::info!("[sharing] Stopped provider tunnels");
 
         // Stop WMS tunnel if active (signal graceful shutdown first)
         if let Some(tx) = state.wms_shutdown_tx.take() {
@@ -743,6 +947,10 @@ pub fn stop_sharing_internal() -> Result<(), String> {
         state.port = 0;
         state.auth_key = None;
         state.auth_salt = None;
+        state.bind_ip = None;
+        state.profile_name = None;
+        state.role = ShareRole::default();
+        state.allowed_worktrees.clear();
         tx
     };
 
@@ -761,6 +969,12 @@ pub fn stop_sharing_internal() -> Result<(), String> {
             count
         );
     }
+    if let Ok(mut expired) = EXPIRED_SESSIONS.lock() {
+        expired.clear();
+    }
+    if let Ok(mut sessions) = QUARANTINED_SESSIONS.lock() {
+        sessions.clear();
+    }
     if let Ok(mut clients) = CONNECTED_CLIENTS.lock() {
         let count = clients.len();
         clients.clear();
@@ -782,23 +996,7 @@ pub(crate) async fn get_share_state() -> Result<ShareStateInfo, String> {
         .lock()
         .map_err(|_| "Internal state error".to_string())?;
     let urls = if state.active {
-        let mut ips: Vec<std::net::IpAddr> = local_ip_address::list_afinet_netifas()
-            .unwrap_or_default()
-            .into_iter()
-            .filter_map(|(_name, ip)| match ip {
-                std::net::IpAddr::V4(v4)
-                    if !v4.is_loopback() && !v4.is_unspecified() && !v4.is_multicast() =>
-                {
-                    Some(ip)
-                }
-                _ => None,
-            })
-            .collect();
-        ips.sort();
-        ips.dedup();
-        ips.iter()
-            .map(|ip| format!("https://{}:{}", ip, state.port))
-            .collect()
+        share_urls(&reachable_ips(state.bind_ip), state.port)
     } else {
         vec![]
     };
@@ -826,7 +1024,8 @@ pub(crate) async fn get_share_state() -> Result<ShareStateInfo, String> {
     Ok(ShareStateInfo {
         active: state.active,
         urls,
-        ngrok_url: state.ngrok_url.clone(),
+        ngrok_url: tunnel::provider("ngrok").and_then(|p| p.url()),
+        tunnels: tunnel::tunnel_infos(),
         wms_url: state.wms_url.clone(),
         wms_connected,
         wms_reconnecting,
@@ -834,6 +1033,7 @@ pub(crate) async fn get_share_state() -> Result<ShareStateInfo, String> {
         wms_next_retry_secs,
         workspace_path: state.workspace_path.clone(),
         current_workspace_name,
+        profile_name: state.profile_name.clone(),
     })
 }
 
@@ -928,7 +1128,7 @@ pub fn kick_client_internal(session_id: &str) -> Result<(), String> {
 
     // Remove from authenticated sessions
     if let Ok(mut sessions) = AUTHENTICATED_SESSIONS.lock() {
-        let removed = sessions.remove(session_id);
+        let removed = sessions.remove(session_id).is_some();
         log::info!(
             "[sharing] Session {} {} from authenticated sessions",
             session_id,
@@ -936,6 +1136,10 @@ pub fn kick_client_internal(session_id: &str) -> Result<(), String> {
         );
     }
 
+    if let Ok(mut sessions) = QUARANTINED_SESSIONS.lock() {
+        sessions.remove(session_id);
+    }
+
     // Remove from connected clients
     if let Ok(mut clients) = CONNECTED_CLIENTS.lock() {
         let removed = clients.remove(session_id).is_some();
@@ -953,3 +1157,563 @@ pub fn kick_client_internal(session_id: &str) -> Result<(), String> {
 pub(crate) fn kick_client(session_id: String) -> Result<(), String> {
     kick_client_internal(&session_id)
 }
+
+/// Recent remote actions (newest first) for the desktop activity feed;
+/// live updates arrive via the "share-activity" event.
+#[tauri::command]
+pub(crate) fn get_share_activity() -> Vec<ShareActivity> {
+    SHARE_ACTIVITY
+        .lock()
+        .map(|feed| feed.iter().rev().cloned().collect())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub(crate) fn clear_share_activity() {
+    if let Ok(mut feed) = SHARE_ACTIVITY.lock() {
+        feed.clear();
+    }
+}
+
+/// Put a client into quarantine: its WebSocket stays connected (read-only) while
+/// every mutating API call is rejected, until it is released or kicked.
+pub fn quarantine_client_internal(session_id: &str) -> Result<(), String> {
+    set_client_quarantined(session_id, true)
+}
+
+pub fn release_client_internal(session_id: &str) -> Result<(), String> {
+    set_client_quarantined(session_id, false)
+}
+
+fn set_client_quarantined(session_id: &str, quarantined: bool) -> Result<(), String> {
+    {
+        let mut clients = CONNECTED_CLIENTS
+            .lock()
+            .map_err(|_| "Internal state error".to_string())?;
+        let client = clients
+            .get_mut(session_id)
+            .ok_or_else(|| format!("Client not found: {}", session_id))?;
+        client.quarantined = quarantined;
+    }
+    if let Ok(mut sessions) = QUARANTINED_SESSIONS.lock() {
+        if quarantined {
+            sessions.insert(session_id.to_string());
+        } else {
+            sessions.remove(session_id);
+        }
+    }
+    log::info!(
+        "[sharing] Session {} {}",
+        session_id,
+        if quarantined { "quarantined" } else { "released from quarantine" }
+    );
+
+    let notification = serde_json::json!({
+        "session_id": session_id,
+        "type": if quarantined { "quarantined" } else { "released" },
+        "reason": if quarantined { "您的会话已被管理员设为只读" } else { "" },
+    })
+    .to_string();
+    let _ = CLIENT_NOTIFICATION_BROADCAST.send(notification);
+    Ok(())
+}
+
+pub(crate) fn is_session_quarantined(session_id: &str) -> bool {
+    QUARANTINED_SESSIONS
+        .lock()
+        .map(|s| s.contains(session_id))
+        .unwrap_or(false)
+}
+
+#[tauri::command]
+pub(crate) fn quarantine_client(session_id: String) -> Result<(), String> {
+    quarantine_client_internal(&session_id)
+}
+
+#[tauri::command]
+pub(crate) fn release_client(session_id: String) -> Result<(), String> {
+    release_client_internal(&session_id)
+}
+
+/// Change what an approved client may do; takes effect on its next API call.
+pub fn set_client_role_internal(session_id: &str, role: ShareRole) -> Result<(), String> {
+    {
+        let mut clients = CONNECTED_CLIENTS
+            .lock()
+            .map_err(|_| "Internal state error".to_string())?;
+        let client = clients
+            .get_mut(session_id)
+            .ok_or_else(|| format!("Client not found: {}", session_id))?;
+        client.role = role;
+    }
+    if let Ok(mut sessions) = AUTHENTICATED_SESSIONS.lock() {
+        if let Some(session) = sessions.get_mut(session_id) {
+            session.role = role;
+        }
+    }
+    log::info!(
+        "[sharing] Session {} role set to {}",
+        session_id,
+        role.as_str()
+    );
+
+    let notification = serde_json::json!({
+        "session_id": session_id,
+        "type": "role_changed",
+        "role": role,
+    })
+    .to_string();
+    let _ = CLIENT_NOTIFICATION_BROADCAST.send(notification);
+    Ok(())
+}
+
+/// Role of a remote session: the one it was approved with when sharing is password
+/// protected, the share's role otherwise. `None` when the session still has to authenticate.
+pub(crate) fn session_role(session_id: &str) -> Option<ShareRole> {
+    let (active, protected, share_role) = SHARE_STATE
+        .lock()
+        .map(|s| (s.active, s.auth_key.is_some(), s.role))
+        .unwrap_or((false, false, ShareRole::Admin));
+    if !active {
+        return Some(ShareRole::Admin);
+    }
+    if !protected {
+        return Some(share_role);
+    }
+    let ttl = session_ttl();
+    AUTHENTICATED_SESSIONS.lock().ok().and_then(|sessions| {
+        sessions
+            .get(session_id)
+            .filter(|s| ttl.is_none_or(|ttl| s.last_seen.elapsed() < ttl))
+            .map(|s| s.role)
+    })
+}
+
+// ==================== Session Expiry ====================
+
+/// How often idle sessions are swept while sharing
+const SESSION_CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+/// How long an expired session keeps answering `session_expired` before it is forgotten
+const EXPIRED_SESSION_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Idle time after which a session has to log in again; `None` when sessions never expire.
+fn session_ttl() -> Option<Duration> {
+    match load_global_config().session_ttl_mins {
+        0 => None,
+        mins => Some(Duration::from_secs(u64::from(mins) * 60)),
+    }
+}
+
+/// Slide the session's expiry forward (called for every authenticated API request and
+/// WebSocket message).
+pub(crate) fn touch_session(session_id: &str) {
+    if let Ok(mut sessions) = AUTHENTICATED_SESSIONS.lock() {
+        if let Some(session) = sessions.get_mut(session_id) {
+            session.last_seen = std::time::Instant::now();
+        }
+    }
+}
+
+/// Whether the session logged in with the share password (and has not been swept as expired).
+pub(crate) fn is_authenticated_session(session_id: &str) -> bool {
+    AUTHENTICATED_SESSIONS
+        .lock()
+        .map(|sessions| sessions.contains_key(session_id))
+        .unwrap_or(false)
+}
+
+/// Whether the session was authenticated but has been idle past the TTL, as opposed to never
+/// having logged in (the web client then asks to log in again instead of failing).
+pub(crate) fn is_session_expired(session_id: &str) -> bool {
+    if EXPIRED_SESSIONS
+        .lock()
+        .map(|expired| expired.contains_key(session_id))
+        .unwrap_or(false)
+    {
+        return true;
+    }
+    let Some(ttl) = session_ttl() else {
+        return false;
+    };
+    AUTHENTICATED_SESSIONS
+        .lock()
+        .ok()
+        .and_then(|sessions| {
+            sessions
+                .get(session_id)
+                .map(|s| s.last_seen.elapsed() >= ttl)
+        })
+        .unwrap_or(false)
+}
+
+/// Remove sessions idle longer than the TTL, remembering them so their next request gets a
+/// `session_expired` error, and close their WebSocket / event stream (which also stops
+/// their terminal forwarders).
+fn expire_idle_sessions() {
+    if let Ok(mut set) = EXPIRED_SESSIONS.lock() {
+        set.retain(|_, at| at.elapsed() < EXPIRED_SESSION_RETENTION);
+    }
+    let Some(ttl) = session_ttl() else {
+        return;
+    };
+    let expired: Vec<String> = {
+        let Ok(mut sessions) = AUTHENTICATED_SESSIONS.lock() else {
+            return;
+        };
+        let expired: Vec<String> = sessions
+            .iter()
+            .filter(|(_, s)| s.last_seen.elapsed() >= ttl)
+            .map(|(sid, _)| sid.clone())
+            .collect();
+        for sid in &expired {
+            sessions.remove(sid);
+        }
+        expired
+    };
+    if expired.is_empty() {
+        return;
+    }
+    if let Ok(mut quarantined) = QUARANTINED_SESSIONS.lock() {
+        for sid in &expired {
+            quarantined.remove(sid);
+        }
+    }
+    if let Ok(mut clients) = CONNECTED_CLIENTS.lock() {
+        for sid in &expired {
+            clients.remove(sid);
+        }
+    }
+    if let Ok(mut set) = EXPIRED_SESSIONS.lock() {
+        let now = std::time::Instant::now();
+        set.extend(expired.iter().map(|sid| (sid.clone(), now)));
+    }
+    for sid in &expired {
+        let notification = serde_json::json!({
+            "session_id": sid,
+            "type": "session_expired",
+        })
+        .to_string();
+        let _ = CLIENT_NOTIFICATION_BROADCAST.send(notification);
+    }
+    log::info!(
+        "[sharing] Expired {} session(s) idle for more than {} min",
+        expired.len(),
+        ttl.as_secs() / 60
+    );
+}
+
+async fn expire_idle_sessions_loop(mut shutdown_rx: tokio::sync::watch::Receiver<bool>) {
+    let mut interval = tokio::time::interval(SESSION_CLEANUP_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = shutdown_rx.changed() => break,
+            _ = interval.tick() => expire_idle_sessions(),
+        }
+    }
+}
+
+#[tauri::command]
+pub(crate) fn get_session_ttl_mins() -> u32 {
+    load_global_config().session_ttl_mins
+}
+
+/// Set the idle timeout of remote sessions (0 = never expire); applies to existing sessions too.
+#[tauri::command]
+pub(crate) fn set_session_ttl_mins(mins: u32) -> Result<(), String> {
+    let mut config = load_global_config();
+    config.session_ttl_mins = mins;
+    save_global_config_internal(&config)?;
+    log::info!("[sharing] Session TTL set to {} min", mins);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn set_client_role(session_id: String, role: ShareRole) -> Result<(), String> {
+    set_client_role_internal(&session_id, role)
+}
+
+// ==================== API Tokens ====================
+
+const API_TOKEN_PREFIX: &str = "wmt_";
+
+fn hash_api_token(token: &str) -> String {
+    let digest = ring::digest::digest(&ring::digest::SHA256, token.as_bytes());
+    hex::encode(digest)
+}
+
+#[tauri::command]
+pub(crate) fn list_api_tokens() -> Vec<ApiToken> {
+    load_global_config().api_tokens
+}
+
+/// Create a token for scripts and CI; the plain token is returned once and only its hash
+/// is stored.
+#[tauri::command]
+pub(crate) fn create_api_token(name: String, role: ShareRole) -> Result<String, String> {
+    use ring::rand::{SecureRandom, SystemRandom};
+
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Token name is required".to_string());
+    }
+    let mut config = load_global_config();
+    if config.api_tokens.iter().any(|t| t.name == name) {
+        return Err(format!("Duplicate token name: {}", name));
+    }
+
+    let mut bytes = [0u8; 32];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| "Failed to generate token")?;
+    let token = format!("{}{}", API_TOKEN_PREFIX, hex::encode(bytes));
+    config.api_tokens.push(ApiToken {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.clone(),
+        role,
+        token_hash: hash_api_token(&token),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    });
+    save_global_config_internal(&config)?;
+    log::info!("[sharing] API token '{}' created ({})", name, role.as_str());
+    Ok(token)
+}
+
+#[tauri::command]
+pub(crate) fn revoke_api_token(id: String) -> Result<(), String> {
+    let mut config = load_global_config();
+    let before = config.api_tokens.len();
+    config.api_tokens.retain(|t| t.id != id);
+    if config.api_tokens.len() == before {
+        return Err(format!("API token not found: {}", id));
+    }
+    save_global_config_internal(&config)?;
+    log::info!("[sharing] API token {} revoked", id);
+    Ok(())
+}
+
+// ==================== Git webhook ====================
+
+#[tauri::command]
+pub(crate) fn get_webhook_secret() -> Option<String> {
+    load_global_config().webhook_secret
+}
+
+/// Generate a new webhook secret (the old one stops working at once). It goes into GitHub's
+/// "Secret" or GitLab's "Secret token" field of a webhook pointing at `/api/webhook/git`.
+#[tauri::command]
+pub(crate) fn rotate_webhook_secret() -> Result<String, String> {
+    use ring::rand::{SecureRandom, SystemRandom};
+
+    let mut bytes = [0u8; 32];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| "Failed to generate webhook secret")?;
+    let secret = hex::encode(bytes);
+    let mut config = load_global_config();
+    config.webhook_secret = Some(secret.clone());
+    save_global_config_internal(&config)?;
+    log::info!("[sharing] Webhook secret rotated");
+    Ok(secret)
+}
+
+#[tauri::command]
+pub(crate) fn disable_webhook() -> Result<(), String> {
+    let mut config = load_global_config();
+    config.webhook_secret = None;
+    save_global_config_internal(&config)?;
+    log::info!("[sharing] Webhook disabled");
+    Ok(())
+}
+
+/// Role granted by a bearer token, `None` when it matches no configured token.
+pub(crate) fn api_token_role(token: &str) -> Option<ShareRole> {
+    if !token.starts_with(API_TOKEN_PREFIX) {
+        return None;
+    }
+    let hash = hash_api_token(token);
+    load_global_config()
+        .api_tokens
+        .into_iter()
+        .find(|t| t.token_hash == hash)
+        .map(|t| t.role)
+}
+
+/// Hash of a configured API token: identifies its caller (e.g. for rate limiting) without
+/// keeping the token itself. None for unknown tokens.
+pub(crate) fn known_api_token_hash(token: &str) -> Option<String> {
+    api_token_role(token).map(|_| hash_api_token(token))
+}
+
+// ==================== TOTP ====================
+
+#[tauri::command]
+pub(crate) fn get_totp_enabled() -> bool {
+    load_global_config().totp_enabled
+}
+
+/// Generate a new secret to add to an authenticator app. It only takes effect once a code
+/// from the app is confirmed with `confirm_totp`; until then TOTP stays off.
+#[tauri::command]
+pub(crate) fn enroll_totp() -> Result<TotpEnrollment, String> {
+    let secret = totp::generate_secret()?;
+    let mut config = load_global_config();
+    config.totp_secret = Some(secret.clone());
+    config.totp_enabled = false;
+    save_global_config_internal(&config)?;
+    log::info!("[sharing] TOTP secret generated, waiting for confirmation");
+    Ok(TotpEnrollment {
+        otpauth_url: totp::otpauth_url(&secret, "share"),
+        secret,
+    })
+}
+
+#[tauri::command]
+pub(crate) fn confirm_totp(code: String) -> Result<(), String> {
+    let mut config = load_global_config();
+    let secret = config
+        .totp_secret
+        .clone()
+        .ok_or("No TOTP secret enrolled")?;
+    if !totp::verify_code(&secret, &code) {
+        return Err("Invalid verification code".to_string());
+    }
+    config.totp_enabled = true;
+    save_global_config_internal(&config)?;
+    log::info!("[sharing] TOTP enabled for share authentication");
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn disable_totp() -> Result<(), String> {
+    let mut config = load_global_config();
+    config.totp_secret = None;
+    config.totp_enabled = false;
+    save_global_config_internal(&config)?;
+    log::info!("[sharing] TOTP disabled");
+    Ok(())
+}
+
+/// Check the second factor of a password login; always passes when TOTP is off.
+pub(crate) fn verify_share_totp(code: Option<&str>) -> bool {
+    let config = load_global_config();
+    match (config.totp_enabled, config.totp_secret) {
+        (true, Some(secret)) => code.is_some_and(|c| totp::verify_code(&secret, c)),
+        _ => true,
+    }
+}
+
+// ==================== Rate Limiting ====================
+
+pub(crate) fn get_rate_limit_config_inner() -> Result<RateLimitConfig, String> {
+    Ok(load_global_config().rate_limit)
+}
+
+pub(crate) fn set_rate_limit_config_inner(limits: RateLimitConfig) -> Result<(), String> {
+    if limits.enabled && (limits.api_requests_per_sec == 0 || limits.pty_writes_per_sec == 0) {
+        return Err("Rate limits must be greater than 0".to_string());
+    }
+    let mut config = load_global_config();
+    config.rate_limit = limits;
+    save_global_config_internal(&config)?;
+    if let Ok(mut limiter) = API_RATE_LIMITER.lock() {
+        limiter.limits = limits;
+    }
+    log::info!(
+        "[sharing] Rate limits: enabled={}, api {}/s (burst {}), pty input {}/s (burst {})",
+        limits.enabled,
+        limits.api_requests_per_sec,
+        limits.api_burst,
+        limits.pty_writes_per_sec,
+        limits.pty_write_burst
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn get_rate_limit_config() -> Result<RateLimitConfig, String> {
+    get_rate_limit_config_inner()
+}
+
+#[tauri::command]
+pub(crate) fn set_rate_limit_config(limits: RateLimitConfig) -> Result<(), String> {
+    set_rate_limit_config_inner(limits)
+}
+
+/// Take one request from `key`'s bucket; `pty_input` selects the terminal input limits.
+pub(crate) fn rate_limit_allows(key: &str, pty_input: bool) -> bool {
+    let Ok(mut limiter) = API_RATE_LIMITER.lock() else {
+        return true;
+    };
+    let limits = limiter.limits;
+    if !limits.enabled {
+        return true;
+    }
+    let (bucket, rate, burst) = if pty_input {
+        (
+            format!("pty:{}", key),
+            limits.pty_writes_per_sec,
+            limits.pty_write_burst,
+        )
+    } else {
+        (
+            format!("api:{}", key),
+            limits.api_requests_per_sec,
+            limits.api_burst,
+        )
+    };
+    limiter.check(&bucket, rate, burst)
+}
+
+// ==================== Custom TLS Certificate ====================
+
+#[tauri::command]
+pub(crate) fn get_custom_tls() -> Option<CustomTlsConfig> {
+    load_global_config().custom_tls
+}
+
+/// Set (after checking the files load) or clear the share server's certificate; applies the
+/// next time sharing starts.
+#[tauri::command]
+pub(crate) fn set_custom_tls(custom_tls: Option<CustomTlsConfig>) -> Result<(), String> {
+    let custom_tls = custom_tls.filter(|t| !t.cert_path.trim().is_empty());
+    if let Some(ref custom) = custom_tls {
+        tls::load_custom(custom)?;
+    }
+    let mut config = load_global_config();
+    config.custom_tls = custom_tls;
+    save_global_config_internal(&config)?;
+    log::info!(
+        "[sharing] TLS certificate: {}",
+        config
+            .custom_tls
+            .as_ref()
+            .map_or("self-signed", |t| t.cert_path.as_str())
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_tunnel_accepts_every_provider() {
+        for id in [
+            None,
+            Some("ngrok"),
+            Some("cloudflare"),
+            Some("tailscale"),
+            Some("frp"),
+            Some("wms"),
+        ] {
+            assert!(validate_profile_tunnel(id).is_ok(), "{:?}", id);
+        }
+    }
+
+    #[test]
+    fn profile_tunnel_rejects_unknown_providers() {
+        assert!(validate_profile_tunnel(Some("bore")).is_err());
+        assert!(validate_profile_tunnel(Some("")).is_err());
+        assert!(validate_profile_tunnel(Some("Ngrok")).is_err());
+    }
+}
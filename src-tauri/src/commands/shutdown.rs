@@ -0,0 +1,107 @@
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+use crate::commands::sharing::{stop_sharing_internal, stop_wms_tunnel_internal};
+use crate::commands::window::broadcast_lock_state;
+use crate::state::{
+    AUTO_LOCKS, AUTO_LOCK_LAST_SEEN, GIT_WORKTREE_LOCKED, IN_FLIGHT_GIT_OPS, PTY_MANAGER,
+    SHARE_STATE, SHUTDOWN_COMPLETE, SHUTTING_DOWN, WINDOW_WORKSPACES, WORKTREE_LOCKS,
+};
+
+// 等待进行中的 Git 操作完成的最长时间
+const GIT_DRAIN_TIMEOUT_SECS: u64 = 10;
+
+// ==================== 退出流程 ====================
+
+/// 应用退出前的有序清理：
+/// 1. 拒绝新的 Git 操作，等待进行中的操作完成（超时后继续）
+/// 2. 停止 ngrok / WMS 隧道和 HTTP 分享服务
+/// 3. 关闭所有 PTY 会话
+/// 4. 释放所有 worktree 锁（含自动锁）和窗口绑定，并对手动锁定的 worktree 执行 `git worktree unlock`；
+///    部署到主工作区的 worktree 保留 git 锁：部署状态持久化在磁盘上，退出后仍需防止被 prune
+/// 5. 刷新日志（配置每次修改时已写入磁盘，无需再写回）
+///
+/// 可重复调用：仅第一次调用执行清理并返回 true，之后直接返回 false。
+pub(crate) async fn graceful_shutdown_internal() -> bool {
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        log::info!("[shutdown] Shutdown already in progress");
+        return false;
+    }
+    log::info!("[shutdown] ===== START graceful shutdown =====");
+
+    // Step 1: 等待进行中的 Git 操作
+    let started = Instant::now();
+    loop {
+        let in_flight = IN_FLIGHT_GIT_OPS.load(Ordering::SeqCst);
+        if in_flight == 0 {
+            break;
+        }
+        if started.elapsed() >= Duration::from_secs(GIT_DRAIN_TIMEOUT_SECS) {
+            log::warn!(
+                "[shutdown] Step 1/5: {} git operation(s) still running after {}s, continuing",
+                in_flight,
+                GIT_DRAIN_TIMEOUT_SECS
+            );
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    log::info!("[shutdown] Step 1/5: git operations drained");
+
+    // Step 2: 停止隧道和分享
+    let share_active = SHARE_STATE.lock().map(|s| s.active).unwrap_or(false);
+    crate::tunnel::stop_all();
+    if let Err(e) = stop_wms_tunnel_internal().await {
+        log::warn!("[shutdown] Failed to stop WMS tunnel: {}", e);
+    }
+    let still_active = SHARE_STATE.lock().map(|s| s.active).unwrap_or(false);
+    if still_active {
+        if let Err(e) = stop_sharing_internal() {
+            log::warn!("[shutdown] Failed to stop sharing: {}", e);
+        }
+    }
+    log::info!(
+        "[shutdown] Step 2/5: sharing stopped (was active: {})",
+        share_active
+    );
+
+    // Step 3: 关闭 PTY 会话
+    let closed = PTY_MANAGER
+        .lock()
+        .map(|mut m| m.close_all_sessions())
+        .unwrap_or_default();
+    log::info!("[shutdown] Step 3/5: closed {} PTY session(s)", closed.len());
+
+    // Step 4: 释放锁和窗口绑定；broadcast_lock_state 会同步解除不再需要的 git worktree lock
+    let git_locked: Vec<String> = {
+        let git_locked = GIT_WORKTREE_LOCKED.lock().unwrap();
+        git_locked.iter().map(|(ws, _)| ws.clone()).collect()
+    };
+    let affected_workspaces: Vec<String> = {
+        let mut locks = WORKTREE_LOCKS.lock().unwrap();
+        let mut affected: Vec<String> = locks.keys().map(|(ws, _)| ws.clone()).collect();
+        affected.extend(git_locked);
+        affected.sort();
+        affected.dedup();
+        locks.clear();
+        AUTO_LOCKS.lock().unwrap().clear();
+        AUTO_LOCK_LAST_SEEN.lock().unwrap().clear();
+        affected
+    };
+    for ws_path in &affected_workspaces {
+        broadcast_lock_state(ws_path);
+    }
+    WINDOW_WORKSPACES.lock().unwrap().clear();
+    log::info!(
+        "[shutdown] Step 4/5: released locks in {} workspace(s)",
+        affected_workspaces.len()
+    );
+
+    // Step 5: 刷新日志
+    log::info!("[shutdown] Step 5/5: flushing logs");
+    log::logger().flush();
+
+    SHUTDOWN_COMPLETE.store(true, Ordering::SeqCst);
+    log::info!("[shutdown] ===== END graceful shutdown =====");
+    true
+}
@@ -1,14 +1,30 @@
-use std::path::PathBuf;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
 use std::process::Command;
-
-use crate::types::OpenEditorRequest;
-use crate::utils::normalize_path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::config::{
+    get_workspace_config_path, load_global_config, load_workspace_config, resolve_workspace_path,
+    save_global_config_internal, save_workspace_config_internal,
+};
+use crate::pty_manager::bytes_to_utf8_with_pending;
+use crate::state::LOG_BROADCAST;
+use crate::types::{
+    CustomEditor, DevcontainerStatus, EnvironmentCheck, EnvironmentCheckStatus, EnvironmentReport,
+    IssueTrackerAuth, NotifierConfig, OpenEditorRequest, ProxyConfig,
+};
+use crate::utils::{format_size, normalize_path};
 
 // ==================== Tauri 命令：工具 ====================
 
 #[tauri::command]
-pub(crate) fn open_in_terminal(path: String) -> Result<(), String> {
-    let normalized = normalize_path(&path);
+pub(crate) fn open_in_terminal(window: tauri::Window, path: String) -> Result<(), String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    open_terminal_at_path(&resolved)
+}
+
+fn open_terminal_at_path(path: &str) -> Result<(), String> {
+    let normalized = normalize_path(path);
     log::info!("[system] Opening terminal at: {}", normalized);
 
     #[cfg(target_os = "macos")]
@@ -95,31 +111,165 @@ fn editor_app_name(editor: &str) -> &'static str {
     }
 }
 
+/// 要打开的文件（相对 path，不能跳出该目录）和行号
+fn editor_target(request: &OpenEditorRequest) -> Result<Option<(String, u32)>, String> {
+    let Some(file) = request
+        .file
+        .as_deref()
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+    else {
+        return Ok(None);
+    };
+    let relative = Path::new(file);
+    if relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(format!("无效的文件路径: {}", file));
+    }
+    let full = Path::new(&request.path).join(relative);
+    let line = request.line.unwrap_or(1).max(1);
+    Ok(Some((normalize_path(&full.to_string_lossy()), line)))
+}
+
+/// 内置编辑器打开文件并定位到行的 CLI 参数
+fn editor_file_args(editor: &str, path: &str, file: &str, line: u32) -> Vec<String> {
+    match editor {
+        "idea" => vec!["--line".to_string(), line.to_string(), file.to_string()],
+        // VS Code 系（code / cursor / antigravity）：打开目录并跳转到 file:line
+        _ => vec![
+            path.to_string(),
+            "-g".to_string(),
+            format!("{}:{}", file, line),
+        ],
+    }
+}
+
+/// 自定义编辑器：macOS 上先尝试 app_name，再执行 command。参数模板中 {path} 替换为目录，
+/// file_args 模板中另有 {file}、{line}
+fn open_custom_editor(
+    editor: &CustomEditor,
+    path: &str,
+    target: Option<&(String, u32)>,
+) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        // open -a 无法定位到行，打开文件时有命令就用命令
+        let has_command = editor
+            .command
+            .as_deref()
+            .is_some_and(|c| !c.trim().is_empty());
+        let app_name = editor
+            .app_name
+            .as_deref()
+            .filter(|a| !a.trim().is_empty() && (target.is_none() || !has_command));
+        if let Some(app_name) = app_name {
+            let open_path = target.map_or(path, |(file, _)| file.as_str());
+            if Command::new("open")
+                .args(["-a", app_name, open_path])
+                .spawn()
+                .is_ok()
+            {
+                log::info!(
+                    "[system] Spawned {} via open -a for: {}",
+                    app_name,
+                    open_path
+                );
+                return Ok(());
+            }
+        }
+    }
+
+    let cmd = editor
+        .command
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .ok_or_else(|| format!("编辑器 {} 未配置命令", editor.name))?;
+    let args: Vec<String> = match target {
+        Some((file, _)) if editor.file_args.is_empty() => vec![file.clone()],
+        Some((file, line)) => editor
+            .file_args
+            .iter()
+            .map(|a| {
+                a.replace("{path}", path)
+                    .replace("{file}", file)
+                    .replace("{line}", &line.to_string())
+            })
+            .collect(),
+        None if editor.args.is_empty() => vec![path.to_string()],
+        None => editor
+            .args
+            .iter()
+            .map(|a| a.replace("{path}", path))
+            .collect(),
+    };
+    match Command::new(cmd).args(&args).spawn() {
+        Ok(_) => {
+            log::info!("[system] Spawned {} {:?}", cmd, args);
+            Ok(())
+        }
+        Err(e) => {
+            log::error!("[system] Failed to spawn editor process: {}", e);
+            Err(format!("无法打开编辑器 {}: {}", editor.name, e))
+        }
+    }
+}
+
 pub(crate) fn open_editor_at_path(request: &OpenEditorRequest) -> Result<(), String> {
     let path = &request.path;
     log::info!(
-        "[system] Opening editor: type={}, path={}",
+        "[system] Opening editor: type={}, path={}, file={:?}, line={:?}",
         request.editor,
-        path
+        path,
+        request.file,
+        request.line
     );
+    let target = editor_target(request)?;
+
+    if let Some(editor) = load_global_config()
+        .editors
+        .into_iter()
+        .find(|e| e.name == request.editor)
+    {
+        return open_custom_editor(&editor, path, target.as_ref());
+    }
+
+    let cmd = editor_cli_command(&request.editor);
+    let args = match &target {
+        Some((file, line)) => editor_file_args(&request.editor, path, file, *line),
+        None => vec![path.clone()],
+    };
 
     #[cfg(target_os = "macos")]
     {
         let app_name = editor_app_name(&request.editor);
-        if Command::new("open")
-            .args(["-a", app_name, path])
-            .spawn()
-            .is_ok()
+        // open -a 无法定位到行，打开文件时优先用 CLI
+        if target.is_none()
+            && Command::new("open")
+                .args(["-a", app_name, path])
+                .spawn()
+                .is_ok()
         {
             log::info!("[system] Spawned {} via open -a for: {}", app_name, path);
             return Ok(());
         }
-        let cmd = editor_cli_command(&request.editor);
-        match Command::new(cmd).arg(path).spawn() {
+        match Command::new(cmd).args(&args).spawn() {
             Ok(_) => {
-                log::info!("[system] Spawned {} CLI for: {}", cmd, path);
+                log::info!("[system] Spawned {} CLI {:?}", cmd, args);
             }
             Err(e) => {
+                if let Some((file, _)) = &target {
+                    if Command::new("open")
+                        .args(["-a", app_name, file])
+                        .spawn()
+                        .is_ok()
+                    {
+                        log::info!("[system] Spawned {} via open -a for: {}", app_name, file);
+                        return Ok(());
+                    }
+                }
                 log::error!("[system] Failed to spawn editor process: {}", e);
                 return Err(format!("无法打开 {}，请确认已安装该编辑器", app_name));
             }
@@ -128,10 +278,9 @@ pub(crate) fn open_editor_at_path(request: &OpenEditorRequest) -> Result<(), Str
 
     #[cfg(not(target_os = "macos"))]
     {
-        let cmd = editor_cli_command(&request.editor);
-        match Command::new(cmd).arg(path).spawn() {
+        match Command::new(cmd).args(&args).spawn() {
             Ok(_) => {
-                log::info!("[system] Spawned {} for: {}", cmd, path);
+                log::info!("[system] Spawned {} {:?}", cmd, args);
             }
             Err(e) => {
                 log::error!("[system] Failed to spawn editor process: {}", e);
@@ -144,13 +293,44 @@ pub(crate) fn open_editor_at_path(request: &OpenEditorRequest) -> Result<(), Str
 }
 
 #[tauri::command]
-pub(crate) fn open_in_editor(request: OpenEditorRequest) -> Result<(), String> {
+pub(crate) fn open_in_editor(
+    window: tauri::Window,
+    mut request: OpenEditorRequest,
+) -> Result<(), String> {
+    request.path = resolve_workspace_path(window.label(), &request.path)?;
     open_editor_at_path(&request)
 }
 
 #[tauri::command]
-pub(crate) fn reveal_in_finder(path: String) -> Result<(), String> {
-    let normalized = normalize_path(&path);
+pub(crate) async fn open_in_devcontainer(
+    window: tauri::Window,
+    path: String,
+) -> Result<(), String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    tokio::task::spawn_blocking(move || crate::devcontainer::open(&resolved))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub(crate) async fn get_devcontainer_status(
+    window: tauri::Window,
+    path: String,
+) -> Result<DevcontainerStatus, String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    tokio::task::spawn_blocking(move || crate::devcontainer::status(&resolved))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+}
+
+#[tauri::command]
+pub(crate) fn reveal_in_finder(window: tauri::Window, path: String) -> Result<(), String> {
+    let resolved = resolve_workspace_path(window.label(), &path)?;
+    reveal_path_in_file_manager(&resolved)
+}
+
+fn reveal_path_in_file_manager(path: &str) -> Result<(), String> {
+    let normalized = normalize_path(path);
     log::info!("[system] Revealing in file manager: {}", normalized);
 
     #[cfg(target_os = "macos")]
@@ -238,7 +418,7 @@ pub(crate) fn open_log_dir() -> Result<(), String> {
 }
 
 /// Get the platform-appropriate log directory.
-fn get_platform_log_dir() -> Result<PathBuf, String> {
+pub(crate) fn get_platform_log_dir() -> Result<PathBuf, String> {
     #[cfg(target_os = "macos")]
     {
         let home = std::env::var("HOME").map_err(|_| "无法获取用户目录".to_string())?;
@@ -263,10 +443,517 @@ fn get_platform_log_dir() -> Result<PathBuf, String> {
     }
 }
 
+// ==================== 网络代理 ====================
+
+#[tauri::command]
+pub(crate) fn get_proxy_config() -> ProxyConfig {
+    load_global_config().proxy
+}
+
+#[tauri::command]
+pub(crate) fn set_proxy_config(config: ProxyConfig) -> Result<(), String> {
+    if let Some(ca_path) = config.ca_cert_path.as_deref().filter(|p| !p.trim().is_empty()) {
+        if !std::path::Path::new(ca_path.trim()).is_file() {
+            return Err(format!("CA certificate file not found: {}", ca_path));
+        }
+    }
+    let mut global = load_global_config();
+    global.proxy = config;
+    save_global_config_internal(&global)?;
+    crate::network::apply_network_settings(&global.proxy);
+    Ok(())
+}
+
+// ==================== 工单系统凭据 ====================
+
+#[tauri::command]
+pub(crate) fn get_issue_tracker_auth() -> IssueTrackerAuth {
+    load_global_config().issue_tracker_auth
+}
+
+/// 空字符串视为未配置
+#[tauri::command]
+pub(crate) fn set_issue_tracker_auth(auth: IssueTrackerAuth) -> Result<(), String> {
+    let non_empty = |value: Option<String>| {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let mut global = load_global_config();
+    global.issue_tracker_auth = IssueTrackerAuth {
+        jira_email: non_empty(auth.jira_email),
+        jira_token: non_empty(auth.jira_token),
+        linear_api_key: non_empty(auth.linear_api_key),
+    };
+    save_global_config_internal(&global)?;
+    crate::state::ISSUE_CACHE
+        .lock()
+        .map_err(|_| "Internal state error".to_string())?
+        .clear();
+    Ok(())
+}
+
+// ==================== 自定义编辑器 ====================
+
+#[tauri::command]
+pub(crate) fn get_custom_editors() -> Vec<CustomEditor> {
+    load_global_config().editors
+}
+
+#[tauri::command]
+pub(crate) fn set_custom_editors(editors: Vec<CustomEditor>) -> Result<(), String> {
+    let mut names = std::collections::HashSet::new();
+    for editor in &editors {
+        let name = editor.name.trim();
+        if name.is_empty() {
+            return Err("编辑器名称不能为空".to_string());
+        }
+        if !names.insert(name) {
+            return Err(format!("编辑器名称重复: {}", name));
+        }
+        let has_command = editor
+            .command
+            .as_deref()
+            .is_some_and(|c| !c.trim().is_empty());
+        let has_app = editor
+            .app_name
+            .as_deref()
+            .is_some_and(|a| !a.trim().is_empty());
+        if !has_command && !has_app {
+            return Err(format!("编辑器 {} 需要配置命令或应用名", name));
+        }
+    }
+    let mut global = load_global_config();
+    global.editors = editors
+        .into_iter()
+        .map(|e| CustomEditor {
+            name: e.name.trim().to_string(),
+            ..e
+        })
+        .collect();
+    save_global_config_internal(&global)
+}
+
+// ==================== 频道通知 ====================
+
+#[tauri::command]
+pub(crate) fn get_notifiers() -> Vec<NotifierConfig> {
+    load_global_config().notifiers
+}
+
+/// 忽略未填写 webhook 地址的条目
+#[tauri::command]
+pub(crate) fn set_notifiers(notifiers: Vec<NotifierConfig>) -> Result<(), String> {
+    let mut global = load_global_config();
+    global.notifiers = notifiers
+        .into_iter()
+        .map(|n| NotifierConfig {
+            webhook_url: n.webhook_url.trim().to_string(),
+            ..n
+        })
+        .filter(|n| !n.webhook_url.is_empty())
+        .collect();
+    save_global_config_internal(&global)
+}
+
+// ==================== 配置加密 ====================
+
+#[tauri::command]
+pub(crate) fn get_config_encryption_enabled() -> bool {
+    load_global_config().encrypt_config
+}
+
+/// 开启/关闭敏感字段加密，并按新设置重写 global.json 和所有已添加 workspace 的配置文件
+#[tauri::command]
+pub(crate) fn set_config_encryption_enabled(enabled: bool) -> Result<(), String> {
+    if enabled {
+        crate::secrets::ensure_key()?;
+    }
+    let mut global = load_global_config();
+    global.encrypt_config = enabled;
+    save_global_config_internal(&global)?;
+
+    for workspace in &global.workspaces {
+        if !get_workspace_config_path(&workspace.path).exists() {
+            continue;
+        }
+        let config = load_workspace_config(&workspace.path);
+        if let Err(e) = save_workspace_config_internal(&workspace.path, &config) {
+            log::warn!(
+                "[system] Failed to rewrite workspace config {}: {}",
+                workspace.path,
+                e
+            );
+        }
+    }
+    log::info!(
+        "[system] Config encryption {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+    Ok(())
+}
+
+// ==================== 日志查看 ====================
+
+const DEFAULT_TAIL_LINES: usize = 200;
+const MAX_TAIL_LINES: usize = 5000;
+// 只读取日志文件末尾的这部分内容来取最后 N 行，避免大文件整体读入内存
+const TAIL_READ_BYTES: u64 = 2 * 1024 * 1024;
+const LOG_POLL_INTERVAL_MS: u64 = 500;
+
+static LOG_TAILER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// 找到日志目录下最近修改的 .log 文件
+fn latest_log_file() -> Result<PathBuf, String> {
+    let log_dir = get_platform_log_dir()?;
+    std::fs::read_dir(&log_dir)
+        .map_err(|e| format!("无法读取日志目录: {}", e))?
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "log"))
+        .filter_map(|e| {
+            let modified = e.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, e.path()))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+        .ok_or_else(|| "未找到日志文件".to_string())
+}
+
+/// 返回最新日志文件的最后 N 行（默认 200，最多 5000）
+pub(crate) fn tail_app_logs_internal(lines: Option<usize>) -> Result<Vec<String>, String> {
+    let count = lines.unwrap_or(DEFAULT_TAIL_LINES).clamp(1, MAX_TAIL_LINES);
+    let path = latest_log_file()?;
+
+    let mut file = std::fs::File::open(&path).map_err(|e| format!("无法打开日志文件: {}", e))?;
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let start = len.saturating_sub(TAIL_READ_BYTES);
+    file.seek(SeekFrom::Start(start))
+        .map_err(|e| format!("无法读取日志文件: {}", e))?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)
+        .map_err(|e| format!("无法读取日志文件: {}", e))?;
+
+    let text = String::from_utf8_lossy(&buf);
+    let mut all: Vec<&str> = text.lines().collect();
+    // 从中间截断时第一行可能不完整
+    if start > 0 && !all.is_empty() {
+        all.remove(0);
+    }
+    let skip = all.len().saturating_sub(count);
+    Ok(all[skip..].iter().map(|l| l.to_string()).collect())
+}
+
+#[tauri::command]
+pub(crate) fn tail_app_logs(lines: Option<usize>) -> Result<Vec<String>, String> {
+    tail_app_logs_internal(lines)
+}
+
+/// 启动日志跟随任务（只启动一次）：轮询最新日志文件，将新增行推送到 LOG_BROADCAST。
+/// 没有订阅者时只推进读取位置，不做广播。
+pub(crate) fn ensure_log_tailer() {
+    if LOG_TAILER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    log::info!("[system] Starting app log tailer");
+    tokio::spawn(async move {
+        let mut current: Option<PathBuf> = None;
+        let mut offset: u64 = 0;
+        let mut pending = String::new();
+        let mut utf8_pending: Vec<u8> = Vec::new();
+        loop {
+            tokio::time::sleep(std::time::Duration::from_millis(LOG_POLL_INTERVAL_MS)).await;
+
+            let path = match latest_log_file() {
+                Ok(p) => p,
+                Err(_) => continue,
+            };
+            let len = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            // 新文件或文件被轮转/截断：从末尾开始跟随
+            if current.as_ref() != Some(&path) || len < offset {
+                current = Some(path);
+                offset = len;
+                pending.clear();
+                utf8_pending.clear();
+                continue;
+            }
+            if len == offset {
+                continue;
+            }
+
+            let mut chunk = Vec::new();
+            let read_ok = std::fs::File::open(&path)
+                .and_then(|mut f| {
+                    f.seek(SeekFrom::Start(offset))?;
+                    f.read_to_end(&mut chunk)
+                })
+                .is_ok();
+            if !read_ok {
+                continue;
+            }
+            offset += chunk.len() as u64;
+
+            if LOG_BROADCAST.receiver_count() == 0 {
+                pending.clear();
+                utf8_pending.clear();
+                continue;
+            }
+            let mut combined = std::mem::take(&mut utf8_pending);
+            combined.extend(chunk);
+            let (text, rest) = bytes_to_utf8_with_pending(&combined);
+            utf8_pending = rest;
+            pending.push_str(&text);
+            while let Some(pos) = pending.find('\n') {
+                let line: String = pending.drain(..=pos).collect();
+                let line = line.trim_end_matches(['\r', '\n']);
+                if let Ok(json_str) = serde_json::to_string(&serde_json::json!({ "line": line })) {
+                    let _ = LOG_BROADCAST.send(json_str);
+                }
+            }
+        }
+    });
+}
+
+// ==================== 环境检查 ====================
+
+// git worktree repair / sparse-checkout --cone 需要的最低版本
+const MIN_GIT_VERSION: (u32, u32) = (2, 30);
+const DISK_SPACE_WARNING_BYTES: u64 = 5 * 1024 * 1024 * 1024;
+const DISK_SPACE_ERROR_BYTES: u64 = 1024 * 1024 * 1024;
+
+fn env_check(
+    id: &str,
+    status: EnvironmentCheckStatus,
+    detail: impl Into<String>,
+    fix: Option<&str>,
+) -> EnvironmentCheck {
+    EnvironmentCheck {
+        id: id.to_string(),
+        status,
+        detail: detail.into(),
+        fix: fix.map(|f| f.to_string()),
+    }
+}
+
+/// 运行命令并返回 trim 后的 stdout；命令不存在或退出码非 0 时返回 None
+fn command_stdout(program: &str, args: &[&str]) -> Option<String> {
+    Command::new(program)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+}
+
+/// 从 "git version 2.39.2 (Apple Git-143)" 中解析出 (major, minor)
+fn parse_git_version(output: &str) -> Option<(u32, u32)> {
+    let version = output.split_whitespace().nth(2)?;
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+fn check_git() -> EnvironmentCheck {
+    let Some(output) = command_stdout("git", &["--version"]) else {
+        return env_check(
+            "git",
+            EnvironmentCheckStatus::Error,
+            "git not found in PATH",
+            Some("Install Git from https://git-scm.com/downloads and restart the app"),
+        );
+    };
+    match parse_git_version(&output) {
+        Some(version) if version < MIN_GIT_VERSION => env_check(
+            "git",
+            EnvironmentCheckStatus::Warning,
+            output,
+            Some("Upgrade Git to 2.30 or newer; worktree repair and sparse checkout need it"),
+        ),
+        _ => env_check("git", EnvironmentCheckStatus::Ok, output, None),
+    }
+}
+
+fn check_gh() -> EnvironmentCheck {
+    let Some(output) = command_stdout("gh", &["--version"]) else {
+        return env_check(
+            "gh",
+            EnvironmentCheckStatus::Warning,
+            "GitHub CLI (gh) not found",
+            Some("Install GitHub CLI from https://cli.github.com to create pull requests"),
+        );
+    };
+    let version = output.lines().next().unwrap_or_default().to_string();
+    // gh auth token 只读取本地凭据，不发网络请求
+    if command_stdout("gh", &["auth", "token"]).is_none() {
+        return env_check(
+            "gh",
+            EnvironmentCheckStatus::Warning,
+            format!("{} (not logged in)", version),
+            Some("Run `gh auth login` in a terminal"),
+        );
+    }
+    env_check("gh", EnvironmentCheckStatus::Ok, version, None)
+}
+
+fn check_credential_helper() -> EnvironmentCheck {
+    match command_stdout("git", &["config", "--get-all", "credential.helper"]) {
+        Some(helpers) if !helpers.is_empty() => env_check(
+            "credential_helper",
+            EnvironmentCheckStatus::Ok,
+            helpers.lines().collect::<Vec<_>>().join(", "),
+            None,
+        ),
+        _ => {
+            #[cfg(target_os = "macos")]
+            let fix = "Run `git config --global credential.helper osxkeychain`";
+            #[cfg(target_os = "windows")]
+            let fix = "Run `git config --global credential.helper manager`";
+            #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+            let fix = "Run `git config --global credential.helper store` or configure SSH keys";
+            env_check(
+                "credential_helper",
+                EnvironmentCheckStatus::Warning,
+                "No git credential helper configured; HTTPS fetch/push will prompt for credentials",
+                Some(fix),
+            )
+        }
+    }
+}
+
+fn check_symlink() -> EnvironmentCheck {
+    let dir = std::env::temp_dir().join(format!("wm-symlink-check-{}", std::process::id()));
+    let target = dir.join("target");
+    let link = dir.join("link");
+    let result = std::fs::create_dir_all(&target).and_then(|_| {
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&target, &link)
+        }
+        #[cfg(windows)]
+        {
+            std::os::windows::fs::symlink_dir(&target, &link)
+        }
+    });
+    let _ = std::fs::remove_dir_all(&dir);
+
+    match result {
+        Ok(()) => env_check(
+            "symlink",
+            EnvironmentCheckStatus::Ok,
+            "Symlinks can be created",
+            None,
+        ),
+        Err(e) => {
+            #[cfg(windows)]
+            let fix = "Enable Developer Mode (Settings > Privacy & security > For developers); folders fall back to junctions until then";
+            #[cfg(not(windows))]
+            let fix = "Check permissions on the temp and workspace directories";
+            env_check(
+                "symlink",
+                EnvironmentCheckStatus::Warning,
+                format!("Cannot create symlinks: {}", e),
+                Some(fix),
+            )
+        }
+    }
+}
+
+/// 查询 path 所在磁盘的可用空间（字节）
+fn free_disk_space(path: &std::path::Path) -> Option<u64> {
+    #[cfg(not(target_os = "windows"))]
+    {
+        let output = command_stdout("df", &["-Pk", path.to_str()?])?;
+        let line = output.lines().nth(1)?;
+        let available_kb: u64 = line.split_whitespace().nth(3)?.parse().ok()?;
+        Some(available_kb * 1024)
+    }
+    #[cfg(target_os = "windows")]
+    {
+        let script = format!(
+            "(Get-Item -LiteralPath '{}').PSDrive.Free",
+            path.to_string_lossy().replace('\'', "''")
+        );
+        command_stdout("powershell", &["-NoProfile", "-Command", &script])?
+            .parse()
+            .ok()
+    }
+}
+
+fn check_disk_space(path: &std::path::Path) -> EnvironmentCheck {
+    let Some(free) = free_disk_space(path) else {
+        return env_check(
+            "disk_space",
+            EnvironmentCheckStatus::Warning,
+            format!("Unable to determine free space for {}", path.display()),
+            None,
+        );
+    };
+    let detail = format!("{} free at {}", format_size(free), path.display());
+    if free < DISK_SPACE_ERROR_BYTES {
+        env_check(
+            "disk_space",
+            EnvironmentCheckStatus::Error,
+            detail,
+            Some("Free up disk space; each worktree needs a full checkout of its projects"),
+        )
+    } else if free < DISK_SPACE_WARNING_BYTES {
+        env_check(
+            "disk_space",
+            EnvironmentCheckStatus::Warning,
+            detail,
+            Some("Low disk space; archive or delete unused worktrees"),
+        )
+    } else {
+        env_check("disk_space", EnvironmentCheckStatus::Ok, detail, None)
+    }
+}
+
+/// 首次启动检查：git 版本、gh CLI、凭据助手、软链接能力、磁盘空间。
+/// path 为空时检查用户目录所在磁盘。
+pub(crate) fn check_environment_internal(path: Option<String>) -> EnvironmentReport {
+    let disk_path = path
+        .filter(|p| !p.trim().is_empty())
+        .map(PathBuf::from)
+        .filter(|p| p.exists())
+        .or_else(|| {
+            std::env::var(if cfg!(windows) { "USERPROFILE" } else { "HOME" })
+                .ok()
+                .map(PathBuf::from)
+        })
+        .unwrap_or_else(std::env::temp_dir);
+
+    let checks = vec![
+        check_git(),
+        check_gh(),
+        check_credential_helper(),
+        check_symlink(),
+        check_disk_space(&disk_path),
+    ];
+    let ready = checks
+        .iter()
+        .all(|c| c.status != EnvironmentCheckStatus::Error);
+    log::info!(
+        "[system] Environment check: {}",
+        checks
+            .iter()
+            .map(|c| format!("{}={:?}", c.id, c.status))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    EnvironmentReport { checks, ready }
+}
+
+#[tauri::command]
+pub(crate) async fn check_environment(path: Option<String>) -> Result<EnvironmentReport, String> {
+    tokio::task::spawn_blocking(move || check_environment_internal(path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+}
+
 // ==================== HTTP Server 共享接口 ====================
 
 pub fn open_in_terminal_internal(path: &str) -> Result<(), String> {
-    open_in_terminal(path.to_string())
+    open_terminal_at_path(path)
 }
 
 pub fn open_in_editor_internal(request: &OpenEditorRequest) -> Result<(), String> {
@@ -274,9 +961,60 @@ pub fn open_in_editor_internal(request: &OpenEditorRequest) -> Result<(), String
 }
 
 pub fn reveal_in_finder_internal(path: &str) -> Result<(), String> {
-    reveal_in_finder(path.to_string())
+    reveal_path_in_file_manager(path)
 }
 
 pub fn open_log_dir_internal() -> Result<(), String> {
     open_log_dir()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(file: Option<&str>, line: Option<u32>) -> OpenEditorRequest {
+        OpenEditorRequest {
+            path: "/ws/projects/api".to_string(),
+            editor: "vscode".to_string(),
+            file: file.map(str::to_string),
+            line,
+        }
+    }
+
+    #[test]
+    fn editor_target_without_file_opens_the_directory() {
+        assert_eq!(editor_target(&request(None, Some(3))).unwrap(), None);
+        assert_eq!(editor_target(&request(Some("  "), None)).unwrap(), None);
+    }
+
+    #[test]
+    fn editor_target_joins_the_file_and_line() {
+        let (file, line) = editor_target(&request(Some("src/main.rs"), Some(42)))
+            .unwrap()
+            .unwrap();
+        assert_eq!(Path::new(&file), Path::new("/ws/projects/api/src/main.rs"));
+        assert_eq!(line, 42);
+
+        let (file, line) = editor_target(&request(Some("./README.md"), None))
+            .unwrap()
+            .unwrap();
+        assert_eq!(Path::new(&file), Path::new("/ws/projects/api/README.md"));
+        assert_eq!(line, 1);
+
+        let (_, line) = editor_target(&request(Some("README.md"), Some(0)))
+            .unwrap()
+            .unwrap();
+        assert_eq!(line, 1);
+    }
+
+    #[test]
+    fn editor_target_rejects_files_outside_the_directory() {
+        for file in ["../secrets.env", "src/../../other", "/etc/passwd"] {
+            assert!(
+                editor_target(&request(Some(file), None)).is_err(),
+                "{}",
+                file
+            );
+        }
+    }
+}
@@ -489,7 +489,7 @@ pub(crate) async fn voice_refine_text_inner(text: String) -> Result<String, Stri
         .filter(|k| !k.is_empty())
         .ok_or_else(|| "Dashscope API Key 未配置".to_string())?;
 
-    let client = reqwest::Client::builder()
+    let client = crate::network::http_client_builder()
         .timeout(std::time::Duration::from_secs(10))
         .build()
         .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
@@ -1,11 +1,17 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use tauri::Emitter;
 
-use crate::config::{load_global_config, load_occupation_state};
+use crate::config::{
+    get_window_workspace_config, load_global_config, load_occupation_state,
+    load_workspace_config, save_global_config_internal,
+};
+use crate::git_ops;
 use crate::state::{
-    LOCK_BROADCAST, TERMINAL_STATES, TERMINAL_STATE_BROADCAST, WINDOW_WORKSPACES, WORKTREE_LOCKS,
+    APP_HANDLE, AUTO_LOCKS, AUTO_LOCK_LAST_SEEN, FOCUS_BROADCAST, FOCUS_STATES, GIT_WORKTREE_LOCKED,
+    LOCK_BROADCAST, PTY_MANAGER, TERMINAL_STATES, TERMINAL_STATE_BROADCAST, WINDOW_WORKSPACES,
+    WORKTREE_LOCKS,
 };
-use crate::types::TerminalState;
+use crate::types::{FocusState, TerminalState};
 
 // ==================== 多窗口管理 ====================
 
@@ -61,6 +67,7 @@ pub fn unregister_window_impl(window_label: &str) {
             .map(|((ws_path, _), _)| ws_path.clone())
             .collect();
         locks.retain(|_, v| *v != label);
+        AUTO_LOCKS.lock().unwrap().retain(|_, (v, _)| *v != label);
         log::info!(
             "[window] Window '{}' unregistered, released {} locks",
             window_label,
@@ -100,6 +107,8 @@ pub fn lock_worktree_impl(
                 return Err(format!("Worktree \"{}\" 已在其他窗口中打开", worktree_name));
             }
         }
+        // 手动锁定后不再受空闲自动释放影响
+        AUTO_LOCKS.lock().unwrap().remove(&key);
         locks.insert(key, label);
     }
     log::info!(
@@ -130,6 +139,7 @@ pub fn unlock_worktree_impl(window_label: &str, workspace_path: String, worktree
         if let Some(existing_label) = locks.get(&key) {
             if *existing_label == label {
                 locks.remove(&key);
+                AUTO_LOCKS.lock().unwrap().remove(&key);
                 log::info!(
                     "[window] Worktree unlocked: ws={}, wt={}, by={}",
                     workspace_path,
@@ -151,6 +161,135 @@ pub(crate) fn unlock_worktree(
     unlock_worktree_impl(window.label(), workspace_path, worktree_name)
 }
 
+// ==================== 终端活动自动锁 ====================
+
+/// 同一终端两次处理活动之间的最小间隔，避免每次按键都读取配置
+const AUTO_LOCK_THROTTLE_SECS: u64 = 5;
+/// 空闲检查周期
+const AUTO_LOCK_SWEEP_INTERVAL_SECS: u64 = 15;
+
+/// 客户端向终端写入时调用：若 workspace 开启了 auto_lock，且该终端位于某个 worktree 内，
+/// 则为该客户端获取锁（已被其他客户端锁定时不抢占），并刷新其活动时间
+pub(crate) fn note_terminal_activity(window_label: &str, pty_session_id: &str) {
+    {
+        let mut seen = AUTO_LOCK_LAST_SEEN.lock().unwrap();
+        let now = std::time::Instant::now();
+        if let Some(last) = seen.get(pty_session_id) {
+            if now.duration_since(*last).as_secs() < AUTO_LOCK_THROTTLE_SECS {
+                return;
+            }
+        }
+        seen.insert(pty_session_id.to_string(), now);
+    }
+
+    let Some((workspace_path, config)) = get_window_workspace_config(window_label) else {
+        return;
+    };
+    if !config.auto_lock.enabled {
+        return;
+    }
+    let cwd = match PTY_MANAGER.lock() {
+        Ok(m) => m.session_cwd(pty_session_id),
+        Err(_) => None,
+    };
+    let Some(cwd) = cwd else {
+        return;
+    };
+    let worktrees_root = std::path::Path::new(&workspace_path).join(&config.worktrees_dir);
+    let worktree_name = match std::path::Path::new(&cwd)
+        .strip_prefix(&worktrees_root)
+        .ok()
+        .and_then(|rel| rel.components().next())
+    {
+        Some(c) => c.as_os_str().to_string_lossy().to_string(),
+        None => return,
+    };
+
+    let label = window_label.to_string();
+    let key = (workspace_path.clone(), worktree_name.clone());
+    let acquired = {
+        let mut locks = WORKTREE_LOCKS.lock().unwrap();
+        let mut auto_locks = AUTO_LOCKS.lock().unwrap();
+        match locks.get(&key) {
+            Some(holder) if *holder != label => return,
+            Some(_) => {
+                // 已持有：只刷新自动锁的活动时间，手动锁保持不变
+                if let Some(entry) = auto_locks.get_mut(&key) {
+                    entry.1 = std::time::Instant::now();
+                }
+                false
+            }
+            None => {
+                locks.insert(key.clone(), label.clone());
+                auto_locks.insert(key, (label, std::time::Instant::now()));
+                true
+            }
+        }
+    };
+    if acquired {
+        log::info!(
+            "[window] Auto-locked worktree on terminal activity: ws={}, wt={}, by={}",
+            workspace_path,
+            worktree_name,
+            window_label
+        );
+        broadcast_lock_state(&workspace_path);
+    }
+}
+
+/// 释放空闲超时（或所在 workspace 已关闭 auto_lock）的自动锁
+pub(crate) fn release_idle_auto_locks() {
+    let entries: Vec<((String, String), String, std::time::Instant)> = {
+        let auto_locks = AUTO_LOCKS.lock().unwrap();
+        auto_locks
+            .iter()
+            .map(|(k, (label, at))| (k.clone(), label.clone(), *at))
+            .collect()
+    };
+
+    AUTO_LOCK_LAST_SEEN
+        .lock()
+        .unwrap()
+        .retain(|_, at| at.elapsed().as_secs() < 3600);
+
+    let mut affected = std::collections::HashSet::new();
+    for (key, label, last_active) in entries {
+        let auto_lock = load_workspace_config(&key.0).auto_lock;
+        if auto_lock.enabled && last_active.elapsed().as_secs() < auto_lock.idle_secs {
+            continue;
+        }
+        let mut locks = WORKTREE_LOCKS.lock().unwrap();
+        let mut auto_locks = AUTO_LOCKS.lock().unwrap();
+        // 期间可能被手动锁定或重新活跃，需再次确认
+        match auto_locks.get(&key) {
+            Some((l, at)) if *l == label && *at == last_active => {}
+            _ => continue,
+        }
+        auto_locks.remove(&key);
+        if locks.get(&key) == Some(&label) {
+            locks.remove(&key);
+            log::info!(
+                "[window] Auto-lock released after inactivity: ws={}, wt={}, by={}",
+                key.0,
+                key.1,
+                label
+            );
+            affected.insert(key.0.clone());
+        }
+    }
+    for ws_path in affected {
+        broadcast_lock_state(&ws_path);
+    }
+}
+
+/// 启动后台线程，定期释放空闲的自动锁
+pub(crate) fn start_auto_lock_sweeper() {
+    std::thread::spawn(|| loop {
+        std::thread::sleep(std::time::Duration::from_secs(AUTO_LOCK_SWEEP_INTERVAL_SECS));
+        release_idle_auto_locks();
+    });
+}
+
 /// 获取指定 workspace 中所有被锁定的 worktree 列表 (worktree_name -> window_label)
 #[tauri::command]
 pub(crate) fn get_locked_worktrees(workspace_path: String) -> HashMap<String, String> {
@@ -201,17 +340,16 @@ pub(crate) fn broadcast_terminal_state(
     let key = (workspace_path.clone(), worktree_name.clone());
 
     // 更新缓存
-    if let Ok(mut states) = TERMINAL_STATES.lock() {
-        states.insert(
-            key,
-            TerminalState {
-                activated_terminals: activated_terminals.clone(),
-                active_terminal_tab: active_terminal_tab.clone(),
-                terminal_visible,
-                client_id: client_id.clone(),
-            },
-        );
-    }
+    let tab_labels = cache_terminal_state(
+        key,
+        TerminalState {
+            activated_terminals: activated_terminals.clone(),
+            active_terminal_tab: active_terminal_tab.clone(),
+            terminal_visible,
+            client_id: client_id.clone(),
+            tab_labels: BTreeMap::new(),
+        },
+    );
 
     // 广播给所有连接的客户端（WebSocket）
     if let Ok(json_str) = serde_json::to_string(&serde_json::json!({
@@ -221,6 +359,7 @@ pub(crate) fn broadcast_terminal_state(
         "activeTerminalTab": active_terminal_tab,
         "terminalVisible": terminal_visible,
         "clientId": client_id,
+        "tabLabels": tab_labels,
     })) {
         let _ = TERMINAL_STATE_BROADCAST.send(json_str);
     }
@@ -235,10 +374,179 @@ pub(crate) fn broadcast_terminal_state(
             "activeTerminalTab": active_terminal_tab,
             "terminalVisible": terminal_visible,
             "clientId": client_id,
+            "tabLabels": tab_labels,
         }),
     );
 }
 
+/// 写入终端状态缓存。客户端广播的状态不带标签名，仍打开的标签沿用缓存中的标签名；
+/// 返回最终的标签名供广播
+pub(crate) fn cache_terminal_state(
+    key: (String, String),
+    mut state: TerminalState,
+) -> BTreeMap<String, String> {
+    let Ok(mut states) = TERMINAL_STATES.lock() else {
+        return state.tab_labels;
+    };
+    if let Some(prev) = states.get(&key) {
+        for (path, label) in &prev.tab_labels {
+            if state.activated_terminals.contains(path) {
+                state
+                    .tab_labels
+                    .entry(path.clone())
+                    .or_insert_with(|| label.clone());
+            }
+        }
+    }
+    let tab_labels = state.tab_labels.clone();
+    states.insert(key, state);
+    tab_labels
+}
+
+/// 将后端打开的终端（终端预设）加入 worktree 的终端状态并设为当前标签，广播给所有客户端
+pub(crate) fn register_terminal_tab(
+    workspace_path: &str,
+    worktree_name: &str,
+    terminal_path: &str,
+    label: &str,
+) {
+    let state = {
+        let Ok(mut states) = TERMINAL_STATES.lock() else {
+            return;
+        };
+        let state = states
+            .entry((workspace_path.to_string(), worktree_name.to_string()))
+            .or_insert_with(|| TerminalState {
+                activated_terminals: vec![],
+                active_terminal_tab: None,
+                terminal_visible: false,
+                client_id: None,
+                tab_labels: BTreeMap::new(),
+            });
+        if !state.activated_terminals.iter().any(|p| p == terminal_path) {
+            state.activated_terminals.push(terminal_path.to_string());
+        }
+        state.active_terminal_tab = Some(terminal_path.to_string());
+        state.terminal_visible = true;
+        state.client_id = None;
+        state
+            .tab_labels
+            .insert(terminal_path.to_string(), label.to_string());
+        state.clone()
+    };
+
+    let payload = serde_json::json!({
+        "workspacePath": workspace_path,
+        "worktreeName": worktree_name,
+        "activatedTerminals": state.activated_terminals,
+        "activeTerminalTab": state.active_terminal_tab,
+        "terminalVisible": state.terminal_visible,
+        "clientId": state.client_id,
+        "tabLabels": state.tab_labels,
+    });
+    let _ = TERMINAL_STATE_BROADCAST.send(payload.to_string());
+    if let Some(app) = APP_HANDLE.lock().ok().and_then(|h| h.as_ref().cloned()) {
+        let _ = app.emit("terminal-state-update", payload);
+    }
+}
+
+// ==================== 聚焦跟随（演示模式） ====================
+
+pub(crate) fn get_focus_follow_enabled_inner() -> Result<bool, String> {
+    Ok(load_global_config().focus_follow_enabled)
+}
+
+pub(crate) fn set_focus_follow_enabled_inner(enabled: bool) -> Result<(), String> {
+    let mut config = load_global_config();
+    config.focus_follow_enabled = enabled;
+    save_global_config_internal(&config)?;
+    if !enabled {
+        if let Ok(mut states) = FOCUS_STATES.lock() {
+            states.clear();
+        }
+    }
+    log::info!("[window] Focus follow enabled: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn get_focus_follow_enabled() -> Result<bool, String> {
+    get_focus_follow_enabled_inner()
+}
+
+#[tauri::command]
+pub(crate) fn set_focus_follow_enabled(enabled: bool) -> Result<(), String> {
+    set_focus_follow_enabled_inner(enabled)
+}
+
+/// 获取缓存的聚焦状态（网页端进入演示模式时先同步一次）
+pub(crate) fn get_focus_state_inner(workspace_path: String) -> Option<FocusState> {
+    FOCUS_STATES
+        .lock()
+        .ok()
+        .and_then(|states| states.get(&workspace_path).cloned())
+}
+
+#[tauri::command]
+pub(crate) fn get_focus_state(workspace_path: String) -> Option<FocusState> {
+    get_focus_state_inner(workspace_path)
+}
+
+/// 广播桌面端聚焦的 worktree/project，未开启聚焦跟随时直接忽略
+pub(crate) fn broadcast_focus_inner(
+    workspace_path: String,
+    worktree_name: Option<String>,
+    project_name: Option<String>,
+    client_id: Option<String>,
+) {
+    if !load_global_config().focus_follow_enabled {
+        return;
+    }
+    log::debug!(
+        "[window] Broadcasting focus: ws={}, wt={:?}, project={:?}",
+        workspace_path,
+        worktree_name,
+        project_name
+    );
+    let state = FocusState {
+        worktree_name,
+        project_name,
+        client_id,
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    // 更新缓存
+    if let Ok(mut states) = FOCUS_STATES.lock() {
+        states.insert(workspace_path.clone(), state.clone());
+    }
+
+    let payload = serde_json::json!({
+        "workspacePath": workspace_path,
+        "worktreeName": state.worktree_name,
+        "projectName": state.project_name,
+        "clientId": state.client_id,
+        "updatedAt": state.updated_at,
+    });
+
+    // 广播给所有连接的客户端（WebSocket）
+    let _ = FOCUS_BROADCAST.send(payload.to_string());
+
+    // 同时通过 Tauri 事件发送给所有桌面端窗口
+    if let Some(app) = APP_HANDLE.lock().ok().and_then(|h| h.clone()) {
+        let _ = app.emit("focus-update", payload);
+    }
+}
+
+#[tauri::command]
+pub(crate) fn broadcast_focus(
+    workspace_path: String,
+    worktree_name: Option<String>,
+    project_name: Option<String>,
+    client_id: Option<String>,
+) {
+    broadcast_focus_inner(workspace_path, worktree_name, project_name, client_id)
+}
+
 #[tauri::command]
 pub(crate) async fn open_workspace_window(
     app: tauri::AppHandle,
@@ -305,6 +613,8 @@ pub(crate) async fn open_workspace_window(
 /// Broadcast the current lock state for a given workspace to all WebSocket clients.
 /// `locks` must already be dropped before calling this to avoid deadlocks.
 pub(crate) fn broadcast_lock_state(workspace_path: &str) {
+    // 所有锁状态变更都会经过这里，顺带同步 git 层面的 worktree lock
+    sync_git_worktree_locks(workspace_path);
     let lock_snapshot: HashMap<String, String> = {
         let locks = WORKTREE_LOCKS.lock().unwrap();
         locks
@@ -328,6 +638,128 @@ pub(crate) fn broadcast_lock_state(workspace_path: &str) {
     }
 }
 
+// ==================== Git worktree lock ====================
+
+const GIT_LOCK_REASON: &str = "in use by worktree-manager (pinned or deployed to main)";
+
+/// 对 worktree 下每个项目执行 `git worktree lock`/`unlock`，防止外部 prune/remove 误删。
+/// worktree 目录不存在时返回 false
+fn set_project_worktrees_locked(
+    workspace_path: &str,
+    worktrees_dir: &str,
+    worktree_name: &str,
+    locked: bool,
+) -> bool {
+    let root = std::path::Path::new(workspace_path);
+    let projects_path = root.join(worktrees_dir).join(worktree_name).join("projects");
+    let Ok(entries) = std::fs::read_dir(&projects_path) else {
+        return false;
+    };
+    for entry in entries.flatten() {
+        let proj_path = entry.path();
+        let main_proj_path = root.join("projects").join(entry.file_name());
+        if !proj_path.join(".git").is_file() || !main_proj_path.exists() {
+            continue;
+        }
+        if let Err(e) =
+            git_ops::set_worktree_locked(&main_proj_path, &proj_path, locked, GIT_LOCK_REASON)
+        {
+            log::warn!("[window] {}", e);
+        }
+    }
+    true
+}
+
+/// 手动锁定（非自动锁）或部署到主工作区的 worktree 需要持有 git worktree lock，
+/// 其余之前加过锁的在此解锁
+pub(crate) fn sync_git_worktree_locks(workspace_path: &str) {
+    let mut git_locked = GIT_WORKTREE_LOCKED.lock().unwrap();
+
+    let mut wanted: std::collections::HashSet<String> = {
+        let locks = WORKTREE_LOCKS.lock().unwrap();
+        let auto_locks = AUTO_LOCKS.lock().unwrap();
+        locks
+            .keys()
+            .filter(|key| key.0 == workspace_path && !auto_locks.contains_key(*key))
+            .map(|(_, wt)| wt.clone())
+            .collect()
+    };
+    if let Some(occupation) = load_occupation_state(workspace_path) {
+        wanted.insert(occupation.worktree_name);
+    }
+
+    let to_unlock: Vec<String> = git_locked
+        .iter()
+        .filter(|(ws, wt)| ws == workspace_path && !wanted.contains(wt))
+        .map(|(_, wt)| wt.clone())
+        .collect();
+    let to_lock: Vec<String> = wanted
+        .into_iter()
+        .filter(|wt| !git_locked.contains(&(workspace_path.to_string(), wt.clone())))
+        .collect();
+    if to_lock.is_empty() && to_unlock.is_empty() {
+        return;
+    }
+
+    let config = load_workspace_config(workspace_path);
+    for wt in to_unlock {
+        set_project_worktrees_locked(workspace_path, &config.worktrees_dir, &wt, false);
+        git_locked.remove(&(workspace_path.to_string(), wt));
+    }
+    for wt in to_lock {
+        if set_project_worktrees_locked(workspace_path, &config.worktrees_dir, &wt, true) {
+            log::info!("[window] git worktree lock: ws={}, wt={}", workspace_path, wt);
+            git_locked.insert((workspace_path.to_string(), wt));
+        }
+    }
+}
+
+/// 归档等需要移除 git worktree 的操作前调用：立即解锁并忘记该 worktree 的加锁记录
+pub(crate) fn release_git_worktree_lock(workspace_path: &str, worktrees_dir: &str, worktree_name: &str) {
+    let key = (workspace_path.to_string(), worktree_name.to_string());
+    let mut git_locked = GIT_WORKTREE_LOCKED.lock().unwrap();
+    // 即使没有记录也解锁一次，清理上次异常退出遗留的锁
+    set_project_worktrees_locked(workspace_path, worktrees_dir, worktree_name, false);
+    git_locked.remove(&key);
+}
+
+/// worktree 改名后迁移按名称索引的锁和终端状态；终端路径中的旧目录前缀替换为新目录。
+/// 调用方随后需要 `broadcast_lock_state`
+pub(crate) fn rename_worktree_state(
+    workspace_path: &str,
+    old_name: &str,
+    new_name: &str,
+    old_path: &str,
+    new_path: &str,
+) {
+    let old_key = (workspace_path.to_string(), old_name.to_string());
+    let new_key = (workspace_path.to_string(), new_name.to_string());
+    {
+        let mut locks = WORKTREE_LOCKS.lock().unwrap();
+        let mut auto_locks = AUTO_LOCKS.lock().unwrap();
+        if let Some(label) = locks.remove(&old_key) {
+            locks.insert(new_key.clone(), label);
+        }
+        if let Some(entry) = auto_locks.remove(&old_key) {
+            auto_locks.insert(new_key.clone(), entry);
+        }
+    }
+
+    let rebase = |p: &str| match p.strip_prefix(old_path) {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\', '#']) => {
+            format!("{}{}", new_path, rest)
+        }
+        _ => p.to_string(),
+    };
+    if let Ok(mut states) = TERMINAL_STATES.lock() {
+        if let Some(mut state) = states.remove(&old_key) {
+            state.activated_terminals = state.activated_terminals.iter().map(|p| rebase(p)).collect();
+            state.active_terminal_tab = state.active_terminal_tab.as_deref().map(rebase);
+            states.insert(new_key, state);
+        }
+    }
+}
+
 // ==================== DevTools ====================
 
 #[tauri::command]
@@ -2,11 +2,17 @@ use std::fs;
 use std::path::PathBuf;
 
 use crate::config::{
-    get_window_workspace_config, get_window_workspace_path, get_workspace_config_path,
-    load_global_config, save_global_config_internal, save_workspace_config_internal,
+    get_global_config_path, get_occupation_state_path, get_window_workspace_config,
+    get_window_workspace_path, get_workspace_config_path,
+    load_global_config, load_workspace_config_versioned, save_global_config_internal,
+    save_workspace_config_internal, save_workspace_config_versioned,
 };
 use crate::state::{WINDOW_WORKSPACES, WORKSPACE_CONFIG_CACHE};
-use crate::types::{default_linked_workspace_items, WorkspaceConfig, WorkspaceRef};
+use crate::types::{
+    default_archive_ignore_patterns, default_linked_workspace_items, AppPaths, AutoLockConfig,
+    BaseUpdateConfig, PortAllocationConfig, WorkspacePaths, ConfigWarning, ExistingDirPolicy, ShellConfig, VersionedWorkspaceConfig, WorkspaceConfig,
+    WorkspaceRef,
+};
 use crate::utils::normalize_path;
 
 // ==================== Tauri 命令：Workspace 管理 ====================
@@ -56,10 +62,10 @@ pub fn switch_workspace_impl(window_label: &str, path: String) -> Result<(), Str
         map.insert(window_label.to_string(), path.clone());
     }
 
-    // 清除 workspace 配置缓存
+    // 清除该 workspace 的配置缓存，切换后从磁盘重新读取
     {
         let mut cache = WORKSPACE_CONFIG_CACHE.lock().unwrap();
-        *cache = None;
+        cache.remove(&path);
     }
 
     log::info!("[workspace] Successfully switched to workspace '{}'", path);
@@ -165,6 +171,18 @@ pub(crate) fn create_workspace(name: String, path: String) -> Result<(), String>
         worktrees_dir: "worktrees".to_string(),
         projects: vec![],
         linked_workspace_items: default_linked_workspace_items(),
+        archive_ignore_patterns: default_archive_ignore_patterns(),
+        existing_dir_policy: ExistingDirPolicy::default(),
+        auto_lock: AutoLockConfig::default(),
+        disk_quota_mb: None,
+        base_update: BaseUpdateConfig::default(),
+        quick_commands: vec![],
+        hooks: vec![],
+        port_allocation: PortAllocationConfig::default(),
+        kill_processes_on_archive: false,
+        shell: None,
+        issue_trackers: vec![],
+        webhooks: vec![],
     };
     save_workspace_config_internal(&path, &ws_config)?;
 
@@ -187,20 +205,99 @@ pub(crate) fn get_workspace_config(window: tauri::Window) -> Result<WorkspaceCon
     get_workspace_config_impl(window.label())
 }
 
+pub fn get_workspace_config_versioned_impl(
+    window_label: &str,
+) -> Result<VersionedWorkspaceConfig, String> {
+    let workspace_path = get_window_workspace_path(window_label).ok_or("No workspace selected")?;
+    Ok(load_workspace_config_versioned(&workspace_path))
+}
+
+#[tauri::command]
+pub(crate) fn get_workspace_config_versioned(
+    window: tauri::Window,
+) -> Result<VersionedWorkspaceConfig, String> {
+    get_workspace_config_versioned_impl(window.label())
+}
+
+/// 保存配置。`base_etag` 为读取配置时拿到的版本，其他窗口在此期间保存过时按部分合并，
+/// 同一部分双方都改过则返回冲突；不传则直接覆盖
 pub fn save_workspace_config_impl(
     window_label: &str,
     config: WorkspaceConfig,
-) -> Result<(), String> {
+    base_etag: Option<String>,
+) -> Result<VersionedWorkspaceConfig, String> {
     let workspace_path = get_window_workspace_path(window_label).ok_or("No workspace selected")?;
-    save_workspace_config_internal(&workspace_path, &config)
+    save_workspace_config_versioned(&workspace_path, config, base_etag.as_deref())
 }
 
 #[tauri::command]
 pub(crate) fn save_workspace_config(
     window: tauri::Window,
     config: WorkspaceConfig,
-) -> Result<(), String> {
-    save_workspace_config_impl(window.label(), config)
+    base_etag: Option<String>,
+) -> Result<VersionedWorkspaceConfig, String> {
+    save_workspace_config_impl(window.label(), config, base_etag)
+}
+
+/// 校验当前 workspace 配置：主项目目录是否存在、base_branch 是否存在于远程。
+/// 仅基于本地已有的远程引用判断（不执行 fetch）；base_branch 缺失时提示远程默认分支。
+pub fn validate_workspace_config_impl(window_label: &str) -> Result<Vec<ConfigWarning>, String> {
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let projects_root = PathBuf::from(&workspace_path).join("projects");
+
+    let mut warnings = vec![];
+    let missing_shell = |shell: &Option<ShellConfig>| {
+        shell
+            .as_ref()
+            .map(|s| s.program.trim())
+            .filter(|p| !p.is_empty() && crate::pty_manager::find_program(p).is_none())
+            .map(|p| format!("Shell '{}' not found; terminals fall back to the default shell", p))
+    };
+    if let Some(message) = missing_shell(&config.shell) {
+        warnings.push(ConfigWarning {
+            project_name: None,
+            message,
+        });
+    }
+    for project in &config.projects {
+        if let Some(message) = missing_shell(&project.shell) {
+            warnings.push(ConfigWarning {
+                project_name: Some(project.name.clone()),
+                message,
+            });
+        }
+        let proj_path = projects_root.join(&project.name);
+        if !proj_path.exists() {
+            warnings.push(ConfigWarning {
+                project_name: Some(project.name.clone()),
+                message: format!("Project directory not found: {}", normalize_path(&proj_path.to_string_lossy())),
+            });
+            continue;
+        }
+        if !crate::git_ops::remote_branch_known(&proj_path, &project.remote, &project.base_branch) {
+            let message = match crate::git_ops::detect_default_branch(&proj_path, &project.remote) {
+                Some(default_branch) => format!(
+                    "Base branch '{}' not found on {}; remote default is '{}', which is used for ahead/behind until this is fixed",
+                    project.base_branch, project.remote, default_branch
+                ),
+                None => format!("Base branch '{}' not found on {}", project.base_branch, project.remote),
+            };
+            log::warn!("[workspace] Config warning for '{}': {}", project.name, message);
+            warnings.push(ConfigWarning {
+                project_name: Some(project.name.clone()),
+                message,
+            });
+        }
+    }
+    Ok(warnings)
+}
+
+#[tauri::command]
+pub(crate) fn validate_workspace_config(
+    window: tauri::Window,
+) -> Result<Vec<ConfigWarning>, String> {
+    validate_workspace_config_impl(window.label())
 }
 
 #[tauri::command]
@@ -226,6 +323,33 @@ pub(crate) fn get_config_path_info(window: tauri::Window) -> String {
     get_config_path_info_impl(window.label())
 }
 
+/// 应用和当前 workspace 使用的全部文件位置（均为绝对路径，文件不一定已存在）
+pub fn get_paths_impl(window_label: &str) -> AppPaths {
+    let path_str = |p: &std::path::Path| normalize_path(&p.to_string_lossy());
+    let global_config = get_global_config_path();
+    let workspace = get_window_workspace_config(window_label).map(|(workspace_path, config)| {
+        WorkspacePaths {
+            root: normalize_path(&workspace_path),
+            config: path_str(&get_workspace_config_path(&workspace_path)),
+            worktrees_dir: path_str(&PathBuf::from(&workspace_path).join(&config.worktrees_dir)),
+            occupation_state: path_str(&get_occupation_state_path(&workspace_path)),
+        }
+    });
+    AppPaths {
+        global_config_dir: global_config.parent().map(path_str).unwrap_or_default(),
+        global_config: path_str(&global_config),
+        log_dir: crate::commands::system::get_platform_log_dir()
+            .ok()
+            .map(|p| path_str(&p)),
+        workspace,
+    }
+}
+
+#[tauri::command]
+pub(crate) fn get_paths(window: tauri::Window) -> AppPaths {
+    get_paths_impl(window.label())
+}
+
 // ==================== HTTP Server 共享接口 ====================
 
 pub fn add_workspace_internal(name: &str, path: &str) -> Result<(), String> {
@@ -275,6 +399,18 @@ pub fn create_workspace_internal(name: &str, path: &str) -> Result<(), String> {
         worktrees_dir: "worktrees".to_string(),
         projects: vec![],
         linked_workspace_items: default_linked_workspace_items(),
+        archive_ignore_patterns: default_archive_ignore_patterns(),
+        existing_dir_policy: ExistingDirPolicy::default(),
+        auto_lock: AutoLockConfig::default(),
+        disk_quota_mb: None,
+        base_update: BaseUpdateConfig::default(),
+        quick_commands: vec![],
+        hooks: vec![],
+        port_allocation: PortAllocationConfig::default(),
+        kill_processes_on_archive: false,
+        shell: None,
+        issue_trackers: vec![],
+        webhooks: vec![],
     };
     save_workspace_config_internal(path, &ws_config)?;
     add_workspace_internal(name, path)?;
@@ -1,21 +1,42 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 use std::process::Command;
+use tauri::Emitter;
 
-use crate::commands::window::broadcast_lock_state;
+use crate::commands::window::{broadcast_lock_state, release_git_worktree_lock};
 use crate::config::{
-    clear_occupation_state, get_window_workspace_config, load_occupation_state,
-    save_occupation_state,
+    clear_occupation_state, get_window_workspace_config, get_window_workspace_path,
+    load_occupation_state, resolve_workspace_path, save_occupation_state, to_workspace_relative,
+};
+use crate::git_ops::{self, get_branch_status, get_worktree_info, MergeStrategy, SyncAdvice, WorktreeInfo};
+use crate::hooks::{ensure_hooks_succeeded, run_hooks, send_webhooks, HookContext};
+use crate::notifier;
+use crate::oplog::{LoggedCommand, OperationRecorder};
+use crate::state::{
+    APP_HANDLE, BASE_UPDATES, CLIENT_NOTIFICATION_BROADCAST, DISK_QUOTA_WARNED, PTY_MANAGER,
+    REFRESHING_WORKSPACES, WINDOW_WORKSPACES, WORKSPACE_CONFIG_CACHE,
 };
-use crate::git_ops::{get_branch_status, get_worktree_info};
-use crate::state::PTY_MANAGER;
 use crate::types::{
-    AddProjectToWorktreeRequest, CreateWorktreeRequest, DeployProjectError, DeployToMainResult,
-    MainProjectStatus, MainWorkspaceOccupation, MainWorkspaceStatus, ProjectConfig, ProjectStatus,
-    ScannedFolder, WorktreeArchiveStatus, WorktreeListItem,
+    default_remote, AddProjectToWorktreeRequest, BaseUpdate, BranchDeleteResult, BranchRenameProjectResult,
+    BulkArchiveOutcome, BulkArchiveResult, BundleExportResult, BundleImportProject,
+    BundleImportResult, BundleProject, ComposeStatus, CopyOverwritePolicy, CopyRule,
+    CreateProjectRequest, CreateWorktreeRequest, CreateWorktreeResult, DeleteArchivedResult,
+    DeployProjectError, DeployToMainResult, DirtyProject, DirtyReport, DiskUsageReport,
+    ExistingDirPolicy, HookEvent, HookRun, KilledProcess, LinkResult, LinkStatus, MainProjectStatus,
+    MainWorkspaceOccupation,
+    MainWorkspaceStatus, ManifestImportResult, ManifestProject, ManifestWorktree, NotifyEvent, ProjectConfig,
+    ProjectStatus, ProjectSyncAdvice, ProjectSyncResult,
+    RenameWorktreeResult, RepairWorktreesResult, RestoreWorktreeResult, ScannedFolder, SyncLinksResult,
+    UnregisteredWorktree, WorkspaceManifest, WorkspaceRefreshResult,
+    WorktreeArchiveStatus, WorktreeBundleManifest, WorktreeDiskUsage, WorktreeListItem,
+    WorktreeIssue, WorktreeListQuery, WorktreeMeta, WorktreePage, WorktreePorts, WorktreeSortBy,
+    WorktreeSyncAdvice,
+};
+use crate::utils::{
+    begin_git_operation, calculate_dir_size, enter_repo_queue, format_size, normalize_path,
+    run_git_command_with_timeout, scan_dir_for_linkable_folders,
 };
-use crate::utils::{normalize_path, run_git_command_with_timeout, scan_dir_for_linkable_folders};
 
 /// Cross-platform symlink creation.
 /// On Unix: uses std::os::unix::fs::symlink.
@@ -58,11 +79,387 @@ fn create_symlink(src: &std::path::Path, dst: &std::path::Path) -> std::io::Resu
     }
 }
 
+/// 创建单个软链接并记录结果，不再静默吞掉失败。
+/// 源不存在或目标已是软链接时记为 skipped；目标是真实目录时按 `policy` 处理；
+/// 创建失败时附带原因（Windows 上多为未开启开发者模式）。
+fn link_item(
+    src: &std::path::Path,
+    dst: &std::path::Path,
+    project: Option<&str>,
+    item: &str,
+    policy: ExistingDirPolicy,
+) -> LinkResult {
+    let result = |status: LinkStatus, reason: Option<String>| LinkResult {
+        project: project.map(|p| p.to_string()),
+        item: item.to_string(),
+        status,
+        reason,
+    };
+
+    if !src.exists() {
+        return result(LinkStatus::Skipped, Some("源路径不存在".to_string()));
+    }
+    if dst.is_symlink() {
+        return result(LinkStatus::Skipped, Some("已存在软链接".to_string()));
+    }
+    if dst.is_dir() && src.is_dir() && policy != ExistingDirPolicy::Skip {
+        return match swap_dir_for_link(src, dst, policy == ExistingDirPolicy::Merge) {
+            Ok(()) => result(LinkStatus::Created, None),
+            Err(e) => result(LinkStatus::Failed, Some(e)),
+        };
+    }
+    if dst.is_dir() {
+        return result(
+            LinkStatus::Skipped,
+            Some("目标位置已存在真实目录（可使用“转换为链接”替换）".to_string()),
+        );
+    }
+    if dst.exists() {
+        return result(LinkStatus::Skipped, Some("目标位置已存在同名文件".to_string()));
+    }
+
+    match create_symlink(src, dst) {
+        Ok(()) => {
+            log::debug!("[worktree] Linked {:?} -> {:?}", dst, src);
+            result(LinkStatus::Created, None)
+        }
+        Err(e) => {
+            log::warn!("[worktree] Failed to link {:?} -> {:?}: {}", dst, src, e);
+            result(LinkStatus::Failed, Some(symlink_error_reason(&e)))
+        }
+    }
+}
+
+/// 按项目的 copy_rules 从主项目复制文件，结果与软链接结果一起返回给前端展示
+fn apply_copy_rules(
+    main_proj_path: &Path,
+    wt_proj_path: &Path,
+    project: &str,
+    rules: &[CopyRule],
+) -> Vec<LinkResult> {
+    rules
+        .iter()
+        .map(|rule| copy_item(main_proj_path, wt_proj_path, project, rule))
+        .collect()
+}
+
+fn copy_item(
+    main_proj_path: &Path,
+    wt_proj_path: &Path,
+    project: &str,
+    rule: &CopyRule,
+) -> LinkResult {
+    let item = rule.path.trim();
+    let result = |status: LinkStatus, reason: Option<String>| LinkResult {
+        project: Some(project.to_string()),
+        item: item.to_string(),
+        status,
+        reason,
+    };
+
+    let relative = Path::new(item);
+    let inside_project = relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if item.is_empty() || !inside_project {
+        return result(
+            LinkStatus::Failed,
+            Some("复制路径必须是项目内的相对路径".to_string()),
+        );
+    }
+    let src = main_proj_path.join(relative);
+    let dst = wt_proj_path.join(relative);
+    if !src.exists() {
+        return result(LinkStatus::Skipped, Some("源路径不存在".to_string()));
+    }
+    if dst.is_symlink() {
+        return result(LinkStatus::Skipped, Some("目标位置已是软链接".to_string()));
+    }
+    match copy_path(&src, &dst, rule.overwrite) {
+        Ok(true) => {
+            log::debug!("[worktree] Copied {:?} -> {:?}", src, dst);
+            result(LinkStatus::Created, None)
+        }
+        Ok(false) => result(
+            LinkStatus::Skipped,
+            Some("目标位置已存在，按覆盖策略保留".to_string()),
+        ),
+        Err(e) => {
+            log::warn!("[worktree] Failed to copy {:?} -> {:?}: {}", src, dst, e);
+            result(LinkStatus::Failed, Some(e.to_string()))
+        }
+    }
+}
+
+/// 递归复制，覆盖策略按单个文件判断，跳过软链接；返回是否复制了任何文件
+fn copy_path(src: &Path, dst: &Path, policy: CopyOverwritePolicy) -> std::io::Result<bool> {
+    if src.is_dir() {
+        fs::create_dir_all(dst)?;
+        let mut copied = false;
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            if entry.file_type()?.is_symlink() {
+                continue;
+            }
+            copied |= copy_path(&entry.path(), &dst.join(entry.file_name()), policy)?;
+        }
+        return Ok(copied);
+    }
+    if dst.is_symlink() || dst.is_dir() {
+        return Ok(false);
+    }
+    if dst.exists() {
+        let overwrite = match policy {
+            CopyOverwritePolicy::Skip => false,
+            CopyOverwritePolicy::Always => true,
+            CopyOverwritePolicy::IfNewer => {
+                let modified = |p: &Path| fs::metadata(p).and_then(|m| m.modified()).ok();
+                matches!((modified(src), modified(dst)), (Some(s), Some(d)) if s > d)
+            }
+        };
+        if !overwrite {
+            return Ok(false);
+        }
+    }
+    if let Some(parent) = dst.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(src, dst)?;
+    Ok(true)
+}
+
+fn symlink_error_reason(e: &std::io::Error) -> String {
+    #[cfg(windows)]
+    return format!("{}（请开启 Windows 开发者模式或以管理员身份运行）", e);
+    #[cfg(not(windows))]
+    return e.to_string();
+}
+
+/// 将真实目录替换为指向 `src` 的软链接。
+/// 先把 `dst` 重命名为备份目录，链接创建成功后再删除备份；任一步失败都会把备份移回原处。
+/// `merge` 为 true 时，备份中主项目没有的文件会先复制到 `src`（已存在的以主项目为准）；
+/// 之后任一步失败都会删除已复制到主项目的条目。
+fn swap_dir_for_link(
+    src: &std::path::Path,
+    dst: &std::path::Path,
+    merge: bool,
+) -> Result<(), String> {
+    let file_name = dst
+        .file_name()
+        .ok_or_else(|| format!("无效的目标路径: {}", dst.display()))?
+        .to_string_lossy()
+        .to_string();
+    let backup = dst.with_file_name(format!(
+        ".{}.wm-backup-{}",
+        file_name,
+        chrono::Utc::now().timestamp_millis()
+    ));
+
+    log::info!(
+        "[worktree] Replacing real directory {:?} with link to {:?} (merge={})",
+        dst, src, merge
+    );
+    fs::rename(dst, &backup).map_err(|e| format!("无法移动原目录 {}: {}", dst.display(), e))?;
+
+    let mut merged: Vec<PathBuf> = vec![];
+    let restore_backup = |err: String, merged: &[PathBuf]| -> String {
+        remove_merged_entries(merged);
+        if let Err(e) = fs::rename(&backup, dst) {
+            log::error!(
+                "[worktree] Failed to restore {:?} from backup {:?}: {}",
+                dst, backup, e
+            );
+            return format!("{}；且恢复原目录失败，备份保留在 {}", err, backup.display());
+        }
+        err
+    };
+
+    if merge {
+        if let Err(e) = merge_dir_into(&backup, src, &mut merged) {
+            return Err(restore_backup(format!("合并到主项目失败: {}", e), &merged));
+        }
+    }
+
+    if let Err(e) = create_symlink(src, dst) {
+        log::warn!("[worktree] Failed to link {:?} -> {:?}: {}", dst, src, e);
+        return Err(restore_backup(symlink_error_reason(&e), &merged));
+    }
+
+    if let Err(e) = fs::remove_dir_all(&backup) {
+        log::warn!("[worktree] Failed to remove backup {:?}: {}", backup, e);
+    }
+    Ok(())
+}
+
+/// 将 `from` 中 `to` 不存在的条目复制到 `to`，同名子目录递归合并，同名文件保留 `to` 的版本。
+/// `from` 不会被修改；新建的条目（含复制到一半的）记入 `created`，供失败时回滚。
+fn merge_dir_into(
+    from: &std::path::Path,
+    to: &std::path::Path,
+    created: &mut Vec<PathBuf>,
+) -> std::io::Result<()> {
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+        if target.symlink_metadata().is_err() {
+            created.push(target.clone());
+            copy_entry(&source, &target)?;
+        } else if entry.file_type()?.is_dir() && target.is_dir() && !target.is_symlink() {
+            merge_dir_into(&source, &target, created)?;
+        }
+    }
+    Ok(())
+}
+
+/// 复制文件、软链接（复制链接本身）或整个目录
+fn copy_entry(source: &std::path::Path, target: &std::path::Path) -> std::io::Result<()> {
+    let file_type = source.symlink_metadata()?.file_type();
+    if file_type.is_symlink() {
+        create_symlink(&fs::read_link(source)?, target)
+    } else if file_type.is_dir() {
+        fs::create_dir(target)?;
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            copy_entry(&entry.path(), &target.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(source, target).map(|_| ())
+    }
+}
+
+/// 回滚 merge_dir_into：删除已复制到主项目的条目
+fn remove_merged_entries(created: &[PathBuf]) {
+    for path in created.iter().rev() {
+        let result = match path.symlink_metadata() {
+            Ok(m) if m.is_dir() => fs::remove_dir_all(path),
+            Ok(_) => fs::remove_file(path),
+            Err(_) => continue,
+        };
+        if let Err(e) = result {
+            log::warn!("[worktree] Failed to roll back merged {:?}: {}", path, e);
+        }
+    }
+}
+
+/// 软链接创建后将其从 git 索引中移除（若该目录曾被跟踪）
+/// 项目配置为 rebase 时，让该分支上的 `git pull` 也走 rebase（branch.<name>.rebase）
+fn apply_branch_strategy(wt_proj_path: &std::path::Path, branch: &str, proj_config: &ProjectConfig) {
+    if MergeStrategy::from_config(&proj_config.merge_strategy) != MergeStrategy::Rebase {
+        return;
+    }
+    let result = Command::new("git")
+        .arg("-C")
+        .arg(wt_proj_path)
+        .args(["config", &format!("branch.{}.rebase", branch), "true"])
+        .logged_output();
+    match result {
+        Ok(o) if o.status.success() => log::info!(
+            "[worktree] Project '{}': branch '{}' set to rebase on pull",
+            proj_config.name, branch
+        ),
+        Ok(o) => log::warn!(
+            "[worktree] Failed to set rebase for branch '{}': {}",
+            branch,
+            String::from_utf8_lossy(&o.stderr)
+        ),
+        Err(e) => log::warn!("[worktree] Failed to run git config: {}", e),
+    }
+}
+
+/// checkout 时跳过了 LFS smudge（GIT_LFS_SKIP_SMUDGE=1），这里按项目配置补拉 LFS 对象。
+/// 失败只记录警告：指针文件不影响 worktree 的其他操作。
+pub(crate) fn sync_lfs_objects(checkout_path: &std::path::Path, proj_config: &ProjectConfig) {
+    if !git_ops::uses_lfs(checkout_path) {
+        return;
+    }
+    if proj_config.skip_lfs {
+        log::info!(
+            "[worktree] Project '{}': skip_lfs set, leaving LFS pointer files",
+            proj_config.name
+        );
+        return;
+    }
+    log::info!("[worktree] Project '{}': pulling Git LFS objects", proj_config.name);
+    if let Err(e) = git_ops::pull_lfs_objects(checkout_path) {
+        log::warn!("[worktree] Project '{}': {}", proj_config.name, e);
+    }
+}
+
+/// 按请求（优先）或项目配置中的 sparse_paths 收窄 worktree 的检出范围。
+/// 失败只记录警告：完整检出依然可用。
+fn apply_sparse_checkout(
+    checkout_path: &std::path::Path,
+    proj_config: &ProjectConfig,
+    requested: &[String],
+) {
+    let sparse_paths: Vec<String> = if requested.is_empty() {
+        proj_config.sparse_paths.as_slice()
+    } else {
+        requested
+    }
+    .iter()
+    .map(|p| p.trim().to_string())
+    .filter(|p| !p.is_empty())
+    .collect();
+    if sparse_paths.is_empty() {
+        return;
+    }
+    log::info!(
+        "[worktree] Project '{}': sparse checkout {:?}",
+        proj_config.name,
+        sparse_paths
+    );
+    if let Err(e) = git_ops::set_sparse_checkout(checkout_path, &sparse_paths) {
+        log::warn!("[worktree] Project '{}': {}", proj_config.name, e);
+    }
+}
+
+fn untrack_linked_folder(wt_proj_path: &std::path::Path, folder_name: &str) {
+    Command::new("git")
+        .args([
+            "-C",
+            wt_proj_path.to_str().unwrap(),
+            "rm",
+            "--cached",
+            "-r",
+            folder_name,
+        ])
+        .logged_output()
+        .ok();
+}
+
 // ==================== Tauri 命令：Worktree 操作 ====================
 
+/// 项目配置中的状态扫描排除规则（未配置的项目返回空）
+fn project_status_excludes(
+    config: &crate::types::WorkspaceConfig,
+    project_name: &str,
+) -> Vec<String> {
+    config
+        .projects
+        .iter()
+        .find(|p| p.name == project_name)
+        .map(|p| p.status_excludes.clone())
+        .unwrap_or_default()
+}
+
+/// 项目配置中的远程名（未配置的项目使用 origin）
+fn project_remote(config: &crate::types::WorkspaceConfig, project_name: &str) -> String {
+    config
+        .projects
+        .iter()
+        .find(|p| p.name == project_name)
+        .map(|p| p.remote.clone())
+        .unwrap_or_else(default_remote)
+}
+
+/// `labels` 非空时只返回同时带有全部这些标签的 worktree
 pub fn list_worktrees_impl(
     window_label: &str,
     include_archived: bool,
+    labels: &[String],
 ) -> Result<Vec<WorktreeListItem>, String> {
     let start = std::time::Instant::now();
     let (workspace_path, config) =
@@ -74,7 +471,15 @@ pub fn list_worktrees_impl(
         return Ok(vec![]);
     }
 
-    let result = scan_worktrees_dir(&worktrees_path, &config, include_archived);
+    let labels = normalize_labels(labels);
+    let result = scan_worktrees_dir_with_progress(
+        &worktrees_path,
+        &workspace_path,
+        &config,
+        include_archived,
+        &labels,
+        |_, _| {},
+    );
     log::info!("list_worktrees took {:?}", start.elapsed());
     result
 }
@@ -83,23 +488,254 @@ pub fn list_worktrees_impl(
 pub(crate) fn list_worktrees(
     window: tauri::Window,
     include_archived: bool,
+    labels: Option<Vec<String>>,
 ) -> Result<Vec<WorktreeListItem>, String> {
-    list_worktrees_impl(window.label(), include_archived)
+    list_worktrees_impl(
+        window.label(),
+        include_archived,
+        &labels.unwrap_or_default(),
+    )
+}
+
+/// 排序和分页在 git status 之前完成，只有当前页的 worktree 会被扫描；
+/// 按 status 排序时必须先扫描全部。`visible` 供 HTTP 端按分享范围过滤，保证 total 准确。
+pub fn list_worktrees_page_impl(
+    window_label: &str,
+    query: WorktreeListQuery,
+    visible: impl Fn(&str) -> bool,
+) -> Result<WorktreePage, String> {
+    let start = std::time::Instant::now();
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let worktrees_path = PathBuf::from(&workspace_path).join(&config.worktrees_dir);
+    if !worktrees_path.exists() {
+        return Ok(WorktreePage {
+            items: vec![],
+            total: 0,
+            offset: query.offset,
+        });
+    }
+
+    let labels = normalize_labels(&query.labels);
+    let mut entries = collect_worktree_entries(&worktrees_path, query.include_archived, &labels)?;
+    entries.retain(|e| visible(&e.name));
+    let total = entries.len();
+    let limit = query.limit.unwrap_or(usize::MAX);
+
+    // 活动 worktree 始终排在归档之前
+    let items: Vec<WorktreeListItem> = match query.sort_by {
+        WorktreeSortBy::Status => {
+            let mut items: Vec<WorktreeListItem> = entries
+                .into_iter()
+                .map(|e| build_worktree_item(e, &workspace_path, &config, true))
+                .collect();
+            items.sort_by_key(|w| (w.is_archived, status_rank(w), w.name.clone()));
+            items.into_iter().skip(query.offset).take(limit).collect()
+        }
+        sort_by => {
+            if sort_by == WorktreeSortBy::LastCommit {
+                let mut keyed: Vec<(i64, WorktreeEntry)> = entries
+                    .into_iter()
+                    .map(|e| (last_commit_time(&e.path), e))
+                    .collect();
+                keyed.sort_by(|(ta, a), (tb, b)| {
+                    (a.is_archived, std::cmp::Reverse(*ta), &a.name).cmp(&(
+                        b.is_archived,
+                        std::cmp::Reverse(*tb),
+                        &b.name,
+                    ))
+                });
+                entries = keyed.into_iter().map(|(_, e)| e).collect();
+            } else {
+                entries.sort_by(|a, b| (a.is_archived, &a.name).cmp(&(b.is_archived, &b.name)));
+            }
+            entries
+                .into_iter()
+                .skip(query.offset)
+                .take(limit)
+                .map(|e| build_worktree_item(e, &workspace_path, &config, !query.lightweight))
+                .collect()
+        }
+    };
+
+    log::info!(
+        "list_worktrees_page ({:?}, offset {}, {} of {}) took {:?}",
+        query.sort_by,
+        query.offset,
+        items.len(),
+        total,
+        start.elapsed()
+    );
+    Ok(WorktreePage {
+        items,
+        total,
+        offset: query.offset,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn list_worktrees_page(
+    window: tauri::Window,
+    query: WorktreeListQuery,
+) -> Result<WorktreePage, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || list_worktrees_page_impl(&label, query, |_| true))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// 单个 worktree 的完整状态，配合 lightweight 列表在展开时调用
+pub fn get_worktree_impl(window_label: &str, name: String) -> Result<WorktreeListItem, String> {
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let path = worktree_dir(window_label, &name)?;
+    let entry = WorktreeEntry {
+        is_archived: name.ends_with(".archive"),
+        meta: read_worktree_meta(&path),
+        name,
+        path,
+    };
+    Ok(build_worktree_item(entry, &workspace_path, &config, true))
+}
+
+#[tauri::command]
+pub(crate) fn get_worktree(
+    window: tauri::Window,
+    name: String,
+) -> Result<WorktreeListItem, String> {
+    get_worktree_impl(window.label(), name)
+}
+
+/// 只扫描一个 worktree（可指定单个项目）的 git 状态，收到 worktree-status-changed 后
+/// 用于局部刷新，避免重新扫描整个列表
+pub fn get_worktree_status_impl(
+    window_label: &str,
+    name: String,
+    project: Option<String>,
+) -> Result<Vec<ProjectStatus>, String> {
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let path = worktree_dir(window_label, &name)?;
+    let with_status = !name.ends_with(".archive");
+    let projects_path = path.join("projects");
+    let proj_paths: Vec<PathBuf> = match project {
+        Some(project) => {
+            if project.is_empty() || project.starts_with('.') || project.contains(['/', '\\']) {
+                return Err(format!("无效的项目名称: {}", project));
+            }
+            let proj_path = projects_path.join(&project);
+            if !proj_path.is_dir() {
+                return Err(format!("项目不存在: {}", project));
+            }
+            vec![proj_path]
+        }
+        None => fs::read_dir(&projects_path)
+            .map_err(|e| format!("Failed to read {}: {}", projects_path.display(), e))?
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_dir())
+            .collect(),
+    };
+    Ok(proj_paths
+        .iter()
+        .map(|p| build_project_status(p, &workspace_path, &config, with_status))
+        .collect())
+}
+
+#[tauri::command]
+pub(crate) async fn get_worktree_status(
+    window: tauri::Window,
+    name: String,
+    project: Option<String>,
+) -> Result<Vec<ProjectStatus>, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || get_worktree_status_impl(&label, name, project))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// 各项目 HEAD 中最新的提交时间，读不到时为 0（排在最后）
+fn last_commit_time(worktree_path: &Path) -> i64 {
+    fs::read_dir(worktree_path.join("projects"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| git_ops::head_commit_time(&e.path()))
+                .max()
+                .unwrap_or(0)
+        })
+        .unwrap_or(0)
+}
+
+fn status_rank(item: &WorktreeListItem) -> u8 {
+    if item.projects.iter().any(|p| p.has_uncommitted) {
+        0
+    } else if item.projects.iter().any(|p| p.ahead_of_base > 0) {
+        1
+    } else if item.projects.iter().any(|p| p.behind_base > 0) {
+        2
+    } else {
+        3
+    }
 }
 
 fn scan_worktrees_dir(
     dir: &PathBuf,
+    workspace_path: &str,
+    config: &crate::types::WorkspaceConfig,
+    include_archived: bool,
+) -> Result<Vec<WorktreeListItem>, String> {
+    scan_worktrees_dir_with_progress(
+        dir,
+        workspace_path,
+        config,
+        include_archived,
+        &[],
+        |_, _| {},
+    )
+}
+
+/// on_entry 在处理每个目录项前收到 (序号, 总数)
+fn scan_worktrees_dir_with_progress(
+    dir: &PathBuf,
+    workspace_path: &str,
     config: &crate::types::WorkspaceConfig,
     include_archived: bool,
+    labels: &[String],
+    mut on_entry: impl FnMut(usize, usize),
 ) -> Result<Vec<WorktreeListItem>, String> {
+    let entries = collect_worktree_entries(dir, include_archived, labels)?;
+    let total = entries.len();
     let mut result = vec![];
+    for (index, entry) in entries.into_iter().enumerate() {
+        on_entry(index + 1, total);
+        result.push(build_worktree_item(entry, workspace_path, config, true));
+    }
+    Ok(result)
+}
 
-    let entries = std::fs::read_dir(dir).map_err(|e| format!("Failed to read directory: {}", e))?;
+/// worktrees 目录下的一个条目，尚未读取 git 状态
+struct WorktreeEntry {
+    name: String,
+    path: PathBuf,
+    is_archived: bool,
+    meta: Option<WorktreeMeta>,
+}
 
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-        let path = entry.path();
+/// 列出 worktree 目录（不跑 git），按归档和标签过滤
+fn collect_worktree_entries(
+    dir: &PathBuf,
+    include_archived: bool,
+    labels: &[String],
+) -> Result<Vec<WorktreeEntry>, String> {
+    let paths = std::fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read directory: {}", e))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read entry: {}", e))?;
 
+    let mut result = vec![];
+    for path in paths {
         if !path.is_dir() {
             continue;
         }
@@ -120,120 +756,370 @@ fn scan_worktrees_dir(
             continue;
         }
 
-        let projects_path = path.join("projects");
-        let mut projects = vec![];
-
-        if !projects_path.exists() || !projects_path.is_dir() {
+        if !path.join("projects").is_dir() {
             continue;
         }
 
-        if let Ok(proj_entries) = std::fs::read_dir(&projects_path) {
-            for proj_entry in proj_entries.flatten() {
-                let proj_path = proj_entry.path();
-                if !proj_path.is_dir() {
-                    continue;
-                }
-
-                let proj_name = proj_path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("")
-                    .to_string();
-
-                let proj_config = config
-                    .projects
-                    .iter()
-                    .find(|p| p.name == proj_name)
-                    .cloned()
-                    .unwrap_or(ProjectConfig {
-                        name: proj_name.clone(),
-                        base_branch: "uat".to_string(),
-                        test_branch: "test".to_string(),
-                        merge_strategy: "merge".to_string(),
-                        linked_folders: vec![],
-                    });
-
-                let info = get_worktree_info(&proj_path);
-
-                projects.push(ProjectStatus {
-                    name: proj_name,
-                    path: normalize_path(&proj_path.to_string_lossy()),
-                    current_branch: info.current_branch,
-                    base_branch: proj_config.base_branch,
-                    test_branch: proj_config.test_branch,
-                    has_uncommitted: info.uncommitted_count > 0,
-                    uncommitted_count: info.uncommitted_count,
-                    is_merged_to_test: info.is_merged_to_test,
-                    ahead_of_base: info.ahead_of_base,
-                    behind_base: info.behind_base,
-                });
+        // 按标签筛选放在 git status 之前，避免为被过滤掉的 worktree 扫描项目
+        let meta = read_worktree_meta(&path);
+        if !labels.is_empty() {
+            let own = meta.as_ref().map(|m| m.labels.as_slice()).unwrap_or(&[]);
+            if !labels.iter().all(|l| own.contains(l)) {
+                continue;
             }
         }
 
-        result.push(WorktreeListItem {
+        result.push(WorktreeEntry {
             name,
-            path: normalize_path(&path.to_string_lossy()),
+            path,
             is_archived,
-            projects,
+            meta,
         });
     }
-
     Ok(result)
 }
 
-pub fn get_main_workspace_status_impl(window_label: &str) -> Result<MainWorkspaceStatus, String> {
-    let start = std::time::Instant::now();
-    let (workspace_path, config) =
-        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
-
-    let root_path = PathBuf::from(&workspace_path);
-    let projects_path = root_path.join("projects");
-
+/// with_status 为 false 时跳过 git status，项目只带名称、路径和配置中的分支
+fn build_worktree_item(
+    entry: WorktreeEntry,
+    workspace_path: &str,
+    config: &crate::types::WorkspaceConfig,
+    with_status: bool,
+) -> WorktreeListItem {
+    let WorktreeEntry {
+        name,
+        path,
+        is_archived,
+        meta,
+    } = entry;
     let mut projects = vec![];
 
-    for proj_config in &config.projects {
-        let proj_path = projects_path.join(&proj_config.name);
-        if !proj_path.exists() {
-            continue;
+    if let Ok(proj_entries) = std::fs::read_dir(path.join("projects")) {
+        for proj_entry in proj_entries.flatten() {
+            let proj_path = proj_entry.path();
+            if !proj_path.is_dir() {
+                continue;
+            }
+            // 归档目录已没有 git worktree 注册，跑 git status 只会报错，这里只返回目录信息
+            projects.push(build_project_status(
+                &proj_path,
+                workspace_path,
+                config,
+                with_status && !is_archived,
+            ));
         }
-
-        let info = get_worktree_info(&proj_path);
-
-        projects.push(MainProjectStatus {
-            name: proj_config.name.clone(),
-            path: normalize_path(&proj_path.to_string_lossy()),
-            current_branch: info.current_branch,
-            has_uncommitted: info.uncommitted_count > 0,
-            uncommitted_count: info.uncommitted_count,
-            is_merged_to_test: info.is_merged_to_test,
-            ahead_of_base: info.ahead_of_base,
-            behind_base: info.behind_base,
-            base_branch: proj_config.base_branch.clone(),
-            test_branch: proj_config.test_branch.clone(),
-            linked_folders: proj_config.linked_folders.clone(),
-        });
     }
 
-    let result = MainWorkspaceStatus {
-        path: normalize_path(&root_path.to_string_lossy()),
-        name: config.name.clone(),
-        projects,
+    // 目录大小需要遍历整个目录，轻量模式下跳过
+    let size_bytes = (is_archived && with_status).then(|| calculate_dir_size(&path));
+    let archived_at = if is_archived {
+        archived_at(&path)
+    } else {
+        None
     };
-    log::info!("get_main_workspace_status took {:?}", start.elapsed());
-    Ok(result)
-}
 
-#[tauri::command]
-pub(crate) fn get_main_workspace_status(
-    window: tauri::Window,
-) -> Result<MainWorkspaceStatus, String> {
-    get_main_workspace_status_impl(window.label())
+    WorktreeListItem {
+        name,
+        path: normalize_path(&path.to_string_lossy()),
+        rel_path: to_workspace_relative(workspace_path, &path),
+        is_archived,
+        projects,
+        size_bytes,
+        size_display: size_bytes.map(format_size),
+        archived_at,
+        meta,
+    }
 }
 
-pub fn create_worktree_impl(
-    window_label: &str,
+/// worktree 中单个项目的状态；`with_status` 为 false 时只返回目录信息和配置的分支
+fn build_project_status(
+    proj_path: &Path,
+    workspace_path: &str,
+    config: &crate::types::WorkspaceConfig,
+    with_status: bool,
+) -> ProjectStatus {
+    let proj_name = proj_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("")
+        .to_string();
+
+    let proj_config = config
+        .projects
+        .iter()
+        .find(|p| p.name == proj_name)
+        .cloned()
+        .unwrap_or(ProjectConfig {
+            name: proj_name.clone(),
+            base_branch: "uat".to_string(),
+            test_branch: "test".to_string(),
+            merge_strategy: "merge".to_string(),
+            linked_folders: vec![],
+            copy_rules: vec![],
+            status_excludes: vec![],
+            skip_lfs: false,
+            sparse_paths: vec![],
+            remote: default_remote(),
+            quick_commands: vec![],
+            tasks: Default::default(),
+            terminal_presets: vec![],
+            shell: None,
+            env: Default::default(),
+        });
+
+    let info = if with_status {
+        get_worktree_info(proj_path, &proj_config.status_excludes, &proj_config.remote)
+    } else {
+        WorktreeInfo::default()
+    };
+
+    ProjectStatus {
+        name: proj_name,
+        path: normalize_path(&proj_path.to_string_lossy()),
+        rel_path: to_workspace_relative(workspace_path, proj_path),
+        current_branch: info.current_branch,
+        base_branch: proj_config.base_branch,
+        test_branch: proj_config.test_branch,
+        has_uncommitted: info.uncommitted_count > 0,
+        uncommitted_count: info.uncommitted_count,
+        is_merged_to_test: info.is_merged_to_test,
+        ahead_of_base: info.ahead_of_base,
+        behind_base: info.behind_base,
+        line_ending_mismatch: info.line_ending_mismatch,
+    }
+}
+
+/// 归档时间：归档会移除 projects 下的 git worktree 并重命名目录，取两者中较新的修改时间
+fn archived_at(path: &std::path::Path) -> Option<String> {
+    [path.to_path_buf(), path.join("projects")]
+        .iter()
+        .filter_map(|p| std::fs::metadata(p).and_then(|m| m.modified()).ok())
+        .max()
+        .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339())
+}
+
+// ==================== 结束 Worktree 进程 ====================
+
+/// 归档/删除前调用：结束残留进程并记录结果
+fn kill_processes_in(path: &Path) {
+    let killed = crate::process_tracker::kill_processes_under(path);
+    if killed.is_empty() {
+        return;
+    }
+    let failed = killed.iter().filter(|p| !p.killed).count();
+    log::info!(
+        "[worktree] Killed {} processes in {} ({} failed)",
+        killed.len() - failed,
+        path.display(),
+        failed
+    );
+}
+
+/// 结束工作目录位于 `path`（某个 worktree 或其子目录）下的所有进程，
+/// 包括外部终端中启动的；先 SIGTERM，宽限期后仍未退出的强制结束
+pub fn kill_worktree_processes_impl(
+    window_label: &str,
+    path: String,
+) -> Result<Vec<KilledProcess>, String> {
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let path = PathBuf::from(resolve_workspace_path(window_label, &path)?);
+    let worktrees_root = PathBuf::from(&workspace_path).join(&config.worktrees_dir);
+    let in_worktree = path
+        .strip_prefix(&worktrees_root)
+        .is_ok_and(|rel| rel.components().next().is_some());
+    if !in_worktree || !path.is_dir() {
+        return Err(format!("不是 worktree 目录: {}", path.display()));
+    }
+
+    log::info!("[worktree] Killing processes under {}", path.display());
+    let killed = crate::process_tracker::kill_processes_under(&path);
+    log::info!(
+        "[worktree] Killed {}/{} processes under {}",
+        killed.iter().filter(|p| p.killed).count(),
+        killed.len(),
+        path.display()
+    );
+    Ok(killed)
+}
+
+#[tauri::command]
+pub(crate) async fn kill_worktree_processes(
+    window: tauri::Window,
+    path: String,
+) -> Result<Vec<KilledProcess>, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || kill_worktree_processes_impl(&label, path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+// ==================== 刷新工作区 ====================
+
+struct RefreshGuard(String);
+
+impl Drop for RefreshGuard {
+    fn drop(&mut self) {
+        if let Ok(mut set) = REFRESHING_WORKSPACES.lock() {
+            set.remove(&self.0);
+        }
+    }
+}
+
+/// 丢弃配置缓存后重新扫描 worktree 与主工作区状态，扫描过程中推送进度。
+/// 供网页端“拉取最新状态”使用，比循环调用 list_worktrees 更省。
+pub fn refresh_workspace_impl(window_label: &str) -> Result<WorkspaceRefreshResult, String> {
+    let start = std::time::Instant::now();
+    let workspace_path = get_window_workspace_path(window_label).ok_or("No workspace selected")?;
+    let _refresh_guard = {
+        let mut set = REFRESHING_WORKSPACES.lock().unwrap();
+        if !set.insert(workspace_path.clone()) {
+            return Err("Workspace refresh already in progress".to_string());
+        }
+        RefreshGuard(workspace_path.clone())
+    };
+    log::info!("[worktree] Refreshing workspace '{}'", workspace_path);
+
+    WORKSPACE_CONFIG_CACHE.lock().unwrap().remove(&workspace_path);
+    let (_, config) = get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+
+    let worktrees_path = PathBuf::from(&workspace_path).join(&config.worktrees_dir);
+    let worktrees = if worktrees_path.exists() {
+        scan_worktrees_dir_with_progress(
+            &worktrees_path,
+            &workspace_path,
+            &config,
+            true,
+            &[],
+            |scanned, total| {
+                notify_client(
+                    window_label,
+                    "workspace-refresh-progress",
+                    "workspace_refresh_progress",
+                    serde_json::json!({
+                        "workspacePath": workspace_path,
+                        "scanned": scanned,
+                        "total": total,
+                    }),
+                )
+            },
+        )?
+    } else {
+        vec![]
+    };
+    let main_workspace = get_main_workspace_status_impl(window_label)?;
+
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+    log::info!(
+        "[worktree] Workspace '{}' refreshed: {} worktrees in {}ms",
+        workspace_path,
+        worktrees.len(),
+        elapsed_ms
+    );
+    Ok(WorkspaceRefreshResult {
+        worktrees,
+        main_workspace,
+        elapsed_ms,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn refresh_workspace(
+    window: tauri::Window,
+) -> Result<WorkspaceRefreshResult, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || refresh_workspace_impl(&label))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+pub fn get_main_workspace_status_impl(window_label: &str) -> Result<MainWorkspaceStatus, String> {
+    let start = std::time::Instant::now();
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+
+    let root_path = PathBuf::from(&workspace_path);
+    let projects_path = root_path.join("projects");
+
+    let mut projects = vec![];
+
+    for proj_config in &config.projects {
+        let proj_path = projects_path.join(&proj_config.name);
+        if !proj_path.exists() {
+            continue;
+        }
+
+        let info = get_worktree_info(&proj_path, &proj_config.status_excludes, &proj_config.remote);
+
+        projects.push(MainProjectStatus {
+            name: proj_config.name.clone(),
+            path: normalize_path(&proj_path.to_string_lossy()),
+            rel_path: to_workspace_relative(&workspace_path, &proj_path),
+            current_branch: info.current_branch,
+            has_uncommitted: info.uncommitted_count > 0,
+            uncommitted_count: info.uncommitted_count,
+            is_merged_to_test: info.is_merged_to_test,
+            ahead_of_base: info.ahead_of_base,
+            behind_base: info.behind_base,
+            base_branch: proj_config.base_branch.clone(),
+            test_branch: proj_config.test_branch.clone(),
+            linked_folders: proj_config.linked_folders.clone(),
+        });
+    }
+
+    let result = MainWorkspaceStatus {
+        path: normalize_path(&root_path.to_string_lossy()),
+        name: config.name.clone(),
+        projects,
+    };
+    log::info!("get_main_workspace_status took {:?}", start.elapsed());
+    Ok(result)
+}
+
+#[tauri::command]
+pub(crate) fn get_main_workspace_status(
+    window: tauri::Window,
+) -> Result<MainWorkspaceStatus, String> {
+    get_main_workspace_status_impl(window.label())
+}
+
+/// 通知发起方当前在主仓库队列中的位置（ahead 为 0 表示轮到执行）
+fn notify_repo_queue(window_label: &str, worktree_name: &str, project_name: &str, ahead: usize) {
+    log::info!(
+        "[worktree] '{}' project '{}': {} operation(s) ahead in repo queue",
+        worktree_name, project_name, ahead
+    );
+    notify_client(
+        window_label,
+        "worktree-queue",
+        "worktree_queue",
+        serde_json::json!({
+            "worktreeName": worktree_name,
+            "projectName": project_name,
+            "ahead": ahead,
+        }),
+    );
+}
+
+/// 向发起请求的窗口 / Web 会话推送进度：桌面窗口收到 Tauri 事件 event，
+/// Web 会话收到 type 为 ws_type 的 WebSocket 消息（payload 字段平铺）
+fn notify_client(window_label: &str, event: &str, ws_type: &str, payload: serde_json::Value) {
+    if let Some(app) = APP_HANDLE.lock().ok().and_then(|h| h.clone()) {
+        let _ = app.emit_to(window_label, event, payload.clone());
+    }
+    let mut notification = payload;
+    if let Some(obj) = notification.as_object_mut() {
+        obj.insert("session_id".to_string(), window_label.into());
+        obj.insert("type".to_string(), ws_type.into());
+    }
+    let _ = CLIENT_NOTIFICATION_BROADCAST.send(notification.to_string());
+}
+
+pub fn create_worktree_impl(
+    window_label: &str,
     request: CreateWorktreeRequest,
-) -> Result<String, String> {
+) -> Result<CreateWorktreeResult, String> {
+    let _guard = begin_git_operation("create_worktree")?;
     let (workspace_path, config) =
         get_window_workspace_config(window_label).ok_or("No workspace selected")?;
 
@@ -250,19 +1136,67 @@ pub fn create_worktree_impl(
     log::info!("[worktree] Step 1: Creating directory structure at {}", worktree_path.display());
     std::fs::create_dir_all(worktree_path.join("projects"))
         .map_err(|e| format!("Failed to create worktree directory: {}", e))?;
+    let meta = WorktreeMeta {
+        created_at: Some(chrono::Utc::now().to_rfc3339()),
+        ..Default::default()
+    };
+    if let Err(e) = write_worktree_meta(&worktree_path, &meta) {
+        log::warn!("[worktree] {}", e);
+    }
+    if config.port_allocation.enabled {
+        if let Err(e) =
+            crate::ports::ensure_worktree_ports(&config, &workspace_path, &worktree_path)
+        {
+            log::warn!("[worktree] Failed to assign ports: {}", e);
+        }
+    }
 
     // Create symlinks for workspace-level items
     log::info!(
         "[worktree] Step 2: Creating workspace-level symlinks ({} items)",
         config.linked_workspace_items.len()
     );
+    let mut link_results: Vec<LinkResult> = vec![];
     for name in &config.linked_workspace_items {
         let src = root.join(name);
         let dst = worktree_path.join(name);
-        if src.exists() && !dst.exists() {
-            #[allow(unused_variables)]
-            let link_result = create_symlink(&src, &dst);
-            log::debug!("[worktree] Linked workspace item: {} (result: {:?})", name, link_result);
+        link_results.push(link_item(
+            &src,
+            &dst,
+            None,
+            name,
+            config.existing_dir_policy,
+        ));
+    }
+
+    // Fetch all requested projects up front, in parallel
+    let fetch_targets: Vec<(String, String)> = request
+        .projects
+        .iter()
+        .map(|proj_req| {
+            let remote = proj_req.remote.clone().unwrap_or_else(|| {
+                config
+                    .projects
+                    .iter()
+                    .find(|p| p.name == proj_req.name)
+                    .map(|p| p.remote.clone())
+                    .unwrap_or_else(default_remote)
+            });
+            (proj_req.name.clone(), remote)
+        })
+        .collect();
+    log::info!(
+        "[worktree] Step 3: Fetching {} projects in parallel",
+        fetch_targets.len()
+    );
+    let recorder = OperationRecorder::current();
+    let fetch_results =
+        fetch_projects_parallel(&root, &fetch_targets, recorder.as_ref(), |name, ahead| {
+            notify_repo_queue(window_label, &request.name, name, ahead)
+        });
+    for ((name, _), result) in fetch_targets.iter().zip(fetch_results) {
+        if let Err(e) = result {
+            return Err(format!("Failed to fetch {}: {}", name, e));
         }
     }
 
@@ -279,17 +1213,26 @@ pub fn create_worktree_impl(
                 test_branch: "test".to_string(),
                 merge_strategy: "merge".to_string(),
                 linked_folders: vec![],
+                copy_rules: vec![],
+                status_excludes: vec![],
+                skip_lfs: false,
+                sparse_paths: vec![],
+                remote: default_remote(),
+                quick_commands: vec![],
+                tasks: Default::default(),
+                terminal_presets: vec![],
+                shell: None,
+                env: Default::default(),
             });
 
         let main_proj_path = root.join("projects").join(&proj_req.name);
         let wt_proj_path = worktree_path.join("projects").join(&proj_req.name);
+        let remote = proj_req.remote.clone().unwrap_or_else(|| proj_config.remote.clone());
 
-        // Fetch origin first (with timeout)
-        log::info!(
-            "[worktree] Project '{}': git fetch origin",
-            proj_req.name
-        );
-        run_git_command_with_timeout(&["fetch", "origin"], main_proj_path.to_str().unwrap())?;
+        // Serialize worktree add against other operations on the same main repo
+        let _repo_slot = enter_repo_queue(&main_proj_path, |ahead| {
+            notify_repo_queue(window_label, &request.name, &proj_req.name, ahead)
+        });
 
         // Check if branch already exists
         let branch_check = Command::new("git")
@@ -300,7 +1243,7 @@ pub fn create_worktree_impl(
                 "--list",
                 &request.name,
             ])
-            .output();
+            .logged_output();
 
         let branch_exists = branch_check
             .as_ref()
@@ -323,13 +1266,15 @@ pub fn create_worktree_impl(
                     wt_proj_path.to_str().unwrap(),
                     &request.name,
                 ])
-                .output()
+                .env("GIT_LFS_SKIP_SMUDGE", "1")
+                .logged_output()
                 .map_err(|e| format!("Failed to create worktree: {}", e))?
         } else {
             log::info!(
-                "Creating new branch '{}' for project {} from origin/{}",
+                "Creating new branch '{}' for project {} from {}/{}",
                 request.name,
                 proj_req.name,
+                remote,
                 proj_req.base_branch
             );
             Command::new("git")
@@ -341,9 +1286,10 @@ pub fn create_worktree_impl(
                     wt_proj_path.to_str().unwrap(),
                     "-b",
                     &request.name,
-                    &format!("origin/{}", proj_req.base_branch),
+                    &format!("{}/{}", remote, proj_req.base_branch),
                 ])
-                .output()
+                .env("GIT_LFS_SKIP_SMUDGE", "1")
+                .logged_output()
                 .map_err(|e| format!("Failed to create worktree: {}", e))?
         };
 
@@ -359,6 +1305,9 @@ pub fn create_worktree_impl(
             ));
         }
         log::info!("[worktree] Project '{}': git worktree add succeeded", proj_req.name);
+        apply_sparse_checkout(&wt_proj_path, &proj_config, &proj_req.sparse_paths);
+        apply_branch_strategy(&wt_proj_path, &request.name, &proj_config);
+        sync_lfs_objects(&wt_proj_path, &proj_config);
 
         // Link configured folders
         log::info!(
@@ -369,41 +1318,58 @@ pub fn create_worktree_impl(
             let main_folder = main_proj_path.join(folder_name);
             let wt_folder = wt_proj_path.join(folder_name);
 
-            if main_folder.exists() && !wt_folder.exists() {
-                create_symlink(&main_folder, &wt_folder).ok();
-
-                // Remove from git index if it's tracked
-                Command::new("git")
-                    .args([
-                        "-C",
-                        wt_proj_path.to_str().unwrap(),
-                        "rm",
-                        "--cached",
-                        "-r",
-                        folder_name,
-                    ])
-                    .output()
-                    .ok();
+            let link = link_item(
+                &main_folder,
+                &wt_folder,
+                Some(&proj_req.name),
+                folder_name,
+                config.existing_dir_policy,
+            );
+            if link.status == LinkStatus::Created {
+                untrack_linked_folder(&wt_proj_path, folder_name);
             }
+            link_results.push(link);
         }
+        link_results.extend(apply_copy_rules(
+            &main_proj_path,
+            &wt_proj_path,
+            &proj_req.name,
+            &proj_config.copy_rules,
+        ));
     }
 
+    let failed = link_results
+        .iter()
+        .filter(|r| r.status == LinkStatus::Failed)
+        .count();
     log::info!(
-        "[worktree] Successfully created worktree '{}' with {} projects",
-        request.name, project_count
+        "[worktree] Successfully created worktree '{}' with {} projects ({} link failures)",
+        request.name, project_count, failed
     );
-    Ok(normalize_path(&worktree_path.to_string_lossy()))
+
+    // on_create 钩子失败只记录在结果中
+    let hook_ctx = HookContext::for_worktree(&workspace_path, &request.name, &worktree_path);
+    let hook_runs = run_hooks(&config, HookEvent::OnCreate, &hook_ctx);
+    send_webhooks(&config, HookEvent::OnCreate, &hook_ctx);
+
+    Ok(CreateWorktreeResult {
+        path: normalize_path(&worktree_path.to_string_lossy()),
+        rel_path: to_workspace_relative(&workspace_path, &worktree_path),
+        link_results,
+        hook_runs,
+    })
 }
 
 #[tauri::command]
 pub(crate) fn create_worktree(
     window: tauri::Window,
     request: CreateWorktreeRequest,
-) -> Result<String, String> {
+) -> Result<CreateWorktreeResult, String> {
     create_worktree_impl(window.label(), request)
 }
 
-pub fn archive_worktree_impl(window_label: &str, name: String) -> Result<(), String> {
+pub fn archive_worktree_impl(window_label: &str, name: String) -> Result<Vec<HookRun>, String> {
+    let _guard = begin_git_operation("archive_worktree")?;
     let (workspace_path, config) =
         get_window_workspace_config(window_label).ok_or("No workspace selected")?;
 
@@ -419,6 +1385,11 @@ pub fn archive_worktree_impl(window_label: &str, name: String) -> Result<(), Str
 
     log::info!("[worktree] Archiving worktree '{}' in workspace '{}'", name, workspace_path);
 
+    // on_archive 钩子：任一命令失败则中止，此时目录和 git 注册均未改动
+    let hook_ctx = HookContext::for_worktree(&workspace_path, &name, &worktree_path);
+    let hook_runs = run_hooks(&config, HookEvent::OnArchive, &hook_ctx);
+    ensure_hooks_succeeded(&hook_runs)?;
+
     // Step 1: Close all PTY sessions associated with this worktree
     log::info!("[worktree] Step 1/3: Closing PTY sessions for worktree '{}'", name);
     {
@@ -436,9 +1407,15 @@ pub fn archive_worktree_impl(window_label: &str, name: String) -> Result<(), Str
             }
         }
     }
+    if config.kill_processes_on_archive {
+        kill_processes_in(&worktree_path);
+    }
+    crate::compose::down_worktree(&workspace_path, &name, &worktree_path);
 
     // Step 2: Remove git worktrees first
     log::info!("[worktree] Step 2/3: Removing git worktree registrations for '{}'", name);
+    // 被锁定的 git worktree 无法 remove，先解锁
+    release_git_worktree_lock(&workspace_path, &config.worktrees_dir, &name);
     let projects_path = worktree_path.join("projects");
     if projects_path.exists() {
         if let Ok(entries) = std::fs::read_dir(&projects_path) {
@@ -458,7 +1435,7 @@ pub fn archive_worktree_impl(window_label: &str, name: String) -> Result<(), Str
                         proj_path.to_str().unwrap(),
                         "--force",
                     ])
-                    .output();
+                    .logged_output();
 
                 match &output {
                     Ok(o) if o.status.success() => {
@@ -495,14 +1472,93 @@ pub fn archive_worktree_impl(window_label: &str, name: String) -> Result<(), Str
         .map_err(|e| format!("Failed to archive worktree: {}", e))?;
 
     log::info!("[worktree] Successfully archived worktree '{}'", name);
-    Ok(())
+    send_webhooks(&config, HookEvent::OnArchive, &hook_ctx);
+    notifier::notify(
+        NotifyEvent::WorktreeArchived,
+        format!("📦 [{}] worktree {} 已归档", config.name, name),
+    );
+    Ok(hook_runs)
 }
 
 #[tauri::command]
-pub(crate) fn archive_worktree(window: tauri::Window, name: String) -> Result<(), String> {
+pub(crate) fn archive_worktree(
+    window: tauri::Window,
+    name: String,
+) -> Result<Vec<HookRun>, String> {
     archive_worktree_impl(window.label(), name)
 }
 
+/// 批量归档：逐个检查，只归档检查通过的 worktree，其余原样保留并返回原因
+pub fn archive_worktrees_impl(
+    window_label: &str,
+    names: Vec<String>,
+) -> Result<Vec<BulkArchiveResult>, String> {
+    let _guard = begin_git_operation("archive_worktrees")?;
+    log::info!("[worktree] Bulk archiving {} worktrees", names.len());
+
+    let mut results = vec![];
+    for name in names {
+        let status = match check_worktree_status_impl(window_label, name.clone()) {
+            Ok(status) => status,
+            Err(e) => {
+                results.push(BulkArchiveResult {
+                    name,
+                    outcome: BulkArchiveOutcome::Failed,
+                    reasons: vec![e],
+                    warnings: vec![],
+                });
+                continue;
+            }
+        };
+        if !status.can_archive {
+            log::info!(
+                "[worktree] Bulk archive: '{}' blocked ({} errors)",
+                name,
+                status.errors.len()
+            );
+            results.push(BulkArchiveResult {
+                name,
+                outcome: BulkArchiveOutcome::Blocked,
+                reasons: status.errors,
+                warnings: status.warnings,
+            });
+            continue;
+        }
+        let (outcome, reasons) = match archive_worktree_impl(window_label, name.clone()) {
+            Ok(_) => (BulkArchiveOutcome::Archived, vec![]),
+            Err(e) => (BulkArchiveOutcome::Failed, vec![e]),
+        };
+        results.push(BulkArchiveResult {
+            name,
+            outcome,
+            reasons,
+            warnings: status.warnings,
+        });
+    }
+
+    let archived = results
+        .iter()
+        .filter(|r| r.outcome == BulkArchiveOutcome::Archived)
+        .count();
+    log::info!(
+        "[worktree] Bulk archive finished: {}/{} archived",
+        archived,
+        results.len()
+    );
+    Ok(results)
+}
+
+#[tauri::command]
+pub(crate) async fn archive_worktrees(
+    window: tauri::Window,
+    names: Vec<String>,
+) -> Result<Vec<BulkArchiveResult>, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || archive_worktrees_impl(&label, names))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
 pub fn check_worktree_status_impl(
     window_label: &str,
     name: String,
@@ -543,14 +1599,33 @@ pub fn check_worktree_status_impl(
                 .unwrap_or("")
                 .to_string();
 
-            let branch_status = get_branch_status(&proj_path, &proj_name);
+            let branch_status = get_branch_status(
+                &proj_path,
+                &proj_name,
+                &project_status_excludes(&config, &proj_name),
+                &config.archive_ignore_patterns,
+                &project_remote(&config, &proj_name),
+            );
 
-            if branch_status.has_uncommitted {
+            // 匹配 archive_ignore_patterns 的文件（如 .DS_Store）不阻止归档
+            let blocking_count = branch_status
+                .uncommitted_count
+                .saturating_sub(branch_status.ignorable_count);
+            if blocking_count > 0 {
                 status.errors.push(format!(
-                    "{}: {} 个未提交的更改",
-                    proj_name, branch_status.uncommitted_count
+                    "{}: {} 个未提交的更改（暂存 {}，修改 {}，未跟踪 {}）",
+                    proj_name,
+                    blocking_count,
+                    branch_status.staged_count,
+                    branch_status.modified_count,
+                    branch_status.untracked_count
                 ));
                 status.can_archive = false;
+            } else if branch_status.ignorable_count > 0 {
+                status.warnings.push(format!(
+                    "{}: {} 个已忽略的未提交文件将随归档移除",
+                    proj_name, branch_status.ignorable_count
+                ));
             }
 
             if !branch_status.is_pushed {
@@ -588,7 +1663,11 @@ pub(crate) fn check_worktree_status(
     check_worktree_status_impl(window.label(), name)
 }
 
-pub fn restore_worktree_impl(window_label: &str, name: String) -> Result<(), String> {
+pub fn restore_worktree_impl(
+    window_label: &str,
+    name: String,
+) -> Result<RestoreWorktreeResult, String> {
+    let _guard = begin_git_operation("restore_worktree")?;
     let (workspace_path, config) =
         get_window_workspace_config(window_label).ok_or("No workspace selected")?;
 
@@ -625,6 +1704,7 @@ pub fn restore_worktree_impl(window_label: &str, name: String) -> Result<(), Str
 
     // Step 2: Re-register git worktrees for each project
     log::info!("[worktree] Step 2/3: Re-registering git worktrees for '{}'", restored_name);
+    let mut link_results: Vec<LinkResult> = vec![];
     let projects_path = worktree_path.join("projects");
     if projects_path.exists() {
         if let Ok(entries) = std::fs::read_dir(&projects_path) {
@@ -663,7 +1743,7 @@ pub fn restore_worktree_impl(window_label: &str, name: String) -> Result<(), Str
                         "--list",
                         branch_name,
                     ])
-                    .output();
+                    .logged_output();
 
                 let branch_exists = branch_check
                     .as_ref()
@@ -678,7 +1758,7 @@ pub fn restore_worktree_impl(window_label: &str, name: String) -> Result<(), Str
                 // Prune stale worktrees first
                 Command::new("git")
                     .args(["-C", main_proj_path.to_str().unwrap(), "worktree", "prune"])
-                    .output()
+                    .logged_output()
                     .ok();
 
                 // Re-add worktree
@@ -697,7 +1777,7 @@ pub fn restore_worktree_impl(window_label: &str, name: String) -> Result<(), Str
                             wt_proj_path.to_str().unwrap(),
                             branch_name,
                         ])
-                        .output()
+                        .logged_output()
                 } else {
                     // Find appropriate base branch from project config
                     let base_branch = config
@@ -706,11 +1786,13 @@ pub fn restore_worktree_impl(window_label: &str, name: String) -> Result<(), Str
                         .find(|p| p.name == proj_name)
                         .map(|p| p.base_branch.clone())
                         .unwrap_or_else(|| "uat".to_string());
+                    let remote = project_remote(&config, &proj_name);
 
                     log::info!(
-                        "Re-adding worktree for {} with new branch {} from origin/{}",
+                        "Re-adding worktree for {} with new branch {} from {}/{}",
                         proj_name,
                         branch_name,
+                        remote,
                         base_branch
                     );
                     Command::new("git")
@@ -722,9 +1804,9 @@ pub fn restore_worktree_impl(window_label: &str, name: String) -> Result<(), Str
                             wt_proj_path.to_str().unwrap(),
                             "-b",
                             branch_name,
-                            &format!("origin/{}", base_branch),
+                            &format!("{}/{}", remote, base_branch),
                         ])
-                        .output()
+                        .logged_output()
                 };
 
                 match output {
@@ -744,17 +1826,28 @@ pub fn restore_worktree_impl(window_label: &str, name: String) -> Result<(), Str
                     }
                 }
 
-                // Restore project-level symlinks (linked_folders)
+                // Restore sparse checkout and project-level symlinks (linked_folders)
                 let proj_config = config.projects.iter().find(|p| p.name == proj_name);
                 if let Some(pc) = proj_config {
+                    apply_sparse_checkout(&wt_proj_path, pc, &[]);
                     for folder_name in &pc.linked_folders {
                         let main_folder = main_proj_path.join(folder_name);
                         let wt_folder = wt_proj_path.join(folder_name);
 
-                        if main_folder.exists() && !wt_folder.exists() {
-                            create_symlink(&main_folder, &wt_folder).ok();
-                        }
+                        link_results.push(link_item(
+                            &main_folder,
+                            &wt_folder,
+                            Some(&proj_name),
+                            folder_name,
+                            config.existing_dir_policy,
+                        ));
                     }
+                    link_results.extend(apply_copy_rules(
+                        &main_proj_path,
+                        &wt_proj_path,
+                        &proj_name,
+                        &pc.copy_rules,
+                    ));
                 }
             }
         }
@@ -768,21 +1861,43 @@ pub fn restore_worktree_impl(window_label: &str, name: String) -> Result<(), Str
     for item_name in &config.linked_workspace_items {
         let src = root.join(item_name);
         let dst = worktree_path.join(item_name);
-        if src.exists() && !dst.exists() {
-            create_symlink(&src, &dst).ok();
-        }
+        link_results.push(link_item(
+            &src,
+            &dst,
+            None,
+            item_name,
+            config.existing_dir_policy,
+        ));
     }
 
     log::info!("Successfully restored worktree '{}'", restored_name);
-    Ok(())
+
+    let hook_ctx = HookContext::for_worktree(&workspace_path, restored_name, &worktree_path);
+    let hook_runs = run_hooks(&config, HookEvent::OnRestore, &hook_ctx);
+    send_webhooks(&config, HookEvent::OnRestore, &hook_ctx);
+    Ok(RestoreWorktreeResult {
+        link_results,
+        hook_runs,
+    })
 }
 
 #[tauri::command]
-pub(crate) fn restore_worktree(window: tauri::Window, name: String) -> Result<(), String> {
+pub(crate) fn restore_worktree(
+    window: tauri::Window,
+    name: String,
+) -> Result<RestoreWorktreeResult, String> {
     restore_worktree_impl(window.label(), name)
 }
 
-pub fn delete_archived_worktree_impl(window_label: &str, name: String) -> Result<(), String> {
+/// 永久删除已归档的 worktree 及各项目中的同名本地分支。
+/// `delete_remote` 为 true 时同时删除 `origin/<branch>`；远程删除失败不会中断，
+/// 结果按项目列出，便于发现部分失败
+pub fn delete_archived_worktree_impl(
+    window_label: &str,
+    name: String,
+    delete_remote: bool,
+) -> Result<DeleteArchivedResult, String> {
+    let _guard = begin_git_operation("delete_archived_worktree")?;
     let (workspace_path, config) =
         get_window_workspace_config(window_label).ok_or("No workspace selected")?;
 
@@ -798,12 +1913,17 @@ pub fn delete_archived_worktree_impl(window_label: &str, name: String) -> Result
         return Err("Archived worktree does not exist".to_string());
     }
 
-    let branch_name = name.strip_suffix(".archive").unwrap_or(&name);
+    let branch_name = name.strip_suffix(".archive").unwrap_or(&name).to_string();
     log::info!(
-        "[worktree] Deleting archived worktree '{}' (branch: {}) in workspace '{}'",
-        name, branch_name, workspace_path
+        "[worktree] Deleting archived worktree '{}' (branch: {}, delete_remote: {}) in workspace '{}'",
+        name, branch_name, delete_remote, workspace_path
     );
 
+    // on_delete 钩子：任一命令失败则中止，分支和目录均保留
+    let hook_ctx = HookContext::for_worktree(&workspace_path, &branch_name, &archive_path);
+    let hook_runs = run_hooks(&config, HookEvent::OnDelete, &hook_ctx);
+    ensure_hooks_succeeded(&hook_runs)?;
+
     // Step 1: Close any related PTY sessions
     log::info!("[worktree] Step 1/3: Closing PTY sessions for archived worktree '{}'", name);
     {
@@ -817,57 +1937,1256 @@ pub fn delete_archived_worktree_impl(window_label: &str, name: String) -> Result
             }
         }
     }
+    if config.kill_processes_on_archive {
+        kill_processes_in(&archive_path);
+    }
+
+    // Step 2: Delete associated branches for each project
+    log::info!("[worktree] Step 2/3: Deleting branch '{}' from projects", branch_name);
+    let mut projects: Vec<BranchDeleteResult> = vec![];
+    let projects_path = root.join("projects");
+    if projects_path.exists() {
+        if let Ok(entries) = std::fs::read_dir(&projects_path) {
+            for entry in entries.flatten() {
+                let proj_path = entry.path();
+                if !proj_path.is_dir() {
+                    continue;
+                }
+                let proj_name = proj_path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+                let remote = project_remote(&config, proj_name);
+                if let Some(result) =
+                    delete_project_branch(&proj_path, &remote, &branch_name, delete_remote)
+                {
+                    projects.push(result);
+                }
+            }
+        }
+    }
+    projects.sort_by(|a, b| a.project_name.cmp(&b.project_name));
+
+    // Step 3: Remove the directory
+    log::info!("[worktree] Step 3/3: Removing directory {}", archive_path.display());
+    fs::remove_dir_all(&archive_path)
+        .map_err(|e| format!("Failed to delete archived worktree: {}", e))?;
+
+    log::info!("[worktree] Successfully deleted archived worktree '{}'", name);
+    send_webhooks(&config, HookEvent::OnDelete, &hook_ctx);
+    Ok(DeleteArchivedResult {
+        name,
+        branch: branch_name,
+        delete_remote,
+        projects,
+        hook_runs,
+    })
+}
+
+/// 删除单个项目中的本地分支（及可选的远程分支）；两者都不存在时返回 None
+fn delete_project_branch(
+    proj_path: &std::path::Path,
+    remote: &str,
+    branch_name: &str,
+    delete_remote: bool,
+) -> Option<BranchDeleteResult> {
+    let proj_name = proj_path.file_name().and_then(|n| n.to_str()).unwrap_or("").to_string();
+    let proj_str = proj_path.to_string_lossy().to_string();
+    let local_exists = Command::new("git")
+        .args(["-C", &proj_str, "rev-parse", "--verify", "--quiet"])
+        .arg(format!("refs/heads/{}", branch_name))
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+    let remote_exists = git_ops::remote_branch_known(proj_path, remote, branch_name);
+    if !local_exists && (!delete_remote || !remote_exists) {
+        return None;
+    }
+
+    let mut result = BranchDeleteResult {
+        project_name: proj_name,
+        local_deleted: false,
+        remote_exists,
+        remote_deleted: false,
+        error: None,
+    };
+
+    if local_exists {
+        match Command::new("git")
+            .args(["-C", &proj_str, "branch", "-D", branch_name])
+            .logged_output()
+        {
+            Ok(o) if o.status.success() => {
+                log::info!("Deleted branch '{}' from project '{}'", branch_name, result.project_name);
+                result.local_deleted = true;
+            }
+            Ok(o) => result.error = Some(String::from_utf8_lossy(&o.stderr).trim().to_string()),
+            Err(e) => result.error = Some(e.to_string()),
+        }
+    }
+
+    if delete_remote && remote_exists {
+        match run_git_command_with_timeout(&["push", remote, "--delete", branch_name], &proj_str) {
+            Ok(o) if o.status.success() => {
+                log::info!(
+                    "Deleted remote branch '{}/{}' from project '{}'",
+                    remote,
+                    branch_name,
+                    result.project_name
+                );
+                result.remote_deleted = true;
+            }
+            Ok(o) => {
+                let stderr = String::from_utf8_lossy(&o.stderr).trim().to_string();
+                result.error = Some(match result.error.take() {
+                    Some(prev) => format!("{}; {}", prev, stderr),
+                    None => stderr,
+                });
+            }
+            Err(e) => {
+                result.error = Some(match result.error.take() {
+                    Some(prev) => format!("{}; {}", prev, e),
+                    None => e,
+                });
+            }
+        }
+    }
+
+    Some(result)
+}
+
+#[tauri::command]
+pub(crate) async fn delete_archived_worktree(
+    window: tauri::Window,
+    name: String,
+    delete_remote: Option<bool>,
+) -> Result<DeleteArchivedResult, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || {
+        delete_archived_worktree_impl(&label, name, delete_remote.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+// ==================== Worktree 元数据 ====================
+
+const WORKTREE_META_FILE: &str = ".worktree-meta.json";
+const MAX_LABEL_LEN: usize = 32;
+
+pub(crate) fn read_worktree_meta(worktree_path: &Path) -> Option<WorktreeMeta> {
+    let content = fs::read_to_string(worktree_path.join(WORKTREE_META_FILE)).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(meta) => Some(meta),
+        Err(e) => {
+            log::warn!(
+                "[worktree] Ignoring invalid {} in {}: {}",
+                WORKTREE_META_FILE,
+                worktree_path.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+pub(crate) fn write_worktree_meta(worktree_path: &Path, meta: &WorktreeMeta) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(meta)
+        .map_err(|e| format!("Failed to serialize worktree meta: {}", e))?;
+    fs::write(worktree_path.join(WORKTREE_META_FILE), json)
+        .map_err(|e| format!("Failed to write {}: {}", WORKTREE_META_FILE, e))
+}
+
+/// `name` 为 worktree 目录名，归档条目带 `.archive` 后缀
+fn worktree_dir(window_label: &str, name: &str) -> Result<PathBuf, String> {
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(format!("无效的 worktree 名称: {}", name));
+    }
+    let path = PathBuf::from(&workspace_path)
+        .join(&config.worktrees_dir)
+        .join(name);
+    if !path.join("projects").is_dir() {
+        return Err("Worktree does not exist".to_string());
+    }
+    Ok(path)
+}
+
+/// 未设置过元数据的 worktree 返回空值
+pub fn get_worktree_meta_impl(window_label: &str, name: String) -> Result<WorktreeMeta, String> {
+    let path = worktree_dir(window_label, &name)?;
+    Ok(read_worktree_meta(&path).unwrap_or_default())
+}
+
+#[tauri::command]
+pub(crate) fn get_worktree_meta(
+    window: tauri::Window,
+    name: String,
+) -> Result<WorktreeMeta, String> {
+    get_worktree_meta_impl(window.label(), name)
+}
+
+/// 更新描述和工单链接，created_at 保持不变
+pub fn set_worktree_meta_impl(
+    window_label: &str,
+    name: String,
+    description: String,
+    ticket_url: String,
+) -> Result<WorktreeMeta, String> {
+    let ticket_url = ticket_url.trim().to_string();
+    if !ticket_url.is_empty()
+        && !ticket_url.starts_with("http://")
+        && !ticket_url.starts_with("https://")
+    {
+        return Err("工单链接必须以 http:// 或 https:// 开头".to_string());
+    }
+    let path = worktree_dir(window_label, &name)?;
+    let mut meta = read_worktree_meta(&path).unwrap_or_default();
+    meta.description = description.trim().to_string();
+    meta.ticket_url = ticket_url;
+    write_worktree_meta(&path, &meta)?;
+    log::info!("[worktree] Updated meta for worktree '{}'", name);
+    Ok(meta)
+}
+
+#[tauri::command]
+pub(crate) fn set_worktree_meta(
+    window: tauri::Window,
+    name: String,
+    description: String,
+    ticket_url: String,
+) -> Result<WorktreeMeta, String> {
+    set_worktree_meta_impl(window.label(), name, description, ticket_url)
+}
+
+/// 标签统一小写、去掉首尾空白并去重，保持首次出现的顺序
+fn normalize_labels(labels: &[String]) -> Vec<String> {
+    let mut result: Vec<String> = vec![];
+    for label in labels {
+        let label = label.trim().to_lowercase();
+        if !label.is_empty() && !result.contains(&label) {
+            result.push(label);
+        }
+    }
+    result
+}
+
+/// 替换 worktree 的全部标签，其余元数据保持不变
+pub fn set_worktree_labels_impl(
+    window_label: &str,
+    name: String,
+    labels: Vec<String>,
+) -> Result<WorktreeMeta, String> {
+    let labels = normalize_labels(&labels);
+    if let Some(bad) = labels.iter().find(|l| l.chars().count() > MAX_LABEL_LEN) {
+        return Err(format!(
+            "标签过长（最多 {} 个字符）: {}",
+            MAX_LABEL_LEN, bad
+        ));
+    }
+    let path = worktree_dir(window_label, &name)?;
+    let mut meta = read_worktree_meta(&path).unwrap_or_default();
+    meta.labels = labels;
+    write_worktree_meta(&path, &meta)?;
+    log::info!(
+        "[worktree] Set labels for worktree '{}': {:?}",
+        name,
+        meta.labels
+    );
+    Ok(meta)
+}
+
+#[tauri::command]
+pub(crate) fn set_worktree_labels(
+    window: tauri::Window,
+    name: String,
+    labels: Vec<String>,
+) -> Result<WorktreeMeta, String> {
+    set_worktree_labels_impl(window.label(), name, labels)
+}
+
+/// 替换 worktree 的终端环境变量（新开的终端生效），其余元数据保持不变
+pub fn set_worktree_env_impl(
+    window_label: &str,
+    name: String,
+    env: BTreeMap<String, String>,
+) -> Result<WorktreeMeta, String> {
+    let env: BTreeMap<String, String> = env
+        .into_iter()
+        .map(|(key, value)| (key.trim().to_string(), value))
+        .filter(|(key, _)| !key.is_empty())
+        .collect();
+    if let Some(bad) = env.keys().find(|k| !crate::commands::pty::is_valid_env_name(k)) {
+        return Err(format!("无效的环境变量名: {}", bad));
+    }
+    let path = worktree_dir(window_label, &name)?;
+    let mut meta = read_worktree_meta(&path).unwrap_or_default();
+    meta.env = env;
+    write_worktree_meta(&path, &meta)?;
+    log::info!(
+        "[worktree] Set env for worktree '{}': {:?}",
+        name,
+        meta.env.keys().collect::<Vec<_>>()
+    );
+    Ok(meta)
+}
+
+#[tauri::command]
+pub(crate) fn set_worktree_env(
+    window: tauri::Window,
+    name: String,
+    env: BTreeMap<String, String>,
+) -> Result<WorktreeMeta, String> {
+    set_worktree_env_impl(window.label(), name, env)
+}
+
+/// 返回 worktree 的端口段（尚未分配时立即分配）以及与其他 worktree 重叠或已被占用的端口
+pub fn get_worktree_ports_impl(window_label: &str, name: String) -> Result<WorktreePorts, String> {
+    worktree_dir(window_label, &name)?;
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    crate::ports::worktree_ports(&config, &workspace_path, &name)
+}
+
+#[tauri::command]
+pub(crate) async fn get_worktree_ports(
+    window: tauri::Window,
+    name: String,
+) -> Result<WorktreePorts, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || get_worktree_ports_impl(&label, name))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// 按 workspace 的 issue_trackers 规则从 worktree 名称或项目分支名中识别工单，未匹配时返回 None
+pub async fn get_worktree_issue_impl(
+    window_label: &str,
+    name: String,
+) -> Result<Option<WorktreeIssue>, String> {
+    let path = worktree_dir(window_label, &name)?;
+    let (_, config) = get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    if config.issue_trackers.is_empty() {
+        return Ok(None);
+    }
+    let names = tokio::task::spawn_blocking(move || crate::issues::candidate_names(&path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?;
+    crate::issues::resolve_issue(&config.issue_trackers, &names).await
+}
+
+#[tauri::command]
+pub(crate) async fn get_worktree_issue(
+    window: tauri::Window,
+    name: String,
+) -> Result<Option<WorktreeIssue>, String> {
+    get_worktree_issue_impl(window.label(), name).await
+}
+
+// ==================== Docker Compose ====================
+
+/// 返回 (项目目录, compose 项目名, 工作区配置, worktree 目录)
+fn compose_target(
+    window_label: &str,
+    worktree: &str,
+    project: &str,
+) -> Result<(PathBuf, String, crate::types::WorkspaceConfig, PathBuf), String> {
+    let worktree_path = worktree_dir(window_label, worktree)?;
+    if project.is_empty() || project.starts_with('.') || project.contains(['/', '\\']) {
+        return Err(format!("无效的项目名称: {}", project));
+    }
+    let project_dir = worktree_path.join("projects").join(project);
+    if !project_dir.is_dir() {
+        return Err(format!("项目不存在: {}", project));
+    }
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let name = crate::compose::project_name(&workspace_path, worktree, project);
+    Ok((project_dir, name, config, worktree_path))
+}
+
+pub fn compose_status_impl(
+    window_label: &str,
+    name: String,
+    project: String,
+) -> Result<ComposeStatus, String> {
+    let (project_dir, compose_name, _, _) = compose_target(window_label, &name, &project)?;
+    crate::compose::status(&project_dir, &compose_name)
+}
+
+/// 带上 worktree 已分配的端口变量（PORT、WM_PORT_* 等），compose 文件中可用 ${PORT} 引用
+pub fn compose_up_impl(
+    window_label: &str,
+    name: String,
+    project: String,
+) -> Result<ComposeStatus, String> {
+    let (project_dir, compose_name, config, worktree_path) =
+        compose_target(window_label, &name, &project)?;
+    let envs = crate::ports::assigned_port_env(&config, &worktree_path, Some(&project));
+    crate::compose::up(&project_dir, &compose_name, &envs)
+}
+
+pub fn compose_down_impl(
+    window_label: &str,
+    name: String,
+    project: String,
+) -> Result<ComposeStatus, String> {
+    let (project_dir, compose_name, _, _) = compose_target(window_label, &name, &project)?;
+    crate::compose::down(&project_dir, &compose_name)
+}
+
+#[tauri::command]
+pub(crate) async fn compose_status(
+    window: tauri::Window,
+    name: String,
+    project: String,
+) -> Result<ComposeStatus, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || compose_status_impl(&label, name, project))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub(crate) async fn compose_up(
+    window: tauri::Window,
+    name: String,
+    project: String,
+) -> Result<ComposeStatus, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || compose_up_impl(&label, name, project))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub(crate) async fn compose_down(
+    window: tauri::Window,
+    name: String,
+    project: String,
+) -> Result<ComposeStatus, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || compose_down_impl(&label, name, project))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+// ==================== 重命名 Worktree 分支 ====================
+
+/// 新名称同时作为目录名和分支名，需满足 git 分支命名规则
+fn validate_worktree_name(name: &str) -> Result<(), String> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() || trimmed != name {
+        return Err("Worktree 名称不能为空或包含首尾空白".to_string());
+    }
+    if name.starts_with('.') || name.contains(['/', '\\']) || name.ends_with(".archive") {
+        return Err(format!("无效的 worktree 名称: {}", name));
+    }
+    let output = Command::new("git")
+        .args(["check-ref-format", "--branch", name])
+        .output()
+        .map_err(|e| format!("Failed to run git check-ref-format: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("无效的分支名: {}", name));
+    }
+    Ok(())
+}
+
+fn current_branch_name(path: &std::path::Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["-C", path.to_str()?, "symbolic-ref", "--short", "-q", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn rename_branch(path: &std::path::Path, from: &str, to: &str) -> Result<(), String> {
+    let output = Command::new("git")
+        .args(["-C", path.to_str().unwrap_or(""), "branch", "-m", from, to])
+        .logged_output()
+        .map_err(|e| format!("Failed to run git branch -m: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(())
+}
+
+/// 推送新分支并删除远程旧分支，返回 (pushed, old_remote_deleted, error)
+fn push_renamed_branch(
+    path: &std::path::Path,
+    remote: &str,
+    old_name: &str,
+    new_name: &str,
+) -> (bool, bool, Option<String>) {
+    let path_str = path.to_string_lossy().to_string();
+    match run_git_command_with_timeout(&["push", "-u", remote, new_name], &path_str) {
+        Ok(o) if o.status.success() => {}
+        Ok(o) => return (false, false, Some(String::from_utf8_lossy(&o.stderr).trim().to_string())),
+        Err(e) => return (false, false, Some(e)),
+    }
+    if !git_ops::remote_branch_known(path, remote, old_name) {
+        return (true, false, None);
+    }
+    match run_git_command_with_timeout(&["push", remote, "--delete", old_name], &path_str) {
+        Ok(o) if o.status.success() => (true, true, None),
+        Ok(o) => (true, false, Some(String::from_utf8_lossy(&o.stderr).trim().to_string())),
+        Err(e) => (true, false, Some(e)),
+    }
+}
+
+/// 将 worktree 改名：各项目的本地分支、worktree 目录、git worktree 登记、
+/// 锁与终端状态一并迁移。`push` 为 true 时推送新分支并删除远程旧分支。
+/// 本地重命名任一步失败都会回滚已改名的分支；推送失败只记录在结果中
+pub fn rename_worktree_branch_impl(
+    window_label: &str,
+    worktree_name: String,
+    new_name: String,
+    push: bool,
+) -> Result<RenameWorktreeResult, String> {
+    let _guard = begin_git_operation("rename_worktree_branch")?;
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+
+    if worktree_name == new_name {
+        return Err("新名称与原名称相同".to_string());
+    }
+    validate_worktree_name(&new_name)?;
+    if worktree_name.ends_with(".archive") {
+        return Err("Cannot rename an archived worktree".to_string());
+    }
+
+    let root = PathBuf::from(&workspace_path);
+    let worktrees_root = root.join(&config.worktrees_dir);
+    let old_path = worktrees_root.join(&worktree_name);
+    let new_path = worktrees_root.join(&new_name);
+    if !old_path.exists() {
+        return Err("Worktree does not exist".to_string());
+    }
+    if new_path.exists() || worktrees_root.join(format!("{}.archive", new_name)).exists() {
+        return Err(format!("Worktree '{}' 已存在", new_name));
+    }
+    if load_occupation_state(&workspace_path).is_some_and(|o| o.worktree_name == worktree_name) {
+        return Err("该 worktree 已部署到主工作区，请先退出占用再重命名".to_string());
+    }
+    {
+        let locks = crate::state::WORKTREE_LOCKS.lock().unwrap();
+        let key = (workspace_path.clone(), worktree_name.clone());
+        if let Some(owner) = locks.get(&key) {
+            if owner != window_label {
+                return Err(format!("Worktree '{}' 正被其他窗口使用", worktree_name));
+            }
+        }
+    }
+
+    log::info!(
+        "[worktree] Renaming worktree '{}' to '{}' in workspace '{}'",
+        worktree_name, new_name, workspace_path
+    );
+
+    // Step 1: 重命名各项目中与 worktree 同名的本地分支
+    let project_names = worktree_project_names(&old_path);
+    let mut renamed: Vec<String> = vec![];
+    let rollback = |renamed: &[String], base: &std::path::Path| {
+        for proj in renamed {
+            let path = base.join("projects").join(proj);
+            if let Err(e) = rename_branch(&path, &new_name, &worktree_name) {
+                log::warn!("[worktree] Failed to roll back branch in '{}': {}", proj, e);
+            }
+        }
+    };
+    for proj in &project_names {
+        let proj_path = old_path.join("projects").join(proj);
+        if current_branch_name(&proj_path).as_deref() != Some(worktree_name.as_str()) {
+            log::info!("[worktree] Project '{}' is not on branch '{}', skipping", proj, worktree_name);
+            continue;
+        }
+        if let Err(e) = rename_branch(&proj_path, &worktree_name, &new_name) {
+            rollback(&renamed, &old_path);
+            return Err(format!("项目 {} 分支重命名失败: {}", proj, e));
+        }
+        renamed.push(proj.clone());
+    }
+
+    // Step 2: 关闭终端并移动目录
+    release_git_worktree_lock(&workspace_path, &config.worktrees_dir, &worktree_name);
+    let old_path_str = old_path.to_string_lossy().to_string();
+    let new_path_str = new_path.to_string_lossy().to_string();
+    if let Ok(mut manager) = PTY_MANAGER.lock() {
+        let closed = manager.close_sessions_by_path_prefix(&old_path_str);
+        if !closed.is_empty() {
+            log::info!("[worktree] Closed {} PTY sessions before rename", closed.len());
+        }
+    }
+    if let Err(e) = fs::rename(&old_path, &new_path) {
+        rollback(&renamed, &old_path);
+        return Err(format!("Failed to rename worktree directory: {}", e));
+    }
+
+    // Step 3: 修复主仓库中的 worktree 登记路径
+    for proj in &project_names {
+        let main_proj_path = root.join("projects").join(proj);
+        let wt_proj_path = new_path.join("projects").join(proj);
+        let output = Command::new("git")
+            .args([
+                "-C",
+                main_proj_path.to_str().unwrap_or(""),
+                "worktree",
+                "repair",
+                wt_proj_path.to_str().unwrap_or(""),
+            ])
+            .logged_output();
+        match output {
+            Ok(o) if o.status.success() => {}
+            Ok(o) => log::warn!(
+                "[worktree] git worktree repair for '{}' failed: {}",
+                proj,
+                String::from_utf8_lossy(&o.stderr).trim()
+            ),
+            Err(e) => log::warn!("[worktree] Failed to run git worktree repair for '{}': {}", proj, e),
+        }
+    }
+
+    crate::commands::window::rename_worktree_state(
+        &workspace_path,
+        &worktree_name,
+        &new_name,
+        &old_path_str,
+        &new_path_str,
+    );
+    broadcast_lock_state(&workspace_path);
+
+    // Step 4: 可选推送
+    let projects = project_names
+        .iter()
+        .map(|proj| {
+            let was_renamed = renamed.contains(proj);
+            let (pushed, old_remote_deleted, error) = if push && was_renamed {
+                push_renamed_branch(
+                    &new_path.join("projects").join(proj),
+                    &project_remote(&config, proj),
+                    &worktree_name,
+                    &new_name,
+                )
+            } else {
+                (false, false, None)
+            };
+            BranchRenameProjectResult {
+                project_name: proj.clone(),
+                renamed: was_renamed,
+                pushed,
+                old_remote_deleted,
+                error,
+            }
+        })
+        .collect();
+
+    log::info!("[worktree] Renamed worktree '{}' to '{}'", worktree_name, new_name);
+    Ok(RenameWorktreeResult {
+        old_name: worktree_name,
+        new_name,
+        projects,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn rename_worktree_branch(
+    window: tauri::Window,
+    worktree_name: String,
+    new_name: String,
+    push: Option<bool>,
+) -> Result<RenameWorktreeResult, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || {
+        rename_worktree_branch_impl(&label, worktree_name, new_name, push.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+// ==================== 工作区清单（迁移） ====================
+
+const MANIFEST_VERSION: u32 = 1;
+
+pub fn export_workspace_manifest_impl(window_label: &str) -> Result<WorkspaceManifest, String> {
+    let (_, config) = get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let mut items = list_worktrees_impl(window_label, true, &[])?;
+    items.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let worktrees = items
+        .into_iter()
+        .map(|wt| {
+            let name = wt
+                .name
+                .strip_suffix(".archive")
+                .unwrap_or(&wt.name)
+                .to_string();
+            let projects = wt
+                .projects
+                .into_iter()
+                .map(|p| ManifestProject {
+                    // Archived worktrees aren't registered with git, so HEAD may be unreadable
+                    branch: if p.current_branch.is_empty() {
+                        name.clone()
+                    } else {
+                        p.current_branch
+                    },
+                    name: p.name,
+                    base_branch: p.base_branch,
+                    has_uncommitted: p.has_uncommitted,
+                    ahead_of_base: p.ahead_of_base,
+                })
+                .collect();
+            ManifestWorktree {
+                name,
+                is_archived: wt.is_archived,
+                projects,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    log::info!(
+        "[worktree] Exported manifest for workspace '{}' ({} worktrees)",
+        config.name,
+        worktrees.len()
+    );
+    Ok(WorkspaceManifest {
+        version: MANIFEST_VERSION,
+        workspace_name: config.name,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        worktrees,
+    })
+}
+
+#[tauri::command]
+pub(crate) fn export_workspace_manifest(window: tauri::Window) -> Result<WorkspaceManifest, String> {
+    export_workspace_manifest_impl(window.label())
+}
+
+/// Make sure `<worktree_name>` exists locally, tracking the pushed branch if there is one,
+/// so create_worktree picks up the remote work instead of branching fresh from base.
+fn prepare_branch_from_remote(
+    main_proj_path: &std::path::Path,
+    remote: &str,
+    worktree_name: &str,
+    branch: &str,
+) {
+    let cwd = main_proj_path.to_string_lossy().to_string();
+    if let Err(e) = run_git_command_with_timeout(&["fetch", remote], &cwd) {
+        log::warn!("[worktree] git fetch failed in {}: {}", cwd, e);
+        return;
+    }
+
+    let has_local = Command::new("git")
+        .args(["-C", &cwd, "branch", "--list", worktree_name])
+        .logged_output()
+        .map(|o| !String::from_utf8_lossy(&o.stdout).trim().is_empty())
+        .unwrap_or(false);
+    if has_local {
+        return;
+    }
+
+    let mut candidates = vec![branch];
+    if branch != worktree_name {
+        candidates.push(worktree_name);
+    }
+    for candidate in candidates {
+        let remote_ref = format!("refs/remotes/{}/{}", remote, candidate);
+        let exists = Command::new("git")
+            .args(["-C", &cwd, "rev-parse", "--verify", "--quiet", &remote_ref])
+            .logged_output()
+            .map(|o| o.status.success())
+            .unwrap_or(false);
+        if !exists {
+            continue;
+        }
+        let output = Command::new("git")
+            .args([
+                "-C",
+                &cwd,
+                "branch",
+                "--track",
+                worktree_name,
+                &format!("{}/{}", remote, candidate),
+            ])
+            .logged_output();
+        match output {
+            Ok(o) if o.status.success() => {
+                log::info!(
+                    "[worktree] Created local branch '{}' tracking {}/{} in {}",
+                    worktree_name, remote, candidate, cwd
+                );
+            }
+            Ok(o) => log::warn!(
+                "[worktree] Failed to create tracking branch '{}': {}",
+                worktree_name,
+                String::from_utf8_lossy(&o.stderr)
+            ),
+            Err(e) => log::warn!("[worktree] Failed to run git branch: {}", e),
+        }
+        return;
+    }
+}
+
+/// Recreate the active worktrees listed in a manifest. Existing worktrees are left
+/// alone, archived entries are skipped, and projects missing from this workspace are
+/// reported rather than failing the whole worktree.
+pub fn import_workspace_manifest_impl(
+    window_label: &str,
+    manifest: WorkspaceManifest,
+) -> Result<Vec<ManifestImportResult>, String> {
+    let _guard = begin_git_operation("import_workspace_manifest")?;
+    if manifest.version > MANIFEST_VERSION {
+        return Err(format!(
+            "Unsupported manifest version {} (expected <= {})",
+            manifest.version, MANIFEST_VERSION
+        ));
+    }
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let root = PathBuf::from(&workspace_path);
+
+    log::info!(
+        "[worktree] Importing manifest from workspace '{}' ({} worktrees) into '{}'",
+        manifest.workspace_name,
+        manifest.worktrees.len(),
+        workspace_path
+    );
+
+    let mut results = vec![];
+    for wt in manifest.worktrees.into_iter().filter(|w| !w.is_archived) {
+        let skip = |reason: String| ManifestImportResult {
+            worktree_name: wt.name.clone(),
+            created: false,
+            reason: Some(reason),
+        };
+
+        if wt.name.is_empty()
+            || wt.name.starts_with('.')
+            || wt.name.contains(['/', '\\'])
+            || wt.name.contains("..")
+        {
+            results.push(skip("Invalid worktree name".to_string()));
+            continue;
+        }
+        if root.join(&config.worktrees_dir).join(&wt.name).exists() {
+            results.push(skip("Worktree already exists".to_string()));
+            continue;
+        }
+
+        // 项目名来自外部清单，只接受单级目录名，避免拼接出 projects/ 之外的路径
+        let invalid_project = wt.projects.iter().find(|proj| {
+            let mut components = Path::new(&proj.name).components();
+            !matches!(
+                (components.next(), components.next()),
+                (Some(Component::Normal(_)), None)
+            )
+        });
+        if let Some(proj) = invalid_project {
+            results.push(skip(format!("Invalid project name: {}", proj.name)));
+            continue;
+        }
+
+        let mut projects = vec![];
+        let mut missing = vec![];
+        for proj in &wt.projects {
+            let main_proj_path = root.join("projects").join(&proj.name);
+            if !main_proj_path.exists() {
+                missing.push(proj.name.clone());
+                continue;
+            }
+            let remote = project_remote(&config, &proj.name);
+            prepare_branch_from_remote(&main_proj_path, &remote, &wt.name, &proj.branch);
+            projects.push(CreateProjectRequest {
+                name: proj.name.clone(),
+                base_branch: proj.base_branch.clone(),
+                sparse_paths: vec![],
+                remote: None,
+            });
+        }
+
+        if projects.is_empty() {
+            results.push(skip("None of its projects exist in this workspace".to_string()));
+            continue;
+        }
+
+        let request = CreateWorktreeRequest {
+            name: wt.name.clone(),
+            projects,
+        };
+        let result = match create_worktree_impl(window_label, request) {
+            Ok(_) => ManifestImportResult {
+                worktree_name: wt.name.clone(),
+                created: true,
+                reason: (!missing.is_empty())
+                    .then(|| format!("Missing projects: {}", missing.join(", "))),
+            },
+            Err(e) => {
+                log::error!("[worktree] Failed to import worktree '{}': {}", wt.name, e);
+                skip(e)
+            }
+        };
+        results.push(result);
+    }
+
+    log::info!(
+        "[worktree] Manifest import done: {} created, {} skipped/failed",
+        results.iter().filter(|r| r.created).count(),
+        results.iter().filter(|r| !r.created).count()
+    );
+    Ok(results)
+}
+
+#[tauri::command]
+pub(crate) async fn import_workspace_manifest(
+    window: tauri::Window,
+    manifest: WorkspaceManifest,
+) -> Result<Vec<ManifestImportResult>, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || import_workspace_manifest_impl(&label, manifest))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r)
+}
+
+// ==================== 导出 Worktree Bundle ====================
+
+/// Run a git command in `path` and return its trimmed stdout, or the stderr on failure.
+fn git_stdout(path: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(args)
+        .logged_output()
+        .map_err(|e| format!("Failed to run git {}: {}", args.first().unwrap_or(&""), e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Bundle the commits of one project's branch that are not on its base branch.
+fn bundle_project(
+    proj_path: &Path,
+    name: &str,
+    base_branch: &str,
+    remote: &str,
+    output_dir: &Path,
+) -> BundleProject {
+    let mut project = BundleProject {
+        name: name.to_string(),
+        branch: String::new(),
+        base_branch: base_branch.to_string(),
+        base_commit: None,
+        head_commit: String::new(),
+        commits: 0,
+        bundle: None,
+        has_uncommitted: false,
+        error: None,
+    };
+
+    let Some(branch) = current_branch_name(proj_path) else {
+        project.error = Some("HEAD 未指向分支（detached HEAD），无法导出".to_string());
+        return project;
+    };
+    project.branch = branch;
+    match git_stdout(proj_path, &["rev-parse", "HEAD"]) {
+        Ok(head) => project.head_commit = head,
+        Err(e) => {
+            project.error = Some(e);
+            return project;
+        }
+    }
+    project.has_uncommitted = git_stdout(proj_path, &["status", "--porcelain"])
+        .map(|s| !s.is_empty())
+        .unwrap_or(false);
+
+    // Same fallback order as build_diff_archive: <remote>/<base>, then local <base>
+    let candidates = [
+        format!("{}/{}", remote, base_branch),
+        base_branch.to_string(),
+    ];
+    project.base_commit = candidates
+        .iter()
+        .find_map(|c| git_stdout(proj_path, &["merge-base", "HEAD", c]).ok());
+
+    let range = match &project.base_commit {
+        Some(base) => format!("{}..{}", base, project.branch),
+        None => project.branch.clone(),
+    };
+    project.commits = match git_stdout(proj_path, &["rev-list", "--count", &range]) {
+        Ok(count) => count.parse().unwrap_or(0),
+        Err(e) => {
+            project.error = Some(e);
+            return project;
+        }
+    };
+    // git refuses to write an empty bundle
+    if project.commits == 0 {
+        return project;
+    }
+
+    let file_name = format!("{}.bundle", name);
+    let bundle_path = output_dir.join(&file_name);
+    match git_stdout(
+        proj_path,
+        &["bundle", "create", &bundle_path.to_string_lossy(), &range],
+    ) {
+        Ok(_) => project.bundle = Some(file_name),
+        Err(e) => project.error = Some(e),
+    }
+    project
+}
+
+/// Write one `git bundle` per project (branch versus base) plus a `manifest.json` into
+/// `output_path`, giving an offline snapshot of the worktree's committed work.
+pub fn export_worktree_bundle_impl(
+    window_label: &str,
+    name: String,
+    output_path: String,
+) -> Result<BundleExportResult, String> {
+    let _guard = begin_git_operation("export_worktree_bundle")?;
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let projects_path = PathBuf::from(&workspace_path)
+        .join(&config.worktrees_dir)
+        .join(&name)
+        .join("projects");
+    if !projects_path.exists() {
+        return Err("Worktree does not exist".to_string());
+    }
+
+    // Never mix bundles from different exports in one directory
+    let output_dir = PathBuf::from(&output_path);
+    if output_dir.exists() {
+        let has_entries = fs::read_dir(&output_dir)
+            .map_err(|e| format!("Failed to read {}: {}", output_path, e))?
+            .next()
+            .is_some();
+        if has_entries {
+            return Err(format!("输出目录不为空: {}", output_path));
+        }
+    }
+    fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create {}: {}", output_path, e))?;
+
+    log::info!(
+        "[worktree] Exporting bundles for worktree '{}' to {}",
+        name,
+        output_path
+    );
+
+    let mut entries: Vec<PathBuf> = fs::read_dir(&projects_path)
+        .map_err(|e| format!("Failed to read projects directory: {}", e))?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect();
+    entries.sort();
+
+    let projects = entries
+        .iter()
+        .map(|proj_path| {
+            let proj_name = proj_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("")
+                .to_string();
+            let base_branch = config
+                .projects
+                .iter()
+                .find(|p| p.name == proj_name)
+                .map(|p| p.base_branch.clone())
+                .unwrap_or_else(|| "uat".to_string());
+            let remote = project_remote(&config, &proj_name);
+            bundle_project(proj_path, &proj_name, &base_branch, &remote, &output_dir)
+        })
+        .collect::<Vec<_>>();
+
+    let manifest = WorktreeBundleManifest {
+        version: MANIFEST_VERSION,
+        workspace_name: config.name,
+        worktree_name: name,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        projects,
+    };
+    let json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    fs::write(output_dir.join("manifest.json"), json)
+        .map_err(|e| format!("Failed to write manifest: {}", e))?;
+
+    let bundled = manifest.projects.iter().filter(|p| p.bundle.is_some());
+    let failed = manifest.projects.iter().filter(|p| p.error.is_some());
+    log::info!(
+        "[worktree] Exported {} bundles for worktree '{}' ({} projects failed)",
+        bundled.count(),
+        manifest.worktree_name,
+        failed.count()
+    );
+    Ok(BundleExportResult {
+        output_path,
+        manifest,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn export_worktree_bundle(
+    window: tauri::Window,
+    name: String,
+    output_path: String,
+) -> Result<BundleExportResult, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || export_worktree_bundle_impl(&label, name, output_path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Bring a project's branch from a bundle into the main repo as the local branch
+/// `worktree_name`, so create_worktree checks it out instead of branching from base.
+/// Returns why the project has to be skipped.
+fn prepare_branch_from_bundle(
+    main_proj_path: &Path,
+    bundle_dir: &Path,
+    worktree_name: &str,
+    project: &BundleProject,
+) -> Option<String> {
+    if let Some(e) = &project.error {
+        return Some(format!("导出时失败: {}", e));
+    }
+    let has_local = git_stdout(main_proj_path, &["branch", "--list", worktree_name])
+        .map(|s| !s.is_empty())
+        .unwrap_or(false);
+    if has_local {
+        return Some(format!("本地已存在分支 {}", worktree_name));
+    }
+
+    let target = format!("refs/heads/{}", worktree_name);
+    match &project.bundle {
+        Some(file_name) => {
+            let bundle_path = bundle_dir.join(file_name);
+            let bundle = bundle_path.to_string_lossy();
+            // Fails with a clear message when the base commit is missing here
+            if let Err(e) = git_stdout(main_proj_path, &["bundle", "verify", &bundle]) {
+                return Some(e);
+            }
+            let refspec = format!("refs/heads/{}:{}", project.branch, target);
+            git_stdout(main_proj_path, &["fetch", &bundle, &refspec]).err()
+        }
+        // Nothing was committed on top of base: start the branch where the sender's did
+        None => {
+            let start = project.base_commit.as_deref()?;
+            git_stdout(main_proj_path, &["branch", worktree_name, start])
+                .err()
+                .map(|e| format!("找不到基础提交 {}: {}", start, e))
+        }
+    }
+}
+
+/// Create a worktree from an export_worktree_bundle directory: each project's branch is
+/// fetched from its bundle into the main repo, then the worktree is created as usual
+/// (including symlinks). Projects that cannot be imported are reported and left out.
+pub fn import_worktree_impl(
+    window_label: &str,
+    manifest_path: String,
+) -> Result<BundleImportResult, String> {
+    let _guard = begin_git_operation("import_worktree")?;
+    let manifest_file = PathBuf::from(&manifest_path);
+    let content = fs::read_to_string(&manifest_file)
+        .map_err(|e| format!("Failed to read {}: {}", manifest_path, e))?;
+    let manifest: WorktreeBundleManifest =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid bundle manifest: {}", e))?;
+    if manifest.version > MANIFEST_VERSION {
+        return Err(format!(
+            "Unsupported manifest version {} (expected <= {})",
+            manifest.version, MANIFEST_VERSION
+        ));
+    }
+    validate_worktree_name(&manifest.worktree_name)?;
+
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let root = PathBuf::from(&workspace_path);
+    let name = &manifest.worktree_name;
+    if root.join(&config.worktrees_dir).join(name).exists() {
+        return Err(format!("Worktree '{}' already exists", name));
+    }
+    let bundle_dir = manifest_file.parent().unwrap_or(Path::new("."));
 
-    // Step 2: Delete associated local branches for each project
-    log::info!("[worktree] Step 2/3: Deleting local branch '{}' from projects", branch_name);
-    let projects_path = root.join("projects");
-    if projects_path.exists() {
-        if let Ok(entries) = std::fs::read_dir(&projects_path) {
-            for entry in entries.flatten() {
-                let proj_path = entry.path();
-                if !proj_path.is_dir() {
-                    continue;
-                }
+    log::info!(
+        "[worktree] Importing worktree '{}' from {} (exported from '{}')",
+        manifest.worktree_name,
+        manifest_path,
+        manifest.workspace_name
+    );
 
-                // Try to delete the branch (it may not exist in all projects)
-                let output = Command::new("git")
-                    .args([
-                        "-C",
-                        proj_path.to_str().unwrap(),
-                        "branch",
-                        "-D",
-                        branch_name,
-                    ])
-                    .output();
+    let mut projects = vec![];
+    let mut requests = vec![];
+    for proj in &manifest.projects {
+        let main_proj_path = root.join("projects").join(&proj.name);
+        let skipped = if main_proj_path.exists() {
+            let _repo_slot = enter_repo_queue(&main_proj_path, |ahead| {
+                notify_repo_queue(window_label, &manifest.worktree_name, &proj.name, ahead)
+            });
+            prepare_branch_from_bundle(&main_proj_path, bundle_dir, &manifest.worktree_name, proj)
+        } else {
+            Some("项目不存在于当前 workspace".to_string())
+        };
 
-                match output {
-                    Ok(o) if o.status.success() => {
-                        let proj_name =
-                            proj_path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-                        log::info!(
-                            "Deleted branch '{}' from project '{}'",
-                            branch_name,
-                            proj_name
-                        );
-                    }
-                    _ => {} // Branch might not exist in this project, that's fine
-                }
-            }
+        match &skipped {
+            Some(reason) => log::warn!(
+                "[worktree] Import: skipping project '{}': {}",
+                proj.name,
+                reason
+            ),
+            None => requests.push(CreateProjectRequest {
+                name: proj.name.clone(),
+                base_branch: proj.base_branch.clone(),
+                sparse_paths: vec![],
+                remote: None,
+            }),
         }
+        projects.push(BundleImportProject {
+            name: proj.name.clone(),
+            commits: if skipped.is_none() { proj.commits } else { 0 },
+            skipped,
+        });
     }
 
-    // Step 3: Remove the directory
-    log::info!("[worktree] Step 3/3: Removing directory {}", archive_path.display());
-    fs::remove_dir_all(&archive_path)
-        .map_err(|e| format!("Failed to delete archived worktree: {}", e))?;
+    if requests.is_empty() {
+        return Err("没有可导入的项目".to_string());
+    }
 
-    log::info!("[worktree] Successfully deleted archived worktree '{}'", name);
-    Ok(())
+    let created = create_worktree_impl(
+        window_label,
+        CreateWorktreeRequest {
+            name: manifest.worktree_name.clone(),
+            projects: requests,
+        },
+    )?;
+
+    log::info!(
+        "[worktree] Imported worktree '{}' ({}/{} projects)",
+        manifest.worktree_name,
+        projects.iter().filter(|p| p.skipped.is_none()).count(),
+        projects.len()
+    );
+    Ok(BundleImportResult {
+        worktree_name: manifest.worktree_name,
+        path: created.path,
+        projects,
+        link_results: created.link_results,
+    })
 }
 
 #[tauri::command]
-pub(crate) fn delete_archived_worktree(window: tauri::Window, name: String) -> Result<(), String> {
-    delete_archived_worktree_impl(window.label(), name)
+pub(crate) async fn import_worktree(
+    window: tauri::Window,
+    manifest_path: String,
+) -> Result<BundleImportResult, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || import_worktree_impl(&label, manifest_path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
 }
 
 // ==================== 向已有 Worktree 添加项目 ====================
@@ -876,6 +3195,7 @@ pub fn add_project_to_worktree_impl(
     window_label: &str,
     request: AddProjectToWorktreeRequest,
 ) -> Result<(), String> {
+    let _guard = begin_git_operation("add_project_to_worktree")?;
     let (workspace_path, config) =
         get_window_workspace_config(window_label).ok_or("No workspace selected")?;
 
@@ -925,6 +3245,16 @@ pub fn add_project_to_worktree_impl(
             test_branch: "test".to_string(),
             merge_strategy: "merge".to_string(),
             linked_folders: vec![],
+            copy_rules: vec![],
+            status_excludes: vec![],
+            skip_lfs: false,
+            sparse_paths: vec![],
+            remote: default_remote(),
+            quick_commands: vec![],
+            tasks: Default::default(),
+            terminal_presets: vec![],
+            shell: None,
+            env: Default::default(),
         });
 
     log::info!(
@@ -932,12 +3262,18 @@ pub fn add_project_to_worktree_impl(
         request.project_name, request.worktree_name, request.base_branch
     );
 
-    // Step 1: Fetch origin first
+    let _repo_slot = enter_repo_queue(&main_proj_path, |ahead| {
+        notify_repo_queue(window_label, &request.worktree_name, &request.project_name, ahead)
+    });
+
+    // Step 1: Fetch the configured remote first
+    let remote = proj_config.remote.clone();
     log::info!(
-        "[worktree] Step 1/3: git fetch origin for project '{}'",
+        "[worktree] Step 1/3: git fetch {} for project '{}'",
+        remote,
         request.project_name
     );
-    run_git_command_with_timeout(&["fetch", "origin"], main_proj_path.to_str().unwrap())?;
+    run_git_command_with_timeout(&["fetch", &remote], main_proj_path.to_str().unwrap())?;
 
     // Check if branch already exists
     let branch_check = Command::new("git")
@@ -948,7 +3284,7 @@ pub fn add_project_to_worktree_impl(
             "--list",
             &request.worktree_name,
         ])
-        .output();
+        .logged_output();
 
     let branch_exists = branch_check
         .as_ref()
@@ -975,13 +3311,15 @@ pub fn add_project_to_worktree_impl(
                 wt_proj_path.to_str().unwrap(),
                 &request.worktree_name,
             ])
-            .output()
+            .env("GIT_LFS_SKIP_SMUDGE", "1")
+            .logged_output()
             .map_err(|e| format!("Failed to create worktree: {}", e))?
     } else {
         log::info!(
-            "[worktree] Creating new branch '{}' for project '{}' from origin/{}",
+            "[worktree] Creating new branch '{}' for project '{}' from {}/{}",
             request.worktree_name,
             request.project_name,
+            remote,
             request.base_branch
         );
         Command::new("git")
@@ -993,77 +3331,692 @@ pub fn add_project_to_worktree_impl(
                 wt_proj_path.to_str().unwrap(),
                 "-b",
                 &request.worktree_name,
-                &format!("origin/{}", request.base_branch),
+                &format!("{}/{}", remote, request.base_branch),
             ])
-            .output()
+            .env("GIT_LFS_SKIP_SMUDGE", "1")
+            .logged_output()
             .map_err(|e| format!("Failed to create worktree: {}", e))?
     };
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        log::error!(
-            "[worktree] FAILED: git worktree add for project '{}': {}",
-            request.project_name, stderr
-        );
-        return Err(format!(
-            "Failed to add project {} to worktree: {}",
-            request.project_name, stderr
-        ));
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        log::error!(
+            "[worktree] FAILED: git worktree add for project '{}': {}",
+            request.project_name, stderr
+        );
+        return Err(format!(
+            "Failed to add project {} to worktree: {}",
+            request.project_name, stderr
+        ));
+    }
+    log::info!(
+        "[worktree] Project '{}': git worktree add succeeded",
+        request.project_name
+    );
+    apply_sparse_checkout(&wt_proj_path, &proj_config, &[]);
+    apply_branch_strategy(&wt_proj_path, &request.worktree_name, &proj_config);
+    sync_lfs_objects(&wt_proj_path, &proj_config);
+
+    // Step 3: Link configured folders
+    log::info!(
+        "[worktree] Step 3/3: Creating symlinks for {} linked folders",
+        proj_config.linked_folders.len()
+    );
+    for folder_name in &proj_config.linked_folders {
+        let main_folder = main_proj_path.join(folder_name);
+        let wt_folder = wt_proj_path.join(folder_name);
+
+        let link = link_item(
+            &main_folder,
+            &wt_folder,
+            Some(&request.project_name),
+            folder_name,
+            config.existing_dir_policy,
+        );
+        if link.status == LinkStatus::Created {
+            untrack_linked_folder(&wt_proj_path, folder_name);
+        }
+    }
+    for copy in apply_copy_rules(
+        &main_proj_path,
+        &wt_proj_path,
+        &request.project_name,
+        &proj_config.copy_rules,
+    ) {
+        if copy.status == LinkStatus::Failed {
+            log::warn!(
+                "[worktree] Project '{}': failed to copy {}: {:?}",
+                request.project_name,
+                copy.item,
+                copy.reason
+            );
+        }
+    }
+
+    log::info!(
+        "Successfully added project '{}' to worktree '{}'",
+        request.project_name,
+        request.worktree_name
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn add_project_to_worktree(
+    window: tauri::Window,
+    request: AddProjectToWorktreeRequest,
+) -> Result<(), String> {
+    add_project_to_worktree_impl(window.label(), request)
+}
+
+// ==================== 同步建议 ====================
+
+/// 对 worktree 中每个项目：fetch 基础分支后在内存中预演合并，
+/// 分类为 up_to_date / safe_to_sync / conflicts_expected（附冲突文件）/ blocked。
+pub fn get_sync_advice_impl(
+    window_label: &str,
+    worktree_name: String,
+) -> Result<WorktreeSyncAdvice, String> {
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let projects_path = PathBuf::from(&workspace_path)
+        .join(&config.worktrees_dir)
+        .join(&worktree_name)
+        .join("projects");
+    if !projects_path.is_dir() {
+        return Err(format!("Worktree '{}' 不存在", worktree_name));
+    }
+
+    log::info!("[worktree] Computing sync advice for '{}'", worktree_name);
+    let mut entries: Vec<_> = fs::read_dir(&projects_path)
+        .map_err(|e| format!("Failed to read directory: {}", e))?
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .collect();
+    entries.sort_by_key(|e| e.file_name());
+
+    let mut projects = vec![];
+    for entry in entries {
+        let project_name = entry.file_name().to_string_lossy().to_string();
+        let Some(proj_config) = config.projects.iter().find(|p| p.name == project_name) else {
+            continue;
+        };
+        let proj_path = entry.path();
+
+        if let Err(e) = run_git_command_with_timeout(
+            &["fetch", &proj_config.remote, &proj_config.base_branch],
+            proj_path.to_str().unwrap(),
+        ) {
+            log::warn!(
+                "[worktree] Project '{}': fetch failed, using cached refs: {}",
+                project_name, e
+            );
+        }
+
+        let preview = crate::git_ops::preview_sync_with_base_branch(
+            &proj_path,
+            &proj_config.remote,
+            &proj_config.base_branch,
+        );
+        log::info!(
+            "[worktree] Project '{}': {:?} (behind {})",
+            project_name, preview.advice, preview.behind
+        );
+        projects.push(ProjectSyncAdvice {
+            project_name,
+            base_branch: proj_config.base_branch.clone(),
+            preview,
+        });
+    }
+
+    Ok(WorktreeSyncAdvice {
+        worktree_name,
+        projects,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn get_sync_advice(
+    window: tauri::Window,
+    worktree_name: String,
+) -> Result<WorktreeSyncAdvice, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || get_sync_advice_impl(&label, worktree_name))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// 重新计算同步建议，只同步 safe_to_sync 的项目，其余项目原样保留
+pub fn sync_safe_projects_impl(
+    window_label: &str,
+    worktree_name: String,
+) -> Result<Vec<ProjectSyncResult>, String> {
+    let _guard = begin_git_operation("sync_safe_projects")?;
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let advice = get_sync_advice_impl(window_label, worktree_name.clone())?;
+    let projects_path = PathBuf::from(&workspace_path)
+        .join(&config.worktrees_dir)
+        .join(&worktree_name)
+        .join("projects");
+
+    let mut results = vec![];
+    for project in advice
+        .projects
+        .into_iter()
+        .filter(|p| p.preview.advice == SyncAdvice::SafeToSync)
+    {
+        let proj_path = projects_path.join(&project.project_name);
+        let strategy = config
+            .projects
+            .iter()
+            .find(|p| p.name == project.project_name)
+            .map(|p| MergeStrategy::from_config(&p.merge_strategy))
+            .unwrap_or(MergeStrategy::Merge);
+        let remote = project_remote(&config, &project.project_name);
+        let result = crate::git_ops::sync_with_base_branch(
+            &proj_path,
+            &remote,
+            &project.base_branch,
+            strategy,
+        );
+        results.push(ProjectSyncResult {
+            project_name: project.project_name,
+            success: result.is_ok(),
+            message: result.unwrap_or_else(|e| e),
+        });
+    }
+
+    log::info!(
+        "[worktree] Synced {} safe projects in '{}'",
+        results.iter().filter(|r| r.success).count(),
+        worktree_name
+    );
+    Ok(results)
+}
+
+#[tauri::command]
+pub(crate) async fn sync_safe_projects(
+    window: tauri::Window,
+    worktree_name: String,
+) -> Result<Vec<ProjectSyncResult>, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || sync_safe_projects_impl(&label, worktree_name))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+// ==================== 批量 Fetch ====================
+
+/// 同时进行的 git fetch 数量上限
+const FETCH_PARALLELISM: usize = 4;
+
+/// 并发 fetch 多个主仓库：(项目名, remote)，结果顺序与输入一致。
+/// 每个仓库仍先进入主仓库队列，不会与同仓库的创建操作并发；on_wait 收到 (项目名, 前方操作数)。
+/// 工作线程的 git 命令记入 `recorder` 对应的操作日志。
+fn fetch_projects_parallel(
+    root: &Path,
+    projects: &[(String, String)],
+    recorder: Option<&OperationRecorder>,
+    on_wait: impl Fn(&str, usize) + Sync,
+) -> Vec<Result<std::process::Output, String>> {
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    let results: std::sync::Mutex<Vec<Option<Result<std::process::Output, String>>>> =
+        std::sync::Mutex::new(projects.iter().map(|_| None).collect());
+
+    std::thread::scope(|scope| {
+        for _ in 0..FETCH_PARALLELISM.min(projects.len()) {
+            scope.spawn(|| {
+                let _attached = recorder.map(|r| r.attach());
+                loop {
+                    let index = next.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    let Some((name, remote)) = projects.get(index) else {
+                        break;
+                    };
+                    let main_proj_path = root.join("projects").join(name);
+                    let result = {
+                        let _repo_slot =
+                            enter_repo_queue(&main_proj_path, |ahead| on_wait(name, ahead));
+                        log::info!("[worktree] Project '{}': git fetch {}", name, remote);
+                        run_git_command_with_timeout(
+                            &["fetch", remote],
+                            &main_proj_path.to_string_lossy(),
+                        )
+                    };
+                    results.lock().unwrap()[index] = Some(result);
+                }
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|r| r.unwrap_or_else(|| Err("Fetch was not run".to_string())))
+        .collect()
+}
+
+/// 并发 fetch 主工作区中的所有项目，返回每个项目的结果
+pub fn fetch_all_projects_impl(window_label: &str) -> Result<Vec<ProjectSyncResult>, String> {
+    let _guard = begin_git_operation("fetch_all_projects")?;
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let root = PathBuf::from(&workspace_path);
+
+    let projects: Vec<(String, String)> = config
+        .projects
+        .iter()
+        .filter(|p| root.join("projects").join(&p.name).join(".git").exists())
+        .map(|p| (p.name.clone(), p.remote.clone()))
+        .collect();
+    log::info!(
+        "[worktree] Fetching {} projects in '{}' (parallelism {})",
+        projects.len(),
+        workspace_path,
+        FETCH_PARALLELISM
+    );
+
+    let recorder = OperationRecorder::current();
+    let results = fetch_projects_parallel(&root, &projects, recorder.as_ref(), |_, _| {});
+    Ok(projects
+        .into_iter()
+        .zip(results)
+        .map(|((name, remote), result)| {
+            let result = result.and_then(|output| {
+                if output.status.success() {
+                    Ok(format!("Fetched {}", remote))
+                } else {
+                    Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+                }
+            });
+            ProjectSyncResult {
+                project_name: name,
+                success: result.is_ok(),
+                message: result.unwrap_or_else(|e| e),
+            }
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub(crate) async fn fetch_all_projects(
+    window: tauri::Window,
+) -> Result<Vec<ProjectSyncResult>, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || fetch_all_projects_impl(&label))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+// ==================== 真实目录转换为链接 ====================
+
+/// 名称只能是单个普通路径段（不含分隔符、`.`、`..`），拼接到目录下时不会越出该目录
+fn is_single_path_component(name: &str) -> bool {
+    let mut components = std::path::Path::new(name).components();
+    matches!(
+        (components.next(), components.next()),
+        (Some(std::path::Component::Normal(_)), None)
+    )
+}
+
+/// 将 worktree 中的真实目录（如先执行过 install 生成的 node_modules）安全替换为指向主工作区的软链接。
+/// `project_name` 为空时处理工作区级共享项；`merge` 为 true 时先把主项目缺少的内容合并过去。
+pub fn convert_to_link_impl(
+    window_label: &str,
+    worktree_name: String,
+    project_name: Option<String>,
+    item: String,
+    merge: bool,
+) -> Result<LinkResult, String> {
+    let _guard = begin_git_operation("convert_to_link")?;
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+
+    let item_path = std::path::Path::new(&item);
+    if item.is_empty()
+        || item_path.is_absolute()
+        || item_path
+            .components()
+            .any(|c| !matches!(c, std::path::Component::Normal(_)))
+    {
+        return Err(format!("无效的链接路径: {}", item));
+    }
+    if !is_single_path_component(&worktree_name) {
+        return Err(format!("无效的 worktree 名称: {}", worktree_name));
+    }
+    if let Some(proj) = &project_name {
+        if !is_single_path_component(proj) {
+            return Err(format!("无效的项目名称: {}", proj));
+        }
+    }
+
+    let root = PathBuf::from(&workspace_path);
+    let worktree_path = root.join(&config.worktrees_dir).join(&worktree_name);
+    if !worktree_path.exists() {
+        return Err(format!("Worktree '{}' 不存在", worktree_name));
+    }
+
+    let (src, dst) = match &project_name {
+        Some(proj) => (
+            root.join("projects").join(proj).join(&item),
+            worktree_path.join("projects").join(proj).join(&item),
+        ),
+        None => (root.join(&item), worktree_path.join(&item)),
+    };
+
+    if dst.is_symlink() {
+        return Err(format!("{} 已经是软链接", item));
+    }
+    if !dst.is_dir() {
+        return Err(format!("{} 不是目录，无法转换", dst.display()));
+    }
+    if !src.is_dir() {
+        return Err(format!("主工作区中不存在目录 {}", src.display()));
+    }
+
+    log::info!(
+        "[worktree] Converting '{}' in worktree '{}' (project: {:?}) to link, merge={}",
+        item, worktree_name, project_name, merge
+    );
+    swap_dir_for_link(&src, &dst, merge)?;
+
+    if let Some(proj) = &project_name {
+        untrack_linked_folder(&worktree_path.join("projects").join(proj), &item);
+    }
+
+    Ok(LinkResult {
+        project: project_name,
+        item,
+        status: LinkStatus::Created,
+        reason: None,
+    })
+}
+
+#[tauri::command]
+pub(crate) fn convert_to_link(
+    window: tauri::Window,
+    worktree_name: String,
+    project_name: Option<String>,
+    item: String,
+    merge: bool,
+) -> Result<LinkResult, String> {
+    convert_to_link_impl(window.label(), worktree_name, project_name, item, merge)
+}
+
+/// 对所有未归档的 worktree 重新应用 linked_workspace_items / linked_folders，
+/// 补建主工作区中后来才出现的目录（例如安装依赖后生成的 .pnpm-store）。
+/// 已是软链接或主工作区中不存在的项直接跳过，不计入结果。
+/// 同时按各项目的 copy_rules 复制文件，是否覆盖由规则的 overwrite 决定。
+pub fn sync_links_impl(window_label: &str) -> Result<Vec<SyncLinksResult>, String> {
+    let _guard = begin_git_operation("sync_links")?;
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+
+    let root = PathBuf::from(&workspace_path);
+    let worktrees_root = root.join(&config.worktrees_dir);
+    let entries = match std::fs::read_dir(&worktrees_root) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(vec![]),
+    };
+
+    let mut results = vec![];
+    for entry in entries.flatten() {
+        let worktree_path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !worktree_path.is_dir() || name.starts_with('.') || name.ends_with(".archive") {
+            continue;
+        }
+        let wt_projects_path = worktree_path.join("projects");
+        if !wt_projects_path.is_dir() {
+            continue;
+        }
+
+        let mut link_results: Vec<LinkResult> = vec![];
+        for item in &config.linked_workspace_items {
+            let src = root.join(item);
+            let dst = worktree_path.join(item);
+            if !src.exists() || dst.is_symlink() {
+                continue;
+            }
+            link_results.push(link_item(&src, &dst, None, item, config.existing_dir_policy));
+        }
+
+        for proj in &config.projects {
+            let main_proj_path = root.join("projects").join(&proj.name);
+            let wt_proj_path = wt_projects_path.join(&proj.name);
+            if !wt_proj_path.is_dir() {
+                continue;
+            }
+            for folder_name in &proj.linked_folders {
+                let src = main_proj_path.join(folder_name);
+                let dst = wt_proj_path.join(folder_name);
+                if !src.exists() || dst.is_symlink() {
+                    continue;
+                }
+                let link = link_item(
+                    &src,
+                    &dst,
+                    Some(&proj.name),
+                    folder_name,
+                    config.existing_dir_policy,
+                );
+                if link.status == LinkStatus::Created {
+                    untrack_linked_folder(&wt_proj_path, folder_name);
+                }
+                link_results.push(link);
+            }
+            // 按覆盖策略保留的文件不计入结果
+            link_results.extend(
+                apply_copy_rules(&main_proj_path, &wt_proj_path, &proj.name, &proj.copy_rules)
+                    .into_iter()
+                    .filter(|r| r.status != LinkStatus::Skipped),
+            );
+        }
+
+        if !link_results.is_empty() {
+            log::info!(
+                "[worktree] sync_links: worktree '{}' -> {} link(s) processed",
+                name,
+                link_results.len()
+            );
+            results.push(SyncLinksResult {
+                worktree: name,
+                link_results,
+            });
+        }
+    }
+
+    results.sort_by(|a, b| a.worktree.cmp(&b.worktree));
+    Ok(results)
+}
+
+#[tauri::command]
+pub(crate) async fn sync_links(window: tauri::Window) -> Result<Vec<SyncLinksResult>, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || sync_links_impl(&label))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r)
+}
+
+// ==================== 修复 worktree 登记 ====================
+
+fn is_registered_worktree(registered: &[PathBuf], path: &std::path::Path) -> bool {
+    let target = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    registered
+        .iter()
+        .any(|p| p.canonicalize().unwrap_or_else(|_| p.clone()) == target)
+}
+
+/// 让主仓库重新登记 `path`：.git 文件还在时先试 `git worktree repair`；
+/// 否则把目录挪开，用 `--no-checkout` 登记出新的 .git 文件后换回原目录，
+/// 再 mixed reset 重建索引，工作区中的文件（含未提交修改）保持不动。
+fn reregister_worktree(
+    main_repo: &std::path::Path,
+    path: &std::path::Path,
+    branch: &str,
+) -> Result<(), String> {
+    let dot_git = path.join(".git");
+    if dot_git.is_dir() {
+        return Err("目录是独立的 git 仓库，不是 worktree".to_string());
+    }
+    if dot_git.is_file() && git_ops::repair_worktree(main_repo, path).is_ok() {
+        return Ok(());
     }
-    log::info!(
-        "[worktree] Project '{}': git worktree add succeeded",
-        request.project_name
-    );
 
-    // Step 3: Link configured folders
-    log::info!(
-        "[worktree] Step 3/3: Creating symlinks for {} linked folders",
-        proj_config.linked_folders.len()
-    );
-    for folder_name in &proj_config.linked_folders {
-        let main_folder = main_proj_path.join(folder_name);
-        let wt_folder = wt_proj_path.join(folder_name);
+    let main_str = main_repo.to_string_lossy().to_string();
+    let path_str = path.to_string_lossy().to_string();
+    let branch_ref = format!("refs/heads/{}", branch);
+    let branch_exists = run_git_command_with_timeout(
+        &["rev-parse", "--verify", "--quiet", &branch_ref],
+        &main_str,
+    )
+    .map(|o| o.status.success())
+    .unwrap_or(false);
+    if !branch_exists {
+        return Err(format!("本地分支 {} 不存在，无法重新登记", branch));
+    }
 
-        if main_folder.exists() && !wt_folder.exists() {
-            create_symlink(&main_folder, &wt_folder).ok();
+    let dir_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("project");
+    let parked = path.with_file_name(format!("{}.repairing", dir_name));
+    fs::rename(path, &parked).map_err(|e| format!("Failed to move directory aside: {}", e))?;
 
-            // Remove from git index if it's tracked
-            Command::new("git")
-                .args([
-                    "-C",
-                    wt_proj_path.to_str().unwrap(),
-                    "rm",
-                    "--cached",
-                    "-r",
-                    folder_name,
-                ])
-                .output()
-                .ok();
+    let add = run_git_command_with_timeout(
+        &["worktree", "add", "--no-checkout", &path_str, branch],
+        &main_str,
+    );
+    match add {
+        Ok(o) if o.status.success() => {}
+        other => {
+            let _ = fs::rename(&parked, path);
+            return Err(match other {
+                Ok(o) => String::from_utf8_lossy(&o.stderr).trim().to_string(),
+                Err(e) => e,
+            });
         }
     }
 
-    log::info!(
-        "Successfully added project '{}' to worktree '{}'",
-        request.project_name,
-        request.worktree_name
-    );
+    let _ = fs::remove_file(parked.join(".git"));
+    fs::rename(&dot_git, parked.join(".git"))
+        .and_then(|_| fs::remove_dir(path))
+        .and_then(|_| fs::rename(&parked, path))
+        .map_err(|e| format!("Failed to restore directory after re-registering: {}", e))?;
+
+    let reset = run_git_command_with_timeout(&["reset", "-q"], &path_str)?;
+    if !reset.status.success() {
+        return Err(format!(
+            "已重新登记，但重建索引失败: {}",
+            String::from_utf8_lossy(&reset.stderr).trim()
+        ));
+    }
     Ok(())
 }
 
+/// 对每个主仓库执行 `git worktree prune`，再检查 worktrees/ 下未归档的项目目录：
+/// 未被主仓库登记的尝试重新登记（分支取 worktree 名称），无法处理的列入 unresolved。
+/// 没有任何变化的项目不计入结果。
+pub fn repair_worktrees_impl(window_label: &str) -> Result<Vec<RepairWorktreesResult>, String> {
+    let _guard = begin_git_operation("repair_worktrees")?;
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+
+    let root = PathBuf::from(&workspace_path);
+    let worktrees_root = root.join(&config.worktrees_dir);
+    let mut worktree_names: Vec<String> = std::fs::read_dir(&worktrees_root)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().is_dir())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|n| !n.starts_with('.') && !n.ends_with(".archive"))
+                .collect()
+        })
+        .unwrap_or_default();
+    worktree_names.sort();
+
+    let mut results = vec![];
+    for proj in &config.projects {
+        let main_proj_path = root.join("projects").join(&proj.name);
+        if !main_proj_path.is_dir() {
+            continue;
+        }
+        let mut result = RepairWorktreesResult {
+            project_name: proj.name.clone(),
+            pruned: vec![],
+            reregistered: vec![],
+            unresolved: vec![],
+            error: None,
+        };
+
+        let registered = match git_ops::prune_worktrees(&main_proj_path)
+            .and_then(|pruned| {
+                result.pruned = pruned;
+                git_ops::registered_worktree_paths(&main_proj_path)
+            }) {
+            Ok(paths) => paths,
+            Err(e) => {
+                log::warn!("[worktree] repair_worktrees: project '{}': {}", proj.name, e);
+                result.error = Some(e);
+                results.push(result);
+                continue;
+            }
+        };
+
+        for wt_name in &worktree_names {
+            let wt_proj_path = worktrees_root.join(wt_name).join("projects").join(&proj.name);
+            if !wt_proj_path.is_dir() || is_registered_worktree(&registered, &wt_proj_path) {
+                continue;
+            }
+            log::info!(
+                "[worktree] repair_worktrees: '{}' is not registered in project '{}'",
+                wt_name,
+                proj.name
+            );
+            match reregister_worktree(&main_proj_path, &wt_proj_path, wt_name) {
+                Ok(()) => result.reregistered.push(wt_name.clone()),
+                Err(reason) => {
+                    log::warn!(
+                        "[worktree] repair_worktrees: could not re-register '{}' in '{}': {}",
+                        wt_name,
+                        proj.name,
+                        reason
+                    );
+                    result.unresolved.push(UnregisteredWorktree {
+                        worktree: wt_name.clone(),
+                        path: to_workspace_relative(&workspace_path, &wt_proj_path),
+                        reason,
+                    });
+                }
+            }
+        }
+
+        if !result.pruned.is_empty() || !result.reregistered.is_empty() || !result.unresolved.is_empty() {
+            results.push(result);
+        }
+    }
+    Ok(results)
+}
+
 #[tauri::command]
-pub(crate) fn add_project_to_worktree(
+pub(crate) async fn repair_worktrees(
     window: tauri::Window,
-    request: AddProjectToWorktreeRequest,
-) -> Result<(), String> {
-    add_project_to_worktree_impl(window.label(), request)
+) -> Result<Vec<RepairWorktreesResult>, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || repair_worktrees_impl(&label))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r)
 }
 
 // ==================== 智能扫描 ====================
 
 #[tauri::command]
 pub(crate) async fn scan_linked_folders(
+    window: tauri::Window,
     project_path: String,
 ) -> Result<Vec<ScannedFolder>, String> {
+    let project_path = resolve_workspace_path(window.label(), &project_path)?;
     scan_linked_folders_sync(&project_path)
 }
 
@@ -1086,12 +4039,423 @@ fn scan_linked_folders_sync(project_path: &str) -> Result<Vec<ScannedFolder>, St
     Ok(results)
 }
 
+// ==================== Base 分支更新检测 ====================
+
+/// 后台线程的检查粒度；各 workspace 按自己的 interval_mins 执行
+const BASE_UPDATE_TICK_SECS: u64 = 60;
+
+/// fetch 各项目的 base 分支（worktree 与主仓库共享 refs，fetch 一次即可），
+/// 返回落后超过阈值的 worktree 项目
+fn scan_base_updates(
+    workspace_path: &str,
+    config: &crate::types::WorkspaceConfig,
+) -> Vec<BaseUpdate> {
+    let root = PathBuf::from(workspace_path);
+    for project in &config.projects {
+        let main_path = root.join("projects").join(&project.name);
+        if !main_path.exists() || project.base_branch.is_empty() {
+            continue;
+        }
+        let main_str = main_path.to_string_lossy().to_string();
+        if let Err(e) =
+            run_git_command_with_timeout(&["fetch", &project.remote, &project.base_branch], &main_str)
+        {
+            log::warn!(
+                "[worktree] Base update fetch failed for {}: {}",
+                project.name,
+                e
+            );
+        }
+    }
+
+    let worktrees_path = root.join(&config.worktrees_dir);
+    let items = if worktrees_path.exists() {
+        scan_worktrees_dir(&worktrees_path, workspace_path, config, false).unwrap_or_default()
+    } else {
+        vec![]
+    };
+
+    let mut updates = vec![];
+    for wt in items {
+        for proj in &wt.projects {
+            let Some(behind) = git_ops::behind_base_count(
+                std::path::Path::new(&proj.path),
+                &project_remote(config, &proj.name),
+                &proj.base_branch,
+            ) else {
+                continue;
+            };
+            if behind > config.base_update.behind_threshold {
+                updates.push(BaseUpdate {
+                    worktree_name: wt.name.clone(),
+                    project_name: proj.name.clone(),
+                    path: proj.rel_path.clone(),
+                    base_branch: proj.base_branch.clone(),
+                    behind,
+                });
+            }
+        }
+    }
+    updates
+}
+
+/// 检查并缓存结果；出现新的落后项时发送 `base-update-available` 事件
+fn refresh_base_updates(workspace_path: &str, config: &crate::types::WorkspaceConfig) {
+    let updates = scan_base_updates(workspace_path, config);
+    let newly_behind: Vec<BaseUpdate> = {
+        let mut cache = BASE_UPDATES.lock().unwrap();
+        let previous = cache.get(workspace_path).cloned().unwrap_or_default();
+        cache.insert(workspace_path.to_string(), updates.clone());
+        updates
+            .iter()
+            .filter(|u| {
+                !previous
+                    .iter()
+                    .any(|p| p.worktree_name == u.worktree_name && p.project_name == u.project_name)
+            })
+            .cloned()
+            .collect()
+    };
+    if newly_behind.is_empty() {
+        return;
+    }
+
+    log::info!(
+        "[worktree] {} worktree project(s) fell behind base in '{}'",
+        newly_behind.len(),
+        workspace_path
+    );
+    if let Some(app) = APP_HANDLE.lock().unwrap().as_ref() {
+        let _ = app.emit(
+            "base-update-available",
+            serde_json::json!({
+                "workspacePath": workspace_path,
+                "updates": updates,
+            }),
+        );
+    }
+}
+
+/// 最近一次检查的结果（不触发 fetch）
+pub fn get_base_updates_impl(window_label: &str) -> Result<Vec<BaseUpdate>, String> {
+    let workspace_path = get_window_workspace_path(window_label).ok_or("No workspace selected")?;
+    Ok(BASE_UPDATES
+        .lock()
+        .unwrap()
+        .get(&workspace_path)
+        .cloned()
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+pub(crate) fn get_base_updates(window: tauri::Window) -> Result<Vec<BaseUpdate>, String> {
+    get_base_updates_impl(window.label())
+}
+
+/// 启动后台线程，按各 workspace 配置的间隔检查 base 分支更新
+pub(crate) fn start_base_update_monitor() {
+    std::thread::spawn(|| {
+        let mut last_checked: HashMap<String, std::time::Instant> = HashMap::new();
+        loop {
+            std::thread::sleep(std::time::Duration::from_secs(BASE_UPDATE_TICK_SECS));
+            let workspaces: std::collections::HashSet<String> =
+                WINDOW_WORKSPACES.lock().unwrap().values().cloned().collect();
+            for workspace_path in workspaces {
+                let config = crate::config::load_workspace_config(&workspace_path);
+                if !config.base_update.enabled {
+                    continue;
+                }
+                let interval = config.base_update.interval_mins.max(1) * 60;
+                if let Some(at) = last_checked.get(&workspace_path) {
+                    if at.elapsed().as_secs() < interval {
+                        continue;
+                    }
+                }
+                last_checked.insert(workspace_path.clone(), std::time::Instant::now());
+                refresh_base_updates(&workspace_path, &config);
+            }
+        }
+    });
+}
+
+// ==================== 磁盘占用 ====================
+
+/// 超出配额后的重复提醒间隔
+const DISK_QUOTA_REWARN_SECS: u64 = 6 * 60 * 60;
+/// 后台检查周期
+const DISK_QUOTA_CHECK_INTERVAL_SECS: u64 = 30 * 60;
+/// 提醒中列出的 worktree 数量
+const DISK_QUOTA_TOP_N: usize = 5;
+
+fn build_disk_usage_report(
+    workspace_path: &str,
+    config: &crate::types::WorkspaceConfig,
+) -> Result<DiskUsageReport, String> {
+    let worktrees_path = PathBuf::from(workspace_path).join(&config.worktrees_dir);
+    let items = if worktrees_path.exists() {
+        scan_worktrees_dir(&worktrees_path, workspace_path, config, true)?
+    } else {
+        vec![]
+    };
+
+    // 软链接（linked_folders）不计入，calculate_dir_size 会跳过
+    let mut sized: Vec<(WorktreeListItem, u64)> = items
+        .into_iter()
+        .map(|wt| {
+            let size = wt
+                .size_bytes
+                .unwrap_or_else(|| calculate_dir_size(std::path::Path::new(&wt.path)));
+            (wt, size)
+        })
+        .collect();
+    sized.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+
+    let total_bytes: u64 = sized.iter().map(|(_, s)| s).sum();
+    let quota_bytes = config.disk_quota_mb.map(|mb| mb * 1024 * 1024);
+    let archive_candidates = sized
+        .iter()
+        .filter(|(wt, _)| !wt.is_archived && wt.projects.iter().all(|p| !p.has_uncommitted))
+        .map(|(wt, _)| wt.name.clone())
+        .take(DISK_QUOTA_TOP_N)
+        .collect();
+    let worktrees = sized
+        .into_iter()
+        .map(|(wt, size)| WorktreeDiskUsage {
+            name: wt.name,
+            is_archived: wt.is_archived,
+            size_bytes: size,
+            size_display: format_size(size),
+        })
+        .collect();
+
+    Ok(DiskUsageReport {
+        workspace_path: workspace_path.to_string(),
+        total_bytes,
+        total_display: format_size(total_bytes),
+        quota_bytes,
+        over_quota: quota_bytes.is_some_and(|q| total_bytes > q),
+        worktrees,
+        archive_candidates,
+    })
+}
+
+/// 超出配额时向桌面端发送 `disk-quota-warning` 事件（同一 workspace 6 小时内只提醒一次）
+fn notify_disk_quota(report: &DiskUsageReport) {
+    let mut warned = DISK_QUOTA_WARNED.lock().unwrap();
+    if !report.over_quota {
+        warned.remove(&report.workspace_path);
+        return;
+    }
+    if let Some(at) = warned.get(&report.workspace_path) {
+        if at.elapsed().as_secs() < DISK_QUOTA_REWARN_SECS {
+            return;
+        }
+    }
+    warned.insert(report.workspace_path.clone(), std::time::Instant::now());
+    drop(warned);
+
+    log::warn!(
+        "[worktree] Disk quota exceeded in '{}': {} used, top: {}",
+        report.workspace_path,
+        report.total_display,
+        report
+            .worktrees
+            .iter()
+            .take(DISK_QUOTA_TOP_N)
+            .map(|w| format!("{} ({})", w.name, w.size_display))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    if let Some(app) = APP_HANDLE.lock().unwrap().as_ref() {
+        let mut payload = report.clone();
+        payload.worktrees.truncate(DISK_QUOTA_TOP_N);
+        let _ = app.emit("disk-quota-warning", payload);
+    }
+}
+
+pub fn get_disk_usage_impl(window_label: &str) -> Result<DiskUsageReport, String> {
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let report = build_disk_usage_report(&workspace_path, &config)?;
+    notify_disk_quota(&report);
+    Ok(report)
+}
+
+#[tauri::command]
+pub(crate) async fn get_disk_usage(window: tauri::Window) -> Result<DiskUsageReport, String> {
+    let label = window.label().to_string();
+    tokio::task::spawn_blocking(move || get_disk_usage_impl(&label))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// 启动后台线程，定期检查已打开 workspace 的磁盘配额
+pub(crate) fn start_disk_quota_monitor() {
+    std::thread::spawn(|| loop {
+        std::thread::sleep(std::time::Duration::from_secs(DISK_QUOTA_CHECK_INTERVAL_SECS));
+        let workspaces: std::collections::HashSet<String> =
+            WINDOW_WORKSPACES.lock().unwrap().values().cloned().collect();
+        for workspace_path in workspaces {
+            let config = crate::config::load_workspace_config(&workspace_path);
+            if config.disk_quota_mb.is_none() {
+                continue;
+            }
+            match build_disk_usage_report(&workspace_path, &config) {
+                Ok(report) => notify_disk_quota(&report),
+                Err(e) => log::warn!(
+                    "[worktree] Disk quota check failed for '{}': {}",
+                    workspace_path,
+                    e
+                ),
+            }
+        }
+    });
+}
+
+// ==================== 未提交修改汇总 ====================
+
+const DIRTY_REPORT_MAX_FILES: usize = 20;
+
+/// 汇总主工作区中指定项目（为空时为全部已配置项目）的未提交修改
+fn build_dirty_report(
+    workspace_path: &str,
+    config: &crate::types::WorkspaceConfig,
+    project_names: &[String],
+) -> DirtyReport {
+    let mut names: Vec<String> = if project_names.is_empty() {
+        config.projects.iter().map(|p| p.name.clone()).collect()
+    } else {
+        project_names.to_vec()
+    };
+    names.sort();
+    names.dedup();
+
+    let main_projects_path = PathBuf::from(workspace_path).join("projects");
+    let mut projects = vec![];
+    let mut fingerprint_input = String::new();
+    for name in names {
+        let proj_path = main_projects_path.join(&name);
+        if !proj_path.exists() {
+            continue;
+        }
+        let excludes = project_status_excludes(config, &name);
+        let info = get_worktree_info(&proj_path, &excludes, &project_remote(config, &name));
+        if info.uncommitted_count == 0 {
+            continue;
+        }
+
+        let files = crate::git_ops::get_dirty_files(&proj_path, &excludes);
+        fingerprint_input.push_str(&format!("{}\n{}\n", name, files.join("\n")));
+        projects.push(DirtyProject {
+            project_name: name,
+            staged_count: info.staged_count,
+            modified_count: info.modified_count,
+            untracked_count: info.untracked_count,
+            files: files.into_iter().take(DIRTY_REPORT_MAX_FILES).collect(),
+        });
+    }
+
+    let digest = ring::digest::digest(&ring::digest::SHA256, fingerprint_input.as_bytes());
+    DirtyReport {
+        projects,
+        fingerprint: hex::encode(&digest.as_ref()[..8]),
+    }
+}
+
+/// 存在未提交修改且确认指纹不匹配时拒绝操作，一次性列出全部项目，
+/// 避免执行到中途才在某个项目上失败、留下部分切换的状态
+fn ensure_dirty_acknowledged(report: &DirtyReport, dirty_ack: Option<&str>) -> Result<(), String> {
+    if report.projects.is_empty() || dirty_ack == Some(report.fingerprint.as_str()) {
+        return Ok(());
+    }
+    let details: Vec<String> = report
+        .projects
+        .iter()
+        .map(|p| {
+            format!(
+                "- {}: {} staged, {} modified, {} untracked",
+                p.project_name, p.staged_count, p.modified_count, p.untracked_count
+            )
+        })
+        .collect();
+    Err(format!(
+        "主工作区有 {} 个项目存在未提交的修改，需要确认后才能继续：\n{}",
+        report.projects.len(),
+        details.join("\n")
+    ))
+}
+
+fn worktree_project_names(worktree_path: &std::path::Path) -> Vec<String> {
+    fs::read_dir(worktree_path.join("projects"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().is_dir())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `worktree_name` 指定时只汇总该 worktree 包含的项目（即部署会切换的项目），
+/// 否则汇总 `project_names`，两者都为空时汇总全部项目
+pub fn get_dirty_report_impl(
+    window_label: &str,
+    worktree_name: Option<String>,
+    project_names: Vec<String>,
+) -> Result<DirtyReport, String> {
+    let (workspace_path, config) =
+        get_window_workspace_config(window_label).ok_or("No workspace selected")?;
+    let names = match worktree_name {
+        Some(name) => {
+            let worktree_path = PathBuf::from(&workspace_path)
+                .join(&config.worktrees_dir)
+                .join(&name);
+            worktree_project_names(&worktree_path)
+        }
+        None => project_names,
+    };
+    Ok(build_dirty_report(&workspace_path, &config, &names))
+}
+
+#[tauri::command]
+pub(crate) fn get_dirty_report(
+    window: tauri::Window,
+    worktree_name: Option<String>,
+    project_names: Option<Vec<String>>,
+) -> Result<DirtyReport, String> {
+    get_dirty_report_impl(window.label(), worktree_name, project_names.unwrap_or_default())
+}
+
+/// 切换主工作区项目分支前检查该项目的未提交修改（worktree 中的项目不受限制）
+pub(crate) fn guard_main_project_switch(
+    window_label: &str,
+    project_path: &str,
+    dirty_ack: Option<&str>,
+) -> Result<(), String> {
+    let Some((workspace_path, config)) = get_window_workspace_config(window_label) else {
+        return Ok(());
+    };
+    let path = PathBuf::from(normalize_path(project_path));
+    let main_projects_path = PathBuf::from(&workspace_path).join("projects");
+    if path.parent() != Some(main_projects_path.as_path()) {
+        return Ok(());
+    }
+    let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+        return Ok(());
+    };
+    let report = build_dirty_report(&workspace_path, &config, &[name]);
+    ensure_dirty_acknowledged(&report, dirty_ack)
+}
+
 // ==================== 部署到主工作区 ====================
 
 pub fn deploy_to_main_impl(
     window_label: &str,
     worktree_name: String,
+    dirty_ack: Option<String>,
 ) -> Result<DeployToMainResult, String> {
+    let _guard = begin_git_operation("deploy_to_main")?;
     let (workspace_path, config) =
         get_window_workspace_config(window_label).ok_or("No workspace selected")?;
 
@@ -1129,7 +4493,11 @@ pub fn deploy_to_main_impl(
                 .unwrap_or("")
                 .to_string();
 
-            let info = crate::git_ops::get_worktree_info(&proj_path);
+            let info = crate::git_ops::get_worktree_info(
+                &proj_path,
+                &project_status_excludes(&config, &proj_name),
+                &project_remote(&config, &proj_name),
+            );
             wt_branches.insert(proj_name, info.current_branch);
         }
     }
@@ -1142,19 +4510,27 @@ pub fn deploy_to_main_impl(
     let main_projects_path = root.join("projects");
     let mut original_branches: HashMap<String, String> = HashMap::new();
 
+    let wt_project_names: Vec<String> = wt_branches.keys().cloned().collect();
+    let report = build_dirty_report(&workspace_path, &config, &wt_project_names);
+    ensure_dirty_acknowledged(&report, dirty_ack.as_deref())?;
+    if !report.projects.is_empty() {
+        log::warn!(
+            "[deploy] Proceeding with {} dirty main projects (acknowledged)",
+            report.projects.len()
+        );
+    }
+
     for (proj_name, _) in &wt_branches {
         let main_proj_path = main_projects_path.join(proj_name);
         if !main_proj_path.exists() {
             continue;
         }
 
-        let info = crate::git_ops::get_worktree_info(&main_proj_path);
-        if info.uncommitted_count > 0 {
-            return Err(format!(
-                "Project '{}' in main workspace has {} uncommitted changes. Please commit or stash them first.",
-                proj_name, info.uncommitted_count
-            ));
-        }
+        let info = crate::git_ops::get_worktree_info(
+            &main_proj_path,
+            &project_status_excludes(&config, proj_name),
+            &project_remote(&config, proj_name),
+        );
         original_branches.insert(proj_name.clone(), info.current_branch);
     }
 
@@ -1183,7 +4559,7 @@ pub fn deploy_to_main_impl(
         );
         let detach_output = Command::new("git")
             .args(["-C", wt_proj_path.to_str().unwrap(), "checkout", "--detach"])
-            .output();
+            .logged_output();
 
         match &detach_output {
             Ok(o) if o.status.success() => {
@@ -1229,7 +4605,7 @@ pub fn deploy_to_main_impl(
                 "checkout",
                 wt_branch,
             ])
-            .output();
+            .logged_output();
 
         match switch_output {
             Ok(o) if o.status.success() => {
@@ -1280,10 +4656,32 @@ pub fn deploy_to_main_impl(
 
     broadcast_lock_state(&workspace_path);
 
+    // on_deploy_to_main 钩子在主工作区中执行，per_project 钩子只针对已切换的项目
+    let hook_runs = if switched_projects.is_empty() {
+        vec![]
+    } else {
+        let mut projects: Vec<(String, PathBuf)> = switched_projects
+            .iter()
+            .map(|name| (name.clone(), main_projects_path.join(name)))
+            .collect();
+        projects.sort();
+        let ctx = HookContext {
+            workspace_path: workspace_path.clone(),
+            worktree_name: worktree_name.clone(),
+            worktree_path: worktree_path.clone(),
+            cwd: root.clone(),
+            projects,
+        };
+        let runs = run_hooks(&config, HookEvent::OnDeployToMain, &ctx);
+        send_webhooks(&config, HookEvent::OnDeployToMain, &ctx);
+        runs
+    };
+
     Ok(DeployToMainResult {
         success: failed_projects.is_empty(),
         switched_projects,
         failed_projects,
+        hook_runs,
     })
 }
 
@@ -1291,11 +4689,13 @@ pub fn deploy_to_main_impl(
 pub(crate) fn deploy_to_main(
     window: tauri::Window,
     worktree_name: String,
+    dirty_ack: Option<String>,
 ) -> Result<DeployToMainResult, String> {
-    deploy_to_main_impl(window.label(), worktree_name)
+    deploy_to_main_impl(window.label(), worktree_name, dirty_ack)
 }
 
 pub fn exit_main_occupation_impl(window_label: &str, force: bool) -> Result<(), String> {
+    let _guard = begin_git_operation("exit_main_occupation")?;
     let (workspace_path, config) =
         get_window_workspace_config(window_label).ok_or("No workspace selected")?;
 
@@ -1317,7 +4717,11 @@ pub fn exit_main_occupation_impl(window_label: &str, force: bool) -> Result<(),
                 continue;
             }
 
-            let info = crate::git_ops::get_worktree_info(&main_proj_path);
+            let info = crate::git_ops::get_worktree_info(
+                &main_proj_path,
+                &project_status_excludes(&config, proj_name),
+                &project_remote(&config, proj_name),
+            );
             if info.uncommitted_count > 0 {
                 return Err(format!(
                     "Project '{}' in main workspace has {} uncommitted changes. Use force to discard them.",
@@ -1344,7 +4748,7 @@ pub fn exit_main_occupation_impl(window_label: &str, force: bool) -> Result<(),
         if force {
             Command::new("git")
                 .args(["-C", main_proj_path.to_str().unwrap(), "reset", "HEAD"])
-                .output()
+                .logged_output()
                 .ok();
             Command::new("git")
                 .args([
@@ -1354,11 +4758,11 @@ pub fn exit_main_occupation_impl(window_label: &str, force: bool) -> Result<(),
                     "--",
                     ".",
                 ])
-                .output()
+                .logged_output()
                 .ok();
             Command::new("git")
                 .args(["-C", main_proj_path.to_str().unwrap(), "clean", "-fd"])
-                .output()
+                .logged_output()
                 .ok();
         }
 
@@ -1369,7 +4773,7 @@ pub fn exit_main_occupation_impl(window_label: &str, force: bool) -> Result<(),
                 "checkout",
                 original_branch,
             ])
-            .output()
+            .logged_output()
             .map_err(|e| format!("Failed to switch project '{}': {}", proj_name, e))?;
 
         if !output.status.success() {
@@ -1398,7 +4802,7 @@ pub fn exit_main_occupation_impl(window_label: &str, force: bool) -> Result<(),
 
         let output = Command::new("git")
             .args(["-C", wt_proj_path.to_str().unwrap(), "checkout", branch])
-            .output();
+            .logged_output();
 
         match output {
             Ok(o) if o.status.success() => {
@@ -1455,3 +4859,178 @@ pub(crate) fn get_main_occupation(
 ) -> Result<Option<MainWorkspaceOccupation>, String> {
     get_main_occupation_impl(window.label())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    /// Scratch directory with `main/` and `wt/` sides, removed when dropped
+    struct TempDirs(PathBuf);
+
+    impl TempDirs {
+        fn new() -> Self {
+            let root = std::env::temp_dir().join(format!("wm-copy-{}", uuid::Uuid::new_v4()));
+            fs::create_dir_all(root.join("main")).unwrap();
+            fs::create_dir_all(root.join("wt")).unwrap();
+            TempDirs(root)
+        }
+
+        fn main(&self, rel: &str) -> PathBuf {
+            self.0.join("main").join(rel)
+        }
+
+        fn wt(&self, rel: &str) -> PathBuf {
+            self.0.join("wt").join(rel)
+        }
+    }
+
+    impl Drop for TempDirs {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn write(path: &Path, content: &str, age_secs: u64) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+        let modified = SystemTime::now() - Duration::from_secs(age_secs);
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    fn read(path: &Path) -> String {
+        fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn copy_path_creates_missing_files() {
+        let dirs = TempDirs::new();
+        write(&dirs.main(".env.local"), "main", 0);
+        for policy in [
+            CopyOverwritePolicy::Skip,
+            CopyOverwritePolicy::IfNewer,
+            CopyOverwritePolicy::Always,
+        ] {
+            let dst = dirs.wt(&format!("{:?}/.env.local", policy));
+            assert!(copy_path(&dirs.main(".env.local"), &dst, policy).unwrap());
+            assert_eq!(read(&dst), "main");
+        }
+    }
+
+    #[test]
+    fn copy_path_skip_keeps_existing_files() {
+        let dirs = TempDirs::new();
+        write(&dirs.main(".env.local"), "main", 0);
+        write(&dirs.wt(".env.local"), "worktree", 60);
+        let copied = copy_path(
+            &dirs.main(".env.local"),
+            &dirs.wt(".env.local"),
+            CopyOverwritePolicy::Skip,
+        )
+        .unwrap();
+        assert!(!copied);
+        assert_eq!(read(&dirs.wt(".env.local")), "worktree");
+    }
+
+    #[test]
+    fn copy_path_if_newer_compares_modification_times() {
+        let dirs = TempDirs::new();
+        write(&dirs.main("newer"), "main", 0);
+        write(&dirs.wt("newer"), "worktree", 60);
+        write(&dirs.main("older"), "main", 60);
+        write(&dirs.wt("older"), "worktree", 0);
+
+        let policy = CopyOverwritePolicy::IfNewer;
+        assert!(copy_path(&dirs.main("newer"), &dirs.wt("newer"), policy).unwrap());
+        assert_eq!(read(&dirs.wt("newer")), "main");
+        assert!(!copy_path(&dirs.main("older"), &dirs.wt("older"), policy).unwrap());
+        assert_eq!(read(&dirs.wt("older")), "worktree");
+    }
+
+    #[test]
+    fn copy_path_always_overwrites() {
+        let dirs = TempDirs::new();
+        write(&dirs.main(".env.local"), "main", 60);
+        write(&dirs.wt(".env.local"), "worktree", 0);
+        let copied = copy_path(
+            &dirs.main(".env.local"),
+            &dirs.wt(".env.local"),
+            CopyOverwritePolicy::Always,
+        )
+        .unwrap();
+        assert!(copied);
+        assert_eq!(read(&dirs.wt(".env.local")), "main");
+    }
+
+    #[test]
+    fn copy_path_applies_the_policy_per_file_in_directories() {
+        let dirs = TempDirs::new();
+        write(&dirs.main("config/a.json"), "main a", 0);
+        write(&dirs.main("config/nested/b.json"), "main b", 0);
+        write(&dirs.wt("config/a.json"), "worktree a", 0);
+
+        let copied = copy_path(
+            &dirs.main("config"),
+            &dirs.wt("config"),
+            CopyOverwritePolicy::Skip,
+        )
+        .unwrap();
+        assert!(copied);
+        assert_eq!(read(&dirs.wt("config/a.json")), "worktree a");
+        assert_eq!(read(&dirs.wt("config/nested/b.json")), "main b");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn copy_path_skips_symlinks() {
+        let dirs = TempDirs::new();
+        write(&dirs.main("config/a.json"), "main a", 0);
+        std::os::unix::fs::symlink(dirs.main("config/a.json"), dirs.main("config/link.json"))
+            .unwrap();
+        write(&dirs.main("shared.json"), "main shared", 0);
+        std::os::unix::fs::symlink(dirs.main("shared.json"), dirs.wt("shared.json")).unwrap();
+
+        let policy = CopyOverwritePolicy::Always;
+        copy_path(&dirs.main("config"), &dirs.wt("config"), policy).unwrap();
+        assert!(!dirs.wt("config/link.json").exists());
+        // An existing link in the worktree is never written through
+        assert!(!copy_path(&dirs.main("shared.json"), &dirs.wt("shared.json"), policy).unwrap());
+        assert!(dirs.wt("shared.json").is_symlink());
+    }
+
+    #[test]
+    fn merge_dir_into_copies_missing_entries_and_keeps_the_source() {
+        let dirs = TempDirs::new();
+        write(&dirs.wt("pkg/a.js"), "worktree a", 0);
+        write(&dirs.wt("pkg/b.js"), "worktree b", 0);
+        write(&dirs.wt("only-in-wt/c.js"), "worktree c", 0);
+        write(&dirs.main("pkg/a.js"), "main a", 0);
+
+        let mut created = vec![];
+        merge_dir_into(&dirs.wt(""), &dirs.main(""), &mut created).unwrap();
+        assert_eq!(read(&dirs.main("pkg/a.js")), "main a");
+        assert_eq!(read(&dirs.main("pkg/b.js")), "worktree b");
+        assert_eq!(read(&dirs.main("only-in-wt/c.js")), "worktree c");
+        assert_eq!(read(&dirs.wt("pkg/b.js")), "worktree b");
+
+        remove_merged_entries(&created);
+        assert!(!dirs.main("pkg/b.js").exists());
+        assert!(!dirs.main("only-in-wt").exists());
+        assert_eq!(read(&dirs.main("pkg/a.js")), "main a");
+    }
+
+    #[test]
+    fn link_names_must_be_single_components() {
+        for name in ["feat-x", "api", "feat..x"] {
+            assert!(is_single_path_component(name), "{}", name);
+        }
+        for name in ["", ".", "..", "../feat-x", "feat-x/projects", "/etc"] {
+            assert!(!is_single_path_component(name), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! Docker Compose stacks per worktree: a project's compose file is started under a project
+//! name derived from the workspace, worktree and project, so the same stack checked out in
+//! several worktrees runs side by side instead of replacing each other's containers.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::types::{ComposeService, ComposeStatus};
+use crate::utils::{normalize_path, run_command_with_timeout};
+
+const COMPOSE_FILES: &[&str] = &[
+    "compose.yaml",
+    "compose.yml",
+    "docker-compose.yml",
+    "docker-compose.yaml",
+];
+/// `up` may pull or build images
+const UP_TIMEOUT_SECS: u64 = 600;
+const DOWN_TIMEOUT_SECS: u64 = 120;
+const PS_TIMEOUT_SECS: u64 = 30;
+
+pub(crate) fn find_compose_file(project_dir: &Path) -> Option<PathBuf> {
+    COMPOSE_FILES
+        .iter()
+        .map(|f| project_dir.join(f))
+        .find(|p| p.is_file())
+}
+
+/// `{workspace}-{worktree}-{project}`, lowercased with everything but `[a-z0-9_-]` replaced,
+/// as Compose requires.
+pub(crate) fn project_name(workspace_path: &str, worktree_name: &str, project: &str) -> String {
+    let workspace = Path::new(workspace_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name: String = format!("{}-{}-{}", workspace, worktree_name, project)
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    name.trim_start_matches(['-', '_']).to_string()
+}
+
+fn docker_compose(
+    project_dir: &Path,
+    compose_file: &Path,
+    name: &str,
+    args: &[&str],
+    envs: &[(String, String)],
+    timeout_secs: u64,
+) -> Result<String, String> {
+    let file = compose_file.to_string_lossy();
+    let mut full_args = vec!["compose", "-p", name, "-f", &file];
+    full_args.extend_from_slice(args);
+    let output = run_command_with_timeout(
+        "docker",
+        &full_args,
+        &project_dir.to_string_lossy(),
+        envs,
+        timeout_secs,
+    )?;
+    if !output.status.success() {
+        return Err(format!(
+            "docker compose {} 失败: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Older Compose versions print a JSON array, newer ones one object per line.
+fn parse_ps(stdout: &str) -> Vec<ComposeService> {
+    let trimmed = stdout.trim();
+    let items: Vec<Value> = if trimmed.starts_with('[') {
+        serde_json::from_str(trimmed).unwrap_or_default()
+    } else {
+        trimmed
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    };
+    items
+        .iter()
+        .map(|item| {
+            let field = |key: &str| item[key].as_str().unwrap_or("").to_string();
+            ComposeService {
+                name: field("Name"),
+                service: field("Service"),
+                state: field("State"),
+                status: field("Status"),
+            }
+        })
+        .collect()
+}
+
+fn compose_file_or_err(project_dir: &Path) -> Result<PathBuf, String> {
+    find_compose_file(project_dir).ok_or_else(|| "该项目没有 docker compose 文件".to_string())
+}
+
+pub(crate) fn status(project_dir: &Path, name: &str) -> Result<ComposeStatus, String> {
+    let Some(compose_file) = find_compose_file(project_dir) else {
+        return Ok(ComposeStatus {
+            compose_file: None,
+            project_name: name.to_string(),
+            services: vec![],
+        });
+    };
+    let stdout = docker_compose(
+        project_dir,
+        &compose_file,
+        name,
+        &["ps", "-a", "--format", "json"],
+        &[],
+        PS_TIMEOUT_SECS,
+    )?;
+    Ok(ComposeStatus {
+        compose_file: Some(normalize_path(&compose_file.to_string_lossy())),
+        project_name: name.to_string(),
+        services: parse_ps(&stdout),
+    })
+}
+
+/// `docker compose up -d`; `envs` (the worktree's port variables) are available for
+/// interpolation in the compose file.
+pub(crate) fn up(
+    project_dir: &Path,
+    name: &str,
+    envs: &[(String, String)],
+) -> Result<ComposeStatus, String> {
+    let compose_file = compose_file_or_err(project_dir)?;
+    log::info!("[compose] Starting '{}' in {}", name, project_dir.display());
+    docker_compose(
+        project_dir,
+        &compose_file,
+        name,
+        &["up", "-d"],
+        envs,
+        UP_TIMEOUT_SECS,
+    )?;
+    status(project_dir, name)
+}
+
+pub(crate) fn down(project_dir: &Path, name: &str) -> Result<ComposeStatus, String> {
+    let compose_file = compose_file_or_err(project_dir)?;
+    log::info!("[compose] Stopping '{}' in {}", name, project_dir.display());
+    docker_compose(
+        project_dir,
+        &compose_file,
+        name,
+        &["down"],
+        &[],
+        DOWN_TIMEOUT_SECS,
+    )?;
+    status(project_dir, name)
+}
+
+/// Pre-archive cleanup: take down every stack of the worktree that has containers.
+/// Failures (e.g. Docker not running) are only logged.
+pub(crate) fn down_worktree(workspace_path: &str, worktree_name: &str, worktree_path: &Path) {
+    let Ok(entries) = fs::read_dir(worktree_path.join("projects")) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let project_dir = entry.path();
+        if find_compose_file(&project_dir).is_none() {
+            continue;
+        }
+        let project = entry.file_name().to_string_lossy().to_string();
+        let name = project_name(workspace_path, worktree_name, &project);
+        match status(&project_dir, &name) {
+            Ok(s) if s.services.is_empty() => continue,
+            Ok(_) => {}
+            Err(e) => {
+                log::warn!("[compose] Failed to check '{}': {}", name, e);
+                continue;
+            }
+        }
+        if let Err(e) = down(&project_dir, &name) {
+            log::warn!("[compose] Failed to stop '{}': {}", name, e);
+        }
+    }
+}
@@ -1,8 +1,18 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use crate::state::{GLOBAL_CONFIG_CACHE, WINDOW_WORKSPACES, WORKSPACE_CONFIG_CACHE};
-use crate::types::{GlobalConfig, MainWorkspaceOccupation, WorkspaceConfig};
+use std::collections::VecDeque;
+
+use serde_json::{json, Map, Value};
+
+use crate::state::{
+    GLOBAL_CONFIG_CACHE, WINDOW_WORKSPACES, WORKSPACE_CONFIG_CACHE, WORKSPACE_CONFIG_VERSIONS,
+};
+use crate::types::{
+    GlobalConfig, MainWorkspaceOccupation, ProjectConfig, ShellConfig, VersionedWorkspaceConfig,
+    WorkspaceConfig,
+};
+use crate::utils::normalize_path;
 
 // ==================== 配置路径 ====================
 
@@ -34,10 +44,119 @@ pub(crate) fn get_global_config_path() -> PathBuf {
     }
 }
 
+/// 终端输出持久化目录（与 global.json 同级）
+pub(crate) fn get_scrollback_dir() -> PathBuf {
+    get_global_config_path()
+        .parent()
+        .map(|p| p.join("scrollback"))
+        .unwrap_or_else(|| PathBuf::from("scrollback"))
+}
+
+/// 打开的终端会话列表（与 global.json 同级），重启后据此恢复终端
+pub(crate) fn get_pty_sessions_path() -> PathBuf {
+    get_global_config_path()
+        .parent()
+        .map(|p| p.join("pty-sessions.json"))
+        .unwrap_or_else(|| PathBuf::from("pty-sessions.json"))
+}
+
 pub(crate) fn get_workspace_config_path(workspace_path: &str) -> PathBuf {
     PathBuf::from(workspace_path).join(".worktree-manager.json")
 }
 
+/// 主工作区占用状态文件（deploy_to_main 写入）
+pub(crate) fn get_occupation_state_path(workspace_path: &str) -> PathBuf {
+    PathBuf::from(workspace_path).join(".worktree-manager-occupation.json")
+}
+
+// ==================== 配置加密 ====================
+
+/// 解析配置文件，先解密其中加密存储的敏感字段
+fn parse_config<T: serde::de::DeserializeOwned>(content: &str) -> serde_json::Result<T> {
+    let mut value: Value = serde_json::from_str(content)?;
+    crate::secrets::decrypt_config_value(&mut value);
+    serde_json::from_value(value)
+}
+
+/// 解析 workspace 配置：解密后先迁移旧版字段
+fn parse_workspace_config(content: &str) -> serde_json::Result<WorkspaceConfig> {
+    let mut value: Value = serde_json::from_str(content)?;
+    crate::secrets::decrypt_config_value(&mut value);
+    migrate_shorthand_commands(&mut value);
+    serde_json::from_value(value)
+}
+
+/// 旧版的 `post_create_commands` / `pre_archive_commands`（workspace 级与项目级）迁移为等价的
+/// per_project on_create / on_archive 钩子，追加在已有钩子之后，同一事件中 workspace 级排在项目级之前
+fn migrate_shorthand_commands(value: &mut Value) {
+    const SHORTHANDS: [(&str, &str); 2] = [
+        ("post_create_commands", "on_create"),
+        ("pre_archive_commands", "on_archive"),
+    ];
+    let Some(root) = value.as_object_mut() else {
+        return;
+    };
+    let take = |obj: &mut Map<String, Value>, key: &str| -> Vec<String> {
+        obj.remove(key)
+            .and_then(|v| serde_json::from_value::<Vec<String>>(v).ok())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|c| !c.trim().is_empty())
+            .collect()
+    };
+    let hook = |event: &str, command: String, projects: Vec<String>| {
+        json!({
+            "event": event,
+            "command": command,
+            "per_project": true,
+            "projects": projects,
+        })
+    };
+
+    let mut migrated: Vec<Value> = vec![];
+    for (key, event) in SHORTHANDS {
+        migrated.extend(take(root, key).into_iter().map(|c| hook(event, c, vec![])));
+    }
+    if let Some(projects) = root.get_mut("projects").and_then(|p| p.as_array_mut()) {
+        for project in projects.iter_mut().filter_map(|p| p.as_object_mut()) {
+            let name = project["name"].as_str().unwrap_or_default().to_string();
+            for (key, event) in SHORTHANDS {
+                migrated.extend(
+                    take(project, key)
+                        .into_iter()
+                        .map(|c| hook(event, c, vec![name.clone()])),
+                );
+            }
+        }
+    }
+    if migrated.is_empty() {
+        return;
+    }
+    log::info!(
+        "[config] Migrated {} shorthand commands to hooks",
+        migrated.len()
+    );
+    match root.get_mut("hooks").and_then(|h| h.as_array_mut()) {
+        Some(hooks) => hooks.extend(migrated),
+        None => {
+            root.insert("hooks".to_string(), Value::Array(migrated));
+        }
+    }
+}
+
+/// 序列化配置；`encrypt` 时敏感字段加密后写入（经过 Value，键按字母排序）
+fn serialize_config<T: serde::Serialize>(config: &T, encrypt: bool) -> Result<String, String> {
+    let result = if encrypt {
+        let mut value = serde_json::to_value(config)
+            .map_err(|e| format!("Failed to serialize config: {}", e))?;
+        crate::secrets::encrypt_config_value(&mut value)?;
+        serde_json::to_string_pretty(&value)
+    } else {
+        serde_json::to_string_pretty(config)
+    };
+    result.map_err(|e| format!("Failed to serialize config: {}", e))
+}
+
 // ==================== 全局配置加载/保存 ====================
 
 pub fn load_global_config() -> GlobalConfig {
@@ -51,7 +170,7 @@ pub fn load_global_config() -> GlobalConfig {
     let config_path = get_global_config_path();
     let config = if config_path.exists() {
         match fs::read_to_string(&config_path) {
-            Ok(content) => match serde_json::from_str::<GlobalConfig>(&content) {
+            Ok(content) => match parse_config::<GlobalConfig>(&content) {
                 Ok(cfg) => cfg,
                 Err(e) => {
                     log::warn!("Failed to parse global config at {:?}: {}", config_path, e);
@@ -85,8 +204,7 @@ pub fn save_global_config_internal(config: &GlobalConfig) -> Result<(), String>
             .map_err(|e| format!("Failed to create config directory: {}", e))?;
     }
 
-    let content = serde_json::to_string_pretty(config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    let content = serialize_config(config, config.encrypt_config)?;
 
     fs::write(&config_path, content).map_err(|e| format!("Failed to write config file: {}", e))?;
 
@@ -103,10 +221,8 @@ pub fn save_global_config_internal(config: &GlobalConfig) -> Result<(), String>
 pub fn load_workspace_config(workspace_path: &str) -> WorkspaceConfig {
     {
         let cache = WORKSPACE_CONFIG_CACHE.lock().unwrap();
-        if let Some((ref cached_path, ref config)) = *cache {
-            if cached_path == workspace_path {
-                return config.clone();
-            }
+        if let Some(config) = cache.get(workspace_path) {
+            return config.clone();
         }
     }
 
@@ -122,7 +238,7 @@ pub fn load_workspace_config(workspace_path: &str) -> WorkspaceConfig {
             })
             .ok()
             .and_then(|content| {
-                serde_json::from_str::<WorkspaceConfig>(&content)
+                parse_workspace_config(&content)
                     .map_err(|e| {
                         log::warn!(
                             "Failed to parse workspace config at {:?}: {}",
@@ -135,37 +251,192 @@ pub fn load_workspace_config(workspace_path: &str) -> WorkspaceConfig {
             .unwrap_or_default()
     } else {
         let default_config = WorkspaceConfig::default();
-        let _ = save_workspace_config_internal(workspace_path, &default_config);
+        let _ = write_workspace_config(workspace_path, &default_config);
         default_config
     };
 
     {
         let mut cache = WORKSPACE_CONFIG_CACHE.lock().unwrap();
-        *cache = Some((workspace_path.to_string(), config.clone()));
+        cache.insert(workspace_path.to_string(), config.clone());
     }
 
     config
 }
 
-pub fn save_workspace_config_internal(
-    workspace_path: &str,
-    config: &WorkspaceConfig,
-) -> Result<(), String> {
+/// Re-read the config file after it changed on disk (edited by hand, pulled with git, ...).
+/// Returns false when the file matches the cache, i.e. the change was our own write, or
+/// when it doesn't parse (yet).
+pub(crate) fn reload_workspace_config(workspace_path: &str) -> bool {
     let config_path = get_workspace_config_path(workspace_path);
+    let Ok(content) = fs::read_to_string(&config_path) else {
+        return false;
+    };
+    let config = match parse_workspace_config(&content) {
+        Ok(config) => config,
+        Err(e) => {
+            log::warn!(
+                "Failed to parse workspace config at {:?}: {}",
+                config_path,
+                e
+            );
+            return false;
+        }
+    };
+    let mut cache = WORKSPACE_CONFIG_CACHE.lock().unwrap();
+    let unchanged = cache.get(workspace_path).is_some_and(|cached| {
+        serde_json::to_value(cached).ok() == serde_json::to_value(&config).ok()
+    });
+    if unchanged {
+        return false;
+    }
+    log::info!(
+        "Workspace config at {:?} changed on disk, reloaded",
+        config_path
+    );
+    cache.insert(workspace_path.to_string(), config);
+    true
+}
 
-    let content = serde_json::to_string_pretty(config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+/// Write the config file and refresh the cache (callers hold WORKSPACE_CONFIG_VERSIONS)
+fn write_workspace_config(workspace_path: &str, config: &WorkspaceConfig) -> Result<(), String> {
+    let config_path = get_workspace_config_path(workspace_path);
+
+    let content = serialize_config(config, load_global_config().encrypt_config)?;
 
     fs::write(&config_path, content).map_err(|e| format!("Failed to write config file: {}", e))?;
 
     {
         let mut cache = WORKSPACE_CONFIG_CACHE.lock().unwrap();
-        *cache = Some((workspace_path.to_string(), config.clone()));
+        cache.insert(workspace_path.to_string(), config.clone());
     }
 
     Ok(())
 }
 
+pub fn save_workspace_config_internal(
+    workspace_path: &str,
+    config: &WorkspaceConfig,
+) -> Result<(), String> {
+    let mut versions = WORKSPACE_CONFIG_VERSIONS.lock().unwrap();
+    write_workspace_config(workspace_path, config)?;
+    remember_config_version(&mut versions, workspace_path, config);
+    Ok(())
+}
+
+// ==================== Workspace 配置版本（etag） ====================
+
+/// 每个 workspace 保留的历史版本数，超出后基于更旧版本的保存只能报冲突
+const CONFIG_VERSION_HISTORY: usize = 20;
+
+fn config_value_and_etag(config: &WorkspaceConfig) -> (Value, String) {
+    // Value 的对象按键排序，序列化结果稳定
+    let value = serde_json::to_value(config).unwrap_or(Value::Null);
+    let digest = ring::digest::digest(&ring::digest::SHA256, value.to_string().as_bytes());
+    let etag = digest.as_ref()[..8]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    (value, etag)
+}
+
+fn remember_config_version(
+    versions: &mut std::collections::HashMap<String, VecDeque<(String, Value)>>,
+    workspace_path: &str,
+    config: &WorkspaceConfig,
+) -> String {
+    let (value, etag) = config_value_and_etag(config);
+    let history = versions.entry(workspace_path.to_string()).or_default();
+    if history.back().map(|(e, _)| e != &etag).unwrap_or(true) {
+        history.push_back((etag.clone(), value));
+        while history.len() > CONFIG_VERSION_HISTORY {
+            history.pop_front();
+        }
+    }
+    etag
+}
+
+/// Current config plus its etag, to be passed back as `base_etag` when saving.
+pub fn load_workspace_config_versioned(workspace_path: &str) -> VersionedWorkspaceConfig {
+    let config = load_workspace_config(workspace_path);
+    let mut versions = WORKSPACE_CONFIG_VERSIONS.lock().unwrap();
+    let etag = remember_config_version(&mut versions, workspace_path, &config);
+    VersionedWorkspaceConfig { config, etag }
+}
+
+/// Three-way merge by top-level section: take every section the client changed
+/// relative to `base`, unless another writer changed the same section differently.
+fn merge_config_sections(base: &Value, current: &Value, incoming: &Value) -> Result<Value, Vec<String>> {
+    let (Some(base), Some(current), Some(incoming)) =
+        (base.as_object(), current.as_object(), incoming.as_object())
+    else {
+        return Err(vec![]);
+    };
+    let mut merged = current.clone();
+    let mut conflicts = vec![];
+    for (key, value) in incoming {
+        let base_value = base.get(key);
+        if base_value == Some(value) {
+            continue;
+        }
+        let current_value = current.get(key);
+        if current_value == base_value || current_value == Some(value) {
+            merged.insert(key.clone(), value.clone());
+        } else {
+            conflicts.push(key.clone());
+        }
+    }
+    if conflicts.is_empty() {
+        Ok(Value::Object(merged))
+    } else {
+        Err(conflicts)
+    }
+}
+
+/// Save a config edited from the version `base_etag`.
+///
+/// If another window saved in between, independent sections are merged; sections
+/// changed on both sides are reported as a conflict. Without `base_etag` the save
+/// overwrites unconditionally. Returns the stored config and its new etag.
+pub fn save_workspace_config_versioned(
+    workspace_path: &str,
+    config: WorkspaceConfig,
+    base_etag: Option<&str>,
+) -> Result<VersionedWorkspaceConfig, String> {
+    let mut versions = WORKSPACE_CONFIG_VERSIONS.lock().unwrap();
+    let current = load_workspace_config(workspace_path);
+    let (current_value, current_etag) = config_value_and_etag(&current);
+
+    let config = match base_etag {
+        Some(base_etag) if base_etag != current_etag => {
+            let base = versions
+                .get(workspace_path)
+                .and_then(|h| h.iter().find(|(e, _)| e == base_etag))
+                .map(|(_, v)| v.clone())
+                .ok_or_else(|| "配置已被其他窗口修改，请刷新后重试".to_string())?;
+            let incoming = serde_json::to_value(&config)
+                .map_err(|e| format!("Failed to serialize config: {}", e))?;
+            let merged = merge_config_sections(&base, &current_value, &incoming).map_err(|sections| {
+                format!(
+                    "配置已被其他窗口修改，冲突的部分：{}。请刷新后重试",
+                    sections.join(", ")
+                )
+            })?;
+            log::info!(
+                "[config] Merged concurrent edit of {} (base {}, current {})",
+                workspace_path,
+                base_etag,
+                current_etag
+            );
+            serde_json::from_value(merged).map_err(|e| format!("Failed to merge config: {}", e))?
+        }
+        _ => config,
+    };
+
+    write_workspace_config(workspace_path, &config)?;
+    let etag = remember_config_version(&mut versions, workspace_path, &config);
+    Ok(VersionedWorkspaceConfig { config, etag })
+}
+
 // ==================== 获取当前 Workspace ====================
 
 /// 获取窗口绑定的 workspace 路径，优先从 WINDOW_WORKSPACES 获取，
@@ -189,10 +460,119 @@ pub(crate) fn get_window_workspace_config(window_label: &str) -> Option<(String,
     Some((workspace_path, config))
 }
 
+// ==================== 工作区相对路径 ====================
+
+/// 将绝对路径转换为相对于 workspace 根目录、以 `/` 分隔的路径，供客户端寻址使用，
+/// 避免宿主机绝对路径和平台分隔符泄露给 Web 端。不在 workspace 内的路径原样返回（统一为 `/`）。
+pub(crate) fn to_workspace_relative(workspace_path: &str, path: &Path) -> String {
+    let rel = path.strip_prefix(workspace_path).unwrap_or(path);
+    rel.components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 路径所在的 worktree 目录名与项目名：`<worktrees_dir>/<worktree>/projects/<project>/...`，
+/// 主工作区为 `projects/<project>/...`（worktree 为 None）
+pub(crate) fn path_location(
+    workspace_path: &str,
+    config: &WorkspaceConfig,
+    path: &str,
+) -> (Option<String>, Option<String>) {
+    let Ok(rel) = Path::new(path).strip_prefix(workspace_path) else {
+        return (None, None);
+    };
+    let components: Vec<String> = rel
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    let worktrees_dir: Vec<String> = Path::new(&config.worktrees_dir)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    let (worktree, rest) = match components.strip_prefix(worktrees_dir.as_slice()) {
+        Some([worktree, rest @ ..]) => (Some(worktree.clone()), rest),
+        Some([]) => return (None, None),
+        None => (None, components.as_slice()),
+    };
+    let project = match rest {
+        [dir, project, ..] if dir == "projects" => Some(project.clone()),
+        _ => None,
+    };
+    (worktree, project)
+}
+
+/// 将客户端传来的路径解析为服务端绝对路径。
+/// 相对路径（如 `worktrees/feat-x/projects/api`）按窗口/会话绑定的 workspace 解析；
+/// 绝对路径保持兼容，但必须位于该 workspace 内。两者都不允许包含 `..`，避免绕过按路径做的范围检查。
+pub(crate) fn resolve_workspace_path(window_label: &str, path: &str) -> Result<String, String> {
+    if path.is_empty() {
+        return Err("Path is required".to_string());
+    }
+    let workspace_path = get_window_workspace_path(window_label).ok_or("No workspace selected")?;
+    if Path::new(path).is_absolute() {
+        return confine_to_workspace(&workspace_path, path);
+    }
+    join_workspace_relative(&workspace_path, path)
+}
+
+fn confine_to_workspace(workspace_path: &str, path: &str) -> Result<String, String> {
+    let normalized = normalize_path(path);
+    if has_parent_segment(path)
+        || !Path::new(&normalized).starts_with(normalize_path(workspace_path))
+    {
+        return Err(format!("Path is outside the workspace: {}", path));
+    }
+    Ok(normalized)
+}
+
+fn has_parent_segment(path: &str) -> bool {
+    path.split(['/', '\\']).any(|s| s == "..")
+}
+
+fn join_workspace_relative(workspace_path: &str, path: &str) -> Result<String, String> {
+    if has_parent_segment(path) {
+        return Err(format!("Invalid workspace-relative path: {}", path));
+    }
+    let mut resolved = PathBuf::from(workspace_path);
+    for segment in path.split(['/', '\\']).filter(|s| !s.is_empty() && *s != ".") {
+        resolved.push(segment);
+    }
+    Ok(resolved.to_string_lossy().to_string())
+}
+
+/// 根据项目目录名（`.../projects/<name>`）查找当前 workspace 中的项目配置
+pub(crate) fn project_config_for_path(window_label: &str, path: &str) -> Option<ProjectConfig> {
+    let (_, config) = get_window_workspace_config(window_label)?;
+    let name = Path::new(path).file_name()?.to_string_lossy().to_string();
+    config.projects.into_iter().find(|p| p.name == name)
+}
+
+/// 终端使用的 shell：项目级配置优先，其次 workspace 级；都未配置时为 None（系统默认 shell）
+pub(crate) fn shell_for_path(window_label: &str, path: &str) -> Option<ShellConfig> {
+    project_config_for_path(window_label, path)
+        .and_then(|p| p.shell)
+        .or_else(|| get_window_workspace_config(window_label).and_then(|(_, c)| c.shell))
+}
+
+/// 项目配置的 merge_strategy；找不到项目配置时按 merge 处理
+pub(crate) fn merge_strategy_for_path(window_label: &str, path: &str) -> crate::git_ops::MergeStrategy {
+    project_config_for_path(window_label, path)
+        .map(|p| crate::git_ops::MergeStrategy::from_config(&p.merge_strategy))
+        .unwrap_or(crate::git_ops::MergeStrategy::Merge)
+}
+
+/// 项目配置的 remote；找不到项目配置时使用 origin
+pub(crate) fn remote_for_path(window_label: &str, path: &str) -> String {
+    project_config_for_path(window_label, path)
+        .map(|p| p.remote)
+        .unwrap_or_else(crate::types::default_remote)
+}
+
 // ==================== 主工作区占用状态 ====================
 
 pub fn load_occupation_state(workspace_path: &str) -> Option<MainWorkspaceOccupation> {
-    let path = std::path::PathBuf::from(workspace_path).join(".worktree-manager-occupation.json");
+    let path = get_occupation_state_path(workspace_path);
     if !path.exists() {
         return None;
     }
@@ -202,7 +582,7 @@ pub fn load_occupation_state(workspace_path: &str) -> Option<MainWorkspaceOccupa
 }
 
 pub fn save_occupation_state(workspace_path: &str, state: &MainWorkspaceOccupation) -> Result<(), String> {
-    let path = std::path::PathBuf::from(workspace_path).join(".worktree-manager-occupation.json");
+    let path = get_occupation_state_path(workspace_path);
     let content = serde_json::to_string_pretty(state)
         .map_err(|e| format!("Failed to serialize occupation state: {}", e))?;
     std::fs::write(&path, content)
@@ -210,10 +590,95 @@ pub fn save_occupation_state(workspace_path: &str, state: &MainWorkspaceOccupati
 }
 
 pub fn clear_occupation_state(workspace_path: &str) -> Result<(), String> {
-    let path = std::path::PathBuf::from(workspace_path).join(".worktree-manager-occupation.json");
+    let path = get_occupation_state_path(workspace_path);
     if path.exists() {
         std::fs::remove_file(&path)
             .map_err(|e| format!("Failed to clear occupation state: {}", e))?;
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_paths_join_the_workspace() {
+        let resolved = join_workspace_relative("/ws", "worktrees/feat-x/./projects/api/").unwrap();
+        assert_eq!(
+            Path::new(&resolved),
+            Path::new("/ws/worktrees/feat-x/projects/api")
+        );
+        let resolved = join_workspace_relative("/ws", "worktrees\\feat-x").unwrap();
+        assert_eq!(Path::new(&resolved), Path::new("/ws/worktrees/feat-x"));
+    }
+
+    #[test]
+    fn relative_paths_reject_parent_segments() {
+        assert!(join_workspace_relative("/ws", "..").is_err());
+        assert!(join_workspace_relative("/ws", "worktrees/../../etc").is_err());
+        assert!(join_workspace_relative("/ws", "worktrees\\..\\secrets").is_err());
+        // `..` only counts as a whole segment
+        assert!(join_workspace_relative("/ws", "worktrees/feat..x").is_ok());
+    }
+
+    #[test]
+    fn shorthand_commands_are_migrated_to_hooks() {
+        let config = parse_workspace_config(
+            r#"{
+                "name": "ws",
+                "worktrees_dir": "worktrees",
+                "hooks": [{ "event": "on_create", "command": "echo first" }],
+                "post_create_commands": ["pnpm install", " "],
+                "pre_archive_commands": ["docker compose down"],
+                "projects": [{
+                    "name": "api",
+                    "base_branch": "main",
+                    "test_branch": "test",
+                    "merge_strategy": "merge",
+                    "post_create_commands": ["make setup"]
+                }]
+            }"#,
+        )
+        .unwrap();
+        let hooks: Vec<String> = config
+            .hooks
+            .iter()
+            .map(|h| {
+                let scope = if h.per_project {
+                    h.projects.join(",")
+                } else {
+                    "-".into()
+                };
+                format!("{} [{}] {}", h.event.as_str(), scope, h.command)
+            })
+            .collect();
+        assert_eq!(
+            hooks,
+            [
+                "on_create [-] echo first",
+                "on_create [] pnpm install",
+                "on_archive [] docker compose down",
+                "on_create [api] make setup",
+            ]
+        );
+
+        let saved = serde_json::to_value(&config).unwrap();
+        assert!(saved.get("post_create_commands").is_none());
+        assert!(saved["projects"][0].get("post_create_commands").is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn absolute_paths_are_confined_to_the_workspace() {
+        assert_eq!(
+            confine_to_workspace("/ws", "/ws/worktrees/feat-x").unwrap(),
+            "/ws/worktrees/feat-x"
+        );
+        assert_eq!(confine_to_workspace("/ws", "/ws").unwrap(), "/ws");
+        assert!(confine_to_workspace("/ws", "/ws/worktrees/feat-x/../../etc").is_err());
+        assert!(confine_to_workspace("/ws", "/etc/passwd").is_err());
+        assert!(confine_to_workspace("/ws", "/wsx/worktrees/feat-x").is_err());
+        assert!(resolve_workspace_path("test-window", "").is_err());
+    }
+}
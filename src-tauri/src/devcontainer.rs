@@ -0,0 +1,146 @@
+//! Dev Containers: detect a project's `devcontainer.json`, report the state of the container
+//! the devcontainer CLI / VS Code created for it, and open the project inside the container
+//! (`devcontainer up`, then VS Code with a `vscode-remote://dev-container+...` folder URI).
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde_json::Value;
+
+use crate::types::DevcontainerStatus;
+use crate::utils::{normalize_path, run_command_with_timeout};
+
+const CONFIG_CANDIDATES: &[&str] = &[".devcontainer/devcontainer.json", ".devcontainer.json"];
+/// The first `devcontainer up` builds the image, which can take a while
+const UP_TIMEOUT_SECS: u64 = 900;
+
+pub(crate) fn find_config(project: &Path) -> Option<PathBuf> {
+    CONFIG_CANDIDATES
+        .iter()
+        .map(|c| project.join(c))
+        .find(|p| p.is_file())
+}
+
+fn cli_available() -> bool {
+    Command::new("devcontainer")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+/// (id, state) of the container labelled with this folder, newest first
+fn find_container(project_path: &str) -> Option<(String, String)> {
+    let output = Command::new("docker")
+        .args([
+            "ps",
+            "-a",
+            "--filter",
+            &format!("label=devcontainer.local_folder={}", project_path),
+            "--format",
+            "{{.ID}}\t{{.State}}",
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (id, state) = stdout.lines().next()?.split_once('\t')?;
+    Some((id.to_string(), state.to_string()))
+}
+
+pub(crate) fn status(project_path: &str) -> DevcontainerStatus {
+    let Some(config) = find_config(Path::new(project_path)) else {
+        return DevcontainerStatus {
+            config_path: None,
+            container_id: None,
+            state: None,
+            cli_available: false,
+        };
+    };
+    let container = find_container(project_path);
+    DevcontainerStatus {
+        config_path: Some(normalize_path(&config.to_string_lossy())),
+        container_id: container.as_ref().map(|(id, _)| id.clone()),
+        state: container.map(|(_, state)| state),
+        cli_available: cli_available(),
+    }
+}
+
+/// `devcontainer up --workspace-folder <path>`; returns the workspace folder inside the container.
+fn devcontainer_up(project_path: &str) -> Result<Option<String>, String> {
+    let output = run_command_with_timeout(
+        "devcontainer",
+        &["up", "--workspace-folder", project_path],
+        project_path,
+        &[],
+        UP_TIMEOUT_SECS,
+    )?;
+    // Build logs go to stderr, the result is a JSON line on stdout:
+    // {"outcome":"success","containerId":...,"remoteWorkspaceFolder":...}
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let result: Option<Value> = stdout
+        .lines()
+        .rev()
+        .find_map(|line| serde_json::from_str(line.trim()).ok());
+    let outcome = result.as_ref().and_then(|r| r["outcome"].as_str());
+    if !output.status.success() || outcome.is_some_and(|o| o != "success") {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = result
+            .as_ref()
+            .and_then(|r| r["message"].as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| stderr.lines().rev().take(20).collect::<Vec<_>>().join("\n"));
+        return Err(format!("devcontainer up 失败: {}", message));
+    }
+    Ok(result.and_then(|r| r["remoteWorkspaceFolder"].as_str().map(str::to_string)))
+}
+
+/// `workspaceFolder` from devcontainer.json, or the Dev Containers default `/workspaces/<name>`.
+/// The file is JSONC; when it doesn't parse as plain JSON the default is used.
+fn default_workspace_folder(project: &Path, config: &Path) -> String {
+    let configured = std::fs::read_to_string(config)
+        .ok()
+        .and_then(|s| serde_json::from_str::<Value>(&s).ok())
+        .and_then(|v| v["workspaceFolder"].as_str().map(str::to_string));
+    configured.unwrap_or_else(|| {
+        let name = project
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        format!("/workspaces/{}", name)
+    })
+}
+
+/// Start the project's dev container (when the devcontainer CLI is installed) and open it in
+/// VS Code. Without the CLI, VS Code's Dev Containers extension builds the container itself.
+pub(crate) fn open(project_path: &str) -> Result<(), String> {
+    let project = Path::new(project_path);
+    let config = find_config(project).ok_or_else(|| "该项目没有 devcontainer.json".to_string())?;
+    log::info!(
+        "[devcontainer] Opening {} in its dev container",
+        project_path
+    );
+
+    let remote_folder = if cli_available() {
+        devcontainer_up(project_path)?
+    } else {
+        log::info!("[devcontainer] devcontainer CLI not found, leaving the build to VS Code");
+        None
+    };
+    let remote_folder = remote_folder.unwrap_or_else(|| default_workspace_folder(project, &config));
+
+    let uri = format!(
+        "vscode-remote://dev-container+{}{}",
+        hex::encode(project_path.as_bytes()),
+        remote_folder
+    );
+    Command::new("code")
+        .args(["--folder-uri", &uri])
+        .spawn()
+        .map_err(|e| format!("无法打开 VS Code: {}", e))?;
+    log::info!("[devcontainer] Opened {}", uri);
+    Ok(())
+}
@@ -0,0 +1,110 @@
+//! Zip downloads over the share server: a folder or file inside the shared workspace (e.g.
+//! build artifacts in a worktree) is packed into a temporary zip that is streamed to the
+//! client and removed afterwards.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::config::{get_window_workspace_path, resolve_workspace_path};
+use crate::utils::SKIP_DIRS;
+
+/// Max total size of the files in one download (uncompressed)
+const MAX_DOWNLOAD_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Max number of files in one download
+const MAX_DOWNLOAD_FILES: usize = 50_000;
+
+/// A zip written to a temp file; the file is deleted when this is dropped.
+pub(crate) struct DownloadArchive {
+    pub path: PathBuf,
+    pub file_name: String,
+    pub file_count: usize,
+    /// Total size of the archived files (uncompressed)
+    pub size_bytes: u64,
+}
+
+impl Drop for DownloadArchive {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Canonical path of `path` (absolute, or relative to the session's workspace), which must
+/// exist inside the workspace. Also returns the part relative to the workspace root.
+pub(crate) fn resolve_target(window_label: &str, path: &str) -> Result<(PathBuf, PathBuf), String> {
+    let workspace_path = get_window_workspace_path(window_label).ok_or("No workspace selected")?;
+    let resolved = resolve_workspace_path(window_label, path)?;
+    let root = fs::canonicalize(&workspace_path)
+        .map_err(|e| format!("Failed to resolve workspace {}: {}", workspace_path, e))?;
+    let target = fs::canonicalize(&resolved).map_err(|_| format!("路径不存在: {}", path))?;
+    let rel = target
+        .strip_prefix(&root)
+        .map_err(|_| "只能下载工作区内的文件".to_string())?
+        .to_path_buf();
+    Ok((target, rel))
+}
+
+/// Zip `target` (a folder, recursively, or a single file) under its own name. Symlinks
+/// (e.g. linked node_modules) and VCS folders are skipped.
+pub(crate) fn build_archive(target: &Path) -> Result<DownloadArchive, String> {
+    let name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "download".to_string());
+    let mut archive = DownloadArchive {
+        path: std::env::temp_dir().join(format!("wm-download-{}.zip", uuid::Uuid::new_v4())),
+        file_name: format!("{}.zip", name),
+        file_count: 0,
+        size_bytes: 0,
+    };
+    let file = File::create(&archive.path)
+        .map_err(|e| format!("Failed to create {}: {}", archive.path.display(), e))?;
+    let mut zip = zip::ZipWriter::new(BufWriter::new(file));
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(true);
+
+    let mut pending = vec![(target.to_path_buf(), name)];
+    while let Some((path, entry_name)) = pending.pop() {
+        if path.is_dir() {
+            zip.add_directory(format!("{}/", entry_name), options)
+                .map_err(|e| format!("Failed to add {} to archive: {}", entry_name, e))?;
+            let entries = fs::read_dir(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            for entry in entries.flatten() {
+                let child = entry.path();
+                let child_name = entry.file_name().to_string_lossy().to_string();
+                if child.is_symlink() || SKIP_DIRS.contains(&child_name.as_str()) {
+                    continue;
+                }
+                pending.push((child, format!("{}/{}", entry_name, child_name)));
+            }
+            continue;
+        }
+
+        archive.file_count += 1;
+        archive.size_bytes += fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        if archive.file_count > MAX_DOWNLOAD_FILES {
+            return Err(format!("文件数量超过下载上限 ({})", MAX_DOWNLOAD_FILES));
+        }
+        if archive.size_bytes > MAX_DOWNLOAD_BYTES {
+            return Err(format!(
+                "文件总大小超过下载上限 ({} MB)",
+                MAX_DOWNLOAD_BYTES / 1024 / 1024
+            ));
+        }
+        let mut source =
+            File::open(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        zip.start_file(entry_name.as_str(), options)
+            .map_err(|e| format!("Failed to add {} to archive: {}", entry_name, e))?;
+        std::io::copy(&mut source, &mut zip)
+            .map_err(|e| format!("Failed to write {} to archive: {}", entry_name, e))?;
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finalize archive: {}", e))?
+        .flush()
+        .map_err(|e| format!("Failed to write archive: {}", e))?;
+    Ok(archive)
+}
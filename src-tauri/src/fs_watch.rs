@@ -0,0 +1,569 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use git2::Repository;
+use notify::{EventKind, RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use serde_json::json;
+use tauri::Emitter;
+
+use crate::config::{
+    get_workspace_config_path, load_global_config, load_workspace_config, reload_workspace_config,
+};
+use crate::state::{APP_HANDLE, WINDOW_WORKSPACES, WORKSPACE_EVENT_BROADCAST};
+
+/// Events for the same path within this window are merged into one
+const DEBOUNCE: Duration = Duration::from_millis(300);
+/// Upper bound on changes in one batch; the rest is reported as `truncated`
+const MAX_BATCH: usize = 500;
+/// How often workspace watches are matched against open workspaces and their projects
+const WORKSPACE_RECONCILE_INTERVAL: Duration = Duration::from_secs(5);
+
+struct WorktreeWatch {
+    _watcher: notify::RecommendedWatcher,
+    tx: tokio::sync::broadcast::Sender<String>,
+    subscribers: usize,
+}
+
+/// One watcher per worktree directory, shared by every subscribed client
+static WORKTREE_WATCHES: Lazy<Mutex<HashMap<PathBuf, WorktreeWatch>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Clone, Copy, PartialEq)]
+enum ChangeKind {
+    Created,
+    Modified,
+    Deleted,
+}
+
+impl ChangeKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            ChangeKind::Created => "created",
+            ChangeKind::Modified => "modified",
+            ChangeKind::Deleted => "deleted",
+        }
+    }
+
+    /// Merge a later event into an earlier one; None means the two cancel out
+    fn merge(self, later: ChangeKind) -> Option<ChangeKind> {
+        match (self, later) {
+            (ChangeKind::Created, ChangeKind::Deleted) => None,
+            (ChangeKind::Created, _) => Some(ChangeKind::Created),
+            (ChangeKind::Deleted, ChangeKind::Created) => Some(ChangeKind::Modified),
+            (_, later) => Some(later),
+        }
+    }
+}
+
+fn classify(event: &notify::Event, path: &Path) -> Option<ChangeKind> {
+    match event.kind {
+        EventKind::Create(_) => Some(ChangeKind::Created),
+        EventKind::Remove(_) => Some(ChangeKind::Deleted),
+        // 重命名：源路径视为删除，目标路径视为新建
+        EventKind::Modify(notify::event::ModifyKind::Name(_)) => Some(if path.exists() {
+            ChangeKind::Created
+        } else {
+            ChangeKind::Deleted
+        }),
+        EventKind::Modify(notify::event::ModifyKind::Metadata(_)) => None,
+        EventKind::Modify(_) => Some(ChangeKind::Modified),
+        _ => None,
+    }
+}
+
+/// gitignore-aware filter: paths under `projects/<name>/` are checked against that
+/// project's repository; `.git` internals are always skipped.
+struct IgnoreFilter {
+    root: PathBuf,
+    repos: HashMap<PathBuf, Option<Repository>>,
+}
+
+impl IgnoreFilter {
+    fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            repos: HashMap::new(),
+        }
+    }
+
+    /// Worktree-relative path if the change should be reported
+    fn relevant(&mut self, path: &Path) -> Option<String> {
+        let rel = path.strip_prefix(&self.root).ok()?;
+        if rel
+            .components()
+            .any(|c| matches!(c, Component::Normal(n) if n == ".git"))
+        {
+            return None;
+        }
+
+        let mut comps = rel.components();
+        if let (Some(first), Some(Component::Normal(project))) = (comps.next(), comps.next()) {
+            if first.as_os_str() == "projects" {
+                let project_root = self.root.join("projects").join(project);
+                let in_project = comps.as_path();
+                if !in_project.as_os_str().is_empty() {
+                    let repo = self
+                        .repos
+                        .entry(project_root.clone())
+                        .or_insert_with(|| Repository::open(&project_root).ok());
+                    if let Some(repo) = repo {
+                        if repo.is_path_ignored(in_project).unwrap_or(false) {
+                            return None;
+                        }
+                    }
+                }
+            }
+        }
+
+        Some(rel.to_string_lossy().replace('\\', "/"))
+    }
+}
+
+/// Collect raw notify events, debounce them and publish batches as JSON on `tx`.
+fn run_debouncer(
+    root: PathBuf,
+    rx: mpsc::Receiver<notify::Event>,
+    tx: tokio::sync::broadcast::Sender<String>,
+) {
+    let mut filter = IgnoreFilter::new(&root);
+    // 发送端随 watcher 一起释放，recv 出错即表示已取消订阅
+    while let Ok(first) = rx.recv() {
+        let mut pending: Vec<(String, ChangeKind)> = vec![];
+        let mut index: HashMap<String, usize> = HashMap::new();
+        let mut absorb = |event: notify::Event, pending: &mut Vec<(String, ChangeKind)>| {
+            for path in &event.paths {
+                let Some(kind) = classify(&event, path) else {
+                    continue;
+                };
+                let Some(rel) = filter.relevant(path) else {
+                    continue;
+                };
+                match index.get(&rel) {
+                    Some(&i) => match pending[i].1.merge(kind) {
+                        Some(merged) => pending[i].1 = merged,
+                        None => {
+                            // 先建后删：从本批次中移除
+                            pending.remove(i);
+                            index.remove(&rel);
+                            for v in index.values_mut() {
+                                if *v > i {
+                                    *v -= 1;
+                                }
+                            }
+                        }
+                    },
+                    None => {
+                        index.insert(rel.clone(), pending.len());
+                        pending.push((rel, kind));
+                    }
+                }
+            }
+        };
+
+        absorb(first, &mut pending);
+        let deadline = Instant::now() + DEBOUNCE;
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            match rx.recv_timeout(deadline - now) {
+                Ok(event) => absorb(event, &mut pending),
+                Err(mpsc::RecvTimeoutError::Timeout) => break,
+                Err(mpsc::RecvTimeoutError::Disconnected) => return,
+            }
+        }
+
+        if pending.is_empty() {
+            continue;
+        }
+        let truncated = pending.len() > MAX_BATCH;
+        pending.truncate(MAX_BATCH);
+        let changes: Vec<_> = pending
+            .iter()
+            .map(|(path, kind)| json!({ "path": path, "kind": kind.as_str() }))
+            .collect();
+        let msg = json!({
+            "worktreePath": root.to_string_lossy(),
+            "changes": changes,
+            "truncated": truncated,
+        });
+        let _ = tx.send(msg.to_string());
+    }
+}
+
+/// Subscribe to debounced file changes under a worktree directory.
+/// Each call must be paired with `unsubscribe_worktree_changes`.
+pub(crate) fn subscribe_worktree_changes(
+    worktree_path: &Path,
+) -> Result<tokio::sync::broadcast::Receiver<String>, String> {
+    let mut watches = WORKTREE_WATCHES.lock().unwrap();
+    if let Some(watch) = watches.get_mut(worktree_path) {
+        watch.subscribers += 1;
+        return Ok(watch.tx.subscribe());
+    }
+
+    if !worktree_path.is_dir() {
+        return Err(format!("Worktree 目录不存在: {}", worktree_path.display()));
+    }
+
+    let (raw_tx, raw_rx) = mpsc::channel::<notify::Event>();
+    let mut watcher = notify::RecommendedWatcher::new(
+        move |res: notify::Result<notify::Event>| match res {
+            Ok(event) => {
+                let _ = raw_tx.send(event);
+            }
+            Err(e) => log::warn!("[fs-watch] Watch error: {}", e),
+        },
+        // linked_folders 是软链接（node_modules 等），不跟随
+        notify::Config::default().with_follow_symlinks(false),
+    )
+    .map_err(|e| format!("Failed to create file watcher: {}", e))?;
+    watcher
+        .watch(worktree_path, RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch {}: {}", worktree_path.display(), e))?;
+
+    let (tx, rx) = tokio::sync::broadcast::channel(64);
+    let root = worktree_path.to_path_buf();
+    let publish = tx.clone();
+    std::thread::spawn(move || run_debouncer(root, raw_rx, publish));
+
+    log::info!("[fs-watch] Watching {}", worktree_path.display());
+    watches.insert(
+        worktree_path.to_path_buf(),
+        WorktreeWatch {
+            _watcher: watcher,
+            tx,
+            subscribers: 1,
+        },
+    );
+    Ok(rx)
+}
+
+/// Drop one subscription; the watcher stops once nobody is listening.
+pub(crate) fn unsubscribe_worktree_changes(worktree_path: &Path) {
+    let mut watches = WORKTREE_WATCHES.lock().unwrap();
+    if let Some(watch) = watches.get_mut(worktree_path) {
+        watch.subscribers = watch.subscribers.saturating_sub(1);
+        if watch.subscribers == 0 {
+            watches.remove(worktree_path);
+            log::info!("[fs-watch] Stopped watching {}", worktree_path.display());
+        }
+    }
+}
+
+/// Part of a workspace that changed on disk; clients reload the matching data
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum WorkspaceChange {
+    /// A worktree directory appeared, disappeared or was renamed (archive / restore)
+    Worktrees,
+    /// A project directory appeared or disappeared
+    Projects,
+    /// `.worktree-manager.json` was edited outside the app
+    Config,
+    /// HEAD or index of a project's main checkout (worktree checkouts are reported one by one
+    /// as `worktree_status_changed`)
+    Git,
+}
+
+impl WorkspaceChange {
+    fn as_str(self) -> &'static str {
+        match self {
+            WorkspaceChange::Worktrees => "worktrees",
+            WorkspaceChange::Projects => "projects",
+            WorkspaceChange::Config => "config",
+            WorkspaceChange::Git => "git",
+        }
+    }
+}
+
+struct WorkspaceWatch {
+    watcher: notify::RecommendedWatcher,
+    root: PathBuf,
+    config_path: PathBuf,
+    worktrees_dir: String,
+    /// Directories currently registered with `watcher`
+    watched: HashSet<PathBuf>,
+}
+
+/// The workspace root, the worktrees and projects directories (all non-recursive) and, per
+/// project, its `.git` directory plus `.git/worktrees` where linked worktrees keep HEAD and index.
+/// Only existing directories are returned; the rest is picked up on a later reconcile.
+fn workspace_watch_targets(root: &Path, worktrees_dir: &str) -> Vec<(PathBuf, RecursiveMode)> {
+    let mut targets = vec![
+        (root.to_path_buf(), RecursiveMode::NonRecursive),
+        (root.join(worktrees_dir), RecursiveMode::NonRecursive),
+        (root.join("projects"), RecursiveMode::NonRecursive),
+    ];
+    if let Ok(entries) = std::fs::read_dir(root.join("projects")) {
+        for entry in entries.flatten() {
+            let git_dir = entry.path().join(".git");
+            targets.push((git_dir.join("worktrees"), RecursiveMode::Recursive));
+            targets.push((git_dir, RecursiveMode::NonRecursive));
+        }
+    }
+    targets.retain(|(path, _)| path.is_dir());
+    targets
+}
+
+impl WorkspaceWatch {
+    fn new(
+        workspace_path: &str,
+        tx: mpsc::Sender<(String, notify::Event)>,
+    ) -> Result<Self, String> {
+        let workspace = workspace_path.to_string();
+        let watcher = notify::RecommendedWatcher::new(
+            move |res: notify::Result<notify::Event>| match res {
+                Ok(event) => {
+                    let _ = tx.send((workspace.clone(), event));
+                }
+                Err(e) => log::warn!("[fs-watch] Watch error: {}", e),
+            },
+            notify::Config::default().with_follow_symlinks(false),
+        )
+        .map_err(|e| format!("Failed to create file watcher: {}", e))?;
+        Ok(Self {
+            watcher,
+            root: PathBuf::from(workspace_path),
+            config_path: get_workspace_config_path(workspace_path),
+            worktrees_dir: String::new(),
+            watched: HashSet::new(),
+        })
+    }
+
+    /// Register new directories (projects cloned, worktrees_dir changed) and drop vanished ones
+    fn sync_targets(&mut self, worktrees_dir: &str) {
+        self.worktrees_dir = worktrees_dir.to_string();
+        let targets = workspace_watch_targets(&self.root, worktrees_dir);
+        let wanted: HashSet<PathBuf> = targets.iter().map(|(path, _)| path.clone()).collect();
+        for path in self.watched.difference(&wanted) {
+            let _ = self.watcher.unwatch(path);
+        }
+        self.watched.retain(|path| wanted.contains(path));
+        for (path, mode) in targets {
+            if self.watched.contains(&path) {
+                continue;
+            }
+            match self.watcher.watch(&path, mode) {
+                Ok(()) => {
+                    self.watched.insert(path);
+                }
+                Err(e) => log::warn!("[fs-watch] Failed to watch {}: {}", path.display(), e),
+            }
+        }
+    }
+
+    fn classify(&self, event: &notify::Event, path: &Path) -> Option<WorkspaceChange> {
+        if path == self.config_path {
+            return Some(WorkspaceChange::Config);
+        }
+        let rel = path.strip_prefix(&self.root).ok()?;
+        let structural = matches!(
+            event.kind,
+            EventKind::Create(_)
+                | EventKind::Remove(_)
+                | EventKind::Modify(notify::event::ModifyKind::Name(_))
+        );
+        let in_dir = |dir: &Path| rel == dir || rel.parent() == Some(dir);
+        if structural && in_dir(Path::new(&self.worktrees_dir)) {
+            return Some(WorkspaceChange::Worktrees);
+        }
+        if structural && in_dir(Path::new("projects")) {
+            return Some(WorkspaceChange::Projects);
+        }
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        let parts: Vec<&str> = rel.split('/').collect();
+        match parts.as_slice() {
+            ["projects", _, ".git", "HEAD" | "index"] => Some(WorkspaceChange::Git),
+            _ => None,
+        }
+    }
+
+    /// (worktree, project) whose HEAD or index changed, from `.git/worktrees/<id>/{HEAD,index}`.
+    /// The id is git's, so the checkout is found through the `gitdir` file next to it, which
+    /// points at `<worktrees_dir>/<worktree>/projects/<project>/.git`.
+    fn worktree_checkout(&self, path: &Path) -> Option<(String, String)> {
+        let file_name = path.file_name()?;
+        if file_name != "HEAD" && file_name != "index" {
+            return None;
+        }
+        let admin_dir = path.parent()?;
+        let rel = admin_dir.strip_prefix(self.root.join("projects")).ok()?;
+        let mut comps = rel.components();
+        let (Some(_), Some(git), Some(worktrees), Some(_), None) = (
+            comps.next(),
+            comps.next(),
+            comps.next(),
+            comps.next(),
+            comps.next(),
+        ) else {
+            return None;
+        };
+        if git.as_os_str() != ".git" || worktrees.as_os_str() != "worktrees" {
+            return None;
+        }
+        let gitdir = std::fs::read_to_string(admin_dir.join("gitdir")).ok()?;
+        let checkout = Path::new(gitdir.trim()).parent()?;
+        let rel = checkout
+            .strip_prefix(self.root.join(&self.worktrees_dir))
+            .ok()?;
+        let parts: Vec<String> = rel
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        match parts.as_slice() {
+            [worktree, projects, project] if projects == "projects" => {
+                Some((worktree.clone(), project.clone()))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct PendingChanges {
+    changes: BTreeSet<WorkspaceChange>,
+    /// (worktree, project) checkouts whose HEAD or index changed
+    checkouts: BTreeSet<(String, String)>,
+}
+
+/// Start or stop watches so they follow the workspaces bound to windows and web sessions
+fn reconcile_workspace_watches(
+    watches: &mut HashMap<String, WorkspaceWatch>,
+    tx: &mpsc::Sender<(String, notify::Event)>,
+) {
+    // Workspaces bound to a window or web session, plus the default one
+    let mut open: HashSet<String> = WINDOW_WORKSPACES
+        .lock()
+        .map(|map| map.values().cloned().collect())
+        .unwrap_or_default();
+    open.extend(load_global_config().current_workspace);
+    watches.retain(|workspace_path, _| {
+        let keep = open.contains(workspace_path);
+        if !keep {
+            log::info!("[fs-watch] Stopped watching workspace {}", workspace_path);
+        }
+        keep
+    });
+    for workspace_path in open {
+        if !watches.contains_key(&workspace_path) {
+            match WorkspaceWatch::new(&workspace_path, tx.clone()) {
+                Ok(watch) => {
+                    log::info!("[fs-watch] Watching workspace {}", workspace_path);
+                    watches.insert(workspace_path.clone(), watch);
+                }
+                Err(e) => {
+                    log::warn!("[fs-watch] {}", e);
+                    continue;
+                }
+            }
+        }
+        let worktrees_dir = load_workspace_config(&workspace_path).worktrees_dir;
+        if let Some(watch) = watches.get_mut(&workspace_path) {
+            watch.sync_targets(&worktrees_dir);
+        }
+    }
+}
+
+fn broadcast(event: &str, payload: serde_json::Value) {
+    let _ = WORKSPACE_EVENT_BROADCAST.send(payload.to_string());
+    if let Some(app) = APP_HANDLE.lock().ok().and_then(|h| h.as_ref().cloned()) {
+        let _ = app.emit(event, payload);
+    }
+}
+
+/// Tauri event `workspace-fs-changed` / WebSocket `workspace_fs_changed`, plus one
+/// `worktree-status-changed` / `worktree_status_changed` per changed worktree checkout so
+/// clients can refresh that project alone. Config changes are only reported when the file
+/// differs from the cache, so the app's own saves stay silent.
+fn publish_workspace_changes(workspace_path: &str, pending: PendingChanges) {
+    for (worktree, project) in &pending.checkouts {
+        broadcast(
+            "worktree-status-changed",
+            json!({
+                "type": "worktree_status_changed",
+                "workspacePath": workspace_path,
+                "worktreeName": worktree,
+                "projectName": project,
+            }),
+        );
+    }
+
+    let changes: Vec<&str> = pending
+        .changes
+        .into_iter()
+        .filter(|c| *c != WorkspaceChange::Config || reload_workspace_config(workspace_path))
+        .map(WorkspaceChange::as_str)
+        .collect();
+    if changes.is_empty() {
+        return;
+    }
+    log::debug!(
+        "[fs-watch] Workspace {} changed: {:?}",
+        workspace_path,
+        changes
+    );
+    broadcast(
+        "workspace-fs-changed",
+        json!({
+            "type": "workspace_fs_changed",
+            "workspacePath": workspace_path,
+            "changes": changes,
+        }),
+    );
+}
+
+/// Background thread watching the structure of every open workspace: worktree and project
+/// directories, HEAD / index of each checkout and the workspace config file.
+pub(crate) fn start_workspace_watcher() {
+    std::thread::spawn(|| {
+        let (tx, rx) = mpsc::channel::<(String, notify::Event)>();
+        let mut watches: HashMap<String, WorkspaceWatch> = HashMap::new();
+        let mut pending: HashMap<String, PendingChanges> = HashMap::new();
+        let mut flush_at: Option<Instant> = None;
+        let mut next_reconcile = Instant::now();
+        loop {
+            if Instant::now() >= next_reconcile {
+                reconcile_workspace_watches(&mut watches, &tx);
+                next_reconcile = Instant::now() + WORKSPACE_RECONCILE_INTERVAL;
+            }
+            if flush_at.is_some_and(|at| Instant::now() >= at) {
+                flush_at = None;
+                for (workspace_path, changes) in pending.drain() {
+                    // New projects need their .git directories watched right away
+                    if changes.changes.contains(&WorkspaceChange::Projects) {
+                        next_reconcile = Instant::now();
+                    }
+                    publish_workspace_changes(&workspace_path, changes);
+                }
+            }
+
+            let wake_at = flush_at.map_or(next_reconcile, |at| at.min(next_reconcile));
+            let (workspace_path, event) =
+                match rx.recv_timeout(wake_at.saturating_duration_since(Instant::now())) {
+                    Ok(received) => received,
+                    Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                    Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                };
+            let Some(watch) = watches.get(&workspace_path) else {
+                continue;
+            };
+            for path in &event.paths {
+                if let Some(checkout) = watch.worktree_checkout(path) {
+                    let entry = pending.entry(workspace_path.clone()).or_default();
+                    entry.checkouts.insert(checkout);
+                } else if let Some(change) = watch.classify(&event, path) {
+                    let entry = pending.entry(workspace_path.clone()).or_default();
+                    entry.changes.insert(change);
+                } else {
+                    continue;
+                }
+                flush_at.get_or_insert_with(|| Instant::now() + DEBOUNCE);
+            }
+        }
+    });
+}
@@ -1,8 +1,11 @@
 use git2::{Repository, StatusOptions};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::process::Command;
 
+use crate::oplog::LoggedCommand;
+use crate::utils::{begin_git_operation, path_matches_any};
+
 /// Helper function to find the main worktree path for a given repository
 fn find_main_worktree(repo_path: &Path) -> Option<std::path::PathBuf> {
     let git_path = repo_path.join(".git");
@@ -54,7 +57,7 @@ fn handle_branch_checkout_conflict(
                 .arg(main_worktree_path)
                 .arg("status")
                 .arg("--porcelain")
-                .output()
+                .logged_output()
                 .map_err(|e| format!("检查主工作区 git status 失败: {}", e))?;
 
             let status_str = String::from_utf8_lossy(&status_output.stdout);
@@ -89,7 +92,7 @@ fn handle_branch_checkout_conflict(
                 .arg("checkout")
                 .arg("--detach")
                 .arg(&commit_sha)
-                .output()
+                .logged_output()
                 .map_err(|e| format!("执行 git checkout --detach 失败: {}", e))?;
 
             if !checkout_output.status.success() {
@@ -116,9 +119,14 @@ fn handle_branch_checkout_conflict(
 pub struct WorktreeInfo {
     pub current_branch: String,
     pub uncommitted_count: usize,
+    pub staged_count: usize,
+    pub modified_count: usize,
+    pub untracked_count: usize,
     pub is_merged_to_test: bool,
     pub ahead_of_base: usize,
     pub behind_base: usize,
+    /// Most modified files differ from the index only in line endings (autocrlf mismatch)
+    pub line_ending_mismatch: bool,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -127,6 +135,11 @@ pub struct BranchStatus {
     pub branch_name: String,
     pub has_uncommitted: bool,
     pub uncommitted_count: usize,
+    pub staged_count: usize,
+    pub modified_count: usize,
+    pub untracked_count: usize,
+    /// Uncommitted entries matching the workspace's archive ignore patterns
+    pub ignorable_count: usize,
     pub is_pushed: bool,
     pub unpushed_commits: usize,
     pub has_merge_request: bool,
@@ -138,14 +151,93 @@ impl Default for WorktreeInfo {
         Self {
             current_branch: "unknown".to_string(),
             uncommitted_count: 0,
+            staged_count: 0,
+            modified_count: 0,
+            untracked_count: 0,
             is_merged_to_test: false,
             ahead_of_base: 0,
             behind_base: 0,
+            line_ending_mismatch: false,
         }
     }
 }
 
-pub fn get_worktree_info(path: &Path) -> WorktreeInfo {
+/// Build the status options shared by the status scanners.
+///
+/// Ignored files are never reported and untracked directories are not walked.
+/// `status_excludes` are gitignore-style patterns from the project config; they are
+/// registered as in-memory ignore rules on `repo` so libgit2 skips those paths
+/// entirely instead of scanning and filtering afterwards.
+fn build_status_options(repo: &Repository, status_excludes: &[String]) -> StatusOptions {
+    let rules: Vec<&str> = status_excludes
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty() && !p.starts_with('#'))
+        .collect();
+    if !rules.is_empty() {
+        if let Err(e) = repo.add_ignore_rule(&rules.join("\n")) {
+            log::warn!("[git] Failed to apply status excludes {:?}: {}", rules, e);
+        }
+    }
+
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(false)
+        .include_ignored(false)
+        .recurse_ignored_dirs(false);
+    opts
+}
+
+/// Per-category breakdown of a status scan.
+/// An entry that is both staged and modified in the working tree counts toward both.
+#[derive(Debug, Default)]
+struct StatusBreakdown {
+    total: usize,
+    staged: usize,
+    modified: usize,
+    untracked: usize,
+    ignorable: usize,
+}
+
+fn summarize_statuses(statuses: &git2::Statuses, ignore_patterns: &[String]) -> StatusBreakdown {
+    let staged_mask = git2::Status::INDEX_NEW
+        | git2::Status::INDEX_MODIFIED
+        | git2::Status::INDEX_DELETED
+        | git2::Status::INDEX_RENAMED
+        | git2::Status::INDEX_TYPECHANGE;
+    let modified_mask = git2::Status::WT_MODIFIED
+        | git2::Status::WT_DELETED
+        | git2::Status::WT_RENAMED
+        | git2::Status::WT_TYPECHANGE
+        | git2::Status::CONFLICTED;
+
+    let mut breakdown = StatusBreakdown {
+        total: statuses.len(),
+        ..Default::default()
+    };
+    for entry in statuses.iter() {
+        let status = entry.status();
+        if status.intersects(staged_mask) {
+            breakdown.staged += 1;
+        }
+        if status.intersects(modified_mask) {
+            breakdown.modified += 1;
+        }
+        if status.contains(git2::Status::WT_NEW) {
+            breakdown.untracked += 1;
+        }
+        if !ignore_patterns.is_empty() {
+            if let Some(p) = entry.path() {
+                if path_matches_any(p, ignore_patterns) {
+                    breakdown.ignorable += 1;
+                }
+            }
+        }
+    }
+    breakdown
+}
+
+pub fn get_worktree_info(path: &Path, status_excludes: &[String], remote: &str) -> WorktreeInfo {
     let repo = match Repository::open(path) {
         Ok(r) => r,
         Err(_) => return WorktreeInfo::default(),
@@ -161,17 +253,24 @@ pub fn get_worktree_info(path: &Path) -> WorktreeInfo {
     }
 
     // Get uncommitted changes count
-    let mut opts = StatusOptions::new();
-    opts.include_untracked(true).recurse_untracked_dirs(false);
+    let mut opts = build_status_options(&repo, status_excludes);
 
     if let Ok(statuses) = repo.statuses(Some(&mut opts)) {
-        info.uncommitted_count = statuses.len();
+        let breakdown = summarize_statuses(&statuses, &[]);
+        info.uncommitted_count = breakdown.total;
+        info.staged_count = breakdown.staged;
+        info.modified_count = breakdown.modified;
+        info.untracked_count = breakdown.untracked;
+        if breakdown.modified > 0 {
+            let (sampled, eol_only) = scan_eol_only_changes(&repo, &statuses, EOL_SAMPLE_SIZE);
+            info.line_ending_mismatch = eol_only > 0 && eol_only * 2 >= sampled;
+        }
     }
 
     // Check if merged to test branch
     // This is a simplified check - just see if test branch ref exists and compare
     let test_branch = get_test_branch_for_path(path);
-    if let Ok(test_ref) = repo.find_reference(&format!("refs/remotes/origin/{}", test_branch)) {
+    if let Ok(test_ref) = repo.find_reference(&format!("refs/remotes/{}/{}", remote, test_branch)) {
         if let Ok(head) = repo.head() {
             if let (Ok(test_commit), Ok(head_commit)) =
                 (test_ref.peel_to_commit(), head.peel_to_commit())
@@ -188,7 +287,7 @@ pub fn get_worktree_info(path: &Path) -> WorktreeInfo {
 
     // Get ahead/behind count relative to base branch
     let base_branch = get_base_branch_for_path(path);
-    if let Ok(base_ref) = repo.find_reference(&format!("refs/remotes/origin/{}", base_branch)) {
+    if let Some(base_ref) = find_base_ref(&repo, remote, base_branch) {
         if let Ok(head) = repo.head() {
             if let (Ok(base_oid), Ok(head_oid)) =
                 (base_ref.target().ok_or(()), head.target().ok_or(()))
@@ -204,6 +303,500 @@ pub fn get_worktree_info(path: &Path) -> WorktreeInfo {
     info
 }
 
+/// How many modified files `get_worktree_info` inspects when looking for line-ending-only changes
+const EOL_SAMPLE_SIZE: usize = 50;
+/// Larger files are not compared
+const EOL_MAX_FILE_BYTES: u64 = 512 * 1024;
+
+fn strip_cr(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len());
+    for (i, &b) in bytes.iter().enumerate() {
+        if b == b'\r' && bytes.get(i + 1) == Some(&b'\n') {
+            continue;
+        }
+        out.push(b);
+    }
+    out
+}
+
+/// Whether the working-tree file differs from its index entry only in CRLF vs LF.
+fn is_eol_only_change(repo: &Repository, index: &git2::Index, rel_path: &str) -> bool {
+    let Some(workdir) = repo.workdir() else {
+        return false;
+    };
+    let file = workdir.join(rel_path);
+    match std::fs::metadata(&file) {
+        Ok(meta) if meta.is_file() && meta.len() <= EOL_MAX_FILE_BYTES => {}
+        _ => return false,
+    }
+    let Some(entry) = index.get_path(Path::new(rel_path), 0) else {
+        return false;
+    };
+    let (Ok(blob), Ok(working)) = (repo.find_blob(entry.id), std::fs::read(&file)) else {
+        return false;
+    };
+    let indexed = blob.content();
+    if indexed == working.as_slice() || blob.is_binary() {
+        return false;
+    }
+    strip_cr(indexed) == strip_cr(&working)
+}
+
+/// Inspect up to `limit` working-tree modifications; returns (inspected, line-ending-only).
+fn scan_eol_only_changes(
+    repo: &Repository,
+    statuses: &git2::Statuses,
+    limit: usize,
+) -> (usize, usize) {
+    let Ok(index) = repo.index() else {
+        return (0, 0);
+    };
+    let mut inspected = 0;
+    let mut eol_only = 0;
+    for entry in statuses.iter() {
+        if inspected >= limit {
+            break;
+        }
+        if !entry.status().contains(git2::Status::WT_MODIFIED) {
+            continue;
+        }
+        let Some(p) = entry.path() else { continue };
+        inspected += 1;
+        if is_eol_only_change(repo, &index, p) {
+            eol_only += 1;
+        }
+    }
+    (inspected, eol_only)
+}
+
+/// Repair phantom changes caused by autocrlf mismatches: files whose only difference from
+/// the index is line endings are checked out again so they match the current settings.
+/// Files with real edits are never touched. Returns how many files no longer show as modified.
+pub fn normalize_line_endings(path: &Path) -> Result<usize, String> {
+    let _guard = begin_git_operation("normalize_line_endings")?;
+    // 先刷新 index 的 stat 信息，去掉仅因时间戳变化而显示的修改
+    let _ = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["update-index", "-q", "--refresh"])
+        .logged_output();
+
+    let repo = Repository::open(path).map_err(|e| format!("Failed to open repository: {}", e))?;
+    let index = repo
+        .index()
+        .map_err(|e| format!("Failed to read index: {}", e))?;
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(false).include_ignored(false);
+    let statuses = repo
+        .statuses(Some(&mut opts))
+        .map_err(|e| format!("Failed to get status: {}", e))?;
+    let files: Vec<String> = statuses
+        .iter()
+        .filter(|e| e.status().contains(git2::Status::WT_MODIFIED))
+        .filter_map(|e| e.path().map(|p| p.to_string()))
+        .filter(|p| is_eol_only_change(&repo, &index, p))
+        .collect();
+    if files.is_empty() {
+        return Ok(0);
+    }
+
+    for chunk in files.chunks(100) {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(path)
+            .args(["checkout", "--"])
+            .args(chunk)
+            .logged_output()
+            .map_err(|e| format!("Failed to execute git checkout: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "git checkout failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+    }
+    // 若差异来自 .gitattributes/autocrlf 本身，重新检出后仍会显示为修改
+    let still_modified = Repository::open(path)
+        .ok()
+        .map(|repo| {
+            files
+                .iter()
+                .filter(|f| {
+                    repo.status_file(Path::new(f))
+                        .map(|s| s.contains(git2::Status::WT_MODIFIED))
+                        .unwrap_or(false)
+                })
+                .count()
+        })
+        .unwrap_or(0);
+    let fixed = files.len() - still_modified;
+    log::info!(
+        "[git] Normalized line endings in {}: {} fixed, {} still modified",
+        path.display(),
+        fixed,
+        still_modified
+    );
+    Ok(fixed)
+}
+
+/// Uncommitted paths (same scan rules as `get_worktree_info`), sorted.
+pub fn get_dirty_files(path: &Path, status_excludes: &[String]) -> Vec<String> {
+    let repo = match Repository::open(path) {
+        Ok(r) => r,
+        Err(_) => return vec![],
+    };
+    let mut opts = build_status_options(&repo, status_excludes);
+    let mut files: Vec<String> = repo
+        .statuses(Some(&mut opts))
+        .map(|statuses| {
+            statuses
+                .iter()
+                .filter_map(|e| e.path().map(|p| p.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+fn validate_rel_paths(paths: &[String]) -> Result<(), String> {
+    for p in paths {
+        if p.is_empty()
+            || p.starts_with('-')
+            || Path::new(p).is_absolute()
+            || p.split(['/', '\\']).any(|c| c == "..")
+        {
+            return Err(format!("Invalid file path: {}", p));
+        }
+    }
+    Ok(())
+}
+
+/// Whether `file` is one of `selectors` or lies under one of them (directory prefix)
+fn matches_selectors(file: &str, selectors: &[String]) -> bool {
+    selectors.iter().any(|s| {
+        let s = s.trim_end_matches('/');
+        file == s || file.starts_with(&format!("{}/", s))
+    })
+}
+
+/// Revert tracked changes (staged and unstaged) back to HEAD.
+///
+/// `files` are repo-relative files or directories; empty means every tracked change.
+/// Untracked files are left alone (see `clean_untracked`). With `dry_run` nothing is
+/// touched. Returns the affected files.
+pub fn discard_changes(path: &Path, files: &[String], dry_run: bool) -> Result<Vec<String>, String> {
+    validate_rel_paths(files)?;
+    let _guard = if dry_run {
+        None
+    } else {
+        Some(begin_git_operation("discard_changes")?)
+    };
+
+    let repo = Repository::open(path).map_err(|e| format!("Failed to open repository: {}", e))?;
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(false).include_ignored(false);
+    let statuses = repo
+        .statuses(Some(&mut opts))
+        .map_err(|e| format!("Failed to get status: {}", e))?;
+    let mut targets: Vec<String> = statuses
+        .iter()
+        .filter(|e| !e.status().is_conflicted())
+        .filter_map(|e| e.path().map(|p| p.to_string()))
+        .filter(|p| files.is_empty() || matches_selectors(p, files))
+        .collect();
+    targets.sort();
+    targets.dedup();
+    if dry_run || targets.is_empty() {
+        return Ok(targets);
+    }
+
+    log::info!(
+        "[git] Discarding changes in {}: {} files",
+        path.display(),
+        targets.len()
+    );
+    for chunk in targets.chunks(100) {
+        // --staged --worktree：同时还原 index 和工作区；HEAD 中不存在的新增文件会被删除
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(path)
+            .args(["restore", "--source=HEAD", "--staged", "--worktree", "--"])
+            .args(chunk)
+            .logged_output()
+            .map_err(|e| format!("Failed to execute git restore: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "git restore failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+    }
+    Ok(targets)
+}
+
+/// Remove untracked files and directories (ignored files are kept).
+///
+/// `paths` limit the clean to those repo-relative paths; empty means the whole
+/// checkout. With `dry_run` nothing is removed. Returns what was (or would be) removed.
+pub fn clean_untracked(path: &Path, paths: &[String], dry_run: bool) -> Result<Vec<String>, String> {
+    validate_rel_paths(paths)?;
+    let _guard = if dry_run {
+        None
+    } else {
+        Some(begin_git_operation("clean_untracked")?)
+    };
+
+    let mut cmd = Command::new("git");
+    cmd.arg("-C").arg(path).args(["clean", "-d"]);
+    cmd.arg(if dry_run { "--dry-run" } else { "--force" });
+    cmd.arg("--").args(paths);
+    let output = cmd
+        .logged_output()
+        .map_err(|e| format!("Failed to execute git clean: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git clean failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let prefix = if dry_run { "Would remove " } else { "Removing " };
+    let removed: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|l| l.strip_prefix(prefix))
+        .map(|l| l.to_string())
+        .collect();
+    if !dry_run {
+        log::info!(
+            "[git] Cleaned {} untracked entries in {}",
+            removed.len(),
+            path.display()
+        );
+    }
+    Ok(removed)
+}
+
+/// Detect the remote's default branch from `refs/remotes/<remote>/HEAD`,
+/// falling back to <remote>/main or <remote>/master when the symbolic ref isn't set.
+pub fn detect_default_branch(path: &Path, remote: &str) -> Option<String> {
+    let repo = Repository::open(path).ok()?;
+    detect_default_branch_in(&repo, remote)
+}
+
+fn detect_default_branch_in(repo: &Repository, remote: &str) -> Option<String> {
+    let prefix = format!("refs/remotes/{}/", remote);
+    if let Ok(head) = repo.find_reference(&format!("{}HEAD", prefix)) {
+        if let Some(target) = head.symbolic_target() {
+            if let Some(name) = target.strip_prefix(&prefix) {
+                return Some(name.to_string());
+            }
+        }
+    }
+    ["main", "master"]
+        .into_iter()
+        .find(|b| repo.find_reference(&format!("{}{}", prefix, b)).is_ok())
+        .map(|b| b.to_string())
+}
+
+/// Resolve `<remote>/<base_branch>`, or the remote default branch if the configured
+/// base doesn't exist on the remote (e.g. config says "uat" but the repo only has "main").
+fn find_base_ref<'r>(
+    repo: &'r Repository,
+    remote: &str,
+    base_branch: &str,
+) -> Option<git2::Reference<'r>> {
+    if let Ok(r) = repo.find_reference(&format!("refs/remotes/{}/{}", remote, base_branch)) {
+        return Some(r);
+    }
+    let fallback = detect_default_branch_in(repo, remote)?;
+    repo.find_reference(&format!("refs/remotes/{}/{}", remote, fallback))
+        .ok()
+        // <remote>/HEAD is symbolic; resolve so target() yields an oid
+        .and_then(|r| r.resolve().ok())
+}
+
+/// Commits on <remote>/<base> (or the remote default branch) not yet in HEAD.
+pub fn behind_base_count(path: &Path, remote: &str, base_branch: &str) -> Option<usize> {
+    let repo = Repository::open(path).ok()?;
+    let base_oid = find_base_ref(&repo, remote, base_branch)?.target()?;
+    let head_oid = repo.head().ok()?.target()?;
+    repo.graph_ahead_behind(head_oid, base_oid)
+        .ok()
+        .map(|(_, behind)| behind)
+}
+
+/// Committer time (unix seconds) of HEAD, without touching the working tree.
+pub fn head_commit_time(path: &Path) -> Option<i64> {
+    let repo = Repository::open(path).ok()?;
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+    Some(commit.time().seconds())
+}
+
+/// Whether the checkout declares Git LFS filters in its top-level `.gitattributes`.
+pub fn uses_lfs(path: &Path) -> bool {
+    std::fs::read_to_string(path.join(".gitattributes"))
+        .map(|content| {
+            content
+                .lines()
+                .any(|l| !l.trim_start().starts_with('#') && l.contains("filter=lfs"))
+        })
+        .unwrap_or(false)
+}
+
+/// `git lfs install --local` + `git lfs pull`, replacing pointer files with content.
+pub fn pull_lfs_objects(path: &Path) -> Result<(), String> {
+    let version = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["lfs", "version"])
+        .logged_output();
+    if !matches!(&version, Ok(o) if o.status.success()) {
+        return Err("仓库使用了 Git LFS，但未安装 git-lfs".to_string());
+    }
+
+    for args in [&["lfs", "install", "--local"][..], &["lfs", "pull"][..]] {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(path)
+            .args(args)
+            .logged_output()
+            .map_err(|e| format!("Failed to execute git {}: {}", args.join(" "), e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "git {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// `git sparse-checkout set` so only the given directories stay in the working tree.
+pub fn set_sparse_checkout(path: &Path, sparse_paths: &[String]) -> Result<(), String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["sparse-checkout", "set"])
+        .args(sparse_paths)
+        .logged_output()
+        .map_err(|e| format!("Failed to execute git sparse-checkout: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git sparse-checkout set failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// `git worktree lock` / `unlock` on a linked worktree of `main_repo`.
+/// Already being in the requested state is not an error.
+pub fn set_worktree_locked(
+    main_repo: &Path,
+    worktree: &Path,
+    locked: bool,
+    reason: &str,
+) -> Result<(), String> {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C").arg(main_repo).arg("worktree");
+    if locked {
+        cmd.args(["lock", "--reason", reason]);
+    } else {
+        cmd.arg("unlock");
+    }
+    let output = cmd
+        .arg(worktree)
+        .logged_output()
+        .map_err(|e| format!("Failed to execute git worktree lock: {}", e))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("already locked") || stderr.contains("is not locked") {
+        return Ok(());
+    }
+    Err(format!(
+        "git worktree {} failed: {}",
+        if locked { "lock" } else { "unlock" },
+        stderr.trim()
+    ))
+}
+
+/// `git worktree prune -v`; returns git's description of each pruned entry.
+pub fn prune_worktrees(main_repo: &Path) -> Result<Vec<String>, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(main_repo)
+        .args(["worktree", "prune", "-v"])
+        .logged_output()
+        .map_err(|e| format!("Failed to execute git worktree prune: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git worktree prune failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    // -v reports on stderr on some git versions, stdout on others
+    let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    Ok(text
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect())
+}
+
+/// Paths of the linked worktrees registered in `main_repo` (the main checkout excluded).
+pub fn registered_worktree_paths(main_repo: &Path) -> Result<Vec<std::path::PathBuf>, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(main_repo)
+        .args(["worktree", "list", "--porcelain"])
+        .logged_output()
+        .map_err(|e| format!("Failed to execute git worktree list: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git worktree list failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    // The first entry is always the main worktree
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|l| l.strip_prefix("worktree "))
+        .skip(1)
+        .map(std::path::PathBuf::from)
+        .collect())
+}
+
+/// `git worktree repair <worktree>`: reconnect a linked worktree whose admin files
+/// still exist in `main_repo` but whose paths no longer match (moved or copied directories).
+pub fn repair_worktree(main_repo: &Path, worktree: &Path) -> Result<(), String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(main_repo)
+        .args(["worktree", "repair"])
+        .arg(worktree)
+        .logged_output()
+        .map_err(|e| format!("Failed to execute git worktree repair: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(())
+}
+
+/// Whether `<remote>/<branch>` is known locally (as of the last fetch).
+pub fn remote_branch_known(path: &Path, remote: &str, branch: &str) -> bool {
+    Repository::open(path)
+        .map(|repo| {
+            repo.find_reference(&format!("refs/remotes/{}/{}", remote, branch))
+                .is_ok()
+        })
+        .unwrap_or(false)
+}
+
 fn get_base_branch_for_path(_path: &Path) -> &str {
     "uat"
 }
@@ -212,12 +805,22 @@ fn get_test_branch_for_path(_path: &Path) -> &str {
     "test"
 }
 
-pub fn get_branch_status(path: &Path, project_name: &str) -> BranchStatus {
+pub fn get_branch_status(
+    path: &Path,
+    project_name: &str,
+    status_excludes: &[String],
+    archive_ignore_patterns: &[String],
+    remote: &str,
+) -> BranchStatus {
     let mut status = BranchStatus {
         project_name: project_name.to_string(),
         branch_name: "unknown".to_string(),
         has_uncommitted: false,
         uncommitted_count: 0,
+        staged_count: 0,
+        modified_count: 0,
+        untracked_count: 0,
+        ignorable_count: 0,
         is_pushed: false,
         unpushed_commits: 0,
         has_merge_request: false,
@@ -237,22 +840,26 @@ pub fn get_branch_status(path: &Path, project_name: &str) -> BranchStatus {
     }
 
     // Get uncommitted changes
-    let mut opts = StatusOptions::new();
-    opts.include_untracked(true).recurse_untracked_dirs(false);
+    let mut opts = build_status_options(&repo, status_excludes);
     if let Ok(statuses) = repo.statuses(Some(&mut opts)) {
-        status.uncommitted_count = statuses.len();
+        let breakdown = summarize_statuses(&statuses, archive_ignore_patterns);
+        status.uncommitted_count = breakdown.total;
+        status.staged_count = breakdown.staged;
+        status.modified_count = breakdown.modified;
+        status.untracked_count = breakdown.untracked;
+        status.ignorable_count = breakdown.ignorable;
         status.has_uncommitted = status.uncommitted_count > 0;
     }
 
     // Get remote URL
-    if let Ok(remote) = repo.find_remote("origin") {
-        if let Some(url) = remote.url() {
+    if let Ok(git_remote) = repo.find_remote(remote) {
+        if let Some(url) = git_remote.url() {
             status.remote_url = url.to_string();
         }
     }
 
-    // Check if branch is pushed to remote (compare with origin/branch)
-    let remote_branch = format!("refs/remotes/origin/{}", status.branch_name);
+    // Check if branch is pushed to remote (compare with <remote>/branch)
+    let remote_branch = format!("refs/remotes/{}/{}", remote, status.branch_name);
     if let Ok(head) = repo.head() {
         if let Some(head_oid) = head.target() {
             if let Ok(remote_ref) = repo.find_reference(&remote_branch) {
@@ -266,10 +873,9 @@ pub fn get_branch_status(path: &Path, project_name: &str) -> BranchStatus {
             } else {
                 // Remote branch doesn't exist, not pushed
                 status.is_pushed = false;
-                // Count commits from merge-base with origin/uat or origin/master
+                // Count commits from merge-base with <remote>/uat or <remote>/master
                 let base_branch = get_base_branch_for_path(path);
-                let base_ref = format!("refs/remotes/origin/{}", base_branch);
-                if let Ok(base_ref) = repo.find_reference(&base_ref) {
+                if let Some(base_ref) = find_base_ref(&repo, remote, base_branch) {
                     if let Some(base_oid) = base_ref.target() {
                         if let Ok((ahead, _)) = repo.graph_ahead_behind(head_oid, base_oid) {
                             status.unpushed_commits = ahead;
@@ -317,7 +923,7 @@ pub fn get_branch_status(path: &Path, project_name: &str) -> BranchStatus {
         && !status.branch_name.starts_with("staging")
     {
         // Check if the remote branch exists
-        let remote_branch = format!("refs/remotes/origin/{}", branch_name);
+        let remote_branch = format!("refs/remotes/{}/{}", remote, branch_name);
         if repo.find_reference(&remote_branch).is_ok() {
             // Branch is pushed to remote - we mark has_merge_request as "unknown"
             // by keeping it false, user should verify manually
@@ -334,44 +940,133 @@ pub struct BranchDiffStats {
     pub changed_files: usize,
 }
 
-/// Sync with base branch (pull from base branch)
-pub fn sync_with_base_branch(path: &Path, base_branch: &str) -> Result<String, String> {
+/// How a feature branch takes in its base branch (`ProjectConfig.merge_strategy`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MergeStrategy {
+    Merge,
+    Rebase,
+}
+
+impl MergeStrategy {
+    /// Anything other than "rebase" keeps the historical merge behaviour.
+    pub fn from_config(value: &str) -> Self {
+        if value.trim().eq_ignore_ascii_case("rebase") {
+            MergeStrategy::Rebase
+        } else {
+            MergeStrategy::Merge
+        }
+    }
+}
+
+/// Files left in a conflicted (unmerged) state by a merge/rebase/cherry-pick.
+fn list_conflicted_files(path: &Path) -> Vec<String> {
+    Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["diff", "--name-only", "--diff-filter=U"])
+        .logged_output()
+        .map(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .filter(|l| !l.is_empty())
+                .map(|l| l.to_string())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default()
+}
+
+/// `git rebase <upstream>`; on failure the rebase is aborted and the conflicting
+/// files (if any) are reported.
+fn rebase_onto(path: &Path, upstream: &str) -> Result<(), String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .arg("rebase")
+        .arg(upstream)
+        .logged_output()
+        .map_err(|e| format!("Failed to execute git rebase: {}", e))?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let conflicted = list_conflicted_files(path);
+    let _ = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .arg("rebase")
+        .arg("--abort")
+        .logged_output();
+
+    if !conflicted.is_empty() {
+        log::warn!(
+            "[git] Rebase onto {} conflicted and was aborted: {}",
+            upstream,
+            conflicted.join(", ")
+        );
+        return Err(format!(
+            "Rebase onto {} has conflicts in {} file(s), aborted:\n{}",
+            upstream,
+            conflicted.len(),
+            conflicted.join("\n")
+        ));
+    }
+    log::error!("[git] Rebase onto {} failed: {}", upstream, stderr);
+    Err(format!("Git rebase failed: {}", stderr))
+}
+
+/// Sync with base branch (pull from base branch), merging or rebasing per project config
+pub fn sync_with_base_branch(
+    path: &Path,
+    remote: &str,
+    base_branch: &str,
+    strategy: MergeStrategy,
+) -> Result<String, String> {
+    let _guard = begin_git_operation("sync_with_base_branch")?;
     log::info!(
-        "[git] Syncing with base branch: path={}, base_branch={}",
-        path.display(), base_branch
+        "[git] Syncing with base branch: path={}, remote={}, base_branch={}, strategy={:?}",
+        path.display(), remote, base_branch, strategy
     );
 
     // Step 1: Fetch from remote
-    log::info!("[git] Step 1/2: git fetch origin {}", base_branch);
+    log::info!("[git] Step 1/2: git fetch {} {}", remote, base_branch);
     let fetch_output = Command::new("git")
         .arg("-C")
         .arg(path)
         .arg("fetch")
-        .arg("origin")
+        .arg(remote)
         .arg(base_branch)
-        .output()
+        .logged_output()
         .map_err(|e| format!("Failed to execute git fetch: {}", e))?;
 
     if !fetch_output.status.success() {
         let stderr = String::from_utf8_lossy(&fetch_output.stderr);
-        log::error!("[git] Step 1/2 FAILED: git fetch origin {}: {}", base_branch, stderr);
+        log::error!("[git] Step 1/2 FAILED: git fetch {} {}: {}", remote, base_branch, stderr);
         return Err(format!("Git fetch failed: {}", stderr));
     }
     log::info!("[git] Step 1/2: git fetch succeeded");
 
-    // Step 2: Merge origin/base_branch into current branch
-    log::info!("[git] Step 2/2: git merge origin/{}", base_branch);
+    let upstream = format!("{}/{}", remote, base_branch);
+    if strategy == MergeStrategy::Rebase {
+        log::info!("[git] Step 2/2: git rebase {}", upstream);
+        rebase_onto(path, &upstream)?;
+        log::info!("[git] Successfully rebased onto '{}'", base_branch);
+        return Ok(format!("Successfully rebased onto {}", base_branch));
+    }
+
+    // Step 2: Merge <remote>/base_branch into current branch
+    log::info!("[git] Step 2/2: git merge {}", upstream);
     let merge_output = Command::new("git")
         .arg("-C")
         .arg(path)
         .arg("merge")
-        .arg(format!("origin/{}", base_branch))
-        .output()
+        .arg(&upstream)
+        .logged_output()
         .map_err(|e| format!("Failed to execute git merge: {}", e))?;
 
     if !merge_output.status.success() {
         let stderr = String::from_utf8_lossy(&merge_output.stderr);
-        log::error!("[git] Step 2/2 FAILED: git merge origin/{}: {}", base_branch, stderr);
+        log::error!("[git] Step 2/2 FAILED: git merge {}: {}", upstream, stderr);
         return Err(format!("Git merge failed: {}", stderr));
     }
 
@@ -379,9 +1074,119 @@ pub fn sync_with_base_branch(path: &Path, base_branch: &str) -> Result<String, S
     Ok(format!("Successfully synced with {}", base_branch))
 }
 
-/// Push current branch to remote
-pub fn push_to_remote(path: &Path) -> Result<String, String> {
-    log::info!("[git] Pushing to remote: path={}", path.display());
+/// Outcome of a preview merge of <remote>/<base> into the current branch.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncAdvice {
+    UpToDate,
+    SafeToSync,
+    ConflictsExpected,
+    /// Can't be decided or shouldn't be auto-synced (dirty tree, missing base ref, ...)
+    Blocked,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SyncPreview {
+    pub advice: SyncAdvice,
+    pub behind: usize,
+    pub conflicted_files: Vec<String>,
+    pub reason: Option<String>,
+}
+
+/// Preview merging <remote>/<base_branch> into HEAD entirely in memory (no working tree changes).
+/// Uses the remote-tracking ref as-is, so callers should fetch first.
+pub fn preview_sync_with_base_branch(path: &Path, remote: &str, base_branch: &str) -> SyncPreview {
+    let blocked = |reason: String| SyncPreview {
+        advice: SyncAdvice::Blocked,
+        behind: 0,
+        conflicted_files: vec![],
+        reason: Some(reason),
+    };
+
+    let repo = match Repository::open(path) {
+        Ok(r) => r,
+        Err(e) => return blocked(format!("Failed to open repository: {}", e)),
+    };
+    let base_commit = match repo
+        .find_reference(&format!("refs/remotes/{}/{}", remote, base_branch))
+        .and_then(|r| r.peel_to_commit())
+    {
+        Ok(c) => c,
+        Err(_) => return blocked(format!("找不到远程分支 {}/{}", remote, base_branch)),
+    };
+    let head_commit = match repo.head().and_then(|h| h.peel_to_commit()) {
+        Ok(c) => c,
+        Err(e) => return blocked(format!("Failed to resolve HEAD: {}", e)),
+    };
+
+    let behind = repo
+        .graph_ahead_behind(head_commit.id(), base_commit.id())
+        .map(|(_, behind)| behind)
+        .unwrap_or(0);
+    if behind == 0 {
+        return SyncPreview {
+            advice: SyncAdvice::UpToDate,
+            behind,
+            conflicted_files: vec![],
+            reason: None,
+        };
+    }
+
+    let index = match repo.merge_commits(&head_commit, &base_commit, None) {
+        Ok(i) => i,
+        Err(e) => {
+            return SyncPreview {
+                behind,
+                ..blocked(format!("Preview merge failed: {}", e))
+            }
+        }
+    };
+
+    if index.has_conflicts() {
+        let conflicted_files = index
+            .conflicts()
+            .map(|conflicts| {
+                conflicts
+                    .filter_map(|c| c.ok())
+                    .filter_map(|c| c.our.or(c.their).or(c.ancestor))
+                    .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        return SyncPreview {
+            advice: SyncAdvice::ConflictsExpected,
+            behind,
+            conflicted_files,
+            reason: None,
+        };
+    }
+
+    // A clean merge can still be refused (or mix states) when tracked files are dirty
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(false).include_ignored(false);
+    let dirty = repo
+        .statuses(Some(&mut opts))
+        .map(|s| !s.is_empty())
+        .unwrap_or(false);
+    if dirty {
+        return SyncPreview {
+            behind,
+            ..blocked("存在未提交的修改，请先提交或暂存".to_string())
+        };
+    }
+
+    SyncPreview {
+        advice: SyncAdvice::SafeToSync,
+        behind,
+        conflicted_files: vec![],
+        reason: None,
+    }
+}
+
+/// Push current branch to `remote`
+pub fn push_to_remote(path: &Path, remote: &str) -> Result<String, String> {
+    let _guard = begin_git_operation("push_to_remote")?;
+    log::info!("[git] Pushing to remote: path={}, remote={}", path.display(), remote);
 
     // Step 1: Get current branch
     let branch_output = Command::new("git")
@@ -390,7 +1195,7 @@ pub fn push_to_remote(path: &Path) -> Result<String, String> {
         .arg("rev-parse")
         .arg("--abbrev-ref")
         .arg("HEAD")
-        .output()
+        .logged_output()
         .map_err(|e| format!("Failed to get current branch: {}", e))?;
 
     if !branch_output.status.success() {
@@ -403,15 +1208,15 @@ pub fn push_to_remote(path: &Path) -> Result<String, String> {
         .to_string();
 
     // Step 2: Push to remote
-    log::info!("[git] Pushing branch '{}' to origin", current_branch);
+    log::info!("[git] Pushing branch '{}' to {}", current_branch, remote);
     let push_output = Command::new("git")
         .arg("-C")
         .arg(path)
         .arg("push")
         .arg("-u")
-        .arg("origin")
+        .arg(remote)
         .arg(&current_branch)
-        .output()
+        .logged_output()
         .map_err(|e| format!("Failed to execute git push: {}", e))?;
 
     if !push_output.status.success() {
@@ -420,8 +1225,89 @@ pub fn push_to_remote(path: &Path) -> Result<String, String> {
         return Err(format!("Git push failed: {}", stderr));
     }
 
-    log::info!("[git] Successfully pushed '{}' to origin", current_branch);
-    Ok(format!("Successfully pushed {} to origin", current_branch))
+    log::info!("[git] Successfully pushed '{}' to {}", current_branch, remote);
+    Ok(format!("Successfully pushed {} to {}", current_branch, remote))
+}
+
+/// Amend the last commit: replace its message (`new_message`, empty keeps the old one)
+/// and optionally fold in the currently staged changes.
+///
+/// Refuses when HEAD is already contained in `<remote>/<branch>`, since rewriting it
+/// would require a force push. Returns the new short commit id.
+pub fn amend_commit(
+    path: &Path,
+    remote: &str,
+    new_message: &str,
+    include_staged: bool,
+) -> Result<String, String> {
+    let new_message = new_message.trim();
+    if new_message.is_empty() && !include_staged {
+        return Err("Nothing to amend: provide a new message or include staged changes".to_string());
+    }
+    let _guard = begin_git_operation("amend_commit")?;
+
+    {
+        let repo =
+            Repository::open(path).map_err(|e| format!("Failed to open repository: {}", e))?;
+        let head = repo
+            .head()
+            .map_err(|e| format!("Failed to read HEAD: {}", e))?;
+        if !head.is_branch() {
+            return Err("HEAD is detached; check out a branch before amending".to_string());
+        }
+        let branch = head.shorthand().unwrap_or_default().to_string();
+        let head_oid = head
+            .target()
+            .ok_or_else(|| "HEAD has no commit to amend".to_string())?;
+        let remote_oid = repo
+            .find_reference(&format!("refs/remotes/{}/{}", remote, branch))
+            .ok()
+            .and_then(|r| r.target());
+        if let Some(remote_oid) = remote_oid {
+            let pushed = remote_oid == head_oid
+                || repo.graph_descendant_of(remote_oid, head_oid).unwrap_or(false);
+            if pushed {
+                return Err(format!(
+                    "The last commit is already on {}/{}; amending it would require a force push",
+                    remote, branch
+                ));
+            }
+        }
+    }
+
+    log::info!(
+        "[git] Amending last commit at {}: new_message={}, include_staged={}",
+        path.display(),
+        !new_message.is_empty(),
+        include_staged
+    );
+    let mut cmd = Command::new("git");
+    cmd.arg("-C").arg(path).args(["commit", "--amend"]);
+    if !include_staged {
+        // --only 且不带路径：只改提交信息，不带入暂存区
+        cmd.arg("--only");
+    }
+    if new_message.is_empty() {
+        cmd.arg("--no-edit");
+    } else {
+        cmd.arg("-m").arg(new_message);
+    }
+    let output = cmd
+        .logged_output()
+        .map_err(|e| format!("Failed to execute git commit: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        log::error!("[git] Amend failed at {}: {}", path.display(), stderr);
+        return Err(format!("Git commit --amend failed: {}", stderr.trim()));
+    }
+
+    let rev = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["rev-parse", "--short", "HEAD"])
+        .logged_output()
+        .map_err(|e| format!("Failed to read new HEAD: {}", e))?;
+    Ok(String::from_utf8_lossy(&rev.stdout).trim().to_string())
 }
 
 /// Helper to restore main worktree and checkout back to original branch on error/cleanup
@@ -439,7 +1325,7 @@ fn restore_merge_state(
         .arg(path)
         .arg("checkout")
         .arg(original_branch)
-        .output();
+        .logged_output();
     match &restore {
         Ok(output) if output.status.success() => {
             log::info!("[merge] Restored worktree to {}", original_branch);
@@ -465,7 +1351,7 @@ fn restore_merge_state(
                 .arg(main_wt)
                 .arg("checkout")
                 .arg(orig_branch)
-                .output();
+                .logged_output();
             match &restore_output {
                 Ok(output) if output.status.success() => {
                     log::info!("[merge] Restored main worktree to {}", orig_branch);
@@ -485,10 +1371,29 @@ fn restore_merge_state(
     }
 }
 
-/// Merge current branch to test branch
-pub fn merge_to_test_branch(path: &Path, test_branch: &str) -> Result<String, String> {
+/// Merge current branch to test branch.
+///
+/// Always a plain merge regardless of `merge_strategy`: the test branch is a shared
+/// integration branch, and rebasing the feature onto it would pull test-only commits in.
+/// Merge the current branch into the test branch and push it.
+///
+/// With `keep_conflicts` a conflicting merge is left in progress on the test
+/// branch so it can be resolved via `resolve_conflict` / `continue_merge` /
+/// `abort_merge`; otherwise the merge is aborted and the original state restored.
+pub fn merge_to_test_branch(
+    path: &Path,
+    remote: &str,
+    test_branch: &str,
+    keep_conflicts: bool,
+) -> Result<String, String> {
+    let _guard = begin_git_operation("merge_to_test_branch")?;
     log::info!("[merge-test] ===== START merge_to_test_branch =====");
-    log::info!("[merge-test] path={}, test_branch={}", path.display(), test_branch);
+    log::info!(
+        "[merge-test] path={}, remote={}, test_branch={}",
+        path.display(),
+        remote,
+        test_branch
+    );
 
     let repo = Repository::open(path)
         .map_err(|e| format!("无法打开仓库 ({}): {}", path.display(), e))?;
@@ -525,7 +1430,7 @@ pub fn merge_to_test_branch(path: &Path, test_branch: &str) -> Result<String, St
         .arg(path)
         .arg("checkout")
         .arg(test_branch)
-        .output()
+        .logged_output()
         .map_err(|e| format!("执行 git checkout {} 失败: {}", test_branch, e))?;
 
     if !checkout_output.status.success() {
@@ -541,15 +1446,15 @@ pub fn merge_to_test_branch(path: &Path, test_branch: &str) -> Result<String, St
     log::info!("[merge-test] Step 2 OK: checked out {}", test_branch);
 
     // Step 3: Pull latest
-    log::info!("[merge-test] Step 3: git pull origin {}", test_branch);
+    log::info!("[merge-test] Step 3: git pull {} {}", remote, test_branch);
     let pull_output = Command::new("git")
         .arg("-C")
         .arg(path)
         .arg("pull")
-        .arg("origin")
+        .arg(remote)
         .arg(test_branch)
-        .output()
-        .map_err(|e| format!("执行 git pull origin {} 失败: {}", test_branch, e))?;
+        .logged_output()
+        .map_err(|e| format!("执行 git pull {} {} 失败: {}", remote, test_branch, e))?;
 
     if !pull_output.status.success() {
         let stderr = String::from_utf8_lossy(&pull_output.stderr);
@@ -568,15 +1473,38 @@ pub fn merge_to_test_branch(path: &Path, test_branch: &str) -> Result<String, St
         .arg(path)
         .arg("merge")
         .arg(current_branch)
-        .output()
+        .logged_output()
         .map_err(|e| format!("执行 git merge {} 失败: {}", current_branch, e))?;
 
     if !merge_output.status.success() {
         let stderr = String::from_utf8_lossy(&merge_output.stderr);
         let stdout = String::from_utf8_lossy(&merge_output.stdout);
         log::error!("[merge-test] Step 4 FAILED: merge => stderr={}, stdout={}", stderr, stdout);
+        let conflicted = list_conflicted_files(path);
+        if keep_conflicts && !conflicted.is_empty() {
+            let pending = PendingMergeToTest {
+                remote: remote.to_string(),
+                original_branch: current_branch.to_string(),
+                test_branch: test_branch.to_string(),
+                switched_main,
+                main_worktree_path: main_worktree_path.clone(),
+                original_main_branch: original_main_branch.clone(),
+            };
+            save_pending_merge(&repo, &pending)?;
+            log::warn!(
+                "[merge-test] Left merge in progress for in-app resolution: {}",
+                conflicted.join(", ")
+            );
+            return Err(format!(
+                "合并 {} 到 {} 存在冲突（{} 个文件），请解决后继续或中止合并:\n{}",
+                current_branch,
+                test_branch,
+                conflicted.len(),
+                conflicted.join("\n")
+            ));
+        }
         // Abort merge if in conflict state
-        let _ = Command::new("git").arg("-C").arg(path).arg("merge").arg("--abort").output();
+        let _ = Command::new("git").arg("-C").arg(path).arg("merge").arg("--abort").logged_output();
         restore_merge_state(path, current_branch, switched_main, &main_worktree_path, &original_main_branch);
         return Err(format!(
             "合并 {} 到 {} 失败: {}{}", current_branch, test_branch, stderr,
@@ -586,15 +1514,15 @@ pub fn merge_to_test_branch(path: &Path, test_branch: &str) -> Result<String, St
     log::info!("[merge-test] Step 4 OK: merged {} into {}", current_branch, test_branch);
 
     // Step 5: Push
-    log::info!("[merge-test] Step 5: git push origin {}", test_branch);
+    log::info!("[merge-test] Step 5: git push {} {}", remote, test_branch);
     let push_output = Command::new("git")
         .arg("-C")
         .arg(path)
         .arg("push")
-        .arg("origin")
+        .arg(remote)
         .arg(test_branch)
-        .output()
-        .map_err(|e| format!("执行 git push origin {} 失败: {}", test_branch, e))?;
+        .logged_output()
+        .map_err(|e| format!("执行 git push {} {} 失败: {}", remote, test_branch, e))?;
 
     let push_failed = !push_output.status.success();
     if push_failed {
@@ -630,10 +1558,25 @@ pub fn merge_to_test_branch(path: &Path, test_branch: &str) -> Result<String, St
     Ok(result)
 }
 
-/// Merge current branch to base branch
-pub fn merge_to_base_branch(path: &Path, base_branch: &str) -> Result<String, String> {
+/// Merge current branch to base branch.
+///
+/// With `MergeStrategy::Rebase` the feature branch is first rebased onto
+/// <remote>/<base> and then fast-forwarded into base, keeping base history linear.
+pub fn merge_to_base_branch(
+    path: &Path,
+    remote: &str,
+    base_branch: &str,
+    strategy: MergeStrategy,
+) -> Result<String, String> {
+    let _guard = begin_git_operation("merge_to_base_branch")?;
     log::info!("[merge-base] ===== START merge_to_base_branch =====");
-    log::info!("[merge-base] path={}, base_branch={}", path.display(), base_branch);
+    log::info!(
+        "[merge-base] path={}, remote={}, base_branch={}, strategy={:?}",
+        path.display(),
+        remote,
+        base_branch,
+        strategy
+    );
 
     let repo = Repository::open(path)
         .map_err(|e| format!("无法打开仓库 ({}): {}", path.display(), e))?;
@@ -647,6 +1590,36 @@ pub fn merge_to_base_branch(path: &Path, base_branch: &str) -> Result<String, St
 
     log::info!("[merge-base] current_branch={}", current_branch);
 
+    if strategy == MergeStrategy::Rebase {
+        log::info!(
+            "[merge-base] Step 0: rebase {} onto {}/{}",
+            current_branch,
+            remote,
+            base_branch
+        );
+        let fetch_output = Command::new("git")
+            .arg("-C")
+            .arg(path)
+            .arg("fetch")
+            .arg(remote)
+            .arg(base_branch)
+            .logged_output()
+            .map_err(|e| format!("执行 git fetch {} {} 失败: {}", remote, base_branch, e))?;
+        if !fetch_output.status.success() {
+            return Err(format!(
+                "拉取 {} 失败: {}",
+                base_branch,
+                String::from_utf8_lossy(&fetch_output.stderr)
+            ));
+        }
+        rebase_onto(path, &format!("{}/{}", remote, base_branch))?;
+        log::info!(
+            "[merge-base] Step 0 OK: rebased onto {}/{}",
+            remote,
+            base_branch
+        );
+    }
+
     // Find main worktree and handle potential checkout conflict
     let mut main_worktree_path: Option<std::path::PathBuf> = None;
     let mut switched_main = false;
@@ -670,7 +1643,7 @@ pub fn merge_to_base_branch(path: &Path, base_branch: &str) -> Result<String, St
         .arg(path)
         .arg("checkout")
         .arg(base_branch)
-        .output()
+        .logged_output()
         .map_err(|e| format!("执行 git checkout {} 失败: {}", base_branch, e))?;
 
     if !checkout_output.status.success() {
@@ -686,15 +1659,15 @@ pub fn merge_to_base_branch(path: &Path, base_branch: &str) -> Result<String, St
     log::info!("[merge-base] Step 2 OK: checked out {}", base_branch);
 
     // Step 3: Pull latest
-    log::info!("[merge-base] Step 3: git pull origin {}", base_branch);
+    log::info!("[merge-base] Step 3: git pull {} {}", remote, base_branch);
     let pull_output = Command::new("git")
         .arg("-C")
         .arg(path)
         .arg("pull")
-        .arg("origin")
+        .arg(remote)
         .arg(base_branch)
-        .output()
-        .map_err(|e| format!("执行 git pull origin {} 失败: {}", base_branch, e))?;
+        .logged_output()
+        .map_err(|e| format!("执行 git pull {} {} 失败: {}", remote, base_branch, e))?;
 
     if !pull_output.status.success() {
         let stderr = String::from_utf8_lossy(&pull_output.stderr);
@@ -706,14 +1679,16 @@ pub fn merge_to_base_branch(path: &Path, base_branch: &str) -> Result<String, St
     }
     log::info!("[merge-base] Step 3 OK: pulled latest {}", base_branch);
 
-    // Step 4: Merge
+    // Step 4: Merge (fast-forward only after a rebase)
     log::info!("[merge-base] Step 4: git merge {}", current_branch);
-    let merge_output = Command::new("git")
-        .arg("-C")
-        .arg(path)
-        .arg("merge")
+    let mut merge_cmd = Command::new("git");
+    merge_cmd.arg("-C").arg(path).arg("merge");
+    if strategy == MergeStrategy::Rebase {
+        merge_cmd.arg("--ff-only");
+    }
+    let merge_output = merge_cmd
         .arg(current_branch)
-        .output()
+        .logged_output()
         .map_err(|e| format!("执行 git merge {} 失败: {}", current_branch, e))?;
 
     if !merge_output.status.success() {
@@ -721,7 +1696,7 @@ pub fn merge_to_base_branch(path: &Path, base_branch: &str) -> Result<String, St
         let stdout = String::from_utf8_lossy(&merge_output.stdout);
         log::error!("[merge-base] Step 4 FAILED: merge => stderr={}, stdout={}", stderr, stdout);
         // Abort merge if in conflict state
-        let _ = Command::new("git").arg("-C").arg(path).arg("merge").arg("--abort").output();
+        let _ = Command::new("git").arg("-C").arg(path).arg("merge").arg("--abort").logged_output();
         restore_merge_state(path, current_branch, switched_main, &main_worktree_path, &original_main_branch);
         return Err(format!(
             "合并 {} 到 {} 失败: {}{}", current_branch, base_branch, stderr,
@@ -731,15 +1706,15 @@ pub fn merge_to_base_branch(path: &Path, base_branch: &str) -> Result<String, St
     log::info!("[merge-base] Step 4 OK: merged {} into {}", current_branch, base_branch);
 
     // Step 5: Push
-    log::info!("[merge-base] Step 5: git push origin {}", base_branch);
+    log::info!("[merge-base] Step 5: git push {} {}", remote, base_branch);
     let push_output = Command::new("git")
         .arg("-C")
         .arg(path)
         .arg("push")
-        .arg("origin")
+        .arg(remote)
         .arg(base_branch)
-        .output()
-        .map_err(|e| format!("执行 git push origin {} 失败: {}", base_branch, e))?;
+        .logged_output()
+        .map_err(|e| format!("执行 git push {} {} 失败: {}", remote, base_branch, e))?;
 
     let push_failed = !push_output.status.success();
     if push_failed {
@@ -775,8 +1750,350 @@ pub fn merge_to_base_branch(path: &Path, base_branch: &str) -> Result<String, St
     Ok(result)
 }
 
+/// Cherry-pick the given commits (in order) onto the current branch.
+/// On any failure the in-progress cherry-pick is aborted so the worktree is
+/// left exactly as it was; conflicting files are reported in the error.
+pub fn cherry_pick_commits(path: &Path, commit_shas: &[String]) -> Result<String, String> {
+    let _guard = begin_git_operation("cherry_pick_commits")?;
+    if commit_shas.is_empty() {
+        return Err("No commits to cherry-pick".to_string());
+    }
+    for sha in commit_shas {
+        if sha.is_empty() || sha.starts_with('-') || !sha.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid commit id: {}", sha));
+        }
+    }
+    log::info!(
+        "[git] Cherry-picking {} commit(s) into {}: {}",
+        commit_shas.len(),
+        path.display(),
+        commit_shas.join(", ")
+    );
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .arg("cherry-pick")
+        .args(commit_shas)
+        .logged_output()
+        .map_err(|e| format!("Failed to execute git cherry-pick: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        let conflicted = list_conflicted_files(path);
+
+        // Abort whatever is in progress (no-op if git bailed before starting)
+        let _ = Command::new("git")
+            .arg("-C")
+            .arg(path)
+            .arg("cherry-pick")
+            .arg("--abort")
+            .logged_output();
+
+        if !conflicted.is_empty() {
+            log::warn!(
+                "[git] Cherry-pick conflicted and was aborted: {}",
+                conflicted.join(", ")
+            );
+            return Err(format!(
+                "Cherry-pick conflicts in {} file(s), aborted:\n{}",
+                conflicted.len(),
+                conflicted.join("\n")
+            ));
+        }
+        log::error!("[git] Cherry-pick failed: {}", stderr);
+        return Err(format!("Git cherry-pick failed: {}", stderr));
+    }
+
+    log::info!("[git] Cherry-picked {} commit(s)", commit_shas.len());
+    Ok(format!("Successfully cherry-picked {} commit(s)", commit_shas.len()))
+}
+
+// ==================== 合并冲突处理 ====================
+
+/// Sides larger than this are not returned inline
+const CONFLICT_CONTENT_LIMIT: usize = 512 * 1024;
+const PENDING_MERGE_FILE: &str = "worktree-manager-pending-merge.json";
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ConflictFile {
+    pub path: String,
+    /// Common ancestor; None when the file was added on both sides
+    pub base: Option<String>,
+    /// Current branch side (HEAD)
+    pub ours: Option<String>,
+    /// Incoming side (MERGE_HEAD / commit being applied)
+    pub theirs: Option<String>,
+    pub is_binary: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MergeConflictState {
+    /// "merge" | "rebase" | "cherry-pick", None when nothing is in progress
+    pub operation: Option<String>,
+    pub files: Vec<ConflictFile>,
+}
+
+/// Restore info for a merge_to_test_branch left in progress, stored in the git dir
+#[derive(Debug, Serialize, Deserialize)]
+struct PendingMergeToTest {
+    /// Remote the test branch is pushed to (state files from before remotes were
+    /// configurable have none)
+    #[serde(default = "crate::types::default_remote")]
+    remote: String,
+    original_branch: String,
+    test_branch: String,
+    switched_main: bool,
+    main_worktree_path: Option<std::path::PathBuf>,
+    original_main_branch: Option<String>,
+}
+
+fn save_pending_merge(repo: &Repository, pending: &PendingMergeToTest) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(pending)
+        .map_err(|e| format!("Failed to serialize merge state: {}", e))?;
+    std::fs::write(repo.path().join(PENDING_MERGE_FILE), content)
+        .map_err(|e| format!("Failed to save merge state: {}", e))
+}
+
+fn take_pending_merge(repo: &Repository) -> Option<PendingMergeToTest> {
+    let file = repo.path().join(PENDING_MERGE_FILE);
+    let content = std::fs::read_to_string(&file).ok()?;
+    let _ = std::fs::remove_file(&file);
+    serde_json::from_str(&content).ok()
+}
+
+fn in_progress_operation(repo: &Repository) -> Option<&'static str> {
+    use git2::RepositoryState;
+    match repo.state() {
+        RepositoryState::Merge => Some("merge"),
+        RepositoryState::Rebase
+        | RepositoryState::RebaseInteractive
+        | RepositoryState::RebaseMerge => Some("rebase"),
+        RepositoryState::CherryPick | RepositoryState::CherryPickSequence => Some("cherry-pick"),
+        _ => None,
+    }
+}
+
+/// Conflicted files of the merge/rebase/cherry-pick in progress, with the
+/// content of each side read from the index stages.
+pub fn list_merge_conflicts(path: &Path) -> Result<MergeConflictState, String> {
+    let repo = Repository::open(path).map_err(|e| format!("Failed to open repo: {}", e))?;
+    let operation = in_progress_operation(&repo).map(|s| s.to_string());
+    let index = repo.index().map_err(|e| format!("Failed to read index: {}", e))?;
+
+    let mut files = vec![];
+    let conflicts = index
+        .conflicts()
+        .map_err(|e| format!("Failed to read conflicts: {}", e))?;
+    for conflict in conflicts.flatten() {
+        let entry_path = [&conflict.our, &conflict.their, &conflict.ancestor]
+            .into_iter()
+            .flatten()
+            .next()
+            .map(|e| String::from_utf8_lossy(&e.path).to_string())
+            .unwrap_or_default();
+
+        let mut is_binary = false;
+        let mut read_side = |entry: &Option<git2::IndexEntry>| -> Option<String> {
+            let blob = repo.find_blob(entry.as_ref()?.id).ok()?;
+            if blob.is_binary() || blob.size() > CONFLICT_CONTENT_LIMIT {
+                is_binary |= blob.is_binary();
+                return None;
+            }
+            Some(String::from_utf8_lossy(blob.content()).to_string())
+        };
+        let base = read_side(&conflict.ancestor);
+        let ours = read_side(&conflict.our);
+        let theirs = read_side(&conflict.their);
+
+        files.push(ConflictFile {
+            path: entry_path,
+            base,
+            ours,
+            theirs,
+            is_binary,
+        });
+    }
+
+    Ok(MergeConflictState { operation, files })
+}
+
+/// Resolve one conflicted file: "ours" / "theirs" take that side, "mark_resolved"
+/// stages the file as currently edited in the working tree.
+pub fn resolve_conflict(path: &Path, file: &str, resolution: &str) -> Result<(), String> {
+    let _guard = begin_git_operation("resolve_conflict")?;
+    if file.is_empty() || file.starts_with('-') {
+        return Err(format!("Invalid file path: {}", file));
+    }
+    let side = match resolution {
+        "ours" => Some("--ours"),
+        "theirs" => Some("--theirs"),
+        "mark_resolved" => None,
+        other => return Err(format!("Unknown resolution: {}", other)),
+    };
+    log::info!(
+        "[git] Resolving conflict in {}: {} ({})",
+        path.display(),
+        file,
+        resolution
+    );
+
+    if let Some(side) = side {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(path)
+            .args(["checkout", side, "--", file])
+            .logged_output()
+            .map_err(|e| format!("Failed to execute git checkout: {}", e))?;
+        if !output.status.success() {
+            // 一侧已删除该文件时 checkout 会失败，按删除处理
+            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+            if !stderr.contains("does not have") {
+                return Err(format!("Git checkout {} failed: {}", side, stderr));
+            }
+            let rm = Command::new("git")
+                .arg("-C")
+                .arg(path)
+                .args(["rm", "--quiet", "--", file])
+                .logged_output()
+                .map_err(|e| format!("Failed to execute git rm: {}", e))?;
+            if !rm.status.success() {
+                return Err(format!(
+                    "Git rm failed: {}",
+                    String::from_utf8_lossy(&rm.stderr)
+                ));
+            }
+            return Ok(());
+        }
+    }
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["add", "--", file])
+        .logged_output()
+        .map_err(|e| format!("Failed to execute git add: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Git add failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    Ok(())
+}
+
+/// Push the test branch and restore branches for a merge_to_test_branch that was
+/// left in progress. Returns a note for the result message.
+fn finish_pending_merge(path: &Path, pending: Option<PendingMergeToTest>, push: bool) -> String {
+    let Some(pending) = pending else {
+        return String::new();
+    };
+    let mut note = String::new();
+    if push {
+        match Command::new("git")
+            .arg("-C")
+            .arg(path)
+            .args(["push", &pending.remote, &pending.test_branch])
+            .logged_output()
+        {
+            Ok(o) if o.status.success() => {
+                note = format!("\n已推送 {}", pending.test_branch);
+            }
+            Ok(o) => {
+                note = format!(
+                    "\n推送 {} 到远程失败: {}",
+                    pending.test_branch,
+                    String::from_utf8_lossy(&o.stderr)
+                );
+            }
+            Err(e) => note = format!("\n执行 git push 失败: {}", e),
+        }
+    }
+    restore_merge_state(
+        path,
+        &pending.original_branch,
+        pending.switched_main,
+        &pending.main_worktree_path,
+        &pending.original_main_branch,
+    );
+    note.push_str(&format!("\n已切回 {}", pending.original_branch));
+    note
+}
+
+/// Commit the resolved merge (or continue the rebase / cherry-pick).
+/// A merge_to_test_branch left in progress is then pushed and restored.
+pub fn continue_merge(path: &Path) -> Result<String, String> {
+    let _guard = begin_git_operation("continue_merge")?;
+    let repo = Repository::open(path).map_err(|e| format!("Failed to open repo: {}", e))?;
+    let operation = in_progress_operation(&repo).ok_or("没有进行中的合并")?;
+
+    let remaining = list_conflicted_files(path);
+    if !remaining.is_empty() {
+        return Err(format!(
+            "仍有 {} 个文件未解决冲突:\n{}",
+            remaining.len(),
+            remaining.join("\n")
+        ));
+    }
+
+    log::info!("[git] Continuing {} in {}", operation, path.display());
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["-c", "core.editor=true", operation, "--continue"])
+        .env("GIT_EDITOR", "true")
+        .logged_output()
+        .map_err(|e| format!("Failed to execute git {} --continue: {}", operation, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} --continue 失败: {}",
+            operation,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    // rebase 可能在下一个提交上再次冲突
+    if in_progress_operation(&repo).is_some() {
+        let conflicted = list_conflicted_files(path);
+        return Err(format!(
+            "继续 {} 时出现新的冲突（{} 个文件）:\n{}",
+            operation,
+            conflicted.len(),
+            conflicted.join("\n")
+        ));
+    }
+
+    let note = finish_pending_merge(path, take_pending_merge(&repo), true);
+    Ok(format!("{} 已完成{}", operation, note))
+}
+
+/// Abort the merge/rebase/cherry-pick in progress and restore the original branches.
+pub fn abort_merge(path: &Path) -> Result<String, String> {
+    let _guard = begin_git_operation("abort_merge")?;
+    let repo = Repository::open(path).map_err(|e| format!("Failed to open repo: {}", e))?;
+    let operation = in_progress_operation(&repo).ok_or("没有进行中的合并")?;
+
+    log::info!("[git] Aborting {} in {}", operation, path.display());
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args([operation, "--abort"])
+        .logged_output()
+        .map_err(|e| format!("Failed to execute git {} --abort: {}", operation, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} --abort 失败: {}",
+            operation,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let note = finish_pending_merge(path, take_pending_merge(&repo), false);
+    Ok(format!("{} 已中止{}", operation, note))
+}
+
 /// Get branch diff statistics
-pub fn get_branch_diff_stats(path: &Path, base_branch: &str) -> BranchDiffStats {
+pub fn get_branch_diff_stats(path: &Path, remote: &str, base_branch: &str) -> BranchDiffStats {
     let repo = match Repository::open(path) {
         Ok(r) => r,
         Err(_) => {
@@ -795,7 +2112,7 @@ pub fn get_branch_diff_stats(path: &Path, base_branch: &str) -> BranchDiffStats
     };
 
     // Get ahead/behind count
-    if let Ok(base_ref) = repo.find_reference(&format!("refs/remotes/origin/{}", base_branch)) {
+    if let Some(base_ref) = find_base_ref(&repo, remote, base_branch) {
         if let Ok(head) = repo.head() {
             if let (Ok(base_oid), Ok(head_oid)) =
                 (base_ref.target().ok_or(()), head.target().ok_or(()))
@@ -809,8 +2126,7 @@ pub fn get_branch_diff_stats(path: &Path, base_branch: &str) -> BranchDiffStats
     }
 
     // Get changed files count
-    let mut opts = StatusOptions::new();
-    opts.include_untracked(true).recurse_untracked_dirs(false);
+    let mut opts = build_status_options(&repo, &[]);
 
     if let Ok(statuses) = repo.statuses(Some(&mut opts)) {
         stats.changed_files = statuses.len();
@@ -833,7 +2149,7 @@ pub fn detect_git_platform(path: &Path) -> Result<GitPlatform, String> {
         .arg(path)
         .arg("remote")
         .arg("-v")
-        .output()
+        .logged_output()
         .map_err(|e| format!("Failed to execute git remote: {}", e))?;
 
     if !remote_output.status.success() {
@@ -861,10 +2177,12 @@ pub fn detect_git_platform(path: &Path) -> Result<GitPlatform, String> {
 /// Create a pull request using gh CLI (GitHub) or git push options (GitLab)
 pub fn create_pull_request(
     path: &Path,
+    remote: &str,
     base_branch: &str,
     title: &str,
     body: &str,
 ) -> Result<String, String> {
+    let _guard = begin_git_operation("create_pull_request")?;
     log::info!(
         "[git] Creating pull request: path={}, base_branch={}, title='{}'",
         path.display(), base_branch, title
@@ -878,7 +2196,7 @@ pub fn create_pull_request(
         GitPlatform::GitHub => {
             // Check if gh CLI is available
             log::info!("[git] Checking gh CLI availability");
-            let gh_check = Command::new("gh").arg("--version").output().map_err(|_| {
+            let gh_check = Command::new("gh").arg("--version").logged_output().map_err(|_| {
                 "gh CLI is not installed. Please install it from https://cli.github.com/"
                     .to_string()
             })?;
@@ -900,7 +2218,7 @@ pub fn create_pull_request(
                 .arg("--body")
                 .arg(body)
                 .current_dir(path)
-                .output()
+                .logged_output()
                 .map_err(|e| format!("Failed to execute gh pr create: {}", e))?;
 
             if !pr_output.status.success() {
@@ -924,7 +2242,7 @@ pub fn create_pull_request(
                 .arg("rev-parse")
                 .arg("--abbrev-ref")
                 .arg("HEAD")
-                .output()
+                .logged_output()
                 .map_err(|e| format!("Failed to get current branch: {}", e))?;
 
             if !branch_output.status.success() {
@@ -939,15 +2257,17 @@ pub fn create_pull_request(
             // Push with merge request creation options
             // GitLab supports creating MR via git push options
             log::info!(
-                "[git] Running: git push -u origin {} with MR options (target={})",
-                current_branch, base_branch
+                "[git] Running: git push -u {} {} with MR options (target={})",
+                remote,
+                current_branch,
+                base_branch
             );
             let push_output = Command::new("git")
                 .arg("-C")
                 .arg(path)
                 .arg("push")
                 .arg("-u")
-                .arg("origin")
+                .arg(remote)
                 .arg(&current_branch)
                 .arg("-o")
                 .arg("merge_request.create")
@@ -957,7 +2277,7 @@ pub fn create_pull_request(
                 .arg(format!("merge_request.title={}", title))
                 .arg("-o")
                 .arg(format!("merge_request.description={}", body))
-                .output()
+                .logged_output()
                 .map_err(|e| format!("Failed to push and create MR: {}", e))?;
 
             if !push_output.status.success() {
@@ -1001,15 +2321,223 @@ pub fn create_pull_request(
     }
 }
 
-/// Fetch from remote origin (updates remote-tracking branches)
-pub fn fetch_remote(path: &Path) -> Result<(), String> {
-    log::info!("[git] Fetching remote origin: path={}", path.display());
+/// Overall CI result for a commit
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CiState {
+    Success,
+    Failure,
+    Pending,
+    /// No checks / pipelines for the commit, or the platform can't be queried
+    None,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CiStatus {
+    pub state: CiState,
+    /// Commit the status belongs to (the pushed branch head when there is one)
+    pub commit: String,
+    pub total: usize,
+    pub failed: usize,
+    pub pending: usize,
+    /// Checks page / pipeline of the commit
+    pub url: Option<String>,
+}
+
+impl CiStatus {
+    fn none(commit: &str) -> Self {
+        CiStatus {
+            state: CiState::None,
+            commit: commit.to_string(),
+            total: 0,
+            failed: 0,
+            pending: 0,
+            url: None,
+        }
+    }
+}
+
+/// Seconds a status stays cached: finished results rarely change, running ones do
+const CI_CACHE_FINAL_SECS: u64 = 300;
+const CI_CACHE_PENDING_SECS: u64 = 30;
+
+fn rev_parse_commit(path: &Path, rev: &str) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .arg("rev-parse")
+        .arg("--verify")
+        .arg("--quiet")
+        .arg(format!("{}^{{commit}}", rev))
+        .logged_output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// GitHub check runs and commit statuses via `gh api` (run in the repo so `{owner}/{repo}` resolve)
+fn github_ci_status(path: &Path, commit: &str) -> Result<CiStatus, String> {
+    let gh_api = |endpoint: String| -> Result<serde_json::Value, String> {
+        let output = Command::new("gh")
+            .arg("api")
+            .arg(&endpoint)
+            .current_dir(path)
+            .logged_output()
+            .map_err(|_| {
+                "gh CLI is not installed. Please install it from https://cli.github.com/"
+                    .to_string()
+            })?;
+        if !output.status.success() {
+            return Err(format!(
+                "gh api {} failed: {}",
+                endpoint,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        serde_json::from_slice(&output.stdout)
+            .map_err(|e| format!("Invalid gh api response: {}", e))
+    };
+
+    let checks = gh_api(format!(
+        "repos/{{owner}}/{{repo}}/commits/{}/check-runs?per_page=100",
+        commit
+    ))?;
+    let statuses = gh_api(format!(
+        "repos/{{owner}}/{{repo}}/commits/{}/status",
+        commit
+    ))?;
+
+    let mut status = CiStatus::none(commit);
+    for run in checks["check_runs"].as_array().into_iter().flatten() {
+        status.total += 1;
+        if run["status"].as_str() != Some("completed") {
+            status.pending += 1;
+        } else if !matches!(
+            run["conclusion"].as_str(),
+            Some("success" | "neutral" | "skipped")
+        ) {
+            status.failed += 1;
+        }
+        if status.url.is_none() {
+            status.url = run["html_url"].as_str().map(str::to_string);
+        }
+    }
+    for s in statuses["statuses"].as_array().into_iter().flatten() {
+        status.total += 1;
+        match s["state"].as_str() {
+            Some("pending") => status.pending += 1,
+            Some("success") => {}
+            _ => status.failed += 1,
+        }
+        if status.url.is_none() {
+            status.url = s["target_url"].as_str().map(str::to_string);
+        }
+    }
+    status.state = summarize_ci(&status);
+    Ok(status)
+}
+
+/// Latest GitLab pipeline of the commit via `glab api` (`:id` resolves to the current project)
+fn gitlab_ci_status(path: &Path, commit: &str) -> Result<CiStatus, String> {
+    let endpoint = format!("projects/:id/pipelines?sha={}&per_page=1", commit);
+    let output = Command::new("glab")
+        .arg("api")
+        .arg(&endpoint)
+        .current_dir(path)
+        .logged_output()
+        .map_err(|_| {
+            "glab CLI is not installed. Please install it from https://gitlab.com/gitlab-org/cli"
+                .to_string()
+        })?;
+    if !output.status.success() {
+        return Err(format!(
+            "glab api {} failed: {}",
+            endpoint,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let pipelines: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Invalid glab api response: {}", e))?;
+
+    let mut status = CiStatus::none(commit);
+    if let Some(pipeline) = pipelines.as_array().and_then(|p| p.first()) {
+        status.total = 1;
+        match pipeline["status"].as_str() {
+            Some("success" | "skipped") => {}
+            Some("failed" | "canceled") => status.failed = 1,
+            _ => status.pending = 1,
+        }
+        status.url = pipeline["web_url"].as_str().map(str::to_string);
+    }
+    status.state = summarize_ci(&status);
+    Ok(status)
+}
+
+fn summarize_ci(status: &CiStatus) -> CiState {
+    if status.total == 0 {
+        CiState::None
+    } else if status.failed > 0 {
+        CiState::Failure
+    } else if status.pending > 0 {
+        CiState::Pending
+    } else {
+        CiState::Success
+    }
+}
+
+/// CI status of `branch`'s head commit (`remote/branch` when pushed, else the local branch) on
+/// the detected platform. Results are cached per commit.
+pub fn get_ci_status(path: &Path, remote: &str, branch: &str) -> Result<CiStatus, String> {
+    let commit = rev_parse_commit(path, &format!("refs/remotes/{}/{}", remote, branch))
+        .or_else(|| rev_parse_commit(path, &format!("refs/heads/{}", branch)))
+        .ok_or_else(|| format!("Branch not found: {}", branch))?;
+
+    let key = (path.to_string_lossy().to_string(), commit.clone());
+    if let Ok(cache) = crate::state::CI_STATUS_CACHE.lock() {
+        if let Some((at, status)) = cache.get(&key) {
+            let ttl = if status.state == CiState::Pending {
+                CI_CACHE_PENDING_SECS
+            } else {
+                CI_CACHE_FINAL_SECS
+            };
+            if at.elapsed().as_secs() < ttl {
+                return Ok(status.clone());
+            }
+        }
+    }
+
+    let status = match detect_git_platform(path)? {
+        GitPlatform::GitHub => github_ci_status(path, &commit)?,
+        GitPlatform::GitLab => gitlab_ci_status(path, &commit)?,
+        GitPlatform::Unknown => CiStatus::none(&commit),
+    };
+    log::debug!(
+        "[git] CI status for {} @ {}: {:?} ({} checks, {} failed, {} pending)",
+        branch,
+        &commit[..commit.len().min(8)],
+        status.state,
+        status.total,
+        status.failed,
+        status.pending
+    );
+    if let Ok(mut cache) = crate::state::CI_STATUS_CACHE.lock() {
+        cache.insert(key, (std::time::Instant::now(), status.clone()));
+    }
+    Ok(status)
+}
+
+/// Fetch from `remote` (updates remote-tracking branches)
+pub fn fetch_remote(path: &Path, remote: &str) -> Result<(), String> {
+    let _guard = begin_git_operation("fetch_remote")?;
+    log::info!("[git] Fetching remote {}: path={}", remote, path.display());
     let output = Command::new("git")
         .arg("-C")
         .arg(path)
         .arg("fetch")
-        .arg("origin")
-        .output()
+        .arg(remote)
+        .logged_output()
         .map_err(|e| format!("Failed to execute git fetch: {}", e))?;
 
     if !output.status.success() {
@@ -1023,10 +2551,14 @@ pub fn fetch_remote(path: &Path) -> Result<(), String> {
 }
 
 /// Check if a remote branch exists
-pub fn check_remote_branch_exists(path: &Path, branch_name: &str) -> Result<bool, String> {
+pub fn check_remote_branch_exists(
+    path: &Path,
+    remote: &str,
+    branch_name: &str,
+) -> Result<bool, String> {
     log::debug!(
-        "[git] Checking remote branch exists: path={}, branch=origin/{}",
-        path.display(), branch_name
+        "[git] Checking remote branch exists: path={}, branch={}/{}",
+        path.display(), remote, branch_name
     );
     // Check locally if the remote-tracking branch exists (no network call).
     // Remote-tracking branches are updated by git fetch/pull/push operations,
@@ -1037,34 +2569,34 @@ pub fn check_remote_branch_exists(path: &Path, branch_name: &str) -> Result<bool
         .arg("branch")
         .arg("-r")
         .arg("--list")
-        .arg(format!("origin/{}", branch_name))
-        .output()
+        .arg(format!("{}/{}", remote, branch_name))
+        .logged_output()
         .map_err(|e| format!("Failed to execute git branch: {}", e))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        log::error!("[git] Branch check failed for origin/{}: {}", branch_name, stderr);
+        log::error!("[git] Branch check failed for {}/{}: {}", remote, branch_name, stderr);
         return Err(format!("Git branch check failed: {}", stderr));
     }
 
     let output_str = String::from_utf8_lossy(&output.stdout);
     let exists = !output_str.trim().is_empty();
-    log::debug!("[git] Remote branch origin/{} exists: {}", branch_name, exists);
+    log::debug!("[git] Remote branch {}/{} exists: {}", remote, branch_name, exists);
     Ok(exists)
 }
 
-/// Get list of remote branches
-pub fn get_remote_branches(path: &Path) -> Result<Vec<String>, String> {
-    log::info!("[git] Getting remote branches: path={}", path.display());
+/// Get list of branches on `remote`
+pub fn get_remote_branches(path: &Path, remote: &str) -> Result<Vec<String>, String> {
+    log::info!("[git] Getting remote branches: path={}, remote={}", path.display(), remote);
 
     // Fetch from remote to ensure we have the latest branch info
-    log::info!("[git] Step 1/2: git fetch origin");
+    log::info!("[git] Step 1/2: git fetch {}", remote);
     let fetch_output = Command::new("git")
         .arg("-C")
         .arg(path)
         .arg("fetch")
-        .arg("origin")
-        .output()
+        .arg(remote)
+        .logged_output()
         .map_err(|e| format!("Failed to execute git fetch: {}", e))?;
 
     if !fetch_output.status.success() {
@@ -1074,14 +2606,14 @@ pub fn get_remote_branches(path: &Path) -> Result<Vec<String>, String> {
     }
 
     // Get list of remote branches
-    log::info!("[git] Step 2/2: git ls-remote --heads origin");
+    log::info!("[git] Step 2/2: git ls-remote --heads {}", remote);
     let ls_remote_output = Command::new("git")
         .arg("-C")
         .arg(path)
         .arg("ls-remote")
         .arg("--heads")
-        .arg("origin")
-        .output()
+        .arg(remote)
+        .logged_output()
         .map_err(|e| format!("Failed to execute git ls-remote: {}", e))?;
 
     if !ls_remote_output.status.success() {
@@ -1107,3 +2639,327 @@ pub fn get_remote_branches(path: &Path) -> Result<Vec<String>, String> {
     log::info!("[git] Found {} remote branches", branches.len());
     Ok(branches)
 }
+
+// ==================== Diff export ====================
+
+/// Hard cap on the uncompressed size of a diff archive.
+const MAX_DIFF_ARCHIVE_BYTES: u64 = 512 * 1024 * 1024;
+
+#[derive(Debug, Serialize)]
+pub struct DiffArchiveSummary {
+    pub file_name: String,
+    pub base_ref: String,
+    pub file_count: usize,
+    pub binary_count: usize,
+    pub deleted_files: Vec<String>,
+    pub size_bytes: u64,
+}
+
+/// Same heuristic git uses: a NUL byte in the first 8000 bytes means binary.
+fn is_binary_content(bytes: &[u8]) -> bool {
+    bytes.iter().take(8000).any(|b| *b == 0)
+}
+
+/// Split `git ... -z` output into its NUL-separated fields.
+fn split_nul(bytes: &[u8]) -> Vec<String> {
+    bytes
+        .split(|b| *b == 0)
+        .filter(|s| !s.is_empty())
+        .map(|s| String::from_utf8_lossy(s).to_string())
+        .collect()
+}
+
+/// Package the files changed versus the base branch (committed, uncommitted and untracked)
+/// into a zip. Binary files are stored without recompression; deleted files are only
+/// listed in the CHANGES.txt manifest.
+pub fn build_diff_archive(
+    path: &Path,
+    remote: &str,
+    base_branch: &str,
+) -> Result<(Vec<u8>, DiffArchiveSummary), String> {
+    use std::io::Write;
+
+    log::info!(
+        "[git] Building diff archive: path={}, base={}",
+        path.display(),
+        base_branch
+    );
+
+    // Step 1: Resolve the merge base against <remote>/<base> (fallback: local <base>)
+    let mut base_ref = String::new();
+    for candidate in [
+        format!("{}/{}", remote, base_branch),
+        base_branch.to_string(),
+    ] {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(path)
+            .args(["merge-base", "HEAD", &candidate])
+            .logged_output()
+            .map_err(|e| format!("Failed to execute git merge-base: {}", e))?;
+        if output.status.success() {
+            base_ref = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            break;
+        }
+    }
+    if base_ref.is_empty() {
+        return Err(format!("找不到基础分支 {} 的合并基点", base_branch));
+    }
+
+    // Step 2: Collect changed paths (tracked) and untracked files
+    let diff_output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["diff", "--name-status", "--no-renames", "-z", &base_ref])
+        .logged_output()
+        .map_err(|e| format!("Failed to execute git diff: {}", e))?;
+    if !diff_output.status.success() {
+        let stderr = String::from_utf8_lossy(&diff_output.stderr);
+        return Err(format!("Git diff failed: {}", stderr));
+    }
+
+    let untracked_output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["ls-files", "--others", "--exclude-standard", "-z"])
+        .logged_output()
+        .map_err(|e| format!("Failed to execute git ls-files: {}", e))?;
+
+    // name-status -z output: <status>\0<path>\0...
+    let mut changes: Vec<(String, String)> = split_nul(&diff_output.stdout)
+        .chunks(2)
+        .filter(|c| c.len() == 2)
+        .map(|c| (c[0].clone(), c[1].clone()))
+        .collect();
+    for file in split_nul(&untracked_output.stdout) {
+        changes.push(("A".to_string(), file));
+    }
+
+    // Step 3: Write the archive
+    let repo_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "project".to_string());
+    let branch = Repository::open(path)
+        .ok()
+        .and_then(|r| r.head().ok().and_then(|h| h.shorthand().map(|s| s.to_string())))
+        .unwrap_or_else(|| "HEAD".to_string());
+    let root = format!("{}-{}", repo_name, branch.replace(['/', '\\'], "-"));
+
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let deflated = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    let stored = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Stored)
+        .large_file(true);
+
+    let mut manifest = format!("# Changes in {} versus {} ({})\n", branch, base_branch, base_ref);
+    let mut summary = DiffArchiveSummary {
+        file_name: format!("{}-diff.zip", root),
+        base_ref: base_ref.clone(),
+        file_count: 0,
+        binary_count: 0,
+        deleted_files: vec![],
+        size_bytes: 0,
+    };
+
+    for (status, file) in &changes {
+        let full_path = path.join(file);
+        if status.starts_with('D') || !full_path.is_file() {
+            manifest.push_str(&format!("D {}\n", file));
+            summary.deleted_files.push(file.clone());
+            continue;
+        }
+
+        let bytes = std::fs::read(&full_path)
+            .map_err(|e| format!("Failed to read {}: {}", full_path.display(), e))?;
+        summary.size_bytes += bytes.len() as u64;
+        if summary.size_bytes > MAX_DIFF_ARCHIVE_BYTES {
+            return Err(format!(
+                "变更文件总大小超过上限 ({} MB)",
+                MAX_DIFF_ARCHIVE_BYTES / 1024 / 1024
+            ));
+        }
+
+        let binary = is_binary_content(&bytes);
+        let options = if binary { stored } else { deflated };
+        zip.start_file(format!("{}/{}", root, file), options)
+            .map_err(|e| format!("Failed to add {} to archive: {}", file, e))?;
+        zip.write_all(&bytes)
+            .map_err(|e| format!("Failed to write {} to archive: {}", file, e))?;
+
+        manifest.push_str(&format!(
+            "{} {}{}\n",
+            &status[..1],
+            file,
+            if binary { " (binary)" } else { "" }
+        ));
+        summary.file_count += 1;
+        if binary {
+            summary.binary_count += 1;
+        }
+    }
+
+    zip.start_file(format!("{}/CHANGES.txt", root), deflated)
+        .map_err(|e| format!("Failed to add manifest: {}", e))?;
+    zip.write_all(manifest.as_bytes())
+        .map_err(|e| format!("Failed to write manifest: {}", e))?;
+    let bytes = zip
+        .finish()
+        .map_err(|e| format!("Failed to finalize archive: {}", e))?
+        .into_inner();
+
+    log::info!(
+        "[git] Diff archive built: {} files ({} binary), {} deleted, {} bytes",
+        summary.file_count,
+        summary.binary_count,
+        summary.deleted_files.len(),
+        bytes.len()
+    );
+    Ok((bytes, summary))
+}
+
+// ==================== 单文件 Diff ====================
+
+/// Hard cap on diff lines returned for a single file, to keep payloads sane.
+const MAX_FILE_DIFF_LINES: usize = 20_000;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FileDiffLine {
+    /// '+' added, '-' removed, ' ' context
+    pub origin: char,
+    pub old_lineno: Option<u32>,
+    pub new_lineno: Option<u32>,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FileDiffHunk {
+    pub header: String,
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    pub lines: Vec<FileDiffLine>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FileDiff {
+    pub file: String,
+    pub old_file: Option<String>,
+    pub is_binary: bool,
+    pub truncated: bool,
+    pub hunks: Vec<FileDiffHunk>,
+}
+
+/// Unified diff of one file.
+///
+/// Without `base_ref` this is the working-tree change (HEAD vs. index + workdir).
+/// With `base_ref` (e.g. "origin/uat") it is the branch-vs-base diff: the merge base
+/// of HEAD and `base_ref` against the current working tree, so uncommitted edits
+/// are included just like in the "files changed" view.
+pub fn get_file_diff(path: &Path, file: &str, base_ref: Option<&str>) -> Result<FileDiff, String> {
+    if file.is_empty() || file.starts_with('/') || file.split('/').any(|c| c == "..") {
+        return Err(format!("Invalid file path: {}", file));
+    }
+    let repo = Repository::open(path)
+        .map_err(|e| format!("无法打开仓库 ({}): {}", path.display(), e))?;
+
+    let head_commit = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+    let old_tree = match base_ref.filter(|r| !r.is_empty()) {
+        Some(base) => {
+            let base_commit = repo
+                .revparse_single(base)
+                .and_then(|o| o.peel_to_commit())
+                .map_err(|e| format!("无法解析 {}: {}", base, e))?;
+            let merge_base = match &head_commit {
+                Some(head) => repo
+                    .merge_base(head.id(), base_commit.id())
+                    .map_err(|e| format!("找不到 {} 的合并基点: {}", base, e))?,
+                None => base_commit.id(),
+            };
+            Some(
+                repo.find_commit(merge_base)
+                    .and_then(|c| c.tree())
+                    .map_err(|e| format!("读取合并基点失败: {}", e))?,
+            )
+        }
+        None => head_commit.as_ref().and_then(|c| c.tree().ok()),
+    };
+
+    let mut opts = git2::DiffOptions::new();
+    opts.pathspec(file)
+        .disable_pathspec_match(true)
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+    let mut diff = repo
+        .diff_tree_to_workdir_with_index(old_tree.as_ref(), Some(&mut opts))
+        .map_err(|e| format!("生成 diff 失败: {}", e))?;
+    let mut find_opts = git2::DiffFindOptions::new();
+    find_opts.renames(true);
+    let _ = diff.find_similar(Some(&mut find_opts));
+
+    let mut result = FileDiff {
+        file: file.to_string(),
+        old_file: None,
+        is_binary: false,
+        truncated: false,
+        hunks: vec![],
+    };
+    if diff.deltas().len() == 0 {
+        return Ok(result);
+    }
+
+    let patch = git2::Patch::from_diff(&diff, 0).map_err(|e| format!("生成 diff 失败: {}", e))?;
+    let delta = diff.get_delta(0).ok_or("生成 diff 失败: missing delta")?;
+    let old_path = delta.old_file().path().map(|p| p.to_string_lossy().replace('\\', "/"));
+    if old_path.as_deref() != Some(file) {
+        result.old_file = old_path;
+    }
+    result.is_binary = delta.flags().is_binary();
+    let Some(patch) = patch else {
+        // libgit2 yields no patch for binary content
+        result.is_binary = true;
+        return Ok(result);
+    };
+
+    let mut total_lines = 0usize;
+    'hunks: for h in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch.hunk(h).map_err(|e| format!("读取 diff 失败: {}", e))?;
+        let mut out = FileDiffHunk {
+            header: String::from_utf8_lossy(hunk.header()).trim_end().to_string(),
+            old_start: hunk.old_start(),
+            old_lines: hunk.old_lines(),
+            new_start: hunk.new_start(),
+            new_lines: hunk.new_lines(),
+            lines: Vec::with_capacity(line_count),
+        };
+        for l in 0..line_count {
+            if total_lines >= MAX_FILE_DIFF_LINES {
+                result.truncated = true;
+                result.hunks.push(out);
+                break 'hunks;
+            }
+            let line = patch
+                .line_in_hunk(h, l)
+                .map_err(|e| format!("读取 diff 失败: {}", e))?;
+            // Skip "\ No newline at end of file" markers and similar
+            if !matches!(line.origin(), '+' | '-' | ' ') {
+                continue;
+            }
+            out.lines.push(FileDiffLine {
+                origin: line.origin(),
+                old_lineno: line.old_lineno(),
+                new_lineno: line.new_lineno(),
+                content: String::from_utf8_lossy(line.content())
+                    .trim_end_matches(['\n', '\r'])
+                    .to_string(),
+            });
+            total_lines += 1;
+        }
+        result.hunks.push(out);
+    }
+
+    Ok(result)
+}
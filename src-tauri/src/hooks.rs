@@ -0,0 +1,301 @@
+//! Lifecycle hooks: user-configured shell commands run around worktree operations
+//! (create / archive / restore / delete / deploy to main), and webhooks notified after them.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use ring::hmac;
+use serde_json::json;
+
+use crate::ports::assigned_port_env;
+use crate::types::{HookConfig, HookEvent, HookRun, WebhookConfig, WorkspaceConfig};
+use crate::utils::{normalize_path, run_shell_command_with_timeout, HOOK_COMMAND_TIMEOUT_SECS};
+
+/// Keep the tail of hook output in results; the full transcript is in the operation log.
+const HOOK_OUTPUT_TAIL_CHARS: usize = 2000;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What a hook event fires for and where its commands run.
+pub(crate) struct HookContext {
+    pub workspace_path: String,
+    pub worktree_name: String,
+    pub worktree_path: PathBuf,
+    /// Working directory for hooks that aren't per-project
+    pub cwd: PathBuf,
+    /// (name, directory) of each project per-project hooks run in
+    pub projects: Vec<(String, PathBuf)>,
+}
+
+impl HookContext {
+    /// Context for a worktree directory; its projects are the `projects/*` subdirectories.
+    pub(crate) fn for_worktree(
+        workspace_path: &str,
+        worktree_name: &str,
+        worktree_path: &Path,
+    ) -> Self {
+        let mut projects: Vec<(String, PathBuf)> = fs::read_dir(worktree_path.join("projects"))
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|e| e.path())
+                    .filter(|p| p.is_dir())
+                    .filter_map(|p| Some((p.file_name()?.to_str()?.to_string(), p)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        projects.sort();
+        HookContext {
+            workspace_path: workspace_path.to_string(),
+            worktree_name: worktree_name.to_string(),
+            worktree_path: worktree_path.to_path_buf(),
+            cwd: worktree_path.to_path_buf(),
+            projects,
+        }
+    }
+}
+
+/// Hooks configured for an event, in configuration order.
+fn configured_hooks(config: &WorkspaceConfig, event: HookEvent) -> Vec<HookConfig> {
+    config
+        .hooks
+        .iter()
+        .filter(|h| h.event == event && !h.command.trim().is_empty())
+        .cloned()
+        .collect()
+}
+
+/// Run every hook configured for `event` in order. Blocking events stop at the first
+/// failure; pass the result to `ensure_hooks_succeeded` to abort the operation.
+pub(crate) fn run_hooks(
+    config: &WorkspaceConfig,
+    event: HookEvent,
+    ctx: &HookContext,
+) -> Vec<HookRun> {
+    let hooks = configured_hooks(config, event);
+    if hooks.is_empty() {
+        return vec![];
+    }
+    log::info!(
+        "[hooks] Running {} {} hooks for worktree '{}'",
+        hooks.len(),
+        event.as_str(),
+        ctx.worktree_name
+    );
+
+    let project_names: Vec<&str> = ctx.projects.iter().map(|(name, _)| name.as_str()).collect();
+    let base_env: Vec<(String, String)> = vec![
+        ("WM_HOOK_EVENT".to_string(), event.as_str().to_string()),
+        ("WM_WORKSPACE_NAME".to_string(), config.name.clone()),
+        ("WM_WORKSPACE_PATH".to_string(), ctx.workspace_path.clone()),
+        ("WM_WORKTREE_NAME".to_string(), ctx.worktree_name.clone()),
+        (
+            "WM_WORKTREE_PATH".to_string(),
+            normalize_path(&ctx.worktree_path.to_string_lossy()),
+        ),
+        ("WM_PROJECTS".to_string(), project_names.join(",")),
+    ];
+
+    let mut runs = vec![];
+    for hook in &hooks {
+        let targets: Vec<(Option<&str>, &Path)> = if hook.per_project {
+            ctx.projects
+                .iter()
+                .filter(|(name, _)| hook.projects.is_empty() || hook.projects.contains(name))
+                .map(|(name, dir)| (Some(name.as_str()), dir.as_path()))
+                .collect()
+        } else {
+            vec![(None, ctx.cwd.as_path())]
+        };
+        for (project, dir) in targets {
+            let mut envs = base_env.clone();
+            if let Some(name) = project {
+                envs.push(("WM_PROJECT_NAME".to_string(), name.to_string()));
+                envs.push((
+                    "WM_PROJECT_PATH".to_string(),
+                    normalize_path(&dir.to_string_lossy()),
+                ));
+            }
+            envs.extend(assigned_port_env(config, &ctx.worktree_path, project));
+            let run = run_hook(event, hook, project, dir, &envs);
+            let failed = !run.success;
+            runs.push(run);
+            if failed && event.is_blocking() {
+                return runs;
+            }
+        }
+    }
+    runs
+}
+
+fn run_hook(
+    event: HookEvent,
+    hook: &HookConfig,
+    project: Option<&str>,
+    dir: &Path,
+    envs: &[(String, String)],
+) -> HookRun {
+    let command = hook.command.trim();
+    let cwd = normalize_path(&dir.to_string_lossy());
+    let timeout = hook
+        .timeout_secs
+        .filter(|t| *t > 0)
+        .unwrap_or(HOOK_COMMAND_TIMEOUT_SECS);
+
+    log::info!("[hooks] {} hook '{}' in {}", event.as_str(), command, cwd);
+    let started = Instant::now();
+    let result = run_shell_command_with_timeout(command, &cwd, envs, timeout);
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    let (success, exit_code, output) = match result {
+        Ok(o) => (
+            o.status.success(),
+            o.status.code(),
+            output_tail(&o.stdout, &o.stderr),
+        ),
+        Err(e) => (false, None, e),
+    };
+    if success {
+        log::info!(
+            "[hooks] {} hook '{}' succeeded in {}ms",
+            event.as_str(),
+            command,
+            duration_ms
+        );
+    } else {
+        log::warn!(
+            "[hooks] {} hook '{}' failed in {} (exit {:?}): {}",
+            event.as_str(),
+            command,
+            cwd,
+            exit_code,
+            output
+        );
+    }
+
+    HookRun {
+        event,
+        command: command.to_string(),
+        project: project.map(str::to_string),
+        cwd,
+        success,
+        exit_code,
+        duration_ms,
+        output,
+    }
+}
+
+fn output_tail(stdout: &[u8], stderr: &[u8]) -> String {
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(stdout),
+        String::from_utf8_lossy(stderr)
+    );
+    let combined = combined.trim();
+    let skip = combined
+        .chars()
+        .count()
+        .saturating_sub(HOOK_OUTPUT_TAIL_CHARS);
+    combined.chars().skip(skip).collect()
+}
+
+/// Abort a blocking operation when one of its hooks failed, surfacing the hook output.
+pub(crate) fn ensure_hooks_succeeded(runs: &[HookRun]) -> Result<(), String> {
+    let Some(run) = runs.iter().find(|r| !r.success) else {
+        return Ok(());
+    };
+    let location = run
+        .project
+        .as_deref()
+        .map(|p| format!(" in {}", p))
+        .unwrap_or_default();
+    let exit = run
+        .exit_code
+        .map(|c| c.to_string())
+        .unwrap_or_else(|| "none".to_string());
+    Err(format!(
+        "{} hook `{}` failed{} (exit {}), operation aborted:\n{}",
+        run.event.as_str(),
+        run.command,
+        location,
+        exit,
+        run.output
+    ))
+}
+
+/// POST the event to every webhook in the workspace config subscribed to it. Runs in the
+/// background after the operation succeeded; delivery failures are only logged.
+pub(crate) fn send_webhooks(config: &WorkspaceConfig, event: HookEvent, ctx: &HookContext) {
+    let webhooks: Vec<WebhookConfig> = config
+        .webhooks
+        .iter()
+        .filter(|w| !w.url.trim().is_empty())
+        .filter(|w| w.events.is_empty() || w.events.contains(&event))
+        .cloned()
+        .collect();
+    if webhooks.is_empty() {
+        return;
+    }
+
+    let payload = json!({
+        "event": event.as_str(),
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "workspace": {
+            "name": config.name,
+            "path": normalize_path(&ctx.workspace_path),
+        },
+        "worktree": {
+            "name": ctx.worktree_name,
+            "path": normalize_path(&ctx.worktree_path.to_string_lossy()),
+        },
+        "projects": ctx.projects.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+    })
+    .to_string();
+
+    tauri::async_runtime::spawn(async move {
+        let client = match crate::network::http_client_builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                log::warn!("[hooks] Failed to create webhook client: {}", e);
+                return;
+            }
+        };
+        for webhook in webhooks {
+            let url = webhook.url.trim();
+            let mut request = client
+                .post(url)
+                .header("Content-Type", "application/json")
+                .header("X-Worktree-Manager-Event", event.as_str())
+                .body(payload.clone());
+            if let Some(secret) = webhook.secret.as_deref().filter(|s| !s.is_empty()) {
+                let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+                let signature = hex::encode(hmac::sign(&key, payload.as_bytes()).as_ref());
+                request = request.header(
+                    "X-Worktree-Manager-Signature-256",
+                    format!("sha256={}", signature),
+                );
+            }
+            match request.send().await {
+                Ok(resp) if resp.status().is_success() => {
+                    log::info!("[hooks] {} webhook delivered to {}", event.as_str(), url);
+                }
+                Ok(resp) => log::warn!(
+                    "[hooks] {} webhook to {} returned {}",
+                    event.as_str(),
+                    url,
+                    resp.status()
+                ),
+                Err(e) => log::warn!(
+                    "[hooks] {} webhook to {} failed: {}",
+                    event.as_str(),
+                    url,
+                    e
+                ),
+            }
+        }
+    });
+}
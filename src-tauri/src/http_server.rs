@@ -3,24 +3,32 @@ use axum::{
         ws::{Message, WebSocket, WebSocketUpgrade},
         ConnectInfo, Json, Query, Request,
     },
-    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    http::{header, Extensions, HeaderMap, HeaderValue, Method, StatusCode, Version},
     middleware::Next,
-    response::{IntoResponse, Response},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     routing::{get, post},
     Extension, Router,
 };
 use futures_util::{SinkExt, StreamExt};
 use serde::Deserialize;
 use serde_json::{json, Value};
-use std::collections::HashMap;
-use std::net::SocketAddr;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use tauri::Emitter;
 use tokio::sync::Mutex as TokioMutex;
+use tower_http::compression::predicate::{DefaultPredicate, NotForContentType, Predicate};
+use tower_http::compression::CompressionLayer;
 use tower_http::cors::CorsLayer;
 use tower_http::limit::RequestBodyLimitLayer;
 use tower_http::services::{ServeDir, ServeFile};
 
+use crate::config::resolve_workspace_path;
 use crate::pty_manager::bytes_to_utf8_with_pending;
 use crate::tls::TlsCerts;
 
@@ -29,33 +37,54 @@ use crate::{
     archive_worktree_impl,
     check_worktree_status_impl,
     clone_project_impl,
+    compose_down_impl,
+    compose_status_impl,
+    compose_up_impl,
+    convert_to_link_impl,
     create_worktree_impl,
     delete_archived_worktree_impl,
     deploy_to_main_impl,
     exit_main_occupation_impl,
     get_config_path_info_impl,
+    get_paths_impl,
     // _impl functions (window-context commands)
     get_current_workspace_impl,
     get_main_occupation_impl,
     get_main_workspace_status_impl,
     get_workspace_config_impl,
+    get_workspace_config_versioned_impl,
+    get_worktree_impl,
+    get_worktree_meta_impl,
+    get_worktree_issue_impl,
+    get_worktree_ports_impl,
+    get_worktree_status_impl,
+    kill_worktree_processes_impl,
     git_ops,
     list_worktrees_impl,
+    list_worktrees_page_impl,
     load_workspace_config,
     lock_worktree_impl,
-    normalize_path,
+    merge_to_test_branch_internal,
+    rename_worktree_branch_impl,
     restore_worktree_impl,
     save_workspace_config_impl,
     set_window_workspace_impl,
+    set_worktree_env_impl,
+    set_worktree_labels_impl,
+    set_worktree_meta_impl,
     switch_workspace_impl,
     unlock_worktree_impl,
     unregister_window_impl,
     AddProjectToWorktreeRequest,
+    AuthenticatedSession,
     CloneProjectRequest,
     ConnectedClient,
     CreateWorktreeRequest,
     OpenEditorRequest,
+    ShareActivity,
+    ShareRole,
     SwitchBranchRequest,
+    WorktreeListQuery,
     // WMS config & tunnel
     load_global_config,
     save_global_config_internal,
@@ -70,8 +99,12 @@ use crate::{
     LOCK_BROADCAST,
     NONCE_CACHE,
     PTY_MANAGER,
+    SERVER_STARTED_AT,
+    SHARE_ACTIVITY,
     SHARE_STATE,
+    SHUTTING_DOWN,
     TERMINAL_STATE_BROADCAST,
+    WORKSPACE_EVENT_BROADCAST,
 };
 
 // ---------------------------------------------------------------------------
@@ -100,13 +133,56 @@ fn session_id(headers: &HeaderMap) -> String {
 }
 
 /// Convert a Result<T, String> to an Axum response (200 with JSON or 400 with error text).
+/// Replace `path` with `rel_path` wherever a response carries both, so worktree and project
+/// listings never expose absolute host paths to remote clients.
+fn strip_host_paths(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            if let Some(rel) = map.get("rel_path").cloned() {
+                if map.contains_key("path") {
+                    map.insert("path".to_string(), rel);
+                }
+            }
+            map.values_mut().for_each(strip_host_paths);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_host_paths),
+        _ => {}
+    }
+}
+
 fn result_json<T: serde::Serialize>(r: Result<T, String>) -> Response {
     match r {
-        Ok(v) => (StatusCode::OK, Json(json!(v))).into_response(),
+        Ok(v) => {
+            let mut value = json!(v);
+            strip_host_paths(&mut value);
+            (StatusCode::OK, Json(value)).into_response()
+        }
         Err(e) => (StatusCode::BAD_REQUEST, e).into_response(),
     }
 }
 
+/// Run a git operation on a blocking thread as one recorded operation. The transcript id is
+/// sent in `x-operation-id` on success and failure, for `/api/get_operation_log`.
+async fn recorded_json<T, F>(operation: &'static str, f: F) -> Response
+where
+    T: serde::Serialize + Send + 'static,
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+{
+    let (result, id) =
+        match tokio::task::spawn_blocking(move || crate::oplog::with_operation(operation, f)).await
+        {
+            Ok(recorded) => recorded,
+            Err(e) => (Err(format!("Task join error: {}", e)), None),
+        };
+    let mut response = result_json(result);
+    if let Some(id) = id.and_then(|id| HeaderValue::from_str(&id).ok()) {
+        response.headers_mut().insert(OPERATION_ID_HEADER, id);
+    }
+    response
+}
+
+const OPERATION_ID_HEADER: &str = "x-operation-id";
+
 fn result_ok(r: Result<(), String>) -> Response {
     match r {
         Ok(()) => StatusCode::NO_CONTENT.into_response(),
@@ -171,9 +247,39 @@ async fn h_switch_workspace(headers: HeaderMap, Json(args): Json<Value>) -> Resp
     result_ok(switch_workspace_impl(&sid, path))
 }
 
-async fn h_get_workspace_config(headers: HeaderMap) -> Response {
+/// Viewers and operators may read the workspace config, but webhook signing secrets stay
+/// with admins (who are the only ones allowed to save it back).
+fn redact_workspace_config(config: &mut WorkspaceConfig, role: Option<ShareRole>) {
+    if role == Some(ShareRole::Admin) {
+        return;
+    }
+    for webhook in &mut config.webhooks {
+        webhook.secret = None;
+    }
+}
+
+async fn h_get_workspace_config(
+    headers: HeaderMap,
+    role: Option<Extension<ShareRole>>,
+) -> Response {
+    let sid = session_id(&headers);
+    result_json(get_workspace_config_impl(&sid).map(|mut config| {
+        redact_workspace_config(&mut config, role.map(|Extension(r)| r));
+        config
+    }))
+}
+
+async fn h_get_workspace_config_versioned(
+    headers: HeaderMap,
+    role: Option<Extension<ShareRole>>,
+) -> Response {
     let sid = session_id(&headers);
-    result_json(get_workspace_config_impl(&sid))
+    result_json(
+        get_workspace_config_versioned_impl(&sid).map(|mut versioned| {
+            redact_workspace_config(&mut versioned.config, role.map(|Extension(r)| r));
+            versioned
+        }),
+    )
 }
 
 async fn h_save_workspace_config(headers: HeaderMap, Json(args): Json<Value>) -> Response {
@@ -184,7 +290,13 @@ async fn h_save_workspace_config(headers: HeaderMap, Json(args): Json<Value>) ->
             return (StatusCode::BAD_REQUEST, format!("Invalid config: {}", e)).into_response()
         }
     };
-    result_ok(save_workspace_config_impl(&sid, config))
+    let base_etag = args["baseEtag"].as_str().map(|s| s.to_string());
+    result_json(save_workspace_config_impl(&sid, config, base_etag))
+}
+
+async fn h_validate_workspace_config(headers: HeaderMap) -> Response {
+    let sid = session_id(&headers);
+    result_json(crate::validate_workspace_config_impl(&sid))
 }
 
 async fn h_get_config_path_info(headers: HeaderMap) -> Response {
@@ -192,12 +304,64 @@ async fn h_get_config_path_info(headers: HeaderMap) -> Response {
     Json(json!(get_config_path_info_impl(&sid))).into_response()
 }
 
+async fn h_get_paths(headers: HeaderMap) -> Response {
+    let sid = session_id(&headers);
+    Json(json!(get_paths_impl(&sid))).into_response()
+}
+
 // -- Worktree operations --
 
 async fn h_list_worktrees(headers: HeaderMap, Json(args): Json<Value>) -> Response {
     let sid = session_id(&headers);
     let include_archived = args["includeArchived"].as_bool().unwrap_or(false);
-    result_json(list_worktrees_impl(&sid, include_archived))
+    let labels: Vec<String> = args["labels"]
+        .as_array()
+        .map(|a| a.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default();
+    let allowed = share_allowed_worktrees();
+    result_json(list_worktrees_impl(&sid, include_archived, &labels).map(|mut worktrees| {
+        if !allowed.is_empty() {
+            worktrees.retain(|w| is_worktree_allowed(&allowed, &w.name));
+        }
+        worktrees
+    }))
+}
+
+async fn h_list_worktrees_page(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let query: WorktreeListQuery = match serde_json::from_value(args["query"].clone()) {
+        Ok(q) => q,
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, format!("Invalid query: {}", e)).into_response()
+        }
+    };
+    let allowed = share_allowed_worktrees();
+    let result = tokio::task::spawn_blocking(move || {
+        list_worktrees_page_impl(&sid, query, |name| {
+            allowed.is_empty() || is_worktree_allowed(&allowed, name)
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+    .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_get_worktree(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let name = args["name"].as_str().unwrap_or("").to_string();
+    result_json(get_worktree_impl(&sid, name))
+}
+
+async fn h_get_worktree_status(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let name = args["name"].as_str().unwrap_or("").to_string();
+    let project = args["project"].as_str().map(|s| s.to_string());
+    let result = tokio::task::spawn_blocking(move || get_worktree_status_impl(&sid, name, project))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r);
+    result_json(result)
 }
 
 async fn h_get_main_workspace_status(headers: HeaderMap) -> Response {
@@ -213,13 +377,29 @@ async fn h_create_worktree(headers: HeaderMap, Json(args): Json<Value>) -> Respo
             return (StatusCode::BAD_REQUEST, format!("Invalid request: {}", e)).into_response()
         }
     };
-    result_json(create_worktree_impl(&sid, request))
+    recorded_json("create_worktree", move || {
+        create_worktree_impl(&sid, request)
+    })
+    .await
 }
 
 async fn h_archive_worktree(headers: HeaderMap, Json(args): Json<Value>) -> Response {
     let sid = session_id(&headers);
     let name = args["name"].as_str().unwrap_or("").to_string();
-    result_ok(archive_worktree_impl(&sid, name))
+    result_json(archive_worktree_impl(&sid, name))
+}
+
+async fn h_archive_worktrees(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let names: Vec<String> = args["names"]
+        .as_array()
+        .map(|a| a.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default();
+    let result = tokio::task::spawn_blocking(move || crate::archive_worktrees_impl(&sid, names))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r);
+    result_json(result)
 }
 
 async fn h_check_worktree_status(headers: HeaderMap, Json(args): Json<Value>) -> Response {
@@ -228,16 +408,130 @@ async fn h_check_worktree_status(headers: HeaderMap, Json(args): Json<Value>) ->
     result_json(check_worktree_status_impl(&sid, name))
 }
 
+async fn h_get_worktree_meta(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let name = args["name"].as_str().unwrap_or("").to_string();
+    result_json(get_worktree_meta_impl(&sid, name))
+}
+
+async fn h_get_worktree_ports(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let name = args["name"].as_str().unwrap_or("").to_string();
+    let result = tokio::task::spawn_blocking(move || get_worktree_ports_impl(&sid, name))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_get_worktree_issue(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let name = args["name"].as_str().unwrap_or("").to_string();
+    result_json(get_worktree_issue_impl(&sid, name).await)
+}
+
+async fn h_compose_status(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let name = args["name"].as_str().unwrap_or("").to_string();
+    let project = args["project"].as_str().unwrap_or("").to_string();
+    let result = tokio::task::spawn_blocking(move || compose_status_impl(&sid, name, project))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_compose_up(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let name = args["name"].as_str().unwrap_or("").to_string();
+    let project = args["project"].as_str().unwrap_or("").to_string();
+    let result = tokio::task::spawn_blocking(move || compose_up_impl(&sid, name, project))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_compose_down(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let name = args["name"].as_str().unwrap_or("").to_string();
+    let project = args["project"].as_str().unwrap_or("").to_string();
+    let result = tokio::task::spawn_blocking(move || compose_down_impl(&sid, name, project))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_kill_worktree_processes(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let path = args["path"].as_str().unwrap_or("").to_string();
+    let result = tokio::task::spawn_blocking(move || kill_worktree_processes_impl(&sid, path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_set_worktree_meta(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let name = args["name"].as_str().unwrap_or("").to_string();
+    let description = args["description"].as_str().unwrap_or("").to_string();
+    let ticket_url = args["ticketUrl"].as_str().unwrap_or("").to_string();
+    result_json(set_worktree_meta_impl(&sid, name, description, ticket_url))
+}
+
+async fn h_set_worktree_env(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let name = args["name"].as_str().unwrap_or("").to_string();
+    let env: BTreeMap<String, String> =
+        serde_json::from_value(args["env"].clone()).unwrap_or_default();
+    result_json(set_worktree_env_impl(&sid, name, env))
+}
+
+async fn h_set_worktree_labels(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let name = args["name"].as_str().unwrap_or("").to_string();
+    let labels: Vec<String> = args["labels"]
+        .as_array()
+        .map(|a| a.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default();
+    result_json(set_worktree_labels_impl(&sid, name, labels))
+}
+
 async fn h_restore_worktree(headers: HeaderMap, Json(args): Json<Value>) -> Response {
     let sid = session_id(&headers);
     let name = args["name"].as_str().unwrap_or("").to_string();
-    result_ok(restore_worktree_impl(&sid, name))
+    result_json(restore_worktree_impl(&sid, name))
 }
 
 async fn h_delete_archived_worktree(headers: HeaderMap, Json(args): Json<Value>) -> Response {
     let sid = session_id(&headers);
     let name = args["name"].as_str().unwrap_or("").to_string();
-    result_ok(delete_archived_worktree_impl(&sid, name))
+    let delete_remote = args["deleteRemote"].as_bool().unwrap_or(false);
+    result_json(
+        tokio::task::spawn_blocking(move || {
+            delete_archived_worktree_impl(&sid, name, delete_remote)
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r),
+    )
+}
+
+async fn h_rename_worktree_branch(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let worktree_name = args["worktreeName"].as_str().unwrap_or("").to_string();
+    let new_name = args["newName"].as_str().unwrap_or("").to_string();
+    let push = args["push"].as_bool().unwrap_or(false);
+    result_json(
+        tokio::task::spawn_blocking(move || {
+            rename_worktree_branch_impl(&sid, worktree_name, new_name, push)
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r),
+    )
 }
 
 async fn h_add_project_to_worktree(headers: HeaderMap, Json(args): Json<Value>) -> Response {
@@ -252,10 +546,153 @@ async fn h_add_project_to_worktree(headers: HeaderMap, Json(args): Json<Value>)
     result_ok(add_project_to_worktree_impl(&sid, request))
 }
 
+async fn h_get_sync_advice(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let worktree_name = args["worktreeName"].as_str().unwrap_or("").to_string();
+    let result =
+        tokio::task::spawn_blocking(move || crate::get_sync_advice_impl(&sid, worktree_name))
+            .await
+            .map_err(|e| format!("Task join error: {}", e))
+            .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_sync_safe_projects(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let worktree_name = args["worktreeName"].as_str().unwrap_or("").to_string();
+    let result =
+        tokio::task::spawn_blocking(move || crate::sync_safe_projects_impl(&sid, worktree_name))
+            .await
+            .map_err(|e| format!("Task join error: {}", e))
+            .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_refresh_workspace(headers: HeaderMap) -> Response {
+    let sid = session_id(&headers);
+    let allowed = share_allowed_worktrees();
+    let result = tokio::task::spawn_blocking(move || crate::refresh_workspace_impl(&sid))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r)
+        .map(|mut refreshed| {
+            if !allowed.is_empty() {
+                refreshed.worktrees.retain(|w| is_worktree_allowed(&allowed, &w.name));
+            }
+            refreshed
+        });
+    result_json(result)
+}
+
+async fn h_fetch_all_projects(headers: HeaderMap) -> Response {
+    let sid = session_id(&headers);
+    let result = tokio::task::spawn_blocking(move || crate::fetch_all_projects_impl(&sid))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_list_actions(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let worktree_name = args["worktreeName"].as_str().map(|s| s.to_string());
+    let project_name = args["projectName"].as_str().map(|s| s.to_string());
+    Json(crate::list_actions_impl(&sid, worktree_name, project_name)).into_response()
+}
+
+async fn h_get_base_updates(headers: HeaderMap) -> Response {
+    let sid = session_id(&headers);
+    result_json(crate::get_base_updates_impl(&sid))
+}
+
+async fn h_get_disk_usage(headers: HeaderMap) -> Response {
+    let sid = session_id(&headers);
+    let result = tokio::task::spawn_blocking(move || crate::get_disk_usage_impl(&sid))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_export_workspace_manifest(headers: HeaderMap) -> Response {
+    let sid = session_id(&headers);
+    result_json(crate::export_workspace_manifest_impl(&sid))
+}
+
+async fn h_import_workspace_manifest(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let manifest: crate::WorkspaceManifest = match serde_json::from_value(args["manifest"].clone())
+    {
+        Ok(m) => m,
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, format!("Invalid manifest: {}", e)).into_response()
+        }
+    };
+    let result = tokio::task::spawn_blocking(move || {
+        crate::import_workspace_manifest_impl(&sid, manifest)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+    .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_convert_to_link(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let worktree_name = args["worktreeName"].as_str().unwrap_or("").to_string();
+    let project_name = args["projectName"].as_str().map(|s| s.to_string());
+    let item = args["item"].as_str().unwrap_or("").to_string();
+    let merge = args["merge"].as_bool().unwrap_or(false);
+    result_json(convert_to_link_impl(
+        &sid,
+        worktree_name,
+        project_name,
+        item,
+        merge,
+    ))
+}
+
+async fn h_sync_links(headers: HeaderMap) -> Response {
+    let sid = session_id(&headers);
+    let result = tokio::task::spawn_blocking(move || crate::sync_links_impl(&sid))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_repair_worktrees(headers: HeaderMap) -> Response {
+    let sid = session_id(&headers);
+    let result = tokio::task::spawn_blocking(move || crate::repair_worktrees_impl(&sid))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_get_dirty_report(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let worktree_name = args["worktreeName"].as_str().map(|s| s.to_string());
+    let project_names: Vec<String> = args["projectNames"]
+        .as_array()
+        .map(|a| {
+            a.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+    result_json(crate::get_dirty_report_impl(
+        &sid,
+        worktree_name,
+        project_names,
+    ))
+}
+
 async fn h_deploy_to_main(headers: HeaderMap, Json(args): Json<Value>) -> Response {
     let sid = session_id(&headers);
     let worktree_name = args["worktreeName"].as_str().unwrap_or("").to_string();
-    result_json(deploy_to_main_impl(&sid, worktree_name))
+    let dirty_ack = args["dirtyAck"].as_str().map(|s| s.to_string());
+    result_json(deploy_to_main_impl(&sid, worktree_name, dirty_ack))
 }
 
 async fn h_exit_main_occupation(headers: HeaderMap, Json(args): Json<Value>) -> Response {
@@ -282,39 +719,94 @@ async fn h_clone_project(headers: HeaderMap, Json(args): Json<Value>) -> Respons
 
 // -- Git operations --
 
-async fn h_switch_branch(Json(args): Json<Value>) -> Response {
-    let request: SwitchBranchRequest = match serde_json::from_value(args["request"].clone()) {
+/// 解析客户端传入的路径参数（支持 workspace 相对路径），失败时返回 400
+fn resolve_path_arg(sid: &str, args: &Value, key: &str) -> Result<String, Box<Response>> {
+    let raw = args[key].as_str().unwrap_or("");
+    resolve_workspace_path(sid, raw)
+        .map_err(|e| Box::new((StatusCode::BAD_REQUEST, e).into_response()))
+}
+
+async fn h_switch_branch(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let mut request: SwitchBranchRequest = match serde_json::from_value(args["request"].clone()) {
         Ok(r) => r,
         Err(e) => {
             return (StatusCode::BAD_REQUEST, format!("Invalid request: {}", e)).into_response()
         }
     };
-    result_ok(crate::switch_branch_internal(&request))
+    request.project_path = match resolve_workspace_path(&sid, &request.project_path) {
+        Ok(p) => p,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    if let Err(e) = crate::commands::worktree::guard_main_project_switch(
+        &sid,
+        &request.project_path,
+        request.dirty_ack.as_deref(),
+    ) {
+        return (StatusCode::BAD_REQUEST, e).into_response();
+    }
+    let remote = crate::config::remote_for_path(&sid, &request.project_path);
+    result_ok(crate::switch_branch_internal(&request, &remote))
 }
 
-async fn h_get_branch_diff_stats(Json(args): Json<Value>) -> Response {
-    let path = args["path"].as_str().unwrap_or("").to_string();
+async fn h_get_branch_diff_stats(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
     let base_branch = args["baseBranch"].as_str().unwrap_or("").to_string();
-    let normalized = normalize_path(&path);
-    let stats = git_ops::get_branch_diff_stats(std::path::Path::new(&normalized), &base_branch);
+    let remote = crate::config::remote_for_path(&sid, &normalized);
+    let stats =
+        git_ops::get_branch_diff_stats(std::path::Path::new(&normalized), &remote, &base_branch);
     Json(json!(stats)).into_response()
 }
 
-async fn h_check_remote_branch_exists(Json(args): Json<Value>) -> Response {
-    let path = args["path"].as_str().unwrap_or("").to_string();
+async fn h_get_file_diff(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    let file = args["file"].as_str().unwrap_or("").to_string();
+    let base_ref = args["baseRef"].as_str().map(String::from);
+    let result = tokio::task::spawn_blocking(move || {
+        git_ops::get_file_diff(
+            std::path::Path::new(&normalized),
+            &file,
+            base_ref.as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+    .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_check_remote_branch_exists(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
     let branch_name = args["branchName"].as_str().unwrap_or("").to_string();
-    let normalized = normalize_path(&path);
+    let remote = crate::config::remote_for_path(&sid, &normalized);
     result_json(git_ops::check_remote_branch_exists(
         std::path::Path::new(&normalized),
+        &remote,
         &branch_name,
     ))
 }
 
-async fn h_fetch_project_remote(Json(args): Json<Value>) -> Response {
-    let path = args["path"].as_str().unwrap_or("").to_string();
-    let normalized = normalize_path(&path);
+async fn h_fetch_project_remote(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    let remote = crate::config::remote_for_path(&sid, &normalized);
     let result = tokio::task::spawn_blocking(move || {
-        git_ops::fetch_remote(std::path::Path::new(&normalized))
+        git_ops::fetch_remote(std::path::Path::new(&normalized), &remote)
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))
@@ -322,12 +814,22 @@ async fn h_fetch_project_remote(Json(args): Json<Value>) -> Response {
     result_json(result)
 }
 
-async fn h_sync_with_base_branch(Json(args): Json<Value>) -> Response {
-    let path = args["path"].as_str().unwrap_or("").to_string();
+async fn h_sync_with_base_branch(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
     let base_branch = args["baseBranch"].as_str().unwrap_or("").to_string();
-    let normalized = normalize_path(&path);
+    let strategy = crate::config::merge_strategy_for_path(&sid, &normalized);
+    let remote = crate::config::remote_for_path(&sid, &normalized);
     let result = tokio::task::spawn_blocking(move || {
-        git_ops::sync_with_base_branch(std::path::Path::new(&normalized), &base_branch)
+        git_ops::sync_with_base_branch(
+            std::path::Path::new(&normalized),
+            &remote,
+            &base_branch,
+            strategy,
+        )
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))
@@ -335,11 +837,22 @@ async fn h_sync_with_base_branch(Json(args): Json<Value>) -> Response {
     result_json(result)
 }
 
-async fn h_push_to_remote(Json(args): Json<Value>) -> Response {
-    let path = args["path"].as_str().unwrap_or("").to_string();
-    let normalized = normalize_path(&path);
+async fn h_amend_commit(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    let new_message = args["newMessage"].as_str().unwrap_or("").to_string();
+    let include_staged = args["includeStaged"].as_bool().unwrap_or(false);
+    let remote = crate::config::remote_for_path(&sid, &normalized);
     let result = tokio::task::spawn_blocking(move || {
-        git_ops::push_to_remote(std::path::Path::new(&normalized))
+        git_ops::amend_commit(
+            std::path::Path::new(&normalized),
+            &remote,
+            &new_message,
+            include_staged,
+        )
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))
@@ -347,12 +860,15 @@ async fn h_push_to_remote(Json(args): Json<Value>) -> Response {
     result_json(result)
 }
 
-async fn h_merge_to_test_branch(Json(args): Json<Value>) -> Response {
-    let path = args["path"].as_str().unwrap_or("").to_string();
-    let test_branch = args["testBranch"].as_str().unwrap_or("").to_string();
-    let normalized = normalize_path(&path);
+async fn h_push_to_remote(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    let remote = crate::config::remote_for_path(&sid, &normalized);
     let result = tokio::task::spawn_blocking(move || {
-        git_ops::merge_to_test_branch(std::path::Path::new(&normalized), &test_branch)
+        git_ops::push_to_remote(std::path::Path::new(&normalized), &remote)
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))
@@ -360,44 +876,55 @@ async fn h_merge_to_test_branch(Json(args): Json<Value>) -> Response {
     result_json(result)
 }
 
-async fn h_merge_to_base_branch(Json(args): Json<Value>) -> Response {
-    let path = args["path"].as_str().unwrap_or("").to_string();
-    let base_branch = args["baseBranch"].as_str().unwrap_or("").to_string();
-    let normalized = normalize_path(&path);
-    let result = tokio::task::spawn_blocking(move || {
-        git_ops::merge_to_base_branch(std::path::Path::new(&normalized), &base_branch)
+async fn h_merge_to_test_branch(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    let test_branch = args["testBranch"].as_str().unwrap_or("").to_string();
+    let keep_conflicts = args["keepConflicts"].as_bool().unwrap_or(false);
+    let remote = crate::config::remote_for_path(&sid, &normalized);
+    recorded_json("merge_to_test_branch", move || {
+        merge_to_test_branch_internal(
+            std::path::Path::new(&normalized),
+            &remote,
+            &test_branch,
+            keep_conflicts,
+        )
     })
     .await
-    .map_err(|e| format!("Task join error: {}", e))
-    .and_then(|r| r);
-    result_json(result)
 }
 
-async fn h_create_pull_request(Json(args): Json<Value>) -> Response {
-    let path = args["path"].as_str().unwrap_or("").to_string();
+async fn h_merge_to_base_branch(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
     let base_branch = args["baseBranch"].as_str().unwrap_or("").to_string();
-    let title = args["title"].as_str().unwrap_or("").to_string();
-    let body = args["body"].as_str().unwrap_or("").to_string();
-    let normalized = normalize_path(&path);
-    let result = tokio::task::spawn_blocking(move || {
-        git_ops::create_pull_request(
+    let strategy = crate::config::merge_strategy_for_path(&sid, &normalized);
+    let remote = crate::config::remote_for_path(&sid, &normalized);
+    recorded_json("merge_to_base_branch", move || {
+        git_ops::merge_to_base_branch(
             std::path::Path::new(&normalized),
+            &remote,
             &base_branch,
-            &title,
-            &body,
+            strategy,
         )
     })
     .await
-    .map_err(|e| format!("Task join error: {}", e))
-    .and_then(|r| r);
-    result_json(result)
 }
 
-async fn h_get_remote_branches(Json(args): Json<Value>) -> Response {
-    let path = args["path"].as_str().unwrap_or("").to_string();
-    let normalized = normalize_path(&path);
+async fn h_cherry_pick(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    let commit_shas = string_array_arg(&args, "commitShas");
     let result = tokio::task::spawn_blocking(move || {
-        git_ops::get_remote_branches(std::path::Path::new(&normalized))
+        git_ops::cherry_pick_commits(std::path::Path::new(&normalized), &commit_shas)
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))
@@ -405,39 +932,416 @@ async fn h_get_remote_branches(Json(args): Json<Value>) -> Response {
     result_json(result)
 }
 
-// -- Scan --
+async fn h_list_merge_conflicts(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    result_json(git_ops::list_merge_conflicts(std::path::Path::new(&normalized)))
+}
+
+async fn h_resolve_conflict(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    let file = args["file"].as_str().unwrap_or("");
+    let resolution = args["resolution"].as_str().unwrap_or("");
+    result_ok(git_ops::resolve_conflict(
+        std::path::Path::new(&normalized),
+        file,
+        resolution,
+    ))
+}
+
+async fn h_continue_merge(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    let result = tokio::task::spawn_blocking(move || {
+        git_ops::continue_merge(std::path::Path::new(&normalized))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+    .and_then(|r| r);
+    result_json(result)
+}
+
+/// Read a string array argument (missing or malformed = empty)
+fn string_array_arg(args: &Value, key: &str) -> Vec<String> {
+    args[key]
+        .as_array()
+        .map(|a| a.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+async fn h_discard_changes(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    let files = string_array_arg(&args, "files");
+    let dry_run = args["dryRun"].as_bool().unwrap_or(false);
+    let result = tokio::task::spawn_blocking(move || {
+        git_ops::discard_changes(std::path::Path::new(&normalized), &files, dry_run)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+    .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_clean_untracked(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    let paths = string_array_arg(&args, "paths");
+    let dry_run = args["dryRun"].as_bool().unwrap_or(false);
+    let result = tokio::task::spawn_blocking(move || {
+        git_ops::clean_untracked(std::path::Path::new(&normalized), &paths, dry_run)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+    .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_normalize_line_endings(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    let result = tokio::task::spawn_blocking(move || {
+        git_ops::normalize_line_endings(std::path::Path::new(&normalized))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+    .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_abort_merge(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    result_json(git_ops::abort_merge(std::path::Path::new(&normalized)))
+}
+
+async fn h_create_pull_request(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    let base_branch = args["baseBranch"].as_str().unwrap_or("").to_string();
+    let title = args["title"].as_str().unwrap_or("").to_string();
+    let body = args["body"].as_str().unwrap_or("").to_string();
+    let remote = crate::config::remote_for_path(&sid, &normalized);
+    let result = tokio::task::spawn_blocking(move || {
+        git_ops::create_pull_request(
+            std::path::Path::new(&normalized),
+            &remote,
+            &base_branch,
+            &title,
+            &body,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+    .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_get_ci_status(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    let branch = args["branch"].as_str().unwrap_or("").to_string();
+    let remote = crate::config::remote_for_path(&sid, &normalized);
+    let result = tokio::task::spawn_blocking(move || {
+        git_ops::get_ci_status(std::path::Path::new(&normalized), &remote, &branch)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+    .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_get_remote_branches(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    let remote = crate::config::remote_for_path(&sid, &normalized);
+    let result = tokio::task::spawn_blocking(move || {
+        git_ops::get_remote_branches(std::path::Path::new(&normalized), &remote)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+    .and_then(|r| r);
+    result_json(result)
+}
+
+/// Responds with the zip itself so the browser can download it directly.
+async fn h_export_diff_zip(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    let base_branch = args["baseBranch"].as_str().unwrap_or("").to_string();
+    let remote = crate::config::remote_for_path(&sid, &normalized);
+    let result = tokio::task::spawn_blocking(move || {
+        git_ops::build_diff_archive(std::path::Path::new(&normalized), &remote, &base_branch)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+    .and_then(|r| r);
+
+    match result {
+        Ok((bytes, summary)) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "application/zip".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    attachment_disposition(&summary.file_name),
+                ),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e).into_response(),
+    }
+}
+
+/// `Content-Disposition` for a download named `file_name` (non-ASCII and quotes replaced).
+fn attachment_disposition(file_name: &str) -> String {
+    let file_name: String = file_name
+        .chars()
+        .map(|c| if c.is_ascii_graphic() && c != '"' { c } else { '_' })
+        .collect();
+    format!("attachment; filename=\"{}\"", file_name)
+}
+
+#[derive(Deserialize)]
+struct DownloadParams {
+    path: Option<String>,
+}
+
+/// `GET /api/download?path=...`: zip of a folder or file inside the workspace (e.g. build
+/// artifacts in a worktree), streamed from a temp file so large downloads don't sit in memory.
+/// Every download is recorded in the share activity feed.
+async fn h_download(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Query(params): Query<DownloadParams>,
+) -> Response {
+    use tokio::io::AsyncReadExt;
+
+    let sid = session_id(&headers);
+    let raw = params.path.unwrap_or_default();
+    let (target, rel) = match crate::download::resolve_target(&sid, &raw) {
+        Ok(t) => t,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+
+    // The worktree scope middleware only sees JSON bodies, so check the query path here
+    let allowed = share_allowed_worktrees();
+    if !allowed.is_empty() {
+        let worktrees_dir = crate::config::get_window_workspace_config(&sid)
+            .map(|(_, config)| config.worktrees_dir)
+            .unwrap_or_default();
+        let worktree = worktree_of_path("", &worktrees_dir, &rel.to_string_lossy());
+        if !worktree.is_some_and(|name| is_worktree_allowed(&allowed, &name)) {
+            return (StatusCode::FORBIDDEN, "当前分享配置不允许下载该路径").into_response();
+        }
+    }
 
-async fn h_scan_linked_folders(Json(args): Json<Value>) -> Response {
-    let project_path = args["projectPath"].as_str().unwrap_or("").to_string();
+    let started = std::time::Instant::now();
+    let result = tokio::task::spawn_blocking(move || crate::download::build_archive(&target))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r);
+
+    let (status, summary) = match &result {
+        Ok(archive) => (
+            StatusCode::OK,
+            format!(
+                "path={}, {} files, {}",
+                rel.display(),
+                archive.file_count,
+                crate::utils::format_size(archive.size_bytes)
+            ),
+        ),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            format!("path={}, {}", rel.display(), e),
+        ),
+    };
+    log::info!(
+        "[http] Download from {} (session {}): {}",
+        addr.ip(),
+        sid,
+        summary
+    );
+    record_share_activity(ShareActivity {
+        session_id: sid,
+        ip: addr.ip().to_string(),
+        command: "download".to_string(),
+        summary: Some(summary),
+        status: status.as_u16(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        at: chrono::Utc::now().to_rfc3339(),
+    });
+
+    let archive = match result {
+        Ok(archive) => archive,
+        Err(e) => return (status, e).into_response(),
+    };
+    let file = match tokio::fs::File::open(&archive.path).await {
+        Ok(f) => f,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to open archive: {}", e),
+            )
+                .into_response()
+        }
+    };
+    let length = file.metadata().await.map(|m| m.len()).unwrap_or(0);
+    let disposition = attachment_disposition(&archive.file_name);
+
+    // The archive (and so its temp file) lives until the body is sent or the client goes away
+    let stream = futures_util::stream::unfold(Some((file, archive)), |state| async move {
+        let (mut file, archive) = state?;
+        let mut buf = vec![0u8; 64 * 1024];
+        match file.read(&mut buf).await {
+            Ok(0) => None,
+            Ok(n) => {
+                buf.truncate(n);
+                Some((
+                    Ok::<_, std::io::Error>(axum::body::Bytes::from(buf)),
+                    Some((file, archive)),
+                ))
+            }
+            Err(e) => Some((Err(e), None)),
+        }
+    });
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+            (header::CONTENT_LENGTH, length.to_string()),
+        ],
+        axum::body::Body::from_stream(stream),
+    )
+        .into_response()
+}
+
+async fn h_get_operation_log(Json(args): Json<Value>) -> Response {
+    let id = args["id"].as_str().unwrap_or("").to_string();
+    result_json(crate::oplog::get_operation_log_inner(&id))
+}
+
+async fn h_list_operation_logs() -> Response {
+    Json(json!(crate::oplog::list_operation_logs_inner())).into_response()
+}
+
+// -- Scan --
+
+async fn h_scan_linked_folders(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let project_path = match resolve_path_arg(&sid, &args, "projectPath") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
     result_json(crate::scan_linked_folders_internal(&project_path))
 }
 
 // -- System utilities --
 
-async fn h_open_in_terminal(Json(args): Json<Value>) -> Response {
-    let path = args["path"].as_str().unwrap_or("").to_string();
-    result_ok(crate::open_in_terminal_internal(&path))
+async fn h_open_in_terminal(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    result_ok(crate::open_in_terminal_internal(&normalized))
 }
 
-async fn h_open_in_editor(Json(args): Json<Value>) -> Response {
-    let request: OpenEditorRequest = match serde_json::from_value(args["request"].clone()) {
+async fn h_open_in_editor(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let mut request: OpenEditorRequest = match serde_json::from_value(args["request"].clone()) {
         Ok(r) => r,
         Err(e) => {
             return (StatusCode::BAD_REQUEST, format!("Invalid request: {}", e)).into_response()
         }
     };
+    request.path = match resolve_workspace_path(&sid, &request.path) {
+        Ok(p) => p,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
     result_ok(crate::open_in_editor_internal(&request))
 }
 
-async fn h_reveal_in_finder(Json(args): Json<Value>) -> Response {
-    let path = args["path"].as_str().unwrap_or("").to_string();
-    result_ok(crate::reveal_in_finder_internal(&path))
+async fn h_open_in_devcontainer(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    let result = tokio::task::spawn_blocking(move || crate::devcontainer::open(&normalized))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r);
+    result_ok(result)
+}
+
+async fn h_get_devcontainer_status(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    let result = tokio::task::spawn_blocking(move || crate::devcontainer::status(&normalized))
+        .await
+        .map_err(|e| format!("Task join error: {}", e));
+    result_json(result)
+}
+
+async fn h_reveal_in_finder(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let normalized = match resolve_path_arg(&sid, &args, "path") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    result_ok(crate::reveal_in_finder_internal(&normalized))
 }
 
 async fn h_open_log_dir() -> Response {
     result_ok(crate::open_log_dir_internal())
 }
 
+async fn h_tail_app_logs(Json(args): Json<Value>) -> Response {
+    let lines = args["lines"].as_u64().map(|v| v as usize);
+    result_json(crate::commands::system::tail_app_logs_internal(lines))
+}
+
 // -- Multi-window management --
 
 async fn h_get_opened_workspaces() -> Response {
@@ -504,18 +1408,64 @@ where
     .unwrap_or_else(|e| Err(format!("Task error: {}", e)))
 }
 
-async fn h_pty_create(Json(args): Json<Value>) -> Response {
+async fn h_pty_create(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let session_id = args["sessionId"].as_str().unwrap_or("").to_string();
+    let cwd = match resolve_path_arg(&sid, &args, "cwd") {
+        Ok(p) => p,
+        Err(r) => return *r,
+    };
+    let cols = args["cols"].as_u64().unwrap_or(80) as u16;
+    let rows = args["rows"].as_u64().unwrap_or(24) as u16;
+    let (env, shell) = {
+        let cwd = cwd.clone();
+        tokio::task::spawn_blocking(move || {
+            (
+                crate::commands::pty::terminal_env(&sid, &cwd),
+                crate::config::shell_for_path(&sid, &cwd),
+            )
+        })
+        .await
+        .unwrap_or_default()
+    };
+    result_ok(
+        with_pty_manager(move |m| {
+            m.create_session(&session_id, &cwd, cols, rows, &env, shell.as_ref())
+        })
+        .await,
+    )
+}
+
+async fn h_pty_create_ssh(Json(args): Json<Value>) -> Response {
     let session_id = args["sessionId"].as_str().unwrap_or("").to_string();
-    let cwd = args["cwd"].as_str().unwrap_or("").to_string();
+    let host = args["host"].as_str().unwrap_or("").to_string();
+    let user = args["user"].as_str().map(String::from);
+    let cwd = args["cwd"].as_str().map(String::from);
     let cols = args["cols"].as_u64().unwrap_or(80) as u16;
     let rows = args["rows"].as_u64().unwrap_or(24) as u16;
-    result_ok(with_pty_manager(move |m| m.create_session(&session_id, &cwd, cols, rows)).await)
+    let result = tokio::task::spawn_blocking(move || {
+        crate::pty_create_ssh_impl(&session_id, &host, user, cwd, cols, rows)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+    .and_then(|r| r);
+    result_ok(result)
 }
 
-async fn h_pty_write(Json(args): Json<Value>) -> Response {
+async fn h_pty_write(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
     let session_id = args["sessionId"].as_str().unwrap_or("").to_string();
     let data = args["data"].as_str().unwrap_or("").to_string();
-    result_ok(with_pty_manager(move |m| m.write_to_session(&session_id, &data)).await)
+    let pty_id = session_id.clone();
+    let input = data.clone();
+    let result = with_pty_manager(move |m| m.write_to_session(&session_id, &data)).await;
+    if result.is_ok() {
+        tokio::task::spawn_blocking(move || {
+            crate::commands::window::note_terminal_activity(&sid, &pty_id);
+            crate::command_history::record_input(&sid, &pty_id, &input);
+        });
+    }
+    result_ok(result)
 }
 
 async fn h_pty_read(Json(args): Json<Value>) -> Response {
@@ -532,12 +1482,191 @@ async fn h_pty_resize(Json(args): Json<Value>) -> Response {
 
 async fn h_pty_close(Json(args): Json<Value>) -> Response {
     let session_id = args["sessionId"].as_str().unwrap_or("").to_string();
-    result_ok(with_pty_manager(move |m| m.close_session(&session_id)).await)
+    result_ok(
+        with_pty_manager(move |m| {
+            crate::command_history::forget_session(&session_id);
+            m.close_session(&session_id)
+        })
+        .await,
+    )
+}
+
+async fn h_pty_exists(Json(args): Json<Value>) -> Response {
+    let session_id = args["sessionId"].as_str().unwrap_or("").to_string();
+    result_json(with_pty_manager(move |m| Ok(m.has_session(&session_id))).await)
+}
+
+async fn h_pty_get_scrollback(Json(args): Json<Value>) -> Response {
+    let session_id = args["sessionId"].as_str().unwrap_or("").to_string();
+    let lines = args["lines"].as_u64().map(|n| n as usize);
+    let result = tokio::task::spawn_blocking(move || crate::pty_get_scrollback_impl(&session_id, lines))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_pty_search_output(Json(args): Json<Value>) -> Response {
+    let session_id = args["sessionId"].as_str().unwrap_or("").to_string();
+    let pattern = args["pattern"].as_str().unwrap_or("").to_string();
+    let max_matches = args["maxMatches"].as_u64().map(|n| n as usize);
+    let result = tokio::task::spawn_blocking(move || {
+        crate::pty_search_output_impl(&session_id, &pattern, max_matches)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+    .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_pty_start_recording(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let pty_session_id = args["sessionId"].as_str().unwrap_or("").to_string();
+    let title = args["title"].as_str().map(String::from);
+    result_json(crate::pty_start_recording_impl(
+        &sid,
+        &pty_session_id,
+        title,
+    ))
+}
+
+async fn h_pty_stop_recording(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let pty_session_id = args["sessionId"].as_str().unwrap_or("").to_string();
+    result_json(crate::pty_stop_recording_impl(&sid, &pty_session_id))
+}
+
+async fn h_pty_get_recording(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let pty_session_id = args["sessionId"].as_str().unwrap_or("").to_string();
+    result_json(crate::pty_get_recording_impl(&sid, &pty_session_id))
+}
+
+async fn h_list_recordings(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let worktree_name = args["worktreeName"].as_str().map(String::from);
+    let result =
+        tokio::task::spawn_blocking(move || crate::list_recordings_impl(&sid, worktree_name))
+            .await
+            .map_err(|e| format!("Task join error: {}", e))
+            .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_read_recording(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let worktree_name = args["worktreeName"].as_str().map(String::from);
+    let file_name = args["fileName"].as_str().unwrap_or("").to_string();
+    let result = tokio::task::spawn_blocking(move || {
+        crate::read_recording_impl(&sid, worktree_name, &file_name)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+    .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_get_command_history(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let worktree_name = args["worktreeName"].as_str().map(String::from);
+    let query = args["query"].as_str().map(String::from);
+    let limit = args["limit"].as_u64().map(|l| l as usize);
+    let result = tokio::task::spawn_blocking(move || {
+        crate::get_command_history_impl(&sid, worktree_name, query, limit)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+    .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_pty_restore_sessions(headers: HeaderMap) -> Response {
+    let sid = session_id(&headers);
+    let result = tokio::task::spawn_blocking(move || crate::pty_restore_sessions_impl(&sid))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|r| r);
+    result_json(result)
+}
+
+async fn h_get_session_restore_enabled() -> Response {
+    result_json(crate::commands::pty::get_session_restore_enabled_inner())
+}
+
+async fn h_get_ssh_hosts() -> Response {
+    result_json(crate::commands::pty::get_ssh_hosts_inner())
+}
+
+async fn h_get_pty_limits() -> Response {
+    result_json(crate::commands::pty::get_pty_limits_inner())
+}
+
+async fn h_get_rate_limit_config() -> Response {
+    result_json(crate::commands::sharing::get_rate_limit_config_inner())
+}
+
+async fn h_get_scrollback_persist_enabled() -> Response {
+    result_json(crate::commands::pty::get_scrollback_persist_enabled_inner())
+}
+
+async fn h_run_quick_command(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let worktree_name = args["worktreeName"].as_str().unwrap_or("").to_string();
+    let label = args["label"].as_str().unwrap_or("").to_string();
+    let project = args["project"].as_str().map(String::from);
+    let new_session = args["newSession"].as_bool().unwrap_or(false);
+    result_json(crate::run_quick_command_impl(
+        &sid,
+        worktree_name,
+        label,
+        project,
+        new_session,
+        true,
+    ))
+}
+
+async fn h_run_task(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let worktree_name = args["worktreeName"].as_str().unwrap_or("").to_string();
+    let project = args["project"].as_str().unwrap_or("").to_string();
+    let task = args["task"].as_str().unwrap_or("").to_string();
+    result_json(crate::run_task_impl(&sid, worktree_name, project, task))
+}
+
+async fn h_open_terminal_preset(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let worktree_name = args["worktreeName"].as_str().unwrap_or("").to_string();
+    let project = args["project"].as_str().unwrap_or("").to_string();
+    let preset = args["preset"].as_str().unwrap_or("").to_string();
+    result_json(crate::open_terminal_preset_impl(
+        &sid,
+        worktree_name,
+        project,
+        preset,
+    ))
+}
+
+async fn h_list_running_processes(headers: HeaderMap, Json(args): Json<Value>) -> Response {
+    let sid = session_id(&headers);
+    let worktree_name = args["worktreeName"].as_str().map(String::from);
+    let allowed = share_allowed_worktrees();
+    let result =
+        tokio::task::spawn_blocking(move || crate::list_running_processes_impl(&sid, worktree_name))
+            .await
+            .map_err(|e| format!("Task join error: {}", e))
+            .and_then(|r| r)
+            .map(|mut processes| {
+                if !allowed.is_empty() {
+                    processes.retain(|p| is_worktree_allowed(&allowed, &p.worktree_name));
+                }
+                processes
+            });
+    result_json(result)
 }
 
-async fn h_pty_exists(Json(args): Json<Value>) -> Response {
-    let session_id = args["sessionId"].as_str().unwrap_or("").to_string();
-    result_json(with_pty_manager(move |m| Ok(m.has_session(&session_id))).await)
+async fn h_list_quick_commands(headers: HeaderMap) -> Response {
+    let sid = session_id(&headers);
+    result_json(crate::list_quick_commands_impl(&sid, true))
 }
 
 async fn h_pty_close_by_path(Json(args): Json<Value>) -> Response {
@@ -547,9 +1676,96 @@ async fn h_pty_close_by_path(Json(args): Json<Value>) -> Response {
 
 // -- Auth --
 
+/// Client address of a request relayed from loopback by a tunnel or reverse proxy, taken from
+/// the forwarding headers. Only meaningful for loopback peers: anyone else could set them.
+fn forwarded_client_ip(headers: &HeaderMap) -> Option<IpAddr> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let forwarded_for = header("forwarded").and_then(|v| {
+        // `Forwarded: for=1.2.3.4;proto=https, for="[::1]:80"` — first hop is the client
+        v.split(',').next()?.split(';').find_map(|part| {
+            let (key, value) = part.trim().split_once('=')?;
+            key.eq_ignore_ascii_case("for").then_some(value)
+        })
+    });
+    let candidates = [
+        header("cf-connecting-ip"),
+        header("x-real-ip"),
+        header("x-forwarded-for").and_then(|v| v.split(',').next()),
+        forwarded_for,
+    ];
+    candidates.into_iter().flatten().find_map(|value| {
+        let value = value.trim().trim_matches('"');
+        value.parse::<IpAddr>().ok().or_else(|| {
+            // `[v6]:port` or `v4:port`
+            value.parse::<SocketAddr>().ok().map(|a| a.ip())
+        })
+    })
+}
+
+/// Rate-limit bucket of a request. This runs before auth, so only credentials that check out
+/// get their own bucket: a valid API token (by hash) or a logged-in session. Everything else
+/// (including /api/auth/*) is limited per client IP, otherwise a fresh random session id or
+/// token per request would never be limited. Tunnels connect from loopback, so their clients
+/// are told apart by the forwarding headers.
+fn rate_limit_key(ip: IpAddr, headers: &HeaderMap) -> String {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    if let Some(hash) =
+        bearer.and_then(|t| crate::commands::sharing::known_api_token_hash(t.trim()))
+    {
+        return format!("token:{}", hash);
+    }
+    match headers.get("x-session-id").and_then(|v| v.to_str().ok()) {
+        Some(sid) if crate::commands::sharing::is_authenticated_session(sid) => sid.to_string(),
+        _ => {
+            let client_ip = if ip.is_loopback() {
+                forwarded_client_ip(headers).unwrap_or(ip)
+            } else {
+                ip
+            };
+            format!("ip:{}", client_ip)
+        }
+    }
+}
+
+/// Middleware: token-bucket rate limit on every API call, per API token or authenticated
+/// session (per client IP otherwise, see `rate_limit_key`), so a misbehaving client can't
+/// hammer git commands. Limits come from the global config.
+async fn rate_limit_middleware(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !request.uri().path().starts_with("/api/") {
+        return next.run(request).await;
+    }
+    let key = rate_limit_key(addr.ip(), &headers);
+    // Terminal input over HTTP (the SSE fallback) counts against the terminal input limits
+    let pty_input = request.uri().path() == "/api/pty_write";
+    if !crate::commands::sharing::rate_limit_allows(&key, pty_input) {
+        log::warn!(
+            "[http] Rate limited {} {} ({})",
+            key,
+            request.uri().path(),
+            addr.ip()
+        );
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, "1")],
+            "请求过于频繁，请稍后再试",
+        )
+            .into_response();
+    }
+    next.run(request).await
+}
+
 /// Middleware: block dangerous host-only operations from remote (non-localhost) clients.
 /// Operations like open_in_terminal, open_in_editor, reveal_in_finder, open_log_dir
 /// should only be available from localhost, not from remote browser sessions.
+/// See `is_host_request` for what counts as localhost.
 async fn localhost_only_middleware(
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     request: Request,
@@ -559,13 +1775,22 @@ async fn localhost_only_middleware(
     let restricted_paths = [
         "/api/open_in_terminal",
         "/api/open_in_editor",
+        "/api/open_in_devcontainer",
         "/api/reveal_in_finder",
         "/api/open_log_dir",
+        // Quarantine is an admin action for the desktop host
+        "/api/quarantine_client",
+        "/api/release_client",
+        "/api/set_client_role",
         // ngrok management should only be accessible from localhost
         "/api/get_ngrok_token",
         "/api/set_ngrok_token",
         "/api/start_ngrok_tunnel",
         "/api/stop_ngrok_tunnel",
+        "/api/start_cloudflare_tunnel",
+        "/api/stop_cloudflare_tunnel",
+        "/api/start_frp_tunnel",
+        "/api/stop_frp_tunnel",
         // Dashscope config should only be accessible from localhost
         "/api/get_dashscope_api_key",
         "/api/set_dashscope_api_key",
@@ -573,20 +1798,400 @@ async fn localhost_only_middleware(
         "/api/set_dashscope_base_url",
     ];
 
-    if restricted_paths.contains(&path.as_str()) {
-        let ip = addr.ip();
-        if !ip.is_loopback() {
-            return (
-                StatusCode::FORBIDDEN,
-                "This operation is only available from localhost",
-            )
-                .into_response();
-        }
+    if restricted_paths.contains(&path.as_str()) && !is_host_request(addr.ip(), request.headers()) {
+        return (
+            StatusCode::FORBIDDEN,
+            "This operation is only available from localhost",
+        )
+            .into_response();
     }
 
     next.run(request).await
 }
 
+/// Keep the most recent N remote actions for the desktop activity feed.
+const MAX_SHARE_ACTIVITY: usize = 200;
+
+/// Request fields worth showing in an activity summary (never secrets).
+const ACTIVITY_SUMMARY_KEYS: [&str; 6] = [
+    "worktreeName",
+    "name",
+    "projectName",
+    "branch",
+    "baseBranch",
+    "path",
+];
+
+fn summarize_activity_args(args: &Value) -> Option<String> {
+    let mut parts = vec![];
+    for source in [args, &args["request"]] {
+        for key in ACTIVITY_SUMMARY_KEYS {
+            if let Some(v) = source[key].as_str().filter(|v| !v.is_empty()) {
+                parts.push(format!("{}={}", key, v));
+            }
+        }
+    }
+    parts.truncate(3);
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(", "))
+    }
+}
+
+/// Middleware: while sharing, record mutating API calls from remote sessions and push them
+/// to the desktop window ("share-activity" event) so the host sees what collaborators do.
+/// Reads and terminal keystrokes are skipped to keep the feed readable.
+async fn activity_middleware(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path().to_string();
+    let sharing = SHARE_STATE.lock().map(|s| s.active).unwrap_or(false);
+    let Some(command) = path.strip_prefix("/api/").map(|c| c.to_string()) else {
+        return next.run(request).await;
+    };
+    if !sharing
+        || is_read_only_api(&path)
+        || command.starts_with("auth/")
+        || matches!(command.as_str(), "pty_write" | "pty_resize" | "health")
+    {
+        return next.run(request).await;
+    }
+
+    // Buffer the body to pull a short summary, then hand it on unchanged
+    let (parts, body) = request.into_parts();
+    let bytes = match axum::body::to_bytes(body, 1024 * 1024).await {
+        Ok(b) => b,
+        Err(_) => return (StatusCode::PAYLOAD_TOO_LARGE, "Request body too large").into_response(),
+    };
+    let summary = serde_json::from_slice::<Value>(&bytes)
+        .ok()
+        .and_then(|v| summarize_activity_args(&v));
+    let request = Request::from_parts(parts, axum::body::Body::from(bytes));
+
+    let started = std::time::Instant::now();
+    let response = next.run(request).await;
+
+    let entry = ShareActivity {
+        session_id: headers
+            .get("x-session-id")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("web-default")
+            .to_string(),
+        ip: addr.ip().to_string(),
+        command,
+        summary,
+        status: response.status().as_u16(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        at: chrono::Utc::now().to_rfc3339(),
+    };
+    record_share_activity(entry);
+
+    response
+}
+
+/// Add a remote action to the activity feed and push it to the desktop window.
+fn record_share_activity(entry: ShareActivity) {
+    if let Ok(mut feed) = SHARE_ACTIVITY.lock() {
+        feed.push_back(entry.clone());
+        while feed.len() > MAX_SHARE_ACTIVITY {
+            feed.pop_front();
+        }
+    }
+    if let Some(app) = crate::state::APP_HANDLE.lock().ok().and_then(|h| h.clone()) {
+        let _ = app.emit("share-activity", &entry);
+    }
+}
+
+/// Argument keys holding a worktree name
+const WORKTREE_NAME_KEYS: [&str; 2] = ["worktreeName", "worktree_name"];
+/// Argument keys holding a path that may point into a worktree
+const WORKTREE_PATH_KEYS: [&str; 5] = ["path", "cwd", "projectPath", "project_path", "pathPrefix"];
+/// How a route open to restricted share profiles finds the worktree it acts on
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RouteScope {
+    /// Touches no worktree data, or filters its own response to the allowed worktrees
+    Open,
+    /// Binds the session to the workspace in `workspacePath` / `path`
+    Workspace,
+    /// Acts on the worktrees its arguments name or point into; at least one is required,
+    /// so the route never falls back to the main workspace or every worktree
+    Worktree,
+    /// Like `Worktree`, with the worktree name also in `name`
+    NamedWorktree,
+    /// Addresses a PTY session by `sessionId`
+    PtySession,
+}
+
+/// Routes a restricted share profile may call. Everything else (workspace-wide operations,
+/// the main workspace, operation logs, manifests, settings) is rejected, including routes
+/// added later until they are listed here.
+fn restricted_route_scope(path: &str) -> Option<RouteScope> {
+    let scope = match path.trim_start_matches("/api/") {
+        "get_current_workspace"
+        | "get_workspace_config"
+        | "get_workspace_config_versioned"
+        | "list_worktrees"
+        | "list_worktrees_page"
+        | "refresh_workspace"
+        | "list_running_processes"
+        | "download"
+        | "list_quick_commands"
+        | "unregister_window"
+        | "get_pty_limits"
+        | "get_focus_follow_enabled"
+        | "get_session_restore_enabled"
+        | "get_scrollback_persist_enabled"
+        | "get_voice_refine_enabled"
+        | "get_dashscope_base_url"
+        | "voice_start"
+        | "voice_send_audio"
+        | "voice_stop"
+        | "voice_is_active"
+        | "voice_refine_text"
+        | "get_app_version"
+        | "get_share_info"
+        | "health"
+        | "openapi.json"
+        | "events" => RouteScope::Open,
+        "switch_workspace" | "set_window_workspace" | "open_workspace_window" => {
+            RouteScope::Workspace
+        }
+        "archive_worktree"
+        | "restore_worktree"
+        | "delete_archived_worktree"
+        | "check_worktree_status"
+        | "get_worktree"
+        | "get_worktree_status"
+        | "get_worktree_meta"
+        | "get_worktree_ports"
+        | "get_worktree_issue"
+        | "compose_status"
+        | "compose_up"
+        | "compose_down"
+        | "set_worktree_meta"
+        | "set_worktree_labels" => RouteScope::NamedWorktree,
+        "create_worktree"
+        | "archive_worktrees"
+        | "add_project_to_worktree"
+        | "kill_worktree_processes"
+        | "list_actions"
+        | "convert_to_link"
+        | "sync_links"
+        | "repair_worktrees"
+        | "get_sync_advice"
+        | "sync_safe_projects"
+        | "get_dirty_report"
+        | "switch_branch"
+        | "get_branch_diff_stats"
+        | "check_remote_branch_exists"
+        | "fetch_project_remote"
+        | "sync_with_base_branch"
+        | "push_to_remote"
+        | "amend_commit"
+        | "merge_to_test_branch"
+        | "merge_to_base_branch"
+        | "cherry_pick"
+        | "list_merge_conflicts"
+        | "resolve_conflict"
+        | "continue_merge"
+        | "abort_merge"
+        | "normalize_line_endings"
+        | "discard_changes"
+        | "clean_untracked"
+        | "get_file_diff"
+        | "create_pull_request"
+        | "get_ci_status"
+        | "get_remote_branches"
+        | "export_diff_zip"
+        | "scan_linked_folders"
+        | "open_in_terminal"
+        | "open_in_editor"
+        | "open_in_devcontainer"
+        | "get_devcontainer_status"
+        | "reveal_in_finder"
+        | "lock_worktree"
+        | "unlock_worktree"
+        | "get_terminal_state"
+        | "pty_create"
+        | "pty_close_by_path"
+        | "list_recordings"
+        | "read_recording"
+        | "get_command_history"
+        | "run_quick_command"
+        | "run_task"
+        | "open_terminal_preset" => RouteScope::Worktree,
+        "pty_write"
+        | "pty_read"
+        | "pty_resize"
+        | "pty_close"
+        | "pty_exists"
+        | "pty_get_scrollback"
+        | "pty_search_output"
+        | "pty_start_recording"
+        | "pty_stop_recording"
+        | "pty_get_recording" => RouteScope::PtySession,
+        _ => return None,
+    };
+    Some(scope)
+}
+
+/// Worktrees the active share profile restricts remote clients to (empty = no restriction)
+fn share_allowed_worktrees() -> Vec<String> {
+    SHARE_STATE
+        .lock()
+        .map(|s| if s.active { s.allowed_worktrees.clone() } else { vec![] })
+        .unwrap_or_default()
+}
+
+fn is_worktree_allowed(allowed: &[String], name: &str) -> bool {
+    let name = name.strip_suffix(".archive").unwrap_or(name);
+    allowed.iter().any(|a| a == name)
+}
+
+/// Name of the worktree `path` points into, if it lies under `<workspace>/<worktrees_dir>/`.
+/// Paths with `..` components never count as inside a worktree, since they may resolve
+/// anywhere.
+fn worktree_of_path(workspace_path: &str, worktrees_dir: &str, path: &str) -> Option<String> {
+    use std::path::{Component, Path};
+    let path = Path::new(path);
+    if path.components().any(|c| c == Component::ParentDir) {
+        return None;
+    }
+    let rel = if path.is_absolute() {
+        path.strip_prefix(workspace_path).ok()?
+    } else {
+        path
+    };
+    match rel.strip_prefix(worktrees_dir).ok()?.components().next()? {
+        Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+        _ => None,
+    }
+}
+
+/// Whether a restricted client may reach PTY session `pty_session_id`: it must be a local
+/// shell started inside an allowed worktree. Unknown ids pass so handlers can report them.
+fn pty_session_allowed(allowed: &[String], sid: &str, pty_session_id: &str) -> bool {
+    if allowed.is_empty() {
+        return true;
+    }
+    let Ok(manager) = PTY_MANAGER.lock() else {
+        return false;
+    };
+    if !manager.has_session(pty_session_id) {
+        return true;
+    }
+    let Some(cwd) = manager.session_local_cwd(pty_session_id) else {
+        return false;
+    };
+    drop(manager);
+    crate::config::get_window_workspace_config(sid)
+        .and_then(|(ws, config)| worktree_of_path(&ws, &config.worktrees_dir, &cwd))
+        .is_some_and(|name| is_worktree_allowed(allowed, &name))
+}
+
+/// Middleware: when the active share profile lists allowed worktrees, only routes on the
+/// `restricted_route_scope` allowlist pass, and only when their arguments name or point into
+/// an allowed worktree (or address a PTY session running in one).
+async fn worktree_scope_middleware(headers: HeaderMap, request: Request, next: Next) -> Response {
+    let path = request.uri().path().to_string();
+    let allowed = share_allowed_worktrees();
+    if allowed.is_empty() || !path.starts_with("/api/") || path.starts_with("/api/auth/") {
+        return next.run(request).await;
+    }
+    let forbidden = |what: String| {
+        (
+            StatusCode::FORBIDDEN,
+            format!("当前分享配置不允许访问{}", what),
+        )
+            .into_response()
+    };
+    let Some(route_scope) = restricted_route_scope(&path) else {
+        return forbidden(format!("接口 {}", path));
+    };
+    let sid = session_id(&headers);
+    let scope = crate::config::get_window_workspace_config(&sid);
+
+    let (parts, body) = request.into_parts();
+    let bytes = match axum::body::to_bytes(body, 1024 * 1024).await {
+        Ok(b) => b,
+        Err(_) => return (StatusCode::PAYLOAD_TOO_LARGE, "Request body too large").into_response(),
+    };
+    let args = serde_json::from_slice::<Value>(&bytes).unwrap_or(Value::Null);
+
+    // 受限分享只能使用分享的 workspace
+    let shared = SHARE_STATE
+        .lock()
+        .ok()
+        .and_then(|s| s.workspace_path.clone());
+    let target = match route_scope {
+        RouteScope::Workspace => Some(args["workspacePath"].as_str().or(args["path"].as_str())),
+        _ => args.get("workspacePath").map(|v| v.as_str()),
+    };
+    if let Some(target) = target {
+        if target.is_none() || target != shared.as_deref() {
+            return forbidden("其他 workspace".to_string());
+        }
+    }
+
+    let mut names: Vec<String> = vec![];
+    match route_scope {
+        RouteScope::Open | RouteScope::Workspace => {}
+        RouteScope::PtySession => {
+            let Some(pty_session_id) = args["sessionId"].as_str() else {
+                return forbidden("未指定的终端".to_string());
+            };
+            if !pty_session_allowed(&allowed, &sid, pty_session_id) {
+                return forbidden(format!("终端 \"{}\"", pty_session_id));
+            }
+        }
+        RouteScope::Worktree | RouteScope::NamedWorktree => {
+            for source in [&args, &args["request"]] {
+                for key in WORKTREE_NAME_KEYS {
+                    if let Some(name) = source[key].as_str() {
+                        names.push(name.to_string());
+                    }
+                }
+                for key in WORKTREE_PATH_KEYS {
+                    if let Some(p) = source[key].as_str() {
+                        let worktree = scope.as_ref().and_then(|(ws, config)| {
+                            worktree_of_path(ws, &config.worktrees_dir, p)
+                        });
+                        match worktree {
+                            Some(name) => names.push(name),
+                            None => return forbidden(format!("路径 \"{}\"", p)),
+                        }
+                    }
+                }
+            }
+            if route_scope == RouteScope::NamedWorktree {
+                names.extend(args["name"].as_str().map(|n| n.to_string()));
+            }
+            if path == "/api/archive_worktrees" {
+                if let Some(list) = args["names"].as_array() {
+                    names.extend(
+                        list.iter()
+                            .filter_map(|v| v.as_str().map(|n| n.to_string())),
+                    );
+                }
+            }
+            if path == "/api/create_worktree" {
+                names.extend(args["request"]["name"].as_str().map(|n| n.to_string()));
+            }
+            if names.is_empty() {
+                return forbidden("主工作区或全部 worktree".to_string());
+            }
+        }
+    }
+    if let Some(denied) = names.iter().find(|n| !is_worktree_allowed(&allowed, n)) {
+        return forbidden(format!(" worktree \"{}\"", denied));
+    }
+    next.run(Request::from_parts(parts, axum::body::Body::from(bytes))).await
+}
+
 /// Middleware: add security headers to all responses.
 async fn security_headers_middleware(request: Request, next: Next) -> Response {
     let mut response = next.run(request).await;
@@ -611,55 +2216,277 @@ async fn security_headers_middleware(request: Request, next: Next) -> Response {
     response
 }
 
-/// Middleware: check if the request is authenticated when password is set.
-/// Exempt: /api/auth, /api/get_share_info, and non-API paths (static files).
-async fn auth_middleware(headers: HeaderMap, request: Request, next: Next) -> Response {
+/// API calls open to viewers (and to quarantined sessions): queries and terminal output
+/// reads. Listed by name rather than by prefix, so a getter that returns a secret is never
+/// readable by accident; see `is_operator_api` for the rest.
+fn is_read_only_api(path: &str) -> bool {
+    matches!(
+        path.trim_start_matches("/api/"),
+        "list_workspaces"
+            | "get_current_workspace"
+            | "get_workspace_config"
+            | "get_workspace_config_versioned"
+            | "get_paths"
+            | "list_worktrees"
+            | "list_worktrees_page"
+            | "check_worktree_status"
+            | "check_remote_branch_exists"
+            | "get_worktree"
+            | "get_worktree_status"
+            | "get_worktree_meta"
+            | "get_worktree_ports"
+            | "get_worktree_issue"
+            | "get_main_workspace_status"
+            | "compose_status"
+            | "get_devcontainer_status"
+            | "get_disk_usage"
+            | "get_base_updates"
+            | "list_actions"
+            | "get_sync_advice"
+            | "refresh_workspace"
+            | "get_dirty_report"
+            | "get_main_occupation"
+            | "get_branch_diff_stats"
+            | "list_merge_conflicts"
+            | "get_file_diff"
+            | "get_ci_status"
+            | "get_remote_branches"
+            | "download"
+            | "scan_linked_folders"
+            | "get_opened_workspaces"
+            | "get_locked_worktrees"
+            | "get_terminal_state"
+            | "get_focus_state"
+            | "get_focus_follow_enabled"
+            | "pty_read"
+            | "pty_exists"
+            | "pty_get_scrollback"
+            | "pty_search_output"
+            | "get_pty_limits"
+            | "pty_get_recording"
+            | "list_recordings"
+            | "read_recording"
+            | "get_command_history"
+            | "list_quick_commands"
+            | "list_running_processes"
+            | "get_session_restore_enabled"
+            | "get_scrollback_persist_enabled"
+            | "get_voice_refine_enabled"
+            | "get_dashscope_base_url"
+            | "voice_is_active"
+            | "get_app_version"
+            | "get_share_info"
+            | "health"
+            | "openapi.json"
+            | "events"
+    )
+}
+
+/// API calls open to operators: terminal input, git and worktree operations. Anything on
+/// neither list (configuration, hosting settings, getters that return tokens or keys such
+/// as `get_wms_config`, `get_ngrok_token`, `get_dashscope_api_key`, `get_ssh_hosts`, and
+/// routes added later without a decision) needs an admin.
+fn is_operator_api(path: &str) -> bool {
+    matches!(
+        path.trim_start_matches("/api/"),
+        "set_window_workspace"
+            | "switch_workspace"
+            | "unregister_window"
+            | "open_workspace_window"
+            | "validate_workspace_config"
+            | "get_config_path_info"
+            | "create_worktree"
+            | "archive_worktree"
+            | "archive_worktrees"
+            | "delete_archived_worktree"
+            | "restore_worktree"
+            | "add_project_to_worktree"
+            | "rename_worktree_branch"
+            | "set_worktree_meta"
+            | "set_worktree_labels"
+            | "lock_worktree"
+            | "unlock_worktree"
+            | "compose_up"
+            | "compose_down"
+            | "kill_worktree_processes"
+            | "convert_to_link"
+            | "sync_links"
+            | "repair_worktrees"
+            | "sync_safe_projects"
+            | "fetch_all_projects"
+            | "deploy_to_main"
+            | "exit_main_occupation"
+            | "switch_branch"
+            | "clone_project"
+            | "fetch_project_remote"
+            | "sync_with_base_branch"
+            | "push_to_remote"
+            | "amend_commit"
+            | "merge_to_test_branch"
+            | "merge_to_base_branch"
+            | "cherry_pick"
+            | "resolve_conflict"
+            | "continue_merge"
+            | "abort_merge"
+            | "normalize_line_endings"
+            | "discard_changes"
+            | "clean_untracked"
+            | "create_pull_request"
+            | "export_diff_zip"
+            | "get_operation_log"
+            | "list_operation_logs"
+            | "open_in_terminal"
+            | "open_in_editor"
+            | "open_in_devcontainer"
+            | "reveal_in_finder"
+            | "pty_create"
+            | "pty_create_ssh"
+            | "pty_write"
+            | "pty_resize"
+            | "pty_close"
+            | "pty_close_by_path"
+            | "pty_restore_sessions"
+            | "pty_start_recording"
+            | "pty_stop_recording"
+            | "run_quick_command"
+            | "run_task"
+            | "open_terminal_preset"
+            | "voice_start"
+            | "voice_send_audio"
+            | "voice_stop"
+            | "voice_refine_text"
+    )
+}
+
+/// App logs reveal paths and share details, so only admin sessions may read them. Decided on
+/// the role alone: every tunnel forwards from loopback, so the peer address proves nothing.
+fn can_view_app_logs(session_id: &str) -> bool {
+    crate::commands::sharing::session_role(session_id) == Some(ShareRole::Admin)
+}
+
+/// Headers set by the tunnels (and reverse proxies in general) on forwarded requests.
+const FORWARDING_HEADERS: &[&str] = &[
+    "forwarded",
+    "x-forwarded-for",
+    "x-real-ip",
+    "cf-connecting-ip",
+    "tailscale-user-login",
+    crate::wms_tunnel::TUNNEL_MARKER_HEADER,
+];
+
+/// A request made on the host itself: from loopback, without a browser session header and
+/// not relayed by a tunnel (all of which connect from loopback too).
+fn is_host_request(ip: IpAddr, headers: &HeaderMap) -> bool {
+    ip.is_loopback()
+        && !headers.contains_key("x-session-id")
+        && !FORWARDING_HEADERS.iter().any(|h| headers.contains_key(*h))
+}
+
+/// Lowest client role allowed to call an API: listed queries are open to viewers, listed
+/// operations to operators, and everything else needs an admin.
+fn required_role(path: &str) -> ShareRole {
+    if is_read_only_api(path) {
+        ShareRole::Viewer
+    } else if is_operator_api(path) {
+        ShareRole::Operator
+    } else {
+        ShareRole::Admin
+    }
+}
+
+/// Middleware: check if the request is authenticated (API token, or session when password
+/// is set), and that its role allows the call.
+/// Exempt: /api/auth, /api/get_share_info, /api/health, /api/webhook/git (checks its own
+/// secret), and non-API paths (static files).
+async fn auth_middleware(headers: HeaderMap, mut request: Request, next: Next) -> Response {
     let path = request.uri().path().to_string();
 
-    // Allow non-API paths (static files), exempt endpoints, and WebSocket
+    // Allow non-API paths (static files), exempt endpoints, and WebSocket / event stream
     if !path.starts_with("/api/")
         || path == "/api/auth/challenge"
         || path == "/api/auth/verify"
         || path == "/api/get_share_info"
+        || path == "/api/health"
+        || path == "/api/webhook/git"
         || path == "/api/cert.pem"
+        || path == "/api/events"
         || path == "/ws"
     {
         return next.run(request).await;
     }
 
-    // Check if sharing is active and has a password
-    let needs_auth = SHARE_STATE
-        .lock()
-        .map(|state| state.active && state.auth_key.is_some())
+    // Quarantined sessions keep read-only access until released or kicked
+    let quarantined = headers
+        .get("x-session-id")
+        .and_then(|v| v.to_str().ok())
+        .map(crate::commands::sharing::is_session_quarantined)
         .unwrap_or(false);
-
-    if !needs_auth {
-        return next.run(request).await;
+    if quarantined && !is_read_only_api(&path) {
+        return (
+            StatusCode::FORBIDDEN,
+            "会话已被管理员隔离，仅允许只读操作",
+        )
+            .into_response();
     }
 
-    // Check session authentication
+    // API tokens (scripts, CI) authenticate every request on their own; otherwise check the
+    // session (only required while sharing with a password)
     let sid = headers
         .get("x-session-id")
         .and_then(|v| v.to_str().ok())
         .unwrap_or("web-default")
         .to_string();
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    let role = match bearer {
+        Some(token) => match crate::commands::sharing::api_token_role(token.trim()) {
+            Some(role) => role,
+            None => return (StatusCode::UNAUTHORIZED, "Invalid API token").into_response(),
+        },
+        None => match crate::commands::sharing::session_role(&sid) {
+            Some(role) => role,
+            // Distinct code so the web client can ask to log in again
+            None if crate::commands::sharing::is_session_expired(&sid) => {
+                return (
+                    StatusCode::UNAUTHORIZED,
+                    Json(json!({
+                        "error": "session_expired",
+                        "message": "会话已过期，请重新登录",
+                    })),
+                )
+                    .into_response()
+            }
+            None => return (StatusCode::UNAUTHORIZED, "Authentication required").into_response(),
+        },
+    };
 
-    let is_authenticated = AUTHENTICATED_SESSIONS
-        .lock()
-        .map(|sessions| sessions.contains(&sid))
-        .unwrap_or(false);
+    let required = required_role(&path);
+    if role < required {
+        return (
+            StatusCode::FORBIDDEN,
+            format!(
+                "当前会话权限为 {}，此操作需要 {} 权限",
+                role.as_str(),
+                required.as_str()
+            ),
+        )
+            .into_response();
+    }
 
-    if is_authenticated {
-        // Update last_active timestamp
-        if let Ok(mut clients) = CONNECTED_CLIENTS.lock() {
-            if let Some(client) = clients.get_mut(&sid) {
-                client.last_active = chrono::Utc::now().to_rfc3339();
-            }
+    // Update last_active timestamp and slide the session's expiry
+    if let Ok(mut clients) = CONNECTED_CLIENTS.lock() {
+        if let Some(client) = clients.get_mut(&sid) {
+            client.last_active = chrono::Utc::now().to_rfc3339();
         }
-        return next.run(request).await;
     }
-
-    (StatusCode::UNAUTHORIZED, "Authentication required").into_response()
+    if bearer.is_none() {
+        crate::commands::sharing::touch_session(&sid);
+    }
+    // Handlers that trim their response by role read it from here
+    request.extensions_mut().insert(role);
+    next.run(request).await
 }
 
 async fn h_auth_challenge(ConnectInfo(addr): ConnectInfo<SocketAddr>) -> Response {
@@ -717,6 +2544,8 @@ async fn h_auth_challenge(ConnectInfo(addr): ConnectInfo<SocketAddr>) -> Respons
 struct VerifyRequest {
     proof: String,  // hex-encoded HMAC
     nonce: String,  // hex-encoded nonce
+    #[serde(default)]
+    totp: Option<String>, // 6-digit code, required when TOTP is enabled
 }
 
 async fn h_auth_verify(
@@ -775,6 +2604,11 @@ async fn h_auth_verify(
         return (StatusCode::UNAUTHORIZED, "密码错误").into_response();
     }
 
+    if !crate::commands::sharing::verify_share_totp(req.totp.as_deref()) {
+        log::warn!("[auth] Invalid TOTP code from IP: {}", client_ip);
+        return (StatusCode::UNAUTHORIZED, "动态验证码错误").into_response();
+    }
+
     // Generate session ID (same logic as before)
     let sid = uuid::Uuid::new_v4().to_string();
     let now = chrono::Utc::now().to_rfc3339();
@@ -784,6 +2618,11 @@ async fn h_auth_verify(
         .unwrap_or("unknown")
         .to_string();
 
+    // New clients start with the share profile's role; the host can change it per client
+    let role = SHARE_STATE
+        .lock()
+        .map(|state| state.role)
+        .unwrap_or_default();
     let client_ip = addr.ip().to_string();
     let client = ConnectedClient {
         session_id: sid.clone(),
@@ -792,6 +2631,8 @@ async fn h_auth_verify(
         authenticated_at: now.clone(),
         last_active: now,
         ws_connected: false,
+        quarantined: false,
+        role,
     };
 
     // Remove old sessions from the same IP that don't have an active WebSocket
@@ -814,7 +2655,13 @@ async fn h_auth_verify(
         for s in &stale_sids {
             sessions.remove(s);
         }
-        sessions.insert(sid.clone());
+        sessions.insert(
+            sid.clone(),
+            AuthenticatedSession {
+                role,
+                last_seen: std::time::Instant::now(),
+            },
+        );
     }
 
     log::info!(
@@ -850,15 +2697,41 @@ async fn h_start_ngrok_tunnel() -> Response {
 }
 
 async fn h_stop_ngrok_tunnel() -> Response {
-    match SHARE_STATE.lock() {
-        Ok(mut state) => {
-            if let Some(handle) = state.ngrok_task.take() {
-                handle.abort();
-            }
-            state.ngrok_url = None;
-            StatusCode::NO_CONTENT.into_response()
-        }
-        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Internal state error").into_response(),
+    match crate::stop_tunnel_internal("ngrok") {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e).into_response(),
+    }
+}
+
+// -- Cloudflare tunnel --
+
+async fn h_start_cloudflare_tunnel() -> Response {
+    match crate::start_cloudflare_tunnel_internal().await {
+        Ok(url) => Json(json!(url)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e).into_response(),
+    }
+}
+
+async fn h_stop_cloudflare_tunnel() -> Response {
+    match crate::stop_tunnel_internal("cloudflare") {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e).into_response(),
+    }
+}
+
+// -- frp tunnel --
+
+async fn h_start_frp_tunnel() -> Response {
+    match crate::start_frp_tunnel_internal().await {
+        Ok(url) => Json(json!(url)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e).into_response(),
+    }
+}
+
+async fn h_stop_frp_tunnel() -> Response {
+    match crate::stop_tunnel_internal("frp") {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e).into_response(),
     }
 }
 
@@ -918,6 +2791,7 @@ async fn h_get_share_info() -> Response {
             "workspace_name": null,
             "workspace_path": null,
             "current_worktree": null,
+            "totp_required": false,
         }))
         .into_response()
     };
@@ -956,34 +2830,210 @@ async fn h_get_share_info() -> Response {
         "workspace_name": ws_name,
         "workspace_path": ws_path,
         "current_worktree": current_worktree,
+        "totp_required": crate::commands::sharing::get_totp_enabled(),
     }))
     .into_response()
 }
 
-// -- Misc --
+// -- Misc --
+
+async fn h_get_terminal_state(Json(args): Json<Value>) -> Response {
+    let ws_path = args["workspacePath"].as_str().unwrap_or("").to_string();
+    let wt_name = args["worktreeName"].as_str().unwrap_or("").to_string();
+    let state = crate::commands::window::get_terminal_state_inner(ws_path, wt_name);
+    Json(json!(state)).into_response()
+}
+
+async fn h_get_focus_state(Json(args): Json<Value>) -> Response {
+    let ws_path = args["workspacePath"].as_str().unwrap_or("").to_string();
+    let state = crate::commands::window::get_focus_state_inner(ws_path);
+    Json(json!(state)).into_response()
+}
+
+async fn h_get_focus_follow_enabled() -> Response {
+    result_json(crate::commands::window::get_focus_follow_enabled_inner())
+}
+
+async fn h_open_workspace_window(Json(args): Json<Value>) -> Response {
+    // In browser mode, "open new window" just opens a new browser tab
+    let ws_path = args["workspacePath"].as_str().unwrap_or("").to_string();
+    // Return a URL that the frontend can use to open a new tab
+    let url = format!("/?workspace={}", urlencoding::encode(&ws_path));
+    Json(json!(url)).into_response()
+}
+
+async fn h_get_app_version() -> Response {
+    Json(json!(env!("CARGO_PKG_VERSION"))).into_response()
+}
+
+/// OpenAPI description of this API (requires authentication like the other endpoints).
+async fn h_openapi() -> Response {
+    Json(crate::openapi::openapi_document()).into_response()
+}
+
+/// GitHub / GitLab webhook: fetch the pushed repository and notify clients. Authenticated by
+/// the webhook secret instead of a session; answers right away and fetches in the background,
+/// since GitHub gives up on deliveries after 10 seconds.
+async fn h_webhook_git(headers: HeaderMap, body: axum::body::Bytes) -> Response {
+    let Some(secret) = load_global_config()
+        .webhook_secret
+        .filter(|s| !s.is_empty())
+    else {
+        return (StatusCode::NOT_FOUND, "Webhook is not enabled").into_response();
+    };
+    if !crate::webhook::verify_request(&secret, &headers, &body) {
+        log::warn!("[webhook] Rejected delivery with an invalid signature or token");
+        return (StatusCode::UNAUTHORIZED, "Invalid webhook signature").into_response();
+    }
+    let payload: Value = match serde_json::from_slice(&body) {
+        Ok(v) => v,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                format!("Invalid webhook payload: {}", e),
+            )
+                .into_response()
+        }
+    };
+    let Some(event) = crate::webhook::parse_event(&headers, &payload) else {
+        return Json(json!({ "accepted": false })).into_response();
+    };
+    tokio::task::spawn_blocking(move || crate::webhook::handle_event(&event));
+    (StatusCode::ACCEPTED, Json(json!({ "accepted": true }))).into_response()
+}
+
+/// Liveness/readiness probe for reverse proxies, tunnels and monitoring.
+/// Unauthenticated, so it only exposes coarse state (no paths, no client details).
+async fn h_health() -> Response {
+    let uptime_secs = SERVER_STARTED_AT
+        .lock()
+        .ok()
+        .and_then(|t| t.map(|t| t.elapsed().as_secs()))
+        .unwrap_or(0);
+    let workspace_bound = SHARE_STATE
+        .lock()
+        .map(|s| s.workspace_path.is_some())
+        .unwrap_or(false);
+    let ws_clients = CONNECTED_CLIENTS
+        .lock()
+        .map(|c| c.values().filter(|c| c.ws_connected).count())
+        .unwrap_or(0);
+    let shutting_down = SHUTTING_DOWN.load(Ordering::SeqCst);
+
+    let status = if shutting_down {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+    (
+        status,
+        Json(json!({
+            "status": if shutting_down { "shutting_down" } else { "ok" },
+            "version": env!("CARGO_PKG_VERSION"),
+            "uptime_secs": uptime_secs,
+            "workspace_bound": workspace_bound,
+            "ws_clients": ws_clients,
+        })),
+    )
+        .into_response()
+}
+
+// ---------------------------------------------------------------------------
+// WebSocket
+// ---------------------------------------------------------------------------
+
+/// `lock_update` message with the current locks of `workspace_path`
+fn lock_snapshot_message(workspace_path: &str) -> Option<String> {
+    let locks = crate::WORKTREE_LOCKS.lock().ok()?;
+    let lock_snapshot: HashMap<String, String> = locks
+        .iter()
+        .filter(|((wp, _), _)| *wp == workspace_path)
+        .map(|((_, wt), label)| (wt.clone(), label.clone()))
+        .collect();
+    Some(
+        json!({
+            "type": "lock_update",
+            "locks": lock_snapshot,
+        })
+        .to_string(),
+    )
+}
 
-async fn h_get_terminal_state(Json(args): Json<Value>) -> Response {
-    let ws_path = args["workspacePath"].as_str().unwrap_or("").to_string();
-    let wt_name = args["worktreeName"].as_str().unwrap_or("").to_string();
-    let state = crate::commands::window::get_terminal_state_inner(ws_path, wt_name);
-    Json(json!(state)).into_response()
+/// `lock_update` message for a lock broadcast, if it concerns `workspace_path`
+fn lock_update_message(workspace_path: &str, broadcast: &str) -> Option<String> {
+    let val: Value = serde_json::from_str(broadcast).ok()?;
+    if val["workspacePath"].as_str() != Some(workspace_path) {
+        return None;
+    }
+    Some(
+        json!({
+            "type": "lock_update",
+            "locks": val["locks"],
+        })
+        .to_string(),
+    )
 }
 
-async fn h_open_workspace_window(Json(args): Json<Value>) -> Response {
-    // In browser mode, "open new window" just opens a new browser tab
-    let ws_path = args["workspacePath"].as_str().unwrap_or("").to_string();
-    // Return a URL that the frontend can use to open a new tab
-    let url = format!("/?workspace={}", urlencoding::encode(&ws_path));
-    Json(json!(url)).into_response()
+/// A workspace event broadcast, forwarded unchanged if it concerns `workspace_path`
+fn workspace_event_message(workspace_path: &str, broadcast: &str) -> Option<String> {
+    let val: Value = serde_json::from_str(broadcast).ok()?;
+    (val["workspacePath"].as_str() == Some(workspace_path)).then(|| broadcast.to_string())
 }
 
-async fn h_get_app_version() -> Response {
-    Json(json!(env!("CARGO_PKG_VERSION"))).into_response()
+/// `terminal_state_update` message with the cached terminal state of a worktree
+fn terminal_state_snapshot_message(workspace_path: &str, worktree_name: &str) -> Option<String> {
+    let key = (workspace_path.to_string(), worktree_name.to_string());
+    let state = crate::TERMINAL_STATES.lock().ok()?.get(&key).cloned()?;
+    Some(
+        json!({
+            "type": "terminal_state_update",
+            "workspacePath": workspace_path,
+            "worktreeName": worktree_name,
+            "activatedTerminals": state.activated_terminals,
+            "activeTerminalTab": state.active_terminal_tab,
+            "terminalVisible": state.terminal_visible,
+            "clientId": state.client_id,
+        })
+        .to_string(),
+    )
 }
 
-// ---------------------------------------------------------------------------
-// WebSocket
-// ---------------------------------------------------------------------------
+/// Message for a terminal state broadcast: the worktree's terminal state, or process start/stop
+/// events anywhere in the workspace (so clients can mark every worktree with running processes)
+fn terminal_state_update_message(
+    workspace_path: &str,
+    worktree_name: &str,
+    broadcast: &str,
+) -> Option<String> {
+    let val: Value = serde_json::from_str(broadcast).ok()?;
+    if val["workspacePath"].as_str() != Some(workspace_path) {
+        return None;
+    }
+    if val["type"].as_str() == Some("running_processes") {
+        let allowed = share_allowed_worktrees();
+        let visible = allowed.is_empty()
+            || val["worktreeName"]
+                .as_str()
+                .is_some_and(|n| is_worktree_allowed(&allowed, n));
+        return visible.then(|| broadcast.to_string());
+    }
+    if val["worktreeName"].as_str() != Some(worktree_name) {
+        return None;
+    }
+    Some(
+        json!({
+            "type": "terminal_state_update",
+            "workspacePath": workspace_path,
+            "worktreeName": worktree_name,
+            "activatedTerminals": val["activatedTerminals"],
+            "activeTerminalTab": val["activeTerminalTab"],
+            "terminalVisible": val["terminalVisible"],
+            "clientId": val["clientId"],
+            "tabLabels": val["tabLabels"],
+        })
+        .to_string(),
+    )
+}
 
 #[derive(Deserialize)]
 struct WsParams {
@@ -1006,14 +3056,8 @@ async fn h_ws_upgrade(
         .map(|state| state.active && state.auth_key.is_some())
         .unwrap_or(false);
 
-    if needs_auth {
-        let is_authenticated = AUTHENTICATED_SESSIONS
-            .lock()
-            .map(|sessions| sessions.contains(&sid))
-            .unwrap_or(false);
-        if !is_authenticated {
-            return (StatusCode::UNAUTHORIZED, "Not authenticated").into_response();
-        }
+    if needs_auth && crate::commands::sharing::session_role(&sid).is_none() {
+        return (StatusCode::UNAUTHORIZED, "Not authenticated").into_response();
     }
 
     // Mark WebSocket connected
@@ -1025,12 +3069,12 @@ async fn h_ws_upgrade(
     }
     log::info!("WebSocket upgrade for session {} from {}", sid, addr.ip());
 
-    ws.on_upgrade(move |socket| handle_ws(socket, sid))
+    // Admin-only streams (e.g. app logs) are limited to admin sessions
+    let is_admin = can_view_app_logs(&sid);
+    ws.on_upgrade(move |socket| handle_ws(socket, sid, is_admin))
 }
 
-// TODO(security): Consider per-session rate limiting for WebSocket messages
-// to prevent a single client from flooding the server with pty_write commands.
-async fn handle_ws(socket: WebSocket, session_id: String) {
+async fn handle_ws(socket: WebSocket, session_id: String, is_admin: bool) {
     let (ws_sender, mut ws_receiver) = socket.split();
     let ws_sender = Arc::new(TokioMutex::new(ws_sender));
 
@@ -1046,14 +3090,22 @@ async fn handle_ws(socket: WebSocket, session_id: String) {
     // Track spawned forwarder tasks so we can abort them on disconnect
     let mut pty_forwarders: HashMap<String, tokio::task::JoinHandle<()>> = HashMap::new();
     let mut lock_forwarder: Option<tokio::task::JoinHandle<()>> = None;
+    let mut workspace_event_forwarder: Option<tokio::task::JoinHandle<()>> = None;
     let mut terminal_state_forwarder: Option<tokio::task::JoinHandle<()>> = None;
     let mut voice_forwarder: Option<tokio::task::JoinHandle<()>> = None;
+    let mut focus_forwarder: Option<tokio::task::JoinHandle<()>> = None;
+    let mut log_forwarder: Option<tokio::task::JoinHandle<()>> = None;
+    let mut change_forwarder: Option<(std::path::PathBuf, tokio::task::JoinHandle<()>)> = None;
+
+    // Signalled by the notification forwarder when this session is kicked or expires
+    let kicked = Arc::new(tokio::sync::Notify::new());
 
     // Always-on: subscribe to per-client notifications (kick events, etc.)
     let notification_forwarder: tokio::task::JoinHandle<()> = {
         let mut rx = crate::state::CLIENT_NOTIFICATION_BROADCAST.subscribe();
         let sender = Arc::clone(&ws_sender);
         let sid = session_id.clone();
+        let kicked = Arc::clone(&kicked);
         tokio::spawn(async move {
             loop {
                 match rx.recv().await {
@@ -1062,17 +3114,16 @@ async fn handle_ws(socket: WebSocket, session_id: String) {
                             // Only forward notifications targeted at this session
                             if val["session_id"].as_str() == Some(&sid) {
                                 let msg_type = val["type"].as_str().unwrap_or("");
-                                let reason =
-                                    val["reason"].as_str().unwrap_or("").to_string();
-                                let msg = json!({
-                                    "type": msg_type,
-                                    "reason": reason,
-                                });
+                                let mut msg = val.clone();
+                                if let Some(obj) = msg.as_object_mut() {
+                                    obj.remove("session_id");
+                                }
                                 let mut sender = sender.lock().await;
                                 let _ = sender.send(Message::text(msg.to_string())).await;
-                                // After sending kick notification, close the connection
-                                if msg_type == "kicked" {
+                                // After sending a kick / expiry notification, close the connection
+                                if msg_type == "kicked" || msg_type == "session_expired" {
                                     let _ = sender.close().await;
+                                    kicked.notify_one();
                                     break;
                                 }
                             }
@@ -1086,7 +3137,19 @@ async fn handle_ws(socket: WebSocket, session_id: String) {
     };
 
     // Process incoming messages
-    while let Some(msg) = ws_receiver.next().await {
+    loop {
+        let msg = tokio::select! {
+            msg = ws_receiver.next() => msg,
+            // Stop serving a kicked or expired session right away instead of waiting for its
+            // client to close the socket
+            _ = kicked.notified() => {
+                log::info!("WebSocket for session {} closed after kick or expiry", session_id);
+                break;
+            }
+        };
+        let Some(msg) = msg else {
+            break;
+        };
         let msg = match msg {
             Ok(m) => m,
             Err(_) => break,
@@ -1104,6 +3167,8 @@ async fn handle_ws(socket: WebSocket, session_id: String) {
         };
 
         let msg_type = parsed["type"].as_str().unwrap_or("");
+        // Terminal traffic only comes through here, so it has to keep the session alive too
+        crate::commands::sharing::touch_session(&session_id);
 
         match msg_type {
             "pty_subscribe" => {
@@ -1111,6 +3176,9 @@ async fn handle_ws(socket: WebSocket, session_id: String) {
                     Some(s) => s.to_string(),
                     None => continue,
                 };
+                if !pty_session_allowed(&share_allowed_worktrees(), &session_id, &pty_session_id) {
+                    continue;
+                }
 
                 // Abort existing forwarder for this session if any
                 if let Some(handle) = pty_forwarders.remove(&pty_session_id) {
@@ -1213,19 +3281,35 @@ async fn handle_ws(socket: WebSocket, session_id: String) {
             }
 
             "pty_write" => {
+                if crate::commands::sharing::is_session_quarantined(&session_id)
+                    || crate::commands::sharing::session_role(&session_id)
+                        .is_none_or(|role| role < ShareRole::Operator)
+                    || !crate::commands::sharing::rate_limit_allows(&session_id, true)
+                {
+                    continue;
+                }
                 let pty_session_id = match parsed["sessionId"].as_str() {
                     Some(s) => s.to_string(),
                     None => continue,
                 };
+                if !pty_session_allowed(&share_allowed_worktrees(), &session_id, &pty_session_id) {
+                    continue;
+                }
                 let data = match parsed["data"].as_str() {
                     Some(d) => d.to_string(),
                     None => continue,
                 };
+                let label = session_id.clone();
                 let _ = tokio::task::spawn_blocking(move || {
-                    PTY_MANAGER
+                    let result = PTY_MANAGER
                         .lock()
                         .map_err(|e| format!("Lock error: {}", e))
-                        .and_then(|m| m.write_to_session(&pty_session_id, &data))
+                        .and_then(|m| m.write_to_session(&pty_session_id, &data));
+                    if result.is_ok() {
+                        crate::commands::window::note_terminal_activity(&label, &pty_session_id);
+                        crate::command_history::record_input(&label, &pty_session_id, &data);
+                    }
+                    result
                 })
                 .await;
             }
@@ -1242,24 +3326,7 @@ async fn handle_ws(socket: WebSocket, session_id: String) {
                 }
 
                 // Send initial lock state
-                // Scope the std::sync::MutexGuard so it drops before any .await
-                let initial_lock_msg = if let Ok(locks) = crate::WORKTREE_LOCKS.lock() {
-                    let lock_snapshot: HashMap<String, String> = locks
-                        .iter()
-                        .filter(|((wp, _), _)| *wp == workspace_path)
-                        .map(|((_, wt), label)| (wt.clone(), label.clone()))
-                        .collect();
-                    Some(
-                        json!({
-                            "type": "lock_update",
-                            "locks": lock_snapshot,
-                        })
-                        .to_string(),
-                    )
-                } else {
-                    None
-                };
-                if let Some(msg_str) = initial_lock_msg {
+                if let Some(msg_str) = lock_snapshot_message(&workspace_path) {
                     let mut sender = ws_sender.lock().await;
                     let _ = sender.send(Message::text(msg_str)).await;
                 }
@@ -1272,22 +3339,11 @@ async fn handle_ws(socket: WebSocket, session_id: String) {
                     loop {
                         match rx.recv().await {
                             Ok(json_str) => {
-                                // Parse the broadcast to check if it's for our workspace
-                                if let Ok(val) = serde_json::from_str::<Value>(&json_str) {
-                                    if val["workspacePath"].as_str() == Some(&ws_path) {
-                                        let locks = &val["locks"];
-                                        let msg = json!({
-                                            "type": "lock_update",
-                                            "locks": locks,
-                                        });
-                                        let mut sender = sender.lock().await;
-                                        if sender
-                                            .send(Message::text(msg.to_string()))
-                                            .await
-                                            .is_err()
-                                        {
-                                            break;
-                                        }
+                                // Only forward updates for our workspace
+                                if let Some(msg) = lock_update_message(&ws_path, &json_str) {
+                                    let mut sender = sender.lock().await;
+                                    if sender.send(Message::text(msg)).await.is_err() {
+                                        break;
                                     }
                                 }
                             }
@@ -1297,6 +3353,31 @@ async fn handle_ws(socket: WebSocket, session_id: String) {
                     }
                 });
                 lock_forwarder = Some(handle);
+
+                // Workspace events go to every client watching the workspace's locks
+                if let Some(handle) = workspace_event_forwarder.take() {
+                    handle.abort();
+                }
+                let mut rx = WORKSPACE_EVENT_BROADCAST.subscribe();
+                let sender = Arc::clone(&ws_sender);
+                workspace_event_forwarder = Some(tokio::spawn(async move {
+                    loop {
+                        match rx.recv().await {
+                            Ok(json_str) => {
+                                let Some(msg) = workspace_event_message(&workspace_path, &json_str)
+                                else {
+                                    continue;
+                                };
+                                let mut sender = sender.lock().await;
+                                if sender.send(Message::text(msg)).await.is_err() {
+                                    break;
+                                }
+                            }
+                            Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                            Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                        }
+                    }
+                }));
             }
 
             "subscribe_terminal_state" => {
@@ -1315,51 +3396,151 @@ async fn handle_ws(socket: WebSocket, session_id: String) {
                 }
 
                 // Send initial terminal state from cache
-                let initial_state = crate::TERMINAL_STATES.lock().ok().and_then(|states| {
-                    let key = (workspace_path.clone(), worktree_name.clone());
-                    states.get(&key).cloned()
+                if let Some(msg) = terminal_state_snapshot_message(&workspace_path, &worktree_name)
+                {
+                    let mut sender = ws_sender.lock().await;
+                    let _ = sender.send(Message::text(msg)).await;
+                }
+
+                // Subscribe to terminal state broadcast
+                let mut rx = TERMINAL_STATE_BROADCAST.subscribe();
+                let sender = Arc::clone(&ws_sender);
+                let ws_path = workspace_path.clone();
+                let wt_name = worktree_name.clone();
+                let handle = tokio::spawn(async move {
+                    loop {
+                        match rx.recv().await {
+                            Ok(json_str) => {
+                                if let Some(msg) =
+                                    terminal_state_update_message(&ws_path, &wt_name, &json_str)
+                                {
+                                    let mut sender = sender.lock().await;
+                                    if sender.send(Message::text(msg)).await.is_err() {
+                                        break;
+                                    }
+                                }
+                            }
+                            Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                                // Log lagged receiver warning - client is too slow
+                                log::warn!("Terminal state broadcast lagged, skipped {} messages for {}/{}",
+                                    skipped, ws_path, wt_name);
+                                continue;
+                            }
+                            Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                        }
+                    }
                 });
+                terminal_state_forwarder = Some(handle);
+            }
+
+            "broadcast_terminal_state" => {
+                let workspace_path = match parsed["workspacePath"].as_str() {
+                    Some(s) => s.to_string(),
+                    None => continue,
+                };
+                let worktree_name = match parsed["worktreeName"].as_str() {
+                    Some(s) => s.to_string(),
+                    None => continue,
+                };
+                let activated_terminals = parsed["activatedTerminals"]
+                    .as_array()
+                    .map(|arr| {
+                        arr.iter()
+                            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+                let active_terminal_tab =
+                    parsed["activeTerminalTab"].as_str().map(|s| s.to_string());
+                let terminal_visible = parsed["terminalVisible"].as_bool().unwrap_or(false);
+                let client_id = parsed["clientId"].as_str().map(|s| s.to_string());
+
+                // Update cache with client_id (keeps labels of preset tabs that are still open)
+                let tab_labels = crate::commands::window::cache_terminal_state(
+                    (workspace_path.clone(), worktree_name.clone()),
+                    crate::TerminalState {
+                        activated_terminals: activated_terminals.clone(),
+                        active_terminal_tab: active_terminal_tab.clone(),
+                        terminal_visible,
+                        client_id: client_id.clone(),
+                        tab_labels: Default::default(),
+                    },
+                );
+
+                // Broadcast to all connected clients with clientId
+                let broadcast_msg = json!({
+                    "workspacePath": workspace_path,
+                    "worktreeName": worktree_name,
+                    "activatedTerminals": activated_terminals,
+                    "activeTerminalTab": active_terminal_tab,
+                    "terminalVisible": terminal_visible,
+                    "clientId": client_id,
+                    "tabLabels": tab_labels,
+                })
+                .to_string();
+                let _ = TERMINAL_STATE_BROADCAST.send(broadcast_msg);
+
+                // Also emit Tauri event for PC端 to receive Web端 changes
+                if let Some(app_handle) = crate::APP_HANDLE
+                    .lock()
+                    .ok()
+                    .and_then(|h| h.as_ref().cloned())
+                {
+                    let _ = app_handle.emit(
+                        "terminal-state-update",
+                        json!({
+                            "workspacePath": workspace_path,
+                            "worktreeName": worktree_name,
+                            "activatedTerminals": activated_terminals,
+                            "activeTerminalTab": active_terminal_tab,
+                            "terminalVisible": terminal_visible,
+                            "clientId": client_id,
+                            "tabLabels": tab_labels,
+                        }),
+                    );
+                }
+            }
+
+            "subscribe_focus" => {
+                let workspace_path = match parsed["workspacePath"].as_str() {
+                    Some(s) => s.to_string(),
+                    None => continue,
+                };
 
-                if let Some(state) = initial_state {
+                // Abort existing focus forwarder if any
+                if let Some(handle) = focus_forwarder.take() {
+                    handle.abort();
+                }
+
+                // Send initial focus state from cache so the viewer jumps right away
+                if let Some(state) =
+                    crate::commands::window::get_focus_state_inner(workspace_path.clone())
+                {
                     let msg = json!({
-                        "type": "terminal_state_update",
+                        "type": "focus_update",
                         "workspacePath": &workspace_path,
-                        "worktreeName": &worktree_name,
-                        "activatedTerminals": state.activated_terminals,
-                        "activeTerminalTab": state.active_terminal_tab,
-                        "terminalVisible": state.terminal_visible,
+                        "worktreeName": state.worktree_name,
+                        "projectName": state.project_name,
                         "clientId": state.client_id,
+                        "updatedAt": state.updated_at,
                     });
                     let mut sender = ws_sender.lock().await;
                     let _ = sender.send(Message::text(msg.to_string())).await;
                 }
 
-                // Subscribe to terminal state broadcast
-                let mut rx = TERMINAL_STATE_BROADCAST.subscribe();
+                let mut rx = crate::state::FOCUS_BROADCAST.subscribe();
                 let sender = Arc::clone(&ws_sender);
                 let ws_path = workspace_path.clone();
-                let wt_name = worktree_name.clone();
                 let handle = tokio::spawn(async move {
                     loop {
                         match rx.recv().await {
                             Ok(json_str) => {
-                                // Parse the broadcast to check if it's for our workspace/worktree
-                                if let Ok(val) = serde_json::from_str::<Value>(&json_str) {
-                                    if val["workspacePath"].as_str() == Some(&ws_path)
-                                        && val["worktreeName"].as_str() == Some(&wt_name)
-                                    {
-                                        let msg = json!({
-                                            "type": "terminal_state_update",
-                                            "workspacePath": &ws_path,
-                                            "worktreeName": &wt_name,
-                                            "activatedTerminals": val["activatedTerminals"],
-                                            "activeTerminalTab": val["activeTerminalTab"],
-                                            "terminalVisible": val["terminalVisible"],
-                                            "clientId": val["clientId"],
-                                        });
+                                if let Ok(mut val) = serde_json::from_str::<Value>(&json_str) {
+                                    if val["workspacePath"].as_str() == Some(&ws_path) {
+                                        val["type"] = json!("focus_update");
                                         let mut sender = sender.lock().await;
                                         if sender
-                                            .send(Message::text(msg.to_string()))
+                                            .send(Message::text(val.to_string()))
                                             .await
                                             .is_err()
                                         {
@@ -1368,84 +3549,159 @@ async fn handle_ws(socket: WebSocket, session_id: String) {
                                     }
                                 }
                             }
-                            Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
-                                // Log lagged receiver warning - client is too slow
-                                log::warn!("Terminal state broadcast lagged, skipped {} messages for {}/{}",
-                                    skipped, ws_path, wt_name);
-                                continue;
-                            }
+                            Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                            Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                        }
+                    }
+                });
+                focus_forwarder = Some(handle);
+            }
+
+            "unsubscribe_focus" => {
+                if let Some(handle) = focus_forwarder.take() {
+                    handle.abort();
+                }
+            }
+
+            "subscribe_app_logs" => {
+                if !is_admin {
+                    log::warn!(
+                        "Rejected app log subscription from non-admin session {}",
+                        session_id
+                    );
+                    let msg = json!({
+                        "type": "app_log_error",
+                        "error": "App log streaming requires an admin session",
+                    });
+                    let mut sender = ws_sender.lock().await;
+                    let _ = sender.send(Message::text(msg.to_string())).await;
+                    continue;
+                }
+
+                // Abort existing log forwarder if any
+                if let Some(handle) = log_forwarder.take() {
+                    handle.abort();
+                }
+
+                crate::commands::system::ensure_log_tailer();
+
+                // Send the recent tail first so the viewer has context
+                let lines = parsed["lines"].as_u64().map(|v| v as usize);
+                let tail = tokio::task::spawn_blocking(move || {
+                    crate::commands::system::tail_app_logs_internal(lines)
+                })
+                .await
+                .ok()
+                .and_then(|r| r.ok())
+                .unwrap_or_default();
+                {
+                    let msg = json!({
+                        "type": "app_log_tail",
+                        "lines": tail,
+                    });
+                    let mut sender = ws_sender.lock().await;
+                    let _ = sender.send(Message::text(msg.to_string())).await;
+                }
+
+                let mut rx = crate::state::LOG_BROADCAST.subscribe();
+                let sender = Arc::clone(&ws_sender);
+                let handle = tokio::spawn(async move {
+                    loop {
+                        match rx.recv().await {
+                            Ok(json_str) => {
+                                if let Ok(val) = serde_json::from_str::<Value>(&json_str) {
+                                    let msg = json!({
+                                        "type": "app_log_line",
+                                        "line": val["line"],
+                                    });
+                                    let mut sender = sender.lock().await;
+                                    if sender.send(Message::text(msg.to_string())).await.is_err() {
+                                        break;
+                                    }
+                                }
+                            }
+                            Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                            Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                        }
+                    }
+                });
+                log_forwarder = Some(handle);
+            }
+
+            "unsubscribe_app_logs" => {
+                if let Some(handle) = log_forwarder.take() {
+                    handle.abort();
+                }
+            }
+
+            "subscribe_worktree_changes" => {
+                let worktree_name = match parsed["worktreeName"].as_str() {
+                    Some(s) if !s.is_empty() && !s.contains(['/', '\\']) && s != ".." => {
+                        s.to_string()
+                    }
+                    _ => continue,
+                };
+                let allowed = share_allowed_worktrees();
+                if !allowed.is_empty() && !is_worktree_allowed(&allowed, &worktree_name) {
+                    continue;
+                }
+
+                if let Some((path, handle)) = change_forwarder.take() {
+                    handle.abort();
+                    crate::fs_watch::unsubscribe_worktree_changes(&path);
+                }
+
+                let worktree_path = match crate::config::get_window_workspace_config(&session_id)
+                {
+                    Some((ws_path, config)) => std::path::PathBuf::from(ws_path)
+                        .join(&config.worktrees_dir)
+                        .join(&worktree_name),
+                    None => continue,
+                };
+                let mut rx = match crate::fs_watch::subscribe_worktree_changes(&worktree_path) {
+                    Ok(rx) => rx,
+                    Err(e) => {
+                        let msg = json!({
+                            "type": "worktree_changes_error",
+                            "worktreeName": worktree_name,
+                            "error": e,
+                        });
+                        let mut sender = ws_sender.lock().await;
+                        let _ = sender.send(Message::text(msg.to_string())).await;
+                        continue;
+                    }
+                };
+
+                let sender = Arc::clone(&ws_sender);
+                let handle = tokio::spawn(async move {
+                    loop {
+                        match rx.recv().await {
+                            Ok(json_str) => {
+                                if let Ok(val) = serde_json::from_str::<Value>(&json_str) {
+                                    let msg = json!({
+                                        "type": "worktree_changes",
+                                        "worktreeName": worktree_name,
+                                        "changes": val["changes"],
+                                        "truncated": val["truncated"],
+                                    });
+                                    let mut sender = sender.lock().await;
+                                    if sender.send(Message::text(msg.to_string())).await.is_err() {
+                                        break;
+                                    }
+                                }
+                            }
+                            Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
                             Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
                         }
                     }
                 });
-                terminal_state_forwarder = Some(handle);
+                change_forwarder = Some((worktree_path, handle));
             }
 
-            "broadcast_terminal_state" => {
-                let workspace_path = match parsed["workspacePath"].as_str() {
-                    Some(s) => s.to_string(),
-                    None => continue,
-                };
-                let worktree_name = match parsed["worktreeName"].as_str() {
-                    Some(s) => s.to_string(),
-                    None => continue,
-                };
-                let activated_terminals = parsed["activatedTerminals"]
-                    .as_array()
-                    .map(|arr| {
-                        arr.iter()
-                            .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                            .collect::<Vec<_>>()
-                    })
-                    .unwrap_or_default();
-                let active_terminal_tab =
-                    parsed["activeTerminalTab"].as_str().map(|s| s.to_string());
-                let terminal_visible = parsed["terminalVisible"].as_bool().unwrap_or(false);
-                let client_id = parsed["clientId"].as_str().map(|s| s.to_string());
-
-                // Update cache with client_id
-                if let Ok(mut states) = crate::TERMINAL_STATES.lock() {
-                    let key = (workspace_path.clone(), worktree_name.clone());
-                    states.insert(
-                        key,
-                        crate::TerminalState {
-                            activated_terminals: activated_terminals.clone(),
-                            active_terminal_tab: active_terminal_tab.clone(),
-                            terminal_visible,
-                            client_id: client_id.clone(),
-                        },
-                    );
-                }
-
-                // Broadcast to all connected clients with clientId
-                let broadcast_msg = json!({
-                    "workspacePath": workspace_path,
-                    "worktreeName": worktree_name,
-                    "activatedTerminals": activated_terminals,
-                    "activeTerminalTab": active_terminal_tab,
-                    "terminalVisible": terminal_visible,
-                    "clientId": client_id,
-                })
-                .to_string();
-                let _ = TERMINAL_STATE_BROADCAST.send(broadcast_msg);
-
-                // Also emit Tauri event for PC端 to receive Web端 changes
-                if let Some(app_handle) = crate::APP_HANDLE
-                    .lock()
-                    .ok()
-                    .and_then(|h| h.as_ref().cloned())
-                {
-                    let _ = app_handle.emit(
-                        "terminal-state-update",
-                        json!({
-                            "workspacePath": workspace_path,
-                            "worktreeName": worktree_name,
-                            "activatedTerminals": activated_terminals,
-                            "activeTerminalTab": active_terminal_tab,
-                            "terminalVisible": terminal_visible,
-                            "clientId": client_id,
-                        }),
-                    );
+            "unsubscribe_worktree_changes" => {
+                if let Some((path, handle)) = change_forwarder.take() {
+                    handle.abort();
+                    crate::fs_watch::unsubscribe_worktree_changes(&path);
                 }
             }
 
@@ -1494,12 +3750,25 @@ async fn handle_ws(socket: WebSocket, session_id: String) {
     if let Some(handle) = lock_forwarder {
         handle.abort();
     }
+    if let Some(handle) = workspace_event_forwarder {
+        handle.abort();
+    }
     if let Some(handle) = terminal_state_forwarder {
         handle.abort();
     }
     if let Some(handle) = voice_forwarder {
         handle.abort();
     }
+    if let Some(handle) = focus_forwarder {
+        handle.abort();
+    }
+    if let Some(handle) = log_forwarder {
+        handle.abort();
+    }
+    if let Some((path, handle)) = change_forwarder {
+        handle.abort();
+        crate::fs_watch::unsubscribe_worktree_changes(&path);
+    }
     notification_forwarder.abort();
 
     // Mark WebSocket disconnected
@@ -1511,6 +3780,289 @@ async fn handle_ws(socket: WebSocket, session_id: String) {
     log::info!("WebSocket disconnected for session {}", session_id);
 }
 
+// ---------------------------------------------------------------------------
+// Server-sent events (fallback for networks that block WebSockets)
+// ---------------------------------------------------------------------------
+
+#[derive(Deserialize)]
+struct EventsParams {
+    session_id: Option<String>,
+    /// Stream lock updates of this workspace
+    workspace_path: Option<String>,
+    /// With `workspace_path`: stream this worktree's terminal state
+    worktree_name: Option<String>,
+    /// Comma-separated PTY sessions whose output to stream
+    pty: Option<String>,
+    /// Comma-separated subset of `pty` that first gets the replay buffer (newly opened terminals)
+    replay: Option<String>,
+    /// Tail this many app log lines, then follow new ones (admin sessions only)
+    logs: Option<usize>,
+}
+
+/// Forwarders of one event stream; dropped when the client goes away.
+struct EventStreamGuard {
+    session_id: String,
+    forwarders: Vec<tokio::task::JoinHandle<()>>,
+}
+
+impl Drop for EventStreamGuard {
+    fn drop(&mut self) {
+        for handle in &self.forwarders {
+            handle.abort();
+        }
+        if let Ok(mut clients) = CONNECTED_CLIENTS.lock() {
+            if let Some(client) = clients.get_mut(&self.session_id) {
+                client.ws_connected = false;
+            }
+        }
+        log::info!("Event stream closed for session {}", self.session_id);
+    }
+}
+
+fn comma_list(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or("")
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Forward the messages `map` makes of a broadcast into an event stream.
+fn forward_broadcast(
+    mut rx: tokio::sync::broadcast::Receiver<String>,
+    tx: tokio::sync::mpsc::Sender<String>,
+    map: impl Fn(&str) -> Option<String> + Send + 'static,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(payload) => {
+                    if let Some(msg) = map(&payload) {
+                        if tx.send(msg).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    })
+}
+
+/// Forward a PTY session's output (after its replay buffer, if any) into an event stream.
+fn forward_pty_output(
+    pty_session_id: String,
+    replay: Vec<u8>,
+    mut rx: tokio::sync::broadcast::Receiver<Vec<u8>>,
+    tx: tokio::sync::mpsc::Sender<String>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        // Pending buffer for incomplete UTF-8 sequences across chunk boundaries
+        let mut utf8_pending: Vec<u8> = Vec::new();
+        let mut next = Some(replay);
+        loop {
+            if let Some(mut data) = next.take() {
+                if !utf8_pending.is_empty() {
+                    let mut buf = std::mem::take(&mut utf8_pending);
+                    buf.append(&mut data);
+                    data = buf;
+                }
+                let (text, pending) = bytes_to_utf8_with_pending(&data);
+                utf8_pending = pending;
+                if !text.is_empty() {
+                    let msg = json!({
+                        "type": "pty_output",
+                        "sessionId": pty_session_id,
+                        "data": text,
+                    });
+                    if tx.send(msg.to_string()).await.is_err() {
+                        break;
+                    }
+                }
+            }
+            match rx.recv().await {
+                Ok(data) => next = Some(data),
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!(
+                        "PTY output broadcast lagged, skipped {} messages for session {}",
+                        skipped,
+                        pty_session_id
+                    );
+                    utf8_pending.clear();
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    })
+}
+
+/// `GET /api/events`: the lock, terminal state and PTY output messages of the WebSocket as
+/// server-sent events, for clients whose network kills WebSockets. Subscriptions are fixed per
+/// stream (the client reopens it when they change); input goes through `/api/pty_write`.
+/// EventSource can't send headers, so the session comes in the query like for `/ws`.
+async fn h_events(Query(params): Query<EventsParams>) -> Response {
+    let Some(sid) = params.session_id.clone() else {
+        return (StatusCode::UNAUTHORIZED, "Missing session_id").into_response();
+    };
+    let needs_auth = SHARE_STATE
+        .lock()
+        .map(|state| state.active && state.auth_key.is_some())
+        .unwrap_or(false);
+    if needs_auth && crate::commands::sharing::session_role(&sid).is_none() {
+        return (StatusCode::UNAUTHORIZED, "Not authenticated").into_response();
+    }
+
+    let (tx, rx) = tokio::sync::mpsc::channel::<String>(256);
+    let mut forwarders = vec![];
+
+    // Kick / expiry notifications for this session; the stream ends after delivering one
+    forwarders.push(forward_broadcast(
+        crate::state::CLIENT_NOTIFICATION_BROADCAST.subscribe(),
+        tx.clone(),
+        {
+            let sid = sid.clone();
+            move |payload: &str| {
+                let mut val: Value = serde_json::from_str(payload).ok()?;
+                if val["session_id"].as_str() != Some(&sid) {
+                    return None;
+                }
+                val.as_object_mut()?.remove("session_id");
+                Some(val.to_string())
+            }
+        },
+    ));
+
+    if let Some(workspace_path) = params.workspace_path.filter(|p| !p.is_empty()) {
+        if let Some(msg) = lock_snapshot_message(&workspace_path) {
+            let _ = tx.send(msg).await;
+        }
+        let ws_path = workspace_path.clone();
+        forwarders.push(forward_broadcast(
+            LOCK_BROADCAST.subscribe(),
+            tx.clone(),
+            move |payload| lock_update_message(&ws_path, payload),
+        ));
+        let ws_path = workspace_path.clone();
+        forwarders.push(forward_broadcast(
+            WORKSPACE_EVENT_BROADCAST.subscribe(),
+            tx.clone(),
+            move |payload| workspace_event_message(&ws_path, payload),
+        ));
+
+        if let Some(worktree_name) = params.worktree_name.filter(|n| !n.is_empty()) {
+            if let Some(msg) = terminal_state_snapshot_message(&workspace_path, &worktree_name) {
+                let _ = tx.send(msg).await;
+            }
+            forwarders.push(forward_broadcast(
+                TERMINAL_STATE_BROADCAST.subscribe(),
+                tx.clone(),
+                move |payload| {
+                    terminal_state_update_message(&workspace_path, &worktree_name, payload)
+                },
+            ));
+        }
+    }
+
+    let replay = comma_list(params.replay.as_deref());
+    let allowed = share_allowed_worktrees();
+    for pty_session_id in comma_list(params.pty.as_deref()) {
+        if !pty_session_allowed(&allowed, &sid, &pty_session_id) {
+            continue;
+        }
+        let subscription = match PTY_MANAGER.lock() {
+            Ok(manager) => manager.subscribe_session(&pty_session_id),
+            Err(_) => None,
+        };
+        match subscription {
+            Some((buffer, pty_rx)) => {
+                let buffer = if replay.contains(&pty_session_id) {
+                    buffer
+                } else {
+                    vec![]
+                };
+                forwarders.push(forward_pty_output(
+                    pty_session_id,
+                    buffer,
+                    pty_rx,
+                    tx.clone(),
+                ));
+            }
+            None => log::warn!(
+                "Event stream '{}': PTY session not found in PTY manager",
+                pty_session_id
+            ),
+        }
+    }
+
+    if let Some(lines) = params.logs {
+        if can_view_app_logs(&sid) {
+            crate::commands::system::ensure_log_tailer();
+            let tail = tokio::task::spawn_blocking(move || {
+                crate::commands::system::tail_app_logs_internal(Some(lines))
+            })
+            .await
+            .ok()
+            .and_then(|r| r.ok())
+            .unwrap_or_default();
+            let _ = tx
+                .send(json!({ "type": "app_log_tail", "lines": tail }).to_string())
+                .await;
+            forwarders.push(forward_broadcast(
+                crate::state::LOG_BROADCAST.subscribe(),
+                tx.clone(),
+                |payload: &str| {
+                    let val: Value = serde_json::from_str(payload).ok()?;
+                    Some(json!({ "type": "app_log_line", "line": val["line"] }).to_string())
+                },
+            ));
+        } else {
+            log::warn!(
+                "Rejected app log subscription from non-admin session {}",
+                sid
+            );
+            let msg = json!({
+                "type": "app_log_error",
+                "error": "App log streaming requires an admin session",
+            });
+            let _ = tx.send(msg.to_string()).await;
+        }
+    }
+
+    if let Ok(mut clients) = CONNECTED_CLIENTS.lock() {
+        if let Some(client) = clients.get_mut(&sid) {
+            client.ws_connected = true;
+            client.last_active = chrono::Utc::now().to_rfc3339();
+        }
+    }
+    log::info!(
+        "Event stream opened for session {} ({} forwarders)",
+        sid,
+        forwarders.len()
+    );
+
+    // The guard (and an idle sender, so the stream outlives its forwarders) lives as long as
+    // the response body
+    let guard = EventStreamGuard {
+        session_id: sid,
+        forwarders,
+    };
+    let stream = futures_util::stream::unfold(Some((rx, guard, tx)), |state| async move {
+        let (mut rx, guard, tx) = state?;
+        let msg = rx.recv().await?;
+        // A kicked or expired session gets the notification, then the stream ends
+        let closing =
+            msg.contains("\"type\":\"kicked\"") || msg.contains("\"type\":\"session_expired\"");
+        let next = (!closing).then_some((rx, guard, tx));
+        Some((Ok::<_, Infallible>(Event::default().data(msg)), next))
+    });
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
 // -- Voice --
 
 async fn h_voice_start(Json(args): Json<Value>) -> Response {
@@ -1577,6 +4129,25 @@ async fn h_get_connected_clients() -> Response {
     }
 }
 
+async fn h_quarantine_client(Json(args): Json<Value>) -> Response {
+    let session_id = args["sessionId"].as_str().unwrap_or("").to_string();
+    result_ok(crate::quarantine_client_internal(&session_id))
+}
+
+async fn h_release_client(Json(args): Json<Value>) -> Response {
+    let session_id = args["sessionId"].as_str().unwrap_or("").to_string();
+    result_ok(crate::release_client_internal(&session_id))
+}
+
+async fn h_set_client_role(Json(args): Json<Value>) -> Response {
+    let session_id = args["sessionId"].as_str().unwrap_or("").to_string();
+    let role: ShareRole = match serde_json::from_value(args["role"].clone()) {
+        Ok(r) => r,
+        Err(e) => return (StatusCode::BAD_REQUEST, format!("Invalid role: {}", e)).into_response(),
+    };
+    result_ok(crate::set_client_role_internal(&session_id, role))
+}
+
 async fn h_kick_client(Json(args): Json<Value>) -> Response {
     let session_id = args["sessionId"].as_str().unwrap_or("").to_string();
     result_ok(crate::kick_client_internal(&session_id))
@@ -1633,15 +4204,10 @@ fn is_allowed_origin(origin: &str) -> bool {
             return true;
         }
     }
-    // Allow the active ngrok URL if one exists
-    if let Ok(state) = SHARE_STATE.lock() {
-        if let Some(ref ngrok_url) = state.ngrok_url {
-            if origin.starts_with(ngrok_url) {
-                return true;
-            }
-        }
-    }
-    false
+    // Allow the public URLs of active tunnels
+    crate::tunnel::active_urls()
+        .iter()
+        .any(|url| origin.starts_with(url.as_str()))
 }
 
 pub fn create_router(cert_pem: Option<String>) -> Router {
@@ -1653,7 +4219,8 @@ pub fn create_router(cert_pem: Option<String>) -> Router {
         .allow_headers([
             header::CONTENT_TYPE,
             header::HeaderName::from_static("x-session-id"),
-        ]);
+        ])
+        .expose_headers([header::HeaderName::from_static(OPERATION_ID_HEADER)]);
 
     // Resolve the dist/ folder relative to the current executable
     // In debug builds, always use CARGO_MANIFEST_DIR/../dist (= project root's dist/)
@@ -1708,8 +4275,17 @@ pub fn create_router(cert_pem: Option<String>) -> Router {
         .route("/api/switch_workspace", post(h_switch_workspace))
         // Workspace config
         .route("/api/get_workspace_config", post(h_get_workspace_config))
+        .route(
+            "/api/get_workspace_config_versioned",
+            post(h_get_workspace_config_versioned),
+        )
         .route("/api/save_workspace_config", post(h_save_workspace_config))
+        .route(
+            "/api/validate_workspace_config",
+            post(h_validate_workspace_config),
+        )
         .route("/api/get_config_path_info", post(h_get_config_path_info))
+        .route("/api/get_paths", post(h_get_paths))
         // Worktree operations
         .route("/api/list_worktrees", post(h_list_worktrees))
         .route(
@@ -1718,16 +4294,53 @@ pub fn create_router(cert_pem: Option<String>) -> Router {
         )
         .route("/api/create_worktree", post(h_create_worktree))
         .route("/api/archive_worktree", post(h_archive_worktree))
+        .route("/api/archive_worktrees", post(h_archive_worktrees))
         .route("/api/check_worktree_status", post(h_check_worktree_status))
+        .route("/api/list_worktrees_page", post(h_list_worktrees_page))
+        .route("/api/get_worktree", post(h_get_worktree))
+        .route("/api/get_worktree_status", post(h_get_worktree_status))
+        .route("/api/get_worktree_meta", post(h_get_worktree_meta))
+        .route("/api/get_worktree_ports", post(h_get_worktree_ports))
+        .route("/api/get_worktree_issue", post(h_get_worktree_issue))
+        .route("/api/compose_status", post(h_compose_status))
+        .route("/api/compose_up", post(h_compose_up))
+        .route("/api/compose_down", post(h_compose_down))
+        .route("/api/kill_worktree_processes", post(h_kill_worktree_processes))
+        .route("/api/set_worktree_meta", post(h_set_worktree_meta))
+        .route("/api/set_worktree_labels", post(h_set_worktree_labels))
+        .route("/api/set_worktree_env", post(h_set_worktree_env))
         .route("/api/restore_worktree", post(h_restore_worktree))
         .route(
             "/api/delete_archived_worktree",
             post(h_delete_archived_worktree),
         )
+        .route(
+            "/api/rename_worktree_branch",
+            post(h_rename_worktree_branch),
+        )
         .route(
             "/api/add_project_to_worktree",
             post(h_add_project_to_worktree),
         )
+        .route("/api/get_disk_usage", post(h_get_disk_usage))
+        .route("/api/get_base_updates", post(h_get_base_updates))
+        .route("/api/list_actions", post(h_list_actions))
+        .route(
+            "/api/export_workspace_manifest",
+            post(h_export_workspace_manifest),
+        )
+        .route(
+            "/api/import_workspace_manifest",
+            post(h_import_workspace_manifest),
+        )
+        .route("/api/convert_to_link", post(h_convert_to_link))
+        .route("/api/sync_links", post(h_sync_links))
+        .route("/api/repair_worktrees", post(h_repair_worktrees))
+        .route("/api/get_sync_advice", post(h_get_sync_advice))
+        .route("/api/sync_safe_projects", post(h_sync_safe_projects))
+        .route("/api/fetch_all_projects", post(h_fetch_all_projects))
+        .route("/api/refresh_workspace", post(h_refresh_workspace))
+        .route("/api/get_dirty_report", post(h_get_dirty_report))
         .route("/api/deploy_to_main", post(h_deploy_to_main))
         .route("/api/exit_main_occupation", post(h_exit_main_occupation))
         .route("/api/get_main_occupation", post(h_get_main_occupation))
@@ -1742,17 +4355,38 @@ pub fn create_router(cert_pem: Option<String>) -> Router {
         .route("/api/fetch_project_remote", post(h_fetch_project_remote))
         .route("/api/sync_with_base_branch", post(h_sync_with_base_branch))
         .route("/api/push_to_remote", post(h_push_to_remote))
+        .route("/api/amend_commit", post(h_amend_commit))
         .route("/api/merge_to_test_branch", post(h_merge_to_test_branch))
         .route("/api/merge_to_base_branch", post(h_merge_to_base_branch))
+        .route("/api/cherry_pick", post(h_cherry_pick))
+        .route("/api/list_merge_conflicts", post(h_list_merge_conflicts))
+        .route("/api/resolve_conflict", post(h_resolve_conflict))
+        .route("/api/continue_merge", post(h_continue_merge))
+        .route("/api/abort_merge", post(h_abort_merge))
+        .route("/api/normalize_line_endings", post(h_normalize_line_endings))
+        .route("/api/discard_changes", post(h_discard_changes))
+        .route("/api/clean_untracked", post(h_clean_untracked))
+        .route("/api/get_file_diff", post(h_get_file_diff))
         .route("/api/create_pull_request", post(h_create_pull_request))
+        .route("/api/get_ci_status", post(h_get_ci_status))
         .route("/api/get_remote_branches", post(h_get_remote_branches))
+        .route("/api/export_diff_zip", post(h_export_diff_zip))
+        .route("/api/download", get(h_download))
+        .route("/api/get_operation_log", post(h_get_operation_log))
+        .route("/api/list_operation_logs", post(h_list_operation_logs))
         // Scan
         .route("/api/scan_linked_folders", post(h_scan_linked_folders))
         // System utilities
         .route("/api/open_in_terminal", post(h_open_in_terminal))
         .route("/api/open_in_editor", post(h_open_in_editor))
+        .route("/api/open_in_devcontainer", post(h_open_in_devcontainer))
+        .route(
+            "/api/get_devcontainer_status",
+            post(h_get_devcontainer_status),
+        )
         .route("/api/reveal_in_finder", post(h_reveal_in_finder))
         .route("/api/open_log_dir", post(h_open_log_dir))
+        .route("/api/tail_app_logs", post(h_tail_app_logs))
         // Multi-window management
         .route("/api/get_opened_workspaces", post(h_get_opened_workspaces))
         .route("/api/unregister_window", post(h_unregister_window))
@@ -1760,15 +4394,46 @@ pub fn create_router(cert_pem: Option<String>) -> Router {
         .route("/api/unlock_worktree", post(h_unlock_worktree))
         .route("/api/get_locked_worktrees", post(h_get_locked_worktrees))
         .route("/api/get_terminal_state", post(h_get_terminal_state))
+        .route("/api/get_focus_state", post(h_get_focus_state))
+        .route(
+            "/api/get_focus_follow_enabled",
+            post(h_get_focus_follow_enabled),
+        )
         .route("/api/open_workspace_window", post(h_open_workspace_window))
         // PTY
         .route("/api/pty_create", post(h_pty_create))
+        .route("/api/pty_create_ssh", post(h_pty_create_ssh))
         .route("/api/pty_write", post(h_pty_write))
         .route("/api/pty_read", post(h_pty_read))
         .route("/api/pty_resize", post(h_pty_resize))
         .route("/api/pty_close", post(h_pty_close))
         .route("/api/pty_exists", post(h_pty_exists))
         .route("/api/pty_close_by_path", post(h_pty_close_by_path))
+        .route("/api/pty_get_scrollback", post(h_pty_get_scrollback))
+        .route("/api/pty_search_output", post(h_pty_search_output))
+        .route(
+            "/api/get_scrollback_persist_enabled",
+            post(h_get_scrollback_persist_enabled),
+        )
+        .route("/api/pty_restore_sessions", post(h_pty_restore_sessions))
+        .route("/api/get_pty_limits", post(h_get_pty_limits))
+        .route("/api/get_rate_limit_config", post(h_get_rate_limit_config))
+        .route("/api/get_ssh_hosts", post(h_get_ssh_hosts))
+        .route(
+            "/api/get_session_restore_enabled",
+            post(h_get_session_restore_enabled),
+        )
+        .route("/api/pty_start_recording", post(h_pty_start_recording))
+        .route("/api/pty_stop_recording", post(h_pty_stop_recording))
+        .route("/api/pty_get_recording", post(h_pty_get_recording))
+        .route("/api/list_recordings", post(h_list_recordings))
+        .route("/api/read_recording", post(h_read_recording))
+        .route("/api/get_command_history", post(h_get_command_history))
+        .route("/api/run_quick_command", post(h_run_quick_command))
+        .route("/api/list_quick_commands", post(h_list_quick_commands))
+        .route("/api/run_task", post(h_run_task))
+        .route("/api/open_terminal_preset", post(h_open_terminal_preset))
+        .route("/api/list_running_processes", post(h_list_running_processes))
         // Auth
         .route("/api/auth/challenge", post(h_auth_challenge))
         .route("/api/auth/verify", post(h_auth_verify))
@@ -1777,11 +4442,26 @@ pub fn create_router(cert_pem: Option<String>) -> Router {
         // Connected clients
         .route("/api/get_connected_clients", post(h_get_connected_clients))
         .route("/api/kick_client", post(h_kick_client))
+        .route("/api/quarantine_client", post(h_quarantine_client))
+        .route("/api/release_client", post(h_release_client))
+        .route("/api/set_client_role", post(h_set_client_role))
         // ngrok
         .route("/api/get_ngrok_token", post(h_get_ngrok_token))
         .route("/api/set_ngrok_token", post(h_set_ngrok_token))
         .route("/api/start_ngrok_tunnel", post(h_start_ngrok_tunnel))
         .route("/api/stop_ngrok_tunnel", post(h_stop_ngrok_tunnel))
+        // Cloudflare tunnel
+        .route(
+            "/api/start_cloudflare_tunnel",
+            post(h_start_cloudflare_tunnel),
+        )
+        .route(
+            "/api/stop_cloudflare_tunnel",
+            post(h_stop_cloudflare_tunnel),
+        )
+        // frp tunnel
+        .route("/api/start_frp_tunnel", post(h_start_frp_tunnel))
+        .route("/api/stop_frp_tunnel", post(h_stop_frp_tunnel))
         // WMS config & tunnel
         .route("/api/get_wms_config", post(h_get_wms_config))
         .route("/api/set_wms_config", post(h_set_wms_config))
@@ -1814,8 +4494,12 @@ pub fn create_router(cert_pem: Option<String>) -> Router {
         )
         // Misc
         .route("/api/get_app_version", post(h_get_app_version))
-        // WebSocket (auth handled in upgrade handler via query param)
-        .route("/ws", get(h_ws_upgrade));
+        .route("/api/health", get(h_health))
+        .route("/api/webhook/git", post(h_webhook_git))
+        .route("/api/openapi.json", get(h_openapi))
+        // WebSocket and its SSE fallback (auth handled in the handlers via query param)
+        .route("/ws", get(h_ws_upgrade))
+        .route("/api/events", get(h_events));
 
     // Add cert download route when TLS is enabled
     if let Some(pem) = cert_pem {
@@ -1825,15 +4509,33 @@ pub fn create_router(cert_pem: Option<String>) -> Router {
     }
 
     router
+        .layer(axum::middleware::from_fn(worktree_scope_middleware))
+        .layer(axum::middleware::from_fn(activity_middleware))
         .layer(axum::middleware::from_fn(auth_middleware))
+        .layer(axum::middleware::from_fn(rate_limit_middleware))
         .layer(axum::middleware::from_fn(localhost_only_middleware))
         .layer(axum::middleware::from_fn(security_headers_middleware))
         // Limit request body to 1MB
         .layer(RequestBodyLimitLayer::new(1024 * 1024))
         .fallback_service(serve_dir)
+        .layer(compression_layer())
         .layer(cors)
 }
 
+/// gzip/brotli for JSON and static assets (large worktree lists are slow over tunnels).
+/// The default predicate already skips small bodies, images and event streams; zips are
+/// compressed already, and WebSocket upgrades must not get a `Content-Encoding`.
+fn compression_layer() -> CompressionLayer<impl Predicate> {
+    let not_upgrade = |status: StatusCode, _: Version, _: &HeaderMap, _: &Extensions| {
+        status != StatusCode::SWITCHING_PROTOCOLS
+    };
+    CompressionLayer::new().compress_when(
+        DefaultPredicate::new()
+            .and(NotForContentType::const_new("application/zip"))
+            .and(not_upgrade),
+    )
+}
+
 // ---------------------------------------------------------------------------
 // Server startup
 // ---------------------------------------------------------------------------
@@ -1845,12 +4547,10 @@ pub fn create_router(cert_pem: Option<String>) -> Router {
 /// When `tls_certs` is None:
 ///   Plain HTTP for everyone (e.g. dev mode).
 pub async fn start_server(
-    port: u16,
+    addr: SocketAddr,
     mut shutdown_rx: tokio::sync::watch::Receiver<bool>,
     tls_certs: Option<TlsCerts>,
 ) {
-    let addr = SocketAddr::from(([0, 0, 0, 0], port));
-
     log::info!("[http-server] Starting server on {}", addr);
     let listener = match tokio::net::TcpListener::bind(addr).await {
         Ok(l) => l,
@@ -1859,6 +4559,9 @@ pub async fn start_server(
             return;
         }
     };
+    if let Ok(mut started) = SERVER_STARTED_AT.lock() {
+        *started = Some(std::time::Instant::now());
+    }
 
     match tls_certs {
         Some(certs) => {
@@ -1989,3 +4692,199 @@ pub async fn start_server(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn of(path: &str) -> Option<String> {
+        worktree_of_path("/ws", "worktrees", path)
+    }
+
+    #[test]
+    fn worktree_of_path_finds_the_worktree() {
+        for (path, expected) in [
+            ("/ws/worktrees/feat-x", "feat-x"),
+            ("/ws/worktrees/feat-x/projects/api", "feat-x"),
+            ("worktrees/feat-x/projects/api", "feat-x"),
+            ("/ws/worktrees/feat-x.archive", "feat-x.archive"),
+        ] {
+            assert_eq!(of(path).as_deref(), Some(expected), "{}", path);
+        }
+    }
+
+    #[test]
+    fn worktree_of_path_rejects_paths_outside_worktrees() {
+        for path in [
+            "/ws/worktrees",
+            "/ws/projects/api",
+            "/other/worktrees/feat-x",
+            "/wsx/worktrees/feat-x",
+            "projects/api",
+        ] {
+            assert_eq!(of(path), None, "{}", path);
+        }
+    }
+
+    #[test]
+    fn worktree_of_path_rejects_parent_segments() {
+        for path in [
+            "/ws/worktrees/feat-x/../feat-y",
+            "/ws/worktrees/feat-x/../../projects/api",
+            "worktrees/../worktrees/feat-y",
+            "worktrees/feat-x/..",
+        ] {
+            assert_eq!(of(path), None, "{}", path);
+        }
+    }
+
+    #[test]
+    fn allowed_worktrees_include_their_archives() {
+        let allowed = vec!["feat-x".to_string()];
+        assert!(is_worktree_allowed(&allowed, "feat-x"));
+        assert!(is_worktree_allowed(&allowed, "feat-x.archive"));
+        assert!(!is_worktree_allowed(&allowed, "feat-y"));
+        assert!(!is_worktree_allowed(&allowed, "feat-x-2"));
+        assert!(!is_worktree_allowed(&[], "feat-x"));
+    }
+
+    #[test]
+    fn host_paths_are_replaced_by_relative_paths() {
+        let mut value = json!({
+            "path": "/ws",
+            "worktrees": [{
+                "path": "/ws/worktrees/feat-x",
+                "rel_path": "worktrees/feat-x",
+                "projects": [{
+                    "path": "/ws/worktrees/feat-x/projects/api",
+                    "rel_path": "worktrees/feat-x/projects/api",
+                }],
+            }],
+        });
+        strip_host_paths(&mut value);
+        assert_eq!(value["worktrees"][0]["path"], "worktrees/feat-x");
+        assert_eq!(
+            value["worktrees"][0]["projects"][0]["path"],
+            "worktrees/feat-x/projects/api"
+        );
+        assert_eq!(value["path"], "/ws");
+    }
+
+    #[test]
+    fn workspace_events_are_forwarded_to_their_workspace_only() {
+        let event = json!({
+            "type": "git_remote_updated",
+            "workspacePath": "/ws",
+            "projectName": "api",
+        })
+        .to_string();
+        assert_eq!(workspace_event_message("/ws", &event), Some(event.clone()));
+        assert_eq!(workspace_event_message("/other", &event), None);
+
+        let locks = json!({ "workspacePath": "/ws", "locks": { "feat-x": "alice" } }).to_string();
+        let update: Value =
+            serde_json::from_str(&lock_update_message("/ws", &locks).unwrap()).unwrap();
+        assert_eq!(
+            update,
+            json!({ "type": "lock_update", "locks": { "feat-x": "alice" } })
+        );
+    }
+
+    #[test]
+    fn restricted_profiles_only_reach_listed_routes() {
+        for (path, scope) in [
+            ("/api/list_worktrees", Some(RouteScope::Open)),
+            ("/api/switch_workspace", Some(RouteScope::Workspace)),
+            ("/api/get_worktree", Some(RouteScope::NamedWorktree)),
+            ("/api/sync_links", Some(RouteScope::Worktree)),
+            ("/api/pty_write", Some(RouteScope::PtySession)),
+            ("/api/export_workspace_manifest", None),
+            ("/api/import_workspace_manifest", None),
+            ("/api/get_operation_log", None),
+            ("/api/list_operation_logs", None),
+            ("/api/rename_worktree_branch", None),
+            ("/api/get_base_updates", None),
+            ("/api/get_disk_usage", None),
+            ("/api/pty_create_ssh", None),
+            ("/api/some_future_route", None),
+        ] {
+            assert_eq!(restricted_route_scope(path), scope, "{}", path);
+        }
+    }
+
+    #[test]
+    fn roles_come_from_explicit_route_lists() {
+        for (path, role) in [
+            ("/api/list_worktrees", ShareRole::Viewer),
+            ("/api/pty_read", ShareRole::Viewer),
+            ("/api/get_workspace_config_versioned", ShareRole::Viewer),
+            ("/api/create_worktree", ShareRole::Operator),
+            ("/api/pty_write", ShareRole::Operator),
+            ("/api/get_operation_log", ShareRole::Operator),
+            ("/api/save_workspace_config", ShareRole::Admin),
+            ("/api/get_wms_config", ShareRole::Admin),
+            ("/api/get_ngrok_token", ShareRole::Admin),
+            ("/api/get_dashscope_api_key", ShareRole::Admin),
+            ("/api/get_ssh_hosts", ShareRole::Admin),
+            ("/api/get_something_new", ShareRole::Admin),
+            ("/api/list_secrets", ShareRole::Admin),
+        ] {
+            assert_eq!(required_role(path), role, "{}", path);
+        }
+    }
+
+    #[test]
+    fn host_requests_come_from_loopback_without_session_or_tunnel() {
+        let loopback: IpAddr = "127.0.0.1".parse().unwrap();
+        let lan: IpAddr = "192.168.1.20".parse().unwrap();
+        let with = |name: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(name, HeaderValue::from_static("x"));
+            headers
+        };
+        assert!(is_host_request(loopback, &HeaderMap::new()));
+        assert!(is_host_request("::1".parse().unwrap(), &HeaderMap::new()));
+        assert!(!is_host_request(lan, &HeaderMap::new()));
+        assert!(!is_host_request(loopback, &with("x-session-id")));
+        for header in FORWARDING_HEADERS {
+            assert!(!is_host_request(loopback, &with(header)), "{}", header);
+        }
+    }
+
+    #[test]
+    fn unauthenticated_rate_limit_keys_use_the_client_ip() {
+        let loopback: IpAddr = "127.0.0.1".parse().unwrap();
+        let lan: IpAddr = "192.168.1.20".parse().unwrap();
+        let with = |pairs: &[(&'static str, &'static str)]| {
+            let mut headers = HeaderMap::new();
+            for (name, value) in pairs {
+                headers.insert(*name, HeaderValue::from_static(value));
+            }
+            headers
+        };
+        let forwarded = with(&[("x-forwarded-for", "203.0.113.7, 10.0.0.1")]);
+
+        assert_eq!(rate_limit_key(lan, &HeaderMap::new()), "ip:192.168.1.20");
+        assert_eq!(rate_limit_key(loopback, &forwarded), "ip:203.0.113.7");
+        // Only tunnels on loopback are trusted to name the client
+        assert_eq!(rate_limit_key(lan, &forwarded), "ip:192.168.1.20");
+        // Unknown credentials don't get a bucket of their own
+        let unknown = with(&[
+            ("authorization", "Bearer wmt_unknown"),
+            ("x-session-id", "not-logged-in"),
+        ]);
+        assert_eq!(rate_limit_key(lan, &unknown), "ip:192.168.1.20");
+
+        let cases = [
+            ("cf-connecting-ip", "198.51.100.2", "198.51.100.2"),
+            ("x-real-ip", "2001:db8::1", "2001:db8::1"),
+            ("forwarded", "for=\"[::2]:443\";proto=https", "::2"),
+            ("forwarded", "for=192.0.2.9, for=10.0.0.1", "192.0.2.9"),
+            ("x-forwarded-for", "unknown", "127.0.0.1"),
+        ];
+        for (name, value, ip) in cases {
+            let key = rate_limit_key(loopback, &with(&[(name, value)]));
+            assert_eq!(key, format!("ip:{}", ip), "{}: {}", name, value);
+        }
+    }
+}
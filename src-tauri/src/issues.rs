@@ -0,0 +1,215 @@
+//! Issue tracker linking: ticket keys (e.g. `PROJ-123`) are matched in worktree and branch names
+//! with the workspace's `issue_trackers` patterns and turned into links. For Jira and Linear the
+//! ticket's title and status are fetched too, when credentials are configured.
+
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use git2::Repository;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::config::load_global_config;
+use crate::state::ISSUE_CACHE;
+use crate::types::{IssueProvider, IssueTrackerAuth, IssueTrackerConfig, WorktreeIssue};
+
+/// Fetched titles/statuses are reused for this long
+const CACHE_TTL: Duration = Duration::from_secs(300);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const LINEAR_API_URL: &str = "https://api.linear.app/graphql";
+
+/// Names to look for a ticket key in: the worktree name, then the current branch of each project.
+pub(crate) fn candidate_names(worktree_path: &Path) -> Vec<String> {
+    let mut names = vec![];
+    if let Some(name) = worktree_path.file_name() {
+        names.push(name.to_string_lossy().to_string());
+    }
+    let Ok(entries) = fs::read_dir(worktree_path.join("projects")) else {
+        return names;
+    };
+    let mut project_dirs: Vec<_> = entries.flatten().map(|e| e.path()).collect();
+    project_dirs.sort();
+    for dir in project_dirs {
+        let Ok(repo) = Repository::open(&dir) else {
+            continue;
+        };
+        let branch = repo
+            .head()
+            .ok()
+            .and_then(|head| head.shorthand().map(str::to_string));
+        if let Some(branch) = branch {
+            if !names.contains(&branch) {
+                names.push(branch);
+            }
+        }
+    }
+    names
+}
+
+/// First tracker whose pattern matches one of `names`, with the extracted key.
+fn match_key<'a>(
+    trackers: &'a [IssueTrackerConfig],
+    names: &[String],
+) -> Result<Option<(&'a IssueTrackerConfig, String)>, String> {
+    for tracker in trackers {
+        if tracker.pattern.trim().is_empty() {
+            continue;
+        }
+        let re = Regex::new(&tracker.pattern)
+            .map_err(|e| format!("无效的工单匹配规则 {}: {}", tracker.pattern, e))?;
+        for name in names {
+            if let Some(caps) = re.captures(name) {
+                let key = caps.get(1).or_else(|| caps.get(0)).map(|m| m.as_str());
+                if let Some(key) = key.filter(|k| !k.is_empty()) {
+                    return Ok(Some((tracker, key.to_string())));
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// `api_base_url`, or the scheme and host of the link template.
+fn jira_base_url(tracker: &IssueTrackerConfig) -> Option<String> {
+    if let Some(base) = tracker
+        .api_base_url
+        .as_deref()
+        .filter(|b| !b.trim().is_empty())
+    {
+        return Some(base.trim().trim_end_matches('/').to_string());
+    }
+    let url = reqwest::Url::parse(&tracker.url_template.replace("{key}", "KEY")).ok()?;
+    Some(url.origin().ascii_serialization())
+}
+
+async fn fetch_jira(
+    client: &reqwest::Client,
+    tracker: &IssueTrackerConfig,
+    auth: &IssueTrackerAuth,
+    key: &str,
+) -> Result<(Option<String>, Option<String>), String> {
+    let base = jira_base_url(tracker).ok_or("无法确定 Jira API 地址，请配置 api_base_url")?;
+    let mut request = client.get(format!(
+        "{}/rest/api/2/issue/{}?fields=summary,status",
+        base, key
+    ));
+    if let Some(token) = auth.jira_token.as_deref().filter(|t| !t.is_empty()) {
+        request = match auth.jira_email.as_deref().filter(|e| !e.is_empty()) {
+            Some(email) => request.basic_auth(email, Some(token)),
+            None => request.bearer_auth(token),
+        };
+    }
+    let resp = request
+        .send()
+        .await
+        .map_err(|e| format!("Jira 请求失败: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!("Jira API 返回错误 {}", resp.status()));
+    }
+    let json: Value = resp
+        .json()
+        .await
+        .map_err(|e| format!("解析 Jira 响应失败: {}", e))?;
+    Ok((
+        json["fields"]["summary"].as_str().map(str::to_string),
+        json["fields"]["status"]["name"]
+            .as_str()
+            .map(str::to_string),
+    ))
+}
+
+async fn fetch_linear(
+    client: &reqwest::Client,
+    auth: &IssueTrackerAuth,
+    key: &str,
+) -> Result<(Option<String>, Option<String>), String> {
+    let api_key = auth
+        .linear_api_key
+        .as_deref()
+        .filter(|k| !k.is_empty())
+        .ok_or("Linear API Key 未配置")?;
+    let body = serde_json::json!({
+        "query": "query($id: String!) { issue(id: $id) { title state { name } } }",
+        "variables": { "id": key },
+    });
+    let resp = client
+        .post(LINEAR_API_URL)
+        .header("Authorization", api_key)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Linear 请求失败: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!("Linear API 返回错误 {}", resp.status()));
+    }
+    let json: Value = resp
+        .json()
+        .await
+        .map_err(|e| format!("解析 Linear 响应失败: {}", e))?;
+    if let Some(message) = json["errors"][0]["message"].as_str() {
+        return Err(format!("Linear API 返回错误: {}", message));
+    }
+    let issue = &json["data"]["issue"];
+    Ok((
+        issue["title"].as_str().map(str::to_string),
+        issue["state"]["name"].as_str().map(str::to_string),
+    ))
+}
+
+/// Match `names` against `trackers` and, for Jira/Linear trackers, fetch the ticket's title and
+/// status. Fetch failures are reported in `error` so the link can still be shown.
+pub(crate) async fn resolve_issue(
+    trackers: &[IssueTrackerConfig],
+    names: &[String],
+) -> Result<Option<WorktreeIssue>, String> {
+    let Some((tracker, key)) = match_key(trackers, names)? else {
+        return Ok(None);
+    };
+    let mut issue = WorktreeIssue {
+        url: tracker.url_template.replace("{key}", &key),
+        key,
+        provider: tracker.provider,
+        title: None,
+        status: None,
+        error: None,
+    };
+    if tracker.provider == IssueProvider::None {
+        return Ok(Some(issue));
+    }
+
+    let cache_key = issue.url.clone();
+    if let Ok(cache) = ISSUE_CACHE.lock() {
+        if let Some((fetched_at, cached)) = cache.get(&cache_key) {
+            if fetched_at.elapsed() < CACHE_TTL {
+                return Ok(Some(cached.clone()));
+            }
+        }
+    }
+
+    let auth = load_global_config().issue_tracker_auth;
+    let client = crate::network::http_client_builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
+    let fetched = match tracker.provider {
+        IssueProvider::Jira => fetch_jira(&client, tracker, &auth, &issue.key).await,
+        IssueProvider::Linear => fetch_linear(&client, &auth, &issue.key).await,
+        IssueProvider::None => Ok((None, None)),
+    };
+    match fetched {
+        Ok((title, status)) => {
+            issue.title = title;
+            issue.status = status;
+            if let Ok(mut cache) = ISSUE_CACHE.lock() {
+                cache.retain(|_, (fetched_at, _)| fetched_at.elapsed() < CACHE_TTL);
+                cache.insert(cache_key, (Instant::now(), issue.clone()));
+            }
+        }
+        Err(e) => {
+            log::warn!("[issues] Failed to fetch {}: {}", issue.key, e);
+            issue.error = Some(e);
+        }
+    }
+    Ok(Some(issue))
+}
@@ -1,10 +1,28 @@
+mod command_history;
 mod commands;
+pub(crate) mod compose;
 pub mod config;
+pub(crate) mod devcontainer;
+pub(crate) mod download;
+pub(crate) mod fs_watch;
 mod git_ops;
+mod hooks;
+mod issues;
 pub mod http_server;
+mod openapi;
+mod oplog;
+mod ports;
+mod process_tracker;
 mod pty_manager;
+mod recordings;
 pub mod state;
+pub(crate) mod network;
+pub(crate) mod notifier;
+pub(crate) mod secrets;
 pub(crate) mod tls;
+pub(crate) mod totp;
+pub(crate) mod tunnel;
+pub(crate) mod webhook;
 pub mod types;
 pub mod utils;
 pub(crate) mod wms_tunnel;
@@ -16,10 +34,20 @@ pub use types::*;
 pub use utils::normalize_path;
 
 // Re-exports of _impl functions used by http_server
-pub use commands::git::{clone_project_impl, switch_branch_internal};
+pub use commands::actions::list_actions_impl;
+pub use commands::git::{clone_project_impl, merge_to_test_branch_internal, switch_branch_internal};
 pub use commands::sharing::{
-    auto_register_tunnel_internal, kick_client_internal, start_ngrok_tunnel_internal,
-    start_wms_tunnel_internal, stop_wms_tunnel_internal, wms_manual_reconnect_internal, WmsConfig,
+    auto_register_tunnel_internal, kick_client_internal, quarantine_client_internal,
+    release_client_internal, set_client_role_internal, start_cloudflare_tunnel_internal,
+    start_frp_tunnel_internal, start_ngrok_tunnel_internal, start_wms_tunnel_internal,
+    stop_tunnel_internal, stop_wms_tunnel_internal, wms_manual_reconnect_internal, WmsConfig,
+};
+pub use commands::pty::{
+    get_command_history_impl, list_quick_commands_impl, list_recordings_impl,
+    list_running_processes_impl, open_terminal_preset_impl, pty_create_ssh_impl,
+    pty_get_recording_impl, pty_get_scrollback_impl, pty_restore_sessions_impl,
+    pty_search_output_impl, pty_start_recording_impl, pty_stop_recording_impl,
+    read_recording_impl, run_quick_command_impl, run_task_impl,
 };
 pub use commands::system::{
     open_in_editor_internal, open_in_terminal_internal, open_log_dir_internal,
@@ -30,16 +58,26 @@ pub use commands::window::{
 };
 pub use commands::workspace::{
     add_workspace_internal, create_workspace_internal, get_config_path_info_impl,
-    get_current_workspace_impl, get_workspace_config_impl, remove_workspace_internal,
-    save_workspace_config_impl, switch_workspace_impl,
+    get_current_workspace_impl, get_paths_impl, get_workspace_config_impl, get_workspace_config_versioned_impl,
+    remove_workspace_internal,
+    save_workspace_config_impl, switch_workspace_impl, validate_workspace_config_impl,
 };
 pub use commands::worktree::{
-    add_project_to_worktree_impl, archive_worktree_impl, check_worktree_status_impl,
-    create_worktree_impl, delete_archived_worktree_impl, deploy_to_main_impl,
-    exit_main_occupation_impl, get_main_occupation_impl, get_main_workspace_status_impl,
-    list_worktrees_impl, restore_worktree_impl, scan_linked_folders_internal,
+    add_project_to_worktree_impl, archive_worktree_impl, archive_worktrees_impl,
+    check_worktree_status_impl, compose_down_impl, compose_status_impl, compose_up_impl,
+    convert_to_link_impl, create_worktree_impl, delete_archived_worktree_impl, deploy_to_main_impl,
+    exit_main_occupation_impl, export_workspace_manifest_impl, fetch_all_projects_impl,
+    get_main_occupation_impl, get_worktree_impl, get_worktree_meta_impl, get_worktree_ports_impl,
+    get_worktree_issue_impl, get_worktree_status_impl,
+    kill_worktree_processes_impl,
+    get_main_workspace_status_impl, get_dirty_report_impl, get_disk_usage_impl, get_base_updates_impl, get_sync_advice_impl,
+    import_workspace_manifest_impl, list_worktrees_impl, list_worktrees_page_impl, refresh_workspace_impl,
+    rename_worktree_branch_impl,
+    repair_worktrees_impl, restore_worktree_impl,
+    scan_linked_folders_internal, set_worktree_env_impl, set_worktree_labels_impl, set_worktree_meta_impl, sync_links_impl, sync_safe_projects_impl,
 };
 
+use commands::actions::*;
 use commands::git::*;
 use commands::pty::*;
 use commands::sharing::*;
@@ -64,9 +102,13 @@ pub fn run() {
         .plugin(
             tauri_plugin_log::Builder::new()
                 .level(log::LevelFilter::Info)
-                .targets([tauri_plugin_log::Target::new(
-                    tauri_plugin_log::TargetKind::Stdout,
-                )])
+                .targets([
+                    tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::Stdout),
+                    // 写入平台日志目录，供 open_log_dir / tail_app_logs 使用
+                    tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::LogDir {
+                        file_name: None,
+                    }),
+                ])
                 .build(),
         )
         .on_window_event(|window, event| {
@@ -92,10 +134,8 @@ pub fn run() {
                         tauri::async_runtime::spawn(async move {
                             log::info!("Window closing - stopping sharing first");
 
-                            // Stop ngrok tunnel
-                            if let Err(e) = stop_ngrok_tunnel().await {
-                                log::warn!("Failed to stop ngrok tunnel on close: {}", e);
-                            }
+                            // Stop provider tunnels (ngrok, ...)
+                            tunnel::stop_all();
 
                             // Stop WMS tunnel
                             if let Err(e) = stop_wms_tunnel().await {
@@ -130,39 +170,99 @@ pub fn run() {
             create_workspace,
             // Workspace 配置
             get_workspace_config,
+            get_workspace_config_versioned,
             save_workspace_config,
+            validate_workspace_config,
             load_workspace_config_by_path,
             save_workspace_config_by_path,
             get_config_path_info,
+            get_paths,
             // Worktree 操作
             list_worktrees,
             get_main_workspace_status,
             create_worktree,
             archive_worktree,
+            archive_worktrees,
             restore_worktree,
             delete_archived_worktree,
+            rename_worktree_branch,
             check_worktree_status,
+            list_worktrees_page,
+            get_worktree,
+            get_worktree_status,
+            get_worktree_meta,
+            get_worktree_ports,
+            get_worktree_issue,
+            compose_status,
+            compose_up,
+            compose_down,
+            kill_worktree_processes,
+            set_worktree_meta,
+            set_worktree_labels,
+            set_worktree_env,
             add_project_to_worktree,
+            convert_to_link,
+            sync_links,
+            repair_worktrees,
+            fetch_all_projects,
+            refresh_workspace,
+            get_sync_advice,
+            sync_safe_projects,
+            get_dirty_report,
+            get_disk_usage,
+            get_base_updates,
             deploy_to_main,
             exit_main_occupation,
             get_main_occupation,
+            export_workspace_manifest,
+            import_workspace_manifest,
+            export_worktree_bundle,
+            import_worktree,
             // Git 操作
             switch_branch,
             clone_project,
             sync_with_base_branch,
             push_to_remote,
+            amend_commit,
             merge_to_test_branch,
             merge_to_base_branch,
+            cherry_pick,
+            list_merge_conflicts,
+            resolve_conflict,
+            continue_merge,
+            abort_merge,
+            normalize_line_endings,
+            discard_changes,
+            clean_untracked,
             get_branch_diff_stats,
+            get_file_diff,
             create_pull_request,
+            get_ci_status,
             fetch_project_remote,
             check_remote_branch_exists,
             get_remote_branches,
+            export_diff_zip,
+            get_operation_log,
+            list_operation_logs,
             // 工具
             open_in_terminal,
             open_in_editor,
+            open_in_devcontainer,
+            get_devcontainer_status,
             open_log_dir,
+            tail_app_logs,
             reveal_in_finder,
+            get_proxy_config,
+            set_proxy_config,
+            get_config_encryption_enabled,
+            set_config_encryption_enabled,
+            get_issue_tracker_auth,
+            set_issue_tracker_auth,
+            get_notifiers,
+            set_notifiers,
+            get_custom_editors,
+            set_custom_editors,
+            check_environment,
             // 多窗口管理
             set_window_workspace,
             get_opened_workspaces,
@@ -173,23 +273,75 @@ pub fn run() {
             get_locked_worktrees,
             broadcast_terminal_state,
             get_terminal_state,
+            broadcast_focus,
+            get_focus_state,
+            get_focus_follow_enabled,
+            set_focus_follow_enabled,
             // 智能扫描
             scan_linked_folders,
             // PTY 终端
             pty_create,
+            pty_create_ssh,
             pty_write,
             pty_read,
             pty_resize,
             pty_close,
             pty_exists,
             pty_close_by_path,
+            pty_get_scrollback,
+            pty_search_output,
+            get_scrollback_persist_enabled,
+            set_scrollback_persist_enabled,
+            pty_restore_sessions,
+            get_session_restore_enabled,
+            set_session_restore_enabled,
+            get_pty_limits,
+            set_pty_limits,
+            get_ssh_hosts,
+            set_ssh_hosts,
+            pty_start_recording,
+            pty_stop_recording,
+            pty_get_recording,
+            list_recordings,
+            read_recording,
+            get_command_history,
+            run_quick_command,
+            list_quick_commands,
+            run_task,
+            open_terminal_preset,
+            list_running_processes,
             // 分享功能
             start_sharing,
             stop_sharing,
+            get_share_profiles,
+            save_share_profile,
+            delete_share_profile,
+            start_sharing_with_profile,
             get_share_state,
             update_share_password,
             get_connected_clients,
             kick_client,
+            quarantine_client,
+            get_share_activity,
+            clear_share_activity,
+            release_client,
+            set_client_role,
+            list_api_tokens,
+            create_api_token,
+            revoke_api_token,
+            get_webhook_secret,
+            rotate_webhook_secret,
+            disable_webhook,
+            get_totp_enabled,
+            enroll_totp,
+            confirm_totp,
+            disable_totp,
+            get_rate_limit_config,
+            set_rate_limit_config,
+            get_session_ttl_mins,
+            set_session_ttl_mins,
+            get_custom_tls,
+            set_custom_tls,
             // ngrok
             get_ngrok_token,
             set_ngrok_token,
@@ -197,6 +349,20 @@ pub fn run() {
             get_last_share_password,
             start_ngrok_tunnel,
             stop_ngrok_tunnel,
+            // Cloudflare 隧道
+            get_cloudflare_tunnel_config,
+            set_cloudflare_tunnel_config,
+            start_cloudflare_tunnel,
+            stop_cloudflare_tunnel,
+            // frp 隧道
+            get_frp_tunnel_config,
+            set_frp_tunnel_config,
+            start_frp_tunnel,
+            stop_frp_tunnel,
+            // Tailscale
+            get_tailscale_urls,
+            start_tailscale_funnel,
+            stop_tailscale_funnel,
             // WMS 隧道
             get_wms_config,
             set_wms_config,
@@ -216,14 +382,40 @@ pub fn run() {
             voice_stop,
             voice_is_active,
             voice_refine_text,
+            // 命令面板
+            list_actions,
             // DevTools
             open_devtools,
         ])
         .setup(|app| {
             // Initialize APP_HANDLE for use in WebSocket handlers
             *APP_HANDLE.lock().unwrap() = Some(app.handle().clone());
+            network::apply_network_settings(&load_global_config().proxy);
+            commands::window::start_auto_lock_sweeper();
+            commands::worktree::start_disk_quota_monitor();
+            commands::worktree::start_base_update_monitor();
+            process_tracker::start_process_tracker();
+            fs_watch::start_workspace_watcher();
+            commands::pty::init_scrollback_persistence();
+            commands::pty::init_session_registry();
+            commands::pty::start_pty_limits();
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // 应用退出前执行有序清理（PTY、分享、锁、配置），完成后再真正退出
+            if let tauri::RunEvent::ExitRequested { code, api, .. } = event {
+                if SHUTDOWN_COMPLETE.load(std::sync::atomic::Ordering::SeqCst) {
+                    return;
+                }
+                api.prevent_exit();
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if commands::shutdown::graceful_shutdown_internal().await {
+                        app.exit(code.unwrap_or(0));
+                    }
+                });
+            }
+        });
 }
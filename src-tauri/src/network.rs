@@ -0,0 +1,96 @@
+use crate::config::load_global_config;
+use crate::types::ProxyConfig;
+
+/// Non-empty, trimmed value of an optional setting.
+fn setting(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+fn set_or_clear_env(keys: &[&str], value: Option<&str>) {
+    for key in keys {
+        match value {
+            Some(v) => std::env::set_var(key, v),
+            None => std::env::remove_var(key),
+        }
+    }
+}
+
+/// Apply proxy / CA settings process-wide.
+///
+/// git subprocesses inherit the environment, so setting the standard proxy variables
+/// (both cases, since curl only reads lowercase `http_proxy`) and `GIT_SSL_CAINFO`
+/// covers every `git fetch/push/clone` we spawn. libgit2 only does local operations
+/// here, but its TLS stack is pointed at the same CA file for consistency.
+pub(crate) fn apply_network_settings(config: &ProxyConfig) {
+    set_or_clear_env(&["HTTP_PROXY", "http_proxy"], setting(&config.http_proxy));
+    set_or_clear_env(&["HTTPS_PROXY", "https_proxy"], setting(&config.https_proxy));
+    set_or_clear_env(&["NO_PROXY", "no_proxy"], setting(&config.no_proxy));
+
+    let ca = setting(&config.ca_cert_path);
+    set_or_clear_env(&["GIT_SSL_CAINFO"], ca);
+    if let Some(ca_path) = ca {
+        // SAFETY: libgit2 global options aren't synchronised; this runs at startup and
+        // from the settings command, never concurrently with another options call.
+        if let Err(e) = unsafe { git2::opts::set_ssl_cert_file(ca_path) } {
+            log::warn!("[network] Failed to set libgit2 CA file {}: {}", ca_path, e);
+        }
+    }
+
+    log::info!(
+        "[network] Proxy settings applied: http={}, https={}, no_proxy={}, ca={}",
+        setting(&config.http_proxy).is_some(),
+        setting(&config.https_proxy).is_some(),
+        setting(&config.no_proxy).unwrap_or("-"),
+        ca.unwrap_or("-")
+    );
+}
+
+/// reqwest client builder with the configured proxy and extra CA certificates.
+/// Use this for every outbound HTTP integration instead of `reqwest::Client::builder()`.
+pub(crate) fn http_client_builder() -> reqwest::ClientBuilder {
+    let config = load_global_config().proxy;
+    let mut builder = reqwest::Client::builder();
+
+    let no_proxy = setting(&config.no_proxy).and_then(reqwest::NoProxy::from_string);
+    if let Some(url) = setting(&config.http_proxy) {
+        match reqwest::Proxy::http(url) {
+            Ok(p) => builder = builder.proxy(p.no_proxy(no_proxy.clone())),
+            Err(e) => log::warn!("[network] Invalid http_proxy {}: {}", url, e),
+        }
+    }
+    if let Some(url) = setting(&config.https_proxy) {
+        match reqwest::Proxy::https(url) {
+            Ok(p) => builder = builder.proxy(p.no_proxy(no_proxy.clone())),
+            Err(e) => log::warn!("[network] Invalid https_proxy {}: {}", url, e),
+        }
+    }
+
+    if let Some(ca_path) = setting(&config.ca_cert_path) {
+        match std::fs::read(ca_path)
+            .map_err(|e| e.to_string())
+            .and_then(|pem| reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| e.to_string()))
+        {
+            Ok(certs) => {
+                for cert in certs {
+                    builder = builder.add_root_certificate(cert);
+                }
+            }
+            Err(e) => log::warn!("[network] Failed to load CA file {}: {}", ca_path, e),
+        }
+    }
+
+    builder
+}
+
+/// Proxy URL for the ngrok session (HTTPS proxy preferred, then HTTP).
+pub(crate) fn ngrok_proxy_url() -> Option<url::Url> {
+    let config = load_global_config().proxy;
+    let raw = setting(&config.https_proxy).or(setting(&config.http_proxy))?;
+    match url::Url::parse(raw) {
+        Ok(u) => Some(u),
+        Err(e) => {
+            log::warn!("[network] Invalid proxy URL for ngrok {}: {}", raw, e);
+            None
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Chat notifications: Slack / Discord incoming webhooks configured in the global config
+//! receive a short message when a branch is merged to the test branch, a worktree is
+//! archived or sharing starts.
+
+use std::path::Path;
+use std::time::Duration;
+
+use serde_json::json;
+
+use crate::config::load_global_config;
+use crate::types::{NotifierConfig, NotifierProvider, NotifyEvent};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Send `text` to every notifier subscribed to `event`, in the background.
+pub(crate) fn notify(event: NotifyEvent, text: String) {
+    let notifiers: Vec<NotifierConfig> = load_global_config()
+        .notifiers
+        .into_iter()
+        .filter(|n| !n.webhook_url.trim().is_empty())
+        .filter(|n| n.events.is_empty() || n.events.contains(&event))
+        .collect();
+    if notifiers.is_empty() {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let client = match crate::network::http_client_builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                log::warn!("[notifier] Failed to create HTTP client: {}", e);
+                return;
+            }
+        };
+        for notifier in notifiers {
+            let body = match notifier.provider {
+                NotifierProvider::Slack => json!({ "text": text }),
+                NotifierProvider::Discord => json!({ "content": text }),
+            };
+            let result = client
+                .post(notifier.webhook_url.trim())
+                .json(&body)
+                .send()
+                .await
+                .and_then(|resp| resp.error_for_status());
+            if let Err(e) = result {
+                log::warn!(
+                    "[notifier] {:?} notification failed: {}",
+                    notifier.provider,
+                    e
+                );
+            }
+        }
+    });
+}
+
+/// `worktree/project` for a project directory inside a worktree (`{worktree}/projects/{project}`)
+fn project_label(path: &Path) -> String {
+    let project = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    match path
+        .parent()
+        .and_then(|p| p.parent())
+        .and_then(|p| p.file_name())
+    {
+        Some(worktree) => format!("{}/{}", worktree.to_string_lossy(), project),
+        None => project,
+    }
+}
+
+pub(crate) fn notify_merge_to_test(
+    path: &Path,
+    branch: Option<&str>,
+    test_branch: &str,
+    result: &Result<String, String>,
+) {
+    let branch = branch.unwrap_or("?");
+    match result {
+        Ok(_) => notify(
+            NotifyEvent::MergeToTestSucceeded,
+            format!(
+                "✅ [{}] {} 已合并到 {}",
+                project_label(path),
+                branch,
+                test_branch
+            ),
+        ),
+        Err(e) => notify(
+            NotifyEvent::MergeToTestFailed,
+            format!(
+                "❌ [{}] {} 合并到 {} 失败: {}",
+                project_label(path),
+                branch,
+                test_branch,
+                e
+            ),
+        ),
+    }
+}
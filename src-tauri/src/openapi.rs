@@ -0,0 +1,488 @@
+//! OpenAPI description of the HTTP API used by shared web clients, served at
+//! `/api/openapi.json`. Request bodies are described by the endpoint table below (a test
+//! checks it against the router in `http_server.rs`); endpoints that are also command palette
+//! actions take their summary and argument descriptions from the action registry.
+
+use std::collections::HashMap;
+
+use serde_json::{json, Map, Value};
+
+use crate::commands::actions::action_docs;
+use crate::types::ActionInfo;
+
+#[derive(Clone, Copy)]
+enum Method {
+    Get,
+    Post,
+}
+
+impl Method {
+    fn as_str(self) -> &'static str {
+        match self {
+            Method::Get => "get",
+            Method::Post => "post",
+        }
+    }
+}
+
+/// What a successful call returns.
+#[derive(Clone, Copy)]
+enum Reply {
+    /// 200 with a JSON body
+    Json,
+    /// 204 without a body
+    NoContent,
+    /// 200 with a zip archive
+    Zip,
+    /// 200 with a PEM certificate
+    Pem,
+    /// 200 with a stream of server-sent events
+    EventStream,
+    /// 101, upgraded to a WebSocket
+    WebSocket,
+}
+
+/// A top-level field of the JSON request body.
+struct Field {
+    name: &'static str,
+    /// "string" | "path" | "number" | "boolean" | "array" (of strings) | "object"
+    kind: &'static str,
+    required: bool,
+}
+
+const fn req(name: &'static str, kind: &'static str) -> Field {
+    Field {
+        name,
+        kind,
+        required: true,
+    }
+}
+
+const fn opt(name: &'static str, kind: &'static str) -> Field {
+    Field {
+        name,
+        kind,
+        required: false,
+    }
+}
+
+struct Endpoint {
+    path: &'static str,
+    method: Method,
+    tag: &'static str,
+    fields: &'static [Field],
+    reply: Reply,
+}
+
+const fn endpoint(
+    path: &'static str,
+    method: Method,
+    tag: &'static str,
+    fields: &'static [Field],
+    reply: Reply,
+) -> Endpoint {
+    Endpoint {
+        path,
+        method,
+        tag,
+        fields,
+        reply,
+    }
+}
+
+/// Endpoints that skip authentication (see `auth_middleware`)
+const PUBLIC_PATHS: &[&str] = &[
+    "/api/auth/challenge",
+    "/api/auth/verify",
+    "/api/get_share_info",
+    "/api/health",
+    "/api/webhook/git",
+    "/api/cert.pem",
+    "/ws",
+];
+
+#[rustfmt::skip]
+const ENDPOINTS: &[Endpoint] = &[
+    endpoint("/api/list_workspaces", Method::Post, "workspace", &[], Reply::Json),
+    endpoint("/api/add_workspace", Method::Post, "workspace", &[req("name", "string"), req("path", "path")], Reply::NoContent),
+    endpoint("/api/remove_workspace", Method::Post, "workspace", &[req("path", "path")], Reply::NoContent),
+    endpoint("/api/create_workspace", Method::Post, "workspace", &[req("name", "string"), req("path", "path")], Reply::NoContent),
+    endpoint("/api/set_window_workspace", Method::Post, "workspace", &[req("workspacePath", "string")], Reply::NoContent),
+    endpoint("/api/get_current_workspace", Method::Post, "workspace", &[], Reply::Json),
+    endpoint("/api/switch_workspace", Method::Post, "workspace", &[req("path", "string")], Reply::NoContent),
+    endpoint("/api/get_workspace_config", Method::Post, "workspace", &[], Reply::Json),
+    endpoint("/api/get_workspace_config_versioned", Method::Post, "workspace", &[], Reply::Json),
+    endpoint("/api/save_workspace_config", Method::Post, "workspace", &[req("config", "object"), opt("baseEtag", "string")], Reply::Json),
+    endpoint("/api/validate_workspace_config", Method::Post, "workspace", &[], Reply::Json),
+    endpoint("/api/get_config_path_info", Method::Post, "workspace", &[], Reply::Json),
+    endpoint("/api/get_paths", Method::Post, "workspace", &[], Reply::Json),
+    endpoint("/api/list_worktrees", Method::Post, "worktree", &[opt("includeArchived", "boolean"), opt("labels", "array")], Reply::Json),
+    endpoint("/api/get_main_workspace_status", Method::Post, "worktree", &[], Reply::Json),
+    endpoint("/api/create_worktree", Method::Post, "worktree", &[req("request", "object")], Reply::Json),
+    endpoint("/api/archive_worktree", Method::Post, "worktree", &[req("name", "string")], Reply::Json),
+    endpoint("/api/archive_worktrees", Method::Post, "worktree", &[req("names", "array")], Reply::Json),
+    endpoint("/api/check_worktree_status", Method::Post, "worktree", &[req("name", "string")], Reply::Json),
+    endpoint("/api/list_worktrees_page", Method::Post, "worktree", &[req("query", "object")], Reply::Json),
+    endpoint("/api/get_worktree", Method::Post, "worktree", &[req("name", "string")], Reply::Json),
+    endpoint("/api/get_worktree_status", Method::Post, "worktree", &[req("name", "string"), opt("project", "string")], Reply::Json),
+    endpoint("/api/get_worktree_meta", Method::Post, "worktree", &[req("name", "string")], Reply::Json),
+    endpoint("/api/get_worktree_ports", Method::Post, "worktree", &[req("name", "string")], Reply::Json),
+    endpoint("/api/get_worktree_issue", Method::Post, "worktree", &[req("name", "string")], Reply::Json),
+    endpoint("/api/compose_status", Method::Post, "worktree", &[req("name", "string"), req("project", "string")], Reply::Json),
+    endpoint("/api/compose_up", Method::Post, "worktree", &[req("name", "string"), req("project", "string")], Reply::Json),
+    endpoint("/api/compose_down", Method::Post, "worktree", &[req("name", "string"), req("project", "string")], Reply::Json),
+    endpoint("/api/kill_worktree_processes", Method::Post, "worktree", &[req("path", "string")], Reply::Json),
+    endpoint("/api/set_worktree_meta", Method::Post, "worktree", &[req("name", "string"), opt("description", "string"), opt("ticketUrl", "string")], Reply::Json),
+    endpoint("/api/set_worktree_labels", Method::Post, "worktree", &[req("name", "string"), req("labels", "array")], Reply::Json),
+    endpoint("/api/set_worktree_env", Method::Post, "worktree", &[req("name", "string"), req("env", "object")], Reply::Json),
+    endpoint("/api/restore_worktree", Method::Post, "worktree", &[req("name", "string")], Reply::Json),
+    endpoint("/api/delete_archived_worktree", Method::Post, "worktree", &[req("name", "string"), opt("deleteRemote", "boolean")], Reply::Json),
+    endpoint("/api/rename_worktree_branch", Method::Post, "worktree", &[req("worktreeName", "string"), req("newName", "string"), opt("push", "boolean")], Reply::Json),
+    endpoint("/api/add_project_to_worktree", Method::Post, "worktree", &[req("request", "object")], Reply::NoContent),
+    endpoint("/api/get_disk_usage", Method::Post, "worktree", &[], Reply::Json),
+    endpoint("/api/get_base_updates", Method::Post, "worktree", &[], Reply::Json),
+    endpoint("/api/list_actions", Method::Post, "worktree", &[opt("worktreeName", "string"), opt("projectName", "string")], Reply::Json),
+    endpoint("/api/export_workspace_manifest", Method::Post, "worktree", &[], Reply::Json),
+    endpoint("/api/import_workspace_manifest", Method::Post, "worktree", &[req("manifest", "object")], Reply::Json),
+    endpoint("/api/convert_to_link", Method::Post, "worktree", &[req("worktreeName", "string"), opt("projectName", "string"), req("item", "string"), opt("merge", "boolean")], Reply::Json),
+    endpoint("/api/sync_links", Method::Post, "worktree", &[], Reply::Json),
+    endpoint("/api/repair_worktrees", Method::Post, "worktree", &[], Reply::Json),
+    endpoint("/api/get_sync_advice", Method::Post, "worktree", &[req("worktreeName", "string")], Reply::Json),
+    endpoint("/api/sync_safe_projects", Method::Post, "worktree", &[req("worktreeName", "string")], Reply::Json),
+    endpoint("/api/fetch_all_projects", Method::Post, "worktree", &[], Reply::Json),
+    endpoint("/api/refresh_workspace", Method::Post, "worktree", &[], Reply::Json),
+    endpoint("/api/get_dirty_report", Method::Post, "worktree", &[opt("worktreeName", "string"), opt("projectNames", "array")], Reply::Json),
+    endpoint("/api/deploy_to_main", Method::Post, "worktree", &[req("worktreeName", "string"), opt("dirtyAck", "string")], Reply::Json),
+    endpoint("/api/exit_main_occupation", Method::Post, "worktree", &[opt("force", "boolean")], Reply::NoContent),
+    endpoint("/api/get_main_occupation", Method::Post, "worktree", &[], Reply::Json),
+    endpoint("/api/switch_branch", Method::Post, "git", &[req("request", "object")], Reply::NoContent),
+    endpoint("/api/clone_project", Method::Post, "git", &[req("request", "object")], Reply::NoContent),
+    endpoint("/api/get_branch_diff_stats", Method::Post, "git", &[req("baseBranch", "string"), req("path", "path")], Reply::Json),
+    endpoint("/api/check_remote_branch_exists", Method::Post, "git", &[req("branchName", "string"), req("path", "path")], Reply::Json),
+    endpoint("/api/fetch_project_remote", Method::Post, "git", &[req("path", "path")], Reply::Json),
+    endpoint("/api/sync_with_base_branch", Method::Post, "git", &[req("baseBranch", "string"), req("path", "path")], Reply::Json),
+    endpoint("/api/push_to_remote", Method::Post, "git", &[req("path", "path")], Reply::Json),
+    endpoint("/api/amend_commit", Method::Post, "git", &[req("newMessage", "string"), opt("includeStaged", "boolean"), req("path", "path")], Reply::Json),
+    endpoint("/api/merge_to_test_branch", Method::Post, "git", &[req("testBranch", "string"), opt("keepConflicts", "boolean"), req("path", "path")], Reply::Json),
+    endpoint("/api/merge_to_base_branch", Method::Post, "git", &[req("baseBranch", "string"), req("path", "path")], Reply::Json),
+    endpoint("/api/cherry_pick", Method::Post, "git", &[req("path", "path")], Reply::Json),
+    endpoint("/api/list_merge_conflicts", Method::Post, "git", &[req("path", "path")], Reply::Json),
+    endpoint("/api/resolve_conflict", Method::Post, "git", &[req("file", "string"), req("resolution", "string"), req("path", "path")], Reply::NoContent),
+    endpoint("/api/continue_merge", Method::Post, "git", &[req("path", "path")], Reply::Json),
+    endpoint("/api/abort_merge", Method::Post, "git", &[req("path", "path")], Reply::Json),
+    endpoint("/api/normalize_line_endings", Method::Post, "git", &[req("path", "path")], Reply::Json),
+    endpoint("/api/discard_changes", Method::Post, "git", &[opt("dryRun", "boolean"), req("path", "path")], Reply::Json),
+    endpoint("/api/clean_untracked", Method::Post, "git", &[opt("dryRun", "boolean"), req("path", "path")], Reply::Json),
+    endpoint("/api/get_file_diff", Method::Post, "git", &[req("file", "string"), opt("baseRef", "string"), req("path", "path")], Reply::Json),
+    endpoint("/api/create_pull_request", Method::Post, "git", &[req("baseBranch", "string"), req("title", "string"), opt("body", "string"), req("path", "path")], Reply::Json),
+    endpoint("/api/get_ci_status", Method::Post, "git", &[req("path", "path"), req("branch", "string")], Reply::Json),
+    endpoint("/api/get_remote_branches", Method::Post, "git", &[req("path", "path")], Reply::Json),
+    endpoint("/api/export_diff_zip", Method::Post, "git", &[req("baseBranch", "string"), req("path", "path")], Reply::Zip),
+    endpoint("/api/download", Method::Get, "worktree", &[req("path", "path")], Reply::Zip),
+    endpoint("/api/get_operation_log", Method::Post, "git", &[req("id", "string")], Reply::Json),
+    endpoint("/api/list_operation_logs", Method::Post, "git", &[], Reply::Json),
+    endpoint("/api/scan_linked_folders", Method::Post, "workspace", &[req("projectPath", "path")], Reply::Json),
+    endpoint("/api/open_in_terminal", Method::Post, "system", &[req("path", "path")], Reply::NoContent),
+    endpoint("/api/open_in_editor", Method::Post, "system", &[req("request", "object")], Reply::NoContent),
+    endpoint("/api/open_in_devcontainer", Method::Post, "system", &[req("path", "path")], Reply::NoContent),
+    endpoint("/api/get_devcontainer_status", Method::Post, "system", &[req("path", "path")], Reply::Json),
+    endpoint("/api/reveal_in_finder", Method::Post, "system", &[req("path", "path")], Reply::NoContent),
+    endpoint("/api/open_log_dir", Method::Post, "system", &[], Reply::NoContent),
+    endpoint("/api/tail_app_logs", Method::Post, "system", &[opt("lines", "number")], Reply::Json),
+    endpoint("/api/get_opened_workspaces", Method::Post, "window", &[], Reply::Json),
+    endpoint("/api/unregister_window", Method::Post, "window", &[], Reply::NoContent),
+    endpoint("/api/lock_worktree", Method::Post, "window", &[req("workspacePath", "string"), req("worktreeName", "string")], Reply::NoContent),
+    endpoint("/api/unlock_worktree", Method::Post, "window", &[req("workspacePath", "string"), req("worktreeName", "string")], Reply::NoContent),
+    endpoint("/api/get_locked_worktrees", Method::Post, "window", &[req("workspacePath", "string")], Reply::Json),
+    endpoint("/api/get_terminal_state", Method::Post, "window", &[req("workspacePath", "string"), req("worktreeName", "string")], Reply::Json),
+    endpoint("/api/get_focus_state", Method::Post, "window", &[req("workspacePath", "string")], Reply::Json),
+    endpoint("/api/get_focus_follow_enabled", Method::Post, "window", &[], Reply::Json),
+    endpoint("/api/open_workspace_window", Method::Post, "window", &[req("workspacePath", "string")], Reply::Json),
+    endpoint("/api/pty_create", Method::Post, "terminal", &[req("sessionId", "string"), opt("cols", "number"), opt("rows", "number"), req("cwd", "path")], Reply::NoContent),
+    endpoint("/api/pty_create_ssh", Method::Post, "terminal", &[req("sessionId", "string"), req("host", "string"), opt("user", "string"), opt("cwd", "string"), opt("cols", "number"), opt("rows", "number")], Reply::NoContent),
+    endpoint("/api/pty_write", Method::Post, "terminal", &[req("sessionId", "string"), req("data", "string")], Reply::NoContent),
+    endpoint("/api/pty_read", Method::Post, "terminal", &[req("sessionId", "string")], Reply::Json),
+    endpoint("/api/pty_resize", Method::Post, "terminal", &[req("sessionId", "string"), opt("cols", "number"), opt("rows", "number")], Reply::NoContent),
+    endpoint("/api/pty_close", Method::Post, "terminal", &[req("sessionId", "string")], Reply::NoContent),
+    endpoint("/api/pty_exists", Method::Post, "terminal", &[req("sessionId", "string")], Reply::Json),
+    endpoint("/api/pty_close_by_path", Method::Post, "terminal", &[req("pathPrefix", "string")], Reply::Json),
+    endpoint("/api/pty_get_scrollback", Method::Post, "terminal", &[req("sessionId", "string"), opt("lines", "number")], Reply::Json),
+    endpoint("/api/pty_search_output", Method::Post, "terminal", &[req("sessionId", "string"), req("pattern", "string"), opt("maxMatches", "number")], Reply::Json),
+    endpoint("/api/get_scrollback_persist_enabled", Method::Post, "terminal", &[], Reply::Json),
+    endpoint("/api/pty_restore_sessions", Method::Post, "terminal", &[], Reply::Json),
+    endpoint("/api/get_pty_limits", Method::Post, "terminal", &[], Reply::Json),
+    endpoint("/api/get_rate_limit_config", Method::Post, "sharing", &[], Reply::Json),
+    endpoint("/api/get_ssh_hosts", Method::Post, "terminal", &[], Reply::Json),
+    endpoint("/api/get_session_restore_enabled", Method::Post, "terminal", &[], Reply::Json),
+    endpoint("/api/pty_start_recording", Method::Post, "terminal", &[req("sessionId", "string"), opt("title", "string")], Reply::Json),
+    endpoint("/api/pty_stop_recording", Method::Post, "terminal", &[req("sessionId", "string")], Reply::Json),
+    endpoint("/api/pty_get_recording", Method::Post, "terminal", &[req("sessionId", "string")], Reply::Json),
+    endpoint("/api/list_recordings", Method::Post, "terminal", &[opt("worktreeName", "string")], Reply::Json),
+    endpoint("/api/read_recording", Method::Post, "terminal", &[opt("worktreeName", "string"), req("fileName", "string")], Reply::Json),
+    endpoint("/api/get_command_history", Method::Post, "terminal", &[opt("worktreeName", "string"), opt("query", "string"), opt("limit", "number")], Reply::Json),
+    endpoint("/api/run_quick_command", Method::Post, "terminal", &[req("worktreeName", "string"), req("label", "string"), opt("project", "string"), opt("newSession", "boolean")], Reply::Json),
+    endpoint("/api/list_quick_commands", Method::Post, "terminal", &[], Reply::Json),
+    endpoint("/api/run_task", Method::Post, "terminal", &[req("worktreeName", "string"), req("project", "string"), req("task", "string")], Reply::Json),
+    endpoint("/api/open_terminal_preset", Method::Post, "terminal", &[req("worktreeName", "string"), req("project", "string"), req("preset", "string")], Reply::Json),
+    endpoint("/api/list_running_processes", Method::Post, "terminal", &[opt("worktreeName", "string")], Reply::Json),
+    endpoint("/api/auth/challenge", Method::Post, "auth", &[], Reply::Json),
+    endpoint("/api/auth/verify", Method::Post, "auth", &[req("proof", "string"), req("nonce", "string"), opt("totp", "string")], Reply::Json),
+    endpoint("/api/get_share_info", Method::Get, "sharing", &[], Reply::Json),
+    endpoint("/api/get_connected_clients", Method::Post, "sharing", &[], Reply::Json),
+    endpoint("/api/kick_client", Method::Post, "sharing", &[req("sessionId", "string")], Reply::NoContent),
+    endpoint("/api/quarantine_client", Method::Post, "sharing", &[req("sessionId", "string")], Reply::NoContent),
+    endpoint("/api/release_client", Method::Post, "sharing", &[req("sessionId", "string")], Reply::NoContent),
+    endpoint("/api/set_client_role", Method::Post, "sharing", &[req("sessionId", "string"), req("role", "string")], Reply::NoContent),
+    endpoint("/api/get_ngrok_token", Method::Post, "sharing", &[], Reply::Json),
+    endpoint("/api/set_ngrok_token", Method::Post, "sharing", &[req("token", "string")], Reply::NoContent),
+    endpoint("/api/start_ngrok_tunnel", Method::Post, "sharing", &[], Reply::Json),
+    endpoint("/api/stop_ngrok_tunnel", Method::Post, "sharing", &[], Reply::NoContent),
+    endpoint("/api/start_cloudflare_tunnel", Method::Post, "sharing", &[], Reply::Json),
+    endpoint("/api/stop_cloudflare_tunnel", Method::Post, "sharing", &[], Reply::NoContent),
+    endpoint("/api/start_frp_tunnel", Method::Post, "sharing", &[], Reply::Json),
+    endpoint("/api/stop_frp_tunnel", Method::Post, "sharing", &[], Reply::NoContent),
+    endpoint("/api/get_wms_config", Method::Post, "sharing", &[], Reply::Json),
+    endpoint("/api/set_wms_config", Method::Post, "sharing", &[opt("server_url", "string"), opt("token", "string"), opt("subdomain", "string")], Reply::NoContent),
+    endpoint("/api/start_wms_tunnel", Method::Post, "sharing", &[], Reply::Json),
+    endpoint("/api/stop_wms_tunnel", Method::Post, "sharing", &[], Reply::NoContent),
+    endpoint("/api/wms_manual_reconnect", Method::Post, "sharing", &[], Reply::NoContent),
+    endpoint("/api/voice_start", Method::Post, "voice", &[opt("sampleRate", "number")], Reply::NoContent),
+    endpoint("/api/voice_send_audio", Method::Post, "voice", &[req("data", "string")], Reply::NoContent),
+    endpoint("/api/voice_stop", Method::Post, "voice", &[], Reply::NoContent),
+    endpoint("/api/voice_is_active", Method::Post, "voice", &[], Reply::Json),
+    endpoint("/api/voice_refine_text", Method::Post, "voice", &[req("text", "string")], Reply::Json),
+    endpoint("/api/get_dashscope_api_key", Method::Post, "voice", &[], Reply::Json),
+    endpoint("/api/set_dashscope_api_key", Method::Post, "voice", &[req("key", "string")], Reply::NoContent),
+    endpoint("/api/get_dashscope_base_url", Method::Post, "voice", &[], Reply::Json),
+    endpoint("/api/set_dashscope_base_url", Method::Post, "voice", &[req("url", "string")], Reply::NoContent),
+    endpoint("/api/get_voice_refine_enabled", Method::Post, "voice", &[], Reply::Json),
+    endpoint("/api/set_voice_refine_enabled", Method::Post, "voice", &[opt("enabled", "boolean")], Reply::NoContent),
+    endpoint("/api/get_app_version", Method::Post, "system", &[], Reply::Json),
+    endpoint("/api/health", Method::Get, "system", &[], Reply::Json),
+    endpoint("/api/webhook/git", Method::Post, "system", &[], Reply::Json),
+    endpoint("/api/openapi.json", Method::Get, "system", &[], Reply::Json),
+    endpoint("/api/cert.pem", Method::Get, "sharing", &[], Reply::Pem),
+    endpoint("/api/events", Method::Get, "terminal", &[req("session_id", "string"), opt("workspace_path", "string"), opt("worktree_name", "string"), opt("pty", "string"), opt("replay", "string"), opt("logs", "number")], Reply::EventStream),
+    endpoint("/ws", Method::Get, "terminal", &[req("session_id", "string")], Reply::WebSocket),
+];
+
+fn field_schema(field: &Field, description: Option<&str>) -> Value {
+    let mut schema = match field.kind {
+        "path" => json!({
+            "type": "string",
+            "description": "Absolute path, or relative to the workspace root",
+        }),
+        "number" => json!({ "type": "integer", "minimum": 0 }),
+        "array" => json!({ "type": "array", "items": { "type": "string" } }),
+        kind => json!({ "type": kind }),
+    };
+    if let Some(description) = description {
+        schema["description"] = json!(description);
+    }
+    schema
+}
+
+fn responses(reply: Reply, public: bool) -> Value {
+    let success = match reply {
+        Reply::Json => json!({
+            "200": {
+                "description": "OK",
+                "content": { "application/json": { "schema": {} } },
+            }
+        }),
+        Reply::NoContent => json!({ "204": { "description": "Done" } }),
+        Reply::Zip => json!({
+            "200": {
+                "description": "Zip archive",
+                "content": {
+                    "application/zip": { "schema": { "type": "string", "format": "binary" } },
+                },
+            }
+        }),
+        Reply::Pem => json!({
+            "200": {
+                "description": "Certificate of the sharing server",
+                "content": { "application/x-pem-file": { "schema": { "type": "string" } } },
+            }
+        }),
+        Reply::EventStream => json!({
+            "200": {
+                "description": "WebSocket messages (lock_update, terminal_state_update, pty_output) as server-sent events",
+                "content": { "text/event-stream": { "schema": { "type": "string" } } },
+            }
+        }),
+        Reply::WebSocket => json!({
+            "101": {
+                "description": "Switched to a WebSocket carrying terminal I/O, lock and state updates; \
+                    the session is checked against `session_id`",
+            }
+        }),
+    };
+    let mut responses = success.as_object().cloned().unwrap_or_default();
+    let error = |description: &str| {
+        json!({
+            "description": description,
+            "content": { "text/plain": { "schema": { "type": "string" } } },
+        })
+    };
+    responses.insert(
+        "400".to_string(),
+        error("Invalid arguments or the operation failed"),
+    );
+    if !public {
+        responses.insert("401".to_string(), error("Not authenticated"));
+        responses.insert(
+            "403".to_string(),
+            error("Read-only session, or the worktree is not shared"),
+        );
+    }
+    Value::Object(responses)
+}
+
+fn operation(endpoint: &Endpoint, action: Option<&ActionInfo>) -> Value {
+    let id = endpoint
+        .path
+        .trim_start_matches("/api/")
+        .trim_start_matches('/')
+        .replace(['/', '.'], "_");
+    let public = PUBLIC_PATHS.contains(&endpoint.path);
+    let mut op = json!({
+        "operationId": id,
+        "tags": [endpoint.tag],
+        "responses": responses(endpoint.reply, public),
+    });
+    if let Some(action) = action {
+        op["summary"] = json!(action.title);
+    }
+    if public {
+        op["security"] = json!([]);
+    }
+    if !endpoint.fields.is_empty() && matches!(endpoint.method, Method::Get) {
+        // GET endpoints take their arguments in the query string
+        let parameters: Vec<Value> = endpoint
+            .fields
+            .iter()
+            .map(|f| {
+                json!({
+                    "name": f.name,
+                    "in": "query",
+                    "required": f.required,
+                    "schema": field_schema(f, None),
+                })
+            })
+            .collect();
+        op["parameters"] = json!(parameters);
+    } else if !endpoint.fields.is_empty() {
+        let describe = |name: &str| {
+            action
+                .and_then(|a| a.args.iter().find(|arg| arg.name == name))
+                .map(|arg| arg.description.as_str())
+        };
+        let properties: Map<String, Value> = endpoint
+            .fields
+            .iter()
+            .map(|f| (f.name.to_string(), field_schema(f, describe(f.name))))
+            .collect();
+        let required: Vec<&str> = endpoint
+            .fields
+            .iter()
+            .filter(|f| f.required)
+            .map(|f| f.name)
+            .collect();
+        op["requestBody"] = json!({
+            "required": true,
+            "content": {
+                "application/json": {
+                    "schema": {
+                        "type": "object",
+                        "properties": properties,
+                        "required": required,
+                    },
+                },
+            },
+        });
+    }
+    op
+}
+
+/// The OpenAPI 3.1 document of the HTTP API.
+pub(crate) fn openapi_document() -> Value {
+    let actions: HashMap<String, ActionInfo> = action_docs()
+        .into_iter()
+        .map(|a| (a.id.clone(), a))
+        .collect();
+    let mut paths = Map::new();
+    for endpoint in ENDPOINTS {
+        let id = endpoint.path.trim_start_matches("/api/");
+        let item = paths
+            .entry(endpoint.path.to_string())
+            .or_insert_with(|| json!({}));
+        item[endpoint.method.as_str()] = operation(endpoint, actions.get(id));
+    }
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "Worktree Manager API",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "HTTP API of the sharing server. Workspace and worktree arguments are \
+                resolved against the workspace bound to the caller's `x-session-id`. Terminal \
+                output, state and notifications are pushed over the `/ws` WebSocket.",
+        },
+        "security": [{ "session": [] }, { "token": [] }],
+        "components": {
+            "securitySchemes": {
+                "session": {
+                    "type": "apiKey",
+                    "in": "header",
+                    "name": "x-session-id",
+                    "description": "Session id; when sharing is password protected it must first be \
+                        authenticated through /api/auth/challenge and /api/auth/verify",
+                },
+                "token": {
+                    "type": "http",
+                    "scheme": "bearer",
+                    "description": "API token created in the desktop app's settings",
+                },
+            },
+        },
+        "paths": paths,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    /// `(path, method)` of every `.route(...)` registered in `http_server.rs`
+    fn router_routes() -> BTreeSet<(String, String)> {
+        include_str!("http_server.rs")
+            .split(".route(")
+            .skip(1)
+            .filter_map(|rest| {
+                let rest = rest.trim_start().strip_prefix('"')?;
+                let (path, rest) = rest.split_once('"')?;
+                let rest = rest.trim_start().strip_prefix(',')?.trim_start();
+                let method = ["get", "post"]
+                    .into_iter()
+                    .find(|m| rest.starts_with(&format!("{}(", m)))?;
+                Some((path.to_string(), method.to_string()))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn endpoint_table_matches_the_router() {
+        let routes = router_routes();
+        let documented: BTreeSet<(String, String)> = ENDPOINTS
+            .iter()
+            .map(|e| (e.path.to_string(), e.method.as_str().to_string()))
+            .collect();
+        assert!(routes.contains(&("/ws".to_string(), "get".to_string())));
+        let missing: Vec<_> = routes.difference(&documented).collect();
+        let stale: Vec<_> = documented.difference(&routes).collect();
+        assert!(missing.is_empty(), "not in ENDPOINTS: {:?}", missing);
+        assert!(stale.is_empty(), "no such route: {:?}", stale);
+        assert_eq!(ENDPOINTS.len(), documented.len(), "duplicate entries");
+    }
+}
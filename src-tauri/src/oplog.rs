@@ -0,0 +1,287 @@
+use serde::Serialize;
+use std::cell::RefCell;
+use std::process::{Command, Output};
+use tauri::Emitter;
+
+use crate::state::{APP_HANDLE, OPERATION_LOGS};
+
+/// Keep transcripts for the most recent N operations.
+const MAX_OPERATION_LOGS: usize = 50;
+/// Truncate captured stdout/stderr per command to keep memory bounded.
+const MAX_OUTPUT_BYTES: usize = 16 * 1024;
+
+// ==================== Operation transcript types ====================
+
+#[derive(Debug, Serialize, Clone)]
+pub struct OperationLogEntry {
+    pub command: String,
+    pub cwd: Option<String>,
+    pub exit_code: Option<i32>,
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+    pub at: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct OperationLog {
+    pub id: String,
+    pub operation: String,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub failed_commands: usize,
+    pub entries: Vec<OperationLogEntry>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct OperationLogSummary {
+    pub id: String,
+    pub operation: String,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub command_count: usize,
+    pub failed_commands: usize,
+}
+
+thread_local! {
+    // Operation currently recording on this thread (set by the outermost GitOpGuard)
+    static CURRENT_OPERATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+// ==================== Recording ====================
+
+/// Start recording a new operation on the current thread.
+/// Returns None when an operation is already recording (nested call), so only the
+/// outermost caller owns and finishes the transcript.
+pub(crate) fn start_operation(operation: &str) -> Option<String> {
+    let already_recording = CURRENT_OPERATION.with(|c| c.borrow().is_some());
+    if already_recording {
+        return None;
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    if let Ok(mut logs) = OPERATION_LOGS.lock() {
+        logs.push_back(OperationLog {
+            id: id.clone(),
+            operation: operation.to_string(),
+            started_at: chrono::Utc::now().to_rfc3339(),
+            finished_at: None,
+            failed_commands: 0,
+            entries: vec![],
+        });
+        while logs.len() > MAX_OPERATION_LOGS {
+            logs.pop_front();
+        }
+    }
+    CURRENT_OPERATION.with(|c| *c.borrow_mut() = Some(id.clone()));
+    log::debug!("[oplog] Started operation '{}' ({})", operation, id);
+    Some(id)
+}
+
+/// Handle to the transcript of a running operation. Recording follows the thread, so code
+/// that runs commands on worker threads passes the recorder along and `attach`es it there.
+#[derive(Debug, Clone)]
+pub(crate) struct OperationRecorder {
+    id: String,
+}
+
+impl OperationRecorder {
+    /// The transcript the current thread is recording into, if any.
+    pub(crate) fn current() -> Option<Self> {
+        CURRENT_OPERATION
+            .with(|c| c.borrow().clone())
+            .map(|id| OperationRecorder { id })
+    }
+
+    /// Record the commands of the current (worker) thread into this transcript until the
+    /// returned guard is dropped.
+    pub(crate) fn attach(&self) -> AttachedRecorder {
+        let previous = CURRENT_OPERATION.with(|c| c.borrow_mut().replace(self.id.clone()));
+        AttachedRecorder { previous }
+    }
+}
+
+/// Restores the thread's previous recording when dropped (see `OperationRecorder::attach`).
+pub(crate) struct AttachedRecorder {
+    previous: Option<String>,
+}
+
+impl Drop for AttachedRecorder {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT_OPERATION.with(|c| *c.borrow_mut() = previous);
+    }
+}
+
+/// Run `f` as one recorded operation and return the transcript id along with its result,
+/// so callers can hand it to clients on success and failure alike. Operations started
+/// inside `f` record into this transcript. The id is None when the thread was already
+/// recording (the outer operation owns the transcript).
+pub(crate) fn with_operation<T>(operation: &str, f: impl FnOnce() -> T) -> (T, Option<String>) {
+    let id = start_operation(operation);
+    let result = f();
+    if let Some(id) = &id {
+        finish_operation(id);
+    }
+    (result, id)
+}
+
+/// Mark an operation finished and notify desktop windows.
+pub(crate) fn finish_operation(id: &str) {
+    CURRENT_OPERATION.with(|c| {
+        let mut current = c.borrow_mut();
+        if current.as_deref() == Some(id) {
+            *current = None;
+        }
+    });
+
+    let summary = OPERATION_LOGS.lock().ok().and_then(|mut logs| {
+        logs.iter_mut().find(|l| l.id == id).map(|log| {
+            log.finished_at = Some(chrono::Utc::now().to_rfc3339());
+            summarize(log)
+        })
+    });
+
+    if let Some(summary) = summary {
+        if let Some(app) = APP_HANDLE.lock().ok().and_then(|h| h.clone()) {
+            let _ = app.emit("operation-log-finished", summary);
+        }
+    }
+}
+
+fn truncate_output(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    if text.len() <= MAX_OUTPUT_BYTES {
+        return text.to_string();
+    }
+    let mut cut = MAX_OUTPUT_BYTES;
+    while !text.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    format!("{}\n... (truncated, {} bytes total)", &text[..cut], text.len())
+}
+
+/// Append a command result to the operation recording on this thread (no-op otherwise).
+pub(crate) fn record_command(command: String, cwd: Option<String>, result: Result<&Output, &str>) {
+    let Some(id) = CURRENT_OPERATION.with(|c| c.borrow().clone()) else {
+        return;
+    };
+
+    let entry = match result {
+        Ok(output) => OperationLogEntry {
+            command,
+            cwd,
+            exit_code: output.status.code(),
+            success: output.status.success(),
+            stdout: truncate_output(&output.stdout),
+            stderr: truncate_output(&output.stderr),
+            at: chrono::Utc::now().to_rfc3339(),
+        },
+        Err(e) => OperationLogEntry {
+            command,
+            cwd,
+            exit_code: None,
+            success: false,
+            stdout: String::new(),
+            stderr: e.to_string(),
+            at: chrono::Utc::now().to_rfc3339(),
+        },
+    };
+
+    if let Ok(mut logs) = OPERATION_LOGS.lock() {
+        if let Some(log) = logs.iter_mut().find(|l| l.id == id) {
+            if !entry.success {
+                log.failed_commands += 1;
+            }
+            log.entries.push(entry);
+        }
+    }
+}
+
+/// Render a command line for the transcript.
+pub(crate) fn describe_command(cmd: &Command) -> String {
+    std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|a| a.to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `Command::output()` that also records the result into the current operation transcript.
+pub(crate) trait LoggedCommand {
+    fn logged_output(&mut self) -> std::io::Result<Output>;
+}
+
+impl LoggedCommand for Command {
+    fn logged_output(&mut self) -> std::io::Result<Output> {
+        let command = describe_command(self);
+        let cwd = self
+            .get_current_dir()
+            .map(|d| d.to_string_lossy().to_string());
+        let result = self.output();
+        match &result {
+            Ok(output) => record_command(command, cwd, Ok(output)),
+            Err(e) => record_command(command, cwd, Err(&e.to_string())),
+        }
+        result
+    }
+}
+
+// ==================== Queries ====================
+
+fn summarize(log: &OperationLog) -> OperationLogSummary {
+    OperationLogSummary {
+        id: log.id.clone(),
+        operation: log.operation.clone(),
+        started_at: log.started_at.clone(),
+        finished_at: log.finished_at.clone(),
+        command_count: log.entries.len(),
+        failed_commands: log.failed_commands,
+    }
+}
+
+pub(crate) fn get_operation_log_inner(id: &str) -> Result<OperationLog, String> {
+    let logs = OPERATION_LOGS
+        .lock()
+        .map_err(|_| "Internal state error".to_string())?;
+    logs.iter()
+        .find(|l| l.id == id)
+        .cloned()
+        .ok_or_else(|| format!("Operation log not found: {}", id))
+}
+
+/// Recent operations, newest first.
+pub(crate) fn list_operation_logs_inner() -> Vec<OperationLogSummary> {
+    OPERATION_LOGS
+        .lock()
+        .map(|logs| logs.iter().rev().map(summarize).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn worker_threads_record_into_the_attached_operation() {
+        let ((), id) = with_operation("test_operation", || {
+            let recorder = OperationRecorder::current().expect("recording");
+            std::thread::scope(|scope| {
+                scope.spawn(|| {
+                    let _attached = recorder.attach();
+                    let _ = Command::new("git").arg("--version").logged_output();
+                });
+            });
+            // Nested operations share the outer transcript
+            assert!(start_operation("nested").is_none());
+        });
+        let id = id.expect("operation id");
+        assert!(OperationRecorder::current().is_none());
+
+        let log = get_operation_log_inner(&id).unwrap();
+        assert_eq!(log.operation, "test_operation");
+        assert!(log.finished_at.is_some());
+        assert_eq!(log.entries.len(), 1);
+        assert_eq!(log.entries[0].command, "git --version");
+    }
+}
@@ -0,0 +1,230 @@
+//! Dev-server port allocation: every worktree gets its own block of ports (persisted in
+//! `.worktree-meta.json`) that is injected into its terminals and hooks as environment variables.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::net::TcpListener;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::commands::worktree::{read_worktree_meta, write_worktree_meta};
+use crate::types::{PortConflict, PortRange, WorkspaceConfig, WorktreePorts};
+
+/// Serializes allocations so two worktrees created at once can't pick the same block.
+static ALLOCATION_LOCK: Mutex<()> = Mutex::new(());
+
+/// Ranges already assigned to worktrees (active and archived), keyed by directory name.
+fn assigned_ranges(worktrees_root: &Path) -> Vec<(String, PortRange)> {
+    let Ok(entries) = fs::read_dir(worktrees_root) else {
+        return vec![];
+    };
+    entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| {
+            let ports = read_worktree_meta(&e.path())?
+                .ports
+                .filter(|p| p.count > 0)?;
+            Some((e.file_name().to_string_lossy().to_string(), ports))
+        })
+        .collect()
+}
+
+fn port_in_use(port: u16) -> bool {
+    TcpListener::bind(("127.0.0.1", port)).is_err()
+}
+
+/// The worktree's port range; when it has none yet, assign the first block above `base_port`
+/// that no other worktree owns and nothing is listening on.
+pub(crate) fn ensure_worktree_ports(
+    config: &WorkspaceConfig,
+    workspace_path: &str,
+    worktree_path: &Path,
+) -> Result<PortRange, String> {
+    let _lock = ALLOCATION_LOCK
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    let mut meta = read_worktree_meta(worktree_path).unwrap_or_default();
+    if let Some(ports) = meta.ports.filter(|p| p.count > 0) {
+        return Ok(ports);
+    }
+
+    let settings = &config.port_allocation;
+    let count = settings.ports_per_worktree.max(1);
+    let worktrees_root = Path::new(workspace_path).join(&config.worktrees_dir);
+    let taken: Vec<PortRange> = assigned_ranges(&worktrees_root)
+        .into_iter()
+        .map(|(_, range)| range)
+        .collect();
+
+    let mut start = settings.base_port as u32;
+    let range = loop {
+        if start + count as u32 - 1 > u16::MAX as u32 {
+            return Err(format!(
+                "No free range of {} ports left above {}",
+                count, settings.base_port
+            ));
+        }
+        let candidate = PortRange {
+            start: start as u16,
+            count,
+        };
+        if !taken.iter().any(|r| r.overlaps(&candidate))
+            && !(candidate.start..=candidate.end()).any(port_in_use)
+        {
+            break candidate;
+        }
+        start += count as u32;
+    };
+
+    meta.ports = Some(range);
+    write_worktree_meta(worktree_path, &meta)?;
+    log::info!(
+        "[ports] Assigned ports {}-{} to {}",
+        range.start,
+        range.end(),
+        worktree_path.display()
+    );
+    Ok(range)
+}
+
+/// Each configured project gets the next port of the range, in config order; projects
+/// beyond the range size share the worktree's first port.
+fn project_ports(config: &WorkspaceConfig, range: PortRange) -> BTreeMap<String, u16> {
+    config
+        .projects
+        .iter()
+        .take(range.count as usize)
+        .enumerate()
+        .map(|(i, p)| (p.name.clone(), range.start + i as u16))
+        .collect()
+}
+
+/// `my-api` → `MY_API`
+fn env_suffix(project: &str) -> String {
+    project
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// `PORT` (also `ALLOCATED_PORT`) is the project's own port inside a project directory, the first port of the range
+/// elsewhere; `WM_PORT_<PROJECT>` lets projects find each other (e.g. a frontend proxying its API).
+pub(crate) fn port_env(
+    config: &WorkspaceConfig,
+    range: PortRange,
+    project: Option<&str>,
+) -> Vec<(String, String)> {
+    let ports = project_ports(config, range);
+    let port = project
+        .and_then(|name| ports.get(name).copied())
+        .unwrap_or(range.start);
+    let mut env = vec![
+        ("PORT".to_string(), port.to_string()),
+        ("ALLOCATED_PORT".to_string(), port.to_string()),
+        ("WM_PORT_START".to_string(), range.start.to_string()),
+        ("WM_PORT_END".to_string(), range.end().to_string()),
+    ];
+    for (name, port) in &ports {
+        env.push((format!("WM_PORT_{}", env_suffix(name)), port.to_string()));
+    }
+    env
+}
+
+/// Port variables for a hook, from the range the worktree already has (never allocates).
+pub(crate) fn assigned_port_env(
+    config: &WorkspaceConfig,
+    worktree_path: &Path,
+    project: Option<&str>,
+) -> Vec<(String, String)> {
+    if !config.port_allocation.enabled {
+        return vec![];
+    }
+    read_worktree_meta(worktree_path)
+        .and_then(|meta| meta.ports)
+        .map(|range| port_env(config, range, project))
+        .unwrap_or_default()
+}
+
+/// Port variables for a terminal in `worktree_name` (and `project`), allocating the worktree's
+/// range on first use. Empty for archived worktrees or when allocation is disabled.
+pub(crate) fn terminal_port_env(
+    config: &WorkspaceConfig,
+    workspace_path: &str,
+    worktree_name: &str,
+    project: Option<&str>,
+) -> Vec<(String, String)> {
+    if !config.port_allocation.enabled {
+        return vec![];
+    }
+    let worktree_path = Path::new(workspace_path)
+        .join(&config.worktrees_dir)
+        .join(worktree_name);
+    if worktree_name.ends_with(".archive") || !worktree_path.join("projects").is_dir() {
+        return vec![];
+    }
+    match ensure_worktree_ports(config, workspace_path, &worktree_path) {
+        Ok(range) => port_env(config, range, project),
+        Err(e) => {
+            log::warn!("[ports] {}", e);
+            vec![]
+        }
+    }
+}
+
+/// The worktree's range (allocated if needed) with ports that overlap another worktree's
+/// range or are already bound.
+pub(crate) fn worktree_ports(
+    config: &WorkspaceConfig,
+    workspace_path: &str,
+    worktree_name: &str,
+) -> Result<WorktreePorts, String> {
+    if !config.port_allocation.enabled {
+        return Err("Port allocation is disabled for this workspace".to_string());
+    }
+    let worktrees_root = Path::new(workspace_path).join(&config.worktrees_dir);
+    let range = ensure_worktree_ports(config, workspace_path, &worktrees_root.join(worktree_name))?;
+
+    let overlapping: Vec<(String, PortRange)> = assigned_ranges(&worktrees_root)
+        .into_iter()
+        .filter(|(name, r)| name != worktree_name && r.overlaps(&range))
+        .collect();
+    let conflicts: Vec<PortConflict> = (range.start..=range.end())
+        .filter_map(|port| {
+            let worktree_name = overlapping
+                .iter()
+                .find(|(_, r)| r.contains(port))
+                .map(|(name, _)| name.clone());
+            let in_use = port_in_use(port);
+            (worktree_name.is_some() || in_use).then_some(PortConflict {
+                port,
+                worktree_name,
+                in_use,
+            })
+        })
+        .collect();
+    if !overlapping.is_empty() {
+        log::warn!(
+            "[ports] Range {}-{} of '{}' overlaps {:?}",
+            range.start,
+            range.end(),
+            worktree_name,
+            overlapping.iter().map(|(name, _)| name).collect::<Vec<_>>()
+        );
+    }
+
+    Ok(WorktreePorts {
+        worktree_name: worktree_name.to_string(),
+        start: range.start,
+        end: range.end(),
+        project_ports: project_ports(config, range),
+        env: port_env(config, range, None).into_iter().collect(),
+        conflicts,
+    })
+}
@@ -0,0 +1,393 @@
+//! Running process tracker: finds the processes started inside PTY sessions (dev servers,
+//! tasks, quick commands), groups them by worktree and broadcasts start/stop events over
+//! the terminal-state channel. Also kills every process working inside a worktree,
+//! including ones started from external terminals.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+use sysinfo::{
+    Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, Signal, System, UpdateKind,
+};
+use tauri::Emitter;
+
+use crate::state::{APP_HANDLE, PTY_MANAGER, TERMINAL_STATE_BROADCAST, WINDOW_WORKSPACES};
+use crate::types::{KilledProcess, RunningProcess};
+
+/// How often the background tracker re-scans the process table.
+const PROCESS_POLL_INTERVAL_SECS: u64 = 5;
+
+/// One row of the OS process table.
+struct ProcInfo {
+    pid: u32,
+    ppid: u32,
+    cpu_percent: Option<f32>,
+    memory_kb: Option<u64>,
+    uptime_secs: Option<u64>,
+    command: String,
+}
+
+#[cfg(not(windows))]
+fn process_table() -> Vec<ProcInfo> {
+    let output = match Command::new("ps")
+        .args(["-A", "-o", "pid=,ppid=,pcpu=,rss=,etime=,args="])
+        .output()
+    {
+        Ok(o) if o.status.success() => o,
+        Ok(o) => {
+            log::warn!(
+                "[process] ps failed: {}",
+                String::from_utf8_lossy(&o.stderr).trim()
+            );
+            return vec![];
+        }
+        Err(e) => {
+            log::warn!("[process] Failed to run ps: {}", e);
+            return vec![];
+        }
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_ps_line)
+        .collect()
+}
+
+#[cfg(not(windows))]
+fn parse_ps_line(line: &str) -> Option<ProcInfo> {
+    let mut fields = line.split_whitespace();
+    let pid = fields.next()?.parse().ok()?;
+    let ppid = fields.next()?.parse().ok()?;
+    let cpu_percent = fields.next()?.parse().ok();
+    let memory_kb = fields.next()?.parse().ok();
+    let uptime_secs = parse_etime(fields.next()?);
+    let command = fields.collect::<Vec<_>>().join(" ");
+    Some(ProcInfo {
+        pid,
+        ppid,
+        cpu_percent,
+        memory_kb,
+        uptime_secs,
+        command,
+    })
+}
+
+/// `ps` elapsed time: `[[dd-]hh:]mm:ss`
+#[cfg(not(windows))]
+fn parse_etime(etime: &str) -> Option<u64> {
+    let (days, rest) = match etime.split_once('-') {
+        Some((d, rest)) => (d.parse::<u64>().ok()?, rest),
+        None => (0, etime),
+    };
+    let mut secs = 0u64;
+    for part in rest.split(':') {
+        secs = secs * 60 + part.parse::<u64>().ok()?;
+    }
+    Some(days * 86_400 + secs)
+}
+
+/// Windows has no `ps`; read Win32_Process through PowerShell (CPU usage isn't sampled).
+#[cfg(windows)]
+fn process_table() -> Vec<ProcInfo> {
+    const SCRIPT: &str = r#"$now = Get-Date; Get-CimInstance Win32_Process | ForEach-Object { $up = if ($_.CreationDate) { [int]($now - $_.CreationDate).TotalSeconds } else { '' }; $cmd = if ($_.CommandLine) { $_.CommandLine } else { $_.Name }; "$($_.ProcessId)`t$($_.ParentProcessId)`t$($_.WorkingSetSize)`t$up`t$cmd" }"#;
+    let output = match Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", SCRIPT])
+        .output()
+    {
+        Ok(o) if o.status.success() => o,
+        _ => {
+            log::warn!("[process] Failed to list processes via PowerShell");
+            return vec![];
+        }
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(5, '\t');
+            Some(ProcInfo {
+                pid: fields.next()?.trim().parse().ok()?,
+                ppid: fields.next()?.trim().parse().ok()?,
+                cpu_percent: None,
+                memory_kb: fields.next()?.trim().parse::<u64>().ok().map(|b| b / 1024),
+                uptime_secs: fields.next()?.trim().parse().ok(),
+                command: fields.next()?.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Worktree a session belongs to, from its working directory.
+fn session_worktree(worktrees_root: &Path, cwd: &str) -> Option<String> {
+    Path::new(cwd)
+        .strip_prefix(worktrees_root)
+        .ok()?
+        .components()
+        .next()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+}
+
+/// Descendants of each session shell inside the workspace's worktrees.
+fn collect_processes(
+    shells: &[(String, String, u32)],
+    table: &[ProcInfo],
+    workspace_path: &str,
+    worktrees_dir: &str,
+    worktree: Option<&str>,
+) -> Vec<RunningProcess> {
+    let worktrees_root = Path::new(workspace_path).join(worktrees_dir);
+    let mut children: HashMap<u32, Vec<&ProcInfo>> = HashMap::new();
+    for proc in table {
+        children.entry(proc.ppid).or_default().push(proc);
+    }
+
+    let mut processes = vec![];
+    for (session_id, cwd, shell_pid) in shells {
+        let Some(worktree_name) = session_worktree(&worktrees_root, cwd) else {
+            continue;
+        };
+        if worktree.is_some_and(|w| w != worktree_name) {
+            continue;
+        }
+        let mut stack = vec![*shell_pid];
+        let mut visited = HashSet::new();
+        while let Some(pid) = stack.pop() {
+            if !visited.insert(pid) {
+                continue;
+            }
+            for proc in children.get(&pid).into_iter().flatten() {
+                stack.push(proc.pid);
+                processes.push(RunningProcess {
+                    pid: proc.pid,
+                    worktree_name: worktree_name.clone(),
+                    session_id: session_id.clone(),
+                    command: proc.command.clone(),
+                    cpu_percent: proc.cpu_percent,
+                    memory_kb: proc.memory_kb,
+                    uptime_secs: proc.uptime_secs,
+                });
+            }
+        }
+    }
+    processes.sort_by(|a, b| {
+        a.worktree_name
+            .cmp(&b.worktree_name)
+            .then(a.pid.cmp(&b.pid))
+    });
+    processes
+}
+
+fn session_shells() -> Vec<(String, String, u32)> {
+    PTY_MANAGER
+        .lock()
+        .map(|m| m.session_shells())
+        .unwrap_or_default()
+}
+
+/// Processes running in the workspace's terminals, optionally limited to one worktree.
+pub(crate) fn running_processes(
+    workspace_path: &str,
+    worktrees_dir: &str,
+    worktree: Option<&str>,
+) -> Vec<RunningProcess> {
+    let shells = session_shells();
+    if shells.is_empty() {
+        return vec![];
+    }
+    collect_processes(
+        &shells,
+        &process_table(),
+        workspace_path,
+        worktrees_dir,
+        worktree,
+    )
+}
+
+fn broadcast_process_change(
+    workspace_path: &str,
+    worktree_name: &str,
+    started: &[&RunningProcess],
+    stopped: &[u32],
+    processes: &[RunningProcess],
+) {
+    for p in started {
+        log::info!(
+            "[process] Started in '{}': pid={} {}",
+            worktree_name,
+            p.pid,
+            p.command
+        );
+    }
+    if !stopped.is_empty() {
+        log::info!("[process] Stopped in '{}': {:?}", worktree_name, stopped);
+    }
+    let payload = json!({
+        "type": "running_processes",
+        "workspacePath": workspace_path,
+        "worktreeName": worktree_name,
+        "started": started,
+        "stopped": stopped,
+        "processes": processes,
+    });
+    let _ = TERMINAL_STATE_BROADCAST.send(payload.to_string());
+    if let Some(app) = APP_HANDLE.lock().ok().and_then(|h| h.as_ref().cloned()) {
+        let _ = app.emit("running-processes-update", payload);
+    }
+}
+
+/// Spawn the background thread that re-scans terminal processes and broadcasts start/stop per worktree.
+pub(crate) fn start_process_tracker() {
+    std::thread::spawn(|| {
+        let mut last_seen: HashMap<(String, String), HashSet<u32>> = HashMap::new();
+        loop {
+            std::thread::sleep(std::time::Duration::from_secs(PROCESS_POLL_INTERVAL_SECS));
+            let shells = session_shells();
+            if shells.is_empty() && last_seen.is_empty() {
+                continue;
+            }
+            let table = if shells.is_empty() {
+                vec![]
+            } else {
+                process_table()
+            };
+
+            let workspaces: HashSet<String> = WINDOW_WORKSPACES
+                .lock()
+                .unwrap()
+                .values()
+                .cloned()
+                .collect();
+            let mut current: HashMap<(String, String), Vec<RunningProcess>> = HashMap::new();
+            for workspace_path in workspaces {
+                let config = crate::config::load_workspace_config(&workspace_path);
+                for proc in collect_processes(
+                    &shells,
+                    &table,
+                    &workspace_path,
+                    &config.worktrees_dir,
+                    None,
+                ) {
+                    current
+                        .entry((workspace_path.clone(), proc.worktree_name.clone()))
+                        .or_default()
+                        .push(proc);
+                }
+            }
+
+            let keys: HashSet<(String, String)> =
+                last_seen.keys().chain(current.keys()).cloned().collect();
+            for key in keys {
+                let processes = current.remove(&key).unwrap_or_default();
+                let now: HashSet<u32> = processes.iter().map(|p| p.pid).collect();
+                let before = last_seen.remove(&key).unwrap_or_default();
+                if now != before {
+                    let started: Vec<&RunningProcess> = processes
+                        .iter()
+                        .filter(|p| !before.contains(&p.pid))
+                        .collect();
+                    let stopped: Vec<u32> = before.difference(&now).copied().collect();
+                    broadcast_process_change(&key.0, &key.1, &started, &stopped, &processes);
+                }
+                if !now.is_empty() {
+                    last_seen.insert(key, now);
+                }
+            }
+        }
+    });
+}
+
+/// How long processes get to exit after SIGTERM before they are killed.
+const KILL_GRACE_PERIOD: Duration = Duration::from_secs(3);
+
+fn is_gone(sys: &System, pid: Pid) -> bool {
+    sys.process(pid)
+        .is_none_or(|p| matches!(p.status(), ProcessStatus::Zombie | ProcessStatus::Dead))
+}
+
+/// Terminate every process whose working directory is inside `root`: SIGTERM first, then
+/// SIGKILL for whatever is still running after the grace period. This app is never a target.
+pub(crate) fn kill_processes_under(root: &Path) -> Vec<KilledProcess> {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing()
+            .with_cwd(UpdateKind::Always)
+            .with_cmd(UpdateKind::Always),
+    );
+
+    let own_pid = std::process::id();
+    let mut report: Vec<KilledProcess> = vec![];
+    let mut pids: Vec<Pid> = vec![];
+    for (pid, proc) in sys.processes() {
+        let Some(cwd) = proc.cwd() else {
+            continue;
+        };
+        if pid.as_u32() == own_pid || !cwd.starts_with(&root) {
+            continue;
+        }
+        let command = proc
+            .cmd()
+            .iter()
+            .map(|a| a.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ");
+        // Windows has no SIGTERM; fall back to terminating right away
+        if proc.kill_with(Signal::Term).is_none() {
+            proc.kill();
+        }
+        pids.push(*pid);
+        report.push(KilledProcess {
+            pid: pid.as_u32(),
+            name: proc.name().to_string_lossy().to_string(),
+            command,
+            cwd: cwd.to_string_lossy().to_string(),
+            forced: false,
+            killed: false,
+        });
+    }
+    if pids.is_empty() {
+        return report;
+    }
+    log::info!(
+        "[process] Sent SIGTERM to {} processes under {}",
+        pids.len(),
+        root.display()
+    );
+
+    let deadline = Instant::now() + KILL_GRACE_PERIOD;
+    loop {
+        std::thread::sleep(Duration::from_millis(200));
+        sys.refresh_processes(ProcessesToUpdate::Some(&pids), true);
+        if pids.iter().all(|pid| is_gone(&sys, *pid)) || Instant::now() >= deadline {
+            break;
+        }
+    }
+
+    for entry in &mut report {
+        let pid = Pid::from_u32(entry.pid);
+        if is_gone(&sys, pid) {
+            entry.killed = true;
+            continue;
+        }
+        entry.forced = true;
+        entry.killed = sys.process(pid).is_some_and(|p| p.kill());
+        if entry.killed {
+            log::warn!(
+                "[process] Force-killed pid={} ({}) after grace period",
+                entry.pid,
+                entry.name
+            );
+        } else {
+            log::warn!(
+                "[process] Failed to kill pid={} ({})",
+                entry.pid,
+                entry.name
+            );
+        }
+    }
+    report.sort_by_key(|p| p.pid);
+    report
+}
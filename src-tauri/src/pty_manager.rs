@@ -1,13 +1,22 @@
 use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
 use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::{channel, Receiver};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::broadcast;
 
+use crate::types::{OutputMatch, OutputSearchResult, ShellConfig, SshHost};
+
 /// Max replay buffer size per session (64 KB)
 const REPLAY_BUFFER_CAP: usize = 64 * 1024;
 
+/// Max persisted scrollback per session (1 MB); compacted to the newest half when exceeded
+const SCROLLBACK_FILE_CAP: u64 = 1024 * 1024;
+
 /// Get the default shell for the current platform.
 /// Windows: COMSPEC -> PowerShell -> cmd.exe
 /// Unix: SHELL -> /bin/zsh -> /bin/bash
@@ -41,6 +50,83 @@ fn get_default_shell() -> String {
     }
 }
 
+/// Full path of `program`: checked as-is when it is a path, otherwise looked up in PATH.
+pub(crate) fn find_program(program: &str) -> Option<PathBuf> {
+    let path = Path::new(program);
+    if path.is_absolute() || path.components().count() > 1 {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+    let extensions: &[&str] = if cfg!(target_os = "windows") {
+        &["", ".exe", ".cmd", ".bat"]
+    } else {
+        &[""]
+    };
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .flat_map(|dir| {
+            extensions
+                .iter()
+                .map(move |ext| dir.join(format!("{}{}", program, ext)))
+        })
+        .find(|candidate| candidate.is_file())
+}
+
+/// Shell program and arguments for a session: the configured shell when it can be found,
+/// otherwise the platform default.
+fn resolve_shell(shell: Option<&ShellConfig>) -> (String, Vec<String>) {
+    if let Some(shell) = shell.filter(|s| !s.program.trim().is_empty()) {
+        match find_program(shell.program.trim()) {
+            Some(path) => return (path.to_string_lossy().to_string(), shell.args.clone()),
+            None => log::warn!(
+                "Configured shell '{}' not found, falling back to the default shell",
+                shell.program
+            ),
+        }
+    }
+    (get_default_shell(), vec![])
+}
+
+/// `user@host`, or just the host when no user is configured (ssh then uses its own config).
+fn ssh_target(host: &SshHost) -> String {
+    match host.user.as_deref().filter(|u| !u.is_empty()) {
+        Some(user) => format!("{}@{}", user, host.host),
+        None => host.host.clone(),
+    }
+}
+
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// `ssh` arguments for an interactive session on `host`, starting in `cwd` when given.
+fn ssh_args(host: &SshHost, cwd: Option<&str>) -> Vec<String> {
+    let mut args = vec!["-o".to_string(), "ServerAliveInterval=30".to_string()];
+    if let Some(port) = host.port {
+        args.push("-p".to_string());
+        args.push(port.to_string());
+    }
+    if let Some(identity) = host.identity_file.as_deref().filter(|i| !i.is_empty()) {
+        args.push("-i".to_string());
+        args.push(identity.to_string());
+    }
+    args.push(ssh_target(host));
+    if let Some(cwd) = cwd.filter(|c| !c.is_empty()) {
+        // A remote command gets no TTY unless forced
+        args.insert(0, "-t".to_string());
+        let dir = match cwd.strip_prefix("~/") {
+            Some(rest) => format!("~/{}", shell_quote(rest)),
+            None => shell_quote(cwd),
+        };
+        args.push(format!("cd {} && exec \"$SHELL\" -l", dir));
+    }
+    args
+}
+
+fn home_dir() -> String {
+    std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .unwrap_or_else(|_| std::env::temp_dir().to_string_lossy().to_string())
+}
+
 /// Split raw bytes into valid UTF-8 text + incomplete trailing bytes.
 ///
 /// Invalid bytes in the middle are replaced with U+FFFD (same as `from_utf8_lossy`).
@@ -86,6 +172,213 @@ pub(crate) fn bytes_to_utf8_with_pending(data: &[u8]) -> (String, Vec<u8>) {
     }
 }
 
+/// Append-only log of a session's output on disk, so history survives app restarts.
+struct ScrollbackFile {
+    path: PathBuf,
+    file: File,
+    len: u64,
+}
+
+impl ScrollbackFile {
+    fn open(path: PathBuf) -> std::io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let len = file.metadata()?.len();
+        Ok(Self { path, file, len })
+    }
+
+    fn append(&mut self, data: &[u8]) -> std::io::Result<()> {
+        self.file.write_all(data)?;
+        self.len += data.len() as u64;
+        if self.len > SCROLLBACK_FILE_CAP {
+            self.compact()?;
+        }
+        Ok(())
+    }
+
+    /// Keep the newest half of the file, starting at a line boundary.
+    fn compact(&mut self) -> std::io::Result<()> {
+        let data = fs::read(&self.path)?;
+        let mut start = data
+            .len()
+            .saturating_sub((SCROLLBACK_FILE_CAP / 2) as usize);
+        if let Some(newline) = data[start..].iter().position(|&b| b == b'\n') {
+            start += newline + 1;
+        }
+        fs::write(&self.path, &data[start..])?;
+        self.file = OpenOptions::new().append(true).open(&self.path)?;
+        self.len = (data.len() - start) as u64;
+        Ok(())
+    }
+}
+
+/// asciicast v2 recording of a session's output (https://docs.asciinema.org/manual/asciicast/v2/).
+struct CastRecorder {
+    path: PathBuf,
+    file: File,
+    started: Instant,
+    /// Incomplete UTF-8 sequence at the end of the last chunk; events must be valid strings.
+    utf8_pending: Vec<u8>,
+}
+
+impl CastRecorder {
+    fn start(
+        path: PathBuf,
+        shell: &str,
+        cols: u16,
+        rows: u16,
+        title: Option<&str>,
+    ) -> std::io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        let mut header = serde_json::json!({
+            "version": 2,
+            "width": cols,
+            "height": rows,
+            "timestamp": chrono::Utc::now().timestamp(),
+            "env": { "SHELL": shell, "TERM": "xterm-256color" },
+        });
+        if let Some(title) = title {
+            header["title"] = title.into();
+        }
+        writeln!(file, "{}", header)?;
+        Ok(Self {
+            path,
+            file,
+            started: Instant::now(),
+            utf8_pending: vec![],
+        })
+    }
+
+    fn event(&mut self, code: &str, data: &str) -> std::io::Result<()> {
+        let time = self.started.elapsed().as_secs_f64();
+        writeln!(self.file, "{}", serde_json::json!([time, code, data]))
+    }
+
+    fn output(&mut self, data: &[u8]) -> std::io::Result<()> {
+        let mut bytes = std::mem::take(&mut self.utf8_pending);
+        bytes.extend_from_slice(data);
+        let (text, pending) = bytes_to_utf8_with_pending(&bytes);
+        self.utf8_pending = pending;
+        if text.is_empty() {
+            return Ok(());
+        }
+        self.event("o", &text)
+    }
+}
+
+/// Recent output of a session: the in-memory replay buffer plus the optional scrollback file
+/// and recording.
+struct OutputHistory {
+    buffer: VecDeque<u8>,
+    file: Option<ScrollbackFile>,
+    recording: Option<CastRecorder>,
+    /// When the shell last produced output (for the idle timeout).
+    last_output: Instant,
+}
+
+impl OutputHistory {
+    fn append(&mut self, data: &[u8]) {
+        self.last_output = Instant::now();
+        self.buffer.extend(data);
+        // Trim from front if over capacity
+        if self.buffer.len() > REPLAY_BUFFER_CAP {
+            let excess = self.buffer.len() - REPLAY_BUFFER_CAP;
+            self.buffer.drain(..excess);
+        }
+        if let Some(file) = &mut self.file {
+            if let Err(e) = file.append(data) {
+                log::warn!("Failed to write scrollback {}: {}", file.path.display(), e);
+                self.file = None;
+            }
+        }
+        if let Some(recording) = &mut self.recording {
+            if let Err(e) = recording.output(data) {
+                log::warn!(
+                    "Failed to write recording {}: {}",
+                    recording.path.display(),
+                    e
+                );
+                self.recording = None;
+            }
+        }
+    }
+}
+
+/// Scrollback file of a session; named by hash since session ids embed full paths.
+fn scrollback_path(dir: &Path, id: &str) -> PathBuf {
+    let digest = ring::digest::digest(&ring::digest::SHA256, id.as_bytes());
+    dir.join(format!("{}.log", hex::encode(&digest.as_ref()[..16])))
+}
+
+fn open_scrollback(dir: &Path, id: &str) -> Option<ScrollbackFile> {
+    let path = scrollback_path(dir, id);
+    match ScrollbackFile::open(path.clone()) {
+        Ok(file) => Some(file),
+        Err(e) => {
+            log::warn!("Failed to open scrollback {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// A line of terminal output as displayed: escape sequences (colors, cursor movement, titles)
+/// removed, and only the text after the last carriage return (progress bars redraw the line).
+fn displayed_text(line: &str) -> String {
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    let line = line.rsplit('\r').next().unwrap_or(line);
+    let mut text = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                // CSI: parameters up to a final byte in @..~
+                Some('[') => {
+                    for next in chars.by_ref() {
+                        if ('@'..='~').contains(&next) {
+                            break;
+                        }
+                    }
+                }
+                // OSC: terminated by BEL or ESC \
+                Some(']') => {
+                    while let Some(next) = chars.next() {
+                        if next == '\x07' || (next == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            c if c.is_control() && c != '\t' => {}
+            c => text.push(c),
+        }
+    }
+    text
+}
+
+/// The last `lines` lines of `data`.
+fn tail_lines(data: &[u8], lines: usize) -> &[u8] {
+    let mut seen = 0;
+    for (i, &b) in data.iter().enumerate().rev() {
+        // A trailing newline doesn't start another line
+        if b == b'\n' && i + 1 < data.len() {
+            seen += 1;
+            if seen == lines {
+                return &data[i + 1..];
+            }
+        }
+    }
+    data
+}
+
 struct PtyReader {
     receiver: Receiver<Vec<u8>>,
     /// Leftover bytes from the previous `read_from_session` call that formed
@@ -99,8 +392,16 @@ pub struct PtySession {
     reader: PtyReader,
     child: Box<dyn Child + Send + Sync>,
     broadcast_tx: broadcast::Sender<Vec<u8>>,
-    /// Ring buffer of recent PTY output for replaying to new subscribers.
-    replay_buffer: Arc<Mutex<VecDeque<u8>>>,
+    /// Recent PTY output for replaying to new subscribers, optionally persisted to disk.
+    history: Arc<Mutex<OutputHistory>>,
+    /// Working directory the shell was started in.
+    cwd: String,
+    /// Shell program the session runs.
+    shell: String,
+    /// When input was last written to the session (for the idle timeout).
+    last_input: Instant,
+    /// `user@host` of SSH sessions; `cwd` is then the remote directory.
+    remote: Option<String>,
 }
 
 impl PtySession {
@@ -108,6 +409,16 @@ impl PtySession {
         let _ = self.child.kill();
         let _ = self.child.wait();
     }
+
+    /// Time since the last input or output.
+    fn idle_for(&self) -> Duration {
+        let last_output = self
+            .history
+            .lock()
+            .map(|h| h.last_output)
+            .unwrap_or(self.last_input);
+        self.last_input.max(last_output).elapsed()
+    }
 }
 
 impl Drop for PtySession {
@@ -116,14 +427,129 @@ impl Drop for PtySession {
     }
 }
 
+/// An open session as saved in the registry, enough to recreate it after a restart.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    pub id: String,
+    pub cwd: String,
+    pub cols: u16,
+    pub rows: u16,
+}
+
 pub struct PtyManager {
     sessions: HashMap<String, Arc<Mutex<PtySession>>>,
+    /// Where session output is persisted; `None` keeps it in memory only.
+    scrollback_dir: Option<PathBuf>,
+    /// File listing the open sessions so the next run can restore them.
+    registry_path: Option<PathBuf>,
+    /// Sessions of the previous run not restored yet; they stay in the registry until then.
+    restorable: Vec<SessionSnapshot>,
+    /// Max concurrent sessions; 0 = unlimited.
+    max_sessions: usize,
+    /// Sessions without input or output for this long are closed by `close_idle_sessions`.
+    idle_timeout: Option<Duration>,
 }
 
 impl PtyManager {
     pub fn new() -> Self {
         Self {
             sessions: HashMap::new(),
+            scrollback_dir: None,
+            registry_path: None,
+            restorable: Vec::new(),
+            max_sessions: 0,
+            idle_timeout: None,
+        }
+    }
+
+    /// Limit concurrent sessions (0 = unlimited) and close sessions idle for `idle_timeout`.
+    /// Running sessions above a lowered limit are left alone; only new ones are refused.
+    pub fn set_limits(&mut self, max_sessions: usize, idle_timeout: Option<Duration>) {
+        self.max_sessions = max_sessions;
+        self.idle_timeout = idle_timeout;
+    }
+
+    /// Keep the list of open sessions in `path`, picking up the sessions the previous run
+    /// left there as restorable.
+    pub fn set_session_registry(&mut self, path: PathBuf) {
+        self.restorable = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        self.registry_path = Some(path);
+    }
+
+    fn save_registry(&self) {
+        let Some(path) = &self.registry_path else {
+            return;
+        };
+        let mut snapshots: Vec<SessionSnapshot> = self
+            .sessions
+            .iter()
+            .filter_map(|(id, session)| {
+                let session = session.lock().ok()?;
+                // SSH sessions are not restored; reconnecting is left to the user
+                if session.remote.is_some() {
+                    return None;
+                }
+                let size = session.master.get_size().ok()?;
+                Some(SessionSnapshot {
+                    id: id.clone(),
+                    cwd: session.cwd.clone(),
+                    cols: size.cols,
+                    rows: size.rows,
+                })
+            })
+            .collect();
+        snapshots.extend(
+            self.restorable
+                .iter()
+                .filter(|s| !self.sessions.contains_key(&s.id))
+                .cloned(),
+        );
+        let result = serde_json::to_string_pretty(&snapshots)
+            .map_err(|e| e.to_string())
+            .and_then(|json| fs::write(path, json).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            log::warn!("Failed to save PTY session registry: {}", e);
+        }
+    }
+
+    /// Remove and return the previous run's sessions matching `filter`, for recreating them.
+    pub fn take_restorable(
+        &mut self,
+        filter: impl Fn(&SessionSnapshot) -> bool,
+    ) -> Vec<SessionSnapshot> {
+        let (taken, kept) = std::mem::take(&mut self.restorable)
+            .into_iter()
+            .partition(|s| filter(s));
+        self.restorable = kept;
+        taken
+    }
+
+    /// Turn scrollback persistence on (`Some(dir)`) or off; applies to running sessions too.
+    /// Turning it off deletes the persisted files.
+    pub fn set_scrollback_dir(&mut self, dir: Option<PathBuf>) {
+        for (id, session) in &self.sessions {
+            let Ok(session) = session.lock() else {
+                continue;
+            };
+            let Ok(mut history) = session.history.lock() else {
+                continue;
+            };
+            history.file = dir.as_deref().and_then(|d| open_scrollback(d, id));
+        }
+        if dir.is_none() {
+            if let Some(old) = &self.scrollback_dir {
+                let _ = fs::remove_dir_all(old);
+            }
+        }
+        self.scrollback_dir = dir;
+    }
+
+    fn remove_scrollback(&self, id: &str) {
+        if let Some(dir) = &self.scrollback_dir {
+            let _ = fs::remove_file(scrollback_path(dir, id));
         }
     }
 
@@ -133,10 +559,24 @@ impl PtyManager {
         cwd: &str,
         cols: u16,
         rows: u16,
+        env: &[(String, String)],
+        shell: Option<&ShellConfig>,
     ) -> Result<(), String> {
-        // Properly close existing session if any
-        if self.has_session(id) {
-            self.close_session(id)?;
+        if self.max_sessions > 0
+            && !self.sessions.contains_key(id)
+            && self.sessions.len() >= self.max_sessions
+        {
+            return Err(format!(
+                "Terminal limit reached: {} sessions are open. Close an unused terminal first.",
+                self.max_sessions
+            ));
+        }
+
+        // Properly close existing session if any (its scrollback is kept and continued)
+        if let Some(session) = self.sessions.remove(id) {
+            if let Ok(mut session) = session.lock() {
+                session.kill_child();
+            }
         }
 
         let pty_system = native_pty_system();
@@ -150,11 +590,17 @@ impl PtyManager {
             })
             .map_err(|e| format!("Failed to open PTY: {}", e))?;
 
-        // Get the user's shell
-        let shell = get_default_shell();
-        log::info!("PTY session '{}' using shell: {}", id, shell);
+        // Configured shell, or the user's default one
+        let (shell, shell_args) = resolve_shell(shell);
+        log::info!(
+            "PTY session '{}' using shell: {} {:?}",
+            id,
+            shell,
+            shell_args
+        );
 
         let mut cmd = CommandBuilder::new(&shell);
+        cmd.args(&shell_args);
         cmd.cwd(cwd);
 
         // Set environment variables for better terminal support
@@ -199,6 +645,11 @@ impl PtyManager {
             }
         }
 
+        // Session-specific variables (e.g. the worktree's dev-server ports)
+        for (key, value) in env {
+            cmd.env(key, value);
+        }
+
         let child = pair
             .slave
             .spawn_command(cmd)
@@ -224,10 +675,17 @@ impl PtyManager {
         let (broadcast_tx, _) = broadcast::channel::<Vec<u8>>(256);
         let broadcast_tx_clone = broadcast_tx.clone();
 
-        // Replay buffer shared with reader thread
-        let replay_buffer: Arc<Mutex<VecDeque<u8>>> =
-            Arc::new(Mutex::new(VecDeque::with_capacity(REPLAY_BUFFER_CAP)));
-        let replay_buf_clone = replay_buffer.clone();
+        // Output history shared with reader thread
+        let history = Arc::new(Mutex::new(OutputHistory {
+            buffer: VecDeque::with_capacity(REPLAY_BUFFER_CAP),
+            file: self
+                .scrollback_dir
+                .as_deref()
+                .and_then(|dir| open_scrollback(dir, id)),
+            recording: None,
+            last_output: Instant::now(),
+        }));
+        let history_clone = history.clone();
 
         // Spawn a thread to read from PTY
         std::thread::spawn(move || {
@@ -239,14 +697,9 @@ impl PtyManager {
                         let data = buf[..n].to_vec();
                         // Send to broadcast (for WS subscribers); ignore errors (no receivers)
                         let _ = broadcast_tx_clone.send(data.clone());
-                        // Append to replay buffer
-                        if let Ok(mut rb) = replay_buf_clone.lock() {
-                            rb.extend(&data);
-                            // Trim from front if over capacity
-                            if rb.len() > REPLAY_BUFFER_CAP {
-                                let excess = rb.len() - REPLAY_BUFFER_CAP;
-                                rb.drain(..excess);
-                            }
+                        // Append to replay buffer (and scrollback file)
+                        if let Ok(mut history) = history_clone.lock() {
+                            history.append(&data);
                         }
                         // Send to mpsc (for desktop pty_read polling)
                         if tx.send(data).is_err() {
@@ -267,11 +720,42 @@ impl PtyManager {
             },
             child,
             broadcast_tx,
-            replay_buffer,
+            history,
+            cwd: cwd.to_string(),
+            shell,
+            last_input: Instant::now(),
+            remote: None,
         };
 
         self.sessions
             .insert(id.to_string(), Arc::new(Mutex::new(session)));
+        self.save_registry();
+        Ok(())
+    }
+
+    /// Open a session running `ssh` to `host`, in `cwd` on the remote side when given.
+    /// Authentication comes from the user's ssh config, agent and keys.
+    pub fn create_ssh_session(
+        &mut self,
+        id: &str,
+        host: &SshHost,
+        cwd: Option<&str>,
+        cols: u16,
+        rows: u16,
+    ) -> Result<(), String> {
+        let ssh = find_program("ssh").ok_or_else(|| "ssh client not found in PATH".to_string())?;
+        let shell = ShellConfig {
+            program: ssh.to_string_lossy().to_string(),
+            args: ssh_args(host, cwd),
+        };
+        self.create_session(id, &home_dir(), cols, rows, &[], Some(&shell))?;
+        if let Some(session) = self.sessions.get(id) {
+            if let Ok(mut session) = session.lock() {
+                session.remote = Some(ssh_target(host));
+                session.cwd = cwd.unwrap_or("~").to_string();
+            }
+        }
+        self.save_registry();
         Ok(())
     }
 
@@ -290,6 +774,7 @@ impl PtyManager {
             .writer
             .flush()
             .map_err(|e| format!("Flush error: {}", e))?;
+        session.last_input = Instant::now();
         Ok(())
     }
 
@@ -328,32 +813,222 @@ impl PtyManager {
                 pixel_height: 0,
             })
             .map_err(|e| format!("Resize error: {}", e))?;
+        if let Ok(mut history) = session.history.lock() {
+            if let Some(recording) = &mut history.recording {
+                let _ = recording.event("r", &format!("{}x{}", cols, rows));
+            }
+        }
+        drop(session);
+        self.save_registry();
+        Ok(())
+    }
+
+    /// Start recording a session's output to an asciicast file at `path` (must not exist yet).
+    pub fn start_recording(
+        &self,
+        id: &str,
+        path: &Path,
+        title: Option<&str>,
+    ) -> Result<(), String> {
+        let session = self
+            .sessions
+            .get(id)
+            .ok_or_else(|| "Session not found".to_string())?;
+        let session = session.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let size = session
+            .master
+            .get_size()
+            .map_err(|e| format!("Failed to get terminal size: {}", e))?;
+        let mut history = session
+            .history
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        if let Some(recording) = &history.recording {
+            return Err(format!(
+                "Session is already being recorded to {}",
+                recording.path.display()
+            ));
+        }
+        let recorder = CastRecorder::start(
+            path.to_path_buf(),
+            &session.shell,
+            size.cols,
+            size.rows,
+            title,
+        )
+        .map_err(|e| format!("Failed to create recording {}: {}", path.display(), e))?;
+        history.recording = Some(recorder);
         Ok(())
     }
 
+    /// Stop recording a session; returns the recording's path.
+    pub fn stop_recording(&self, id: &str) -> Result<PathBuf, String> {
+        let session = self
+            .sessions
+            .get(id)
+            .ok_or_else(|| "Session not found".to_string())?;
+        let session = session.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let mut history = session
+            .history
+            .lock()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        let mut recording = history
+            .recording
+            .take()
+            .ok_or_else(|| "Session is not being recorded".to_string())?;
+        recording
+            .file
+            .flush()
+            .map_err(|e| format!("Failed to write recording: {}", e))?;
+        Ok(recording.path)
+    }
+
+    /// (session id, recording path) of every session being recorded.
+    pub fn active_recordings(&self) -> Vec<(String, PathBuf)> {
+        self.sessions
+            .iter()
+            .filter_map(|(id, session)| {
+                let session = session.lock().ok()?;
+                let history = session.history.lock().ok()?;
+                let path = history.recording.as_ref()?.path.clone();
+                Some((id.clone(), path))
+            })
+            .collect()
+    }
+
+    /// Close a session for good, discarding its persisted scrollback.
     pub fn close_session(&mut self, id: &str) -> Result<(), String> {
         if let Some(session) = self.sessions.remove(id) {
             if let Ok(mut session) = session.lock() {
                 session.kill_child();
             }
         }
+        self.remove_scrollback(id);
+        self.save_registry();
         Ok(())
     }
 
+    /// All output retained for a session. Prefers the persisted file (which also covers
+    /// previous app runs) over the replay buffer, and works for sessions that no longer exist.
+    /// With `drain_unread`, output queued for `pty_read` is dropped since it is part of the result.
+    fn retained_output(&self, id: &str, drain_unread: bool) -> Result<Vec<u8>, String> {
+        let persisted = |dir: &Path| fs::read(scrollback_path(dir, id)).ok();
+        let data = match self.sessions.get(id) {
+            Some(session) => {
+                let mut session = session.lock().map_err(|e| format!("Lock error: {}", e))?;
+                let history = session.history.clone();
+                let history = history.lock().map_err(|e| format!("Lock error: {}", e))?;
+                let data = match &history.file {
+                    Some(file) => fs::read(&file.path).ok(),
+                    None => None,
+                }
+                .unwrap_or_else(|| history.buffer.iter().copied().collect());
+                if drain_unread {
+                    while session.reader.receiver.try_recv().is_ok() {}
+                    session.reader.utf8_pending.clear();
+                }
+                data
+            }
+            None => self
+                .scrollback_dir
+                .as_deref()
+                .and_then(persisted)
+                .unwrap_or_default(),
+        };
+        Ok(data)
+    }
+
+    /// The last `lines` lines of a session's output, for repopulating a reloaded terminal.
+    /// Unread output queued for `pty_read` is dropped since it is part of the returned history.
+    pub fn get_scrollback(&self, id: &str, lines: usize) -> Result<String, String> {
+        let data = self.retained_output(id, true)?;
+        Ok(String::from_utf8_lossy(tail_lines(&data, lines)).to_string())
+    }
+
+    /// Case-insensitive search of a session's retained output as displayed (escape sequences
+    /// stripped). Keeps the newest `max_matches` matches, oldest first.
+    pub fn search_output(
+        &self,
+        id: &str,
+        pattern: &str,
+        max_matches: usize,
+    ) -> Result<OutputSearchResult, String> {
+        let pattern = pattern.to_lowercase();
+        if pattern.is_empty() {
+            return Err("Search pattern is empty".to_string());
+        }
+        let data = self.retained_output(id, false)?;
+        let output = String::from_utf8_lossy(&data);
+        let lines: Vec<&str> = output
+            .strip_suffix('\n')
+            .unwrap_or(&output)
+            .split('\n')
+            .collect();
+        let total_lines = lines.len();
+        let mut matches: Vec<OutputMatch> = lines
+            .iter()
+            .enumerate()
+            .filter_map(|(line, raw)| {
+                let text = displayed_text(raw);
+                text.to_lowercase().contains(&pattern).then(|| OutputMatch {
+                    line,
+                    lines_from_end: total_lines - 1 - line,
+                    text,
+                })
+            })
+            .collect();
+        let truncated = matches.len() > max_matches;
+        if truncated {
+            matches.drain(..matches.len() - max_matches);
+        }
+        Ok(OutputSearchResult {
+            total_lines,
+            matches,
+            truncated,
+        })
+    }
+
     pub fn has_session(&self, id: &str) -> bool {
         self.sessions.contains_key(id)
     }
 
+    /// Working directory a session was created with.
+    pub fn session_cwd(&self, id: &str) -> Option<String> {
+        let session = self.sessions.get(id)?;
+        let session = session.lock().ok()?;
+        Some(session.cwd.clone())
+    }
+
+    /// Local working directory of a session; `None` for unknown and SSH sessions, whose
+    /// `cwd` is on the remote host.
+    pub fn session_local_cwd(&self, id: &str) -> Option<String> {
+        let session = self.sessions.get(id)?;
+        let session = session.lock().ok()?;
+        session.remote.is_none().then(|| session.cwd.clone())
+    }
+
+    /// (session id, cwd, shell pid) of every session whose shell pid is known.
+    pub fn session_shells(&self) -> Vec<(String, String, u32)> {
+        self.sessions
+            .iter()
+            .filter_map(|(id, session)| {
+                let session = session.lock().ok()?;
+                let pid = session.child.process_id()?;
+                Some((id.clone(), session.cwd.clone(), pid))
+            })
+            .collect()
+    }
+
     /// Get a broadcast receiver and replay buffer snapshot for a PTY session (used by WebSocket subscribers).
     /// Returns (replay_data, broadcast_receiver).
     pub fn subscribe_session(&self, id: &str) -> Option<(Vec<u8>, broadcast::Receiver<Vec<u8>>)> {
         let session_arc = self.sessions.get(id)?;
         let session = session_arc.lock().ok()?;
         let replay = session
-            .replay_buffer
+            .history
             .lock()
             .ok()
-            .map(|rb| rb.iter().copied().collect::<Vec<u8>>())
+            .map(|h| h.buffer.iter().copied().collect::<Vec<u8>>())
             .unwrap_or_default();
         let rx = session.broadcast_tx.subscribe();
         Some((replay, rx))
@@ -374,10 +1049,56 @@ impl PtyManager {
                     session.kill_child();
                 }
             }
+            self.remove_scrollback(id);
+        }
+        if !sessions_to_close.is_empty() {
+            self.save_registry();
         }
 
         sessions_to_close
     }
+
+    /// Close sessions with no input or output for longer than the idle timeout. Their
+    /// scrollback is kept so reopening the terminal still shows it. Returns the closed ids.
+    pub fn close_idle_sessions(&mut self) -> Vec<String> {
+        let Some(timeout) = self.idle_timeout else {
+            return vec![];
+        };
+        let idle: Vec<String> = self
+            .sessions
+            .iter()
+            .filter(|(_, session)| {
+                session
+                    .lock()
+                    .map(|s| s.idle_for() >= timeout)
+                    .unwrap_or(false)
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in &idle {
+            if let Some(session) = self.sessions.remove(id) {
+                if let Ok(mut session) = session.lock() {
+                    session.kill_child();
+                }
+            }
+        }
+        if !idle.is_empty() {
+            self.save_registry();
+        }
+        idle
+    }
+
+    /// Close every session (used by the shutdown path); persisted scrollback and the session
+    /// registry are kept for the next run. Returns the closed session ids.
+    pub fn close_all_sessions(&mut self) -> Vec<String> {
+        let ids: Vec<String> = self.sessions.keys().cloned().collect();
+        for (_, session) in self.sessions.drain() {
+            if let Ok(mut session) = session.lock() {
+                session.kill_child();
+            }
+        }
+        ids
+    }
 }
 
 impl Default for PtyManager {
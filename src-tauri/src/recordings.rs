@@ -0,0 +1,222 @@
+//! Terminal recordings: asciicast v2 files of PTY sessions, written to `.recordings/` in the
+//! worktree the terminal runs in (the workspace root for main-workspace terminals).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::types::{TerminalRecording, WorkspaceConfig};
+use crate::utils::normalize_path;
+
+const RECORDINGS_DIR: &str = ".recordings";
+
+/// Where recordings of a terminal at `cwd` go, and the worktree it belongs to.
+pub(crate) fn recordings_dir_for_cwd(
+    workspace_path: &str,
+    config: &WorkspaceConfig,
+    cwd: &str,
+) -> (PathBuf, Option<String>) {
+    let worktrees_root = Path::new(workspace_path).join(&config.worktrees_dir);
+    let worktree_name = Path::new(cwd)
+        .strip_prefix(&worktrees_root)
+        .ok()
+        .and_then(|rel| rel.components().next())
+        .map(|c| c.as_os_str().to_string_lossy().to_string());
+    match worktree_name {
+        Some(name) => (worktrees_root.join(&name).join(RECORDINGS_DIR), Some(name)),
+        None => (Path::new(workspace_path).join(RECORDINGS_DIR), None),
+    }
+}
+
+/// Recordings directory of a worktree, or of the workspace root when `worktree_name` is `None`.
+pub(crate) fn recordings_dir(
+    workspace_path: &str,
+    config: &WorkspaceConfig,
+    worktree_name: Option<&str>,
+) -> Result<PathBuf, String> {
+    let Some(name) = worktree_name else {
+        return Ok(Path::new(workspace_path).join(RECORDINGS_DIR));
+    };
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(format!("Invalid worktree name: {}", name));
+    }
+    let worktree_path = Path::new(workspace_path)
+        .join(&config.worktrees_dir)
+        .join(name);
+    if !worktree_path.is_dir() {
+        return Err(format!("Worktree not found: {}", name));
+    }
+    Ok(worktree_path.join(RECORDINGS_DIR))
+}
+
+/// `20240501-153012-login-crash.cast`; the title is slugged into the name when given.
+pub(crate) fn recording_file_name(title: Option<&str>) -> String {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let slug: String = title
+        .unwrap_or("")
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        format!("{}.cast", stamp)
+    } else {
+        format!("{}-{}.cast", stamp, slug)
+    }
+}
+
+/// Summary of a recording from its header line and last event.
+pub(crate) fn recording_info(
+    path: &Path,
+    worktree_name: Option<&str>,
+    active_session_id: Option<String>,
+) -> Result<TerminalRecording, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read recording {}: {}", path.display(), e))?;
+    let mut lines = content.lines().filter(|l| !l.trim().is_empty());
+    let header: serde_json::Value = lines
+        .next()
+        .and_then(|l| serde_json::from_str(l).ok())
+        .unwrap_or_default();
+    let duration_secs = lines
+        .next_back()
+        .and_then(|l| serde_json::from_str::<serde_json::Value>(l).ok())
+        .and_then(|event| event[0].as_f64())
+        .unwrap_or(0.0);
+    Ok(TerminalRecording {
+        file_name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        path: normalize_path(&path.to_string_lossy()),
+        worktree_name: worktree_name.map(str::to_string),
+        title: header["title"].as_str().map(str::to_string),
+        started_at: header["timestamp"]
+            .as_i64()
+            .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+            .map(|t| t.to_rfc3339()),
+        duration_secs,
+        size_bytes: content.len() as u64,
+        active_session_id,
+    })
+}
+
+/// Recordings in `dir`, newest first. `active` maps recording paths to the sessions
+/// currently writing them.
+pub(crate) fn list_recordings(
+    dir: &Path,
+    worktree_name: Option<&str>,
+    active: &[(String, PathBuf)],
+) -> Vec<TerminalRecording> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut recordings: Vec<TerminalRecording> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "cast"))
+        .filter_map(|p| {
+            let session = active
+                .iter()
+                .find(|(_, path)| *path == p)
+                .map(|(id, _)| id.clone());
+            recording_info(&p, worktree_name, session).ok()
+        })
+        .collect();
+    // File names start with the start time
+    recordings.sort_by(|a, b| b.file_name.cmp(&a.file_name));
+    recordings
+}
+
+/// Path of a recording in `dir`; `file_name` must be a plain `.cast` file name.
+pub(crate) fn recording_path(dir: &Path, file_name: &str) -> Result<PathBuf, String> {
+    if file_name.contains(['/', '\\'])
+        || file_name.starts_with('.')
+        || !file_name.ends_with(".cast")
+    {
+        return Err(format!("Invalid recording name: {}", file_name));
+    }
+    let path = dir.join(file_name);
+    if !path.is_file() {
+        return Err(format!("Recording not found: {}", file_name));
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scratch workspace with one worktree, removed when dropped
+    struct TempWorkspace(PathBuf);
+
+    impl TempWorkspace {
+        fn new() -> Self {
+            let root = std::env::temp_dir().join(format!("wm-recordings-{}", uuid::Uuid::new_v4()));
+            fs::create_dir_all(root.join("worktrees/feat-x")).unwrap();
+            TempWorkspace(root)
+        }
+
+        fn path(&self) -> String {
+            self.0.to_string_lossy().to_string()
+        }
+    }
+
+    impl Drop for TempWorkspace {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn recordings_dir_of_workspace_and_worktree() {
+        let ws = TempWorkspace::new();
+        assert_eq!(
+            recordings_dir(&ws.path(), &WorkspaceConfig::default(), None).unwrap(),
+            ws.0.join(RECORDINGS_DIR)
+        );
+        assert_eq!(
+            recordings_dir(&ws.path(), &WorkspaceConfig::default(), Some("feat-x")).unwrap(),
+            ws.0.join("worktrees/feat-x").join(RECORDINGS_DIR)
+        );
+        assert!(recordings_dir(&ws.path(), &WorkspaceConfig::default(), Some("feat-y")).is_err());
+    }
+
+    #[test]
+    fn recordings_dir_rejects_path_like_names() {
+        let ws = TempWorkspace::new();
+        for name in ["", ".", "..", ".hidden", "../feat-x", "feat-x/..", "a\\b"] {
+            assert!(
+                recordings_dir(&ws.path(), &WorkspaceConfig::default(), Some(name)).is_err(),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn recording_path_accepts_only_plain_cast_files() {
+        let ws = TempWorkspace::new();
+        let dir = ws.0.join(RECORDINGS_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("20240501-153012.cast"), "{}\n").unwrap();
+        fs::write(dir.join("notes.txt"), "").unwrap();
+
+        assert_eq!(
+            recording_path(&dir, "20240501-153012.cast").unwrap(),
+            dir.join("20240501-153012.cast")
+        );
+        assert!(recording_path(&dir, "missing.cast").is_err());
+        for name in [
+            "notes.txt",
+            ".cast",
+            "../20240501-153012.cast",
+            "sub/20240501-153012.cast",
+            "..\\20240501-153012.cast",
+        ] {
+            assert!(recording_path(&dir, name).is_err(), "{}", name);
+        }
+    }
+}
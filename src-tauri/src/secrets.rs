@@ -0,0 +1,185 @@
+//! Optional encryption of sensitive config values at rest (tokens, passwords, internal URLs,
+//! terminal environment variables). Values are sealed with AES-256-GCM under a random key kept
+//! in the OS keychain and stored as `enc:v1:<base64>` strings, so the rest of global.json and
+//! .worktree-manager.json stays readable and diffable.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde_json::Value;
+
+use crate::state::CONFIG_ENCRYPTION_KEY;
+
+const SEALED_PREFIX: &str = "enc:v1:";
+const KEYCHAIN_SERVICE: &str = "com.guo.worktree-manager";
+const KEYCHAIN_ACCOUNT: &str = "config-encryption-key";
+
+/// String fields holding credentials or internal endpoints, wherever they appear in a config
+const SENSITIVE_KEYS: &[&str] = &[
+    "ngrok_token",
+    "wms_token",
+    "wms_server_url",
+    "dashscope_api_key",
+    "dashscope_base_url",
+    "totp_secret",
+    "token",
+    "password",
+    "jira_token",
+    "linear_api_key",
+    "webhook_secret",
+    "secret",
+    "webhook_url",
+];
+
+/// Objects whose values are all sensitive (environment variables injected into terminals)
+const SENSITIVE_MAPS: &[&str] = &["env"];
+
+/// The config key from the keychain (cached after the first read). With `create`, a new key
+/// is generated and stored when the keychain has none.
+fn encryption_key(create: bool) -> Result<LessSafeKey, String> {
+    let mut cached = CONFIG_ENCRYPTION_KEY
+        .lock()
+        .map_err(|_| "Internal state error".to_string())?;
+    if cached.is_none() {
+        let entry = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)
+            .map_err(|e| format!("无法访问系统钥匙串: {}", e))?;
+        let bytes = match entry.get_password() {
+            Ok(encoded) => BASE64
+                .decode(encoded.trim())
+                .map_err(|e| format!("系统钥匙串中的配置密钥无效: {}", e))?,
+            Err(keyring::Error::NoEntry) if create => {
+                let mut bytes = vec![0u8; 32];
+                SystemRandom::new()
+                    .fill(&mut bytes)
+                    .map_err(|_| "Failed to generate config encryption key".to_string())?;
+                entry
+                    .set_password(&BASE64.encode(&bytes))
+                    .map_err(|e| format!("无法写入系统钥匙串: {}", e))?;
+                log::info!("[secrets] Created config encryption key in the OS keychain");
+                bytes
+            }
+            Err(keyring::Error::NoEntry) => {
+                return Err("系统钥匙串中没有配置密钥".to_string());
+            }
+            Err(e) => return Err(format!("无法读取系统钥匙串: {}", e)),
+        };
+        *cached = Some(bytes);
+    }
+    let bytes = cached.as_deref().unwrap_or_default();
+    UnboundKey::new(&AES_256_GCM, bytes)
+        .map(LessSafeKey::new)
+        .map_err(|_| "系统钥匙串中的配置密钥无效".to_string())
+}
+
+/// Make sure the keychain holds a config key (creating one), before turning encryption on.
+pub(crate) fn ensure_key() -> Result<(), String> {
+    encryption_key(true).map(|_| ())
+}
+
+fn seal(key: &LessSafeKey, plaintext: &str) -> Result<String, String> {
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| "Failed to generate nonce".to_string())?;
+    let mut in_out = plaintext.as_bytes().to_vec();
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce),
+        Aad::empty(),
+        &mut in_out,
+    )
+    .map_err(|_| "Failed to encrypt config value".to_string())?;
+    let mut sealed = nonce.to_vec();
+    sealed.extend(in_out);
+    Ok(format!("{}{}", SEALED_PREFIX, BASE64.encode(sealed)))
+}
+
+fn open(key: &LessSafeKey, sealed: &str) -> Option<String> {
+    let bytes = BASE64.decode(sealed.strip_prefix(SEALED_PREFIX)?).ok()?;
+    if bytes.len() < NONCE_LEN {
+        return None;
+    }
+    let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).ok()?;
+    let mut in_out = ciphertext.to_vec();
+    let plaintext = key.open_in_place(nonce, Aad::empty(), &mut in_out).ok()?;
+    String::from_utf8(plaintext.to_vec()).ok()
+}
+
+/// Call `f` on every string that `sensitive` (or a sensitive key / map) marks as secret.
+fn visit_sensitive(value: &mut Value, sensitive: bool, f: &mut dyn FnMut(&mut String)) {
+    match value {
+        Value::String(s) if sensitive => f(s),
+        Value::Array(items) => {
+            for item in items {
+                visit_sensitive(item, sensitive, f);
+            }
+        }
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                let key = key.as_str();
+                match child {
+                    Value::Object(vars) if SENSITIVE_MAPS.contains(&key) => {
+                        for var in vars.values_mut() {
+                            visit_sensitive(var, true, f);
+                        }
+                    }
+                    _ => visit_sensitive(child, SENSITIVE_KEYS.contains(&key), f),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Seal the sensitive values of a serialized config in place (already sealed ones are kept).
+pub(crate) fn encrypt_config_value(value: &mut Value) -> Result<(), String> {
+    let key = encryption_key(true)?;
+    let mut result = Ok(());
+    visit_sensitive(value, false, &mut |s| {
+        if s.is_empty() || s.starts_with(SEALED_PREFIX) || result.is_err() {
+            return;
+        }
+        match seal(&key, s) {
+            Ok(sealed) => *s = sealed,
+            Err(e) => result = Err(e),
+        }
+    });
+    result
+}
+
+/// Open every sealed value of a loaded config in place. Values that can't be opened (no key on
+/// this machine, e.g. a workspace config synced from elsewhere) stay sealed.
+pub(crate) fn decrypt_config_value(value: &mut Value) {
+    let mut sealed = 0;
+    visit_sensitive(value, false, &mut |s| {
+        if s.starts_with(SEALED_PREFIX) {
+            sealed += 1;
+        }
+    });
+    if sealed == 0 {
+        return;
+    }
+    let key = match encryption_key(false) {
+        Ok(key) => key,
+        Err(e) => {
+            log::warn!("[secrets] Cannot decrypt {} config values: {}", sealed, e);
+            return;
+        }
+    };
+    let mut failed = 0;
+    visit_sensitive(value, false, &mut |s| {
+        if s.starts_with(SEALED_PREFIX) {
+            match open(&key, s) {
+                Some(plaintext) => *s = plaintext,
+                None => failed += 1,
+            }
+        }
+    });
+    if failed > 0 {
+        log::warn!(
+            "[secrets] {} config values were encrypted with another key and stay sealed",
+            failed
+        );
+    }
+}
@@ -1,11 +1,17 @@
 use once_cell::sync::Lazy;
-use std::collections::HashMap;
-use std::sync::Mutex;
-
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize};
+use std::sync::{Condvar, Mutex};
+use std::time::Instant;
+
+use crate::config::load_global_config;
+use crate::git_ops::CiStatus;
+use crate::oplog::OperationLog;
 use crate::pty_manager::PtyManager;
 use crate::types::{
-    AuthRateLimiter, ConnectedClient, GlobalConfig, NonceCache, ShareState, TerminalState,
-    WorkspaceConfig,
+    ApiRateLimiter, AuthRateLimiter, AuthenticatedSession, BaseUpdate, ConnectedClient, FocusState,
+    GlobalConfig, NonceCache, ShareActivity, ShareState, TerminalState, WorkspaceConfig,
+    WorktreeIssue,
 };
 
 // PTY Manager 全局实例
@@ -21,19 +27,62 @@ pub(crate) static WINDOW_WORKSPACES: Lazy<Mutex<HashMap<String, String>>> =
 pub(crate) static WORKTREE_LOCKS: Lazy<Mutex<HashMap<(String, String), String>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
+// 由终端活动自动获取的锁：(workspace_path, worktree_name) -> (window_label, 最近一次输入时间)
+// 手动锁不在此表中，不会被空闲释放
+type AutoLocks = HashMap<(String, String), (String, Instant)>;
+pub(crate) static AUTO_LOCKS: Lazy<Mutex<AutoLocks>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+// 已执行 `git worktree lock` 的 worktree：(workspace_path, worktree_name)
+// 手动锁定或部署到主工作区时加锁，两者都解除后解锁
+pub(crate) static GIT_WORKTREE_LOCKED: Lazy<Mutex<HashSet<(String, String)>>> =
+    Lazy::new(|| Mutex::new(HashSet::new()));
+
+// Base 分支更新检测结果：workspace_path -> 落后超过阈值的 worktree 项目
+pub(crate) static BASE_UPDATES: Lazy<Mutex<HashMap<String, Vec<BaseUpdate>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// 磁盘配额提醒去重：workspace_path -> 上次提醒时间
+pub(crate) static DISK_QUOTA_WARNED: Lazy<Mutex<HashMap<String, Instant>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// 正在刷新的 workspace：同一 workspace 同时只允许一次 refresh_workspace
+pub(crate) static REFRESHING_WORKSPACES: Lazy<Mutex<HashSet<String>>> =
+    Lazy::new(|| Mutex::new(HashSet::new()));
+
+// 自动锁节流：pty_session_id -> 上次处理终端活动的时间
+pub(crate) static AUTO_LOCK_LAST_SEEN: Lazy<Mutex<HashMap<String, Instant>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
 // ==================== 分享状态 ====================
 
 pub(crate) static SHARE_STATE: Lazy<Mutex<ShareState>> =
     Lazy::new(|| Mutex::new(ShareState::default()));
 
-// 已认证的 session 集合
-pub(crate) static AUTHENTICATED_SESSIONS: Lazy<Mutex<std::collections::HashSet<String>>> =
+// 已认证的 session -> 权限与最近活动时间
+pub(crate) static AUTHENTICATED_SESSIONS: Lazy<Mutex<HashMap<String, AuthenticatedSession>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// 因空闲超时被移除的 session -> 过期时间：再次请求时返回 session_expired，提示重新登录。
+// 记录保留一段时间后清理，避免无限增长
+pub(crate) static EXPIRED_SESSIONS: Lazy<Mutex<HashMap<String, Instant>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// 被隔离的 session：WS 保持连接但只读，所有修改类 API 被拒绝
+pub(crate) static QUARANTINED_SESSIONS: Lazy<Mutex<std::collections::HashSet<String>>> =
     Lazy::new(|| Mutex::new(std::collections::HashSet::new()));
 
 // 已连接的客户端追踪
 pub(crate) static CONNECTED_CLIENTS: Lazy<Mutex<HashMap<String, ConnectedClient>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
+// 分享服务器启动时间（用于 /api/health 的 uptime）
+pub(crate) static SERVER_STARTED_AT: Lazy<Mutex<Option<std::time::Instant>>> =
+    Lazy::new(|| Mutex::new(None));
+
+// 远程会话活动流（最近的 API 调用摘要）
+pub(crate) static SHARE_ACTIVITY: Lazy<Mutex<VecDeque<ShareActivity>>> =
+    Lazy::new(|| Mutex::new(VecDeque::new()));
+
 pub(crate) static TOKIO_RT: Lazy<tokio::runtime::Runtime> = Lazy::new(|| {
     tokio::runtime::Runtime::new().expect("Failed to create tokio runtime for sharing")
 });
@@ -45,6 +94,14 @@ pub(crate) static LOCK_BROADCAST: Lazy<tokio::sync::broadcast::Sender<String>> =
     tx
 });
 
+// Broadcast channel for workspace-wide events (WebSocket push): remote updates from webhooks,
+// file system and worktree status changes. Messages are JSON with "type" and "workspacePath".
+pub(crate) static WORKSPACE_EVENT_BROADCAST: Lazy<tokio::sync::broadcast::Sender<String>> =
+    Lazy::new(|| {
+        let (tx, _) = tokio::sync::broadcast::channel(256);
+        tx
+    });
+
 // Broadcast channel for terminal state changes (WebSocket push)
 // Increased capacity from 64 to 256 to reduce message lag and drops
 pub(crate) static TERMINAL_STATE_BROADCAST: Lazy<tokio::sync::broadcast::Sender<String>> =
@@ -57,6 +114,16 @@ pub(crate) static TERMINAL_STATE_BROADCAST: Lazy<tokio::sync::broadcast::Sender<
 pub(crate) static TERMINAL_STATES: Lazy<Mutex<HashMap<(String, String), TerminalState>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
+// Broadcast channel for desktop focus changes (presentation follow mode)
+pub(crate) static FOCUS_BROADCAST: Lazy<tokio::sync::broadcast::Sender<String>> = Lazy::new(|| {
+    let (tx, _) = tokio::sync::broadcast::channel(64);
+    tx
+});
+
+// Focus state cache: workspace_path -> FocusState
+pub(crate) static FOCUS_STATES: Lazy<Mutex<HashMap<String, FocusState>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
 // Global AppHandle for emitting events from anywhere
 pub(crate) static APP_HANDLE: Lazy<Mutex<Option<tauri::AppHandle>>> =
     Lazy::new(|| Mutex::new(None));
@@ -65,10 +132,18 @@ pub(crate) static APP_HANDLE: Lazy<Mutex<Option<tauri::AppHandle>>> =
 pub(crate) static AUTH_RATE_LIMITER: Lazy<Mutex<AuthRateLimiter>> =
     Lazy::new(|| Mutex::new(AuthRateLimiter::new()));
 
+// Rate limiter for all API calls and WebSocket terminal input
+pub(crate) static API_RATE_LIMITER: Lazy<Mutex<ApiRateLimiter>> =
+    Lazy::new(|| Mutex::new(ApiRateLimiter::new(load_global_config().rate_limit)));
+
 // Nonce cache for challenge-response authentication
 pub(crate) static NONCE_CACHE: Lazy<Mutex<NonceCache>> =
     Lazy::new(|| Mutex::new(NonceCache::new()));
 
+// TOTP 防重放：secret -> 最近一次通过验证的时间步，同一步及更早的验证码不再接受
+pub(crate) static TOTP_LAST_STEPS: Lazy<Mutex<HashMap<String, u64>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
 // Broadcast channel for voice events (WebSocket push to browser clients)
 pub(crate) static VOICE_BROADCAST: Lazy<tokio::sync::broadcast::Sender<String>> = Lazy::new(|| {
     let (tx, _) = tokio::sync::broadcast::channel(64);
@@ -83,9 +158,54 @@ pub(crate) static CLIENT_NOTIFICATION_BROADCAST: Lazy<tokio::sync::broadcast::Se
         tx
     });
 
+// Broadcast channel for app log lines (admin-only WebSocket log stream)
+pub(crate) static LOG_BROADCAST: Lazy<tokio::sync::broadcast::Sender<String>> = Lazy::new(|| {
+    let (tx, _) = tokio::sync::broadcast::channel(512);
+    tx
+});
+
+// ==================== 退出流程 ====================
+
+// 应用正在退出：置位后拒绝新的 Git 写操作
+pub(crate) static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+// 退出流程已完成：ExitRequested 再次触发时直接放行
+pub(crate) static SHUTDOWN_COMPLETE: AtomicBool = AtomicBool::new(false);
+
+// 正在执行的 Git 写操作数量（退出时等待其归零）
+pub(crate) static IN_FLIGHT_GIT_OPS: AtomicUsize = AtomicUsize::new(0);
+
+// 主仓库操作队列：main_repo_path -> 排队的 ticket（队首持有执行权）
+// 同一仓库上的 fetch / worktree add 串行执行，避免桌面端与 Web 端并发创建时互相踩踏
+pub(crate) static REPO_QUEUES: Lazy<Mutex<HashMap<String, VecDeque<u64>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+pub(crate) static REPO_QUEUE_CHANGED: Condvar = Condvar::new();
+pub(crate) static REPO_QUEUE_TICKET: AtomicU64 = AtomicU64::new(0);
+
+// 最近的 Git 操作记录（每次操作的命令输出，供 get_operation_log 查询）
+pub(crate) static OPERATION_LOGS: Lazy<Mutex<VecDeque<OperationLog>>> =
+    Lazy::new(|| Mutex::new(VecDeque::new()));
+
 // ==================== 全局配置缓存 ====================
 
 pub(crate) static GLOBAL_CONFIG_CACHE: Lazy<Mutex<Option<GlobalConfig>>> =
     Lazy::new(|| Mutex::new(None));
-pub(crate) static WORKSPACE_CONFIG_CACHE: Lazy<Mutex<Option<(String, WorkspaceConfig)>>> =
+// workspace_path -> 配置（多窗口可同时打开不同 workspace）
+pub(crate) static WORKSPACE_CONFIG_CACHE: Lazy<Mutex<HashMap<String, WorkspaceConfig>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+// CI 状态缓存：(项目路径, commit) -> (查询时间, 结果)
+type CiStatusCache = HashMap<(String, String), (Instant, CiStatus)>;
+pub(crate) static CI_STATUS_CACHE: Lazy<Mutex<CiStatusCache>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+// 工单标题/状态缓存：工单链接 -> (查询时间, 结果)
+pub(crate) static ISSUE_CACHE: Lazy<Mutex<HashMap<String, (Instant, WorktreeIssue)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+// 配置加密密钥（读取系统钥匙串后缓存，避免反复弹出授权）
+pub(crate) static CONFIG_ENCRYPTION_KEY: Lazy<Mutex<Option<Vec<u8>>>> =
     Lazy::new(|| Mutex::new(None));
+
+// 配置的近期版本：workspace_path -> [(etag, 配置 JSON)]，用于合并基于旧版本的保存。
+// 所有保存都持有该锁，保证比较与写入之间不会被其他窗口插入
+type ConfigVersions = HashMap<String, VecDeque<(String, serde_json::Value)>>;
+pub(crate) static WORKSPACE_CONFIG_VERSIONS: Lazy<Mutex<ConfigVersions>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
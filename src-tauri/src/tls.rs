@@ -1,7 +1,11 @@
 use rcgen::{CertificateParams, DnType, KeyPair, SanType};
 use std::net::IpAddr;
+use std::path::Path;
+use std::process::Command;
 use std::time::Duration;
 
+use crate::types::CustomTlsConfig;
+
 pub struct TlsCerts {
     pub cert_pem: String,
     pub key_pem: String,
@@ -39,3 +43,91 @@ pub fn generate_self_signed(ips: &[IpAddr]) -> Result<TlsCerts, String> {
         key_pem: key_pair.serialize_pem(),
     })
 }
+
+/// Load a configured certificate instead of generating one: a PEM certificate chain with a
+/// PEM private key, or a PKCS#12 bundle (`.p12` / `.pfx`, unpacked with the `openssl` tool).
+pub fn load_custom(config: &CustomTlsConfig) -> Result<TlsCerts, String> {
+    let certs = if is_pkcs12(&config.cert_path) {
+        load_pkcs12(&config.cert_path, config.password.as_deref().unwrap_or(""))?
+    } else {
+        let key_path = config
+            .key_path
+            .as_deref()
+            .filter(|p| !p.trim().is_empty())
+            .ok_or("A private key file is required for PEM certificates")?;
+        TlsCerts {
+            cert_pem: read_file(&config.cert_path)?,
+            key_pem: read_file(key_path)?,
+        }
+    };
+    validate(&certs)?;
+    Ok(certs)
+}
+
+fn read_file(path: &str) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))
+}
+
+fn is_pkcs12(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("p12") || e.eq_ignore_ascii_case("pfx"))
+}
+
+/// Extract the certificates (`-nokeys`) or the key (`-nocerts`) of a PKCS#12 file as PEM.
+/// The password is passed through the environment so it doesn't show up in the process list.
+fn openssl_pkcs12(path: &str, password: &str, part: &str) -> Result<String, String> {
+    let run = |legacy: bool| {
+        let mut cmd = Command::new("openssl");
+        cmd.args(["pkcs12", "-in", path, "-nodes", part])
+            .args(["-passin", "env:WM_PKCS12_PASSWORD"])
+            .env("WM_PKCS12_PASSWORD", password);
+        // OpenSSL 3 needs the legacy provider for bundles encrypted with RC2 / 3DES
+        if legacy {
+            cmd.arg("-legacy");
+        }
+        cmd.output()
+            .map_err(|e| format!("PKCS#12 certificates need the openssl command: {}", e))
+    };
+    let mut output = run(false)?;
+    if !output.status.success() {
+        output = run(true)?;
+    }
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to read {}: {}",
+            path,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn load_pkcs12(path: &str, password: &str) -> Result<TlsCerts, String> {
+    if !Path::new(path).is_file() {
+        return Err(format!("Certificate file not found: {}", path));
+    }
+    Ok(TlsCerts {
+        cert_pem: openssl_pkcs12(path, password, "-nokeys")?,
+        key_pem: openssl_pkcs12(path, password, "-nocerts")?,
+    })
+}
+
+/// Check the files contain a certificate chain and a private key that belong together.
+fn validate(certs: &TlsCerts) -> Result<(), String> {
+    let chain = rustls_pemfile::certs(&mut certs.cert_pem.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Invalid certificate: {}", e))?;
+    if chain.is_empty() {
+        return Err("No certificate found".to_string());
+    }
+    let key = rustls_pemfile::private_key(&mut certs.key_pem.as_bytes())
+        .map_err(|e| format!("Invalid private key: {}", e))?
+        .ok_or("No private key found")?;
+    rustls::ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(chain, key)
+        .map_err(|e| format!("Certificate and key don't match: {}", e))?;
+    Ok(())
+}
@@ -0,0 +1,189 @@
+//! TOTP (RFC 6238) second factor for share authentication: 6-digit codes over 30-second
+//! steps with HMAC-SHA1, the parameters every authenticator app supports by default.
+
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::state::TOTP_LAST_STEPS;
+
+const STEP_SECS: u64 = 30;
+const DIGITS: u32 = 6;
+/// Accept codes one step either side to tolerate clock drift between host and phone
+const ALLOWED_DRIFT_STEPS: u64 = 1;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Unpadded RFC 4648 base32, the secret encoding used in otpauth URLs.
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+fn base32_decode(text: &str) -> Option<Vec<u8>> {
+    let mut out = vec![];
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in text.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a as char == c.to_ascii_uppercase())?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+/// New random 160-bit secret, base32-encoded.
+pub(crate) fn generate_secret() -> Result<String, String> {
+    let mut bytes = [0u8; 20];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| "Failed to generate TOTP secret".to_string())?;
+    Ok(base32_encode(&bytes))
+}
+
+/// `otpauth://` URL for enrolling the secret in an authenticator app.
+pub(crate) fn otpauth_url(secret: &str, account: &str) -> String {
+    let issuer = "Worktree Manager";
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        urlencoding::encode(issuer),
+        urlencoding::encode(account),
+        secret,
+        urlencoding::encode(issuer),
+        DIGITS,
+        STEP_SECS
+    )
+}
+
+fn code_at(key: &[u8], counter: u64) -> u32 {
+    let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, key);
+    let tag = hmac::sign(&key, &counter.to_be_bytes());
+    let digest = tag.as_ref();
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    binary % 10u32.pow(DIGITS)
+}
+
+/// Whether `code` is valid for `secret` now (within the allowed drift). A code is accepted
+/// once: its step and every earlier one are rejected afterwards, so an observed code can't
+/// be replayed within its window.
+pub(crate) fn verify_code(secret: &str, code: &str) -> bool {
+    verify_code_at(secret, code, chrono::Utc::now().timestamp().max(0) as u64)
+}
+
+fn verify_code_at(secret: &str, code: &str, unix_secs: u64) -> bool {
+    let Some(step) = matching_step(secret, code, unix_secs) else {
+        return false;
+    };
+    let Ok(mut last_steps) = TOTP_LAST_STEPS.lock() else {
+        return false;
+    };
+    if last_steps.get(secret).is_some_and(|last| step <= *last) {
+        log::warn!("[totp] Rejected reused verification code");
+        return false;
+    }
+    last_steps.insert(secret.to_string(), step);
+    true
+}
+
+/// Time step within the allowed drift that `code` was generated for.
+fn matching_step(secret: &str, code: &str, unix_secs: u64) -> Option<u64> {
+    let code = code.trim();
+    if code.len() != DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let code = code.parse::<u32>().ok()?;
+    let key = base32_decode(secret).filter(|k| !k.is_empty())?;
+    let now = unix_secs / STEP_SECS;
+    (now.saturating_sub(ALLOWED_DRIFT_STEPS)..=now + ALLOWED_DRIFT_STEPS)
+        .find(|counter| code_at(&key, *counter) == code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Shared secret of the RFC 6238 SHA-1 test vectors
+    const RFC_SECRET: &[u8] = b"12345678901234567890";
+
+    #[test]
+    fn rfc6238_sha1_vectors() {
+        // Appendix B values are 8 digits; 6-digit codes are their last 6 digits
+        let vectors: [(u64, u32); 6] = [
+            (59, 287082),
+            (1111111109, 81804),
+            (1111111111, 50471),
+            (1234567890, 5924),
+            (2000000000, 279037),
+            (20000000000, 353130),
+        ];
+        for (unix_secs, expected) in vectors {
+            assert_eq!(
+                code_at(RFC_SECRET, unix_secs / STEP_SECS),
+                expected,
+                "T = {}",
+                unix_secs
+            );
+        }
+    }
+
+    #[test]
+    fn base32_round_trip() {
+        let secret = base32_encode(RFC_SECRET);
+        assert_eq!(secret, "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+        assert_eq!(base32_decode(&secret).as_deref(), Some(RFC_SECRET));
+        assert_eq!(base32_decode("gezd g===").as_deref(), Some(&b"123"[..]));
+        assert_eq!(base32_decode("GEZ1"), None);
+    }
+
+    #[test]
+    fn accepts_codes_within_drift() {
+        let secret = base32_encode(RFC_SECRET);
+        assert_eq!(matching_step(&secret, "287082", 59), Some(1));
+        assert_eq!(matching_step(&secret, " 287082 ", 59 + STEP_SECS), Some(1));
+        assert_eq!(matching_step(&secret, "287082", 59 + 2 * STEP_SECS), None);
+        assert_eq!(matching_step(&secret, "28708", 59), None);
+        assert_eq!(matching_step(&secret, "28708a", 59), None);
+        assert_eq!(matching_step("", "287082", 59), None);
+    }
+
+    #[test]
+    fn rejects_replayed_codes() {
+        // Unique secret so parallel tests don't share replay state
+        let secret = base32_encode(b"replay-test-secret");
+        let key = base32_decode(&secret).unwrap();
+        let t = 1_700_000_000;
+        let code = format!("{:06}", code_at(&key, t / STEP_SECS));
+        let previous = format!("{:06}", code_at(&key, t / STEP_SECS - 1));
+
+        assert!(verify_code_at(&secret, &code, t));
+        assert!(!verify_code_at(&secret, &code, t));
+        // The previous step is still within drift but older than the accepted one
+        assert!(!verify_code_at(&secret, &previous, t));
+        let next = format!("{:06}", code_at(&key, t / STEP_SECS + 1));
+        assert!(verify_code_at(&secret, &next, t + STEP_SECS));
+    }
+}
@@ -0,0 +1,163 @@
+use futures_util::future::BoxFuture;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::{TunnelProvider, TunnelStatus};
+use crate::config::load_global_config;
+
+/// Cloudflare Tunnel via the `cloudflared` binary. Without a token this opens a quick tunnel
+/// on a random `trycloudflare.com` URL (no account needed); with the token of a named tunnel
+/// it serves the hostname routed to it in the Cloudflare dashboard.
+#[derive(Default)]
+pub(crate) struct CloudflaredTunnel {
+    running: Mutex<Option<(String, Child)>>,
+}
+
+/// The quick tunnel URL cloudflared prints to stderr, e.g. `https://foo-bar.trycloudflare.com`
+fn quick_tunnel_url(line: &str) -> Option<String> {
+    let start = line.find("https://")?;
+    let url = line[start..]
+        .split_whitespace()
+        .next()?
+        .trim_end_matches('|');
+    url.ends_with(".trycloudflare.com").then(|| url.to_string())
+}
+
+impl TunnelProvider for CloudflaredTunnel {
+    fn id(&self) -> &'static str {
+        "cloudflare"
+    }
+
+    fn start(&self, port: u16) -> BoxFuture<'_, Result<String, String>> {
+        Box::pin(async move {
+            let config = load_global_config().cloudflare_tunnel;
+            let binary = config
+                .binary_path
+                .filter(|p| !p.trim().is_empty())
+                .unwrap_or_else(|| "cloudflared".to_string());
+            let token = config.token.filter(|t| !t.trim().is_empty());
+            let named_url = match (&token, config.hostname.as_deref().map(str::trim)) {
+                (None, _) => None,
+                (Some(_), Some(hostname)) if !hostname.is_empty() => Some(format!(
+                    "https://{}",
+                    hostname
+                        .trim_start_matches("https://")
+                        .trim_end_matches('/')
+                )),
+                (Some(_), _) => {
+                    return Err("使用 Cloudflare 命名隧道时需要配置公网域名".to_string())
+                }
+            };
+
+            let mut cmd = Command::new(&binary);
+            cmd.args(["tunnel", "--no-autoupdate"]);
+            match &token {
+                // 命名隧道的路由（域名 → 服务）在 Cloudflare 控制台配置，需指向该端口
+                Some(token) => {
+                    cmd.arg("run").env("TUNNEL_TOKEN", token);
+                }
+                None => {
+                    cmd.args(["--url", &format!("http://localhost:{}", port)]);
+                }
+            }
+            #[cfg(windows)]
+            {
+                use std::os::windows::process::CommandExt;
+                cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+            }
+            log::info!(
+                "[cloudflare] Spawning {} ({} tunnel) for port {}",
+                binary,
+                if token.is_some() { "named" } else { "quick" },
+                port
+            );
+            let mut child = cmd
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped())
+                .spawn()
+                .map_err(|e| format!("无法启动 cloudflared（请确认已安装）: {}", e))?;
+
+            // cloudflared logs to stderr; the quick tunnel URL and connection registration are
+            // printed there. Keep draining it so the pipe never fills up.
+            let (url_tx, url_rx) = std::sync::mpsc::channel::<String>();
+            if let Some(stderr) = child.stderr.take() {
+                let named = named_url.is_some();
+                std::thread::spawn(move || {
+                    let mut sent = false;
+                    for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                        log::debug!("[cloudflare] {}", line);
+                        if sent {
+                            continue;
+                        }
+                        let ready = if named {
+                            line.contains("Registered tunnel connection")
+                                .then(String::new)
+                        } else {
+                            quick_tunnel_url(&line)
+                        };
+                        if let Some(url) = ready {
+                            sent = url_tx.send(url).is_ok();
+                        }
+                    }
+                });
+            }
+
+            // Wait for the tunnel URL (with timeout)
+            match url_rx.recv_timeout(Duration::from_secs(30)) {
+                Ok(quick_url) => {
+                    let url = named_url.unwrap_or(quick_url);
+                    log::info!("[cloudflare] Tunnel created, URL: {}", url);
+                    let mut running = self
+                        .running
+                        .lock()
+                        .map_err(|_| "Internal state error".to_string())?;
+                    *running = Some((url.clone(), child));
+                    Ok(url)
+                }
+                Err(_) => {
+                    let exited = matches!(child.try_wait(), Ok(Some(_)));
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    if exited {
+                        log::error!("[cloudflare] cloudflared exited before the tunnel was up");
+                        Err("cloudflared 启动失败，请检查配置（详见日志）".to_string())
+                    } else {
+                        log::error!("[cloudflare] Tunnel startup timed out after 30s");
+                        Err("Cloudflare 隧道启动超时".to_string())
+                    }
+                }
+            }
+        })
+    }
+
+    fn stop(&self) {
+        let Ok(mut running) = self.running.lock() else {
+            return;
+        };
+        if let Some((_, mut child)) = running.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+            log::info!("[cloudflare] cloudflared process stopped");
+        }
+    }
+
+    fn url(&self) -> Option<String> {
+        let mut running = self.running.lock().ok()?;
+        let (url, child) = running.as_mut()?;
+        matches!(child.try_wait(), Ok(None)).then(|| url.clone())
+    }
+
+    fn status(&self) -> TunnelStatus {
+        let Ok(mut running) = self.running.lock() else {
+            return TunnelStatus::Stopped;
+        };
+        match running.as_mut().map(|(_, child)| child.try_wait()) {
+            Some(Ok(None)) => TunnelStatus::Running,
+            Some(_) => TunnelStatus::Closed,
+            None => TunnelStatus::Stopped,
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use futures_util::future::BoxFuture;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::{TunnelProvider, TunnelStatus};
+use crate::config::{get_global_config_path, load_global_config};
+use crate::types::FrpTunnelConfig;
+
+/// frp tunnel via the `frpc` binary, connecting to a self-hosted frps (useful where ngrok is
+/// blocked). frpc runs with a generated config next to the global config.
+#[derive(Default)]
+pub(crate) struct FrpTunnel {
+    running: Mutex<Option<(String, Child)>>,
+}
+
+fn frpc_config_path() -> PathBuf {
+    get_global_config_path().with_file_name("frpc.toml")
+}
+
+/// TOML basic string (JSON string escaping is a subset of it).
+fn toml_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+/// frpc config forwarding to `127.0.0.1:port` (plain HTTP for localhost connections), plus
+/// the public URL the proxy ends up on.
+fn frpc_config(config: &FrpTunnelConfig, port: u16) -> Result<(String, String), String> {
+    let server_addr = config.server_addr.trim();
+    if server_addr.is_empty() {
+        return Err("未配置 frps 服务器地址，请先在设置中配置".to_string());
+    }
+    let mut toml = format!(
+        "serverAddr = {}\nserverPort = {}\nloginFailExit = true\n",
+        toml_string(server_addr),
+        config.server_port
+    );
+    if let Some(token) = config.token.as_deref().filter(|t| !t.is_empty()) {
+        toml.push_str(&format!(
+            "auth.method = \"token\"\nauth.token = {}\n",
+            toml_string(token)
+        ));
+    }
+    toml.push_str(&format!(
+        "\n[[proxies]]\nname = {}\nlocalIP = \"127.0.0.1\"\nlocalPort = {}\n",
+        toml_string(&format!("worktree-manager-{}", port)),
+        port
+    ));
+
+    let custom_domain = config
+        .custom_domain
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty());
+    let derived_url = match (config.remote_port, custom_domain) {
+        (Some(remote_port), _) => {
+            toml.push_str(&format!("type = \"tcp\"\nremotePort = {}\n", remote_port));
+            format!("http://{}:{}", server_addr, remote_port)
+        }
+        (None, Some(domain)) => {
+            toml.push_str(&format!(
+                "type = \"http\"\ncustomDomains = [{}]\n",
+                toml_string(domain)
+            ));
+            format!("http://{}", domain)
+        }
+        (None, None) => return Err("请配置 frp 远程端口（TCP）或自定义域名（HTTP）".to_string()),
+    };
+    let url = config
+        .public_url
+        .as_deref()
+        .map(|u| u.trim().trim_end_matches('/'))
+        .filter(|u| !u.is_empty())
+        .map(str::to_string)
+        .unwrap_or(derived_url);
+    Ok((toml, url))
+}
+
+impl TunnelProvider for FrpTunnel {
+    fn id(&self) -> &'static str {
+        "frp"
+    }
+
+    fn start(&self, port: u16) -> BoxFuture<'_, Result<String, String>> {
+        Box::pin(async move {
+            let config = load_global_config().frp_tunnel;
+            let (toml, url) = frpc_config(&config, port)?;
+            let config_path = frpc_config_path();
+            std::fs::write(&config_path, toml)
+                .map_err(|e| format!("Failed to write {}: {}", config_path.display(), e))?;
+
+            let binary = config
+                .binary_path
+                .filter(|p| !p.trim().is_empty())
+                .unwrap_or_else(|| "frpc".to_string());
+            let mut cmd = Command::new(&binary);
+            cmd.arg("-c").arg(&config_path);
+            #[cfg(windows)]
+            {
+                use std::os::windows::process::CommandExt;
+                cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+            }
+            log::info!(
+                "[frp] Spawning {} for port {} via {}:{}",
+                binary,
+                port,
+                config.server_addr,
+                config.server_port
+            );
+            let mut child = cmd
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .map_err(|e| format!("无法启动 frpc（请确认已安装）: {}", e))?;
+
+            // frpc logs to stdout; wait for the proxy to start (or fail). Keep draining it so the
+            // pipe never fills up.
+            let (ready_tx, ready_rx) = std::sync::mpsc::channel::<Result<(), String>>();
+            if let Some(stdout) = child.stdout.take() {
+                std::thread::spawn(move || {
+                    let mut sent = false;
+                    for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                        log::debug!("[frp] {}", line);
+                        if sent {
+                            continue;
+                        }
+                        if line.contains("start proxy success") {
+                            sent = ready_tx.send(Ok(())).is_ok();
+                        } else if line.contains("start error")
+                            || line.contains("login to server failed")
+                        {
+                            let message = line.split("] ").last().unwrap_or(&line).to_string();
+                            sent = ready_tx.send(Err(message)).is_ok();
+                        }
+                    }
+                });
+            }
+
+            match ready_rx.recv_timeout(Duration::from_secs(30)) {
+                Ok(Ok(())) => {
+                    log::info!("[frp] Proxy started, URL: {}", url);
+                    let mut running = self
+                        .running
+                        .lock()
+                        .map_err(|_| "Internal state error".to_string())?;
+                    *running = Some((url.clone(), child));
+                    Ok(url)
+                }
+                Ok(Err(e)) => {
+                    log::error!("[frp] Proxy startup failed: {}", e);
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    Err(format!("frp 隧道启动失败: {}", e))
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    log::error!("[frp] frpc exited before the proxy was up");
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    Err("frpc 启动失败，请检查配置（详见日志）".to_string())
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    log::error!("[frp] Proxy startup timed out after 30s");
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    Err("frp 隧道启动超时".to_string())
+                }
+            }
+        })
+    }
+
+    fn stop(&self) {
+        let Ok(mut running) = self.running.lock() else {
+            return;
+        };
+        if let Some((_, mut child)) = running.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+            let _ = std::fs::remove_file(frpc_config_path());
+            log::info!("[frp] frpc process stopped");
+        }
+    }
+
+    fn url(&self) -> Option<String> {
+        let mut running = self.running.lock().ok()?;
+        let (url, child) = running.as_mut()?;
+        matches!(child.try_wait(), Ok(None)).then(|| url.clone())
+    }
+
+    fn status(&self) -> TunnelStatus {
+        let Ok(mut running) = self.running.lock() else {
+            return TunnelStatus::Stopped;
+        };
+        match running.as_mut().map(|(_, child)| child.try_wait()) {
+            Some(Ok(None)) => TunnelStatus::Running,
+            Some(_) => TunnelStatus::Closed,
+            None => TunnelStatus::Stopped,
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Public tunnel providers for LAN sharing.
+//!
+//! Each provider exposes the local share port (`localhost:<port>`) on a public URL and owns
+//! its own runtime state, so sharing code only deals with `TunnelProvider` and the provider id
+//! stored in `ShareProfile.tunnel`. New providers (bore, localtunnel, ...) are added
+//! by implementing the trait and registering them in `PROVIDERS`.
+//!
+//! The WMS tunnel predates this abstraction and keeps its own reconnect state in `SHARE_STATE`.
+
+mod cloudflared;
+mod frp;
+mod ngrok;
+mod tailscale;
+
+pub(crate) use tailscale::local_node as tailscale_node;
+
+use futures_util::future::BoxFuture;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TunnelStatus {
+    Stopped,
+    Running,
+    /// The tunnel task exited on its own (remote closed the session).
+    Closed,
+}
+
+/// Snapshot of one provider, reported in `ShareStateInfo.tunnels`.
+#[derive(Debug, Serialize, Clone)]
+pub struct TunnelInfo {
+    pub provider: String,
+    pub url: Option<String>,
+    pub status: TunnelStatus,
+}
+
+pub(crate) trait TunnelProvider: Send + Sync {
+    /// Identifier used in share profiles and log prefixes, e.g. "ngrok".
+    fn id(&self) -> &'static str;
+
+    /// Open a tunnel forwarding to `localhost:port` and return its public URL.
+    fn start(&self, port: u16) -> BoxFuture<'_, Result<String, String>>;
+
+    /// Close the tunnel if it is running. Safe to call when stopped.
+    fn stop(&self);
+
+    /// Public URL of the running tunnel.
+    fn url(&self) -> Option<String>;
+
+    fn status(&self) -> TunnelStatus;
+}
+
+static PROVIDERS: Lazy<Vec<Box<dyn TunnelProvider>>> = Lazy::new(|| {
+    vec![
+        Box::new(ngrok::NgrokTunnel::default()),
+        Box::new(cloudflared::CloudflaredTunnel::default()),
+        Box::new(tailscale::TailscaleFunnel::default()),
+        Box::new(frp::FrpTunnel::default()),
+    ]
+});
+
+pub(crate) fn provider(id: &str) -> Option<&'static dyn TunnelProvider> {
+    PROVIDERS.iter().find(|p| p.id() == id).map(|p| p.as_ref())
+}
+
+pub(crate) fn tunnel_infos() -> Vec<TunnelInfo> {
+    PROVIDERS
+        .iter()
+        .map(|p| TunnelInfo {
+            provider: p.id().to_string(),
+            url: p.url(),
+            status: p.status(),
+        })
+        .collect()
+}
+
+/// Public URLs of all running tunnels (allowed as CORS origins).
+pub(crate) fn active_urls() -> Vec<String> {
+    PROVIDERS.iter().filter_map(|p| p.url()).collect()
+}
+
+pub(crate) fn stop_all() {
+    for p in PROVIDERS.iter() {
+        p.stop();
+    }
+}
@@ -0,0 +1,132 @@
+use ngrok::config::ForwarderBuilder; // trait import: provides listen_and_forward()
+use ngrok::forwarder::Forwarder;
+use ngrok::tunnel::{EndpointInfo, HttpTunnel}; // EndpointInfo trait import: provides url()
+
+use futures_util::future::BoxFuture;
+use std::sync::Mutex;
+
+use super::{TunnelProvider, TunnelStatus};
+use crate::config::load_global_config;
+use crate::state::TOKIO_RT;
+
+/// ngrok tunnel via the embedded ngrok agent (requires `ngrok_token` in global config).
+#[derive(Default)]
+pub(crate) struct NgrokTunnel {
+    running: Mutex<Option<(String, tokio::task::JoinHandle<()>)>>,
+}
+
+impl TunnelProvider for NgrokTunnel {
+    fn id(&self) -> &'static str {
+        "ngrok"
+    }
+
+    fn start(&self, port: u16) -> BoxFuture<'_, Result<String, String>> {
+        Box::pin(async move {
+            let ngrok_token = load_global_config()
+                .ngrok_token
+                .ok_or("未配置 ngrok token，请先在设置中配置".to_string())?;
+            log::info!("[ngrok] Token configured, forwarding to port {}", port);
+
+            let (url_tx, url_rx) = std::sync::mpsc::channel::<Result<String, String>>();
+
+            let ngrok_handle = TOKIO_RT.spawn(async move {
+                let result = async {
+                    log::info!("[ngrok] Connecting to ngrok service...");
+                    let mut builder = ngrok::Session::builder();
+                    builder.authtoken(ngrok_token);
+                    if let Some(proxy_url) = crate::network::ngrok_proxy_url() {
+                        log::info!("[ngrok] Connecting through proxy {}", proxy_url);
+                        builder
+                            .proxy_url(proxy_url)
+                            .map_err(|e| format!("ngrok 代理配置无效: {}", e))?;
+                    }
+                    let session = builder
+                        .connect()
+                        .await
+                        .map_err(|e| format!("ngrok 连接失败: {}", e))?;
+                    log::info!(
+                        "[ngrok] Session established, creating HTTP tunnel to localhost:{}",
+                        port
+                    );
+
+                    let forwarder = session
+                        .http_endpoint()
+                        .listen_and_forward(
+                            url::Url::parse(&format!("http://localhost:{}", port))
+                                .map_err(|e| format!("URL 解析失败: {}", e))?,
+                        )
+                        .await
+                        .map_err(|e| format!("ngrok 隧道创建失败: {}", e))?;
+
+                    let ngrok_url = forwarder.url().to_string();
+                    log::info!("[ngrok] Tunnel created, URL: {}", ngrok_url);
+                    Ok::<(String, Forwarder<HttpTunnel>), String>((ngrok_url, forwarder))
+                }
+                .await;
+
+                match result {
+                    Ok((url, mut forwarder)) => {
+                        let _ = url_tx.send(Ok(url));
+                        // join() keeps the forwarder actively forwarding traffic
+                        let _ = forwarder.join().await;
+                        log::info!("[ngrok] Forwarder join() returned, tunnel closed");
+                    }
+                    Err(e) => {
+                        log::error!("[ngrok] Tunnel creation failed: {}", e);
+                        let _ = url_tx.send(Err(e));
+                    }
+                }
+            });
+
+            // Wait for the ngrok URL (with timeout)
+            match url_rx.recv_timeout(std::time::Duration::from_secs(30)) {
+                Ok(Ok(ngrok_url)) => {
+                    let mut running = self
+                        .running
+                        .lock()
+                        .map_err(|_| "Internal state error".to_string())?;
+                    *running = Some((ngrok_url.clone(), ngrok_handle));
+                    Ok(ngrok_url)
+                }
+                Ok(Err(e)) => {
+                    ngrok_handle.abort();
+                    Err(e)
+                }
+                Err(_) => {
+                    log::error!("[ngrok] Tunnel startup timed out after 30s");
+                    ngrok_handle.abort();
+                    Err("ngrok 隧道启动超时".to_string())
+                }
+            }
+        })
+    }
+
+    fn stop(&self) {
+        let Ok(mut running) = self.running.lock() else {
+            return;
+        };
+        if let Some((_, handle)) = running.take() {
+            // abort() is intentional: the ngrok crate's Forwarder does not expose a graceful
+            // shutdown API. Aborting the task triggers its Drop impl, which handles cleanup.
+            handle.abort();
+            log::info!("[ngrok] Tunnel task aborted");
+        }
+    }
+
+    fn url(&self) -> Option<String> {
+        self.running
+            .lock()
+            .ok()?
+            .as_ref()
+            .filter(|(_, handle)| !handle.is_finished())
+            .map(|(url, _)| url.clone())
+    }
+
+    fn status(&self) -> TunnelStatus {
+        match self.running.lock().ok().as_deref() {
+            Some(Some((_, handle))) if handle.is_finished() => TunnelStatus::Closed,
+            Some(Some(_)) => TunnelStatus::Running,
+            _ => TunnelStatus::Stopped,
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use futures_util::future::BoxFuture;
+use std::net::IpAddr;
+use std::process::{Command, Output};
+use std::sync::Mutex;
+
+use super::{TunnelProvider, TunnelStatus};
+
+/// The local node as reported by `tailscale status` (works the same against Headscale).
+pub(crate) struct TailnetNode {
+    pub ips: Vec<IpAddr>,
+    /// MagicDNS name without the trailing dot, e.g. `laptop.tail1234.ts.net`
+    pub dns_name: Option<String>,
+}
+
+/// Run the tailscale CLI (on PATH, or inside the macOS app bundle).
+fn tailscale(args: &[&str]) -> Result<Output, String> {
+    let mut candidates = vec!["tailscale"];
+    if cfg!(target_os = "macos") {
+        candidates.push("/Applications/Tailscale.app/Contents/MacOS/Tailscale");
+    }
+    let mut last_error = String::new();
+    for binary in candidates {
+        let mut cmd = Command::new(binary);
+        cmd.args(args);
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        }
+        match cmd.output() {
+            Ok(output) => return Ok(output),
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+    Err(format!("未找到 tailscale 命令: {}", last_error))
+}
+
+fn stderr_of(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).trim().to_string()
+}
+
+/// This machine's tailnet addresses, or `None` when Tailscale isn't installed or logged in.
+pub(crate) fn local_node() -> Option<TailnetNode> {
+    let output = tailscale(&["status", "--json", "--peers=false"]).ok()?;
+    if !output.status.success() {
+        log::debug!("[tailscale] status failed: {}", stderr_of(&output));
+        return None;
+    }
+    let status: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    if status["BackendState"].as_str() != Some("Running") {
+        return None;
+    }
+    let node = &status["Self"];
+    let ips: Vec<IpAddr> = node["TailscaleIPs"]
+        .as_array()?
+        .iter()
+        .filter_map(|ip| ip.as_str()?.parse().ok())
+        .collect();
+    let dns_name = node["DNSName"]
+        .as_str()
+        .map(|name| name.trim_end_matches('.').to_string())
+        .filter(|name| !name.is_empty());
+    Some(TailnetNode { ips, dns_name })
+}
+
+/// Tailscale Funnel: publishes the share port on `https://<MagicDNS name>` through the tailnet's
+/// public ingress. Funnel has to be allowed for the node in the tailnet policy.
+#[derive(Default)]
+pub(crate) struct TailscaleFunnel {
+    url: Mutex<Option<String>>,
+}
+
+impl TunnelProvider for TailscaleFunnel {
+    fn id(&self) -> &'static str {
+        "tailscale"
+    }
+
+    fn start(&self, port: u16) -> BoxFuture<'_, Result<String, String>> {
+        Box::pin(async move {
+            let url = tokio::task::spawn_blocking(move || {
+                let node = local_node().ok_or("Tailscale 未运行或未登录".to_string())?;
+                let dns_name = node
+                    .dns_name
+                    .ok_or("Tailscale 未启用 MagicDNS，无法使用 Funnel".to_string())?;
+                // Localhost is served over plain HTTP; Funnel terminates TLS with its own cert
+                let target = format!("http://127.0.0.1:{}", port);
+                log::info!("[tailscale] Enabling Funnel for {}", target);
+                let output = tailscale(&["funnel", "--bg", &target])?;
+                if !output.status.success() {
+                    return Err(format!("Tailscale Funnel 启动失败: {}", stderr_of(&output)));
+                }
+                Ok(format!("https://{}", dns_name))
+            })
+            .await
+            .map_err(|e| format!("Task join error: {}", e))??;
+
+            log::info!("[tailscale] Funnel enabled, URL: {}", url);
+            let mut running = self
+                .url
+                .lock()
+                .map_err(|_| "Internal state error".to_string())?;
+            *running = Some(url.clone());
+            Ok(url)
+        })
+    }
+
+    fn stop(&self) {
+        let Ok(mut running) = self.url.lock() else {
+            return;
+        };
+        if running.take().is_some() {
+            match tailscale(&["funnel", "--https=443", "off"]) {
+                Ok(output) if output.status.success() => {
+                    log::info!("[tailscale] Funnel disabled")
+                }
+                Ok(output) => log::warn!(
+                    "[tailscale] Failed to disable Funnel: {}",
+                    stderr_of(&output)
+                ),
+                Err(e) => log::warn!("[tailscale] Failed to disable Funnel: {}", e),
+            }
+        }
+    }
+
+    fn url(&self) -> Option<String> {
+        self.url.lock().ok()?.clone()
+    }
+
+    fn status(&self) -> TunnelStatus {
+        match self.url() {
+            Some(_) => TunnelStatus::Running,
+            None => TunnelStatus::Stopped,
+        }
+    }
+}
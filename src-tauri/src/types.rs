@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -13,8 +13,6 @@ pub struct ShareState {
     pub auth_key: Option<Vec<u8>>,   // PBKDF2 derived key (32 bytes)
     pub auth_salt: Option<Vec<u8>>,  // PBKDF2 salt (16 bytes)
     pub shutdown_tx: Option<tokio::sync::watch::Sender<bool>>,
-    pub ngrok_url: Option<String>,
-    pub ngrok_task: Option<tokio::task::JoinHandle<()>>,
     pub wms_url: Option<String>,
     pub wms_task: Option<tokio::task::JoinHandle<()>>,
     /// Signal to gracefully shut down the WMS tunnel (sends WebSocket Close frame).
@@ -28,6 +26,14 @@ pub struct ShareState {
     pub wms_manual_reconnect_tx: Option<tokio::sync::mpsc::UnboundedSender<()>>,
     /// Whether LAN sharing was auto-started by WMS tunnel (should auto-stop when WMS stops).
     pub wms_auto_started_lan: bool,
+    /// Interface the server is bound to (None = all interfaces).
+    pub bind_ip: Option<std::net::IpAddr>,
+    /// Name of the share profile used to start sharing, if any.
+    pub profile_name: Option<String>,
+    /// Permission granted to remote clients.
+    pub role: ShareRole,
+    /// Worktrees remote clients may access (empty = all).
+    pub allowed_worktrees: Vec<String>,
 }
 
 impl Default for ShareState {
@@ -39,8 +45,6 @@ impl Default for ShareState {
             auth_key: None,
             auth_salt: None,
             shutdown_tx: None,
-            ngrok_url: None,
-            ngrok_task: None,
             wms_url: None,
             wms_task: None,
             wms_shutdown_tx: None,
@@ -48,10 +52,56 @@ impl Default for ShareState {
             wms_reconnect_state: None,
             wms_manual_reconnect_tx: None,
             wms_auto_started_lan: false,
+            bind_ip: None,
+            profile_name: None,
+            role: ShareRole::default(),
+            allowed_worktrees: vec![],
+        }
+    }
+}
+
+/// 远程客户端的权限，按声明顺序由低到高
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ShareRole {
+    Viewer,   // 只读：所有修改类 API 被拒绝
+    Operator, // 可使用终端、git 和 worktree 操作，不能修改工作区配置和分享设置
+    #[default]
+    Admin,
+}
+
+impl ShareRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ShareRole::Viewer => "viewer",
+            ShareRole::Operator => "operator",
+            ShareRole::Admin => "admin",
         }
     }
 }
 
+/// 命名分享配置：保存端口、监听地址、权限、可访问的 worktree 和隧道，切换时无需重新填写
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShareProfile {
+    pub name: String,
+    pub port: u16,
+    #[serde(default)]
+    pub bind_address: Option<String>, // 监听地址，如 "192.168.1.10"；为空则监听所有网卡
+    #[serde(default)]
+    pub role: ShareRole,
+    #[serde(default)]
+    pub allowed_worktrees: Vec<String>, // 为空表示不限制
+    #[serde(default)]
+    pub tunnel: Option<String>, // "ngrok" | "cloudflare" | "tailscale" | "frp" | "wms"
+}
+
+/// 已认证的远程 session：权限 + 最近一次 API 调用时间（用于空闲过期）
+#[derive(Debug, Clone, Copy)]
+pub struct AuthenticatedSession {
+    pub role: ShareRole,
+    pub last_seen: Instant,
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct ConnectedClient {
     pub session_id: String,
@@ -60,6 +110,22 @@ pub struct ConnectedClient {
     pub authenticated_at: String,
     pub last_active: String,
     pub ws_connected: bool,
+    #[serde(default)]
+    pub quarantined: bool,
+    #[serde(default)]
+    pub role: ShareRole, // 认证时取分享配置的权限，之后可由主机单独调整
+}
+
+/// 分享期间远程会话的一次 API 调用摘要（推送到桌面端活动流）
+#[derive(Debug, Clone, Serialize)]
+pub struct ShareActivity {
+    pub session_id: String,
+    pub ip: String,
+    pub command: String,
+    pub summary: Option<String>, // 关键参数，如 worktreeName=feat-x
+    pub status: u16,
+    pub duration_ms: u64,
+    pub at: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,6 +134,17 @@ pub struct TerminalState {
     pub active_terminal_tab: Option<String>,
     pub terminal_visible: bool,
     pub client_id: Option<String>,
+    #[serde(default)]
+    pub tab_labels: BTreeMap<String, String>, // 终端路径 → 标签名（终端预设打开的终端）
+}
+
+/// 桌面端当前聚焦的 worktree/project（演示模式下网页端跟随）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusState {
+    pub worktree_name: Option<String>,
+    pub project_name: Option<String>,
+    pub client_id: Option<String>,
+    pub updated_at: String,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -75,6 +152,7 @@ pub struct ShareStateInfo {
     pub active: bool,
     pub urls: Vec<String>,
     pub ngrok_url: Option<String>,
+    pub tunnels: Vec<crate::tunnel::TunnelInfo>, // 可插拔隧道（ngrok 等）的状态
     pub wms_url: Option<String>,
     pub wms_connected: bool,
     pub wms_reconnecting: bool,
@@ -82,6 +160,7 @@ pub struct ShareStateInfo {
     pub wms_next_retry_secs: u32,
     pub workspace_path: Option<String>,
     pub current_workspace_name: Option<String>,
+    pub profile_name: Option<String>,
 }
 
 // Auth rate limiter: per-IP sliding window (max 5 attempts per 60 seconds)
@@ -124,6 +203,46 @@ impl AuthRateLimiter {
     }
 }
 
+// API rate limiter: token bucket per key (session or IP), refilled at `rate` tokens/s up to `burst`
+pub struct ApiRateLimiter {
+    buckets: HashMap<String, (f64, Instant)>, // key -> (tokens, last refill)
+    /// Cached copy of the global config's limits (checked on every request and keystroke)
+    pub limits: RateLimitConfig,
+}
+
+impl ApiRateLimiter {
+    pub fn new(limits: RateLimitConfig) -> Self {
+        Self {
+            buckets: HashMap::new(),
+            limits,
+        }
+    }
+
+    /// Returns true if the request is allowed, false if rate-limited.
+    pub fn check(&mut self, key: &str, rate: u32, burst: u32) -> bool {
+        if self.buckets.len() > 1000 {
+            self.cleanup();
+        }
+        let burst = burst.max(1) as f64;
+        let now = Instant::now();
+        let (tokens, last) = self.buckets.entry(key.to_string()).or_insert((burst, now));
+        *tokens = (*tokens + now.duration_since(*last).as_secs_f64() * rate as f64).min(burst);
+        *last = now;
+        if *tokens < 1.0 {
+            return false;
+        }
+        *tokens -= 1.0;
+        true
+    }
+
+    /// Drop buckets idle long enough to have refilled completely (call periodically)
+    pub fn cleanup(&mut self) {
+        let now = Instant::now();
+        self.buckets
+            .retain(|_, (_, last)| now.duration_since(*last) < Duration::from_secs(300));
+    }
+}
+
 // Nonce cache for challenge-response authentication (one-time use, 60s TTL)
 pub struct NonceCache {
     entries: HashMap<String, (Instant, Vec<u8>)>, // nonce_hex -> (created_at, nonce_bytes)
@@ -171,8 +290,7 @@ impl NonceCache {
 pub struct GlobalConfig {
     pub workspaces: Vec<WorkspaceRef>,
     pub current_workspace: Option<String>, // 当前选中的 workspace 路径
-    // TODO(security): ngrok_token is stored in plaintext in the config file.
-    // Consider using the OS keychain (e.g., keytar/keyring crate) for sensitive credentials.
+    // 敏感字段默认明文存储，开启 encrypt_config 后加密写入（见 secrets.rs）
     #[serde(default)]
     pub ngrok_token: Option<String>,
     #[serde(default)]
@@ -191,6 +309,236 @@ pub struct GlobalConfig {
     pub voice_refine_enabled: bool,
     #[serde(default)]
     pub device_id: Option<String>,
+    #[serde(default)]
+    pub focus_follow_enabled: bool, // 是否向网页端广播桌面端聚焦（演示跟随）
+    #[serde(default)]
+    pub proxy: ProxyConfig, // 企业网络代理与自定义 CA
+    #[serde(default)]
+    pub share_profiles: Vec<ShareProfile>, // 命名分享配置
+    #[serde(default)]
+    pub persist_terminal_scrollback: bool, // 终端输出写入磁盘，重启/重连后可恢复历史
+    #[serde(default)]
+    pub restore_terminal_sessions: bool, // 启动时在原目录重建上次打开的终端
+    #[serde(default)]
+    pub pty_limits: PtyLimits,
+    #[serde(default)]
+    pub ssh_hosts: Vec<SshHost>, // 可打开远程终端的 SSH 主机
+    #[serde(default)]
+    pub api_tokens: Vec<ApiToken>, // 脚本 / CI 使用的 API token
+    #[serde(default)]
+    pub totp_secret: Option<String>, // base32；登记后需用验证码确认才会启用
+    #[serde(default)]
+    pub totp_enabled: bool, // 分享认证需要密码 + 6 位动态验证码
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default)]
+    pub custom_tls: Option<CustomTlsConfig>, // 为空时分享服务使用自签名证书
+    #[serde(default)]
+    pub cloudflare_tunnel: CloudflareTunnelConfig,
+    #[serde(default)]
+    pub frp_tunnel: FrpTunnelConfig,
+    #[serde(default = "default_session_ttl_mins")]
+    pub session_ttl_mins: u32, // 远程 session 空闲多久后过期需重新登录，0 表示不过期
+    #[serde(default)]
+    pub encrypt_config: bool, // 全局和 workspace 配置中的 token、密码、环境变量等加密存储，密钥在系统钥匙串中
+    #[serde(default)]
+    pub issue_tracker_auth: IssueTrackerAuth, // 查询 Jira / Linear 工单标题和状态用的凭据
+    #[serde(default)]
+    pub webhook_secret: Option<String>, // GitHub / GitLab webhook 的 secret，为空时 /api/webhook/git 不可用
+    #[serde(default)]
+    pub notifiers: Vec<NotifierConfig>, // Slack / Discord 频道通知
+    #[serde(default)]
+    pub editors: Vec<CustomEditor>, // 自定义编辑器，与内置编辑器同名时覆盖内置
+}
+
+fn default_session_ttl_mins() -> u32 {
+    24 * 60
+}
+
+/// Cloudflare Tunnel（cloudflared）设置：不填 token 时使用无需账号的临时隧道（trycloudflare.com）
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CloudflareTunnelConfig {
+    #[serde(default)]
+    pub enabled: bool, // 在分享栏显示 Cloudflare 隧道开关
+    #[serde(default)]
+    pub binary_path: Option<String>, // cloudflared 可执行文件路径，默认从 PATH 查找
+    #[serde(default)]
+    pub token: Option<String>, // 命名隧道的 token
+    #[serde(default)]
+    pub hostname: Option<String>, // 命名隧道在控制台绑定的公网域名
+}
+
+/// frp 隧道（frpc）设置：连接自建的 frps。配置了 remote_port 时使用 TCP 代理，否则按
+/// custom_domain 使用 HTTP 代理
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FrpTunnelConfig {
+    #[serde(default)]
+    pub enabled: bool, // 在分享栏显示 frp 隧道开关
+    #[serde(default)]
+    pub binary_path: Option<String>, // frpc 可执行文件路径，默认从 PATH 查找
+    #[serde(default)]
+    pub server_addr: String,
+    #[serde(default = "default_frp_server_port")]
+    pub server_port: u16,
+    #[serde(default)]
+    pub token: Option<String>, // frps 的 auth.token
+    #[serde(default)]
+    pub remote_port: Option<u16>, // TCP 代理在 frps 上占用的端口
+    #[serde(default)]
+    pub custom_domain: Option<String>, // HTTP 代理的域名（需 frps 配置 vhostHTTPPort）
+    #[serde(default)]
+    pub public_url: Option<String>, // 对外访问地址，为空时按代理类型推导
+}
+
+fn default_frp_server_port() -> u16 {
+    7000
+}
+
+impl Default for FrpTunnelConfig {
+    fn default() -> Self {
+        FrpTunnelConfig {
+            enabled: false,
+            binary_path: None,
+            server_addr: String::new(),
+            server_port: default_frp_server_port(),
+            token: None,
+            remote_port: None,
+            custom_domain: None,
+            public_url: None,
+        }
+    }
+}
+
+/// 分享服务使用的自定义证书（如内部 CA 签发），避免每台设备都出现证书警告
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CustomTlsConfig {
+    pub cert_path: String, // PEM 证书链，或 .p12 / .pfx 文件
+    #[serde(default)]
+    pub key_path: Option<String>, // PEM 私钥；PKCS#12 时不需要
+    #[serde(default)]
+    pub password: Option<String>, // PKCS#12 密码
+}
+
+/// 远程客户端的请求限流（已认证的 session 按 session，其余按 IP）：令牌桶，每秒补充 `*_per_sec` 个，最多积累 `*_burst` 个
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RateLimitConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_api_requests_per_sec")]
+    pub api_requests_per_sec: u32, // 所有 /api 请求
+    #[serde(default = "default_api_burst")]
+    pub api_burst: u32,
+    #[serde(default = "default_pty_writes_per_sec")]
+    pub pty_writes_per_sec: u32, // WebSocket 终端输入
+    #[serde(default = "default_pty_write_burst")]
+    pub pty_write_burst: u32,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            api_requests_per_sec: default_api_requests_per_sec(),
+            api_burst: default_api_burst(),
+            pty_writes_per_sec: default_pty_writes_per_sec(),
+            pty_write_burst: default_pty_write_burst(),
+        }
+    }
+}
+
+fn default_api_requests_per_sec() -> u32 {
+    20
+}
+
+fn default_api_burst() -> u32 {
+    100
+}
+
+fn default_pty_writes_per_sec() -> u32 {
+    200
+}
+
+fn default_pty_write_burst() -> u32 {
+    1000
+}
+
+/// TOTP 登记结果：secret 用于手动输入，otpauth_url 可导入验证器 App
+#[derive(Debug, Serialize, Clone)]
+pub struct TotpEnrollment {
+    pub secret: String,
+    pub otpauth_url: String,
+}
+
+/// 长期有效的 API token：通过 `Authorization: Bearer` 调用 HTTP API，无需交互式密码认证。
+/// 只保存 SHA-256 哈希，明文仅在创建时返回一次
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApiToken {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub role: ShareRole,
+    pub token_hash: String, // hex
+    pub created_at: String,
+}
+
+/// 终端资源限制，防止远程客户端无限制地打开 shell；0 表示不限制
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+pub struct PtyLimits {
+    #[serde(default)]
+    pub max_sessions: u32, // 同时打开的终端数上限，超出时拒绝新建
+    #[serde(default)]
+    pub idle_timeout_mins: u32, // 无输入输出超过该分钟数的终端自动关闭
+}
+
+/// SSH 主机：在远程构建机上打开终端，通过系统 ssh 客户端连接（认证使用本机的 ssh 配置、agent 与密钥）
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SshHost {
+    pub name: String, // 显示名，pty_create_ssh 按名称或 host 查找
+    pub host: String, // 主机名、IP 或 ~/.ssh/config 中的别名
+    #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub identity_file: Option<String>,
+    #[serde(default)]
+    pub cwd: Option<String>, // 默认远程目录，未设置时为登录目录
+}
+
+/// 网络代理与自定义 CA 设置，作用于 git 子进程、libgit2、ngrok 以及应用内 HTTP 请求
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ProxyConfig {
+    #[serde(default)]
+    pub http_proxy: Option<String>,
+    #[serde(default)]
+    pub https_proxy: Option<String>,
+    #[serde(default)]
+    pub no_proxy: Option<String>, // 逗号分隔，如 "localhost,127.0.0.1,.corp.example.com"
+    #[serde(default)]
+    pub ca_cert_path: Option<String>, // PEM 格式的 CA 证书（可包含多个）
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvironmentCheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// 单项环境检查结果（check_environment 返回给首次启动引导展示）
+#[derive(Debug, Serialize, Clone)]
+pub struct EnvironmentCheck {
+    pub id: String, // git / gh / credential_helper / symlink / disk_space
+    pub status: EnvironmentCheckStatus,
+    pub detail: String,
+    pub fix: Option<String>, // 非 ok 时的修复建议
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct EnvironmentReport {
+    pub checks: Vec<EnvironmentCheck>,
+    pub ready: bool, // 没有 error 级别的检查项
 }
 
 fn default_true() -> bool {
@@ -217,10 +565,56 @@ impl Default for GlobalConfig {
             dashscope_base_url: None,
             voice_refine_enabled: true,
             device_id: None,
+            focus_follow_enabled: false,
+            proxy: ProxyConfig::default(),
+            share_profiles: vec![],
+            persist_terminal_scrollback: false,
+            restore_terminal_sessions: false,
+            pty_limits: PtyLimits::default(),
+            ssh_hosts: vec![],
+            api_tokens: vec![],
+            totp_secret: None,
+            totp_enabled: false,
+            rate_limit: RateLimitConfig::default(),
+            custom_tls: None,
+            cloudflare_tunnel: CloudflareTunnelConfig::default(),
+            frp_tunnel: FrpTunnelConfig::default(),
+            session_ttl_mins: default_session_ttl_mins(),
+            encrypt_config: false,
+            issue_tracker_auth: IssueTrackerAuth::default(),
+            webhook_secret: None,
+            notifiers: vec![],
+            editors: vec![],
         }
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifierProvider {
+    Slack,
+    Discord,
+}
+
+/// 发送到聊天频道的事件
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyEvent {
+    MergeToTestSucceeded,
+    MergeToTestFailed,
+    WorktreeArchived,
+    ShareStarted,
+}
+
+/// Slack / Discord incoming webhook，发送失败只记录日志
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NotifierConfig {
+    pub provider: NotifierProvider,
+    pub webhook_url: String,
+    #[serde(default)]
+    pub events: Vec<NotifyEvent>, // 只通知这些事件，空表示全部
+}
+
 // Workspace 配置：存储在 {workspace_root}/.worktree-manager.json
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WorkspaceConfig {
@@ -229,107 +623,769 @@ pub struct WorkspaceConfig {
     pub projects: Vec<ProjectConfig>,
     #[serde(default = "default_linked_workspace_items")]
     pub linked_workspace_items: Vec<String>, // 要链接到每个 worktree 的全局文件/文件夹
+    #[serde(default = "default_archive_ignore_patterns")]
+    pub archive_ignore_patterns: Vec<String>, // 归档检查时忽略的未提交文件，如 ".DS_Store"
+    #[serde(default)]
+    pub existing_dir_policy: ExistingDirPolicy, // 链接目标已是真实目录时的处理方式
+    #[serde(default)]
+    pub auto_lock: AutoLockConfig, // 终端输入时自动锁定 worktree，空闲后释放
+    #[serde(default)]
+    pub disk_quota_mb: Option<u64>, // 所有 worktree 的磁盘占用上限（MB），超出时提醒
+    #[serde(default)]
+    pub base_update: BaseUpdateConfig, // 定期检查 worktree 是否落后 base 分支
+    #[serde(default)]
+    pub quick_commands: Vec<QuickCommand>, // 终端快捷命令，在 worktree 的终端中一键执行
+    #[serde(default)]
+    pub hooks: Vec<HookConfig>, // 生命周期钩子，按配置顺序执行（旧版的 post_create_commands / pre_archive_commands 加载时迁移至此）
+    #[serde(default)]
+    pub port_allocation: PortAllocationConfig, // 为每个 worktree 分配独立端口段，注入到终端环境变量
+    #[serde(default)]
+    pub kill_processes_on_archive: bool, // 归档/删除前结束工作目录在该 worktree 内的所有进程（包括外部终端启动的）
+    #[serde(default)]
+    pub shell: Option<ShellConfig>, // 终端使用的 shell，未配置时使用系统默认 shell
+    #[serde(default)]
+    pub issue_trackers: Vec<IssueTrackerConfig>, // 从 worktree / 分支名中识别工单号，按配置顺序匹配
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>, // worktree 生命周期事件完成后 POST JSON 到这些地址
 }
 
-pub fn default_linked_workspace_items() -> Vec<String> {
-    vec![
-        ".claude".to_string(),
-        "CLAUDE.md".to_string(),
-        "AGENTS.md".to_string(),
-        "requirement-docs".to_string(),
-    ]
+/// 终端 shell：程序名（在 PATH 中查找）或绝对路径，加启动参数，如 `{"program": "zsh", "args": ["-l"]}`
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct ShellConfig {
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// Workspace 配置及其版本标识，保存时回传 etag 用于并发检测
+#[derive(Debug, Serialize, Clone)]
+pub struct VersionedWorkspaceConfig {
+    pub config: WorkspaceConfig,
+    pub etag: String,
+}
+
+/// 配置校验发现的问题（不阻止保存，仅提示）
+#[derive(Debug, Serialize)]
+pub struct ConfigWarning {
+    pub project_name: Option<String>,
+    pub message: String,
 }
 
+/// 终端活动自动锁：客户端在某个 worktree 的终端中输入时自动获取锁，空闲超时后自动释放
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct ProjectConfig {
-    pub name: String,
-    pub base_branch: String,
-    pub test_branch: String,
-    pub merge_strategy: String,
+pub struct AutoLockConfig {
     #[serde(default)]
-    pub linked_folders: Vec<String>, // 要链接的文件夹列表
+    pub enabled: bool,
+    #[serde(default = "default_auto_lock_idle_secs")]
+    pub idle_secs: u64,
 }
 
-impl Default for WorkspaceConfig {
+impl Default for AutoLockConfig {
     fn default() -> Self {
         Self {
-            name: "New Workspace".to_string(),
-            worktrees_dir: "worktrees".to_string(),
-            projects: vec![],
-            linked_workspace_items: default_linked_workspace_items(),
+            enabled: false,
+            idle_secs: default_auto_lock_idle_secs(),
         }
     }
 }
 
-// ==================== 数据结构 ====================
+fn default_auto_lock_idle_secs() -> u64 {
+    300
+}
 
-#[derive(Debug, Serialize)]
-pub struct WorktreeListItem {
-    pub name: String,
-    pub path: String,
-    pub is_archived: bool,
-    pub projects: Vec<ProjectStatus>,
+/// Base 分支更新检测：定期 fetch 各项目的 base 分支，worktree 落后超过阈值时提醒
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BaseUpdateConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_base_update_threshold")]
+    pub behind_threshold: usize,
+    #[serde(default = "default_base_update_interval_mins")]
+    pub interval_mins: u64,
 }
 
-#[derive(Debug, Serialize)]
-pub struct ProjectStatus {
-    pub name: String,
-    pub path: String,
-    pub current_branch: String,
-    pub base_branch: String,
-    pub test_branch: String,
-    pub has_uncommitted: bool,
-    pub uncommitted_count: usize,
-    pub is_merged_to_test: bool,
-    pub ahead_of_base: usize,
-    pub behind_base: usize,
+impl Default for BaseUpdateConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            behind_threshold: default_base_update_threshold(),
+            interval_mins: default_base_update_interval_mins(),
+        }
+    }
 }
 
-#[derive(Debug, Serialize)]
-pub struct MainWorkspaceStatus {
-    pub path: String,
-    pub name: String,
-    pub projects: Vec<MainProjectStatus>,
+fn default_base_update_threshold() -> usize {
+    10
 }
 
-#[derive(Debug, Serialize)]
-pub struct MainProjectStatus {
-    pub name: String,
-    pub path: String,
-    pub current_branch: String,
-    pub has_uncommitted: bool,
-    pub uncommitted_count: usize,
-    pub is_merged_to_test: bool,
-    pub ahead_of_base: usize,
-    pub behind_base: usize,
-    pub base_branch: String,
-    pub test_branch: String,
-    pub linked_folders: Vec<String>,
+fn default_base_update_interval_mins() -> u64 {
+    15
 }
 
-// ==================== 智能软链接扫描 ====================
+/// 端口分配：每个 worktree 从 `base_port` 起分到一段 `ports_per_worktree` 个端口，
+/// 多个 worktree 同时跑 dev server 时不再抢同一个端口
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PortAllocationConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_port_base")]
+    pub base_port: u16,
+    #[serde(default = "default_ports_per_worktree")]
+    pub ports_per_worktree: u16,
+}
 
-#[derive(Debug, Serialize, Clone)]
-pub struct ScannedFolder {
-    pub relative_path: String, // e.g. "packages/web/node_modules"
-    pub display_name: String,  // e.g. "node_modules"
-    pub size_bytes: u64,
-    pub size_display: String, // e.g. "256.3 MB"
-    pub is_recommended: bool, // 推荐预选
+impl Default for PortAllocationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            base_port: default_port_base(),
+            ports_per_worktree: default_ports_per_worktree(),
+        }
+    }
 }
 
-// ==================== Worktree 操作数据结构 ====================
+fn default_port_base() -> u16 {
+    4000
+}
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CreateWorktreeRequest {
-    pub name: String,
-    pub projects: Vec<CreateProjectRequest>,
+fn default_ports_per_worktree() -> u16 {
+    10
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CreateProjectRequest {
+/// 终端快捷命令（如 "start api"、"run tests"）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QuickCommand {
+    pub label: String,
+    pub command: String,
+    /// 在 worktree 的哪个项目目录下执行，None 表示 worktree 根目录
+    #[serde(default)]
+    pub project: Option<String>,
+    /// 是否允许通过分享连接的远程客户端执行（仍需 admin 权限）
+    #[serde(default = "default_true")]
+    pub allow_remote: bool,
+}
+
+/// 工单系统：None 只生成链接，Jira / Linear 还会查询工单标题和状态
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueProvider {
+    #[default]
+    None,
+    Jira,
+    Linear,
+}
+
+/// 工单链接规则，如 `{"pattern": "PROJ-\\d+", "url_template": "https://acme.atlassian.net/browse/{key}"}`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IssueTrackerConfig {
+    /// 正则表达式；有捕获组时取第一个捕获组作为工单号，否则取整个匹配
+    pub pattern: String,
+    /// 工单链接，`{key}` 替换为工单号
+    pub url_template: String,
+    #[serde(default)]
+    pub provider: IssueProvider,
+    /// Jira API 地址（如 `https://acme.atlassian.net`），为空时取 url_template 的域名
+    #[serde(default)]
+    pub api_base_url: Option<String>,
+}
+
+/// 工单系统凭据（存储在全局配置中，开启 encrypt_config 后加密）
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct IssueTrackerAuth {
+    /// Jira Cloud 账号邮箱，与 jira_token 组成 Basic 认证；为空时 jira_token 作为 Bearer token（Jira Server PAT）
+    #[serde(default)]
+    pub jira_email: Option<String>,
+    #[serde(default)]
+    pub jira_token: Option<String>,
+    #[serde(default)]
+    pub linear_api_key: Option<String>,
+}
+
+/// get_worktree_issue 的结果：从 worktree 名称或分支名中识别出的工单
+#[derive(Debug, Serialize, Clone)]
+pub struct WorktreeIssue {
+    pub key: String,
+    pub url: String,
+    pub provider: IssueProvider,
+    pub title: Option<String>,
+    pub status: Option<String>,
+    pub error: Option<String>, // 查询标题/状态失败的原因，链接仍可使用
+}
+
+/// 项目的命名终端预设：在项目目录（或其子目录）新开终端并运行启动命令，标签显示预设名
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TerminalPreset {
+    pub name: String,
+    #[serde(default)]
+    pub command: String, // 启动命令，为空时只打开终端
+    #[serde(default)]
+    pub cwd: Option<String>, // 相对项目目录的子目录，如 "packages/web"
+}
+
+/// run_quick_command 的结果：前端据此打开/切换到对应的终端标签
+#[derive(Debug, Serialize)]
+pub struct QuickCommandRun {
+    pub session_id: String,
+    pub terminal_path: String,
+}
+
+/// PTY 终端（含快捷命令、任务）中运行的子进程；终端 shell 本身不计入
+#[derive(Debug, Serialize, Clone)]
+pub struct RunningProcess {
+    pub pid: u32,
+    pub worktree_name: String,
+    pub session_id: String,
+    pub command: String,
+    pub cpu_percent: Option<f32>,
+    pub memory_kb: Option<u64>,
+    pub uptime_secs: Option<u64>,
+}
+
+/// kill_worktree_processes 结束的进程（工作目录在 worktree 内，包括外部终端启动的）
+#[derive(Debug, Serialize, Clone)]
+pub struct KilledProcess {
+    pub pid: u32,
+    pub name: String,
+    pub command: String,
+    pub cwd: String,
+    /// 宽限期内没有响应 SIGTERM，被强制结束
+    pub forced: bool,
+    pub killed: bool,
+}
+
+/// 终端录制（asciicast v2 .cast 文件，保存在 worktree 的 .recordings 目录）
+#[derive(Debug, Serialize, Clone)]
+pub struct TerminalRecording {
+    pub file_name: String,
+    pub path: String,
+    /// 所属 worktree；主工作区终端的录制为 None
+    pub worktree_name: Option<String>,
+    pub title: Option<String>,
+    pub started_at: Option<String>, // RFC 3339
+    pub duration_secs: f64,
+    pub size_bytes: u64,
+    /// 正在录制的终端会话
+    pub active_session_id: Option<String>,
+}
+
+/// 终端输出中的一处匹配
+#[derive(Debug, Serialize, Clone)]
+pub struct OutputMatch {
+    pub line: usize,           // 从保留输出开头算起的行号（0 起）
+    pub lines_from_end: usize, // 距最后一行的行数（0 为最后一行）
+    pub text: String,          // 去除控制序列后的行内容
+}
+
+/// 终端输出搜索结果
+#[derive(Debug, Serialize, Clone)]
+pub struct OutputSearchResult {
+    pub total_lines: usize,
+    pub matches: Vec<OutputMatch>,
+    /// 匹配数超过上限，只返回了最近的部分
+    pub truncated: bool,
+}
+
+/// 重启后恢复的终端会话
+#[derive(Debug, Serialize, Clone)]
+pub struct RestoredTerminal {
+    pub session_id: String,
+    /// 前端终端标签路径（复制的标签带 `#` 后缀）
+    pub terminal_path: String,
+    /// 所属 worktree；主工作区终端为 None
+    pub worktree_name: Option<String>,
+}
+
+/// 终端命令历史（同一命令合并为一条）
+#[derive(Debug, Serialize, Clone)]
+pub struct CommandHistoryEntry {
+    pub command: String,
+    /// 最近一次执行时的工作目录
+    pub cwd: String,
+    /// 所属 worktree；主工作区终端的历史为 None
+    pub worktree_name: Option<String>,
+    pub last_run_at: String, // RFC 3339
+    /// 执行次数
+    pub count: u32,
+}
+
+/// 生命周期钩子触发的事件。on_archive / on_delete 在操作前执行，失败会中止操作；
+/// 其余在操作完成后执行，失败只记录在结果中
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    OnCreate,
+    OnArchive,
+    OnRestore,
+    OnDelete,
+    OnDeployToMain,
+}
+
+impl HookEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HookEvent::OnCreate => "on_create",
+            HookEvent::OnArchive => "on_archive",
+            HookEvent::OnRestore => "on_restore",
+            HookEvent::OnDelete => "on_delete",
+            HookEvent::OnDeployToMain => "on_deploy_to_main",
+        }
+    }
+
+    /// 是否在操作前执行并可中止操作
+    pub fn is_blocking(self) -> bool {
+        matches!(self, HookEvent::OnArchive | HookEvent::OnDelete)
+    }
+}
+
+/// 一条生命周期钩子配置，命令通过 `sh -c`（Windows 为 `cmd /C`）执行，
+/// 环境变量 WM_HOOK_EVENT / WM_WORKSPACE_* / WM_WORKTREE_* / WM_PROJECTS 描述上下文
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HookConfig {
+    pub event: HookEvent,
+    pub command: String,
+    #[serde(default)]
+    pub per_project: bool, // true: 在每个项目目录中各执行一次（额外提供 WM_PROJECT_NAME / WM_PROJECT_PATH）；false: 在 worktree 根目录执行一次
+    #[serde(default)]
+    pub projects: Vec<String>, // per_project 时只在这些项目中执行，空表示全部
+    #[serde(default)]
+    pub timeout_secs: Option<u64>, // 超时秒数，默认 300
+}
+
+/// 生命周期事件的 webhook：操作成功后在后台 POST JSON，发送失败只记录日志
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WebhookConfig {
+    pub url: String,
+    #[serde(default)]
+    pub events: Vec<HookEvent>, // 只发送这些事件，空表示全部
+    #[serde(default)]
+    pub secret: Option<String>, // 设置后请求头带 X-Worktree-Manager-Signature-256: sha256=<HMAC-SHA256(body)>
+}
+
+/// 一次钩子执行的结果，随操作结果返回给调用方
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HookRun {
+    pub event: HookEvent,
+    pub command: String,
+    pub project: Option<String>,
+    pub cwd: String,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    /// stdout + stderr 末尾部分；超时或无法启动时为错误信息
+    pub output: String,
+}
+
+/// 创建软链接时目标位置已存在真实目录（如先执行过 npm install）的处理策略
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ExistingDirPolicy {
+    /// 保留真实目录，不创建链接
+    #[default]
+    Skip,
+    /// 删除真实目录，替换为链接
+    Replace,
+    /// 将真实目录中主项目没有的内容移入主项目，再替换为链接
+    Merge,
+}
+
+/// 复制（而非软链接）到 worktree 的项目文件，如各 worktree 内容略有不同的 `.env.local`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CopyRule {
+    pub path: String, // 相对项目目录的文件或目录，通常被 gitignore
+    #[serde(default)]
+    pub overwrite: CopyOverwritePolicy,
+}
+
+/// worktree 中已存在同名文件时的处理策略
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CopyOverwritePolicy {
+    /// 保留 worktree 中的文件（可能已按 worktree 修改过）
+    #[default]
+    Skip,
+    /// 主项目的文件比 worktree 中的新时覆盖
+    IfNewer,
+    /// 总是用主项目的文件覆盖
+    Always,
+}
+
+pub fn default_linked_workspace_items() -> Vec<String> {
+    vec![
+        ".claude".to_string(),
+        "CLAUDE.md".to_string(),
+        "AGENTS.md".to_string(),
+        "requirement-docs".to_string(),
+    ]
+}
+
+pub fn default_archive_ignore_patterns() -> Vec<String> {
+    vec![
+        ".DS_Store".to_string(),
+        "Thumbs.db".to_string(),
+        "desktop.ini".to_string(),
+    ]
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProjectConfig {
+    pub name: String,
+    pub base_branch: String,
+    pub test_branch: String,
+    pub merge_strategy: String,
+    #[serde(default)]
+    pub linked_folders: Vec<String>, // 要链接的文件夹列表
+    #[serde(default)]
+    pub copy_rules: Vec<CopyRule>, // 新建、恢复 worktree 和同步链接时从主项目复制的文件
+    #[serde(default)]
+    pub status_excludes: Vec<String>, // 状态扫描时跳过的路径（gitignore 语法），如 "coverage/"
+    #[serde(default)]
+    pub skip_lfs: bool, // 创建 worktree / 克隆时不下载 Git LFS 对象（只保留指针文件）
+    #[serde(default)]
+    pub sparse_paths: Vec<String>, // 非空时 worktree 只检出这些目录（git sparse-checkout）
+    #[serde(default = "default_remote", deserialize_with = "deserialize_remote")]
+    pub remote: String, // 拉取基础分支、推送和计算 ahead/behind 使用的远程，fork 项目可设为 upstream
+    #[serde(default)]
+    pub quick_commands: Vec<QuickCommand>, // 项目级快捷命令，在 worktree 中该项目的目录下执行（忽略 project 字段）
+    #[serde(default)]
+    pub tasks: BTreeMap<String, String>, // 命名任务：名称 → 命令，如 "dev": "pnpm dev"，通过 run_task 在新终端中运行
+    #[serde(default)]
+    pub terminal_presets: Vec<TerminalPreset>, // 命名终端预设，通过 open_terminal_preset 打开
+    #[serde(default)]
+    pub shell: Option<ShellConfig>, // 该项目目录下终端使用的 shell，覆盖 workspace 级配置
+    #[serde(default)]
+    pub env: BTreeMap<String, String>, // 注入该项目目录下终端的环境变量，如 "NODE_ENV": "development"
+}
+
+pub fn default_remote() -> String {
+    "origin".to_string()
+}
+
+/// 设置页清空输入框会保存空字符串，按 origin 处理
+fn deserialize_remote<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let remote = String::deserialize(deserializer)?;
+    let remote = remote.trim();
+    Ok(if remote.is_empty() { default_remote() } else { remote.to_string() })
+}
+
+impl Default for WorkspaceConfig {
+    fn default() -> Self {
+        Self {
+            name: "New Workspace".to_string(),
+            worktrees_dir: "worktrees".to_string(),
+            projects: vec![],
+            linked_workspace_items: default_linked_workspace_items(),
+            archive_ignore_patterns: default_archive_ignore_patterns(),
+            existing_dir_policy: ExistingDirPolicy::Skip,
+            auto_lock: AutoLockConfig::default(),
+            disk_quota_mb: None,
+            base_update: BaseUpdateConfig::default(),
+            quick_commands: vec![],
+            hooks: vec![],
+            port_allocation: PortAllocationConfig::default(),
+            kill_processes_on_archive: false,
+            shell: None,
+            issue_trackers: vec![],
+            webhooks: vec![],
+        }
+    }
+}
+
+// ==================== 数据结构 ====================
+
+#[derive(Debug, Serialize)]
+pub struct WorktreeListItem {
+    pub name: String,
+    pub path: String,
+    pub rel_path: String, // 相对 workspace 根目录、以 / 分隔，客户端用它寻址；HTTP 响应中 path 也替换为它
+    pub is_archived: bool,
+    pub projects: Vec<ProjectStatus>,
+    // 以下仅归档条目填充（归档条目不跑 git status）
+    pub size_bytes: Option<u64>,
+    pub size_display: Option<String>,
+    pub archived_at: Option<String>, // RFC 3339
+    pub meta: Option<WorktreeMeta>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorktreeSortBy {
+    #[default]
+    Name,
+    /// 最近提交在前（取各项目 HEAD 中最新的提交时间）
+    LastCommit,
+    /// 需要处理的在前：有未提交修改 > 领先 base > 落后 base > 干净
+    Status,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct WorktreeListQuery {
+    pub include_archived: bool,
+    pub labels: Vec<String>,
+    pub sort_by: WorktreeSortBy,
+    pub offset: usize,
+    pub limit: Option<usize>,
+    /// 不跑 git status，项目只带名称和路径；展开时再用 get_worktree 取完整状态
+    pub lightweight: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorktreePage {
+    pub items: Vec<WorktreeListItem>,
+    /// 过滤后、分页前的总数
+    pub total: usize,
+    pub offset: usize,
+}
+
+/// 存放在 `<worktree>/.worktree-meta.json`，随归档/恢复一起移动
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorktreeMeta {
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub ticket_url: String,
+    /// 小写、去重，用于侧边栏筛选
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub created_at: Option<String>, // RFC 3339
+    /// 分配给该 worktree 的端口段，首次需要时分配，之后固定不变
+    #[serde(default)]
+    pub ports: Option<PortRange>,
+    /// 注入该 worktree 终端的环境变量，覆盖项目配置中的同名变量
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+/// `[start, start + count)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortRange {
+    pub start: u16,
+    pub count: u16,
+}
+
+impl PortRange {
+    /// 最后一个端口（含）
+    pub fn end(&self) -> u16 {
+        self.start.saturating_add(self.count.saturating_sub(1))
+    }
+
+    pub fn contains(&self, port: u16) -> bool {
+        port >= self.start && port <= self.end()
+    }
+
+    pub fn overlaps(&self, other: &PortRange) -> bool {
+        self.start <= other.end() && other.start <= self.end()
+    }
+}
+
+/// 端口段中有问题的端口
+#[derive(Debug, Serialize)]
+pub struct PortConflict {
+    pub port: u16,
+    /// 端口段与之重叠的其他 worktree
+    pub worktree_name: Option<String>,
+    /// 端口已被占用（可能是本 worktree 自己的 dev server）
+    pub in_use: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorktreePorts {
+    pub worktree_name: String,
+    pub start: u16,
+    pub end: u16,
+    /// 各项目的 `PORT`，按配置中的项目顺序依次取端口段中的端口
+    pub project_ports: BTreeMap<String, u16>,
+    /// 注入到该 worktree 终端的环境变量
+    pub env: BTreeMap<String, String>,
+    pub conflicts: Vec<PortConflict>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectStatus {
+    pub name: String,
+    pub path: String,
+    pub rel_path: String,
+    pub current_branch: String,
+    pub base_branch: String,
+    pub test_branch: String,
+    pub has_uncommitted: bool,
+    pub uncommitted_count: usize,
+    pub is_merged_to_test: bool,
+    pub ahead_of_base: usize,
+    pub behind_base: usize,
+    pub line_ending_mismatch: bool, // 大部分修改只是换行符差异（autocrlf 不一致），可用 normalize_line_endings 修复
+}
+
+#[derive(Debug, Serialize)]
+pub struct MainWorkspaceStatus {
+    pub path: String,
+    pub name: String,
+    pub projects: Vec<MainProjectStatus>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MainProjectStatus {
+    pub name: String,
+    pub path: String,
+    pub rel_path: String,
+    pub current_branch: String,
+    pub has_uncommitted: bool,
+    pub uncommitted_count: usize,
+    pub is_merged_to_test: bool,
+    pub ahead_of_base: usize,
+    pub behind_base: usize,
+    pub base_branch: String,
+    pub test_branch: String,
+    pub linked_folders: Vec<String>,
+}
+
+// ==================== 智能软链接扫描 ====================
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ScannedFolder {
+    pub relative_path: String, // e.g. "packages/web/node_modules"
+    pub display_name: String,  // e.g. "node_modules"
+    pub size_bytes: u64,
+    pub size_display: String, // e.g. "256.3 MB"
+    pub is_recommended: bool, // 推荐预选
+}
+
+// ==================== Worktree 操作数据结构 ====================
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkStatus {
+    Created,
+    Skipped,
+    Failed,
+}
+
+/// 单个软链接的创建结果（create_worktree / restore_worktree 返回给前端展示）
+#[derive(Debug, Serialize, Clone)]
+pub struct LinkResult {
+    pub project: Option<String>, // None 表示 workspace 级别的链接项
+    pub item: String,
+    pub status: LinkStatus,
+    pub reason: Option<String>,
+}
+
+/// delete_archived_worktree 中单个项目的分支删除结果
+#[derive(Debug, Serialize)]
+pub struct BranchDeleteResult {
+    pub project_name: String,
+    pub local_deleted: bool,
+    /// 删除前本地记录中存在 `origin/<branch>`（以上次 fetch 为准）
+    pub remote_exists: bool,
+    pub remote_deleted: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteArchivedResult {
+    pub name: String,
+    pub branch: String,
+    pub delete_remote: bool,
+    /// 只包含存在该分支的项目
+    pub projects: Vec<BranchDeleteResult>,
+    /// on_delete 钩子的执行结果
+    pub hook_runs: Vec<HookRun>,
+}
+
+/// get_paths 返回的当前 workspace 相关路径
+#[derive(Debug, Serialize)]
+pub struct WorkspacePaths {
+    pub root: String,
+    pub config: String,
+    pub worktrees_dir: String,
+    /// 主工作区占用状态文件（仅在部署到主工作区期间存在）
+    pub occupation_state: String,
+}
+
+/// 应用使用的各类文件位置，供外部工具和排障脚本定位
+#[derive(Debug, Serialize)]
+pub struct AppPaths {
+    pub global_config: String,
+    pub global_config_dir: String,
+    /// 当前平台无法确定日志目录时为 None
+    pub log_dir: Option<String>,
+    /// 当前窗口未选择 workspace 时为 None
+    pub workspace: Option<WorkspacePaths>,
+}
+
+/// rename_worktree_branch 中单个项目的结果
+#[derive(Debug, Serialize)]
+pub struct BranchRenameProjectResult {
+    pub project_name: String,
+    /// 本地分支已改名（项目不在旧分支上时为 false）
+    pub renamed: bool,
+    pub pushed: bool,
+    pub old_remote_deleted: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RenameWorktreeResult {
+    pub old_name: String,
+    pub new_name: String,
+    pub projects: Vec<BranchRenameProjectResult>,
+}
+
+/// sync_links 中单个 worktree 的补链结果
+#[derive(Debug, Serialize)]
+pub struct SyncLinksResult {
+    pub worktree: String,
+    pub link_results: Vec<LinkResult>,
+}
+
+/// repair_worktrees 中单个主仓库项目的处理结果
+#[derive(Debug, Serialize)]
+pub struct RepairWorktreesResult {
+    pub project_name: String,
+    pub pruned: Vec<String>,      // git worktree prune 清理掉的登记
+    pub reregistered: Vec<String>, // 重新登记成功的 worktree 名称
+    pub unresolved: Vec<UnregisteredWorktree>,
+    pub error: Option<String>,
+}
+
+/// worktrees/ 下存在但主仓库未登记、且无法自动修复的项目目录
+#[derive(Debug, Serialize)]
+pub struct UnregisteredWorktree {
+    pub worktree: String,
+    pub path: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateWorktreeResult {
+    pub path: String,
+    pub rel_path: String,
+    pub link_results: Vec<LinkResult>,
+    /// on_create 钩子的执行结果
+    pub hook_runs: Vec<HookRun>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RestoreWorktreeResult {
+    pub link_results: Vec<LinkResult>,
+    /// on_restore 钩子的执行结果
+    pub hook_runs: Vec<HookRun>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateWorktreeRequest {
+    pub name: String,
+    pub projects: Vec<CreateProjectRequest>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateProjectRequest {
     pub name: String,
     pub base_branch: String,
+    #[serde(default)]
+    pub sparse_paths: Vec<String>, // 覆盖项目配置中的 sparse_paths，为空时沿用配置
+    #[serde(default)]
+    pub remote: Option<String>, // 覆盖项目配置中的 remote（新分支从 <remote>/<base_branch> 创建）
 }
 
 #[derive(Debug, Serialize)]
@@ -341,6 +1397,176 @@ pub struct WorktreeArchiveStatus {
     pub projects: Vec<crate::git_ops::BranchStatus>,
 }
 
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkArchiveOutcome {
+    Archived,
+    Blocked, // check_worktree_status 未通过，未做任何修改
+    Failed,
+}
+
+/// archive_worktrees 中单个 worktree 的结果
+#[derive(Debug, Serialize)]
+pub struct BulkArchiveResult {
+    pub name: String,
+    pub outcome: BulkArchiveOutcome,
+    pub reasons: Vec<String>,  // blocked / failed 的原因
+    pub warnings: Vec<String>, // 检查通过时的提示（已忽略文件、未建 MR 等）
+}
+
+// ==================== 同步建议 ====================
+
+#[derive(Debug, Serialize)]
+pub struct ProjectSyncAdvice {
+    pub project_name: String,
+    pub base_branch: String,
+    #[serde(flatten)]
+    pub preview: crate::git_ops::SyncPreview,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorktreeSyncAdvice {
+    pub worktree_name: String,
+    pub projects: Vec<ProjectSyncAdvice>,
+}
+
+/// refresh_workspace 的结果：重新扫描后的 worktree 列表与主工作区状态
+#[derive(Debug, Serialize)]
+pub struct WorkspaceRefreshResult {
+    pub worktrees: Vec<WorktreeListItem>,
+    pub main_workspace: MainWorkspaceStatus,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectSyncResult {
+    pub project_name: String,
+    pub success: bool,
+    pub message: String,
+}
+
+// ==================== Base 分支更新检测 ====================
+
+/// worktree 中某个项目落后 origin/<base> 超过阈值
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct BaseUpdate {
+    pub worktree_name: String,
+    pub project_name: String,
+    pub path: String, // workspace 相对路径，可直接传给 sync_with_base_branch
+    pub base_branch: String,
+    pub behind: usize,
+}
+
+// ==================== 磁盘占用 ====================
+
+#[derive(Debug, Serialize, Clone)]
+pub struct WorktreeDiskUsage {
+    pub name: String,
+    pub is_archived: bool,
+    pub size_bytes: u64,
+    pub size_display: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DiskUsageReport {
+    pub workspace_path: String,
+    pub total_bytes: u64,
+    pub total_display: String,
+    pub quota_bytes: Option<u64>,
+    pub over_quota: bool,
+    /// 按占用从大到小排序
+    pub worktrees: Vec<WorktreeDiskUsage>,
+    /// 建议归档的活动 worktree：没有未提交修改，按占用从大到小
+    pub archive_candidates: Vec<String>,
+}
+
+// ==================== 工作区清单（迁移） ====================
+
+/// Portable description of a workspace's worktrees, used to migrate to another machine.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkspaceManifest {
+    pub version: u32,
+    pub workspace_name: String,
+    pub exported_at: String,
+    pub worktrees: Vec<ManifestWorktree>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManifestWorktree {
+    pub name: String,
+    pub is_archived: bool,
+    pub projects: Vec<ManifestProject>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManifestProject {
+    pub name: String,
+    pub branch: String,
+    pub base_branch: String,
+    #[serde(default)]
+    pub has_uncommitted: bool,
+    #[serde(default)]
+    pub ahead_of_base: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ManifestImportResult {
+    pub worktree_name: String,
+    pub created: bool,
+    pub reason: Option<String>,
+}
+
+/// Written as `manifest.json` next to the per-project bundles by export_worktree_bundle.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorktreeBundleManifest {
+    pub version: u32,
+    pub workspace_name: String,
+    pub worktree_name: String,
+    pub exported_at: String,
+    pub projects: Vec<BundleProject>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleProject {
+    pub name: String,
+    pub branch: String,
+    pub base_branch: String,
+    /// Merge base with `<remote>/<base_branch>`; the recipient needs this commit to unbundle.
+    /// None when no base was found and the bundle carries the full history.
+    pub base_commit: Option<String>,
+    pub head_commit: String,
+    /// Commits in the bundle (HEAD versus base_commit)
+    pub commits: usize,
+    /// Bundle file name relative to the manifest; None when there was nothing to bundle
+    pub bundle: Option<String>,
+    /// Uncommitted changes are not part of the bundle
+    pub has_uncommitted: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BundleExportResult {
+    pub output_path: String,
+    pub manifest: WorktreeBundleManifest,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BundleImportProject {
+    pub name: String,
+    /// Commits fetched from the bundle
+    pub commits: usize,
+    /// Why the project was left out of the new worktree
+    pub skipped: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BundleImportResult {
+    pub worktree_name: String,
+    pub path: String,
+    pub projects: Vec<BundleImportProject>,
+    pub link_results: Vec<LinkResult>,
+}
+
 // ==================== 向已有 Worktree 添加项目 ====================
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -356,6 +1582,9 @@ pub struct AddProjectToWorktreeRequest {
 pub struct SwitchBranchRequest {
     pub project_path: String,
     pub branch: String,
+    /// 主工作区项目有未提交修改时，需传入 DirtyReport.fingerprint 表示已确认
+    #[serde(default)]
+    pub dirty_ack: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -366,6 +1595,8 @@ pub struct CloneProjectRequest {
     pub test_branch: String,
     pub merge_strategy: String,
     pub linked_folders: Vec<String>,
+    #[serde(default)]
+    pub skip_lfs: bool,
 }
 
 // ==================== 编辑器 ====================
@@ -373,7 +1604,70 @@ pub struct CloneProjectRequest {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OpenEditorRequest {
     pub path: String,
-    pub editor: String,
+    pub editor: String, // 内置编辑器 id 或自定义编辑器名称
+    #[serde(default)]
+    pub file: Option<String>, // 要打开的文件，相对 path
+    #[serde(default)]
+    pub line: Option<u32>, // 配合 file 跳转到的行号，从 1 开始
+}
+
+/// 项目的 devcontainer 状态
+#[derive(Debug, Serialize, Clone)]
+pub struct DevcontainerStatus {
+    pub config_path: Option<String>, // 未找到 devcontainer.json 时为 None，其余字段无意义
+    pub container_id: Option<String>, // devcontainer CLI / VS Code 为该目录创建的容器
+    pub state: Option<String>,       // docker 容器状态：running / exited / ...
+    pub cli_available: bool,         // 是否安装了 devcontainer CLI
+}
+
+/// `docker compose ps` 中的一个容器
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ComposeService {
+    pub name: String,    // 容器名
+    pub service: String, // compose 文件中的服务名
+    pub state: String,   // running / exited / ...
+    pub status: String,  // 如 "Up 5 minutes"
+}
+
+/// worktree 中某个项目的 compose 栈
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ComposeStatus {
+    pub compose_file: Option<String>, // 未找到 compose 文件时为 None
+    pub project_name: String,         // docker compose -p 使用的项目名，按 worktree 区分
+    pub services: Vec<ComposeService>,
+}
+
+/// 自定义编辑器：macOS 上优先用 `open -a app_name` 打开，否则执行 command
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CustomEditor {
+    pub name: String,
+    #[serde(default)]
+    pub app_name: Option<String>, // macOS 应用名，如 "Zed"、"WebStorm"
+    #[serde(default)]
+    pub command: Option<String>, // CLI 命令，如 "zed"、"subl"
+    #[serde(default)]
+    pub args: Vec<String>, // 参数模板，{path} 替换为打开的目录；为空时只传目录
+    #[serde(default)]
+    pub file_args: Vec<String>, // 打开文件时的参数模板，另有 {file}、{line}；为空时只传文件
+}
+
+// ==================== 未提交修改汇总 ====================
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DirtyProject {
+    pub project_name: String,
+    pub staged_count: usize,
+    pub modified_count: usize,
+    pub untracked_count: usize,
+    pub files: Vec<String>, // 最多列出前 20 个
+}
+
+/// 风险操作前对主工作区各项目未提交修改的汇总。
+/// `fingerprint` 随修改内容变化，操作时回传它表示用户已确认的正是这份状态。
+#[derive(Debug, Serialize, Clone)]
+pub struct DirtyReport {
+    pub projects: Vec<DirtyProject>,
+    pub fingerprint: String,
 }
 
 // ==================== 部署到主工作区 ====================
@@ -390,6 +1684,8 @@ pub struct DeployToMainResult {
     pub success: bool,
     pub switched_projects: Vec<String>,
     pub failed_projects: Vec<DeployProjectError>,
+    /// on_deploy_to_main 钩子的执行结果
+    pub hook_runs: Vec<HookRun>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -397,3 +1693,61 @@ pub struct DeployProjectError {
     pub project_name: String,
     pub error: String,
 }
+
+// ==================== 命令面板：操作注册表 ====================
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ActionArg {
+    pub name: String, // 与 HTTP / invoke 参数名一致（camelCase）
+    pub kind: String, // "string" | "boolean" | "string[]" | "path"
+    pub required: bool,
+    pub description: String,
+}
+
+/// 一个可执行操作；`id` 即 Tauri 命令名，也是 `/api/<id>` 路由名
+#[derive(Debug, Serialize, Clone)]
+pub struct ActionInfo {
+    pub id: String,
+    pub title: String,
+    pub category: String,
+    pub args: Vec<ActionArg>,
+    pub enabled: bool,
+    pub disabled_reason: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limiter_allows_a_burst_then_limits() {
+        let mut limiter = ApiRateLimiter::new(RateLimitConfig::default());
+        for _ in 0..3 {
+            assert!(limiter.check("session-a", 0, 3));
+        }
+        assert!(!limiter.check("session-a", 0, 3));
+    }
+
+    #[test]
+    fn rate_limiter_keys_are_independent() {
+        let mut limiter = ApiRateLimiter::new(RateLimitConfig::default());
+        assert!(limiter.check("10.0.0.1", 0, 1));
+        assert!(!limiter.check("10.0.0.1", 0, 1));
+        assert!(limiter.check("10.0.0.2", 0, 1));
+    }
+
+    #[test]
+    fn rate_limiter_refills_over_time() {
+        let mut limiter = ApiRateLimiter::new(RateLimitConfig::default());
+        assert!(limiter.check("session-a", 1000, 1));
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(limiter.check("session-a", 1000, 1));
+    }
+
+    #[test]
+    fn rate_limiter_treats_zero_burst_as_one() {
+        let mut limiter = ApiRateLimiter::new(RateLimitConfig::default());
+        assert!(limiter.check("session-a", 0, 0));
+        assert!(!limiter.check("session-a", 0, 0));
+    }
+}
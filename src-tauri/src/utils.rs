@@ -1,9 +1,15 @@
+use std::cell::Cell;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 use wait_timeout::ChildExt;
 
+use crate::oplog::{finish_operation, record_command, start_operation};
+use crate::state::{
+    IN_FLIGHT_GIT_OPS, REPO_QUEUES, REPO_QUEUE_CHANGED, REPO_QUEUE_TICKET, SHUTTING_DOWN,
+};
 use crate::types::ScannedFolder;
 
 // Git command timeout (30 seconds)
@@ -12,6 +18,19 @@ pub(crate) const GIT_COMMAND_TIMEOUT_SECS: u64 = 30;
 pub(crate) fn run_git_command_with_timeout(
     args: &[&str],
     cwd: &str,
+) -> Result<std::process::Output, String> {
+    let command = format!("git {}", args.join(" "));
+    let result = run_git_command_with_timeout_unlogged(args, cwd);
+    match &result {
+        Ok(output) => record_command(command, Some(cwd.to_string()), Ok(output)),
+        Err(e) => record_command(command, Some(cwd.to_string()), Err(e)),
+    }
+    result
+}
+
+fn run_git_command_with_timeout_unlogged(
+    args: &[&str],
+    cwd: &str,
 ) -> Result<std::process::Output, String> {
     let mut child = Command::new("git")
         .args(args)
@@ -59,6 +78,188 @@ pub(crate) fn run_git_command_with_timeout(
     }
 }
 
+// Default lifecycle hook timeout (5 minutes)
+pub(crate) const HOOK_COMMAND_TIMEOUT_SECS: u64 = 300;
+
+/// Run a shell command line (`sh -c` / `cmd /C`) in `cwd` with extra environment variables,
+/// killing it after the timeout.
+pub(crate) fn run_shell_command_with_timeout(
+    command: &str,
+    cwd: &str,
+    envs: &[(String, String)],
+    timeout_secs: u64,
+) -> Result<std::process::Output, String> {
+    let cmd = if cfg!(windows) {
+        let mut c = Command::new("cmd");
+        c.args(["/C", command]);
+        c
+    } else {
+        let mut c = Command::new("sh");
+        c.args(["-c", command]);
+        c
+    };
+    run_with_timeout(cmd, command.to_string(), cwd, envs, timeout_secs)
+}
+
+/// Run `program` with `args` (no shell) in `cwd`, killing it after the timeout.
+pub(crate) fn run_command_with_timeout(
+    program: &str,
+    args: &[&str],
+    cwd: &str,
+    envs: &[(String, String)],
+    timeout_secs: u64,
+) -> Result<std::process::Output, String> {
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    let command = format!("{} {}", program, args.join(" "));
+    run_with_timeout(cmd, command, cwd, envs, timeout_secs)
+}
+
+/// Output is drained on reader threads so chatty commands can't block on a full pipe.
+fn run_with_timeout(
+    mut cmd: Command,
+    command: String,
+    cwd: &str,
+    envs: &[(String, String)],
+    timeout_secs: u64,
+) -> Result<std::process::Output, String> {
+    let result = (|| {
+        let mut child = cmd
+            .current_dir(cwd)
+            .envs(envs.iter().map(|(k, v)| (k, v)))
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to spawn command: {}", e))?;
+
+        let drain = |pipe: Option<Box<dyn std::io::Read + Send>>| {
+            std::thread::spawn(move || {
+                let mut buf = Vec::new();
+                if let Some(mut p) = pipe {
+                    std::io::Read::read_to_end(&mut p, &mut buf).ok();
+                }
+                buf
+            })
+        };
+        let stdout = drain(child.stdout.take().map(|p| Box::new(p) as _));
+        let stderr = drain(child.stderr.take().map(|p| Box::new(p) as _));
+
+        match child.wait_timeout(Duration::from_secs(timeout_secs)) {
+            Ok(Some(status)) => Ok(std::process::Output {
+                status,
+                stdout: stdout.join().unwrap_or_default(),
+                stderr: stderr.join().unwrap_or_default(),
+            }),
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                Err(format!("Command timed out after {} seconds", timeout_secs))
+            }
+            Err(e) => Err(format!("Failed to wait for command: {}", e)),
+        }
+    })();
+    match &result {
+        Ok(output) => record_command(command, Some(cwd.to_string()), Ok(output)),
+        Err(e) => record_command(command, Some(cwd.to_string()), Err(e)),
+    }
+    result
+}
+
+thread_local! {
+    // Git operation guards currently held on this thread (> 1 for nested operations)
+    static GIT_OP_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// RAII guard counting an in-flight git write operation.
+/// The shutdown path waits for the counter to drain before tearing down state.
+/// The outermost guard on a thread also owns the operation's command transcript.
+pub(crate) struct GitOpGuard {
+    operation_id: Option<String>,
+}
+
+impl Drop for GitOpGuard {
+    fn drop(&mut self) {
+        if let Some(id) = self.operation_id.take() {
+            finish_operation(&id);
+        }
+        GIT_OP_DEPTH.with(|d| d.set(d.get().saturating_sub(1)));
+        IN_FLIGHT_GIT_OPS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Register a git write operation. New operations are refused once the app has started
+/// shutting down; nested calls from an operation already in flight are still let through,
+/// so the shutdown path can wait for it to finish instead of failing it halfway.
+pub(crate) fn begin_git_operation(operation: &str) -> Result<GitOpGuard, String> {
+    let nested = GIT_OP_DEPTH.with(|d| d.get() > 0);
+    if SHUTTING_DOWN.load(Ordering::SeqCst) && !nested {
+        return Err("应用正在退出，无法执行新的 Git 操作".to_string());
+    }
+    GIT_OP_DEPTH.with(|d| d.set(d.get() + 1));
+    IN_FLIGHT_GIT_OPS.fetch_add(1, Ordering::SeqCst);
+    Ok(GitOpGuard {
+        operation_id: start_operation(operation),
+    })
+}
+
+/// RAII guard holding the head of a main repository's operation queue.
+/// Dropping it hands the repository over to the next waiter.
+pub(crate) struct RepoQueueGuard {
+    repo: String,
+    ticket: u64,
+}
+
+impl Drop for RepoQueueGuard {
+    fn drop(&mut self) {
+        if let Ok(mut queues) = REPO_QUEUES.lock() {
+            if let Some(queue) = queues.get_mut(&self.repo) {
+                queue.retain(|t| *t != self.ticket);
+                if queue.is_empty() {
+                    queues.remove(&self.repo);
+                }
+            }
+        }
+        REPO_QUEUE_CHANGED.notify_all();
+    }
+}
+
+/// Wait for exclusive use of a main repository.
+/// `on_wait` is called with the number of operations ahead whenever it changes.
+pub(crate) fn enter_repo_queue(repo: &Path, mut on_wait: impl FnMut(usize)) -> RepoQueueGuard {
+    let key = repo.to_string_lossy().to_string();
+    let ticket = REPO_QUEUE_TICKET.fetch_add(1, Ordering::SeqCst);
+    let mut queues = REPO_QUEUES.lock().unwrap_or_else(|e| e.into_inner());
+    queues.entry(key.clone()).or_default().push_back(ticket);
+
+    let mut reported = None;
+    loop {
+        let ahead = queues
+            .get(&key)
+            .and_then(|q| q.iter().position(|t| *t == ticket))
+            .unwrap_or(0);
+        if ahead == 0 {
+            break;
+        }
+        if reported != Some(ahead) {
+            reported = Some(ahead);
+            drop(queues);
+            on_wait(ahead);
+            queues = REPO_QUEUES.lock().unwrap_or_else(|e| e.into_inner());
+            continue;
+        }
+        queues = REPO_QUEUE_CHANGED
+            .wait(queues)
+            .unwrap_or_else(|e| e.into_inner());
+    }
+    if reported.is_some() {
+        drop(queues);
+        on_wait(0);
+    }
+
+    RepoQueueGuard { repo: key, ticket }
+}
+
 /// Normalize path separators for the current platform.
 /// On Windows, replaces forward slashes with backslashes.
 pub fn normalize_path(path: &str) -> String {
@@ -217,6 +418,48 @@ pub(crate) fn scan_dir_for_linkable_folders(
     }
 }
 
+/// Simple wildcard match supporting `*` (any run of characters) and `?` (one character).
+pub(crate) fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    let (mut pi, mut ti) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ti));
+            pi += 1;
+        } else if let Some((sp, st)) = star {
+            pi = sp + 1;
+            ti = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|c| *c == '*')
+}
+
+/// Match a repo-relative path against ignore-style patterns.
+/// Patterns containing '/' match the whole path, others match the file name only.
+pub(crate) fn path_matches_any(path: &str, patterns: &[String]) -> bool {
+    let path = path.trim_end_matches('/');
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    patterns.iter().any(|pattern| {
+        let pattern = pattern.trim().trim_end_matches('/');
+        if pattern.is_empty() {
+            return false;
+        }
+        if pattern.contains('/') {
+            wildcard_match(pattern.trim_start_matches('/'), path)
+        } else {
+            wildcard_match(pattern, file_name)
+        }
+    })
+}
+
 // Parse different repo URL formats
 pub(crate) fn parse_repo_url(url: &str) -> Result<String, String> {
     let url = url.trim();
@@ -239,3 +482,55 @@ pub(crate) fn parse_repo_url(url: &str) -> Result<String, String> {
 
     Err(format!("Invalid repository URL format: {}", url))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(list: &[&str]) -> Vec<String> {
+        list.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn wildcard_match_handles_star_and_question_mark() {
+        assert!(wildcard_match("*.lock", "Cargo.lock"));
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("a*b*c", "aXbYbc"));
+        assert!(wildcard_match("file?.txt", "file1.txt"));
+        assert!(wildcard_match("??", "ab"));
+
+        assert!(!wildcard_match("*.lock", "Cargo.toml"));
+        assert!(!wildcard_match("file?.txt", "file.txt"));
+        assert!(!wildcard_match("file?.txt", "file12.txt"));
+        assert!(!wildcard_match("abc", "ab"));
+        assert!(!wildcard_match("", "a"));
+    }
+
+    #[test]
+    fn patterns_without_slash_match_the_file_name_at_any_depth() {
+        let list = patterns(&["*.min.js", "dist/"]);
+        assert!(path_matches_any("app.min.js", &list));
+        assert!(path_matches_any("web/static/app.min.js", &list));
+        assert!(path_matches_any("packages/ui/dist/", &list));
+        assert!(!path_matches_any("web/static/app.js", &list));
+        assert!(!path_matches_any("dist.txt", &list));
+    }
+
+    #[test]
+    fn patterns_with_slash_match_the_whole_path() {
+        let list = patterns(&["/docs/*.md", "src/gen?/*"]);
+        assert!(path_matches_any("docs/README.md", &list));
+        assert!(path_matches_any("src/gen1/api.rs", &list));
+        // `*` also spans directory separators
+        assert!(path_matches_any("docs/guide/setup.md", &list));
+        assert!(!path_matches_any("web/docs/README.md", &list));
+        assert!(!path_matches_any("src/generated/api.rs", &list));
+        assert!(!path_matches_any("README.md", &list));
+    }
+
+    #[test]
+    fn blank_patterns_match_nothing() {
+        assert!(!path_matches_any("a.txt", &patterns(&["", "  ", "/"])));
+        assert!(!path_matches_any("a.txt", &[]));
+    }
+}
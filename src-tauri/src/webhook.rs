@@ -0,0 +1,280 @@
+//! Incoming git webhooks (`/api/webhook/git`): GitHub and GitLab push and pull/merge request
+//! events make every open workspace containing the pushed repository fetch it and tell its
+//! clients to refresh, so worktree status follows a teammate's push within seconds.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use axum::http::HeaderMap;
+use git2::Repository;
+use ring::hmac;
+use serde_json::{json, Value};
+use tauri::Emitter;
+
+use crate::config::{load_global_config, load_workspace_config};
+use crate::state::{APP_HANDLE, WINDOW_WORKSPACES, WORKSPACE_EVENT_BROADCAST};
+use crate::utils::run_git_command_with_timeout;
+
+/// A push or pull/merge request event for one repository
+pub(crate) struct GitEvent {
+    pub provider: &'static str,
+    pub kind: &'static str,
+    /// `owner/repo` (GitHub) or `group/subgroup/repo` (GitLab)
+    pub repository: String,
+    pub branch: Option<String>,
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+/// GitHub signs the body with the secret (`X-Hub-Signature-256`), GitLab sends the secret
+/// itself (`X-Gitlab-Token`).
+pub(crate) fn verify_request(secret: &str, headers: &HeaderMap, body: &[u8]) -> bool {
+    if let Some(signature) = header(headers, "x-hub-signature-256") {
+        let Some(tag) = signature
+            .strip_prefix("sha256=")
+            .and_then(|hex_tag| hex::decode(hex_tag).ok())
+        else {
+            return false;
+        };
+        let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+        return hmac::verify(&key, body, &tag).is_ok();
+    }
+    if let Some(token) = header(headers, "x-gitlab-token") {
+        // Constant-time comparison
+        return token.len() == secret.len()
+            && token
+                .as_bytes()
+                .iter()
+                .zip(secret.as_bytes())
+                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                == 0;
+    }
+    false
+}
+
+/// The event described by a webhook delivery; `None` for events that don't move branches
+/// (GitHub's `ping`, issues, comments, ...).
+pub(crate) fn parse_event(headers: &HeaderMap, payload: &Value) -> Option<GitEvent> {
+    let branch_of = |git_ref: &Value| {
+        git_ref
+            .as_str()
+            .map(|r| r.strip_prefix("refs/heads/").unwrap_or(r).to_string())
+    };
+    if let Some(event) = header(headers, "x-github-event") {
+        let repository = payload["repository"]["full_name"].as_str()?.to_string();
+        return match event {
+            "push" => Some(GitEvent {
+                provider: "github",
+                kind: "push",
+                repository,
+                branch: branch_of(&payload["ref"]),
+            }),
+            "pull_request" => Some(GitEvent {
+                provider: "github",
+                kind: "pull_request",
+                repository,
+                branch: branch_of(&payload["pull_request"]["head"]["ref"]),
+            }),
+            _ => None,
+        };
+    }
+    if let Some(event) = header(headers, "x-gitlab-event") {
+        let repository = payload["project"]["path_with_namespace"]
+            .as_str()?
+            .to_string();
+        return match event {
+            "Push Hook" => Some(GitEvent {
+                provider: "gitlab",
+                kind: "push",
+                repository,
+                branch: branch_of(&payload["ref"]),
+            }),
+            "Merge Request Hook" => Some(GitEvent {
+                provider: "gitlab",
+                kind: "merge_request",
+                repository,
+                branch: branch_of(&payload["object_attributes"]["source_branch"]),
+            }),
+            _ => None,
+        };
+    }
+    None
+}
+
+/// `git@github.com:Owner/Repo.git`, `https://user@host/owner/repo` → `host/owner/repo`
+fn normalize_remote_url(url: &str) -> String {
+    let url = url.trim().trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let url = url.split_once('@').map_or(url, |(_, rest)| rest);
+    url.replacen(':', "/", 1).to_lowercase()
+}
+
+fn remote_matches(repo_path: &Path, remote: &str, repository: &str) -> bool {
+    let Ok(repo) = Repository::open(repo_path) else {
+        return false;
+    };
+    let Ok(remote) = repo.find_remote(remote) else {
+        return false;
+    };
+    let suffix = format!("/{}", repository.to_lowercase());
+    remote
+        .url()
+        .is_some_and(|url| normalize_remote_url(url).ends_with(&suffix))
+}
+
+/// Fetch the event's repository in every open workspace that has it, then broadcast
+/// `git-remote-updated` (Tauri event / WebSocket `git_remote_updated`) per workspace.
+/// Returns the number of projects fetched.
+pub(crate) fn handle_event(event: &GitEvent) -> usize {
+    // Workspaces bound to a window or web session, plus the default one
+    let mut workspaces: HashSet<String> = WINDOW_WORKSPACES
+        .lock()
+        .map(|map| map.values().cloned().collect())
+        .unwrap_or_default();
+    workspaces.extend(load_global_config().current_workspace);
+    let mut fetched = 0;
+    for workspace_path in workspaces {
+        let config = load_workspace_config(&workspace_path);
+        for project in &config.projects {
+            let main_path = PathBuf::from(&workspace_path)
+                .join("projects")
+                .join(&project.name);
+            if !remote_matches(&main_path, &project.remote, &event.repository) {
+                continue;
+            }
+            // Worktrees share refs with the main repository, one fetch updates them all
+            let main_str = main_path.to_string_lossy().to_string();
+            if let Err(e) = run_git_command_with_timeout(&["fetch", &project.remote], &main_str) {
+                log::warn!(
+                    "[webhook] Fetch failed for {} in '{}': {}",
+                    project.name,
+                    workspace_path,
+                    e
+                );
+                continue;
+            }
+            fetched += 1;
+            log::info!(
+                "[webhook] {} {} {} fetched project '{}' in '{}'",
+                event.provider,
+                event.kind,
+                event.repository,
+                project.name,
+                workspace_path
+            );
+            let payload = json!({
+                "type": "git_remote_updated",
+                "workspacePath": workspace_path,
+                "projectName": project.name,
+                "branch": event.branch,
+                "event": event.kind,
+            });
+            let _ = WORKSPACE_EVENT_BROADCAST.send(payload.to_string());
+            if let Some(app) = APP_HANDLE.lock().ok().and_then(|h| h.as_ref().cloned()) {
+                let _ = app.emit("git-remote-updated", payload);
+            }
+        }
+    }
+    fetched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, value.parse().unwrap());
+        }
+        map
+    }
+
+    fn github_signature(secret: &str, body: &[u8]) -> String {
+        let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+        format!("sha256={}", hex::encode(hmac::sign(&key, body).as_ref()))
+    }
+
+    #[test]
+    fn verify_request_checks_github_signatures() {
+        let body = br#"{"ref":"refs/heads/main"}"#;
+        let signature = github_signature("s3cret", body);
+        let signed = headers(&[("x-hub-signature-256", &signature)]);
+        assert!(verify_request("s3cret", &signed, body));
+        assert!(!verify_request("other", &signed, body));
+        assert!(!verify_request("s3cret", &signed, b"{}"));
+        let unprefixed = headers(&[("x-hub-signature-256", &signature[7..])]);
+        assert!(!verify_request("s3cret", &unprefixed, body));
+        let not_hex = headers(&[("x-hub-signature-256", "sha256=zz")]);
+        assert!(!verify_request("s3cret", &not_hex, body));
+    }
+
+    #[test]
+    fn verify_request_checks_gitlab_tokens() {
+        let gitlab =
+            |token: &str| verify_request("s3cret", &headers(&[("x-gitlab-token", token)]), b"");
+        assert!(gitlab("s3cret"));
+        assert!(!gitlab("s3cre"));
+        assert!(!gitlab("s3creT"));
+        assert!(!verify_request("s3cret", &headers(&[]), b""));
+    }
+
+    #[test]
+    fn parse_event_reads_github_events() {
+        let payload = json!({
+            "ref": "refs/heads/feat/login",
+            "repository": { "full_name": "owner/repo" },
+            "pull_request": { "head": { "ref": "feat/pr" } },
+        });
+        let push = parse_event(&headers(&[("x-github-event", "push")]), &payload).unwrap();
+        assert_eq!((push.provider, push.kind), ("github", "push"));
+        assert_eq!(push.repository, "owner/repo");
+        assert_eq!(push.branch.as_deref(), Some("feat/login"));
+
+        let pr = parse_event(&headers(&[("x-github-event", "pull_request")]), &payload).unwrap();
+        assert_eq!(pr.kind, "pull_request");
+        assert_eq!(pr.branch.as_deref(), Some("feat/pr"));
+
+        assert!(parse_event(&headers(&[("x-github-event", "ping")]), &payload).is_none());
+        assert!(parse_event(&headers(&[("x-github-event", "push")]), &json!({})).is_none());
+    }
+
+    #[test]
+    fn parse_event_reads_gitlab_events() {
+        let payload = json!({
+            "ref": "refs/heads/main",
+            "project": { "path_with_namespace": "group/sub/repo" },
+            "object_attributes": { "source_branch": "feat/mr" },
+        });
+        let push = parse_event(&headers(&[("x-gitlab-event", "Push Hook")]), &payload).unwrap();
+        assert_eq!((push.provider, push.kind), ("gitlab", "push"));
+        assert_eq!(push.repository, "group/sub/repo");
+        assert_eq!(push.branch.as_deref(), Some("main"));
+
+        let mr = parse_event(
+            &headers(&[("x-gitlab-event", "Merge Request Hook")]),
+            &payload,
+        )
+        .unwrap();
+        assert_eq!(mr.kind, "merge_request");
+        assert_eq!(mr.branch.as_deref(), Some("feat/mr"));
+
+        assert!(parse_event(&headers(&[("x-gitlab-event", "Note Hook")]), &payload).is_none());
+        assert!(parse_event(&headers(&[]), &payload).is_none());
+    }
+
+    #[test]
+    fn remote_urls_normalize_to_host_and_path() {
+        for url in [
+            "git@github.com:Owner/Repo.git",
+            "https://github.com/owner/repo",
+            "https://user@github.com/owner/repo.git/",
+            "ssh://git@github.com/owner/repo.git",
+        ] {
+            assert_eq!(normalize_remote_url(url), "github.com/owner/repo");
+        }
+    }
+}
@@ -39,7 +39,7 @@ async fn discover_tunnel_config(server_url: &str) -> Option<TunnelDiscoveryRespo
     let url = format!("{}/api/tunnel/config", server_url.trim_end_matches('/'));
     log::info!("[wms-tunnel] Fetching discovery config from {}", url);
 
-    let client = reqwest::Client::builder()
+    let client = crate::network::http_client_builder()
         .timeout(std::time::Duration::from_secs(5))
         .build()
         .ok()?;
@@ -227,6 +227,9 @@ enum ClientMessage {
 }
 
 // Hop-by-hop headers that should not be forwarded
+/// Added to every request relayed to the local server, which otherwise sees a loopback peer.
+pub(crate) const TUNNEL_MARKER_HEADER: &str = "x-wm-tunnel";
+
 const HOP_BY_HOP_HEADERS: &[&str] = &[
     "connection",
     "keep-alive",
@@ -278,6 +281,7 @@ async fn proxy_http(
             builder = builder.header(name.as_str(), value.as_str());
         }
     }
+    builder = builder.header(TUNNEL_MARKER_HEADER, "wms");
 
     if let Some(b) = body {
         match BASE64.decode(&b) {
@@ -493,7 +497,7 @@ async fn run_tunnel_session(
     let ws_streams: Arc<tokio::sync::Mutex<HashMap<String, mpsc::UnboundedSender<String>>>> =
         Arc::new(tokio::sync::Mutex::new(HashMap::new()));
 
-    let http_client = reqwest::Client::builder()
+    let http_client = crate::network::http_client_builder()
         .redirect(reqwest::redirect::Policy::none())
         .build()
         .unwrap_or_default();
@@ -1,4 +1,4 @@
-import { useState, useEffect, useCallback, useRef } from "react";
+import { useState, useEffect, useCallback, useMemo, useRef } from "react";
 import { useTranslation } from "react-i18next";
 import { Button } from "@/components/ui/button";
 import {
@@ -13,20 +13,23 @@ import {
   AddProjectModal,
   AddProjectToWorktreeModal,
   ArchiveConfirmationModal,
+  BulkArchiveModal,
   WorktreeContextMenu,
   TerminalTabContextMenu,
   RefreshIcon,
   ToastProvider,
   GlobalDialogs,
+  DiskQuotaWatcher,
 } from "./components";
-import { useWorkspace, useTerminal, useUpdater, useShareFeature, useBrowserAuth, useWorktreeLocks, useModals, useWorkspaceActions, useMainOccupation } from "./hooks";
+import { useWorkspace, useTerminal, useUpdater, useShareFeature, useBrowserAuth, useWorktreeLocks, useModals, useWorkspaceActions, useMainOccupation, useBaseUpdates, useRunningProcesses, useCiStatus } from "./hooks";
 import { useVoiceInput } from "./hooks/useVoiceInput";
 import { Input } from "@/components/ui/input";
-import { callBackend, isTauri, setWindowTitle, getShareInfo, clearSessionId } from "./lib/backend";
+import { callBackend, isTauri, downloadPath, setWindowTitle, getShareInfo, clearSessionId, listQuickCommands, runQuickCommand, runTask, openTerminalPreset, importWorktreeBundle, ptyGetRecording, ptyStartRecording, ptyStopRecording } from "./lib/backend";
 import { getWebSocketManager } from "./lib/websocket";
 import type {
   ViewMode,
   TerminalTabMenuState,
+  QuickCommand,
 } from "./types";
 import "./index.css";
 
@@ -52,6 +55,7 @@ function App() {
   const workspace = useWorkspace(browserAuth.browserAuthenticated);
 
   const [shareWorkspaceName, setShareWorkspaceName] = useState<string | null>(null);
+  const [shareTotpRequired, setShareTotpRequired] = useState(false);
   const [pendingAutoSelectWorktree, setPendingAutoSelectWorktree] = useState<string | null>(null);
 
   useEffect(() => {
@@ -59,6 +63,7 @@ function App() {
     getShareInfo()
       .then((info) => {
         if (info.workspace_name) setShareWorkspaceName(info.workspace_name);
+        setShareTotpRequired(!!info.totp_required);
         if (info.current_worktree) setPendingAutoSelectWorktree(info.current_worktree);
       })
       .catch(() => { });
@@ -80,11 +85,15 @@ function App() {
   const [sidebarCollapsed, setSidebarCollapsed] = useState(isMobileWeb);
   const [terminalFullscreen, setTerminalFullscreen] = useState(false);
   const [showShortcutHelp, setShowShortcutHelp] = useState(false);
+  const [showBulkArchive, setShowBulkArchive] = useState(false);
   const [terminalTabMenu, setTerminalTabMenu] = useState<TerminalTabMenuState | null>(null);
   const modals = useModals();
   const share = useShareFeature(workspace.setError);
   const locks = useWorktreeLocks(workspace.currentWorkspace?.path, workspace.getLockedWorktrees);
   const mainOccupation = useMainOccupation(workspace.currentWorkspace?.path);
+  const baseUpdates = useBaseUpdates(workspace.currentWorkspace?.path, workspace.loadData, workspace.setError);
+  const { runningProcesses } = useRunningProcesses(workspace.currentWorkspace?.path);
+  const ciStatus = useCiStatus(workspace.worktrees);
   const [selectedWorktree, setSelectedWorktree] = useState<import('./types').WorktreeListItem | null>(null);
   const terminalHook = useTerminal(selectedWorktree, workspace.mainWorkspace, workspace.currentWorkspace?.path);
   const actions = useWorkspaceActions(workspace, modals, terminalHook.cleanupTerminalsForPath, locks, isMobileWeb, selectedWorktree, setSelectedWorktree);
@@ -162,11 +171,94 @@ function App() {
     setWindowTitle(title);
   }, [workspace.currentWorkspace?.name, actions.selectedWorktree]);
 
+  // Recording state of the terminal the tab menu was opened for (null = unknown / no session)
+  const [terminalTabRecording, setTerminalTabRecording] = useState<boolean | null>(null);
+
   const handleTerminalTabContextMenu = useCallback((e: React.MouseEvent, path: string, name: string) => {
     e.preventDefault();
     e.stopPropagation();
     setTerminalTabMenu({ x: e.clientX, y: e.clientY, path, name });
-  }, []);
+    setTerminalTabRecording(null);
+    if (terminalHook.activatedTerminals.has(path)) {
+      ptyGetRecording(`pty-${path.replace(/[\/#]/g, '-')}`)
+        .then(recording => setTerminalTabRecording(recording !== null))
+        .catch(() => setTerminalTabRecording(null));
+    }
+  }, [terminalHook.activatedTerminals]);
+
+  const handleToggleTerminalRecording = useCallback(async (path: string, recording: boolean) => {
+    const sessionId = `pty-${path.replace(/[\/#]/g, '-')}`;
+    try {
+      if (recording) {
+        await ptyStopRecording(sessionId);
+      } else {
+        await ptyStartRecording(sessionId);
+      }
+    } catch (e) {
+      workspace.setError(String(e));
+    }
+  }, [workspace.setError]);
+
+  const [quickCommands, setQuickCommands] = useState<QuickCommand[]>([]);
+  useEffect(() => {
+    if (!workspace.config) return;
+    listQuickCommands().then(setQuickCommands).catch(() => setQuickCommands([]));
+  }, [workspace.config]);
+
+  const handleRunQuickCommand = useCallback(async (qc: QuickCommand, newSession: boolean) => {
+    const worktree = actions.selectedWorktree;
+    if (!worktree) return;
+    try {
+      const run = await runQuickCommand(worktree.name, qc.label, newSession, qc.project);
+      terminalHook.handleTerminalTabClick(run.terminal_path);
+    } catch (e) {
+      workspace.setError(String(e));
+    }
+  }, [actions.selectedWorktree, terminalHook.handleTerminalTabClick, workspace.setError]);
+
+  const projectTasks = useMemo(() => Object.fromEntries(
+    (workspace.config?.projects ?? []).map(p => [p.name, p.tasks ?? {}])
+  ), [workspace.config]);
+
+  const handleRunTask = useCallback(async (project: string, task: string) => {
+    const worktree = actions.selectedWorktree;
+    if (!worktree) return;
+    try {
+      const run = await runTask(worktree.name, project, task);
+      terminalHook.handleTerminalTabClick(run.terminal_path);
+    } catch (e) {
+      workspace.setError(String(e));
+    }
+  }, [actions.selectedWorktree, terminalHook.handleTerminalTabClick, workspace.setError]);
+
+  const projectTerminalPresets = useMemo(() => Object.fromEntries(
+    (workspace.config?.projects ?? []).map(p => [p.name, p.terminal_presets ?? []])
+  ), [workspace.config]);
+
+  const handleOpenTerminalPreset = useCallback(async (project: string, preset: string) => {
+    const worktree = actions.selectedWorktree;
+    if (!worktree) return;
+    try {
+      const run = await openTerminalPreset(worktree.name, project, preset);
+      terminalHook.handleTerminalTabClick(run.terminal_path, preset);
+    } catch (e) {
+      workspace.setError(String(e));
+    }
+  }, [actions.selectedWorktree, terminalHook.handleTerminalTabClick, workspace.setError]);
+
+  const handleImportBundle = useCallback(async () => {
+    try {
+      const result = await importWorktreeBundle();
+      if (!result) return;
+      await workspace.loadData();
+      const skipped = result.projects.filter(p => p.skipped).map(p => `${p.name} (${p.skipped})`);
+      if (skipped.length > 0) {
+        workspace.setError(t('sidebar.importBundleSkipped', { name: result.worktree_name, projects: skipped.join(', ') }));
+      }
+    } catch (e) {
+      workspace.setError(String(e));
+    }
+  }, [workspace.loadData, workspace.setError, t]);
 
   const openSettings = useCallback(() => {
     setViewMode('settings');
@@ -279,13 +371,25 @@ function App() {
               autoFocus
               className="bg-slate-800 border-slate-700"
             />
+            {shareTotpRequired && (
+              <Input
+                type="text"
+                inputMode="numeric"
+                autoComplete="one-time-code"
+                maxLength={6}
+                placeholder={t('app.loginTotpInput')}
+                value={browserAuth.browserLoginTotp}
+                onChange={(e) => browserAuth.setBrowserLoginTotp(e.target.value.replace(/\D/g, ''))}
+                className="bg-slate-800 border-slate-700 font-mono tracking-widest"
+              />
+            )}
             {browserAuth.browserLoginError && (
               <p className="text-sm text-red-400">{browserAuth.browserLoginError}</p>
             )}
             <Button
               type="submit"
               className="w-full"
-              disabled={browserAuth.browserLoggingIn || !browserAuth.browserLoginPassword.trim()}
+              disabled={browserAuth.browserLoggingIn || !browserAuth.browserLoginPassword.trim() || (shareTotpRequired && browserAuth.browserLoginTotp.length !== 6)}
             >
               {browserAuth.browserLoggingIn ? t('app.loginVerifying') : t('app.loginEnter')}
             </Button>
@@ -330,6 +434,7 @@ function App() {
   return (
     <ToastProvider>
       <>
+        {isTauri() && <DiskQuotaWatcher />}
         {/* Loading overlay */}
         {workspace.loading && (
           <div className="fixed inset-0 z-50 bg-slate-900 flex items-center justify-center">
@@ -358,7 +463,7 @@ function App() {
               workspaceConfig={workspace.config}
               configPath={workspace.configPath}
               error={workspace.error}
-              onBack={() => setViewMode('main')}
+              onBack={() => { setViewMode('main'); actions.refreshEditors(); }}
               onSaveConfig={handleSaveConfig}
               onClearError={() => workspace.setError(null)}
               onCheckUpdate={() => updater.checkForUpdates(false)}
@@ -390,7 +495,10 @@ function App() {
               showArchived={modals.showArchived}
               onToggleArchived={() => modals.toggleModal('showArchived')}
               onContextMenu={actions.handleContextMenu}
-              onRefresh={workspace.loadData}
+              onRefresh={workspace.refreshWorkspace}
+              refreshProgress={workspace.refreshProgress}
+              onOpenBulkArchive={() => setShowBulkArchive(true)}
+              onImportBundle={isTauri() ? handleImportBundle : undefined}
               onOpenSettings={openSettings}
               onOpenCreateModal={actions.openCreateModal}
               updaterState={updater.state}
@@ -403,12 +511,23 @@ function App() {
               shareActive={share.shareActive}
               shareUrls={share.shareUrls}
               shareNgrokUrl={share.shareNgrokUrl}
+              shareCloudflareUrl={share.shareCloudflareUrl}
+              shareFrpUrl={share.shareFrpUrl}
+              tailscaleUrls={share.tailscaleUrls}
+              shareFunnelUrl={share.shareFunnelUrl}
               sharePassword={share.sharePassword}
               onStartShare={share.handleStartShare}
+              onStartShareWithProfile={share.handleStartShareWithProfile}
               onStopShare={share.handleStopShare}
               onUpdateSharePassword={share.handleUpdateSharePassword}
               ngrokLoading={share.ngrokLoading}
               onToggleNgrok={share.handleToggleNgrok}
+              cloudflareLoading={share.cloudflareLoading}
+              onToggleCloudflare={share.handleToggleCloudflare}
+              frpLoading={share.frpLoading}
+              onToggleFrp={share.handleToggleFrp}
+              funnelLoading={share.funnelLoading}
+              onToggleFunnel={share.handleToggleFunnel}
               shareWmsUrl={share.shareWmsUrl}
               wmsConnected={share.wmsConnected}
               wmsReconnecting={share.wmsReconnecting}
@@ -418,11 +537,22 @@ function App() {
               onToggleWms={share.handleToggleWms}
               onWmsManualReconnect={share.handleWmsManualReconnect}
               connectedClients={share.connectedClients}
+              shareActivity={share.shareActivity}
+              onClearShareActivity={share.handleClearShareActivity}
               onKickClient={share.handleKickClient}
+              onToggleQuarantine={share.handleToggleQuarantine}
+              onSetClientRole={share.handleSetClientRole}
               hasLastConfig={share.hasLastConfig}
               onQuickShare={share.handleQuickShare}
               hasNgrokToken={share.hasNgrokToken}
+              cloudflareEnabled={share.cloudflareEnabled}
+              frpEnabled={share.frpEnabled}
               occupation={mainOccupation.occupation}
+              baseUpdates={baseUpdates.baseUpdates}
+              syncingBaseWorktree={baseUpdates.syncingWorktree}
+              onSyncBaseUpdates={baseUpdates.handleSyncBaseUpdates}
+              runningProcesses={runningProcesses}
+              ciStatus={ciStatus}
             />
           )}
 
@@ -432,6 +562,7 @@ function App() {
                 <WorktreeDetail
                   selectedWorktree={actions.selectedWorktree}
                   mainWorkspace={workspace.mainWorkspace}
+                  editors={actions.editors}
                   selectedEditor={actions.selectedEditor}
                   showEditorMenu={modals.showEditorMenu}
                   onShowEditorMenu={(v) => modals.setModal('showEditorMenu', v)}
@@ -451,6 +582,12 @@ function App() {
                   onClearError={() => workspace.setError(null)}
                   onRefresh={workspace.loadData}
                   onOpenTerminalPanel={terminalHook.handleTerminalTabClick}
+                  quickCommands={quickCommands}
+                  onRunQuickCommand={handleRunQuickCommand}
+                  projectTasks={projectTasks}
+                  onRunTask={handleRunTask}
+                  projectTerminalPresets={projectTerminalPresets}
+                  onOpenTerminalPreset={handleOpenTerminalPreset}
                   occupation={mainOccupation.occupation}
                   deploying={mainOccupation.deploying}
                   exiting={mainOccupation.exiting}
@@ -547,6 +684,16 @@ function App() {
               y={actions.contextMenu.y}
               onClose={() => actions.setContextMenu(null)}
               onArchive={() => actions.openArchiveModal(actions.contextMenu!.worktree)}
+              onRename={() => {
+                const wt = actions.contextMenu!.worktree;
+                actions.setContextMenu(null);
+                actions.handleRenameWorktree(wt);
+              }}
+              onDownload={() => {
+                const wt = actions.contextMenu!.worktree;
+                actions.setContextMenu(null);
+                downloadPath(wt.path).catch((e) => workspace.setError(String(e)));
+              }}
             />
           )}
 
@@ -571,15 +718,28 @@ function App() {
                 terminalHook.handleCloseAllTerminalTabs();
                 setTerminalTabMenu(null);
               }}
+              recording={terminalTabRecording}
+              onToggleRecording={() => {
+                handleToggleTerminalRecording(terminalTabMenu.path, terminalTabRecording === true);
+                setTerminalTabMenu(null);
+              }}
             />
           )}
 
+          <BulkArchiveModal
+            open={showBulkArchive}
+            onOpenChange={setShowBulkArchive}
+            worktrees={workspace.worktrees}
+            onArchived={workspace.loadData}
+          />
+
           {/* Archive Confirmation Modal */}
           {actions.archiveModal && (
             <ArchiveConfirmationModal
               archiveModal={actions.archiveModal}
               onClose={() => actions.setArchiveModal(null)}
               onConfirmIssue={actions.confirmArchiveIssue}
+              onStatusChanged={actions.refreshArchiveStatus}
               onArchive={actions.handleArchiveWorktree}
               areAllIssuesConfirmed={actions.allArchiveIssuesConfirmed}
               archiving={actions.archiving}
@@ -0,0 +1,85 @@
+import { useEffect, useRef, useState, type FC } from 'react';
+import { useTranslation } from 'react-i18next';
+import {
+  Dialog,
+  DialogContent,
+  DialogHeader,
+  DialogTitle,
+  DialogDescription,
+} from '@/components/ui/dialog';
+import { getWebSocketManager } from '../lib/websocket';
+
+/** Lines fetched when the viewer opens */
+const TAIL_LINES = 500;
+/** Older lines are dropped once the viewer holds this many */
+const MAX_LINES = 5000;
+
+interface AppLogsDialogProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+}
+
+/** Live view of the host's app log for remote admin sessions (browser mode). */
+export const AppLogsDialog: FC<AppLogsDialogProps> = ({ open, onOpenChange }) => {
+  const { t } = useTranslation();
+  const [lines, setLines] = useState<string[]>([]);
+  const [error, setError] = useState<string | null>(null);
+  const scrollRef = useRef<HTMLDivElement>(null);
+  const stickToBottomRef = useRef(true);
+
+  useEffect(() => {
+    if (!open) return;
+    setLines([]);
+    setError(null);
+    stickToBottomRef.current = true;
+    return getWebSocketManager().subscribeAppLogs(TAIL_LINES, (msg) => {
+      if (msg.error) {
+        setError(msg.error);
+      } else if (msg.tail) {
+        setLines(msg.tail);
+      } else if (msg.line !== undefined) {
+        const line = msg.line;
+        setLines(prev => [...prev, line].slice(-MAX_LINES));
+      }
+    });
+  }, [open]);
+
+  useEffect(() => {
+    const el = scrollRef.current;
+    if (el && stickToBottomRef.current) {
+      el.scrollTop = el.scrollHeight;
+    }
+  }, [lines]);
+
+  const handleScroll = () => {
+    const el = scrollRef.current;
+    if (!el) return;
+    stickToBottomRef.current = el.scrollHeight - el.scrollTop - el.clientHeight < 24;
+  };
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="max-w-[900px]">
+        <DialogHeader>
+          <DialogTitle>{t('appLogs.title')}</DialogTitle>
+          <DialogDescription>{t('appLogs.description')}</DialogDescription>
+        </DialogHeader>
+        {error ? (
+          <div className="text-sm text-red-400">{t('appLogs.forbidden')}</div>
+        ) : (
+          <div
+            ref={scrollRef}
+            onScroll={handleScroll}
+            className="h-[60vh] overflow-auto rounded border border-slate-700 bg-slate-900 p-2 font-mono text-xs text-slate-300 whitespace-pre"
+          >
+            {lines.length === 0 ? (
+              <div className="text-slate-500">{t('appLogs.empty')}</div>
+            ) : (
+              lines.map((line, i) => <div key={i}>{line}</div>)
+            )}
+          </div>
+        )}
+      </DialogContent>
+    </Dialog>
+  );
+};
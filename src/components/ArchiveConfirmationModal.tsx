@@ -1,13 +1,25 @@
-import type { FC } from 'react';
+import { useState, type FC } from 'react';
 import { useTranslation } from 'react-i18next';
 import { Button } from '@/components/ui/button';
 import { StatusDot, GitBranchIcon, RefreshIcon, CheckIcon, CheckCircleIcon } from './Icons';
 import type { ArchiveModalState } from '../types';
+import { cleanUntracked, discardChanges } from '../lib/backend';
+
+/** Preview rows shown before the list is collapsed into "+N" */
+const CLEAN_PREVIEW_LIMIT = 20;
+
+interface CleanPreview {
+  project: string;
+  path: string;
+  discard: string[];
+  untracked: string[];
+}
 
 interface ArchiveConfirmationModalProps {
   archiveModal: ArchiveModalState;
   onClose: () => void;
   onConfirmIssue: (issueKey: string) => void;
+  onStatusChanged?: () => Promise<void>;
   onArchive: () => void;
   areAllIssuesConfirmed: boolean;
   archiving?: boolean;
@@ -17,11 +29,54 @@ export const ArchiveConfirmationModal: FC<ArchiveConfirmationModalProps> = ({
   archiveModal,
   onClose,
   onConfirmIssue,
+  onStatusChanged,
   onArchive,
   areAllIssuesConfirmed,
   archiving = false,
 }) => {
   const { t } = useTranslation();
+  const [cleanPreview, setCleanPreview] = useState<CleanPreview | null>(null);
+  const [cleaning, setCleaning] = useState(false);
+  const [cleanError, setCleanError] = useState<string | null>(null);
+
+  const handlePreviewClean = async (project: string) => {
+    const path = archiveModal.worktree.projects.find(p => p.name === project)?.path;
+    if (!path) return;
+    setCleaning(true);
+    setCleanError(null);
+    try {
+      const [discard, untracked] = await Promise.all([
+        discardChanges(path, [], true),
+        cleanUntracked(path, [], true),
+      ]);
+      setCleanPreview({ project, path, discard, untracked });
+    } catch (e) {
+      setCleanError(String(e));
+    } finally {
+      setCleaning(false);
+    }
+  };
+
+  const handleConfirmClean = async () => {
+    if (!cleanPreview) return;
+    setCleaning(true);
+    setCleanError(null);
+    try {
+      // 只处理预览过的文件；空列表表示"全部"，因此跳过
+      if (cleanPreview.discard.length > 0) {
+        await discardChanges(cleanPreview.path, cleanPreview.discard);
+      }
+      if (cleanPreview.untracked.length > 0) {
+        await cleanUntracked(cleanPreview.path, cleanPreview.untracked);
+      }
+      setCleanPreview(null);
+      await onStatusChanged?.();
+    } catch (e) {
+      setCleanError(String(e));
+    } finally {
+      setCleaning(false);
+    }
+  };
   return (
     <div className="fixed inset-0 bg-black/60 flex items-center justify-center z-50">
       <div className="bg-slate-800 border border-slate-700 rounded-xl w-[520px] max-h-[80vh] overflow-hidden shadow-2xl">
@@ -78,17 +133,54 @@ export const ArchiveConfirmationModal: FC<ArchiveConfirmationModalProps> = ({
                                     {t('archive.confirmed')}
                                   </span>
                                 ) : (
-                                  <Button
-                                    variant="outline"
-                                    size="sm"
-                                    onClick={() => onConfirmIssue(uncommittedKey)}
-                                    className="h-6 px-2 text-xs bg-amber-600/30 hover:bg-amber-600/50 text-amber-300 border-amber-600/50"
-                                  >
-                                    {t('archive.confirmNoIssue')}
-                                  </Button>
+                                  <div className="flex items-center gap-1.5">
+                                    {onStatusChanged && (
+                                      <Button
+                                        variant="ghost"
+                                        size="sm"
+                                        onClick={() => handlePreviewClean(proj.project_name)}
+                                        disabled={cleaning}
+                                        className="h-6 px-2 text-xs text-slate-400 hover:text-red-300"
+                                      >
+                                        {t('archive.cleanChanges')}
+                                      </Button>
+                                    )}
+                                    <Button
+                                      variant="outline"
+                                      size="sm"
+                                      onClick={() => onConfirmIssue(uncommittedKey)}
+                                      className="h-6 px-2 text-xs bg-amber-600/30 hover:bg-amber-600/50 text-amber-300 border-amber-600/50"
+                                    >
+                                      {t('archive.confirmNoIssue')}
+                                    </Button>
+                                  </div>
                                 )}
                               </div>
                             )}
+                            {cleanPreview?.project === proj.project_name && (
+                              <div className="bg-red-950/30 border border-red-900/50 rounded p-2 space-y-1">
+                                <p className="text-xs text-red-300">
+                                  {t('archive.cleanPreview', { discard: cleanPreview.discard.length, untracked: cleanPreview.untracked.length })}
+                                </p>
+                                <ul className="text-[11px] text-slate-400 font-mono max-h-32 overflow-y-auto select-text">
+                                  {[...cleanPreview.discard, ...cleanPreview.untracked].slice(0, CLEAN_PREVIEW_LIMIT).map(f => (
+                                    <li key={f} className="truncate">{f}</li>
+                                  ))}
+                                  {cleanPreview.discard.length + cleanPreview.untracked.length > CLEAN_PREVIEW_LIMIT && (
+                                    <li>+{cleanPreview.discard.length + cleanPreview.untracked.length - CLEAN_PREVIEW_LIMIT}</li>
+                                  )}
+                                </ul>
+                                <div className="flex justify-end gap-1.5">
+                                  <Button variant="ghost" size="sm" className="h-6 px-2 text-xs" onClick={() => setCleanPreview(null)} disabled={cleaning}>
+                                    {t('common.cancel')}
+                                  </Button>
+                                  <Button variant="destructive" size="sm" className="h-6 px-2 text-xs" onClick={handleConfirmClean}
+                                    disabled={cleaning || cleanPreview.discard.length + cleanPreview.untracked.length === 0}>
+                                    {cleaning ? t('archive.cleaning') : t('archive.confirmClean')}
+                                  </Button>
+                                </div>
+                              </div>
+                            )}
                             {hasUnpushed && (
                               <div className="flex items-center justify-between">
                                 <span className={`text-xs ${unpushedConfirmed ? 'text-amber-400/60 line-through' : 'text-amber-400'}`}>
@@ -124,6 +216,10 @@ export const ArchiveConfirmationModal: FC<ArchiveConfirmationModalProps> = ({
                 </div>
               </div>
 
+              {cleanError && (
+                <p className="text-xs text-red-400 select-text">{cleanError}</p>
+              )}
+
               {areAllIssuesConfirmed && (
                 <div className="bg-emerald-900/20 border border-emerald-800/50 rounded-lg p-4">
                   <div className="flex items-center gap-2 text-emerald-400 font-medium">
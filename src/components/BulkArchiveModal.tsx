@@ -0,0 +1,136 @@
+import { useEffect, useState, type FC } from 'react';
+import { useTranslation } from 'react-i18next';
+import {
+  Dialog,
+  DialogContent,
+  DialogHeader,
+  DialogFooter,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { archiveWorktrees } from '../lib/backend';
+import type { BulkArchiveResult, WorktreeListItem } from '../types';
+
+interface BulkArchiveModalProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+  worktrees: WorktreeListItem[];
+  onArchived: () => void;
+}
+
+const outcomeColor: Record<BulkArchiveResult['outcome'], string> = {
+  archived: 'text-green-400',
+  blocked: 'text-amber-400',
+  failed: 'text-red-400',
+};
+
+/** Archive several worktrees at once; ones failing the archive check are skipped with reasons. */
+export const BulkArchiveModal: FC<BulkArchiveModalProps> = ({
+  open,
+  onOpenChange,
+  worktrees,
+  onArchived,
+}) => {
+  const { t } = useTranslation();
+  const [selected, setSelected] = useState<Set<string>>(new Set());
+  const [archiving, setArchiving] = useState(false);
+  const [results, setResults] = useState<BulkArchiveResult[] | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const candidates = worktrees.filter(wt => !wt.is_archived);
+
+  useEffect(() => {
+    if (open) {
+      setSelected(new Set());
+      setResults(null);
+      setError(null);
+    }
+  }, [open]);
+
+  const toggle = (name: string) => {
+    setSelected(prev => {
+      const next = new Set(prev);
+      if (next.has(name)) next.delete(name); else next.add(name);
+      return next;
+    });
+  };
+
+  const handleArchive = async () => {
+    setArchiving(true);
+    setError(null);
+    try {
+      const res = await archiveWorktrees([...selected]);
+      setResults(res);
+      if (res.some(r => r.outcome === 'archived')) onArchived();
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setArchiving(false);
+    }
+  };
+
+  return (
+    <Dialog open={open} onOpenChange={(v) => { if (!archiving) onOpenChange(v); }}>
+      <DialogContent className="max-w-[560px] max-h-[80vh] overflow-hidden p-0">
+        <DialogHeader className="p-5 border-b border-slate-700">
+          <DialogTitle>{t('bulkArchive.title')}</DialogTitle>
+        </DialogHeader>
+        <div className="p-5 overflow-y-auto max-h-[60vh] space-y-2">
+          {results ? (
+            results.map(r => (
+              <div key={r.name} className="p-3 rounded-lg border border-slate-700 bg-slate-900/50">
+                <div className="flex items-center justify-between">
+                  <span className="font-medium text-slate-200">{r.name}</span>
+                  <span className={`text-xs ${outcomeColor[r.outcome]}`}>{t(`bulkArchive.outcome.${r.outcome}`)}</span>
+                </div>
+                {r.reasons.map(reason => (
+                  <div key={reason} className="text-xs text-red-300 mt-1 select-text">{reason}</div>
+                ))}
+                {r.warnings.map(warning => (
+                  <div key={warning} className="text-xs text-slate-400 mt-1 select-text">{warning}</div>
+                ))}
+              </div>
+            ))
+          ) : candidates.length === 0 ? (
+            <div className="text-sm text-slate-500">{t('bulkArchive.empty')}</div>
+          ) : (
+            <>
+              <p className="text-xs text-slate-400 mb-3">{t('bulkArchive.hint')}</p>
+              {candidates.map(wt => (
+                <div
+                  key={wt.name}
+                  className={`p-3 rounded-lg border cursor-pointer transition-all flex items-center gap-3 ${
+                    selected.has(wt.name)
+                      ? 'bg-blue-900/20 border-blue-500/50'
+                      : 'bg-slate-900/50 border-slate-700 hover:border-slate-600'
+                  }`}
+                  onClick={() => !archiving && toggle(wt.name)}
+                >
+                  <Checkbox checked={selected.has(wt.name)} onChange={() => {}} />
+                  <span className="font-medium text-slate-200 truncate">{wt.name}</span>
+                  <span className="text-xs text-slate-500 ml-auto">{t('sidebar.projects', { count: wt.projects.length })}</span>
+                </div>
+              ))}
+            </>
+          )}
+          {error && (
+            <div className="text-xs text-red-400 bg-red-900/20 border border-red-800/30 rounded p-2 select-text">{error}</div>
+          )}
+        </div>
+        <DialogFooter className="p-5 border-t border-slate-700">
+          {results ? (
+            <Button onClick={() => onOpenChange(false)}>{t('common.close')}</Button>
+          ) : (
+            <>
+              <Button variant="secondary" onClick={() => onOpenChange(false)} disabled={archiving}>{t('common.cancel')}</Button>
+              <Button onClick={handleArchive} disabled={archiving || selected.size === 0}>
+                {archiving ? t('bulkArchive.archiving') : t('bulkArchive.archiveCount', { count: selected.size })}
+              </Button>
+            </>
+          )}
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+};
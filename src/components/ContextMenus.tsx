@@ -8,6 +8,8 @@ interface ContextMenuProps {
   y: number;
   onClose: () => void;
   onArchive: () => void;
+  onRename: () => void;
+  onDownload: () => void;
 }
 
 export const WorktreeContextMenu: FC<ContextMenuProps> = ({
@@ -15,6 +17,8 @@ export const WorktreeContextMenu: FC<ContextMenuProps> = ({
   y,
   onClose,
   onArchive,
+  onRename,
+  onDownload,
 }) => {
   const { t } = useTranslation();
   return (
@@ -27,6 +31,15 @@ export const WorktreeContextMenu: FC<ContextMenuProps> = ({
         style={{ left: x, top: y }}
         onClick={(e) => e.stopPropagation()}
       >
+        <button
+          onClick={onRename}
+          className="w-full px-4 py-2 text-left text-sm text-slate-200 hover:bg-slate-700 flex items-center gap-2"
+        >
+          <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+            <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={1.5} d="M16.862 4.487l1.687-1.688a1.875 1.875 0 112.652 2.652L6.832 19.82a4.5 4.5 0 01-1.897 1.13l-2.685.8.8-2.685a4.5 4.5 0 011.13-1.897L16.863 4.487z" />
+          </svg>
+          {t('contextMenu.rename')}
+        </button>
         {isTauri() && (
         <button
           onClick={onArchive}
@@ -36,6 +49,17 @@ export const WorktreeContextMenu: FC<ContextMenuProps> = ({
           {t('contextMenu.archive')}
         </button>
         )}
+        {!isTauri() && (
+        <button
+          onClick={onDownload}
+          className="w-full px-4 py-2 text-left text-sm text-slate-200 hover:bg-slate-700 flex items-center gap-2"
+        >
+          <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+            <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={1.5} d="M3 16.5v2.25A2.25 2.25 0 005.25 21h13.5A2.25 2.25 0 0021 18.75V16.5M16.5 12L12 16.5m0 0L7.5 12m4.5 4.5V3" />
+          </svg>
+          {t('contextMenu.download')}
+        </button>
+        )}
       </div>
     </div>
   );
@@ -49,6 +73,9 @@ interface TerminalTabContextMenuProps {
   onCloseTab: () => void;
   onCloseOtherTabs: () => void;
   onCloseAllTabs: () => void;
+  /** Whether the terminal is being recorded; the record item is hidden until known */
+  recording?: boolean | null;
+  onToggleRecording?: () => void;
 }
 
 export const TerminalTabContextMenu: FC<TerminalTabContextMenuProps> = ({
@@ -59,6 +86,8 @@ export const TerminalTabContextMenu: FC<TerminalTabContextMenuProps> = ({
   onCloseTab,
   onCloseOtherTabs,
   onCloseAllTabs,
+  recording,
+  onToggleRecording,
 }) => {
   const { t } = useTranslation();
   return (
@@ -80,6 +109,19 @@ export const TerminalTabContextMenu: FC<TerminalTabContextMenuProps> = ({
           </svg>
           {t('contextMenu.duplicateTerminal')}
         </button>
+        {onToggleRecording && recording != null && (
+          <button
+            onClick={onToggleRecording}
+            className="w-full px-4 py-2 text-left text-sm text-slate-200 hover:bg-slate-700 flex items-center gap-2"
+          >
+            <span className="w-4 h-4 flex items-center justify-center">
+              {recording
+                ? <span className="w-2.5 h-2.5 rounded-sm bg-red-400" />
+                : <span className="w-2.5 h-2.5 rounded-full bg-red-500" />}
+            </span>
+            {recording ? t('contextMenu.stopRecording') : t('contextMenu.startRecording')}
+          </button>
+        )}
         <div className="border-t border-slate-700 my-1" />
         <button
           onClick={onCloseTab}
@@ -17,6 +17,9 @@ import {
   SelectTrigger,
   SelectValue,
 } from '@/components/ui/select';
+import { listen } from '@tauri-apps/api/event';
+import { isTauri } from '../lib/backend';
+import { getWebSocketManager } from '../lib/websocket';
 import type { WorkspaceConfig } from '../types';
 
 // Git branch name rules: no spaces, ~, ^, :, \, .., *, ?, [, leading/trailing dots, @{
@@ -73,6 +76,28 @@ export const CreateWorktreeModal: FC<CreateWorktreeModalProps> = ({
     };
   }, [creating]);
 
+  // Queue position while another operation holds a project's main repo
+  const [queueWait, setQueueWait] = useState<{ projectName: string; ahead: number } | null>(null);
+
+  useEffect(() => {
+    if (!creating) {
+      setQueueWait(null);
+      return;
+    }
+    const handle = (msg: { worktreeName: string; projectName: string; ahead: number }) => {
+      if (msg.worktreeName !== worktreeName) return;
+      setQueueWait(msg.ahead > 0 ? { projectName: msg.projectName, ahead: msg.ahead } : null);
+    };
+    if (isTauri()) {
+      const unlisten = listen<{ worktreeName: string; projectName: string; ahead: number }>(
+        'worktree-queue',
+        (event) => handle(event.payload),
+      );
+      return () => { unlisten.then(fn => fn()); };
+    }
+    return getWebSocketManager().onWorktreeQueue(handle);
+  }, [creating, worktreeName]);
+
   const formatElapsed = (s: number) => {
     const min = Math.floor(s / 60);
     const sec = s % 60;
@@ -175,6 +200,11 @@ export const CreateWorktreeModal: FC<CreateWorktreeModalProps> = ({
               </div>
               <span className="whitespace-nowrap tabular-nums">{t('common.creating')} {formatElapsed(elapsedSeconds)}</span>
             </div>
+            {queueWait && (
+              <div className="text-xs text-amber-400/80 mt-1">
+                {t('createWorktree.queueWaiting', { project: queueWait.projectName, count: queueWait.ahead })}
+              </div>
+            )}
           </div>
         )}
         <DialogFooter className="p-5 border-t border-slate-700">
@@ -0,0 +1,27 @@
+import { useEffect, type FC } from 'react';
+import { useTranslation } from 'react-i18next';
+import { listen } from '@tauri-apps/api/event';
+import { useToast } from './Toast';
+import type { DiskUsageReport } from '../types';
+
+/** Shows a warning toast when the backend reports worktrees over the workspace disk quota. */
+export const DiskQuotaWatcher: FC = () => {
+  const { t } = useTranslation();
+  const { toast } = useToast();
+
+  useEffect(() => {
+    const unlisten = listen<DiskUsageReport>('disk-quota-warning', (event) => {
+      const report = event.payload;
+      const quota = report.quota_bytes != null ? `${Math.round(report.quota_bytes / 1024 / 1024)} MB` : '-';
+      const top = report.worktrees.map(w => `${w.name} (${w.size_display})`).join(', ');
+      let message = t('app.diskQuotaWarning', { total: report.total_display, quota, top });
+      if (report.archive_candidates.length > 0) {
+        message += '\n' + t('app.diskQuotaCandidates', { names: report.archive_candidates.join(', ') });
+      }
+      toast('warning', message);
+    });
+    return () => { unlisten.then(fn => fn()); };
+  }, [t, toast]);
+
+  return null;
+};
@@ -28,6 +28,7 @@ import {
 import {
   syncWithBaseBranch,
   pushToRemote,
+  amendCommit,
   mergeToTestBranch,
   mergeToBaseBranch,
   getBranchDiffStats,
@@ -64,7 +65,7 @@ export const GitOperations: FC<GitOperationsProps> = ({
   const [stats, setStats] = useState<BranchDiffStats | null>(null);
   const [loading, setLoading] = useState(false);
   const [fetchingSyncing, setFetchingSyncing] = useState(false);
-  const [activeAction, setActiveAction] = useState<'sync' | 'push' | 'mergeTest' | 'mergeBase' | null>(null);
+  const [activeAction, setActiveAction] = useState<'sync' | 'push' | 'amend' | 'mergeTest' | 'mergeBase' | null>(null);
   const [showPRModal, setShowPRModal] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [errorPersistent, setErrorPersistent] = useState(false);
@@ -73,6 +74,9 @@ export const GitOperations: FC<GitOperationsProps> = ({
   const [baseBranchExists, setBaseBranchExists] = useState<boolean | null>(null);
   const [dismissing, setDismissing] = useState<'error' | 'success' | null>(null);
   const [showMergeBaseConfirm, setShowMergeBaseConfirm] = useState(false);
+  const [showAmendDialog, setShowAmendDialog] = useState(false);
+  const [amendMessage, setAmendMessage] = useState('');
+  const [amendIncludeStaged, setAmendIncludeStaged] = useState(false);
   const errorTimerRef = useRef<ReturnType<typeof setTimeout> | undefined>(undefined);
   const successTimerRef = useRef<ReturnType<typeof setTimeout> | undefined>(undefined);
 
@@ -199,6 +203,18 @@ export const GitOperations: FC<GitOperationsProps> = ({
     runGitAction('mergeBase', () => mergeToBaseBranch(projectPath, baseBranch));
   };
 
+  const confirmAmend = () => {
+    setShowAmendDialog(false);
+    const message = amendMessage;
+    const includeStaged = amendIncludeStaged;
+    setAmendMessage('');
+    setAmendIncludeStaged(false);
+    runGitAction('amend', async () => {
+      const sha = await amendCommit(projectPath, message, includeStaged);
+      return t('git.amendSuccess', { sha });
+    });
+  };
+
   const actionsDisabled = fetchingSyncing || activeAction !== null;
 
   // Push tooltip: show ahead commit count
@@ -265,15 +281,27 @@ export const GitOperations: FC<GitOperationsProps> = ({
             </div>
           ) : null}
         </div>
-        <Button
-          variant="ghost"
-          size="sm"
-          onClick={handleRefresh}
-          disabled={loading}
-          className="h-6 px-2"
-        >
-          <RefreshIcon className={`w-3 h-3 ${loading ? 'animate-spin' : ''}`} />
-        </Button>
+        <div className="flex items-center gap-1">
+          <Button
+            variant="ghost"
+            size="sm"
+            onClick={() => setShowAmendDialog(true)}
+            disabled={loading || actionsDisabled}
+            className="h-6 px-2 text-xs text-slate-400"
+            title={t('git.amendTooltip')}
+          >
+            {activeAction === 'amend' ? t('git.amending') : t('git.amendLabel')}
+          </Button>
+          <Button
+            variant="ghost"
+            size="sm"
+            onClick={handleRefresh}
+            disabled={loading}
+            className="h-6 px-2"
+          >
+            <RefreshIcon className={`w-3 h-3 ${loading ? 'animate-spin' : ''}`} />
+          </Button>
+        </div>
       </div>
 
       <div className="flex flex-col gap-2">
@@ -391,6 +419,37 @@ export const GitOperations: FC<GitOperationsProps> = ({
         </DialogContent>
       </Dialog>
 
+      {/* Amend last commit dialog */}
+      <Dialog open={showAmendDialog} onOpenChange={setShowAmendDialog}>
+        <DialogContent className="max-w-[420px]">
+          <DialogHeader>
+            <DialogTitle>{t('git.amendTitle')}</DialogTitle>
+            <DialogDescription>{t('git.amendDesc')}</DialogDescription>
+          </DialogHeader>
+          <div className="space-y-3">
+            <textarea
+              value={amendMessage}
+              onChange={(e) => setAmendMessage(e.target.value)}
+              placeholder={t('git.amendMessagePlaceholder')}
+              rows={3}
+              className="flex w-full rounded-md border border-slate-700 bg-slate-900 px-3 py-2 text-sm text-slate-100 placeholder:text-slate-500 focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-blue-500 resize-none"
+            />
+            <label className="flex items-center gap-2 text-xs text-slate-400 cursor-pointer">
+              <input type="checkbox" checked={amendIncludeStaged} onChange={(e) => setAmendIncludeStaged(e.target.checked)} className="h-3 w-3" />
+              {t('git.amendIncludeStaged')}
+            </label>
+          </div>
+          <DialogFooter>
+            <Button variant="secondary" onClick={() => setShowAmendDialog(false)}>
+              {t('common.cancel')}
+            </Button>
+            <Button onClick={confirmAmend} disabled={!amendMessage.trim() && !amendIncludeStaged}>
+              {t('git.confirmAmend')}
+            </Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
+
       <CreatePRModal
         open={showPRModal}
         onOpenChange={setShowPRModal}
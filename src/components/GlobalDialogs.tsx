@@ -1,4 +1,4 @@
-import type { FC } from 'react';
+import { useState, type FC } from 'react';
 import { useTranslation } from 'react-i18next';
 import {
   Dialog,
@@ -30,7 +30,7 @@ interface GlobalDialogsProps {
   onOpenSettings: () => void;
   deleteConfirmWorktree: WorktreeListItem | null;
   onSetDeleteConfirmWorktree: (v: WorktreeListItem | null) => void;
-  onDeleteArchivedWorktree: () => Promise<void>;
+  onDeleteArchivedWorktree: (deleteRemote: boolean) => Promise<void>;
   deletingArchived: boolean;
 }
 
@@ -46,6 +46,7 @@ export const GlobalDialogs: FC<GlobalDialogsProps> = ({
   deletingArchived,
 }) => {
   const { t } = useTranslation();
+  const [deleteRemoteBranches, setDeleteRemoteBranches] = useState(false);
 
   return (
     <>
@@ -216,7 +217,15 @@ export const GlobalDialogs: FC<GlobalDialogsProps> = ({
       </Dialog>
 
       {/* Delete Archived Worktree Confirmation */}
-      <Dialog open={!!deleteConfirmWorktree} onOpenChange={(open) => !open && onSetDeleteConfirmWorktree(null)}>
+      <Dialog
+        open={!!deleteConfirmWorktree}
+        onOpenChange={(open) => {
+          if (!open) {
+            onSetDeleteConfirmWorktree(null);
+            setDeleteRemoteBranches(false);
+          }
+        }}
+      >
         <DialogContent className="max-w-[400px]">
           <DialogHeader>
             <DialogTitle>{t('app.deleteArchivedTitle')}</DialogTitle>
@@ -224,11 +233,20 @@ export const GlobalDialogs: FC<GlobalDialogsProps> = ({
               {t('app.deleteArchivedDesc', { name: deleteConfirmWorktree?.name })}
             </DialogDescription>
           </DialogHeader>
+          <label className="flex items-center gap-2 text-sm text-slate-300">
+            <input
+              type="checkbox"
+              checked={deleteRemoteBranches}
+              onChange={(e) => setDeleteRemoteBranches(e.target.checked)}
+              disabled={deletingArchived}
+            />
+            {t('app.deleteArchivedRemote')}
+          </label>
           <DialogFooter>
             <Button variant="secondary" onClick={() => onSetDeleteConfirmWorktree(null)}>
               {t('common.cancel')}
             </Button>
-            <Button variant="destructive" onClick={onDeleteArchivedWorktree} disabled={deletingArchived}>
+            <Button variant="destructive" onClick={() => onDeleteArchivedWorktree(deleteRemoteBranches)} disabled={deletingArchived}>
               {deletingArchived ? t('app.deleting') : t('app.confirmDelete')}
             </Button>
           </DialogFooter>
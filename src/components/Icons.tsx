@@ -35,6 +35,7 @@ import {
   Mic,
   Link,
   QrCode,
+  Search,
 } from 'lucide-react';
 
 interface IconProps {
@@ -198,3 +199,7 @@ export const LinkIcon: FC<IconProps> = ({ className = "w-4 h-4" }) => (
 export const QrCodeIcon: FC<IconProps> = ({ className = "w-4 h-4" }) => (
   <QrCode className={className} />
 );
+
+export const SearchIcon: FC<IconProps> = ({ className = "w-4 h-4" }) => (
+  <Search className={className} />
+);
@@ -20,8 +20,10 @@ import {
 import { RefreshCw, Search, Mic, Eye, EyeOff, Settings, Globe, Info, Trash2 } from 'lucide-react';
 import { BackIcon, PlusIcon, TrashIcon } from './Icons';
 import { BranchCombobox } from './BranchCombobox';
-import type { WorkspaceRef, WorkspaceConfig, ProjectConfig, ScannedFolder } from '../types';
-import { getAppVersion, getNgrokToken, setNgrokToken as saveNgrokToken, getDashscopeApiKey, setDashscopeApiKey as saveDashscopeApiKey, getDashscopeBaseUrl, setDashscopeBaseUrl as saveDashscopeBaseUrl, getVoiceRefineEnabled, setVoiceRefineEnabled as saveVoiceRefineEnabled, voiceStart, voiceStop, isTauri, getRemoteBranches, openLink, callBackend, loadWorkspaceConfigByPath, saveWorkspaceConfigByPath } from '../lib/backend';
+import { formatEnv, parseEnv } from '@/lib/utils';
+import type { WorkspaceRef, WorkspaceConfig, ProjectConfig, CopyRule, CopyOverwritePolicy, ScannedFolder, ConfigWarning, ProxyConfig, QuickCommand, IssueProvider, IssueTrackerConfig, IssueTrackerAuth, CustomEditor, NotifierConfig, NotifierProvider, NotifyEvent, TerminalPreset, HookConfig, HookEvent, WebhookConfig, PortAllocationConfig, ShellConfig, PtyLimits, RateLimitConfig, CustomTlsConfig, CloudflareTunnelConfig, FrpTunnelConfig, SshHost } from '../types';
+import { getAppVersion, getNgrokToken, setNgrokToken as saveNgrokToken, getDashscopeApiKey, setDashscopeApiKey as saveDashscopeApiKey, getDashscopeBaseUrl, setDashscopeBaseUrl as saveDashscopeBaseUrl, getVoiceRefineEnabled, setVoiceRefineEnabled as saveVoiceRefineEnabled, voiceStart, voiceStop, isTauri, getRemoteBranches, openLink, callBackend, loadWorkspaceConfigByPath, saveWorkspaceConfigByPath, validateWorkspaceConfig, getProxyConfig, setProxyConfig, getConfigEncryptionEnabled, setConfigEncryptionEnabled, getIssueTrackerAuth, setIssueTrackerAuth, getNotifiers, setNotifiers, getCustomEditors, setCustomEditors, getScrollbackPersistEnabled, setScrollbackPersistEnabled, getSessionRestoreEnabled, setSessionRestoreEnabled, getPtyLimits, setPtyLimits, getRateLimitConfig, setRateLimitConfig, getSessionTtlMins, setSessionTtlMins, getCustomTls, setCustomTls, getCloudflareTunnelConfig, setCloudflareTunnelConfig, getFrpTunnelConfig, setFrpTunnelConfig, getSshHosts, setSshHosts, listApiTokens, createApiToken, revokeApiToken, getWebhookSecret, rotateWebhookSecret, disableWebhook, getTotpEnabled, enrollTotp, confirmTotp, disableTotp, syncLinks, repairWorktrees } from '../lib/backend';
+import type { ApiToken, ShareRole, TotpEnrollment } from '../lib/backend';
 
 interface SettingsViewProps {
   workspaceConfig: WorkspaceConfig;
@@ -39,6 +41,21 @@ interface SettingsViewProps {
 
 type SettingsSection = 'workspaces' | 'share' | 'voice' | 'about';
 
+const HOOK_EVENTS: HookEvent[] = ['on_create', 'on_archive', 'on_restore', 'on_delete', 'on_deploy_to_main'];
+const NOTIFY_EVENTS: NotifyEvent[] = ['merge_to_test_succeeded', 'merge_to_test_failed', 'worktree_archived', 'share_started'];
+/** Backend defaults when the config has no port_allocation section */
+const DEFAULT_PORT_ALLOCATION: PortAllocationConfig = { enabled: true, base_port: 4000, ports_per_worktree: 10 };
+
+/** `zsh -l` ↔ { program: 'zsh', args: ['-l'] }; empty input clears the setting */
+function formatShell(shell?: ShellConfig | null): string {
+  return shell ? [shell.program, ...(shell.args ?? [])].join(' ') : '';
+}
+
+function parseShell(text: string): ShellConfig | null {
+  const [program, ...args] = text.trim().split(/\s+/).filter(Boolean);
+  return program ? { program, args } : null;
+}
+
 export const SettingsView: FC<SettingsViewProps> = ({
   workspaceConfig,
   configPath,
@@ -65,6 +82,7 @@ export const SettingsView: FC<SettingsViewProps> = ({
   // The config being edited
   const [config, setConfig] = useState<WorkspaceConfig>(() => JSON.parse(JSON.stringify(workspaceConfig)));
   const [saving, setSaving] = useState(false);
+  const [configWarnings, setConfigWarnings] = useState<ConfigWarning[]>([]);
   const [scanningProject, setScanningProject] = useState<string | null>(null);
   const [scanResultsMap, setScanResultsMap] = useState<Record<string, ScannedFolder[]>>({});
 
@@ -106,7 +124,7 @@ export const SettingsView: FC<SettingsViewProps> = ({
     setConfig(prev => ({ ...prev, [field]: value }));
   }, []);
 
-  const updateProject = useCallback((index: number, field: keyof ProjectConfig, value: string | boolean | string[]) => {
+  const updateProject = useCallback((index: number, field: keyof ProjectConfig, value: string | boolean | string[] | QuickCommand[] | TerminalPreset[] | CopyRule[] | Record<string, string> | ShellConfig | null) => {
     setConfig(prev => {
       const newProjects = [...prev.projects];
       newProjects[index] = { ...newProjects[index], [field]: value };
@@ -145,11 +163,49 @@ export const SettingsView: FC<SettingsViewProps> = ({
     }));
   }, []);
 
+  const updateQuickCommand = useCallback((index: number, patch: Partial<QuickCommand>) => {
+    setConfig(prev => {
+      const next = [...(prev.quick_commands ?? [])];
+      next[index] = { ...next[index], ...patch };
+      return { ...prev, quick_commands: next };
+    });
+  }, []);
+
+  const updateIssueTracker = useCallback((index: number, patch: Partial<IssueTrackerConfig>) => {
+    setConfig(prev => {
+      const next = [...(prev.issue_trackers ?? [])];
+      next[index] = { ...next[index], ...patch };
+      return { ...prev, issue_trackers: next };
+    });
+  }, []);
+
+  const updateWebhook = useCallback((index: number, patch: Partial<WebhookConfig>) => {
+    setConfig(prev => {
+      const next = [...(prev.webhooks ?? [])];
+      next[index] = { ...next[index], ...patch };
+      return { ...prev, webhooks: next };
+    });
+  }, []);
+
+  const updateHook = useCallback((index: number, patch: Partial<HookConfig>) => {
+    setConfig(prev => {
+      const next = [...(prev.hooks ?? [])];
+      next[index] = { ...next[index], ...patch };
+      return { ...prev, hooks: next };
+    });
+  }, []);
+
+  const portAllocation = config.port_allocation ?? DEFAULT_PORT_ALLOCATION;
+  const updatePortAllocation = useCallback((patch: Partial<PortAllocationConfig>) => {
+    setConfig(prev => ({ ...prev, port_allocation: { ...(prev.port_allocation ?? DEFAULT_PORT_ALLOCATION), ...patch } }));
+  }, []);
+
   const handleSave = useCallback(async () => {
     setSaving(true);
     try {
       if (isCurrentWs) {
         await onSaveConfig(config);
+        validateWorkspaceConfig().then(setConfigWarnings).catch(() => setConfigWarnings([]));
       } else {
         await saveWorkspaceConfigByPath(selectedWsPath, config);
       }
@@ -189,6 +245,44 @@ export const SettingsView: FC<SettingsViewProps> = ({
 
   // ==================== Other state ====================
   const [newLinkedItem, setNewLinkedItem] = useState('');
+  const [syncingLinks, setSyncingLinks] = useState(false);
+  const [syncLinksMessage, setSyncLinksMessage] = useState<string | null>(null);
+
+  const handleSyncLinks = useCallback(async () => {
+    setSyncingLinks(true);
+    setSyncLinksMessage(null);
+    try {
+      const results = await syncLinks();
+      const links = results.flatMap(r => r.link_results);
+      const created = links.filter(l => l.status === 'created').length;
+      const failed = links.filter(l => l.status === 'failed').length;
+      setSyncLinksMessage(t('settings.syncLinksResult', { created, failed, worktrees: results.length }));
+    } catch (e) {
+      setSyncLinksMessage(String(e));
+    } finally {
+      setSyncingLinks(false);
+    }
+  }, [t]);
+  const [repairingWorktrees, setRepairingWorktrees] = useState(false);
+  const [repairMessage, setRepairMessage] = useState<string | null>(null);
+  const handleRepairWorktrees = useCallback(async () => {
+    setRepairingWorktrees(true);
+    setRepairMessage(null);
+    try {
+      const results = await repairWorktrees();
+      const pruned = results.reduce((n, r) => n + r.pruned.length, 0);
+      const reregistered = results.reduce((n, r) => n + r.reregistered.length, 0);
+      const problems = [
+        ...results.filter(r => r.error).map(r => `${r.project_name}: ${r.error}`),
+        ...results.flatMap(r => r.unresolved.map(u => `${u.path}: ${u.reason}`)),
+      ];
+      setRepairMessage([t('settings.repairWorktreesResult', { pruned, reregistered, unresolved: problems.length }), ...problems].join('\n'));
+    } catch (e) {
+      setRepairMessage(String(e));
+    } finally {
+      setRepairingWorktrees(false);
+    }
+  }, [t]);
   const [newProjectLinkedFolder, setNewProjectLinkedFolder] = useState<Record<number, string>>({});
   const [appVersion, setAppVersion] = useState('');
   const [removeConfirmWorkspace, setRemoveConfirmWorkspace] = useState<WorkspaceRef | null>(null);
@@ -199,6 +293,58 @@ export const SettingsView: FC<SettingsViewProps> = ({
   const [ngrokSaving, setNgrokSaving] = useState(false);
   const [ngrokSaved, setNgrokSaved] = useState(false);
   const [ngrokError, setNgrokError] = useState<string | null>(null);
+  const [cloudflareConfig, setCloudflareConfigState] = useState<CloudflareTunnelConfig>({ enabled: false });
+  const [cloudflareSaving, setCloudflareSaving] = useState(false);
+  const [cloudflareSaved, setCloudflareSaved] = useState(false);
+  const [cloudflareError, setCloudflareError] = useState<string | null>(null);
+  const [frpConfig, setFrpConfigState] = useState<FrpTunnelConfig>({ enabled: false, server_addr: '', server_port: 7000 });
+  const [frpSaving, setFrpSaving] = useState(false);
+  const [frpSaved, setFrpSaved] = useState(false);
+  const [frpError, setFrpError] = useState<string | null>(null);
+
+  // Proxy / CA state
+  const [proxyConfig, setProxyConfigState] = useState<ProxyConfig>({ http_proxy: null, https_proxy: null, no_proxy: null, ca_cert_path: null });
+  const [proxySaving, setProxySaving] = useState(false);
+  const [proxySaved, setProxySaved] = useState(false);
+  const [proxyError, setProxyError] = useState<string | null>(null);
+  const [persistScrollback, setPersistScrollback] = useState(false);
+  const [issueAuth, setIssueAuthState] = useState<IssueTrackerAuth>({});
+  const [issueAuthSaving, setIssueAuthSaving] = useState(false);
+  const [issueAuthSaved, setIssueAuthSaved] = useState(false);
+  const [issueAuthError, setIssueAuthError] = useState<string | null>(null);
+  const [notifiers, setNotifiersState] = useState<NotifierConfig[]>([]);
+  const [notifiersSaving, setNotifiersSaving] = useState(false);
+  const [notifiersSaved, setNotifiersSaved] = useState(false);
+  const [notifiersError, setNotifiersError] = useState<string | null>(null);
+  const [customEditors, setCustomEditorsState] = useState<CustomEditor[]>([]);
+  const [editorsSaving, setEditorsSaving] = useState(false);
+  const [editorsSaved, setEditorsSaved] = useState(false);
+  const [editorsError, setEditorsError] = useState<string | null>(null);
+  const [encryptConfig, setEncryptConfig] = useState(false);
+  const [encryptConfigError, setEncryptConfigError] = useState<string | null>(null);
+  const [restoreSessions, setRestoreSessions] = useState(false);
+  const [ptyLimits, setPtyLimitsState] = useState<PtyLimits>({ max_sessions: 0, idle_timeout_mins: 0 });
+  const [sshHosts, setSshHostsState] = useState<SshHost[]>([]);
+  const [sshSaving, setSshSaving] = useState(false);
+  const [sshSaved, setSshSaved] = useState(false);
+  const [sshError, setSshError] = useState<string | null>(null);
+  const [apiTokens, setApiTokens] = useState<ApiToken[]>([]);
+  const [newTokenName, setNewTokenName] = useState('');
+  const [newTokenRole, setNewTokenRole] = useState<ShareRole>('operator');
+  const [createdToken, setCreatedToken] = useState<string | null>(null);
+  const [apiTokenError, setApiTokenError] = useState<string | null>(null);
+  const [webhookSecret, setWebhookSecret] = useState<string | null>(null);
+  const [webhookError, setWebhookError] = useState<string | null>(null);
+  const [totpEnabled, setTotpEnabled] = useState(false);
+  const [totpEnrollment, setTotpEnrollment] = useState<TotpEnrollment | null>(null);
+  const [totpCode, setTotpCode] = useState('');
+  const [totpError, setTotpError] = useState<string | null>(null);
+  const [rateLimit, setRateLimitState] = useState<RateLimitConfig | null>(null);
+  const [rateLimitError, setRateLimitError] = useState<string | null>(null);
+  const [sessionTtl, setSessionTtl] = useState<number | null>(null);
+  const [customTls, setCustomTlsState] = useState<CustomTlsConfig>({ cert_path: '', key_path: '', password: '' });
+  const [customTlsSaved, setCustomTlsSaved] = useState(false);
+  const [customTlsError, setCustomTlsError] = useState<string | null>(null);
 
   // Dashscope API key state
   const [dashscopeKey, setDashscopeKey] = useState('');
@@ -304,6 +450,23 @@ export const SettingsView: FC<SettingsViewProps> = ({
         setNgrokToken(token || '');
         setNgrokTokenLoaded(true);
       }).catch(() => setNgrokTokenLoaded(true));
+      getCloudflareTunnelConfig().then(setCloudflareConfigState).catch(() => { });
+      getFrpTunnelConfig().then(setFrpConfigState).catch(() => { });
+      getProxyConfig().then(setProxyConfigState).catch(() => { });
+      getConfigEncryptionEnabled().then(setEncryptConfig).catch(() => { });
+      getIssueTrackerAuth().then(setIssueAuthState).catch(() => { });
+      getNotifiers().then(setNotifiersState).catch(() => { });
+      getCustomEditors().then(setCustomEditorsState).catch(() => { });
+      getScrollbackPersistEnabled().then(setPersistScrollback).catch(() => { });
+      getSessionRestoreEnabled().then(setRestoreSessions).catch(() => { });
+      getPtyLimits().then(setPtyLimitsState).catch(() => { });
+      getSshHosts().then(setSshHostsState).catch(() => { });
+      listApiTokens().then(setApiTokens).catch(() => { });
+      getWebhookSecret().then(setWebhookSecret).catch(() => { });
+      getTotpEnabled().then(setTotpEnabled).catch(() => { });
+      getRateLimitConfig().then(setRateLimitState).catch(() => { });
+      getSessionTtlMins().then(setSessionTtl).catch(() => { });
+      getCustomTls().then(c => { if (c) { setCustomTlsState(c); setCustomTlsSaved(true); } }).catch(() => { });
     }
     getDashscopeApiKey().then(k => {
       setDashscopeKey(k || '');
@@ -355,6 +518,18 @@ export const SettingsView: FC<SettingsViewProps> = ({
         )}
       </div>
 
+      {/* Config warnings (non-blocking) */}
+      {configWarnings.length > 0 && (
+        <div className="mx-4 mt-2 p-3 bg-amber-900/20 border border-amber-800/40 rounded-lg shrink-0">
+          {configWarnings.map((w, i) => (
+            <div key={i} className="text-amber-300 text-xs select-text">
+              {w.project_name ? `${w.project_name}: ` : ''}{w.message}
+            </div>
+          ))}
+          <Button variant="link" size="sm" onClick={() => setConfigWarnings([])} className="text-amber-400 hover:text-amber-200 mt-1 p-0 h-auto">{t('common.close')}</Button>
+        </div>
+      )}
+
       {/* Error banner */}
       {error && (
         <div className="mx-4 mt-2 p-3 bg-red-900/30 border border-red-800/50 rounded-lg shrink-0">
@@ -424,7 +599,40 @@ export const SettingsView: FC<SettingsViewProps> = ({
                       <label className="block text-xs text-slate-500 mb-1">{t('settings.worktreesDirLabel')}</label>
                       <Input type="text" value={config.worktrees_dir} onChange={(e) => updateField('worktrees_dir', e.target.value)} className="h-8 text-sm" />
                     </div>
+                    <div>
+                      <label className="block text-xs text-slate-500 mb-1">{t('settings.diskQuotaLabel')}</label>
+                      <Input type="number" min={0} value={config.disk_quota_mb ?? ''} placeholder={t('settings.diskQuotaPlaceholder')}
+                        onChange={(e) => {
+                          const mb = parseInt(e.target.value, 10);
+                          setConfig(prev => ({ ...prev, disk_quota_mb: Number.isFinite(mb) && mb > 0 ? mb : null }));
+                        }}
+                        className="h-8 text-sm" />
+                    </div>
+                    <div>
+                      <label className="flex items-center gap-1.5 text-xs text-slate-500 mb-1 cursor-pointer" title={t('settings.portAllocationHint')}>
+                        <input type="checkbox" checked={portAllocation.enabled} onChange={(e) => updatePortAllocation({ enabled: e.target.checked })} className="h-3 w-3" />
+                        {t('settings.portAllocation')}
+                      </label>
+                      <div className="flex gap-2">
+                        <Input type="number" min={1} max={65535} value={portAllocation.base_port} disabled={!portAllocation.enabled}
+                          onChange={(e) => updatePortAllocation({ base_port: Number(e.target.value) || DEFAULT_PORT_ALLOCATION.base_port })}
+                          title={t('settings.portAllocationBase')} className="h-8 text-sm" />
+                        <Input type="number" min={1} value={portAllocation.ports_per_worktree} disabled={!portAllocation.enabled}
+                          onChange={(e) => updatePortAllocation({ ports_per_worktree: Number(e.target.value) || DEFAULT_PORT_ALLOCATION.ports_per_worktree })}
+                          title={t('settings.portAllocationCount')} className="h-8 text-sm w-20" />
+                      </div>
+                    </div>
+                    <div>
+                      <label className="block text-xs text-slate-500 mb-1" title={t('settings.shellHint')}>{t('settings.shellLabel')}</label>
+                      <Input type="text" key={formatShell(config.shell)} defaultValue={formatShell(config.shell)}
+                        onBlur={(e) => { const shell = parseShell(e.target.value); setConfig(prev => ({ ...prev, shell })); }}
+                        placeholder={t('settings.shellPlaceholder')} className="h-8 text-sm" />
+                    </div>
                   </div>
+                  <label className="flex items-center gap-1.5 text-xs text-slate-500 cursor-pointer" title={t('settings.killProcessesOnArchiveHint')}>
+                    <input type="checkbox" checked={!!config.kill_processes_on_archive} onChange={(e) => setConfig(prev => ({ ...prev, kill_processes_on_archive: e.target.checked }))} className="h-3 w-3" />
+                    {t('settings.killProcessesOnArchive')}
+                  </label>
                   {/* Linked Workspace Items */}
                   <div>
                     <label className="block text-xs text-slate-500 mb-1.5">{t('settings.linkedWorktreeItems')}</label>
@@ -447,6 +655,152 @@ export const SettingsView: FC<SettingsViewProps> = ({
                       >{t('common.add')}</Button>
                     </div>
                     <p className="text-[10px] text-slate-600 mt-1">{t('settings.linkedWorktreeItemsHint')}</p>
+                    {isCurrentWs && (
+                      <div className="flex items-center gap-2 mt-2">
+                        <Button type="button" variant="secondary" size="sm" className="h-7 text-xs" onClick={handleSyncLinks} disabled={syncingLinks}>
+                          {syncingLinks ? t('settings.syncLinksRunning') : t('settings.syncLinks')}
+                        </Button>
+                        <span className="text-[10px] text-slate-500">{syncLinksMessage ?? t('settings.syncLinksHint')}</span>
+                      </div>
+                    )}
+                    {isCurrentWs && (
+                      <div className="flex items-start gap-2 mt-2">
+                        <Button type="button" variant="secondary" size="sm" className="h-7 text-xs shrink-0" onClick={handleRepairWorktrees} disabled={repairingWorktrees}>
+                          {repairingWorktrees ? t('settings.repairWorktreesRunning') : t('settings.repairWorktrees')}
+                        </Button>
+                        <span className="text-[10px] text-slate-500 whitespace-pre-line">{repairMessage ?? t('settings.repairWorktreesHint')}</span>
+                      </div>
+                    )}
+                  </div>
+                  {/* Quick Commands */}
+                  <div>
+                    <label className="block text-xs text-slate-500 mb-1.5">{t('settings.quickCommands')}</label>
+                    <div className="space-y-1.5 mb-2">
+                      {(config.quick_commands ?? []).map((qc, index) => (
+                        <div key={index} className="flex gap-1.5 items-center">
+                          <Input type="text" value={qc.label} onChange={(e) => updateQuickCommand(index, { label: e.target.value })}
+                            placeholder={t('settings.quickCommandLabel')} className="h-7 text-xs w-32" />
+                          <Input type="text" value={qc.command} onChange={(e) => updateQuickCommand(index, { command: e.target.value })}
+                            placeholder={t('settings.quickCommandCommand')} className="h-7 text-xs flex-1 font-mono" />
+                          <Input type="text" value={qc.project ?? ''} onChange={(e) => updateQuickCommand(index, { project: e.target.value || null })}
+                            placeholder={t('settings.quickCommandProject')} className="h-7 text-xs w-32" />
+                          <label className="flex items-center gap-1 text-[10px] text-slate-500 cursor-pointer shrink-0" title={t('settings.quickCommandAllowRemoteHint')}>
+                            <input type="checkbox" checked={qc.allow_remote ?? true} onChange={(e) => updateQuickCommand(index, { allow_remote: e.target.checked })} className="h-3 w-3" />
+                            {t('settings.quickCommandAllowRemote')}
+                          </label>
+                          <Button variant="ghost" size="icon" className="h-6 w-6 text-red-400/60 hover:text-red-300 shrink-0"
+                            onClick={() => setConfig(prev => ({ ...prev, quick_commands: (prev.quick_commands ?? []).filter((_, i) => i !== index) }))}
+                          ><TrashIcon className="w-3.5 h-3.5" /></Button>
+                        </div>
+                      ))}
+                    </div>
+                    <Button type="button" variant="secondary" size="sm" className="h-7 text-xs"
+                      onClick={() => setConfig(prev => ({ ...prev, quick_commands: [...(prev.quick_commands ?? []), { label: '', command: '', project: null }] }))}
+                    >{t('common.add')}</Button>
+                    <p className="text-[10px] text-slate-600 mt-1">{t('settings.quickCommandsHint')}</p>
+                  </div>
+                  {/* Issue Trackers */}
+                  <div>
+                    <label className="block text-xs text-slate-500 mb-1.5">{t('settings.issueTrackers')}</label>
+                    <div className="space-y-1.5 mb-2">
+                      {(config.issue_trackers ?? []).map((tracker, index) => (
+                        <div key={index} className="flex gap-1.5 items-center">
+                          <Input type="text" value={tracker.pattern} onChange={(e) => updateIssueTracker(index, { pattern: e.target.value })}
+                            placeholder="PROJ-\d+" className="h-7 text-xs w-32 font-mono" />
+                          <Input type="text" value={tracker.url_template} onChange={(e) => updateIssueTracker(index, { url_template: e.target.value })}
+                            placeholder="https://acme.atlassian.net/browse/{key}" className="h-7 text-xs flex-1 font-mono" />
+                          <Select value={tracker.provider ?? 'none'} onValueChange={(value) => updateIssueTracker(index, { provider: value as IssueProvider })}>
+                            <SelectTrigger className="w-24 h-7 text-xs shrink-0"><SelectValue /></SelectTrigger>
+                            <SelectContent>
+                              <SelectItem value="none">{t('settings.issueProviderNone')}</SelectItem>
+                              <SelectItem value="jira">Jira</SelectItem>
+                              <SelectItem value="linear">Linear</SelectItem>
+                            </SelectContent>
+                          </Select>
+                          {tracker.provider === 'jira' && (
+                            <Input type="text" value={tracker.api_base_url ?? ''} onChange={(e) => updateIssueTracker(index, { api_base_url: e.target.value || null })}
+                              placeholder={t('settings.issueApiBaseUrl')} className="h-7 text-xs w-40" />
+                          )}
+                          <Button variant="ghost" size="icon" className="h-6 w-6 text-red-400/60 hover:text-red-300 shrink-0"
+                            onClick={() => setConfig(prev => ({ ...prev, issue_trackers: (prev.issue_trackers ?? []).filter((_, i) => i !== index) }))}
+                          ><TrashIcon className="w-3.5 h-3.5" /></Button>
+                        </div>
+                      ))}
+                    </div>
+                    <Button type="button" variant="secondary" size="sm" className="h-7 text-xs"
+                      onClick={() => setConfig(prev => ({ ...prev, issue_trackers: [...(prev.issue_trackers ?? []), { pattern: '', url_template: '', provider: 'none' }] }))}
+                    >{t('common.add')}</Button>
+                    <p className="text-[10px] text-slate-600 mt-1">{t('settings.issueTrackersHint')}</p>
+                  </div>
+                  {/* Lifecycle Hooks */}
+                  <div>
+                    <label className="block text-xs text-slate-500 mb-1.5">{t('settings.hooks')}</label>
+                    <div className="space-y-1.5 mb-2">
+                      {(config.hooks ?? []).map((hook, index) => (
+                        <div key={index} className="flex gap-1.5 items-center">
+                          <Select value={hook.event} onValueChange={(value) => updateHook(index, { event: value as HookEvent })}>
+                            <SelectTrigger className="w-36 h-7 text-xs shrink-0"><SelectValue /></SelectTrigger>
+                            <SelectContent>
+                              {HOOK_EVENTS.map((event) => (
+                                <SelectItem key={event} value={event}>{event}</SelectItem>
+                              ))}
+                            </SelectContent>
+                          </Select>
+                          <Input type="text" value={hook.command} onChange={(e) => updateHook(index, { command: e.target.value })}
+                            placeholder={t('settings.hookCommand')} className="h-7 text-xs flex-1 font-mono" />
+                          <Input type="number" min={1} value={hook.timeout_secs ?? ''}
+                            onChange={(e) => updateHook(index, { timeout_secs: e.target.value ? Number(e.target.value) : null })}
+                            placeholder="300" title={t('settings.hookTimeout')} className="h-7 text-xs w-16" />
+                          <label className="flex items-center gap-1 text-[10px] text-slate-500 cursor-pointer shrink-0" title={t('settings.hookPerProjectHint')}>
+                            <input type="checkbox" checked={!!hook.per_project} onChange={(e) => updateHook(index, { per_project: e.target.checked })} className="h-3 w-3" />
+                            {t('settings.hookPerProject')}
+                          </label>
+                          {hook.per_project && (
+                            <Input type="text" value={(hook.projects ?? []).join(', ')}
+                              onChange={(e) => updateHook(index, { projects: e.target.value.split(',').map(p => p.trim()).filter(Boolean) })}
+                              placeholder={t('settings.hookProjectsPlaceholder')} title={t('settings.hookProjectsHint')} className="h-7 text-xs w-28" />
+                          )}
+                          <Button variant="ghost" size="icon" className="h-6 w-6 text-red-400/60 hover:text-red-300 shrink-0"
+                            onClick={() => setConfig(prev => ({ ...prev, hooks: (prev.hooks ?? []).filter((_, i) => i !== index) }))}
+                          ><TrashIcon className="w-3.5 h-3.5" /></Button>
+                        </div>
+                      ))}
+                    </div>
+                    <Button type="button" variant="secondary" size="sm" className="h-7 text-xs"
+                      onClick={() => setConfig(prev => ({ ...prev, hooks: [...(prev.hooks ?? []), { event: 'on_create', command: '', per_project: false }] }))}
+                    >{t('common.add')}</Button>
+                    <p className="text-[10px] text-slate-600 mt-1 whitespace-pre-line">{t('settings.hooksHint')}</p>
+                  </div>
+                  {/* Outgoing Webhooks */}
+                  <div>
+                    <label className="block text-xs text-slate-500 mb-1.5">{t('settings.webhooks')}</label>
+                    <div className="space-y-1.5 mb-2">
+                      {(config.webhooks ?? []).map((webhook, index) => (
+                        <div key={index} className="flex gap-1.5 items-center">
+                          <Select value={webhook.events?.[0] ?? 'all'}
+                            onValueChange={(value) => updateWebhook(index, { events: value === 'all' ? [] : [value as HookEvent] })}>
+                            <SelectTrigger className="w-36 h-7 text-xs shrink-0"><SelectValue /></SelectTrigger>
+                            <SelectContent>
+                              <SelectItem value="all">{t('settings.webhookAllEvents')}</SelectItem>
+                              {HOOK_EVENTS.map((event) => (
+                                <SelectItem key={event} value={event}>{event}</SelectItem>
+                              ))}
+                            </SelectContent>
+                          </Select>
+                          <Input type="text" value={webhook.url} onChange={(e) => updateWebhook(index, { url: e.target.value })}
+                            placeholder="https://example.com/hooks/worktrees" className="h-7 text-xs flex-1 font-mono" />
+                          <Input type="password" value={webhook.secret ?? ''} onChange={(e) => updateWebhook(index, { secret: e.target.value || null })}
+                            placeholder={t('settings.webhookSecret')} className="h-7 text-xs w-32" />
+                          <Button variant="ghost" size="icon" className="h-6 w-6 text-red-400/60 hover:text-red-300 shrink-0"
+                            onClick={() => setConfig(prev => ({ ...prev, webhooks: (prev.webhooks ?? []).filter((_, i) => i !== index) }))}
+                          ><TrashIcon className="w-3.5 h-3.5" /></Button>
+                        </div>
+                      ))}
+                    </div>
+                    <Button type="button" variant="secondary" size="sm" className="h-7 text-xs"
+                      onClick={() => setConfig(prev => ({ ...prev, webhooks: [...(prev.webhooks ?? []), { url: '', events: [] }] }))}
+                    >{t('common.add')}</Button>
+                    <p className="text-[10px] text-slate-600 mt-1">{t('settings.webhooksHint')}</p>
                   </div>
                 </div>
 
@@ -535,6 +889,135 @@ export const SettingsView: FC<SettingsViewProps> = ({
                                     <SelectItem value="rebase">rebase</SelectItem>
                                   </SelectContent>
                                 </Select>
+                                <label className="flex items-center gap-1 mt-1 text-[10px] text-slate-500 cursor-pointer" title={t('settings.skipLfsHint')}>
+                                  <input type="checkbox" checked={!!proj.skip_lfs} onChange={(e) => updateProject(index, 'skip_lfs', e.target.checked)} className="h-3 w-3" />
+                                  {t('settings.skipLfs')}
+                                </label>
+                              </div>
+                              <div className="col-span-2">
+                                <label className="block text-[10px] text-slate-600 mb-0.5" title={t('settings.sparsePathsHint')}>{t('settings.sparsePathsLabel')}</label>
+                                <Input type="text" value={(proj.sparse_paths ?? []).join(', ')}
+                                  onChange={(e) => updateProject(index, 'sparse_paths', e.target.value.split(',').map(s => s.trim()))}
+                                  placeholder={t('settings.sparsePathsPlaceholder')} className="h-7 text-xs" />
+                              </div>
+                              <div>
+                                <label className="block text-[10px] text-slate-600 mb-0.5" title={t('settings.remoteHint')}>{t('settings.remoteLabel')}</label>
+                                <Input type="text" value={proj.remote ?? ''} onChange={(e) => updateProject(index, 'remote', e.target.value.trim())} placeholder="origin" className="h-7 text-xs" />
+                              </div>
+                              <div>
+                                <label className="block text-[10px] text-slate-600 mb-0.5" title={t('settings.projectShellHint')}>{t('settings.shellLabel')}</label>
+                                <Input type="text" key={formatShell(proj.shell)} defaultValue={formatShell(proj.shell)}
+                                  onBlur={(e) => updateProject(index, 'shell', parseShell(e.target.value))}
+                                  placeholder={formatShell(config.shell) || t('settings.shellPlaceholder')} className="h-7 text-xs" />
+                              </div>
+                              <div className="col-span-2">
+                                <label className="block text-[10px] text-slate-600 mb-0.5" title={t('settings.projectEnvHint')}>{t('settings.projectEnv')}</label>
+                                <textarea
+                                  key={formatEnv(proj.env)}
+                                  defaultValue={formatEnv(proj.env)}
+                                  onBlur={(e) => updateProject(index, 'env', parseEnv(e.target.value))}
+                                  placeholder="NODE_ENV=development"
+                                  rows={2}
+                                  className="w-full bg-slate-950 border border-slate-700/50 rounded-md p-2 font-mono text-xs text-slate-300 resize-y focus:outline-none focus:ring-1 focus:ring-blue-500/50"
+                                  spellCheck={false}
+                                />
+                              </div>
+                              <div className="col-span-2">
+                                <label className="block text-[10px] text-slate-600 mb-0.5" title={t('settings.projectTasksHint')}>{t('settings.projectTasks')}</label>
+                                {Object.entries(proj.tasks ?? {}).map(([task, command], taskIndex, entries) => {
+                                  const setEntry = (name: string, cmd: string) => updateProject(index, 'tasks',
+                                    Object.fromEntries(entries.map((e, i) => i === taskIndex ? [name, cmd] : e)));
+                                  return (
+                                    <div key={taskIndex} className="flex gap-1.5 items-center mb-1">
+                                      <Input type="text" value={task} onChange={(e) => setEntry(e.target.value, command)}
+                                        placeholder={t('settings.taskName')} className="h-7 text-xs w-28" />
+                                      <Input type="text" value={command} onChange={(e) => setEntry(task, e.target.value)}
+                                        placeholder={t('settings.quickCommandCommand')} className="h-7 text-xs flex-1 font-mono" />
+                                      <Button variant="ghost" size="icon" className="h-6 w-6 text-red-400/60 hover:text-red-300 shrink-0"
+                                        onClick={() => updateProject(index, 'tasks', Object.fromEntries(entries.filter((_, i) => i !== taskIndex)))}
+                                      ><TrashIcon className="w-3.5 h-3.5" /></Button>
+                                    </div>
+                                  );
+                                })}
+                                <Button type="button" variant="ghost" size="sm" className="h-5 text-[10px] text-slate-500 hover:text-slate-300 px-1"
+                                  disabled={'' in (proj.tasks ?? {})}
+                                  onClick={() => updateProject(index, 'tasks', { ...(proj.tasks ?? {}), '': '' })}
+                                >+ {t('common.add')}</Button>
+                              </div>
+                              <div className="col-span-2">
+                                <label className="block text-[10px] text-slate-600 mb-0.5" title={t('settings.terminalPresetsHint')}>{t('settings.terminalPresets')}</label>
+                                {(proj.terminal_presets ?? []).map((preset, presetIndex) => {
+                                  const setPresets = (next: TerminalPreset[]) => updateProject(index, 'terminal_presets', next);
+                                  const patch = (p: Partial<TerminalPreset>) => setPresets((proj.terminal_presets ?? []).map((c, i) => i === presetIndex ? { ...c, ...p } : c));
+                                  return (
+                                    <div key={presetIndex} className="flex gap-1.5 items-center mb-1">
+                                      <Input type="text" value={preset.name} onChange={(e) => patch({ name: e.target.value })}
+                                        placeholder={t('settings.terminalPresetName')} className="h-7 text-xs w-28" />
+                                      <Input type="text" value={preset.cwd ?? ''} onChange={(e) => patch({ cwd: e.target.value || null })}
+                                        placeholder={t('settings.terminalPresetCwd')} className="h-7 text-xs w-28 font-mono" />
+                                      <Input type="text" value={preset.command ?? ''} onChange={(e) => patch({ command: e.target.value })}
+                                        placeholder={t('settings.quickCommandCommand')} className="h-7 text-xs flex-1 font-mono" />
+                                      <Button variant="ghost" size="icon" className="h-6 w-6 text-red-400/60 hover:text-red-300 shrink-0"
+                                        onClick={() => setPresets((proj.terminal_presets ?? []).filter((_, i) => i !== presetIndex))}
+                                      ><TrashIcon className="w-3.5 h-3.5" /></Button>
+                                    </div>
+                                  );
+                                })}
+                                <Button type="button" variant="ghost" size="sm" className="h-5 text-[10px] text-slate-500 hover:text-slate-300 px-1"
+                                  onClick={() => updateProject(index, 'terminal_presets', [...(proj.terminal_presets ?? []), { name: '', command: '' }])}
+                                >+ {t('common.add')}</Button>
+                              </div>
+                              <div className="col-span-2">
+                                <label className="block text-[10px] text-slate-600 mb-0.5" title={t('settings.projectQuickCommandsHint')}>{t('settings.projectQuickCommands')}</label>
+                                {(proj.quick_commands ?? []).map((qc, qcIndex) => {
+                                  const setCommands = (next: QuickCommand[]) => updateProject(index, 'quick_commands', next);
+                                  const patch = (p: Partial<QuickCommand>) => setCommands((proj.quick_commands ?? []).map((c, i) => i === qcIndex ? { ...c, ...p } : c));
+                                  return (
+                                    <div key={qcIndex} className="flex gap-1.5 items-center mb-1">
+                                      <Input type="text" value={qc.label} onChange={(e) => patch({ label: e.target.value })}
+                                        placeholder={t('settings.quickCommandLabel')} className="h-7 text-xs w-28" />
+                                      <Input type="text" value={qc.command} onChange={(e) => patch({ command: e.target.value })}
+                                        placeholder={t('settings.quickCommandCommand')} className="h-7 text-xs flex-1 font-mono" />
+                                      <label className="flex items-center gap-1 text-[10px] text-slate-500 cursor-pointer shrink-0" title={t('settings.quickCommandAllowRemoteHint')}>
+                                        <input type="checkbox" checked={qc.allow_remote ?? true} onChange={(e) => patch({ allow_remote: e.target.checked })} className="h-3 w-3" />
+                                        {t('settings.quickCommandAllowRemote')}
+                                      </label>
+                                      <Button variant="ghost" size="icon" className="h-6 w-6 text-red-400/60 hover:text-red-300 shrink-0"
+                                        onClick={() => setCommands((proj.quick_commands ?? []).filter((_, i) => i !== qcIndex))}
+                                      ><TrashIcon className="w-3.5 h-3.5" /></Button>
+                                    </div>
+                                  );
+                                })}
+                                <Button type="button" variant="ghost" size="sm" className="h-5 text-[10px] text-slate-500 hover:text-slate-300 px-1"
+                                  onClick={() => updateProject(index, 'quick_commands', [...(proj.quick_commands ?? []), { label: '', command: '' }])}
+                                >+ {t('common.add')}</Button>
+                              </div>
+                              <div className="col-span-2">
+                                <label className="block text-[10px] text-slate-600 mb-0.5" title={t('settings.copyRulesHint')}>{t('settings.copyRules')}</label>
+                                {(proj.copy_rules ?? []).map((rule, ruleIndex) => {
+                                  const setRules = (next: CopyRule[]) => updateProject(index, 'copy_rules', next);
+                                  const patch = (p: Partial<CopyRule>) => setRules((proj.copy_rules ?? []).map((r, i) => i === ruleIndex ? { ...r, ...p } : r));
+                                  return (
+                                    <div key={ruleIndex} className="flex gap-1.5 items-center mb-1">
+                                      <Input type="text" value={rule.path} onChange={(e) => patch({ path: e.target.value })}
+                                        placeholder=".env.local" className="h-7 text-xs flex-1 font-mono" />
+                                      <Select value={rule.overwrite ?? 'skip'} onValueChange={(value) => patch({ overwrite: value as CopyOverwritePolicy })}>
+                                        <SelectTrigger className="w-36 h-7 text-xs"><SelectValue /></SelectTrigger>
+                                        <SelectContent>
+                                          <SelectItem value="skip">{t('settings.copyOverwrite_skip')}</SelectItem>
+                                          <SelectItem value="if_newer">{t('settings.copyOverwrite_if_newer')}</SelectItem>
+                                          <SelectItem value="always">{t('settings.copyOverwrite_always')}</SelectItem>
+                                        </SelectContent>
+                                      </Select>
+                                      <Button variant="ghost" size="icon" className="h-6 w-6 text-red-400/60 hover:text-red-300 shrink-0"
+                                        onClick={() => setRules((proj.copy_rules ?? []).filter((_, i) => i !== ruleIndex))}
+                                      ><TrashIcon className="w-3.5 h-3.5" /></Button>
+                                    </div>
+                                  );
+                                })}
+                                <Button type="button" variant="ghost" size="sm" className="h-5 text-[10px] text-slate-500 hover:text-slate-300 px-1"
+                                  onClick={() => updateProject(index, 'copy_rules', [...(proj.copy_rules ?? []), { path: '' }])}
+                                >+ {t('common.add')}</Button>
                               </div>
                             </div>
                             <Button variant="ghost" size="icon" onClick={() => removeProject(index)}
@@ -664,6 +1147,475 @@ export const SettingsView: FC<SettingsViewProps> = ({
                     >{t('settings.ngrokGetToken')}</button>
                   </p>
                 </div>
+                <div className="bg-slate-800/50 border border-slate-700/50 rounded-lg p-4 space-y-3 mt-4">
+                  <div className="flex items-center justify-between">
+                    <div>
+                      <h3 className="text-sm font-medium text-slate-300">{t('settings.cloudflareTitle')}</h3>
+                      <p className="text-xs text-slate-500">{t('settings.cloudflareDesc')}</p>
+                    </div>
+                    <button type="button" onClick={() => { setCloudflareConfigState(prev => ({ ...prev, enabled: !prev.enabled })); setCloudflareSaved(false); }}
+                      className={`relative inline-flex h-5 w-8 items-center rounded-full transition-colors shrink-0 ${cloudflareConfig.enabled ? 'bg-blue-500' : 'bg-slate-600'}`}
+                    ><span className={`inline-block h-3 w-3 rounded-full bg-white transition-transform ${cloudflareConfig.enabled ? 'translate-x-3.5' : 'translate-x-0.5'}`} /></button>
+                  </div>
+                  {([
+                    ['binary_path', 'cloudflared'],
+                    ['token', 'eyJhIjoi...'],
+                    ['hostname', 'share.example.com'],
+                  ] as ['binary_path' | 'token' | 'hostname', string][]).map(([key, placeholder]) => (
+                    <div key={key}>
+                      <label className="block text-sm text-slate-400 mb-1">{t(`settings.cloudflare_${key}`)}</label>
+                      <Input type={key === 'token' ? 'password' : 'text'} value={cloudflareConfig[key] ?? ''} placeholder={placeholder} className="w-full"
+                        onChange={(e) => { setCloudflareConfigState(prev => ({ ...prev, [key]: e.target.value || null })); setCloudflareSaved(false); }}
+                      />
+                    </div>
+                  ))}
+                  <div className="flex items-center justify-between">
+                    <p className="text-xs text-slate-500">{t('settings.cloudflareHint')}</p>
+                    <Button variant="secondary" size="sm" disabled={cloudflareSaving}
+                      onClick={async () => { setCloudflareSaving(true); setCloudflareError(null); try { await setCloudflareTunnelConfig(cloudflareConfig); setCloudflareSaved(true); setTimeout(() => setCloudflareSaved(false), 2000); } catch (e) { setCloudflareError(String(e)); } finally { setCloudflareSaving(false); } }}
+                    >{cloudflareSaving ? t('common.saving') : cloudflareSaved ? t('settings.savedSuccess') : t('common.save')}</Button>
+                  </div>
+                  {cloudflareError && <p className="text-sm text-red-400">{cloudflareError}</p>}
+                </div>
+                <div className="bg-slate-800/50 border border-slate-700/50 rounded-lg p-4 space-y-3 mt-4">
+                  <div className="flex items-center justify-between">
+                    <div>
+                      <h3 className="text-sm font-medium text-slate-300">{t('settings.frpTitle')}</h3>
+                      <p className="text-xs text-slate-500">{t('settings.frpDesc')}</p>
+                    </div>
+                    <button type="button" onClick={() => { setFrpConfigState(prev => ({ ...prev, enabled: !prev.enabled })); setFrpSaved(false); }}
+                      className={`relative inline-flex h-5 w-8 items-center rounded-full transition-colors shrink-0 ${frpConfig.enabled ? 'bg-blue-500' : 'bg-slate-600'}`}
+                    ><span className={`inline-block h-3 w-3 rounded-full bg-white transition-transform ${frpConfig.enabled ? 'translate-x-3.5' : 'translate-x-0.5'}`} /></button>
+                  </div>
+                  <div className="grid grid-cols-[1fr_7rem] gap-3">
+                    <div>
+                      <label className="block text-sm text-slate-400 mb-1">{t('settings.frp_server_addr')}</label>
+                      <Input value={frpConfig.server_addr} placeholder="frps.example.com" className="w-full"
+                        onChange={(e) => { setFrpConfigState(prev => ({ ...prev, server_addr: e.target.value })); setFrpSaved(false); }}
+                      />
+                    </div>
+                    <div>
+                      <label className="block text-sm text-slate-400 mb-1">{t('settings.frp_server_port')}</label>
+                      <Input type="number" min={1} max={65535} value={frpConfig.server_port || ''} className="w-full"
+                        onChange={(e) => { const port = parseInt(e.target.value, 10); setFrpConfigState(prev => ({ ...prev, server_port: Number.isFinite(port) ? port : 0 })); setFrpSaved(false); }}
+                      />
+                    </div>
+                  </div>
+                  {([
+                    ['token', ''],
+                    ['remote_port', '6000'],
+                    ['custom_domain', 'share.example.com'],
+                    ['public_url', 'https://share.example.com'],
+                    ['binary_path', 'frpc'],
+                  ] as ['token' | 'remote_port' | 'custom_domain' | 'public_url' | 'binary_path', string][]).map(([key, placeholder]) => (
+                    <div key={key}>
+                      <label className="block text-sm text-slate-400 mb-1">{t(`settings.frp_${key}`)}</label>
+                      <Input type={key === 'token' ? 'password' : key === 'remote_port' ? 'number' : 'text'} value={frpConfig[key] ?? ''} placeholder={placeholder} className="w-full"
+                        onChange={(e) => {
+                          const value = key === 'remote_port' ? (parseInt(e.target.value, 10) || null) : (e.target.value || null);
+                          setFrpConfigState(prev => ({ ...prev, [key]: value }));
+                          setFrpSaved(false);
+                        }}
+                      />
+                    </div>
+                  ))}
+                  <div className="flex items-center justify-between">
+                    <p className="text-xs text-slate-500">{t('settings.frpHint')}</p>
+                    <Button variant="secondary" size="sm" disabled={frpSaving}
+                      onClick={async () => { setFrpSaving(true); setFrpError(null); try { await setFrpTunnelConfig(frpConfig); setFrpSaved(true); setTimeout(() => setFrpSaved(false), 2000); } catch (e) { setFrpError(String(e)); } finally { setFrpSaving(false); } }}
+                    >{frpSaving ? t('common.saving') : frpSaved ? t('settings.savedSuccess') : t('common.save')}</Button>
+                  </div>
+                  {frpError && <p className="text-sm text-red-400">{frpError}</p>}
+                </div>
+                <div className="bg-slate-800/50 border border-slate-700/50 rounded-lg p-4 space-y-3 mt-4">
+                  <h3 className="text-sm font-medium text-slate-300">{t('settings.proxyTitle')}</h3>
+                  {([
+                    ['https_proxy', 'http://proxy.corp.example.com:8080'],
+                    ['http_proxy', 'http://proxy.corp.example.com:8080'],
+                    ['no_proxy', 'localhost,127.0.0.1,.corp.example.com'],
+                    ['ca_cert_path', '/path/to/corp-ca.pem'],
+                  ] as [keyof ProxyConfig, string][]).map(([key, placeholder]) => (
+                    <div key={key}>
+                      <label className="block text-sm text-slate-400 mb-1">{t(`settings.proxy_${key}`)}</label>
+                      <Input value={proxyConfig[key] ?? ''} placeholder={placeholder} className="w-full"
+                        onChange={(e) => { setProxyConfigState(prev => ({ ...prev, [key]: e.target.value || null })); setProxySaved(false); }}
+                      />
+                    </div>
+                  ))}
+                  <div className="flex items-center justify-between">
+                    <p className="text-xs text-slate-500">{t('settings.proxyHint')}</p>
+                    <Button variant="secondary" size="sm" disabled={proxySaving}
+                      onClick={async () => { setProxySaving(true); setProxyError(null); try { await setProxyConfig(proxyConfig); setProxySaved(true); setTimeout(() => setProxySaved(false), 2000); } catch (e) { setProxyError(String(e)); } finally { setProxySaving(false); } }}
+                    >{proxySaving ? t('common.saving') : proxySaved ? t('settings.savedSuccess') : t('common.save')}</Button>
+                  </div>
+                  {proxyError && <p className="text-sm text-red-400">{proxyError}</p>}
+                </div>
+                <div className="bg-slate-800/50 border border-slate-700/50 rounded-lg p-4 space-y-3 mt-4">
+                  <h3 className="text-sm font-medium text-slate-300">{t('settings.issueAuthTitle')}</h3>
+                  {([
+                    ['jira_email', 'me@example.com'],
+                    ['jira_token', ''],
+                    ['linear_api_key', 'lin_api_...'],
+                  ] as [keyof IssueTrackerAuth, string][]).map(([key, placeholder]) => (
+                    <div key={key}>
+                      <label className="block text-sm text-slate-400 mb-1">{t(`settings.issueAuth_${key}`)}</label>
+                      <Input type={key === 'jira_email' ? 'text' : 'password'} value={issueAuth[key] ?? ''} placeholder={placeholder} className="w-full"
+                        onChange={(e) => { setIssueAuthState(prev => ({ ...prev, [key]: e.target.value || null })); setIssueAuthSaved(false); }}
+                      />
+                    </div>
+                  ))}
+                  <div className="flex items-center justify-between">
+                    <p className="text-xs text-slate-500">{t('settings.issueAuthHint')}</p>
+                    <Button variant="secondary" size="sm" disabled={issueAuthSaving}
+                      onClick={async () => { setIssueAuthSaving(true); setIssueAuthError(null); try { await setIssueTrackerAuth(issueAuth); setIssueAuthSaved(true); setTimeout(() => setIssueAuthSaved(false), 2000); } catch (e) { setIssueAuthError(String(e)); } finally { setIssueAuthSaving(false); } }}
+                    >{issueAuthSaving ? t('common.saving') : issueAuthSaved ? t('settings.savedSuccess') : t('common.save')}</Button>
+                  </div>
+                  {issueAuthError && <p className="text-sm text-red-400">{issueAuthError}</p>}
+                </div>
+                <div className="bg-slate-800/50 border border-slate-700/50 rounded-lg p-4 space-y-3 mt-4">
+                  <h3 className="text-sm font-medium text-slate-300">{t('settings.notifiersTitle')}</h3>
+                  {notifiers.map((notifier, index) => (
+                    <div key={index} className="flex gap-1.5 items-center">
+                      <Select value={notifier.provider}
+                        onValueChange={(value) => { setNotifiersState(prev => prev.map((n, i) => i === index ? { ...n, provider: value as NotifierProvider } : n)); setNotifiersSaved(false); }}>
+                        <SelectTrigger className="w-24 h-8 text-xs shrink-0"><SelectValue /></SelectTrigger>
+                        <SelectContent>
+                          <SelectItem value="slack">Slack</SelectItem>
+                          <SelectItem value="discord">Discord</SelectItem>
+                        </SelectContent>
+                      </Select>
+                      <Select value={notifier.events?.[0] ?? 'all'}
+                        onValueChange={(value) => { setNotifiersState(prev => prev.map((n, i) => i === index ? { ...n, events: value === 'all' ? [] : [value as NotifyEvent] } : n)); setNotifiersSaved(false); }}>
+                        <SelectTrigger className="w-40 h-8 text-xs shrink-0"><SelectValue /></SelectTrigger>
+                        <SelectContent>
+                          <SelectItem value="all">{t('settings.webhookAllEvents')}</SelectItem>
+                          {NOTIFY_EVENTS.map((event) => (
+                            <SelectItem key={event} value={event}>{t(`settings.notifyEvent_${event}`)}</SelectItem>
+                          ))}
+                        </SelectContent>
+                      </Select>
+                      <Input type="password" value={notifier.webhook_url} placeholder="https://hooks.slack.com/services/..." className="h-8 text-xs flex-1"
+                        onChange={(e) => { setNotifiersState(prev => prev.map((n, i) => i === index ? { ...n, webhook_url: e.target.value } : n)); setNotifiersSaved(false); }}
+                      />
+                      <Button variant="ghost" size="icon" className="h-6 w-6 text-red-400/60 hover:text-red-300 shrink-0"
+                        onClick={() => { setNotifiersState(prev => prev.filter((_, i) => i !== index)); setNotifiersSaved(false); }}
+                      ><TrashIcon className="w-3.5 h-3.5" /></Button>
+                    </div>
+                  ))}
+                  <div className="flex items-center justify-between">
+                    <Button type="button" variant="secondary" size="sm"
+                      onClick={() => { setNotifiersState(prev => [...prev, { provider: 'slack', webhook_url: '', events: [] }]); setNotifiersSaved(false); }}
+                    >{t('common.add')}</Button>
+                    <Button variant="secondary" size="sm" disabled={notifiersSaving}
+                      onClick={async () => { setNotifiersSaving(true); setNotifiersError(null); try { await setNotifiers(notifiers); setNotifiersSaved(true); setTimeout(() => setNotifiersSaved(false), 2000); } catch (e) { setNotifiersError(String(e)); } finally { setNotifiersSaving(false); } }}
+                    >{notifiersSaving ? t('common.saving') : notifiersSaved ? t('settings.savedSuccess') : t('common.save')}</Button>
+                  </div>
+                  <p className="text-xs text-slate-500">{t('settings.notifiersHint')}</p>
+                  {notifiersError && <p className="text-sm text-red-400">{notifiersError}</p>}
+                </div>
+                <div className="bg-slate-800/50 border border-slate-700/50 rounded-lg p-4 space-y-3 mt-4">
+                  <h3 className="text-sm font-medium text-slate-300">{t('settings.customEditorsTitle')}</h3>
+                  {customEditors.map((editor, index) => {
+                    const update = (patch: Partial<CustomEditor>) => {
+                      setCustomEditorsState(prev => prev.map((e, i) => i === index ? { ...e, ...patch } : e));
+                      setEditorsSaved(false);
+                    };
+                    return (
+                      <div key={index} className="flex gap-1.5 items-center">
+                        <Input type="text" value={editor.name} onChange={(e) => update({ name: e.target.value })}
+                          placeholder={t('settings.customEditorName')} className="h-8 text-xs w-28" />
+                        <Input type="text" value={editor.app_name ?? ''} onChange={(e) => update({ app_name: e.target.value || null })}
+                          placeholder={t('settings.customEditorAppName')} className="h-8 text-xs w-32" />
+                        <Input type="text" value={editor.command ?? ''} onChange={(e) => update({ command: e.target.value || null })}
+                          placeholder={t('settings.customEditorCommand')} className="h-8 text-xs w-28 font-mono" />
+                        <Input type="text" key={(editor.args ?? []).join(' ')} defaultValue={(editor.args ?? []).join(' ')}
+                          onBlur={(e) => update({ args: e.target.value.trim().split(/\s+/).filter(Boolean) })}
+                          placeholder="{path}" className="h-8 text-xs flex-1 font-mono" />
+                        <Input type="text" key={`file-${(editor.file_args ?? []).join(' ')}`} defaultValue={(editor.file_args ?? []).join(' ')}
+                          onBlur={(e) => update({ file_args: e.target.value.trim().split(/\s+/).filter(Boolean) })}
+                          placeholder="{file}:{line}" title={t('settings.customEditorFileArgs')} className="h-8 text-xs flex-1 font-mono" />
+                        <Button variant="ghost" size="icon" className="h-6 w-6 text-red-400/60 hover:text-red-300 shrink-0"
+                          onClick={() => { setCustomEditorsState(prev => prev.filter((_, i) => i !== index)); setEditorsSaved(false); }}
+                        ><TrashIcon className="w-3.5 h-3.5" /></Button>
+                      </div>
+                    );
+                  })}
+                  <div className="flex items-center justify-between">
+                    <Button type="button" variant="secondary" size="sm"
+                      onClick={() => { setCustomEditorsState(prev => [...prev, { name: '', app_name: null, command: null, args: [], file_args: [] }]); setEditorsSaved(false); }}
+                    >{t('common.add')}</Button>
+                    <Button variant="secondary" size="sm" disabled={editorsSaving}
+                      onClick={async () => { setEditorsSaving(true); setEditorsError(null); try { await setCustomEditors(customEditors); setEditorsSaved(true); setTimeout(() => setEditorsSaved(false), 2000); } catch (e) { setEditorsError(String(e)); } finally { setEditorsSaving(false); } }}
+                    >{editorsSaving ? t('common.saving') : editorsSaved ? t('settings.savedSuccess') : t('common.save')}</Button>
+                  </div>
+                  <p className="text-xs text-slate-500">{t('settings.customEditorsHint')}</p>
+                  {editorsError && <p className="text-sm text-red-400">{editorsError}</p>}
+                </div>
+                <div className="bg-slate-800/50 border border-slate-700/50 rounded-lg p-4 mt-4">
+                  <div className="flex items-center justify-between">
+                    <div>
+                      <label className="text-sm text-slate-400">{t('settings.encryptConfigLabel')}</label>
+                      <p className="text-xs text-slate-500">{t('settings.encryptConfigDesc')}</p>
+                    </div>
+                    <button type="button" onClick={() => { const newVal = !encryptConfig; setEncryptConfig(newVal); setEncryptConfigError(null); setConfigEncryptionEnabled(newVal).catch((e) => { setEncryptConfig(!newVal); setEncryptConfigError(String(e)); }); }}
+                      className={`relative inline-flex h-5 w-8 items-center rounded-full transition-colors ${encryptConfig ? 'bg-blue-500' : 'bg-slate-600'}`}
+                    ><span className={`inline-block h-3 w-3 rounded-full bg-white transition-transform ${encryptConfig ? 'translate-x-3.5' : 'translate-x-0.5'}`} /></button>
+                  </div>
+                  {encryptConfigError && <p className="text-sm text-red-400 mt-2">{encryptConfigError}</p>}
+                </div>
+                <div className="bg-slate-800/50 border border-slate-700/50 rounded-lg p-4 mt-4">
+                  <div className="flex items-center justify-between">
+                    <div>
+                      <label className="text-sm text-slate-400">{t('settings.persistScrollbackLabel')}</label>
+                      <p className="text-xs text-slate-500">{t('settings.persistScrollbackDesc')}</p>
+                    </div>
+                    <button type="button" onClick={() => { const newVal = !persistScrollback; setPersistScrollback(newVal); setScrollbackPersistEnabled(newVal).catch(() => setPersistScrollback(!newVal)); }}
+                      className={`relative inline-flex h-5 w-8 items-center rounded-full transition-colors ${persistScrollback ? 'bg-blue-500' : 'bg-slate-600'}`}
+                    ><span className={`inline-block h-3 w-3 rounded-full bg-white transition-transform ${persistScrollback ? 'translate-x-3.5' : 'translate-x-0.5'}`} /></button>
+                  </div>
+                </div>
+                <div className="bg-slate-800/50 border border-slate-700/50 rounded-lg p-4 mt-4">
+                  <div className="flex items-center justify-between">
+                    <div>
+                      <label className="text-sm text-slate-400">{t('settings.restoreSessionsLabel')}</label>
+                      <p className="text-xs text-slate-500">{t('settings.restoreSessionsDesc')}</p>
+                    </div>
+                    <button type="button" onClick={() => { const newVal = !restoreSessions; setRestoreSessions(newVal); setSessionRestoreEnabled(newVal).catch(() => setRestoreSessions(!newVal)); }}
+                      className={`relative inline-flex h-5 w-8 items-center rounded-full transition-colors ${restoreSessions ? 'bg-blue-500' : 'bg-slate-600'}`}
+                    ><span className={`inline-block h-3 w-3 rounded-full bg-white transition-transform ${restoreSessions ? 'translate-x-3.5' : 'translate-x-0.5'}`} /></button>
+                  </div>
+                </div>
+                <div className="bg-slate-800/50 border border-slate-700/50 rounded-lg p-4 mt-4 space-y-2">
+                  <div>
+                    <label className="text-sm text-slate-400">{t('settings.ptyLimitsLabel')}</label>
+                    <p className="text-xs text-slate-500">{t('settings.ptyLimitsDesc')}</p>
+                  </div>
+                  <div className="grid grid-cols-2 gap-3">
+                    {(['max_sessions', 'idle_timeout_mins'] as const).map(field => (
+                      <div key={field}>
+                        <label className="block text-xs text-slate-500 mb-1">
+                          {field === 'max_sessions' ? t('settings.ptyMaxSessions') : t('settings.ptyIdleTimeout')}
+                        </label>
+                        <Input type="number" min={0} value={ptyLimits[field] || ''} placeholder={t('settings.ptyLimitsUnlimited')}
+                          onChange={(e) => {
+                            const value = parseInt(e.target.value, 10);
+                            setPtyLimitsState(prev => ({ ...prev, [field]: Number.isFinite(value) && value > 0 ? value : 0 }));
+                          }}
+                          onBlur={() => { setPtyLimits(ptyLimits).catch(() => { }); }}
+                          className="h-8 text-sm" />
+                      </div>
+                    ))}
+                  </div>
+                </div>
+                <div className="bg-slate-800/50 border border-slate-700/50 rounded-lg p-4 mt-4 space-y-2">
+                  <div>
+                    <label className="text-sm text-slate-400">{t('settings.sshHostsLabel')}</label>
+                    <p className="text-xs text-slate-500">{t('settings.sshHostsDesc')}</p>
+                  </div>
+                  <div className="space-y-1.5">
+                    {sshHosts.map((host, index) => {
+                      const patch = (p: Partial<SshHost>) => { setSshHostsState(prev => prev.map((h, i) => i === index ? { ...h, ...p } : h)); setSshSaved(false); };
+                      return (
+                        <div key={index} className="flex gap-1.5 items-center">
+                          <Input type="text" value={host.name} onChange={(e) => patch({ name: e.target.value })}
+                            placeholder={t('settings.sshName')} className="h-7 text-xs w-28" />
+                          <Input type="text" value={host.user ?? ''} onChange={(e) => patch({ user: e.target.value || null })}
+                            placeholder={t('settings.sshUser')} className="h-7 text-xs w-24 font-mono" />
+                          <Input type="text" value={host.host} onChange={(e) => patch({ host: e.target.value })}
+                            placeholder="build.example.com" className="h-7 text-xs flex-1 font-mono" />
+                          <Input type="number" min={1} max={65535} value={host.port ?? ''} onChange={(e) => { const port = parseInt(e.target.value, 10); patch({ port: Number.isFinite(port) && port > 0 ? port : null }); }}
+                            placeholder="22" className="h-7 text-xs w-16" />
+                          <Input type="text" value={host.cwd ?? ''} onChange={(e) => patch({ cwd: e.target.value || null })}
+                            placeholder={t('settings.sshCwd')} className="h-7 text-xs w-32 font-mono" />
+                          <Input type="text" value={host.identity_file ?? ''} onChange={(e) => patch({ identity_file: e.target.value || null })}
+                            placeholder={t('settings.sshIdentityFile')} className="h-7 text-xs w-32 font-mono" />
+                          <Button variant="ghost" size="icon" className="h-6 w-6 text-red-400/60 hover:text-red-300 shrink-0"
+                            onClick={() => { setSshHostsState(prev => prev.filter((_, i) => i !== index)); setSshSaved(false); }}
+                          ><TrashIcon className="w-3.5 h-3.5" /></Button>
+                        </div>
+                      );
+                    })}
+                  </div>
+                  <div className="flex items-center justify-between">
+                    <Button type="button" variant="secondary" size="sm" className="h-7 text-xs"
+                      onClick={() => { setSshHostsState(prev => [...prev, { name: '', host: '' }]); setSshSaved(false); }}
+                    >{t('common.add')}</Button>
+                    <Button variant="secondary" size="sm" disabled={sshSaving}
+                      onClick={async () => { setSshSaving(true); setSshError(null); try { await setSshHosts(sshHosts); setSshSaved(true); setTimeout(() => setSshSaved(false), 2000); } catch (e) { setSshError(String(e)); } finally { setSshSaving(false); } }}
+                    >{sshSaving ? t('common.saving') : sshSaved ? t('settings.savedSuccess') : t('common.save')}</Button>
+                  </div>
+                  {sshError && <p className="text-sm text-red-400">{sshError}</p>}
+                </div>
+                <div className="bg-slate-800/50 border border-slate-700/50 rounded-lg p-4 mt-4 space-y-2">
+                  <div>
+                    <label className="text-sm text-slate-400">{t('settings.apiTokensLabel')}</label>
+                    <p className="text-xs text-slate-500">{t('settings.apiTokensDesc')}</p>
+                  </div>
+                  {apiTokens.length > 0 && (
+                    <div className="space-y-1">
+                      {apiTokens.map(token => (
+                        <div key={token.id} className="flex gap-2 items-center text-xs">
+                          <span className="flex-1 min-w-0 truncate text-slate-200">{token.name}</span>
+                          <span className="text-slate-500 shrink-0">{t(`share.role.${token.role}`)}</span>
+                          <span className="text-slate-500 shrink-0">{new Date(token.created_at).toLocaleDateString()}</span>
+                          <Button variant="ghost" size="sm" className="h-6 text-xs text-red-400/70 hover:text-red-300"
+                            onClick={async () => { setApiTokenError(null); try { await revokeApiToken(token.id); setApiTokens(await listApiTokens()); } catch (e) { setApiTokenError(String(e)); } }}
+                          >{t('settings.apiTokenRevoke')}</Button>
+                        </div>
+                      ))}
+                    </div>
+                  )}
+                  <div className="flex gap-1.5 items-center">
+                    <Input type="text" value={newTokenName} onChange={(e) => setNewTokenName(e.target.value)}
+                      placeholder={t('settings.apiTokenName')} className="h-7 text-xs flex-1" />
+                    <Select value={newTokenRole} onValueChange={(value) => setNewTokenRole(value as ShareRole)}>
+                      <SelectTrigger className="w-28 h-7 text-xs"><SelectValue /></SelectTrigger>
+                      <SelectContent>
+                        {(['viewer', 'operator', 'admin'] as ShareRole[]).map(role => (
+                          <SelectItem key={role} value={role}>{t(`share.role.${role}`)}</SelectItem>
+                        ))}
+                      </SelectContent>
+                    </Select>
+                    <Button variant="secondary" size="sm" className="h-7 text-xs" disabled={!newTokenName.trim()}
+                      onClick={async () => { setApiTokenError(null); try { setCreatedToken(await createApiToken(newTokenName, newTokenRole)); setNewTokenName(''); setApiTokens(await listApiTokens()); } catch (e) { setApiTokenError(String(e)); } }}
+                    >{t('settings.apiTokenCreate')}</Button>
+                  </div>
+                  {createdToken && (
+                    <div className="space-y-1">
+                      <p className="text-xs text-amber-400/80">{t('settings.apiTokenCreatedHint')}</p>
+                      <div className="flex gap-1.5 items-center">
+                        <Input type="text" readOnly value={createdToken} className="h-7 text-xs flex-1 font-mono" onFocus={(e) => e.target.select()} />
+                        <Button variant="secondary" size="sm" className="h-7 text-xs" onClick={() => navigator.clipboard.writeText(createdToken)}>
+                          {t('settings.apiTokenCopy')}
+                        </Button>
+                      </div>
+                    </div>
+                  )}
+                  {apiTokenError && <p className="text-sm text-red-400">{apiTokenError}</p>}
+                </div>
+                <div className="bg-slate-800/50 border border-slate-700/50 rounded-lg p-4 mt-4 space-y-2">
+                  <div>
+                    <label className="text-sm text-slate-400">{t('settings.webhookLabel')}</label>
+                    <p className="text-xs text-slate-500">{t('settings.webhookDesc')}</p>
+                  </div>
+                  {webhookSecret && (
+                    <div className="flex gap-1.5 items-center">
+                      <Input type="text" readOnly value={webhookSecret} className="h-7 text-xs flex-1 font-mono" onFocus={(e) => e.target.select()} />
+                      <Button variant="secondary" size="sm" className="h-7 text-xs" onClick={() => navigator.clipboard.writeText(webhookSecret)}>
+                        {t('settings.apiTokenCopy')}
+                      </Button>
+                    </div>
+                  )}
+                  <div className="flex gap-1.5 items-center">
+                    <Button variant="secondary" size="sm" className="h-7 text-xs"
+                      onClick={async () => { setWebhookError(null); try { setWebhookSecret(await rotateWebhookSecret()); } catch (e) { setWebhookError(String(e)); } }}
+                    >{webhookSecret ? t('settings.webhookRotate') : t('settings.webhookEnable')}</Button>
+                    {webhookSecret && (
+                      <Button variant="ghost" size="sm" className="h-7 text-xs text-red-400/70 hover:text-red-300"
+                        onClick={async () => { setWebhookError(null); try { await disableWebhook(); setWebhookSecret(null); } catch (e) { setWebhookError(String(e)); } }}
+                      >{t('settings.webhookDisable')}</Button>
+                    )}
+                  </div>
+                  {webhookError && <p className="text-sm text-red-400">{webhookError}</p>}
+                </div>
+                <div className="bg-slate-800/50 border border-slate-700/50 rounded-lg p-4 mt-4 space-y-2">
+                  <div className="flex items-center justify-between gap-3">
+                    <div>
+                      <label className="text-sm text-slate-400">{t('settings.totpLabel')}</label>
+                      <p className="text-xs text-slate-500">{totpEnabled ? t('settings.totpEnabledDesc') : t('settings.totpDesc')}</p>
+                    </div>
+                    {totpEnabled ? (
+                      <Button variant="secondary" size="sm" className="h-7 text-xs shrink-0"
+                        onClick={async () => { setTotpError(null); try { await disableTotp(); setTotpEnabled(false); } catch (e) { setTotpError(String(e)); } }}
+                      >{t('settings.totpDisable')}</Button>
+                    ) : !totpEnrollment && (
+                      <Button variant="secondary" size="sm" className="h-7 text-xs shrink-0"
+                        onClick={async () => { setTotpError(null); try { setTotpEnrollment(await enrollTotp()); setTotpCode(''); } catch (e) { setTotpError(String(e)); } }}
+                      >{t('settings.totpEnroll')}</Button>
+                    )}
+                  </div>
+                  {totpEnrollment && !totpEnabled && (
+                    <div className="space-y-1.5">
+                      <p className="text-xs text-slate-400">{t('settings.totpEnrollHint')}</p>
+                      <Input type="text" readOnly value={totpEnrollment.secret} className="h-7 text-xs font-mono" onFocus={(e) => e.target.select()} />
+                      <Input type="text" readOnly value={totpEnrollment.otpauth_url} className="h-7 text-xs font-mono" onFocus={(e) => e.target.select()} />
+                      <div className="flex gap-1.5 items-center">
+                        <Input type="text" inputMode="numeric" maxLength={6} value={totpCode} onChange={(e) => setTotpCode(e.target.value.replace(/\D/g, ''))}
+                          placeholder={t('settings.totpCode')} className="h-7 text-xs w-32 font-mono" />
+                        <Button variant="secondary" size="sm" className="h-7 text-xs" disabled={totpCode.length !== 6}
+                          onClick={async () => { setTotpError(null); try { await confirmTotp(totpCode); setTotpEnabled(true); setTotpEnrollment(null); } catch (e) { setTotpError(String(e)); } }}
+                        >{t('settings.totpConfirm')}</Button>
+                      </div>
+                    </div>
+                  )}
+                  {totpError && <p className="text-sm text-red-400">{totpError}</p>}
+                </div>
+                {rateLimit && (
+                  <div className="bg-slate-800/50 border border-slate-700/50 rounded-lg p-4 mt-4 space-y-2">
+                    <div className="flex items-center justify-between">
+                      <div>
+                        <label className="text-sm text-slate-400">{t('settings.rateLimitLabel')}</label>
+                        <p className="text-xs text-slate-500">{t('settings.rateLimitDesc')}</p>
+                      </div>
+                      <button type="button" onClick={() => { const next = { ...rateLimit, enabled: !rateLimit.enabled }; setRateLimitState(next); setRateLimitConfig(next).catch((e) => { setRateLimitState(rateLimit); setRateLimitError(String(e)); }); }}
+                        className={`relative inline-flex h-5 w-8 items-center rounded-full transition-colors shrink-0 ${rateLimit.enabled ? 'bg-blue-500' : 'bg-slate-600'}`}
+                      ><span className={`inline-block h-3 w-3 rounded-full bg-white transition-transform ${rateLimit.enabled ? 'translate-x-3.5' : 'translate-x-0.5'}`} /></button>
+                    </div>
+                    {rateLimit.enabled && (
+                      <div className="grid grid-cols-2 gap-3">
+                        {(['api_requests_per_sec', 'api_burst', 'pty_writes_per_sec', 'pty_write_burst'] as const).map(field => (
+                          <div key={field}>
+                            <label className="block text-xs text-slate-500 mb-1">{t(`settings.rateLimit_${field}`)}</label>
+                            <Input type="number" min={1} value={rateLimit[field] || ''}
+                              onChange={(e) => {
+                                const value = parseInt(e.target.value, 10);
+                                setRateLimitState(prev => prev && ({ ...prev, [field]: Number.isFinite(value) && value > 0 ? value : 0 }));
+                              }}
+                              onBlur={() => { setRateLimitError(null); setRateLimitConfig(rateLimit).catch((e) => setRateLimitError(String(e))); }}
+                              className="h-8 text-sm" />
+                          </div>
+                        ))}
+                      </div>
+                    )}
+                    {rateLimitError && <p className="text-sm text-red-400">{rateLimitError}</p>}
+                  </div>
+                )}
+                {sessionTtl !== null && (
+                  <div className="bg-slate-800/50 border border-slate-700/50 rounded-lg p-4 mt-4 space-y-2">
+                    <label className="text-sm text-slate-400">{t('settings.sessionTtlLabel')}</label>
+                    <p className="text-xs text-slate-500">{t('settings.sessionTtlDesc')}</p>
+                    <Input type="number" min={0} value={sessionTtl}
+                      onChange={(e) => { const value = parseInt(e.target.value, 10); setSessionTtl(Number.isFinite(value) && value > 0 ? value : 0); }}
+                      onBlur={() => setSessionTtlMins(sessionTtl).catch(() => { })}
+                      className="h-8 text-sm w-32" />
+                  </div>
+                )}
+                <div className="bg-slate-800/50 border border-slate-700/50 rounded-lg p-4 mt-4 space-y-2">
+                  <label className="text-sm text-slate-400">{t('settings.customTlsLabel')}</label>
+                  <p className="text-xs text-slate-500">{t('settings.customTlsDesc')}</p>
+                  <Input value={customTls.cert_path} placeholder={t('settings.customTlsCertPlaceholder')}
+                    onChange={(e) => setCustomTlsState(prev => ({ ...prev, cert_path: e.target.value }))} className="h-8 text-sm" />
+                  <Input value={customTls.key_path ?? ''} placeholder={t('settings.customTlsKeyPlaceholder')}
+                    onChange={(e) => setCustomTlsState(prev => ({ ...prev, key_path: e.target.value }))} className="h-8 text-sm" />
+                  <Input type="password" value={customTls.password ?? ''} placeholder={t('settings.customTlsPasswordPlaceholder')}
+                    onChange={(e) => setCustomTlsState(prev => ({ ...prev, password: e.target.value }))} className="h-8 text-sm" />
+                  <div className="flex items-center gap-2">
+                    <Button size="sm" disabled={!customTls.cert_path.trim()} onClick={() => {
+                      setCustomTlsError(null);
+                      setCustomTls({ cert_path: customTls.cert_path.trim(), key_path: customTls.key_path?.trim() || null, password: customTls.password || null })
+                        .then(() => setCustomTlsSaved(true))
+                        .catch((e) => setCustomTlsError(String(e)));
+                    }}>{t('common.save')}</Button>
+                    {customTlsSaved && (
+                      <Button size="sm" variant="secondary" onClick={() => {
+                        setCustomTlsError(null);
+                        setCustomTls(null).then(() => { setCustomTlsState({ cert_path: '', key_path: '', password: '' }); setCustomTlsSaved(false); }).catch((e) => setCustomTlsError(String(e)));
+                      }}>{t('settings.customTlsClear')}</Button>
+                    )}
+                  </div>
+                  <p className="text-xs text-slate-500">{customTlsSaved ? t('settings.customTlsActive') : t('settings.customTlsSelfSigned')}</p>
+                  {customTlsError && <p className="text-sm text-red-400">{customTlsError}</p>}
+                </div>
               </div>
             )}
 
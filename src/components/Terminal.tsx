@@ -14,6 +14,8 @@ interface TerminalProps {
 
 export interface TerminalHandle {
   copyContent: () => Promise<void>;
+  /** Scroll to and select the output line `linesFromEnd` lines above the last one (approximate when lines wrap) */
+  revealLineFromEnd: (linesFromEnd: number) => void;
 }
 
 const TerminalInner = forwardRef<TerminalHandle, TerminalProps>(({ cwd, visible }, ref) => {
@@ -26,6 +28,8 @@ const TerminalInner = forwardRef<TerminalHandle, TerminalProps>(({ cwd, visible
   const readerIntervalRef = useRef<number | null>(null);
   const wsSubscribedRef = useRef(false);
   const initializedRef = useRef(false);
+  // Set once the backend closed the session (idle timeout); polling stops for good
+  const sessionClosedRef = useRef(false);
   const cwdRef = useRef(actualCwd);
 
   // Expose copyContent method
@@ -46,7 +50,15 @@ const TerminalInner = forwardRef<TerminalHandle, TerminalProps>(({ cwd, visible
       }
       // Clear selection after copying
       term.clearSelection();
-    }
+    },
+    revealLineFromEnd: (linesFromEnd: number) => {
+      const term = xtermRef.current;
+      if (!term) return;
+      const buffer = term.buffer.active;
+      const row = Math.max(0, buffer.baseY + buffer.cursorY - linesFromEnd);
+      term.scrollToLine(Math.max(0, row - Math.floor(term.rows / 2)));
+      term.select(0, row, term.cols);
+    },
   }), []);
 
   // Initialize terminal
@@ -149,14 +161,35 @@ const TerminalInner = forwardRef<TerminalHandle, TerminalProps>(({ cwd, visible
           sessionId: sessionIdRef.current,
         });
 
-        if (!exists) {
-          // Create new PTY session
-          await callBackend('pty_create', {
+        // Repopulate output from before a reload (or app restart, when scrollback is persisted).
+        // Browser mode gets the recent output from the WS replay buffer instead.
+        if (!exists || isTauri()) {
+          const history = await callBackend<string>('pty_get_scrollback', {
             sessionId: sessionIdRef.current,
-            cwd: cwdRef.current,
-            cols,
-            rows,
-          });
+            lines: TERMINAL.SCROLLBACK_LINES,
+          }).catch(() => '');
+          if (history) {
+            term.write(exists ? history : `${history}\r\n`);
+          }
+        }
+
+        if (!exists) {
+          // Create new PTY session; `ssh://<host>` tabs run on a configured SSH host
+          if (cwdRef.current.startsWith(TERMINAL.SSH_PREFIX)) {
+            await callBackend('pty_create_ssh', {
+              sessionId: sessionIdRef.current,
+              host: cwdRef.current.slice(TERMINAL.SSH_PREFIX.length),
+              cols,
+              rows,
+            });
+          } else {
+            await callBackend('pty_create', {
+              sessionId: sessionIdRef.current,
+              cwd: cwdRef.current,
+              cols,
+              rows,
+            });
+          }
         }
 
         initializedRef.current = true;
@@ -189,7 +222,7 @@ const TerminalInner = forwardRef<TerminalHandle, TerminalProps>(({ cwd, visible
       // Tauri desktop mode: poll via invoke
       // Use chained setTimeout instead of setInterval to prevent request accumulation.
       // With setInterval, if pty_read takes >100ms the calls pile up and block the event loop.
-      if (readerIntervalRef.current) return; // Already reading
+      if (readerIntervalRef.current || sessionClosedRef.current) return; // Already reading, or nothing left to read
 
       const scheduleNext = () => {
         readerIntervalRef.current = window.setTimeout(readLoop, TERMINAL.POLL_INTERVAL_MS);
@@ -203,8 +236,14 @@ const TerminalInner = forwardRef<TerminalHandle, TerminalProps>(({ cwd, visible
           if (data && xtermRef.current) {
             xtermRef.current.write(data);
           }
-        } catch {
-          // PTY read failed silently
+        } catch (e) {
+          // The session was closed behind our back (e.g. by the idle timeout): say so and stop polling
+          if (String(e).includes('Session not found')) {
+            xtermRef.current?.write('\r\n\x1b[2m[Session closed. Reopen this tab to start a new shell.]\x1b[0m\r\n');
+            sessionClosedRef.current = true;
+            readerIntervalRef.current = null;
+            return;
+          }
         }
         // Schedule next read only after current one completes
         if (readerIntervalRef.current !== null) {
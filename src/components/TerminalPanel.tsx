@@ -1,6 +1,6 @@
 import { useRef, useState, useEffect, useCallback, type FC } from 'react';
 import { useTranslation } from 'react-i18next';
-import { Terminal } from './Terminal';
+import { Terminal, type TerminalHandle } from './Terminal';
 import {
   FolderIcon,
   TerminalIcon,
@@ -10,10 +10,12 @@ import {
   MaximizeIcon,
   RestoreIcon,
   MicIcon,
+  SearchIcon,
 } from './Icons';
 import type { VoiceStatus, StagingState } from '../hooks/useVoiceInput';
-import type { TerminalTab } from '../types';
-import { isTauri } from '@/lib/backend';
+import type { OutputSearchResult, SshHost, TerminalTab } from '../types';
+import { getSshHosts, isTauri, ptySearchOutput } from '@/lib/backend';
+import { TERMINAL } from '../constants';
 
 const IS_MOBILE = typeof window !== 'undefined' && 'ontouchstart' in window;
 const IS_MOBILE_WEB = IS_MOBILE && !isTauri();
@@ -217,6 +219,75 @@ function getVoiceButtonClass(voiceStatus: VoiceStatus): string {
   }
 }
 
+// ---- 终端输出搜索（服务端搜索，不必先拉取全部历史）----
+
+const OutputSearchBar: FC<{
+  sessionId: string;
+  onReveal: (linesFromEnd: number) => void;
+  onClose: () => void;
+}> = ({ sessionId, onReveal, onClose }) => {
+  const { t } = useTranslation();
+  const [query, setQuery] = useState('');
+  const [result, setResult] = useState<OutputSearchResult | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  // A different terminal tab invalidates the results
+  useEffect(() => { setResult(null); setError(null); }, [sessionId]);
+
+  const search = async () => {
+    if (!query.trim()) return;
+    try {
+      setError(null);
+      setResult(await ptySearchOutput(sessionId, query.trim()));
+    } catch (e) {
+      setResult(null);
+      setError(String(e));
+    }
+  };
+
+  return (
+    <div className="absolute top-2 right-4 z-20 w-96 max-w-[calc(100%-2rem)] rounded-lg bg-slate-800/95 border border-slate-600/50 shadow-lg text-xs">
+      <div className="flex items-center gap-1 p-1.5">
+        <SearchIcon className="w-3.5 h-3.5 text-slate-500 shrink-0" />
+        <input
+          autoFocus
+          value={query}
+          onChange={(e) => setQuery(e.target.value)}
+          onKeyDown={(e) => {
+            if (e.key === 'Enter') search();
+            if (e.key === 'Escape') onClose();
+          }}
+          placeholder={t('terminal.searchPlaceholder')}
+          className="flex-1 min-w-0 bg-transparent outline-none text-slate-200 placeholder:text-slate-500"
+        />
+        {result && (
+          <span className="text-slate-500 shrink-0">
+            {result.truncated ? t('terminal.searchMatchesTruncated', { count: result.matches.length }) : t('terminal.searchMatches', { count: result.matches.length })}
+          </span>
+        )}
+        <button onClick={onClose} className="p-1 rounded text-slate-500 hover:text-slate-300 hover:bg-slate-700" aria-label={t('terminal.close')}>
+          <CloseIcon className="w-2.5 h-2.5" />
+        </button>
+      </div>
+      {error && <div className="px-2 pb-2 text-red-400">{error}</div>}
+      {result && result.matches.length > 0 && (
+        <div className="max-h-60 overflow-y-auto border-t border-slate-700/50">
+          {[...result.matches].reverse().map(m => (
+            <button
+              key={m.line}
+              onClick={() => onReveal(m.lines_from_end)}
+              className="w-full flex gap-2 px-2 py-1 text-left hover:bg-slate-700/60"
+            >
+              <span className="text-slate-500 font-mono shrink-0 w-12 text-right">{m.line + 1}</span>
+              <span className="text-slate-300 font-mono truncate">{m.text}</span>
+            </button>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+};
+
 // ---- TerminalPanel ----
 
 interface TerminalPanelProps {
@@ -275,6 +346,21 @@ export const TerminalPanel: FC<TerminalPanelProps> = ({
   const errorTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
   const [showAltVHint, setShowAltVHint] = useState(false);
   const hintTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
+  const [showSearch, setShowSearch] = useState(false);
+  const terminalRefs = useRef<Map<string, TerminalHandle>>(new Map());
+  const [sshHosts, setSshHosts] = useState<SshHost[]>([]);
+  const [showSshMenu, setShowSshMenu] = useState(false);
+
+  useEffect(() => {
+    getSshHosts().then(setSshHosts).catch(() => { });
+  }, []);
+
+  // A host's first terminal is `ssh://<host>`; further ones get a `#<timestamp>` suffix like duplicated tabs
+  const openSshTerminal = (host: SshHost) => {
+    const path = `${TERMINAL.SSH_PREFIX}${host.name}`;
+    onTabClick(activatedTerminals.has(path) ? `${path}#${Date.now()}` : path);
+    setShowSshMenu(false);
+  };
 
   // Long-press support for terminal tab context menus on touch devices
   const tabLongPressTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
@@ -437,6 +523,44 @@ export const TerminalPanel: FC<TerminalPanelProps> = ({
                 <CloseIcon className="w-3.5 h-3.5" />
               </button>
             )}
+            {sshHosts.length > 0 && (
+              <div className="relative">
+                <button
+                  onClick={(e) => { e.stopPropagation(); setShowSshMenu(v => !v); }}
+                  className={`px-1.5 py-1 rounded text-[10px] font-mono transition-colors ${showSshMenu ? 'text-blue-400 bg-slate-700' : 'text-slate-500 hover:text-slate-300 hover:bg-slate-700'}`}
+                  title={t('terminal.openSsh')}
+                  aria-label={t('terminal.openSsh')}
+                >
+                  SSH
+                </button>
+                {showSshMenu && (
+                  <div className="absolute right-0 top-full mt-1 z-30 min-w-[160px] py-1 bg-slate-800 border border-slate-600 rounded-lg shadow-xl">
+                    {sshHosts.map(host => (
+                      <button
+                        key={host.name}
+                        onClick={(e) => { e.stopPropagation(); openSshTerminal(host); }}
+                        className="w-full px-3 py-1.5 text-left text-xs text-slate-300 hover:bg-slate-700 transition-colors"
+                      >
+                        <div>{host.name}</div>
+                        <div className="text-[10px] text-slate-500 font-mono truncate">
+                          {host.user ? `${host.user}@${host.host}` : host.host}{host.cwd ? `:${host.cwd}` : ''}
+                        </div>
+                      </button>
+                    ))}
+                  </div>
+                )}
+              </div>
+            )}
+            {activeTerminalTab && (
+              <button
+                onClick={(e) => { e.stopPropagation(); setShowSearch(v => !v); }}
+                className={`p-1.5 rounded transition-colors ${showSearch ? 'text-blue-400 bg-slate-700' : 'text-slate-500 hover:text-slate-300 hover:bg-slate-700'}`}
+                title={t('terminal.searchOutput')}
+                aria-label={t('terminal.searchOutput')}
+              >
+                <SearchIcon className="w-3.5 h-3.5" />
+              </button>
+            )}
             {onToggleVoice && (
               <button
                 onClick={(e) => { e.stopPropagation(); onToggleVoice(); }}
@@ -495,6 +619,10 @@ export const TerminalPanel: FC<TerminalPanelProps> = ({
                 style={{ display: path === activeTerminalTab ? 'block' : 'none' }}
               >
                 <Terminal
+                  ref={(handle) => {
+                    if (handle) terminalRefs.current.set(path, handle);
+                    else terminalRefs.current.delete(path);
+                  }}
                   cwd={path}
                   visible={visible && path === activeTerminalTab}
                 />
@@ -512,6 +640,14 @@ export const TerminalPanel: FC<TerminalPanelProps> = ({
           </div>
         )}
 
+        {showSearch && activeTerminalTab && (
+          <OutputSearchBar
+            sessionId={`pty-${activeTerminalTab.replace(/[\/#]/g, '-')}`}
+            onReveal={(linesFromEnd) => terminalRefs.current.get(activeTerminalTab)?.revealLineFromEnd(linesFromEnd)}
+            onClose={() => setShowSearch(false)}
+          />
+        )}
+
         {/* 语音错误提示 */}
         {showError && (
           <div className="absolute top-2 left-1/2 -translate-x-1/2 z-20 px-4 py-2 bg-red-900/90 border border-red-700/50 rounded-lg text-sm text-red-200 shadow-lg animate-in fade-in slide-in-from-top-2 duration-200">
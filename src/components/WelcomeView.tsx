@@ -1,6 +1,6 @@
-import type { FC } from 'react';
+import { useCallback, useEffect, useState, type FC } from 'react';
 import { useTranslation } from 'react-i18next';
-import { Globe } from 'lucide-react';
+import { AlertTriangle, CheckCircle2, Globe, RefreshCw, XCircle } from 'lucide-react';
 import { Button } from '@/components/ui/button';
 import {
   Select,
@@ -10,6 +10,8 @@ import {
   SelectValue,
 } from '@/components/ui/select';
 import { FolderIcon, PlusIcon, WorkspaceIcon } from './Icons';
+import { checkEnvironment, isTauri } from '../lib/backend';
+import type { EnvironmentReport } from '../types';
 
 interface WelcomeViewProps {
   onAddWorkspace: () => void;
@@ -18,6 +20,24 @@ interface WelcomeViewProps {
 
 export const WelcomeView: FC<WelcomeViewProps> = ({ onAddWorkspace, onCreateWorkspace }) => {
   const { t, i18n } = useTranslation();
+  const [envReport, setEnvReport] = useState<EnvironmentReport | null>(null);
+  const [envChecking, setEnvChecking] = useState(false);
+
+  const runEnvironmentCheck = useCallback(async () => {
+    setEnvChecking(true);
+    try {
+      setEnvReport(await checkEnvironment());
+    } catch (e) {
+      console.error('Failed to check environment:', e);
+    } finally {
+      setEnvChecking(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    if (isTauri()) runEnvironmentCheck();
+  }, [runEnvironmentCheck]);
+
   return (
     <div className="min-h-screen bg-slate-900 text-slate-100 flex items-center justify-center relative">
       {/* Language Selector */}
@@ -68,6 +88,43 @@ export const WelcomeView: FC<WelcomeViewProps> = ({ onAddWorkspace, onCreateWork
             </pre>
           </div>
 
+          {envReport && (
+            <div className="p-4 rounded-lg bg-slate-800/50 border border-slate-700/50 text-left">
+              <div className="flex items-center justify-between mb-2">
+                <h3 className="text-sm font-medium">{t('welcome.envCheckTitle')}</h3>
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  className="h-6 px-2 text-xs text-slate-400"
+                  onClick={runEnvironmentCheck}
+                  disabled={envChecking}
+                >
+                  <RefreshCw className={`w-3 h-3 mr-1 ${envChecking ? 'animate-spin' : ''}`} />
+                  {t('welcome.envCheckRerun')}
+                </Button>
+              </div>
+              <ul className="space-y-2">
+                {envReport.checks.map((check) => (
+                  <li key={check.id} className="flex items-start gap-2 text-xs">
+                    {check.status === 'ok' && <CheckCircle2 className="w-3.5 h-3.5 mt-0.5 shrink-0 text-green-400" />}
+                    {check.status === 'warning' && <AlertTriangle className="w-3.5 h-3.5 mt-0.5 shrink-0 text-amber-400" />}
+                    {check.status === 'error' && <XCircle className="w-3.5 h-3.5 mt-0.5 shrink-0 text-red-400" />}
+                    <div className="min-w-0">
+                      <div className="text-slate-300">
+                        {t(`welcome.envCheck.${check.id}`, { defaultValue: check.id })}
+                        <span className="text-slate-500"> · {check.detail}</span>
+                      </div>
+                      {check.fix && <div className="text-slate-400 mt-0.5">{check.fix}</div>}
+                    </div>
+                  </li>
+                ))}
+              </ul>
+              {!envReport.ready && (
+                <p className="text-xs text-red-400 mt-2">{t('welcome.envCheckNotReady')}</p>
+              )}
+            </div>
+          )}
+
           <div className="grid grid-cols-2 gap-3">
             <Button
               variant="secondary"
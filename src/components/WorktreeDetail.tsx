@@ -1,6 +1,7 @@
-import { useState, type FC, useCallback } from 'react';
+import { useState, type FC, useCallback, useEffect } from 'react';
 import { useTranslation } from 'react-i18next';
 import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
 import {
   DropdownMenu,
   DropdownMenuContent,
@@ -26,17 +27,28 @@ import {
   ExternalLinkIcon,
   CopyIcon,
   CheckIcon,
+  StopIcon,
 } from './Icons';
 import { Badge } from '@/components/ui/badge';
 import { GitOperations } from './GitOperations';
-import { EDITORS } from '../constants';
-import { isTauri } from '@/lib/backend';
+import { composeDown, composeStatus, composeUp, exportWorktreeBundle, fetchAllProjects, getCommandHistory, getDevcontainerStatus, getWorktreeIssue, getWorktreePorts, isTauri, killWorktreeProcesses, listRecordings, readRecording, normalizeLineEndings, openDirectoryDialog, openInDevcontainer, openLink, setWorktreeEnv, setWorktreeLabels, setWorktreeMeta } from '@/lib/backend';
+import { formatEnv, labelColorClass, parseEnv } from '@/lib/utils';
 import type {
   WorktreeListItem,
   MainWorkspaceStatus,
   MainWorkspaceOccupation,
   ProjectStatus,
   EditorType,
+  EditorConfig,
+  WorktreeMeta,
+  WorktreeIssue,
+  WorktreePorts,
+  QuickCommand,
+  TerminalPreset,
+  TerminalRecording,
+  CommandHistoryEntry,
+  ComposeStatus,
+  DevcontainerStatus,
 } from '../types';
 
 const StatusBadges: FC<{ project: ProjectStatus }> = ({ project }) => {
@@ -54,9 +66,395 @@ const StatusBadges: FC<{ project: ProjectStatus }> = ({ project }) => {
   );
 };
 
+/** Description / ticket link stored in .worktree-meta.json, editable inline */
+const WorktreeMetaPanel: FC<{ name: string; meta: WorktreeMeta | null; readOnly: boolean; onSaved?: () => void }> = ({
+  name,
+  meta,
+  readOnly,
+  onSaved,
+}) => {
+  const { t } = useTranslation();
+  const [editing, setEditing] = useState(false);
+  const [description, setDescription] = useState('');
+  const [ticketUrl, setTicketUrl] = useState('');
+  const [labels, setLabels] = useState('');
+  const [env, setEnv] = useState('');
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [issue, setIssue] = useState<WorktreeIssue | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    setIssue(null);
+    getWorktreeIssue(name)
+      .then(found => { if (!cancelled) setIssue(found); })
+      .catch(() => {});
+    return () => { cancelled = true; };
+  }, [name]);
+
+  const startEdit = () => {
+    setDescription(meta?.description ?? '');
+    setTicketUrl(meta?.ticket_url ?? '');
+    setLabels((meta?.labels ?? []).join(', '));
+    setEnv(formatEnv(meta?.env));
+    setError(null);
+    setEditing(true);
+  };
+
+  const handleSave = async () => {
+    setSaving(true);
+    setError(null);
+    try {
+      await setWorktreeMeta(name, description, ticketUrl);
+      await setWorktreeLabels(name, labels.split(','));
+      await setWorktreeEnv(name, parseEnv(env));
+      setEditing(false);
+      onSaved?.();
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  if (editing) {
+    return (
+      <div className="mb-4 p-3 bg-slate-800/50 border border-slate-700/50 rounded-lg space-y-2">
+        <Input value={description} onChange={e => setDescription(e.target.value)} placeholder={t('detail.metaDescriptionPlaceholder')} autoFocus />
+        <Input value={ticketUrl} onChange={e => setTicketUrl(e.target.value)} placeholder={t('detail.metaTicketPlaceholder')} />
+        <Input value={labels} onChange={e => setLabels(e.target.value)} placeholder={t('detail.metaLabelsPlaceholder')} />
+        <textarea
+          value={env}
+          onChange={e => setEnv(e.target.value)}
+          placeholder={t('detail.metaEnvPlaceholder')}
+          title={t('detail.metaEnvHint')}
+          rows={3}
+          className="w-full bg-slate-950 border border-slate-700/50 rounded-md p-2 font-mono text-xs text-slate-300 resize-y focus:outline-none focus:ring-1 focus:ring-blue-500/50"
+          spellCheck={false}
+        />
+        {error && <div className="text-xs text-red-400 select-text">{error}</div>}
+        <div className="flex gap-2 justify-end">
+          <Button variant="secondary" size="sm" onClick={() => setEditing(false)} disabled={saving}>{t('common.cancel')}</Button>
+          <Button size="sm" onClick={handleSave} disabled={saving}>{saving ? t('common.saving') : t('common.save')}</Button>
+        </div>
+      </div>
+    );
+  }
+
+  const envKeys = Object.keys(meta?.env ?? {});
+  const hasContent = !!(meta?.description || meta?.ticket_url || meta?.labels.length || envKeys.length);
+  const showIssue = !!issue && issue.url !== meta?.ticket_url;
+  if (!hasContent && !showIssue && readOnly) return null;
+  return (
+    <div className="mb-4 text-sm text-slate-400 flex items-start gap-2">
+      <div className="min-w-0 flex-1 space-y-0.5">
+        {meta && meta.labels.length > 0 && (
+          <div className="flex flex-wrap gap-1">
+            {meta.labels.map(label => (
+              <span key={label} className={`text-[11px] px-1.5 py-0.5 rounded border ${labelColorClass(label)}`}>{label}</span>
+            ))}
+          </div>
+        )}
+        {meta?.description && <div className="text-slate-300 whitespace-pre-wrap select-text">{meta.description}</div>}
+        {meta?.ticket_url && (
+          <button className="text-blue-400 hover:text-blue-300 flex items-center gap-1 max-w-full" onClick={() => openLink(meta.ticket_url)}>
+            <ExternalLinkIcon className="w-3 h-3 shrink-0" />
+            <span className="truncate">{meta.ticket_url}</span>
+          </button>
+        )}
+        {showIssue && issue && (
+          <div className="flex items-center gap-1.5 min-w-0" title={issue.error ? t('detail.issueFetchFailed', { error: issue.error }) : undefined}>
+            <button className="text-blue-400 hover:text-blue-300 flex items-center gap-1 shrink-0" onClick={() => openLink(issue.url)}>
+              <ExternalLinkIcon className="w-3 h-3 shrink-0" />
+              <span className="font-mono">{issue.key}</span>
+            </button>
+            {issue.status && <Badge variant="default">{issue.status}</Badge>}
+            {issue.title && <span className="truncate text-slate-300 select-text">{issue.title}</span>}
+            {issue.error && <WarningIcon className="w-3 h-3 shrink-0 text-amber-400" />}
+          </div>
+        )}
+        {envKeys.length > 0 && (
+          <div className="text-xs text-slate-500 font-mono truncate select-text" title={formatEnv(meta?.env)}>
+            {t('detail.metaEnv', { keys: envKeys.join(', ') })}
+          </div>
+        )}
+        {meta?.created_at && (
+          <div className="text-xs text-slate-500">{t('detail.metaCreatedAt', { date: new Date(meta.created_at).toLocaleString() })}</div>
+        )}
+      </div>
+      {!readOnly && (
+        <Button variant="link" size="sm" className="p-0 h-auto text-xs shrink-0" onClick={startEdit}>
+          {hasContent ? t('detail.metaEdit') : t('detail.metaAdd')}
+        </Button>
+      )}
+    </div>
+  );
+};
+
+/** Port range assigned to the worktree (exported to its terminals as PORT / WM_PORT_*) and any conflicts */
+const WorktreePortsPanel: FC<{ name: string }> = ({ name }) => {
+  const { t } = useTranslation();
+  const [ports, setPorts] = useState<WorktreePorts | null>(null);
+  const [checking, setChecking] = useState(false);
+
+  const check = useCallback(async () => {
+    setChecking(true);
+    try {
+      setPorts(await getWorktreePorts(name));
+    } catch {
+      // Port allocation disabled
+      setPorts(null);
+    } finally {
+      setChecking(false);
+    }
+  }, [name]);
+
+  useEffect(() => { check(); }, [check]);
+
+  if (!ports) return null;
+  return (
+    <div className="mb-4 text-xs text-slate-400 space-y-1">
+      <div className="flex flex-wrap items-center gap-1.5">
+        <span>{t('detail.ports', { start: ports.start, end: ports.end })}</span>
+        {Object.entries(ports.project_ports).map(([project, port]) => (
+          <span key={project} className="px-1.5 py-0.5 rounded bg-slate-800 border border-slate-700/50 text-slate-300 select-text">{project}:{port}</span>
+        ))}
+        <Button variant="link" size="sm" className="p-0 h-auto text-xs" onClick={check} disabled={checking}>
+          {t('detail.portsRecheck')}
+        </Button>
+      </div>
+      {ports.conflicts.map(c => (
+        <div key={c.port} className="flex items-center gap-1 text-amber-400/90">
+          <WarningIcon className="w-3 h-3 shrink-0" />
+          {c.worktree_name
+            ? t('detail.portOverlap', { port: c.port, name: c.worktree_name })
+            : t('detail.portInUse', { port: c.port })}
+        </div>
+      ))}
+    </div>
+  );
+};
+
+/** Shown only for projects with a devcontainer.json; the dot is green while the container runs */
+const DevcontainerButton: FC<{ path: string; name: string }> = ({ path, name }) => {
+  const { t } = useTranslation();
+  const [status, setStatus] = useState<DevcontainerStatus | null>(null);
+  const [opening, setOpening] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const refresh = useCallback(() => {
+    getDevcontainerStatus(path).then(setStatus).catch(() => setStatus(null));
+  }, [path]);
+
+  useEffect(() => { refresh(); }, [refresh]);
+
+  if (!status?.config_path) return null;
+  const running = status.state === 'running';
+  return (
+    <Button
+      variant="ghost"
+      size="sm"
+      className={`h-7 px-2 text-xs ${error ? 'text-red-400' : ''}`}
+      disabled={opening}
+      title={error ?? (status.state ? t('detail.devcontainerState', { state: status.state }) : t('detail.devcontainerNoContainer'))}
+      aria-label={t('detail.devcontainerOpenProject', { name })}
+      onClick={async () => {
+        setOpening(true);
+        setError(null);
+        try {
+          await openInDevcontainer(path);
+        } catch (e) {
+          setError(String(e));
+        } finally {
+          setOpening(false);
+          refresh();
+        }
+      }}
+    >
+      <span className={`inline-block w-1.5 h-1.5 rounded-full mr-1.5 ${running ? 'bg-emerald-500' : 'bg-slate-500'}`} />
+      {opening ? t('detail.devcontainerStarting') : t('detail.devcontainer')}
+    </Button>
+  );
+};
+
+/** Shown only for projects with a compose file: starts the stack, or stops it while any container runs */
+const ComposeButton: FC<{ worktree: string; project: string }> = ({ worktree, project }) => {
+  const { t } = useTranslation();
+  const [status, setStatus] = useState<ComposeStatus | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    composeStatus(worktree, project).then(setStatus).catch(() => setStatus(null));
+  }, [worktree, project]);
+
+  if (!status?.compose_file) return null;
+  const running = status.services.filter(s => s.state === 'running');
+  const summary = status.services.length === 0
+    ? t('detail.composeStopped', { project: status.project_name })
+    : status.services.map(s => `${s.service}: ${s.status || s.state}`).join('\n');
+  return (
+    <Button
+      variant="ghost"
+      size="sm"
+      className={`h-7 px-2 text-xs ${error ? 'text-red-400' : ''}`}
+      disabled={busy}
+      title={error ?? summary}
+      aria-label={t(running.length > 0 ? 'detail.composeDownProject' : 'detail.composeUpProject', { name: project })}
+      onClick={async () => {
+        setBusy(true);
+        setError(null);
+        try {
+          setStatus(await (running.length > 0 ? composeDown : composeUp)(worktree, project));
+        } catch (e) {
+          setError(String(e));
+        } finally {
+          setBusy(false);
+        }
+      }}
+    >
+      <span className={`inline-block w-1.5 h-1.5 rounded-full mr-1.5 ${running.length > 0 ? 'bg-emerald-500' : 'bg-slate-500'}`} />
+      {busy
+        ? t(running.length > 0 ? 'detail.composeStopping' : 'detail.composeStarting')
+        : t('detail.compose', { running: running.length, total: status.services.length })}
+    </Button>
+  );
+};
+
+function formatDuration(secs: number): string {
+  const total = Math.round(secs);
+  const m = Math.floor(total / 60);
+  return `${m}:${String(total % 60).padStart(2, '0')}`;
+}
+
+/** Terminal recordings (.cast) of a worktree: revealed in the file manager on desktop, downloaded in the browser */
+const WorktreeRecordingsPanel: FC<{ name: string; onReveal: (path: string) => void }> = ({ name, onReveal }) => {
+  const { t } = useTranslation();
+  const [recordings, setRecordings] = useState<TerminalRecording[]>([]);
+
+  const refresh = useCallback(async () => {
+    try {
+      setRecordings(await listRecordings(name));
+    } catch {
+      setRecordings([]);
+    }
+  }, [name]);
+
+  useEffect(() => { refresh(); }, [refresh]);
+
+  const download = useCallback(async (rec: TerminalRecording) => {
+    const content = await readRecording(rec.worktree_name, rec.file_name);
+    const url = URL.createObjectURL(new Blob([content], { type: 'application/x-asciicast' }));
+    const a = document.createElement('a');
+    a.href = url;
+    a.download = rec.file_name;
+    a.click();
+    URL.revokeObjectURL(url);
+  }, []);
+
+  if (recordings.length === 0) return null;
+  return (
+    <div className="mb-4 text-xs text-slate-400 space-y-1">
+      <div className="flex items-center gap-1.5">
+        <span>{t('detail.recordings', { count: recordings.length })}</span>
+        <Button variant="link" size="sm" className="p-0 h-auto text-xs" onClick={refresh}>
+          {t('detail.recordingsRefresh')}
+        </Button>
+      </div>
+      {recordings.map(rec => (
+        <div key={rec.file_name} className="flex items-center gap-2">
+          {rec.active_session_id && <span className="w-1.5 h-1.5 rounded-full bg-red-500 animate-pulse" title={t('detail.recordingActive')} />}
+          <span className="text-slate-300 select-text">{rec.title ?? rec.file_name}</span>
+          <span className="text-slate-500">{formatDuration(rec.duration_secs)}</span>
+          <Button variant="link" size="sm" className="p-0 h-auto text-xs"
+            onClick={() => (isTauri() ? onReveal(rec.path) : download(rec).catch(() => { }))}
+          >
+            {isTauri() ? t('detail.recordingReveal') : t('detail.recordingDownload')}
+          </Button>
+        </div>
+      ))}
+    </div>
+  );
+};
+
+const COMMAND_HISTORY_LIMIT = 20;
+
+/** Commands entered in the worktree's terminals, fuzzy-searchable; loaded when expanded */
+const WorktreeCommandHistoryPanel: FC<{ name: string }> = ({ name }) => {
+  const { t } = useTranslation();
+  const [expanded, setExpanded] = useState(false);
+  const [query, setQuery] = useState('');
+  const [entries, setEntries] = useState<CommandHistoryEntry[]>([]);
+  const [copied, setCopied] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!expanded) return;
+    let cancelled = false;
+    const timer = setTimeout(() => {
+      getCommandHistory(name, query.trim(), COMMAND_HISTORY_LIMIT)
+        .then(result => { if (!cancelled) setEntries(result); })
+        .catch(() => { if (!cancelled) setEntries([]); });
+    }, 150);
+    return () => {
+      cancelled = true;
+      clearTimeout(timer);
+    };
+  }, [expanded, name, query]);
+
+  const copy = useCallback(async (command: string) => {
+    try {
+      await navigator.clipboard.writeText(command);
+      setCopied(command);
+      setTimeout(() => setCopied(null), 2000);
+    } catch (err) {
+      console.error('Failed to copy command:', err);
+    }
+  }, []);
+
+  return (
+    <div className="mb-4 text-xs text-slate-400 space-y-1">
+      <Button variant="link" size="sm" className="p-0 h-auto text-xs" onClick={() => setExpanded(v => !v)}>
+        {expanded ? t('detail.commandHistoryHide') : t('detail.commandHistory')}
+      </Button>
+      {expanded && (
+        <>
+          <Input
+            value={query}
+            onChange={e => setQuery(e.target.value)}
+            placeholder={t('detail.commandHistorySearch')}
+            className="h-7 text-xs"
+          />
+          {entries.length === 0 ? (
+            <div className="text-slate-500">{query.trim() ? t('detail.commandHistoryNoMatch') : t('detail.commandHistoryEmpty')}</div>
+          ) : entries.map(entry => (
+            <div key={entry.command} className="flex items-center gap-2">
+              <span
+                className="flex-1 min-w-0 truncate font-mono text-slate-300 select-text"
+                title={t('detail.commandHistoryRunIn', { cwd: entry.cwd, date: new Date(entry.last_run_at).toLocaleString() })}
+              >
+                {entry.command}
+              </span>
+              {entry.count > 1 && <span className="text-slate-500 shrink-0">×{entry.count}</span>}
+              <button
+                type="button"
+                className="shrink-0 text-slate-500 hover:text-slate-300"
+                onClick={() => copy(entry.command)}
+                title={t('detail.commandHistoryCopy')}
+              >
+                {copied === entry.command ? <CheckIcon className="w-3 h-3 text-emerald-400" /> : <CopyIcon className="w-3 h-3" />}
+              </button>
+            </div>
+          ))}
+        </>
+      )}
+    </div>
+  );
+};
+
 interface WorktreeDetailProps {
   selectedWorktree: WorktreeListItem | null;
   mainWorkspace: MainWorkspaceStatus | null;
+  editors: EditorConfig[];
   selectedEditor: EditorType;
   showEditorMenu: boolean;
   onShowEditorMenu: (show: boolean) => void;
@@ -72,6 +470,14 @@ interface WorktreeDetailProps {
   onAddProjectToWorktree?: () => void;
   onRefresh?: () => void;
   onOpenTerminalPanel?: (path: string) => void;
+  quickCommands?: QuickCommand[];
+  onRunQuickCommand?: (command: QuickCommand, newSession: boolean) => void;
+  /** Configured tasks per project name (ProjectConfig.tasks) */
+  projectTasks?: Record<string, Record<string, string>>;
+  onRunTask?: (project: string, task: string) => void;
+  /** Configured terminal presets per project name (ProjectConfig.terminal_presets) */
+  projectTerminalPresets?: Record<string, TerminalPreset[]>;
+  onOpenTerminalPreset?: (project: string, preset: string) => void;
   error: string | null;
   onClearError: () => void;
   restoring?: boolean;
@@ -139,6 +545,7 @@ const PathDisplay: FC<{ path: string }> = ({ path }) => {
 export const WorktreeDetail: FC<WorktreeDetailProps> = ({
   selectedWorktree,
   mainWorkspace,
+  editors,
   selectedEditor,
   showEditorMenu,
   onShowEditorMenu,
@@ -154,6 +561,12 @@ export const WorktreeDetail: FC<WorktreeDetailProps> = ({
   onAddProjectToWorktree,
   onRefresh,
   onOpenTerminalPanel,
+  quickCommands = [],
+  onRunQuickCommand,
+  projectTasks = {},
+  onRunTask,
+  projectTerminalPresets = {},
+  onOpenTerminalPreset,
   error,
   onClearError,
   restoring = false,
@@ -166,10 +579,82 @@ export const WorktreeDetail: FC<WorktreeDetailProps> = ({
   onRefreshAfterDeploy,
 }) => {
   const { t } = useTranslation();
-  const selectedEditorName = EDITORS.find(e => e.id === selectedEditor)?.name || 'VS Code';
+  const selectedEditorName = editors.find(e => e.id === selectedEditor)?.name || 'VS Code';
   const [switchingBranch, setSwitchingBranch] = useState<string | null>(null);
   const [showExitConfirm, setShowExitConfirm] = useState(false);
   const [exitError, setExitError] = useState<string | null>(null);
+  const [normalizingPath, setNormalizingPath] = useState<string | null>(null);
+  const [normalizeError, setNormalizeError] = useState<{ path: string; message: string } | null>(null);
+
+  const handleNormalizeLineEndings = useCallback(async (path: string) => {
+    setNormalizingPath(path);
+    setNormalizeError(null);
+    try {
+      await normalizeLineEndings(path);
+      onRefresh?.();
+    } catch (e) {
+      setNormalizeError({ path, message: String(e) });
+    } finally {
+      setNormalizingPath(null);
+    }
+  }, [onRefresh]);
+
+  const [fetchingAll, setFetchingAll] = useState(false);
+  const [fetchAllFailures, setFetchAllFailures] = useState<string[]>([]);
+
+  const handleFetchAll = useCallback(async () => {
+    setFetchingAll(true);
+    setFetchAllFailures([]);
+    try {
+      const results = await fetchAllProjects();
+      setFetchAllFailures(results.filter(r => !r.success).map(r => `${r.project_name}: ${r.message}`));
+      onRefresh?.();
+    } catch (e) {
+      setFetchAllFailures([String(e)]);
+    } finally {
+      setFetchingAll(false);
+    }
+  }, [onRefresh]);
+
+  const [bundleNotice, setBundleNotice] = useState<{ ok: boolean; lines: string[] } | null>(null);
+
+  const handleExportBundle = useCallback(async (name: string) => {
+    const dir = await openDirectoryDialog(t('detail.exportBundleTitle'));
+    if (!dir) return;
+    setBundleNotice(null);
+    try {
+      const result = await exportWorktreeBundle(name, `${dir}/${name}-bundle`);
+      const failures = result.manifest.projects.filter(p => p.error).map(p => `${p.name}: ${p.error}`);
+      const dirty = result.manifest.projects.filter(p => p.has_uncommitted).map(p => p.name);
+      setBundleNotice({
+        ok: failures.length === 0,
+        lines: [
+          t('detail.exportBundleDone', { path: result.output_path }),
+          ...failures,
+          ...(dirty.length > 0 ? [t('detail.exportBundleUncommitted', { projects: dirty.join(', ') })] : []),
+        ],
+      });
+    } catch (e) {
+      setBundleNotice({ ok: false, lines: [String(e)] });
+    }
+  }, [t]);
+
+  const handleKillProcesses = useCallback(async (path: string) => {
+    setBundleNotice(null);
+    try {
+      const killed = await killWorktreeProcesses(path);
+      const failed = killed.filter(p => !p.killed);
+      setBundleNotice({
+        ok: failed.length === 0,
+        lines: [
+          killed.length === 0 ? t('detail.killProcessesNone') : t('detail.killProcessesDone', { count: killed.length - failed.length }),
+          ...killed.map(p => `[${p.pid}] ${p.command || p.name}${p.forced ? ` (${t('detail.killProcessesForced')})` : ''}${p.killed ? '' : ` — ${t('detail.killProcessesFailed')}`}`),
+        ],
+      });
+    } catch (e) {
+      setBundleNotice({ ok: false, lines: [String(e)] });
+    }
+  }, [t]);
 
   const handleDeploy = useCallback(async (name: string) => {
     try {
@@ -263,8 +748,12 @@ export const WorktreeDetail: FC<WorktreeDetailProps> = ({
             <h2 className="text-xl font-semibold text-slate-100 truncate">{t('detail.mainWorkspace', { name: mainWorkspace.name })}</h2>
             <PathDisplay path={mainWorkspace.path} />
           </div>
+          <Button variant="secondary" className="shrink-0 ml-3" onClick={handleFetchAll} disabled={fetchingAll}>
+            <RefreshIcon className={`w-4 h-4 mr-1.5 ${fetchingAll ? 'animate-spin' : ''}`} />
+            {fetchingAll ? t('detail.fetchingAll') : t('detail.fetchAll')}
+          </Button>
           {isTauri() && (
-            <div className="flex gap-2 items-center shrink-0 ml-3">
+            <div className="flex gap-2 items-center shrink-0 ml-2">
               {onAddProject && (
                 <Button onClick={onAddProject} variant="default">
                   <PlusIcon className="w-4 h-4 mr-1.5" />
@@ -285,7 +774,7 @@ export const WorktreeDetail: FC<WorktreeDetailProps> = ({
                     </Button>
                   </DropdownMenuTrigger>
                   <DropdownMenuContent align="end">
-                    {EDITORS.map(editor => (
+                    {editors.map(editor => (
                       <div
                         key={editor.id}
                         className="flex items-stretch rounded-sm text-sm"
@@ -326,6 +815,12 @@ export const WorktreeDetail: FC<WorktreeDetailProps> = ({
             </div>
           )}
         </div>
+        {fetchAllFailures.length > 0 && (
+          <div className="mb-4 p-3 bg-red-900/30 border border-red-800/50 rounded-lg text-xs text-red-300 select-text space-y-1">
+            <div className="font-medium">{t('detail.fetchAllFailed')}</div>
+            {fetchAllFailures.map(line => <div key={line}>{line}</div>)}
+          </div>
+        )}
         {occupation ? (
           /* Deployed state: show only deployed projects in worktree-style cards */
           <div className="space-y-2">
@@ -536,6 +1031,17 @@ export const WorktreeDetail: FC<WorktreeDetailProps> = ({
               </>
             ) : (
               <>
+                {onRunQuickCommand && quickCommands.map(qc => (
+                  <Button
+                    key={`${qc.project ?? ''}:${qc.label}`}
+                    variant="secondary"
+                    onClick={(e) => onRunQuickCommand(qc, e.shiftKey)}
+                    title={`${qc.command}\n${t('detail.quickCommandHint')}`}
+                  >
+                    <TerminalIcon className="w-3.5 h-3.5 mr-1" />
+                    {qc.label}
+                  </Button>
+                ))}
                 {isTauri() && (
                   <>
                     <div className="inline-flex rounded-md">
@@ -552,7 +1058,7 @@ export const WorktreeDetail: FC<WorktreeDetailProps> = ({
                           </Button>
                         </DropdownMenuTrigger>
                         <DropdownMenuContent align="end">
-                          {EDITORS.map(editor => (
+                          {editors.map(editor => (
                             <div
                               key={editor.id}
                               className="flex items-stretch rounded-sm text-sm"
@@ -586,6 +1092,14 @@ export const WorktreeDetail: FC<WorktreeDetailProps> = ({
                             <FolderIcon className="w-4 h-4 mr-1.5 text-slate-400" />
                             {t('detail.openInFolder')}
                           </DropdownMenuItem>
+                          <DropdownMenuItem onClick={() => handleExportBundle(selectedWorktree.name)}>
+                            <ArchiveIcon className="w-4 h-4 mr-1.5 text-slate-400" />
+                            {t('detail.exportBundle')}
+                          </DropdownMenuItem>
+                          <DropdownMenuItem onClick={() => handleKillProcesses(selectedWorktree.path)}>
+                            <StopIcon className="w-4 h-4 mr-1.5 text-red-400" />
+                            {t('detail.killProcesses')}
+                          </DropdownMenuItem>
                         </DropdownMenuContent>
                       </DropdownMenu>
                     </div>
@@ -606,6 +1120,22 @@ export const WorktreeDetail: FC<WorktreeDetailProps> = ({
             )}
           </div>
         </div>
+        {bundleNotice && (
+          <div className={`mb-4 p-3 rounded-lg text-xs select-text space-y-1 ${bundleNotice.ok ? 'bg-emerald-900/20 border border-emerald-800/50 text-emerald-300' : 'bg-red-900/30 border border-red-800/50 text-red-300'}`}>
+            {bundleNotice.lines.map(line => <div key={line}>{line}</div>)}
+            <Button variant="link" size="sm" onClick={() => setBundleNotice(null)} className="p-0 h-auto text-xs">{t('common.close')}</Button>
+          </div>
+        )}
+        <WorktreeMetaPanel
+          key={selectedWorktree.name}
+          name={selectedWorktree.name}
+          meta={selectedWorktree.meta}
+          readOnly={selectedWorktree.is_archived}
+          onSaved={onRefresh}
+        />
+        {!selectedWorktree.is_archived && <WorktreePortsPanel key={`ports-${selectedWorktree.name}`} name={selectedWorktree.name} />}
+        <WorktreeRecordingsPanel key={`recordings-${selectedWorktree.name}`} name={selectedWorktree.name} onReveal={onRevealInFinder} />
+        <WorktreeCommandHistoryPanel key={`history-${selectedWorktree.name}`} name={selectedWorktree.name} />
         <div className="space-y-2">
           {selectedWorktree.projects.map(proj => (
             <div key={proj.name} className={`bg-slate-800/50 border border-slate-700/50 border-l-2 ${statusBorderColor[getProjectStatus(proj)]} rounded-lg p-4 group hover:border-t-slate-600 hover:border-r-slate-600 hover:border-b-slate-600 hover:shadow-md hover:shadow-black/10 hover:-translate-y-px transition-all duration-150`}>
@@ -624,8 +1154,10 @@ export const WorktreeDetail: FC<WorktreeDetailProps> = ({
                     <StatusBadges project={proj} />
                     <div className="text-xs text-slate-500 mt-0.5 select-text">{t('detail.branchInfo', { base: proj.base_branch, test: proj.test_branch })}</div>
                   </div>
+                  {!selectedWorktree.is_archived && <ComposeButton worktree={selectedWorktree.name} project={proj.name} />}
                   {isTauri() && (
                     <div className="flex items-center gap-1 text-slate-500 hover:text-slate-200">
+                      {!selectedWorktree.is_archived && <DevcontainerButton path={proj.path} name={proj.name} />}
                       <Button
                         variant="ghost"
                         size="icon"
@@ -650,6 +1182,53 @@ export const WorktreeDetail: FC<WorktreeDetailProps> = ({
                   )}
                 </div>
               </div>
+              {proj.line_ending_mismatch && (
+                <div className="mt-3 flex items-center justify-between gap-2 rounded border border-amber-500/30 bg-amber-500/10 px-2.5 py-1.5 text-xs text-amber-300">
+                  <span>{normalizeError?.path === proj.path ? normalizeError.message : t('detail.lineEndingMismatch')}</span>
+                  <Button variant="secondary" size="sm" className="h-6 text-xs shrink-0"
+                    onClick={() => handleNormalizeLineEndings(proj.path)}
+                    disabled={normalizingPath !== null}
+                  >
+                    {normalizingPath === proj.path ? t('detail.normalizingLineEndings') : t('detail.normalizeLineEndings')}
+                  </Button>
+                </div>
+              )}
+              {onRunTask && !selectedWorktree.is_archived && Object.keys(projectTasks[proj.name] ?? {}).length > 0 && (
+                <div className="mt-3 flex flex-wrap items-center gap-1.5">
+                  <span className="text-xs text-slate-500 mr-1">{t('detail.tasks')}</span>
+                  {Object.entries(projectTasks[proj.name]).map(([task, command]) => (
+                    <Button
+                      key={task}
+                      variant="secondary"
+                      size="sm"
+                      className="h-6 text-xs"
+                      onClick={() => onRunTask(proj.name, task)}
+                      title={command}
+                    >
+                      <TerminalIcon className="w-3 h-3 mr-1" />
+                      {task}
+                    </Button>
+                  ))}
+                </div>
+              )}
+              {onOpenTerminalPreset && !selectedWorktree.is_archived && (projectTerminalPresets[proj.name] ?? []).length > 0 && (
+                <div className="mt-3 flex flex-wrap items-center gap-1.5">
+                  <span className="text-xs text-slate-500 mr-1">{t('detail.terminalPresets')}</span>
+                  {projectTerminalPresets[proj.name].map(preset => (
+                    <Button
+                      key={preset.name}
+                      variant="secondary"
+                      size="sm"
+                      className="h-6 text-xs"
+                      onClick={() => onOpenTerminalPreset(proj.name, preset.name)}
+                      title={preset.command}
+                    >
+                      <TerminalIcon className="w-3 h-3 mr-1" />
+                      {preset.name}
+                    </Button>
+                  ))}
+                </div>
+              )}
               <div className="mt-3 pt-3 border-t border-slate-700/50">
                 <GitOperations
                   projectPath={proj.path}
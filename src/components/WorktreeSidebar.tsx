@@ -4,6 +4,7 @@ import { QRCodeSVG } from 'qrcode.react';
 import { openLink } from '@/lib/backend';
 import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
+import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from '@/components/ui/select';
 import {
   Dialog,
   DialogContent,
@@ -42,6 +43,7 @@ import {
   CopyIcon,
   GithubIcon,
   CheckCircleIcon,
+  UploadIcon,
   LinkIcon,
   QrCodeIcon,
 } from './Icons';
@@ -50,10 +52,98 @@ import type {
   WorktreeListItem,
   MainWorkspaceStatus,
   MainWorkspaceOccupation,
+  BaseUpdate,
+  RunningProcess,
 } from '../types';
 import type { UpdaterState } from '../hooks/useUpdater';
-import type { ConnectedClient } from '../lib/backend';
-import { callBackend, getAppVersion, getLastSharePort, getWindowLabel, isMainWindow as checkIsMainWindow, isTauri } from '../lib/backend';
+import type { ProjectCiStatus } from '../hooks/useCiStatus';
+import type { ConnectedClient, ShareActivity, ShareProfile, ShareRole } from '../lib/backend';
+import { callBackend, deleteShareProfile, getAppVersion, getLastSharePort, getShareProfiles, getWindowLabel, isMainWindow as checkIsMainWindow, isTauri, saveShareProfile } from '../lib/backend';
+import { labelColorClass } from '@/lib/utils';
+import { AppLogsDialog } from './AppLogsDialog';
+
+const EMPTY_PROFILE_DRAFT = { name: '', bindAddress: '', allowedWorktrees: '', role: 'admin' as ShareRole, tunnel: '' };
+
+const SHARE_ROLES: ShareRole[] = ['viewer', 'operator', 'admin'];
+
+// ==================== TunnelRow ====================
+
+/** Share bar row of a pluggable tunnel provider: public URL (QR code, copy) and on/off toggle */
+const TunnelRow: FC<{
+  label: string;
+  url: string | null;
+  loading: boolean;
+  password: string;
+  /** First WAN row shows the section badge */
+  showBadge: boolean;
+  colorClass: string;
+  activeClass: string;
+  onToggle?: () => void;
+}> = ({ label, url, loading, password, showBadge, colorClass, activeClass, onToggle }) => {
+  const { t } = useTranslation();
+  return (
+    <div className="flex items-center gap-2 min-h-[24px]">
+      {showBadge ? (
+        <span className="text-[11px] font-bold px-1.5 py-0.5 rounded shrink-0 bg-slate-600/30 text-slate-500 w-[52px] text-center">
+          {t('share.wan')}
+        </span>
+      ) : (
+        <span className="shrink-0 w-[52px]" />
+      )}
+      <span className="text-[11px] font-medium text-slate-500 shrink-0">{label}</span>
+      {url ? (
+        <>
+          <span className={`flex-1 text-xs ${colorClass} truncate min-w-0 select-all`} title={url}>
+            {url.replace(/^https?:\/\//, '')}
+          </span>
+          <div className="flex items-center gap-0.5 shrink-0">
+            <TooltipProvider delayDuration={300}>
+              <Tooltip>
+                <TooltipTrigger asChild>
+                  <Button variant="ghost" size="icon" className="h-5 w-5">
+                    <QrCodeIcon className="w-3 h-3" />
+                  </Button>
+                </TooltipTrigger>
+                <TooltipContent side="top" className="bg-white p-3 rounded-lg shadow-xl">
+                  <QRCodeSVG value={`${url}#pwd=${encodeURIComponent(password)}`} size={160} />
+                  <p className="text-center text-xs text-gray-600 mt-2 font-mono">{t('share.password')} {password}</p>
+                  <p className="text-center text-[10px] text-gray-400 mt-1">{t('share.scanToOpen')}</p>
+                </TooltipContent>
+              </Tooltip>
+            </TooltipProvider>
+            <TooltipProvider delayDuration={300}>
+              <Tooltip>
+                <TooltipTrigger asChild>
+                  <Button
+                    variant="ghost"
+                    size="icon"
+                    onClick={() => navigator.clipboard.writeText(url)}
+                    className="h-5 w-5"
+                  >
+                    <CopyIcon className="w-3 h-3" />
+                  </Button>
+                </TooltipTrigger>
+                <TooltipContent side="top">{t('share.copyExternalLink')}</TooltipContent>
+              </Tooltip>
+            </TooltipProvider>
+          </div>
+        </>
+      ) : (
+        <span className="flex-1 text-xs text-slate-500">{t('share.ngrokNotStarted')}</span>
+      )}
+      <button
+        type="button"
+        onClick={onToggle}
+        disabled={loading}
+        className={`relative inline-flex h-4 w-7 items-center rounded-full shrink-0 transition-colors ${loading ? 'opacity-50 cursor-wait' : 'cursor-pointer'
+          } ${url ? activeClass : 'bg-slate-600'}`}
+      >
+        <span className={`inline-block h-3 w-3 rounded-full bg-white transition-transform ${url ? 'translate-x-3.5' : 'translate-x-0.5'
+          }`} />
+      </button>
+    </div>
+  );
+};
 
 // ==================== ShareBar ====================
 
@@ -61,6 +151,10 @@ const ShareBar: FC<{
   active: boolean;
   urls: string[];
   ngrokUrl: string | null;
+  cloudflareUrl: string | null;
+  frpUrl: string | null;
+  tailscaleUrls?: string[];
+  funnelUrl: string | null;
   wmsUrl: string | null;
   wmsConnected?: boolean;
   wmsReconnecting?: boolean;
@@ -68,19 +162,32 @@ const ShareBar: FC<{
   wmsNextRetrySecs?: number;
   password: string;
   ngrokLoading: boolean;
+  cloudflareLoading: boolean;
+  frpLoading: boolean;
+  funnelLoading: boolean;
   wmsLoading: boolean;
   connectedClients?: ConnectedClient[];
+  shareActivity?: ShareActivity[];
+  onClearShareActivity?: () => void;
   onToggleNgrok?: () => void;
+  onToggleCloudflare?: () => void;
+  onToggleFrp?: () => void;
+  onToggleFunnel?: () => void;
   onToggleWms?: () => void;
   onWmsManualReconnect?: () => void;
   onStart?: (port: number) => void | Promise<void>;
+  onStartWithProfile?: (name: string) => void | Promise<void>;
   onStop?: () => void;
   onUpdatePassword?: (password: string) => void;
   onKickClient?: (sessionId: string) => void;
+  onToggleQuarantine?: (sessionId: string, quarantined: boolean) => void;
+  onSetClientRole?: (sessionId: string, role: ShareRole) => void;
   hasLastConfig?: boolean;
   onQuickShare?: () => void;
   hasNgrokToken?: boolean;
-}> = ({ active, urls, ngrokUrl, wmsUrl, wmsConnected = true, wmsReconnecting = false, wmsReconnectAttempt = 0, wmsNextRetrySecs = 0, password, ngrokLoading, wmsLoading, connectedClients = [], onToggleNgrok, onToggleWms, onWmsManualReconnect, onStart, onStop, onUpdatePassword, onKickClient, hasLastConfig = false, onQuickShare, hasNgrokToken = false }) => {
+  cloudflareEnabled?: boolean;
+  frpEnabled?: boolean;
+}> = ({ active, urls, ngrokUrl, cloudflareUrl, frpUrl, tailscaleUrls = [], funnelUrl, wmsUrl, wmsConnected = true, wmsReconnecting = false, wmsReconnectAttempt = 0, wmsNextRetrySecs = 0, password, ngrokLoading, cloudflareLoading, frpLoading, funnelLoading, wmsLoading, connectedClients = [], shareActivity = [], onClearShareActivity, onToggleNgrok, onToggleCloudflare, onToggleFrp, onToggleFunnel, onToggleWms, onWmsManualReconnect, onStart, onStartWithProfile, onStop, onUpdatePassword, onKickClient, onToggleQuarantine, onSetClientRole, hasLastConfig = false, onQuickShare, hasNgrokToken = false, cloudflareEnabled = false, frpEnabled = false }) => {
   const { t } = useTranslation();
   const [showPassword, setShowPassword] = useState(false);
   const [editingPassword, setEditingPassword] = useState('');
@@ -91,6 +198,9 @@ const ShareBar: FC<{
   const [portError, setPortError] = useState<string | null>(null);
   const [kickingSessionId, setKickingSessionId] = useState<string | null>(null);
   const [lanExpanded, setLanExpanded] = useState(false);
+  const [profiles, setProfiles] = useState<ShareProfile[]>([]);
+  const [profileDraft, setProfileDraft] = useState(EMPTY_PROFILE_DRAFT);
+  const [profileError, setProfileError] = useState<string | null>(null);
 
   // Sync editing password when prop changes (e.g., on share start)
   useEffect(() => {
@@ -135,7 +245,44 @@ const ShareBar: FC<{
     }
     setSharePort(port);
     setPortError(null);
+    setProfileError(null);
     setShowShareDialog(true);
+    if (!active && onStartWithProfile) {
+      getShareProfiles().then(setProfiles).catch(() => setProfiles([]));
+    }
+  };
+
+  const handleSaveProfile = async () => {
+    const profile: ShareProfile = {
+      name: profileDraft.name.trim(),
+      port: sharePort,
+      bind_address: profileDraft.bindAddress.trim() || null,
+      role: profileDraft.role,
+      allowed_worktrees: profileDraft.allowedWorktrees.split(',').map(s => s.trim()).filter(Boolean),
+      tunnel: (['ngrok', 'cloudflare', 'tailscale', 'frp', 'wms'] as const).find(tunnel => tunnel === profileDraft.tunnel) ?? null,
+    };
+    try {
+      await saveShareProfile(profile);
+      setProfiles(await getShareProfiles());
+      setProfileDraft(EMPTY_PROFILE_DRAFT);
+      setProfileError(null);
+    } catch (e) {
+      setProfileError(String(e));
+    }
+  };
+
+  const handleDeleteProfile = async (name: string) => {
+    try {
+      await deleteShareProfile(name);
+      setProfiles(prev => prev.filter(p => p.name !== name));
+    } catch (e) {
+      setProfileError(String(e));
+    }
+  };
+
+  const handleStartProfile = (name: string) => {
+    setShowShareDialog(false);
+    onStartWithProfile?.(name);
   };
 
   const handleSmartStart = async () => {
@@ -264,7 +411,7 @@ const ShareBar: FC<{
 
   return (
     <div className="px-3 py-2.5 border-t border-slate-700/50 space-y-1.5">
-      {/* WAN section: NGROK + Remote */}
+      {/* WAN section: NGROK + Cloudflare + frp + Remote */}
       <div className="space-y-0.5">
         {/* NGROK row — only shown if ngrok token is configured */}
         {hasNgrokToken && (
@@ -325,6 +472,15 @@ const ShareBar: FC<{
             </button>
           </div>
         )}
+        {/* Cloudflare / frp rows — only shown if enabled in settings (or still running) */}
+        {(cloudflareEnabled || cloudflareUrl) && (
+          <TunnelRow label={t('share.cloudflareLabel')} url={cloudflareUrl} loading={cloudflareLoading} password={editingPassword}
+            showBadge={!hasNgrokToken} colorClass="text-orange-400" activeClass="bg-orange-500" onToggle={onToggleCloudflare} />
+        )}
+        {(frpEnabled || frpUrl) && (
+          <TunnelRow label={t('share.frpLabel')} url={frpUrl} loading={frpLoading} password={editingPassword}
+            showBadge={!hasNgrokToken && !(cloudflareEnabled || cloudflareUrl)} colorClass="text-teal-400" activeClass="bg-teal-500" onToggle={onToggleFrp} />
+        )}
         {/* Remote row */}
         <div className="flex items-center gap-2 min-h-[24px]">
           <span className="shrink-0 w-[52px]" />
@@ -418,6 +574,73 @@ const ShareBar: FC<{
           </button>
         </div>
       </div>
+      {/* Tailnet section: MagicDNS URL, Funnel toggle (only when Tailscale is running) */}
+      {tailscaleUrls.length > 0 && (
+        <div className="space-y-0.5">
+          <div className="flex items-center gap-2 min-h-[24px]">
+            <span className="text-[11px] font-bold px-1.5 py-0.5 rounded shrink-0 bg-slate-600/30 text-slate-500 w-[52px] text-center">
+              {t('share.tailnet')}
+            </span>
+            <span className="flex-1 text-xs text-cyan-400 truncate min-w-0 select-all" title={tailscaleUrls.join('\n')}>
+              {tailscaleUrls[0].replace(/^https?:\/\//, '')}
+            </span>
+            <TooltipProvider delayDuration={300}>
+              <Tooltip>
+                <TooltipTrigger asChild>
+                  <Button
+                    variant="ghost"
+                    size="icon"
+                    onClick={() => navigator.clipboard.writeText(tailscaleUrls[0])}
+                    className="h-5 w-5 shrink-0"
+                  >
+                    <CopyIcon className="w-3 h-3" />
+                  </Button>
+                </TooltipTrigger>
+                <TooltipContent side="top">{t('share.copyLink')}</TooltipContent>
+              </Tooltip>
+            </TooltipProvider>
+          </div>
+          <div className="flex items-center gap-2 min-h-[24px]">
+            <span className="shrink-0 w-[52px]" />
+            <span className="text-[11px] font-medium text-slate-500 shrink-0">{t('share.funnelLabel')}</span>
+            {funnelUrl ? (
+              <>
+                <span className="flex-1 text-xs text-cyan-400 truncate min-w-0 select-all" title={funnelUrl}>
+                  {funnelUrl.replace(/^https?:\/\//, '')}
+                </span>
+                <TooltipProvider delayDuration={300}>
+                  <Tooltip>
+                    <TooltipTrigger asChild>
+                      <Button
+                        variant="ghost"
+                        size="icon"
+                        onClick={() => navigator.clipboard.writeText(funnelUrl)}
+                        className="h-5 w-5 shrink-0"
+                      >
+                        <CopyIcon className="w-3 h-3" />
+                      </Button>
+                    </TooltipTrigger>
+                    <TooltipContent side="top">{t('share.copyExternalLink')}</TooltipContent>
+                  </Tooltip>
+                </TooltipProvider>
+              </>
+            ) : (
+              <span className="flex-1 text-xs text-slate-500">{t('share.ngrokNotStarted')}</span>
+            )}
+            <button
+              type="button"
+              onClick={onToggleFunnel}
+              disabled={funnelLoading}
+              title={t('share.funnelHint')}
+              className={`relative inline-flex h-4 w-7 items-center rounded-full shrink-0 transition-colors ${funnelLoading ? 'opacity-50 cursor-wait' : 'cursor-pointer'
+                } ${funnelUrl ? 'bg-cyan-500' : 'bg-slate-600'}`}
+            >
+              <span className={`inline-block h-3 w-3 rounded-full bg-white transition-transform ${funnelUrl ? 'translate-x-3.5' : 'translate-x-0.5'
+                }`} />
+            </button>
+          </div>
+        </div>
+      )}
       {/* LAN section: LAN IPs first, localhost last (collapsed) */}
       {urls.length > 0 && (() => {
         const localUrl = `http://localhost:${new URL(urls[0]).port}`;
@@ -551,6 +774,28 @@ const ShareBar: FC<{
                 {c.ws_connected && (
                   <span className="text-[9px] text-blue-400/70 shrink-0">WS</span>
                 )}
+                {c.quarantined && (
+                  <span className="text-[9px] text-amber-400/80 shrink-0">{t('share.quarantined')}</span>
+                )}
+                <Select value={c.role} onValueChange={(value) => onSetClientRole?.(c.session_id, value as ShareRole)}>
+                  <SelectTrigger className="h-4 w-auto gap-0.5 px-1 border-0 bg-transparent text-[9px] text-slate-500 shrink-0" title={t('share.clientRole')}>
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    {SHARE_ROLES.map(role => (
+                      <SelectItem key={role} value={role} className="text-xs">{t(`share.role.${role}`)}</SelectItem>
+                    ))}
+                  </SelectContent>
+                </Select>
+                <Button
+                  variant="ghost"
+                  size="icon"
+                  onClick={() => onToggleQuarantine?.(c.session_id, c.quarantined)}
+                  className="h-4 w-4 shrink-0 opacity-0 group-hover:opacity-100 hover:bg-amber-500/20 hover:text-amber-400"
+                  title={c.quarantined ? t('share.releaseClient') : t('share.quarantineClient')}
+                >
+                  <span className="text-[10px]">{c.quarantined ? '◎' : '⊘'}</span>
+                </Button>
                 <Button
                   variant="ghost"
                   size="icon"
@@ -565,6 +810,33 @@ const ShareBar: FC<{
           </div>
         </div>
       )}
+      {/* Remote activity feed */}
+      {shareActivity.length > 0 && (
+        <div className="space-y-0.5">
+          <div className="flex items-center justify-between">
+            <span className="text-[10px] font-medium text-slate-500">
+              {t('share.activity')}
+            </span>
+            <button
+              onClick={onClearShareActivity}
+              className="text-[9px] text-slate-500 hover:text-slate-300"
+            >
+              {t('share.clearActivity')}
+            </button>
+          </div>
+          <div className="max-h-[80px] overflow-y-auto space-y-0">
+            {shareActivity.map((a, i) => (
+              <div key={`${a.at}-${a.session_id}-${i}`} className="flex items-center gap-1.5 py-px px-1" title={`${a.at}\n${a.ip}\n${a.duration_ms}ms`}>
+                <span className={`w-1.5 h-1.5 rounded-full shrink-0 ${a.status < 400 ? 'bg-emerald-400' : 'bg-red-400'}`} />
+                <span className="text-[10px] text-slate-500 shrink-0 font-mono">{a.ip}</span>
+                <span className="text-[11px] text-slate-400 truncate flex-1">
+                  {a.command}{a.summary ? ` · ${a.summary}` : ''}
+                </span>
+              </div>
+            ))}
+          </div>
+        </div>
+      )}
 
       {/* Share actions row */}
       <div className="flex items-center justify-between pt-1">
@@ -657,6 +929,66 @@ const ShareBar: FC<{
                 {t('share.portHint')}
               </p>
             </div>
+            {!active && onStartWithProfile && (
+              <div className="border-t border-slate-700/50 pt-4">
+                <label className="block text-sm text-slate-400 mb-2">{t('share.profiles')}</label>
+                {profiles.length === 0 ? (
+                  <p className="text-xs text-slate-500 mb-2">{t('share.noProfiles')}</p>
+                ) : (
+                  <div className="space-y-1 mb-3">
+                    {profiles.map(p => (
+                      <div key={p.name} className="flex items-center gap-2">
+                        <span className="flex-1 min-w-0 truncate text-sm text-slate-200" title={p.allowed_worktrees.join(', ')}>{p.name}</span>
+                        <span className="text-xs text-slate-500 shrink-0">
+                          {[p.bind_address ? `${p.bind_address}:${p.port}` : `:${p.port}`, p.tunnel, p.role !== 'admin' ? t(`share.role.${p.role}`) : null].filter(Boolean).join(' · ')}
+                        </span>
+                        <Button size="sm" variant="secondary" className="h-7" onClick={() => handleStartProfile(p.name)}>
+                          {t('share.profileStart')}
+                        </Button>
+                        <Button size="sm" variant="ghost" className="h-7 text-slate-500 hover:text-red-400" onClick={() => handleDeleteProfile(p.name)}>
+                          {t('common.delete')}
+                        </Button>
+                      </div>
+                    ))}
+                  </div>
+                )}
+                <div className="space-y-2">
+                  <Input value={profileDraft.name} onChange={(e) => setProfileDraft({ ...profileDraft, name: e.target.value })}
+                    placeholder={t('share.profileNamePlaceholder')} className="h-8 text-xs" />
+                  <Input value={profileDraft.bindAddress} onChange={(e) => setProfileDraft({ ...profileDraft, bindAddress: e.target.value })}
+                    placeholder={t('share.profileBindPlaceholder')} className="h-8 text-xs" />
+                  <Input value={profileDraft.allowedWorktrees} onChange={(e) => setProfileDraft({ ...profileDraft, allowedWorktrees: e.target.value })}
+                    placeholder={t('share.profileWorktreesPlaceholder')} className="h-8 text-xs" />
+                  <div className="flex items-center gap-3">
+                    <Select value={profileDraft.role} onValueChange={(value) => setProfileDraft({ ...profileDraft, role: value as ShareRole })}>
+                      <SelectTrigger className="w-28 h-7 text-xs" title={t('share.profileRoleHint')}><SelectValue /></SelectTrigger>
+                      <SelectContent>
+                        {SHARE_ROLES.map(role => (
+                          <SelectItem key={role} value={role}>{t(`share.role.${role}`)}</SelectItem>
+                        ))}
+                      </SelectContent>
+                    </Select>
+                    <Select value={profileDraft.tunnel || 'none'} onValueChange={(value) => setProfileDraft({ ...profileDraft, tunnel: value === 'none' ? '' : value })}>
+                      <SelectTrigger className="w-28 h-7 text-xs"><SelectValue /></SelectTrigger>
+                      <SelectContent>
+                        <SelectItem value="none">{t('share.profileNoTunnel')}</SelectItem>
+                        <SelectItem value="ngrok">ngrok</SelectItem>
+                        <SelectItem value="cloudflare">Cloudflare</SelectItem>
+                        <SelectItem value="tailscale">Tailscale Funnel</SelectItem>
+                        <SelectItem value="frp">frp</SelectItem>
+                        <SelectItem value="wms">WMS</SelectItem>
+                      </SelectContent>
+                    </Select>
+                    <Button size="sm" variant="secondary" className="h-7 ml-auto" onClick={handleSaveProfile} disabled={!profileDraft.name.trim()}>
+                      {t('share.profileSave')}
+                    </Button>
+                  </div>
+                  {profileError && (
+                    <p className="text-sm text-red-400">{profileError}</p>
+                  )}
+                </div>
+              </div>
+            )}
           </div>
           <DialogFooter>
             <Button variant="secondary" onClick={() => setShowShareDialog(false)}>
@@ -689,6 +1021,9 @@ interface WorktreeSidebarProps {
   onToggleArchived: () => void;
   onContextMenu: (e: React.MouseEvent, worktree: WorktreeListItem) => void;
   onRefresh: () => void;
+  refreshProgress?: { scanned: number; total: number } | null;
+  onOpenBulkArchive?: () => void;
+  onImportBundle?: () => void;
   onOpenSettings: () => void;
   onOpenCreateModal: () => void;
   updaterState: UpdaterState;
@@ -701,12 +1036,23 @@ interface WorktreeSidebarProps {
   shareActive?: boolean;
   shareUrls?: string[];
   shareNgrokUrl?: string | null;
+  shareCloudflareUrl?: string | null;
+  shareFrpUrl?: string | null;
+  tailscaleUrls?: string[];
+  shareFunnelUrl?: string | null;
   sharePassword?: string;
   onStartShare?: (port: number) => void;
+  onStartShareWithProfile?: (name: string) => void;
   onStopShare?: () => void;
   onUpdateSharePassword?: (password: string) => void;
   ngrokLoading?: boolean;
   onToggleNgrok?: () => void;
+  cloudflareLoading?: boolean;
+  onToggleCloudflare?: () => void;
+  frpLoading?: boolean;
+  onToggleFrp?: () => void;
+  funnelLoading?: boolean;
+  onToggleFunnel?: () => void;
   shareWmsUrl?: string | null;
   wmsConnected?: boolean;
   wmsReconnecting?: boolean;
@@ -716,11 +1062,24 @@ interface WorktreeSidebarProps {
   onToggleWms?: () => void;
   onWmsManualReconnect?: () => void;
   connectedClients?: ConnectedClient[];
+  shareActivity?: ShareActivity[];
+  onClearShareActivity?: () => void;
   onKickClient?: (sessionId: string) => void;
+  onToggleQuarantine?: (sessionId: string, quarantined: boolean) => void;
+  onSetClientRole?: (sessionId: string, role: ShareRole) => void;
   hasLastConfig?: boolean;
   onQuickShare?: () => void;
   occupation?: MainWorkspaceOccupation | null;
   hasNgrokToken?: boolean;
+  cloudflareEnabled?: boolean;
+  frpEnabled?: boolean;
+  baseUpdates?: Record<string, BaseUpdate[]>;
+  syncingBaseWorktree?: string | null;
+  onSyncBaseUpdates?: (worktreeName: string) => void;
+  /** worktree name → processes running in its terminals */
+  runningProcesses?: Record<string, RunningProcess[]>;
+  /** worktree name → CI status of its project branches */
+  ciStatus?: Record<string, ProjectCiStatus[]>;
 }
 
 export const WorktreeSidebar: FC<WorktreeSidebarProps> = ({
@@ -738,6 +1097,9 @@ export const WorktreeSidebar: FC<WorktreeSidebarProps> = ({
   onToggleArchived,
   onContextMenu,
   onRefresh,
+  refreshProgress,
+  onOpenBulkArchive,
+  onImportBundle,
   onOpenSettings,
   onOpenCreateModal,
   updaterState,
@@ -750,12 +1112,23 @@ export const WorktreeSidebar: FC<WorktreeSidebarProps> = ({
   shareActive = false,
   shareUrls = [],
   shareNgrokUrl,
+  shareCloudflareUrl,
+  shareFrpUrl,
+  tailscaleUrls,
+  shareFunnelUrl,
   sharePassword = '',
   onStartShare,
+  onStartShareWithProfile,
   onStopShare,
   onUpdateSharePassword,
   ngrokLoading = false,
   onToggleNgrok,
+  cloudflareLoading = false,
+  onToggleCloudflare,
+  frpLoading = false,
+  onToggleFrp,
+  funnelLoading = false,
+  onToggleFunnel,
   shareWmsUrl,
   wmsConnected = true,
   wmsReconnecting = false,
@@ -765,27 +1138,47 @@ export const WorktreeSidebar: FC<WorktreeSidebarProps> = ({
   onToggleWms,
   onWmsManualReconnect,
   connectedClients = [],
+  shareActivity = [],
+  onClearShareActivity,
   onKickClient,
+  onToggleQuarantine,
+  onSetClientRole,
   hasLastConfig = false,
   onQuickShare,
   occupation,
   hasNgrokToken = false,
+  cloudflareEnabled = false,
+  frpEnabled = false,
+  baseUpdates = {},
+  syncingBaseWorktree = null,
+  onSyncBaseUpdates,
+  runningProcesses = {},
+  ciStatus = {},
 }) => {
   const { t } = useTranslation();
   const _isTauri = isTauri();
 
   // 网页端只显示被锁定的 worktree（正在被桌面端使用的）
   // 桌面端显示所有活动的 worktree
-  const activeWorktrees = worktrees.filter(w => {
+  const visibleWorktrees = worktrees.filter(w => {
     if (w.is_archived) return false;
     if (_isTauri) return true; // 桌面端显示所有
     // 网页端只显示被锁定的
     return lockedWorktrees[w.name];
   });
+  const [labelFilter, setLabelFilter] = useState<string[]>([]);
+  const allLabels = [...new Set(visibleWorktrees.flatMap(w => w.meta?.labels ?? []))].sort();
+  const activeWorktrees = visibleWorktrees.filter(w =>
+    labelFilter.every(l => w.meta?.labels.includes(l))
+  );
+  const toggleLabelFilter = (label: string) => {
+    setLabelFilter(prev => prev.includes(label) ? prev.filter(l => l !== label) : [...prev, label]);
+  };
   const archivedWorktrees = worktrees.filter(w => w.is_archived);
 
   const [appVersion, setAppVersion] = useState('');
   const [switchConfirmPath, setSwitchConfirmPath] = useState<string | null>(null);
+  const [showAppLogs, setShowAppLogs] = useState(false);
   const [isMainWin, setIsMainWin] = useState(true);
   const [currentWindowLabel, setCurrentWindowLabel] = useState('main');
 
@@ -1066,12 +1459,39 @@ export const WorktreeSidebar: FC<WorktreeSidebarProps> = ({
                 variant="ghost"
                 size="icon"
                 onClick={onRefresh}
-                title={t('sidebar.refresh')}
+                disabled={!!refreshProgress}
+                title={refreshProgress && refreshProgress.total > 0
+                  ? t('sidebar.refreshProgress', { scanned: refreshProgress.scanned, total: refreshProgress.total })
+                  : t('sidebar.refresh')}
                 aria-label={t('sidebar.refreshWorktrees')}
                 className="h-8 w-8"
               >
-                <RefreshIcon className="w-4 h-4" />
+                <RefreshIcon className={`w-4 h-4 ${refreshProgress ? 'animate-spin' : ''}`} />
               </Button>
+              {onOpenBulkArchive && (
+                <Button
+                  variant="ghost"
+                  size="icon"
+                  onClick={onOpenBulkArchive}
+                  title={t('bulkArchive.title')}
+                  aria-label={t('bulkArchive.title')}
+                  className="h-8 w-8"
+                >
+                  <ArchiveIcon className="w-4 h-4" />
+                </Button>
+              )}
+              {onImportBundle && (
+                <Button
+                  variant="ghost"
+                  size="icon"
+                  onClick={onImportBundle}
+                  title={t('sidebar.importBundle')}
+                  aria-label={t('sidebar.importBundle')}
+                  className="h-8 w-8"
+                >
+                  <UploadIcon className="w-4 h-4" />
+                </Button>
+              )}
               {onToggleCollapsed && (
                 <Button
                   variant="ghost"
@@ -1129,6 +1549,24 @@ export const WorktreeSidebar: FC<WorktreeSidebarProps> = ({
               {t('sidebar.active')} ({activeWorktrees.length})
             </span>
           </div>
+          {allLabels.length > 0 && (
+            <div className="px-4 pb-2 flex flex-wrap gap-1" aria-label={t('sidebar.labelFilter')}>
+              {allLabels.map(label => (
+                <button
+                  key={label}
+                  onClick={() => toggleLabelFilter(label)}
+                  className={`text-[10px] px-1.5 py-0.5 rounded border transition-opacity ${labelColorClass(label)} ${labelFilter.length === 0 || labelFilter.includes(label) ? '' : 'opacity-40'}`}
+                >
+                  {label}
+                </button>
+              ))}
+              {labelFilter.length > 0 && (
+                <button onClick={() => setLabelFilter([])} className="text-[10px] px-1.5 py-0.5 text-slate-500 hover:text-slate-300">
+                  {t('sidebar.clearLabelFilter')}
+                </button>
+              )}
+            </div>
+          )}
           {activeWorktrees.length === 0 ? (
             <div className="px-4 py-8 text-center">
               <div className="flex justify-center mb-3">
@@ -1171,7 +1609,10 @@ export const WorktreeSidebar: FC<WorktreeSidebarProps> = ({
                         <TooltipTrigger asChild>
                           <span className="font-medium text-sm truncate flex-1">{wt.name}</span>
                         </TooltipTrigger>
-                        <TooltipContent side="right">{wt.name}</TooltipContent>
+                        <TooltipContent side="right">
+                          <div>{wt.name}</div>
+                          {wt.meta?.description && <div className="text-slate-400 max-w-xs whitespace-pre-wrap">{wt.meta.description}</div>}
+                        </TooltipContent>
                       </Tooltip>
                     </TooltipProvider>
                     {isDeployed && (
@@ -1194,6 +1635,73 @@ export const WorktreeSidebar: FC<WorktreeSidebarProps> = ({
                         </Tooltip>
                       </TooltipProvider>
                     )}
+                    {runningProcesses[wt.name] && (() => {
+                      const processes = runningProcesses[wt.name];
+                      const tip = processes.map(p => t('sidebar.runningProcessTip', { pid: p.pid, command: p.command })).join('\n');
+                      return (
+                        <TooltipProvider delayDuration={300}>
+                          <Tooltip>
+                            <TooltipTrigger asChild>
+                              <span className="flex items-center gap-1 text-[10px] text-emerald-400/90 bg-emerald-900/20 border border-emerald-800/30 px-1.5 py-0.5 rounded shrink-0 cursor-help">
+                                <span className="w-1.5 h-1.5 rounded-full bg-emerald-400 animate-pulse" />
+                                {processes.length}
+                              </span>
+                            </TooltipTrigger>
+                            <TooltipContent side="right" className="whitespace-pre">{`${t('sidebar.runningProcesses', { count: processes.length })}\n${tip}`}</TooltipContent>
+                          </Tooltip>
+                        </TooltipProvider>
+                      );
+                    })()}
+                    {ciStatus[wt.name] && (() => {
+                      const statuses = ciStatus[wt.name];
+                      const failing = statuses.find(s => s.status.state === 'failure');
+                      const state = failing ? 'failure' : statuses.some(s => s.status.state === 'pending') ? 'pending' : 'success';
+                      const target = failing ?? statuses[0];
+                      const tip = statuses.map(s => t(`sidebar.ci.${s.status.state}`, { name: s.projectName, branch: s.branch, failed: s.status.failed, pending: s.status.pending, total: s.status.total })).join('\n');
+                      const color = state === 'failure'
+                        ? 'text-red-400/90 bg-red-900/20 border-red-800/30'
+                        : state === 'pending'
+                          ? 'text-amber-400/90 bg-amber-900/20 border-amber-800/30'
+                          : 'text-emerald-400/90 bg-emerald-900/20 border-emerald-800/30';
+                      return (
+                        <TooltipProvider delayDuration={300}>
+                          <Tooltip>
+                            <TooltipTrigger asChild>
+                              <button
+                                type="button"
+                                onClick={(e) => { e.stopPropagation(); if (target.status.url) openLink(target.status.url); }}
+                                className={`text-[10px] border px-1.5 py-0.5 rounded shrink-0 ${color}`}
+                              >
+                                {state === 'failure' ? '✗' : state === 'pending' ? '●' : '✓'} CI
+                              </button>
+                            </TooltipTrigger>
+                            <TooltipContent side="right" className="whitespace-pre">{tip}</TooltipContent>
+                          </Tooltip>
+                        </TooltipProvider>
+                      );
+                    })()}
+                    {baseUpdates[wt.name] && !isLockedByOther && !isDeployed && (() => {
+                      const behind = baseUpdates[wt.name];
+                      const tip = behind.map(u => t('sidebar.baseUpdateTip', { name: u.project_name, count: u.behind, base: u.base_branch })).join('\n');
+                      const syncing = syncingBaseWorktree === wt.name;
+                      return (
+                        <TooltipProvider delayDuration={300}>
+                          <Tooltip>
+                            <TooltipTrigger asChild>
+                              <button
+                                type="button"
+                                disabled={syncing}
+                                onClick={(e) => { e.stopPropagation(); onSyncBaseUpdates?.(wt.name); }}
+                                className="text-[10px] text-sky-400/90 bg-sky-900/20 border border-sky-800/30 px-1.5 py-0.5 rounded shrink-0 hover:bg-sky-900/40 disabled:opacity-50"
+                              >
+                                {syncing ? t('git.syncing') : `↓${Math.max(...behind.map(u => u.behind))}`}
+                              </button>
+                            </TooltipTrigger>
+                            <TooltipContent side="right" className="whitespace-pre">{`${tip}\n${t('sidebar.baseUpdateAction')}`}</TooltipContent>
+                          </Tooltip>
+                        </TooltipProvider>
+                      );
+                    })()}
                     {wt.projects.some(p => p.has_uncommitted) && !isLockedByOther && !isDeployed && (() => {
                       const uncommitted = wt.projects.filter(p => p.has_uncommitted);
                       const tip = uncommitted.map(p => t('sidebar.uncommittedTip', { name: p.name, count: p.uncommitted_count })).join('\n');
@@ -1209,7 +1717,12 @@ export const WorktreeSidebar: FC<WorktreeSidebarProps> = ({
                       );
                     })()}
                   </div>
-                  <div className="text-slate-500 text-xs mt-0.5 pl-6">{t('sidebar.projects', { count: wt.projects.length })}</div>
+                  <div className="text-slate-500 text-xs mt-0.5 pl-6 flex items-center gap-1 min-w-0">
+                    <span className="shrink-0">{t('sidebar.projects', { count: wt.projects.length })}</span>
+                    {wt.meta?.labels.map(label => (
+                      <span key={label} className={`text-[10px] px-1 rounded border truncate ${labelColorClass(label)}`}>{label}</span>
+                    ))}
+                  </div>
                 </div>
               );
             })
@@ -1235,6 +1748,14 @@ export const WorktreeSidebar: FC<WorktreeSidebarProps> = ({
               <div className="flex items-center gap-2.5">
                 <ArchiveIcon className="w-4 h-4 text-slate-500" />
                 <span className="font-medium text-sm truncate">{wt.name}</span>
+                {wt.size_display && (
+                  <span
+                    className="ml-auto text-[10px] text-slate-500 shrink-0"
+                    title={wt.archived_at ? new Date(wt.archived_at).toLocaleString() : undefined}
+                  >
+                    {wt.size_display}
+                  </span>
+                )}
               </div>
             </div>
           ))}
@@ -1246,6 +1767,10 @@ export const WorktreeSidebar: FC<WorktreeSidebarProps> = ({
             active={shareActive}
             urls={shareUrls}
             ngrokUrl={shareNgrokUrl || null}
+            cloudflareUrl={shareCloudflareUrl || null}
+            frpUrl={shareFrpUrl || null}
+            tailscaleUrls={tailscaleUrls}
+            funnelUrl={shareFunnelUrl || null}
             wmsUrl={shareWmsUrl || null}
             wmsConnected={wmsConnected}
             wmsReconnecting={wmsReconnecting}
@@ -1253,18 +1778,31 @@ export const WorktreeSidebar: FC<WorktreeSidebarProps> = ({
             wmsNextRetrySecs={wmsNextRetrySecs}
             password={sharePassword}
             ngrokLoading={ngrokLoading}
+            cloudflareLoading={cloudflareLoading}
+            frpLoading={frpLoading}
+            funnelLoading={funnelLoading}
             wmsLoading={wmsLoading}
             connectedClients={connectedClients}
+            shareActivity={shareActivity}
+            onClearShareActivity={onClearShareActivity}
             onToggleNgrok={onToggleNgrok}
+            onToggleCloudflare={onToggleCloudflare}
+            onToggleFrp={onToggleFrp}
+            onToggleFunnel={onToggleFunnel}
             onToggleWms={onToggleWms}
             onWmsManualReconnect={onWmsManualReconnect}
             onStart={onStartShare}
+            onStartWithProfile={onStartShareWithProfile}
             onStop={onStopShare}
             onUpdatePassword={onUpdateSharePassword}
             onKickClient={onKickClient}
+            onToggleQuarantine={onToggleQuarantine}
+            onSetClientRole={onSetClientRole}
             hasLastConfig={hasLastConfig}
             onQuickShare={onQuickShare}
             hasNgrokToken={hasNgrokToken}
+            cloudflareEnabled={cloudflareEnabled}
+            frpEnabled={frpEnabled}
           />
         )}
 
@@ -1303,23 +1841,21 @@ export const WorktreeSidebar: FC<WorktreeSidebarProps> = ({
             <div />
           )}
           <div className="flex items-center gap-1">
-            {_isTauri && (
-              <TooltipProvider delayDuration={300}>
-                <Tooltip>
-                  <TooltipTrigger asChild>
-                    <Button
-                      variant="ghost"
-                      size="icon"
-                      onClick={handleOpenLogDir}
-                      className="h-7 w-7"
-                    >
-                      <LogIcon className="w-3.5 h-3.5" />
-                    </Button>
-                  </TooltipTrigger>
-                  <TooltipContent side="top">{t('sidebar.logFolder')}</TooltipContent>
-                </Tooltip>
-              </TooltipProvider>
-            )}
+            <TooltipProvider delayDuration={300}>
+              <Tooltip>
+                <TooltipTrigger asChild>
+                  <Button
+                    variant="ghost"
+                    size="icon"
+                    onClick={_isTauri ? handleOpenLogDir : () => setShowAppLogs(true)}
+                    className="h-7 w-7"
+                  >
+                    <LogIcon className="w-3.5 h-3.5" />
+                  </Button>
+                </TooltipTrigger>
+                <TooltipContent side="top">{_isTauri ? t('sidebar.logFolder') : t('appLogs.title')}</TooltipContent>
+              </Tooltip>
+            </TooltipProvider>
             <TooltipProvider delayDuration={300}>
               <Tooltip>
                 <TooltipTrigger asChild>
@@ -1338,6 +1874,8 @@ export const WorktreeSidebar: FC<WorktreeSidebarProps> = ({
           </div>
         </div>
 
+        {!_isTauri && <AppLogsDialog open={showAppLogs} onOpenChange={setShowAppLogs} />}
+
         {/* Switch Workspace Confirmation Dialog */}
         <Dialog open={!!switchConfirmPath} onOpenChange={(open) => !open && setSwitchConfirmPath(null)}>
           <DialogContent className="max-w-[400px]">
@@ -9,6 +9,7 @@ export { AddWorkspaceModal, CreateWorkspaceModal } from './AddWorkspaceModal';
 export { AddProjectModal } from './AddProjectModal';
 export { AddProjectToWorktreeModal } from './AddProjectToWorktreeModal';
 export { ArchiveConfirmationModal } from './ArchiveConfirmationModal';
+export { BulkArchiveModal } from './BulkArchiveModal';
 export { WorktreeContextMenu, TerminalTabContextMenu } from './ContextMenus';
 export * from './Icons';
 export {
@@ -20,3 +21,4 @@ export {
 } from './UpdaterDialogs';
 export { ToastProvider, useToast } from './Toast';
 export { GlobalDialogs } from './GlobalDialogs';
+export { DiskQuotaWatcher } from './DiskQuotaWatcher';
@@ -19,5 +19,7 @@ export const TERMINAL = {
   // Terminal resize settings
   RESIZE_TRIGGER_OFFSET: 5,
   RESIZE_DELAY_MS: 50,
+  // Tab path prefix of terminals on a configured SSH host (`ssh://<host>`)
+  SSH_PREFIX: 'ssh://',
 } as const;
 
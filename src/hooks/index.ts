@@ -16,3 +16,9 @@ export { useWorkspaceActions } from './useWorkspaceActions';
 export type { UseWorkspaceActionsReturn } from './useWorkspaceActions';
 export { useMainOccupation } from './useMainOccupation';
 export type { UseMainOccupationReturn } from './useMainOccupation';
+export { useBaseUpdates } from './useBaseUpdates';
+export type { UseBaseUpdatesReturn } from './useBaseUpdates';
+export { useRunningProcesses } from './useRunningProcesses';
+export type { UseRunningProcessesReturn } from './useRunningProcesses';
+export { useCiStatus } from './useCiStatus';
+export type { ProjectCiStatus } from './useCiStatus';
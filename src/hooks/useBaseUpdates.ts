@@ -0,0 +1,78 @@
+import { useState, useEffect, useCallback } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { isTauri, getBaseUpdates, syncWithBaseBranch } from '../lib/backend';
+import type { BaseUpdate } from '../types';
+
+/** Browser mode has no Tauri events; the backend caches results, so polling is cheap */
+const POLL_INTERVAL_MS = 60_000;
+
+export interface UseBaseUpdatesReturn {
+  /** worktree name → projects behind their base branch */
+  baseUpdates: Record<string, BaseUpdate[]>;
+  syncingWorktree: string | null;
+  handleSyncBaseUpdates: (worktreeName: string) => Promise<void>;
+}
+
+function groupByWorktree(updates: BaseUpdate[]): Record<string, BaseUpdate[]> {
+  const grouped: Record<string, BaseUpdate[]> = {};
+  for (const u of updates) {
+    (grouped[u.worktree_name] ??= []).push(u);
+  }
+  return grouped;
+}
+
+export function useBaseUpdates(
+  currentWorkspacePath: string | undefined,
+  onSynced: () => void,
+  onError: (message: string) => void,
+): UseBaseUpdatesReturn {
+  const [baseUpdates, setBaseUpdates] = useState<Record<string, BaseUpdate[]>>({});
+  const [syncingWorktree, setSyncingWorktree] = useState<string | null>(null);
+
+  const refresh = useCallback(async () => {
+    if (!currentWorkspacePath) return;
+    try {
+      setBaseUpdates(groupByWorktree(await getBaseUpdates()));
+    } catch {
+      // ignore
+    }
+  }, [currentWorkspacePath]);
+
+  useEffect(() => {
+    setBaseUpdates({});
+    refresh();
+    if (isTauri()) {
+      const unlisten = listen<{ workspacePath: string; updates: BaseUpdate[] }>('base-update-available', (event) => {
+        if (event.payload.workspacePath === currentWorkspacePath) {
+          setBaseUpdates(groupByWorktree(event.payload.updates));
+        }
+      });
+      return () => { unlisten.then(fn => fn()); };
+    }
+    const interval = setInterval(refresh, POLL_INTERVAL_MS);
+    return () => clearInterval(interval);
+  }, [currentWorkspacePath, refresh]);
+
+  const handleSyncBaseUpdates = useCallback(async (worktreeName: string) => {
+    const projects = baseUpdates[worktreeName] ?? [];
+    if (projects.length === 0) return;
+    setSyncingWorktree(worktreeName);
+    try {
+      for (const p of projects) {
+        await syncWithBaseBranch(p.path, p.base_branch);
+      }
+      setBaseUpdates(prev => {
+        const next = { ...prev };
+        delete next[worktreeName];
+        return next;
+      });
+      onSynced();
+    } catch (e) {
+      onError(String(e));
+    } finally {
+      setSyncingWorktree(null);
+    }
+  }, [baseUpdates, onSynced, onError]);
+
+  return { baseUpdates, syncingWorktree, handleSyncBaseUpdates };
+}
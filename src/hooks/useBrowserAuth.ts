@@ -1,10 +1,12 @@
 import { useState, useEffect, useCallback } from 'react';
-import { isTauri, callBackend, getSessionId, clearSessionId, authenticate } from '../lib/backend';
+import { isTauri, callBackend, getSessionId, clearSessionId, authenticate, wasSessionExpired, clearSessionExpired } from '../lib/backend';
 
 export interface UseBrowserAuthReturn {
   browserAuthenticated: boolean;
   browserLoginPassword: string;
   setBrowserLoginPassword: (value: string) => void;
+  browserLoginTotp: string;
+  setBrowserLoginTotp: (value: string) => void;
   browserLoginError: string | null;
   browserLoggingIn: boolean;
   handleBrowserLogin: () => Promise<void>;
@@ -13,13 +15,17 @@ export interface UseBrowserAuthReturn {
 export function useBrowserAuth(): UseBrowserAuthReturn {
   const [browserAuthenticated, setBrowserAuthenticated] = useState(isTauri());
   const [browserLoginPassword, setBrowserLoginPassword] = useState('');
-  const [browserLoginError, setBrowserLoginError] = useState<string | null>(null);
+  const [browserLoginTotp, setBrowserLoginTotp] = useState('');
+  const [browserLoginError, setBrowserLoginError] = useState<string | null>(
+    () => (!isTauri() && wasSessionExpired() ? '会话已过期，请重新登录' : null)
+  );
   const [browserLoggingIn, setBrowserLoggingIn] = useState(false);
 
   // Validate stored session on startup (avoids re-auth on refresh)
   // Also checks URL #pwd= fragment for auto-authentication via shared link
   useEffect(() => {
     if (isTauri()) return;
+    clearSessionExpired();
 
     // Check URL #pwd= fragment for auto-authentication
     const hash = window.location.hash;
@@ -66,7 +72,7 @@ export function useBrowserAuth(): UseBrowserAuthReturn {
     setBrowserLoggingIn(true);
     setBrowserLoginError(null);
     try {
-      await authenticate(browserLoginPassword.trim());
+      await authenticate(browserLoginPassword.trim(), browserLoginTotp.trim());
       // Full page reload to reset all singletons (WebSocket, etc.) with new session ID
       // Preserve the current pathname (e.g. /t/{subdomain}/) so tunnel proxy paths are kept
       window.location.replace(window.location.pathname || '/');
@@ -74,7 +80,9 @@ export function useBrowserAuth(): UseBrowserAuthReturn {
     } catch (e) {
       const errorMsg = String(e);
       // Provide user-friendly error messages
-      if (errorMsg.includes('密码错误') || errorMsg.includes('Unauthorized') || errorMsg.includes('401')) {
+      if (errorMsg.includes('动态验证码错误')) {
+        setBrowserLoginError('动态验证码错误，请重试');
+      } else if (errorMsg.includes('密码错误') || errorMsg.includes('Unauthorized') || errorMsg.includes('401')) {
         setBrowserLoginError('密码错误，请重试');
       } else if (errorMsg.includes('expired') || errorMsg.includes('过期')) {
         setBrowserLoginError('会话已过期，请重新登录');
@@ -85,12 +93,14 @@ export function useBrowserAuth(): UseBrowserAuthReturn {
       }
       setBrowserLoggingIn(false);
     }
-  }, [browserLoginPassword]);
+  }, [browserLoginPassword, browserLoginTotp]);
 
   return {
     browserAuthenticated,
     browserLoginPassword,
     setBrowserLoginPassword,
+    browserLoginTotp,
+    setBrowserLoginTotp,
     browserLoginError,
     browserLoggingIn,
     handleBrowserLogin,
@@ -0,0 +1,51 @@
+import { useState, useEffect } from 'react';
+import { getCiStatus } from '../lib/backend';
+import type { CiStatus, WorktreeListItem } from '../types';
+
+/** The backend caches per commit (shorter while checks run), so polling is cheap */
+const POLL_INTERVAL_MS = 120_000;
+
+export interface ProjectCiStatus {
+  projectName: string;
+  branch: string;
+  status: CiStatus;
+}
+
+/** worktree name → CI status of each project branch that has checks */
+export function useCiStatus(worktrees: WorktreeListItem[]): Record<string, ProjectCiStatus[]> {
+  const [ciStatus, setCiStatus] = useState<Record<string, ProjectCiStatus[]>>({});
+
+  useEffect(() => {
+    let cancelled = false;
+    const active = worktrees.filter(wt => !wt.is_archived);
+
+    const refresh = async () => {
+      const next: Record<string, ProjectCiStatus[]> = {};
+      // One at a time: each lookup shells out to gh / glab
+      for (const wt of active) {
+        for (const p of wt.projects) {
+          if (!p.current_branch || p.current_branch === 'HEAD') continue;
+          try {
+            const status = await getCiStatus(p.path, p.current_branch);
+            if (status.state !== 'none') {
+              (next[wt.name] ??= []).push({ projectName: p.name, branch: p.current_branch, status });
+            }
+          } catch {
+            // Not pushed, CLI missing or not logged in: no badge
+          }
+          if (cancelled) return;
+        }
+      }
+      setCiStatus(next);
+    };
+
+    refresh();
+    const interval = setInterval(refresh, POLL_INTERVAL_MS);
+    return () => {
+      cancelled = true;
+      clearInterval(interval);
+    };
+  }, [worktrees]);
+
+  return ciStatus;
+}
@@ -1,5 +1,5 @@
 import { useState, useEffect, useCallback } from 'react';
-import { callBackend } from '../lib/backend';
+import { callBackend, confirmDirtyReport, getDirtyReport } from '../lib/backend';
 import type { MainWorkspaceOccupation, DeployToMainResult } from '../types';
 
 export interface UseMainOccupationReturn {
@@ -34,7 +34,9 @@ export function useMainOccupation(
   const deployToMain = useCallback(async (worktreeName: string): Promise<DeployToMainResult | null> => {
     setDeploying(true);
     try {
-      const result = await callBackend('deploy_to_main', { worktreeName }) as DeployToMainResult;
+      const dirtyAck = confirmDirtyReport(await getDirtyReport(worktreeName));
+      if (dirtyAck === null) return null;
+      const result = await callBackend('deploy_to_main', { worktreeName, dirtyAck }) as DeployToMainResult;
       await refreshOccupation();
       return result;
     } finally {
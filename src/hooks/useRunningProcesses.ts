@@ -0,0 +1,72 @@
+import { useState, useEffect, useCallback } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { isTauri, listRunningProcesses } from '../lib/backend';
+import { getWebSocketManager } from '../lib/websocket';
+import type { RunningProcess } from '../types';
+
+/** Fallback for browser mode when no terminal-state subscription delivers pushes */
+const POLL_INTERVAL_MS = 15_000;
+
+interface RunningProcessesPayload {
+  workspacePath: string;
+  worktreeName: string;
+  processes: RunningProcess[];
+}
+
+export interface UseRunningProcessesReturn {
+  /** worktree name → processes running in its terminals */
+  runningProcesses: Record<string, RunningProcess[]>;
+  refreshRunningProcesses: () => Promise<void>;
+}
+
+function groupByWorktree(processes: RunningProcess[]): Record<string, RunningProcess[]> {
+  const grouped: Record<string, RunningProcess[]> = {};
+  for (const p of processes) {
+    (grouped[p.worktree_name] ??= []).push(p);
+  }
+  return grouped;
+}
+
+export function useRunningProcesses(currentWorkspacePath: string | undefined): UseRunningProcessesReturn {
+  const [runningProcesses, setRunningProcesses] = useState<Record<string, RunningProcess[]>>({});
+
+  const refreshRunningProcesses = useCallback(async () => {
+    if (!currentWorkspacePath) return;
+    try {
+      setRunningProcesses(groupByWorktree(await listRunningProcesses()));
+    } catch {
+      // ignore
+    }
+  }, [currentWorkspacePath]);
+
+  useEffect(() => {
+    setRunningProcesses({});
+    refreshRunningProcesses();
+
+    const apply = (payload: RunningProcessesPayload) => {
+      if (payload.workspacePath !== currentWorkspacePath) return;
+      setRunningProcesses(prev => {
+        const next = { ...prev };
+        if (payload.processes.length > 0) {
+          next[payload.worktreeName] = payload.processes;
+        } else {
+          delete next[payload.worktreeName];
+        }
+        return next;
+      });
+    };
+
+    if (isTauri()) {
+      const unlisten = listen<RunningProcessesPayload>('running-processes-update', (event) => apply(event.payload));
+      return () => { unlisten.then(fn => fn()); };
+    }
+    const unsubscribe = getWebSocketManager().onRunningProcesses(apply);
+    const interval = setInterval(refreshRunningProcesses, POLL_INTERVAL_MS);
+    return () => {
+      unsubscribe();
+      clearInterval(interval);
+    };
+  }, [currentWorkspacePath, refreshRunningProcesses]);
+
+  return { runningProcesses, refreshRunningProcesses };
+}
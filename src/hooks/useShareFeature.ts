@@ -2,6 +2,7 @@ import { useState, useEffect, useCallback } from 'react';
 import {
   isTauri,
   startSharing,
+  startSharingWithProfile,
   stopSharing,
   getShareState,
   getLastSharePassword,
@@ -10,19 +11,43 @@ import {
   setNgrokToken,
   startNgrokTunnel,
   stopNgrokTunnel,
+  getCloudflareTunnelConfig,
+  startCloudflareTunnel,
+  stopCloudflareTunnel,
+  getFrpTunnelConfig,
+  startFrpTunnel,
+  stopFrpTunnel,
+  getTailscaleUrls,
+  startTailscaleFunnel,
+  stopTailscaleFunnel,
   setWmsConfig,
   startWmsTunnel,
   stopWmsTunnel,
   wmsManualReconnect,
   getConnectedClients,
   kickClient,
+  quarantineClient,
+  releaseClient,
+  setClientRole,
+  getShareActivity,
+  clearShareActivity,
 } from '../lib/backend';
-import type { ConnectedClient } from '../lib/backend';
+import type { ConnectedClient, ShareActivity, ShareRole, ShareState } from '../lib/backend';
+import { listen } from '@tauri-apps/api/event';
+
+const MAX_ACTIVITY_ITEMS = 50;
+
+const tunnelUrlOf = (state: ShareState, provider: string) =>
+  state.tunnels?.find(t => t.provider === provider)?.url ?? null;
 
 export interface UseShareFeatureReturn {
   shareActive: boolean;
   shareUrls: string[];
   shareNgrokUrl: string | null;
+  shareCloudflareUrl: string | null;
+  shareFrpUrl: string | null;
+  tailscaleUrls: string[];
+  shareFunnelUrl: string | null;
   shareWmsUrl: string | null;
   wmsConnected: boolean;
   wmsReconnecting: boolean;
@@ -30,6 +55,9 @@ export interface UseShareFeatureReturn {
   wmsNextRetrySecs: number;
   sharePassword: string;
   ngrokLoading: boolean;
+  cloudflareLoading: boolean;
+  frpLoading: boolean;
+  funnelLoading: boolean;
   wmsLoading: boolean;
   showNgrokTokenDialog: boolean;
   setShowNgrokTokenDialog: (show: boolean) => void;
@@ -42,19 +70,29 @@ export interface UseShareFeatureReturn {
   setWmsConfigInput: (value: { token: string; subdomain: string }) => void;
   savingWmsConfig: boolean;
   connectedClients: ConnectedClient[];
+  shareActivity: ShareActivity[];
+  handleClearShareActivity: () => Promise<void>;
   hasLastConfig: boolean;
   handleStartShare: (port: number) => Promise<void>;
+  handleStartShareWithProfile: (name: string) => Promise<void>;
   handleStopShare: () => Promise<void>;
   handleToggleNgrok: () => Promise<void>;
+  handleToggleCloudflare: () => Promise<void>;
+  handleToggleFrp: () => Promise<void>;
+  handleToggleFunnel: () => Promise<void>;
   handleToggleWms: () => Promise<void>;
   handleWmsManualReconnect: () => Promise<void>;
   handleUpdateSharePassword: (newPassword: string) => Promise<void>;
   handleSaveNgrokToken: () => Promise<void>;
   handleSaveWmsConfig: () => Promise<void>;
   handleKickClient: (sessionId: string) => Promise<void>;
+  handleToggleQuarantine: (sessionId: string, quarantined: boolean) => Promise<void>;
+  handleSetClientRole: (sessionId: string, role: ShareRole) => Promise<void>;
   handleQuickShare: () => Promise<void>;
   generatePassword: () => string;
   hasNgrokToken: boolean;
+  cloudflareEnabled: boolean;
+  frpEnabled: boolean;
 }
 
 export function useShareFeature(
@@ -65,6 +103,13 @@ export function useShareFeature(
   const [shareNgrokUrl, setShareNgrokUrl] = useState<string | null>(null);
   const [sharePassword, setSharePassword] = useState('');
   const [ngrokLoading, setNgrokLoading] = useState(false);
+  const [shareCloudflareUrl, setShareCloudflareUrl] = useState<string | null>(null);
+  const [cloudflareLoading, setCloudflareLoading] = useState(false);
+  const [shareFrpUrl, setShareFrpUrl] = useState<string | null>(null);
+  const [frpLoading, setFrpLoading] = useState(false);
+  const [tailscaleUrls, setTailscaleUrls] = useState<string[]>([]);
+  const [shareFunnelUrl, setShareFunnelUrl] = useState<string | null>(null);
+  const [funnelLoading, setFunnelLoading] = useState(false);
   const [showNgrokTokenDialog, setShowNgrokTokenDialog] = useState(false);
   const [ngrokTokenInput, setNgrokTokenInput] = useState('');
   const [savingNgrokToken, setSavingNgrokToken] = useState(false);
@@ -78,7 +123,10 @@ export function useShareFeature(
   const [wmsReconnectAttempt, setWmsReconnectAttempt] = useState(0);
   const [wmsNextRetrySecs, setWmsNextRetrySecs] = useState(0);
   const [connectedClients, setConnectedClients] = useState<ConnectedClient[]>([]);
+  const [shareActivity, setShareActivity] = useState<ShareActivity[]>([]);
   const [hasNgrokToken, setHasNgrokToken] = useState(false);
+  const [cloudflareEnabled, setCloudflareEnabled] = useState(false);
+  const [frpEnabled, setFrpEnabled] = useState(false);
 
   const generatePassword = useCallback(() => {
     const chars = 'abcdefghijkmnpqrstuvwxyz23456789';
@@ -99,12 +147,48 @@ export function useShareFeature(
     }
   }, [setError, generatePassword, sharePassword]);
 
+  const handleStartShareWithProfile = useCallback(async (name: string) => {
+    const pwd = sharePassword || generatePassword();
+    let error: unknown = null;
+    try {
+      await startSharingWithProfile(name, pwd);
+    } catch (e) {
+      error = e;
+    }
+    // 隧道启动失败时局域网分享仍处于开启状态，以实际状态为准
+    try {
+      const state = await getShareState();
+      if (state.active) {
+        setShareActive(true);
+        setShareUrls(state.urls);
+        setShareNgrokUrl(state.ngrok_url ?? null);
+        setShareCloudflareUrl(tunnelUrlOf(state, 'cloudflare'));
+        setShareFrpUrl(tunnelUrlOf(state, 'frp'));
+        setShareFunnelUrl(tunnelUrlOf(state, 'tailscale'));
+        setShareWmsUrl(state.wms_url ?? null);
+        setSharePassword(pwd);
+      }
+    } catch {
+      // ignore
+    }
+    if (error) setError(String(error));
+  }, [setError, generatePassword, sharePassword]);
+
   const handleStopShare = useCallback(async () => {
     try {
       // 如果有 ngrok 或 WMS 正在运行，先停止它们
       if (shareNgrokUrl) {
         await stopNgrokTunnel();
       }
+      if (shareCloudflareUrl) {
+        await stopCloudflareTunnel();
+      }
+      if (shareFrpUrl) {
+        await stopFrpTunnel();
+      }
+      if (shareFunnelUrl) {
+        await stopTailscaleFunnel();
+      }
       if (shareWmsUrl) {
         await stopWmsTunnel();
       }
@@ -113,6 +197,9 @@ export function useShareFeature(
       setShareActive(false);
       setShareUrls([]);
       setShareNgrokUrl(null);
+      setShareCloudflareUrl(null);
+      setShareFrpUrl(null);
+      setShareFunnelUrl(null);
       setShareWmsUrl(null);
       setWmsConnected(false);
       setWmsReconnecting(false);
@@ -122,7 +209,7 @@ export function useShareFeature(
     } catch (e) {
       setError(String(e));
     }
-  }, [setError, shareNgrokUrl, shareWmsUrl]);
+  }, [setError, shareNgrokUrl, shareCloudflareUrl, shareFrpUrl, shareFunnelUrl, shareWmsUrl]);
 
   const handleToggleNgrok = useCallback(async () => {
     if (ngrokLoading) return;
@@ -148,6 +235,57 @@ export function useShareFeature(
     }
   }, [setError, shareNgrokUrl, ngrokLoading]);
 
+  const handleToggleCloudflare = useCallback(async () => {
+    if (cloudflareLoading) return;
+    setCloudflareLoading(true);
+    try {
+      if (shareCloudflareUrl) {
+        await stopCloudflareTunnel();
+        setShareCloudflareUrl(null);
+      } else {
+        setShareCloudflareUrl(await startCloudflareTunnel());
+      }
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setCloudflareLoading(false);
+    }
+  }, [setError, shareCloudflareUrl, cloudflareLoading]);
+
+  const handleToggleFrp = useCallback(async () => {
+    if (frpLoading) return;
+    setFrpLoading(true);
+    try {
+      if (shareFrpUrl) {
+        await stopFrpTunnel();
+        setShareFrpUrl(null);
+      } else {
+        setShareFrpUrl(await startFrpTunnel());
+      }
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setFrpLoading(false);
+    }
+  }, [setError, shareFrpUrl, frpLoading]);
+
+  const handleToggleFunnel = useCallback(async () => {
+    if (funnelLoading) return;
+    setFunnelLoading(true);
+    try {
+      if (shareFunnelUrl) {
+        await stopTailscaleFunnel();
+        setShareFunnelUrl(null);
+      } else {
+        setShareFunnelUrl(await startTailscaleFunnel());
+      }
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setFunnelLoading(false);
+    }
+  }, [setError, shareFunnelUrl, funnelLoading]);
+
   const handleUpdateSharePassword = useCallback(async (newPassword: string) => {
     try {
       await updateSharePassword(newPassword);
@@ -167,6 +305,30 @@ export function useShareFeature(
     }
   }, [setError]);
 
+  const handleToggleQuarantine = useCallback(async (sessionId: string, quarantined: boolean) => {
+    try {
+      if (quarantined) {
+        await releaseClient(sessionId);
+      } else {
+        await quarantineClient(sessionId);
+      }
+      const clients = await getConnectedClients();
+      setConnectedClients(clients);
+    } catch (e) {
+      setError(String(e));
+    }
+  }, [setError]);
+
+  const handleSetClientRole = useCallback(async (sessionId: string, role: ShareRole) => {
+    try {
+      await setClientRole(sessionId, role);
+      const clients = await getConnectedClients();
+      setConnectedClients(clients);
+    } catch (e) {
+      setError(String(e));
+    }
+  }, [setError]);
+
   const handleSaveNgrokToken = useCallback(async () => {
     if (!ngrokTokenInput.trim()) return;
     setSavingNgrokToken(true);
@@ -278,6 +440,9 @@ export function useShareFeature(
           if (state.ngrok_url) {
             setShareNgrokUrl(state.ngrok_url);
           }
+          setShareCloudflareUrl(tunnelUrlOf(state, 'cloudflare'));
+          setShareFrpUrl(tunnelUrlOf(state, 'frp'));
+          setShareFunnelUrl(tunnelUrlOf(state, 'tailscale'));
           if (state.wms_url) {
             setShareWmsUrl(state.wms_url);
           }
@@ -293,6 +458,12 @@ export function useShareFeature(
       getNgrokToken().then(token => {
         setHasNgrokToken(!!token);
       }).catch(() => { });
+      getCloudflareTunnelConfig().then(config => {
+        setCloudflareEnabled(config.enabled);
+      }).catch(() => { });
+      getFrpTunnelConfig().then(config => {
+        setFrpEnabled(config.enabled);
+      }).catch(() => { });
     }
   }, []);
 
@@ -325,10 +496,51 @@ export function useShareFeature(
     return () => clearInterval(interval);
   }, [shareActive, shareWmsUrl, wmsReconnecting]);
 
+  // Tailnet addresses of the share server, when Tailscale is running (Tauri only)
+  useEffect(() => {
+    if (!isTauri() || !shareActive) {
+      setTailscaleUrls([]);
+      return;
+    }
+    getTailscaleUrls().then(setTailscaleUrls).catch(() => { });
+  }, [shareActive]);
+
+  // Live feed of remote actions while sharing (Tauri only)
+  useEffect(() => {
+    if (!isTauri() || !shareActive) {
+      setShareActivity([]);
+      return;
+    }
+    let cancelled = false;
+    getShareActivity()
+      .then(items => { if (!cancelled) setShareActivity(items.slice(0, MAX_ACTIVITY_ITEMS)); })
+      .catch(() => { });
+    const unlisten = listen<ShareActivity>('share-activity', (event) => {
+      setShareActivity(prev => [event.payload, ...prev].slice(0, MAX_ACTIVITY_ITEMS));
+    });
+    return () => {
+      cancelled = true;
+      unlisten.then(fn => fn());
+    };
+  }, [shareActive]);
+
+  const handleClearShareActivity = useCallback(async () => {
+    try {
+      await clearShareActivity();
+      setShareActivity([]);
+    } catch (e) {
+      console.error('Failed to clear share activity:', e);
+    }
+  }, []);
+
   return {
     shareActive,
     shareUrls,
     shareNgrokUrl,
+    shareCloudflareUrl,
+    shareFrpUrl,
+    tailscaleUrls,
+    shareFunnelUrl,
     shareWmsUrl,
     wmsConnected,
     wmsReconnecting,
@@ -336,6 +548,9 @@ export function useShareFeature(
     wmsNextRetrySecs,
     sharePassword,
     ngrokLoading,
+    cloudflareLoading,
+    frpLoading,
+    funnelLoading,
     wmsLoading,
     showNgrokTokenDialog,
     setShowNgrokTokenDialog,
@@ -348,18 +563,28 @@ export function useShareFeature(
     setWmsConfigInput,
     savingWmsConfig,
     connectedClients,
+    shareActivity,
+    handleClearShareActivity,
     hasLastConfig,
     handleStartShare,
+    handleStartShareWithProfile,
     handleStopShare,
     handleToggleNgrok,
+    handleToggleCloudflare,
+    handleToggleFrp,
+    handleToggleFunnel,
     handleToggleWms,
     handleWmsManualReconnect,
     handleUpdateSharePassword,
     handleSaveNgrokToken,
     handleSaveWmsConfig,
     handleKickClient,
+    handleToggleQuarantine,
+    handleSetClientRole,
     handleQuickShare,
     generatePassword,
     hasNgrokToken,
+    cloudflareEnabled,
+    frpEnabled,
   };
 }
@@ -2,7 +2,7 @@ import { useState, useEffect, useCallback, useRef } from 'react';
 import i18next from 'i18next';
 import type { TerminalTab, MainWorkspaceStatus, WorktreeListItem } from '../types';
 import { TERMINAL } from '../constants';
-import { callBackend, isTauri, broadcastTerminalState as broadcastTerminalStateBackend, getTerminalState } from '../lib/backend';
+import { callBackend, isTauri, broadcastTerminalState as broadcastTerminalStateBackend, getTerminalState, ptyRestoreSessions } from '../lib/backend';
 import { getWebSocketManager } from '../lib/websocket';
 import { listen } from '@tauri-apps/api/event';
 
@@ -17,7 +17,8 @@ export interface UseTerminalReturn {
   setTerminalVisible: (visible: boolean) => void;
   setTerminalHeight: (height: number) => void;
   setIsResizing: (resizing: boolean) => void;
-  handleTerminalTabClick: (path: string) => void;
+  /** `label` names a tab the backend opened (terminal presets) */
+  handleTerminalTabClick: (path: string, label?: string) => void;
   handleCloseTerminalTab: (path: string) => void;
   handleCloseOtherTerminalTabs: (keepPath: string) => void;
   handleCloseAllTerminalTabs: () => void;
@@ -39,6 +40,11 @@ export function useTerminal(
   // Global set of all ever-activated terminals — controls Terminal component mounting.
   // Only shrinks when a tab is explicitly closed. Survives worktree switches so PTY sessions stay alive.
   const [mountedTerminals, setMountedTerminals] = useState<Set<string>>(new Set());
+  // Custom tab names by terminal path (terminals opened from a preset), shared through the terminal state
+  const [tabLabels, setTabLabels] = useState<Record<string, string>>({});
+  const mergeTabLabels = useCallback((labels: Record<string, string> | undefined) => {
+    if (labels && Object.keys(labels).length > 0) setTabLabels(prev => ({ ...prev, ...labels }));
+  }, []);
 
   // Remember active tab, activated terminals & visibility per workspace root, so switching back restores them
   const activeTabPerWorkspace = useRef<Map<string, string>>(new Map());
@@ -100,20 +106,33 @@ export function useTerminal(
   ] : [];
 
   const duplicatedTabs: TerminalTab[] = Array.from(activatedTerminals)
-    .filter(path => path.includes('#'))
+    .filter(path => path.includes('#') && !path.startsWith(TERMINAL.SSH_PREFIX))
     .map(path => {
       const basePath = path.split('#')[0];
       const baseTab = baseTabs.find(t => t.path === basePath);
       const count = Array.from(activatedTerminals).filter(p => p.startsWith(basePath + '#')).length;
       return {
-        name: baseTab ? `${baseTab.name} (${count + 1})` : path.split('/').pop() || 'Terminal',
+        name: tabLabels[path] ?? (baseTab ? `${baseTab.name} (${count + 1})` : path.split('/').pop() || 'Terminal'),
         path,
         isRoot: false,
         isDuplicate: true
       };
     });
 
-  const terminalTabs = [...baseTabs, ...duplicatedTabs];
+  // Remote terminals (`ssh://<host>`, `ssh://<host>#<n>` for more than one on a host)
+  const sshPaths = Array.from(activatedTerminals).filter(path => path.startsWith(TERMINAL.SSH_PREFIX));
+  const sshTabs: TerminalTab[] = sshPaths.map(path => {
+    const host = path.slice(TERMINAL.SSH_PREFIX.length).split('#')[0];
+    const index = sshPaths.filter(p => p.slice(TERMINAL.SSH_PREFIX.length).split('#')[0] === host).indexOf(path);
+    return {
+      name: index > 0 ? `${host} (${index + 1})` : host,
+      path,
+      isRoot: false,
+      isDuplicate: false,
+    };
+  });
+
+  const terminalTabs = [...baseTabs, ...duplicatedTabs, ...sshTabs];
   const terminalTabsRef = useRef(terminalTabs);
   terminalTabsRef.current = terminalTabs;
 
@@ -194,6 +213,7 @@ export function useTerminal(
       const wsRoot = currentWorkspaceRoot;
       getTerminalState(workspacePath, worktreeName).then((cached) => {
         if (!cached || prevWorkspaceRoot.current !== wsRoot) return;
+        mergeTabLabels(cached.tab_labels);
 
         const cachedActivated = new Set(cached.activated_terminals);
         const localActivated = activatedTerminalsRef.current;
@@ -223,7 +243,27 @@ export function useTerminal(
     }
 
     prevWorkspaceRoot.current = currentWorkspaceRoot;
-  }, [currentWorkspaceRoot, workspacePath, worktreeName]);
+  }, [currentWorkspaceRoot, workspacePath, worktreeName, mergeTabLabels]);
+
+  // Reopen the terminals the previous app run had in this workspace (once per workspace).
+  // The backend also records them in the terminal state cache, so other worktrees pick them up on switch.
+  const restoredWorkspaces = useRef<Set<string>>(new Set());
+  const worktreeNameRef = useRef(worktreeName);
+  worktreeNameRef.current = worktreeName;
+  useEffect(() => {
+    if (!workspacePath || restoredWorkspaces.current.has(workspacePath)) return;
+    restoredWorkspaces.current.add(workspacePath);
+    ptyRestoreSessions().then((restored) => {
+      const paths = restored
+        .filter(t => (t.worktree_name ?? '') === worktreeNameRef.current)
+        .map(t => t.terminal_path)
+        .filter(p => !activatedTerminalsRef.current.has(p));
+      if (paths.length === 0) return;
+      const newActivated = new Set([...activatedTerminalsRef.current, ...paths]);
+      setActivatedTerminals(newActivated);
+      activatedTerminalsRef.current = newActivated;
+    }).catch(() => {});
+  }, [workspacePath]);
 
   // Shared handler for incoming terminal state messages (used by both Tauri and WebSocket)
   const handleTerminalStateMessage = useCallback((msg: {
@@ -233,9 +273,11 @@ export function useTerminal(
     activeTerminalTab: string | null;
     terminalVisible: boolean;
     clientId?: string;
+    tabLabels?: Record<string, string>;
   }) => {
     // Self-echo filtering: ignore messages from this client
     if (msg.clientId && msg.clientId === clientIdRef.current) return;
+    mergeTabLabels(msg.tabLabels);
 
     const currentActivated = activatedTerminalsRef.current;
     const newActivatedTerminals = new Set(msg.activatedTerminals);
@@ -250,7 +292,7 @@ export function useTerminal(
       setActiveTerminalTab(msg.activeTerminalTab);
       setTerminalVisible(msg.terminalVisible);
     }
-  }, []);
+  }, [mergeTabLabels]);
 
   // Terminal state synchronization: both desktop and web subscribe
   useEffect(() => {
@@ -266,6 +308,7 @@ export function useTerminal(
         activeTerminalTab: string | null;
         terminalVisible: boolean;
         clientId?: string;
+        tabLabels?: Record<string, string>;
       }>('terminal-state-update', (event) => {
         if (event.payload.workspacePath && event.payload.worktreeName &&
             (event.payload.workspacePath !== workspacePath || event.payload.worktreeName !== worktreeName)) {
@@ -331,7 +374,8 @@ export function useTerminal(
     };
   }, [isResizing]);
 
-  const handleTerminalTabClick = useCallback((projectPath: string) => {
+  const handleTerminalTabClick = useCallback((projectPath: string, label?: string) => {
+    if (label) setTabLabels(prev => ({ ...prev, [projectPath]: label }));
     // Update state (async)
     if (!terminalVisibleRef.current) setTerminalVisible(true);
     setActiveTerminalTab(projectPath);
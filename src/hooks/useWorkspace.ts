@@ -1,8 +1,11 @@
 import { useState, useEffect, useCallback, useRef } from 'react';
-import { callBackend, isTauri } from '../lib/backend';
+import { listen } from '@tauri-apps/api/event';
+import { callBackend, confirmDirtyReport, getDirtyReport, getWorktreeStatus, isTauri } from '../lib/backend';
+import { getWebSocketManager } from '../lib/websocket';
 import type {
   WorkspaceRef,
   WorkspaceConfig,
+  VersionedWorkspaceConfig,
   WorktreeListItem,
   MainWorkspaceStatus,
   CreateProjectRequest,
@@ -10,8 +13,41 @@ import type {
   EditorType,
   ScannedFolder,
   AddProjectToWorktreeRequest,
+  CreateWorktreeResult,
+  DeleteArchivedResult,
+  HookRun,
+  LinkResult,
+  RestoreWorktreeResult,
+  WorkspaceRefreshResult,
 } from '../types';
 
+/** Webhook deliveries arriving within this window trigger one refresh */
+const GIT_REMOTE_REFRESH_DEBOUNCE_MS = 2000;
+/** Bursts of filesystem changes (a checkout, a bulk archive) trigger one reload */
+const FS_CHANGE_RELOAD_DEBOUNCE_MS = 1000;
+
+/** Summarize failed symlinks so they surface instead of being silently dropped */
+function describeLinkFailures(results: LinkResult[]): string | null {
+  const failed = results.filter((r) => r.status === 'failed');
+  if (failed.length === 0) return null;
+  const lines = failed.map((r) => {
+    const item = r.project ? `${r.project}/${r.item}` : r.item;
+    return r.reason ? `${item}: ${r.reason}` : item;
+  });
+  return `Failed to create ${failed.length} link(s):\n${lines.join('\n')}`;
+}
+
+/** Summarize failed lifecycle hooks with the tail of their output */
+function describeHookFailures(runs: HookRun[]): string | null {
+  const failed = runs.filter((r) => !r.success);
+  if (failed.length === 0) return null;
+  const lines = failed.map((r) => {
+    const where = r.project ? ` (${r.project})` : '';
+    return `${r.event} \`${r.command}\`${where}:\n${r.output}`;
+  });
+  return `${failed.length} hook(s) failed:\n${lines.join('\n')}`;
+}
+
 export interface UseWorkspaceReturn {
   workspaces: WorkspaceRef[];
   currentWorkspace: WorkspaceRef | null;
@@ -23,7 +59,10 @@ export interface UseWorkspaceReturn {
   error: string | null;
   setError: (error: string | null) => void;
   loadWorkspaces: () => Promise<void>;
-  loadData: () => Promise<void>;
+  loadData: (quiet?: boolean) => Promise<void>;
+  /** Server-side cache invalidation + rescan; progress is reported via refreshProgress */
+  refreshWorkspace: () => Promise<void>;
+  refreshProgress: { scanned: number; total: number } | null;
   switchWorkspace: (path: string) => Promise<void>;
   addWorkspace: (name: string, path: string) => Promise<void>;
   createWorkspace: (name: string, path: string) => Promise<void>;
@@ -39,15 +78,17 @@ export interface UseWorkspaceReturn {
   }) => Promise<void>;
   archiveWorktree: (name: string) => Promise<void>;
   restoreWorktree: (name: string) => Promise<void>;
-  deleteArchivedWorktree: (name: string) => Promise<void>;
+  deleteArchivedWorktree: (name: string, deleteRemote?: boolean) => Promise<DeleteArchivedResult>;
   checkWorktreeStatus: (name: string) => Promise<WorktreeArchiveStatus>;
-  openInEditor: (path: string, editor: EditorType) => Promise<void>;
+  /** `file` is relative to `path`; the editor jumps to `line` when given */
+  openInEditor: (path: string, editor: EditorType, file?: string, line?: number) => Promise<void>;
   openInTerminal: (path: string) => Promise<void>;
   revealInFinder: (path: string) => Promise<void>;
   switchBranch: (projectPath: string, branch: string) => Promise<void>;
   saveConfig: (config: WorkspaceConfig) => Promise<void>;
   scanLinkedFolders: (projectPath: string) => Promise<ScannedFolder[]>;
   addProjectToWorktree: (request: AddProjectToWorktreeRequest) => Promise<void>;
+  convertToLink: (worktreeName: string, projectName: string | null, item: string, merge: boolean) => Promise<LinkResult>;
   openWorkspaceInNewWindow: (workspacePath: string) => Promise<void>;
   lockWorktree: (workspacePath: string, worktreeName: string) => Promise<void>;
   unlockWorktree: (workspacePath: string, worktreeName: string) => Promise<void>;
@@ -63,8 +104,11 @@ export function useWorkspace(ready = true): UseWorkspaceReturn {
   const [configPath, setConfigPath] = useState<string>('');
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
+  const [refreshProgress, setRefreshProgress] = useState<{ scanned: number; total: number } | null>(null);
   const initialLoadDone = useRef(false);
   const loadVersion = useRef(0);
+  // 配置版本：保存时回传，其他窗口已修改时由后端合并或报冲突
+  const configEtag = useRef<string | null>(null);
 
   // 初始化时注册窗口 workspace 绑定（从 URL 参数获取）
   useEffect(() => {
@@ -96,14 +140,17 @@ export function useWorkspace(ready = true): UseWorkspaceReturn {
     }
   }, []);
 
-  const loadData = useCallback(async () => {
+  // quiet：后台重新加载（文件系统变化触发），不显示加载遮罩，失败时保留当前数据
+  const loadData = useCallback(async (quiet = false) => {
     const version = ++loadVersion.current;
     const t0 = performance.now();
-    setLoading(true);
-    setError(null);
+    if (!quiet) {
+      setLoading(true);
+      setError(null);
+    }
     try {
       const [cfg, wts, main, path] = await Promise.all([
-        callBackend<WorkspaceConfig>("get_workspace_config"),
+        callBackend<VersionedWorkspaceConfig>("get_workspace_config_versioned"),
         callBackend<WorktreeListItem[]>("list_worktrees", { includeArchived: true }),
         callBackend<MainWorkspaceStatus>("get_main_workspace_status"),
         callBackend<string>("get_config_path_info"),
@@ -113,13 +160,21 @@ export function useWorkspace(ready = true): UseWorkspaceReturn {
         console.log(`[ws] loadData: discarded (stale v${version}, current v${loadVersion.current})`);
         return;
       }
-      setConfig(cfg);
+      // 配置未变时保留原对象，避免设置页中未保存的修改被重置
+      if (!quiet || cfg.etag !== configEtag.current) {
+        setConfig(cfg.config);
+        configEtag.current = cfg.etag;
+      }
       setWorktrees(wts);
       setMainWorkspace(main);
       setConfigPath(path);
       console.log(`[ws] loadData: ${(performance.now() - t0).toFixed(1)}ms (${wts.length} worktrees)`);
     } catch (e) {
       if (version !== loadVersion.current) return;
+      if (quiet) {
+        console.warn('[ws] background reload failed:', e);
+        return;
+      }
       setError(String(e));
     } finally {
       if (version === loadVersion.current) {
@@ -128,6 +183,139 @@ export function useWorkspace(ready = true): UseWorkspaceReturn {
     }
   }, []);
 
+  const refreshWorkspace = useCallback(async () => {
+    const version = ++loadVersion.current;
+    const t0 = performance.now();
+    setRefreshProgress({ scanned: 0, total: 0 });
+    setError(null);
+    try {
+      // refresh_workspace drops the config cache, so read the config after it
+      const refreshed = await callBackend<WorkspaceRefreshResult>("refresh_workspace");
+      const cfg = await callBackend<VersionedWorkspaceConfig>("get_workspace_config_versioned");
+      if (version !== loadVersion.current) return;
+      setConfig(cfg.config);
+      configEtag.current = cfg.etag;
+      setWorktrees(refreshed.worktrees);
+      setMainWorkspace(refreshed.main_workspace);
+      console.log(`[ws] refreshWorkspace: ${(performance.now() - t0).toFixed(1)}ms (server ${refreshed.elapsed_ms}ms)`);
+    } catch (e) {
+      if (version !== loadVersion.current) return;
+      setError(String(e));
+    } finally {
+      setRefreshProgress(null);
+    }
+  }, []);
+
+  useEffect(() => {
+    if (!ready) return;
+    const onProgress = (msg: { scanned: number; total: number }) => {
+      setRefreshProgress(prev => (prev ? { scanned: msg.scanned, total: msg.total } : prev));
+    };
+    if (isTauri()) {
+      const unlisten = listen<{ scanned: number; total: number }>('workspace-refresh-progress', (event) => onProgress(event.payload));
+      return () => { unlisten.then(fn => fn()); };
+    }
+    return getWebSocketManager().onWorkspaceRefreshProgress(onProgress);
+  }, [ready]);
+
+  // webhook 收到队友的 push 后后端已 fetch，刷新当前 workspace 的 worktree 状态（合并短时间内的多次推送）
+  const currentWorkspacePath = currentWorkspace?.path;
+  useEffect(() => {
+    if (!ready || !currentWorkspacePath) return;
+    let timer: ReturnType<typeof setTimeout> | null = null;
+    const onUpdated = (msg: { workspacePath: string }) => {
+      if (msg.workspacePath !== currentWorkspacePath) return;
+      if (timer) clearTimeout(timer);
+      timer = setTimeout(() => {
+        timer = null;
+        refreshWorkspace();
+      }, GIT_REMOTE_REFRESH_DEBOUNCE_MS);
+    };
+    let unsubscribe: () => void;
+    if (isTauri()) {
+      const unlisten = listen<{ workspacePath: string }>('git-remote-updated', (event) => onUpdated(event.payload));
+      unsubscribe = () => { unlisten.then(fn => fn()); };
+    } else {
+      unsubscribe = getWebSocketManager().onGitRemoteUpdated(onUpdated);
+    }
+    return () => {
+      if (timer) clearTimeout(timer);
+      unsubscribe();
+    };
+  }, [ready, currentWorkspacePath, refreshWorkspace]);
+
+  // 后端监听到 worktree / 项目目录增删、配置文件被外部修改时静默重新加载；
+  // 只有主工作区的 HEAD / index 变化时只刷新主工作区状态
+  useEffect(() => {
+    if (!ready || !currentWorkspacePath) return;
+    let timer: ReturnType<typeof setTimeout> | null = null;
+    let mainOnly = true;
+    const onChanged = (msg: { workspacePath: string; changes: string[] }) => {
+      if (msg.workspacePath !== currentWorkspacePath) return;
+      mainOnly = mainOnly && msg.changes.every(c => c === 'git');
+      if (timer) clearTimeout(timer);
+      timer = setTimeout(() => {
+        timer = null;
+        if (mainOnly) {
+          callBackend<MainWorkspaceStatus>("get_main_workspace_status")
+            .then(setMainWorkspace)
+            .catch(e => console.warn('[ws] main workspace status refresh failed:', e));
+        } else {
+          loadData(true);
+        }
+        mainOnly = true;
+      }, FS_CHANGE_RELOAD_DEBOUNCE_MS);
+    };
+    let unsubscribe: () => void;
+    if (isTauri()) {
+      const unlisten = listen<{ workspacePath: string; changes: string[] }>('workspace-fs-changed', (event) => onChanged(event.payload));
+      unsubscribe = () => { unlisten.then(fn => fn()); };
+    } else {
+      unsubscribe = getWebSocketManager().onWorkspaceFsChanged(onChanged);
+    }
+    return () => {
+      if (timer) clearTimeout(timer);
+      unsubscribe();
+    };
+  }, [ready, currentWorkspacePath, loadData]);
+
+  // 单个 worktree 项目的 HEAD / index 变化：只重新扫描该项目并更新对应卡片
+  useEffect(() => {
+    if (!ready || !currentWorkspacePath) return;
+    const timers = new Map<string, ReturnType<typeof setTimeout>>();
+    const onStatusChanged = (msg: { workspacePath: string; worktreeName: string; projectName: string }) => {
+      if (msg.workspacePath !== currentWorkspacePath) return;
+      const key = `${msg.worktreeName}/${msg.projectName}`;
+      const pending = timers.get(key);
+      if (pending) clearTimeout(pending);
+      timers.set(key, setTimeout(async () => {
+        timers.delete(key);
+        try {
+          const [status] = await getWorktreeStatus(msg.worktreeName, msg.projectName);
+          if (!status) return;
+          setWorktrees(prev => prev.map(w => w.name !== msg.worktreeName ? w : {
+            ...w,
+            projects: w.projects.map(p => p.name === status.name ? status : p),
+          }));
+        } catch (e) {
+          console.warn(`[ws] status refresh for ${key} failed:`, e);
+        }
+      }, FS_CHANGE_RELOAD_DEBOUNCE_MS));
+    };
+    let unsubscribe: () => void;
+    if (isTauri()) {
+      const unlisten = listen<{ workspacePath: string; worktreeName: string; projectName: string }>(
+        'worktree-status-changed', (event) => onStatusChanged(event.payload));
+      unsubscribe = () => { unlisten.then(fn => fn()); };
+    } else {
+      unsubscribe = getWebSocketManager().onWorktreeStatusChanged(onStatusChanged);
+    }
+    return () => {
+      timers.forEach(clearTimeout);
+      unsubscribe();
+    };
+  }, [ready, currentWorkspacePath]);
+
   useEffect(() => {
     if (!ready) return;
     if (initialLoadDone.current) return;
@@ -187,8 +375,13 @@ export function useWorkspace(ready = true): UseWorkspaceReturn {
   }, [loadWorkspaces, loadData]);
 
   const createWorktree = useCallback(async (name: string, projects: CreateProjectRequest[]) => {
-    await callBackend("create_worktree", { request: { name, projects } });
+    const result = await callBackend<CreateWorktreeResult>("create_worktree", { request: { name, projects } });
     await loadData();
+    const failures = [
+      describeLinkFailures(result?.link_results ?? []),
+      describeHookFailures(result?.hook_runs ?? []),
+    ].filter(Boolean);
+    if (failures.length > 0) setError(failures.join('\n\n'));
   }, [loadData]);
 
   const cloneProject = useCallback(async (project: {
@@ -210,29 +403,31 @@ export function useWorkspace(ready = true): UseWorkspaceReturn {
 
   const restoreWorktree = useCallback(async (name: string) => {
     try {
-      await callBackend("restore_worktree", { name });
+      const result = await callBackend<RestoreWorktreeResult>("restore_worktree", { name });
       await loadData();
+      const failures = [
+        describeLinkFailures(result?.link_results ?? []),
+        describeHookFailures(result?.hook_runs ?? []),
+      ].filter(Boolean);
+      if (failures.length > 0) setError(failures.join('\n\n'));
     } catch (e) {
       setError(String(e));
     }
   }, [loadData]);
 
-  const deleteArchivedWorktree = useCallback(async (name: string) => {
-    try {
-      await callBackend("delete_archived_worktree", { name });
-      await loadData();
-    } catch (e) {
-      setError(String(e));
-    }
+  const deleteArchivedWorktree = useCallback(async (name: string, deleteRemote = false) => {
+    const result = await callBackend<DeleteArchivedResult>("delete_archived_worktree", { name, deleteRemote });
+    await loadData();
+    return result;
   }, [loadData]);
 
   const checkWorktreeStatus = useCallback(async (name: string): Promise<WorktreeArchiveStatus> => {
     return callBackend<WorktreeArchiveStatus>("check_worktree_status", { name });
   }, []);
 
-  const openInEditor = useCallback(async (path: string, editor: EditorType) => {
+  const openInEditor = useCallback(async (path: string, editor: EditorType, file?: string, line?: number) => {
     try {
-      await callBackend("open_in_editor", { request: { path, editor } });
+      await callBackend("open_in_editor", { request: { path, editor, file: file ?? null, line: line ?? null } });
     } catch (e) {
       setError(String(e));
     }
@@ -256,16 +451,26 @@ export function useWorkspace(ready = true): UseWorkspaceReturn {
 
   const switchBranch = useCallback(async (projectPath: string, branch: string) => {
     try {
-      await callBackend("switch_branch", { request: { project_path: projectPath, branch } });
+      const projectName = projectPath.split(/[\\/]/).filter(Boolean).pop() ?? '';
+      const isMainProject = mainWorkspace?.projects.some(p => p.path === projectPath) ?? false;
+      const dirtyAck = isMainProject
+        ? confirmDirtyReport(await getDirtyReport(undefined, [projectName]))
+        : undefined;
+      if (dirtyAck === null) return;
+      await callBackend("switch_branch", { request: { project_path: projectPath, branch, dirty_ack: dirtyAck } });
       await loadData();
     } catch (e) {
       setError(String(e));
     }
-  }, [loadData]);
+  }, [loadData, mainWorkspace]);
 
   const saveConfig = useCallback(async (newConfig: WorkspaceConfig) => {
-    await callBackend("save_workspace_config", { config: newConfig });
-    setConfig(newConfig);
+    const saved = await callBackend<VersionedWorkspaceConfig>("save_workspace_config", {
+      config: newConfig,
+      baseEtag: configEtag.current,
+    });
+    configEtag.current = saved.etag;
+    setConfig(saved.config);
     await loadData();
   }, [loadData]);
 
@@ -278,6 +483,15 @@ export function useWorkspace(ready = true): UseWorkspaceReturn {
     await loadData();
   }, [loadData]);
 
+  const convertToLink = useCallback(async (
+    worktreeName: string,
+    projectName: string | null,
+    item: string,
+    merge: boolean,
+  ): Promise<LinkResult> => {
+    return callBackend<LinkResult>("convert_to_link", { worktreeName, projectName, item, merge });
+  }, []);
+
   const openWorkspaceInNewWindow = useCallback(async (workspacePath: string) => {
     const result = await callBackend<string>("open_workspace_window", { workspacePath });
     if (!isTauri() && result) {
@@ -309,6 +523,8 @@ export function useWorkspace(ready = true): UseWorkspaceReturn {
     setError,
     loadWorkspaces,
     loadData,
+    refreshWorkspace,
+    refreshProgress,
     switchWorkspace,
     addWorkspace,
     createWorkspace,
@@ -326,6 +542,7 @@ export function useWorkspace(ready = true): UseWorkspaceReturn {
     saveConfig,
     scanLinkedFolders,
     addProjectToWorktree,
+    convertToLink,
     openWorkspaceInNewWindow,
     lockWorktree,
     unlockWorktree,
@@ -1,4 +1,5 @@
-import { useState, useCallback } from 'react';
+import { useState, useCallback, useEffect } from 'react';
+import i18next from 'i18next';
 import type { UseWorkspaceReturn } from './useWorkspace';
 import type { UseModalsReturn } from './useModals';
 // Only need the cleanup function from terminal hook, not the full return type
@@ -10,8 +11,10 @@ import type {
   ArchiveModalState,
   CreateProjectRequest,
   EditorType,
+  EditorConfig,
 } from '../types';
-import { isTauri, getWindowLabel } from '../lib/backend';
+import { isTauri, getWindowLabel, renameWorktreeBranch, getCustomEditors } from '../lib/backend';
+import { EDITORS } from '../constants';
 
 export interface UseWorkspaceActionsReturn {
   // Selected worktree
@@ -77,17 +80,22 @@ export interface UseWorkspaceActionsReturn {
   setArchiveModal: (v: ArchiveModalState | null) => void;
   openArchiveModal: (worktree: WorktreeListItem) => Promise<void>;
   confirmArchiveIssue: (issueKey: string) => void;
+  refreshArchiveStatus: () => Promise<void>;
   allArchiveIssuesConfirmed: boolean;
   handleArchiveWorktree: () => Promise<void>;
   deleteConfirmWorktree: WorktreeListItem | null;
   setDeleteConfirmWorktree: (v: WorktreeListItem | null) => void;
-  handleDeleteArchivedWorktree: () => Promise<void>;
+  handleDeleteArchivedWorktree: (deleteRemote: boolean) => Promise<void>;
   handleRestoreWorktree: () => Promise<void>;
+  renamingWorktree: boolean;
+  handleRenameWorktree: (worktree: WorktreeListItem) => Promise<void>;
 
   // Editor
+  editors: EditorConfig[];
+  refreshEditors: () => void;
   selectedEditor: EditorType;
   setSelectedEditor: (v: EditorType) => void;
-  handleOpenInEditor: (path: string, editor?: EditorType) => void;
+  handleOpenInEditor: (path: string, editor?: EditorType, file?: string, line?: number) => void;
 
   // Other
   handleOpenInNewWindow: (workspacePath: string) => Promise<void>;
@@ -124,6 +132,7 @@ export function useWorkspaceActions(
   const [cloningProject, setCloningProject] = useState(false);
   const [creating, setCreating] = useState(false);
   const [addingProjectToWorktree, setAddingProjectToWorktree] = useState(false);
+  const [renamingWorktree, setRenamingWorktree] = useState(false);
 
   // Create worktree form state
   const [newWorktreeName, setNewWorktreeName] = useState('');
@@ -140,8 +149,24 @@ export function useWorkspaceActions(
   const [archiveModal, setArchiveModal] = useState<ArchiveModalState | null>(null);
   const [deleteConfirmWorktree, setDeleteConfirmWorktree] = useState<WorktreeListItem | null>(null);
 
-  // Editor selection
+  // Editor selection: built-ins plus the custom editors from the global config
   const [selectedEditor, setSelectedEditor] = useState<EditorType>('vscode');
+  const [editors, setEditors] = useState<EditorConfig[]>(EDITORS);
+
+  const refreshEditors = useCallback(() => {
+    if (!isTauri()) return;
+    getCustomEditors().then(custom => {
+      const names = new Set(custom.map(e => e.name));
+      setEditors([
+        ...EDITORS.filter(e => !names.has(e.id)),
+        ...custom.map(e => ({ id: e.name, name: e.name, icon: e.name.charAt(0).toUpperCase() })),
+      ]);
+    }).catch(() => { });
+  }, []);
+
+  useEffect(() => {
+    refreshEditors();
+  }, [refreshEditors]);
 
   // Select worktree with lock handling
   const handleSelectWorktree = useCallback(async (worktree: WorktreeListItem | null) => {
@@ -338,6 +363,16 @@ export function useWorkspaceActions(
     }
   }, [workspace]);
 
+  const refreshArchiveStatus = useCallback(async () => {
+    if (!archiveModal) return;
+    try {
+      const status = await workspace.checkWorktreeStatus(archiveModal.worktree.name);
+      setArchiveModal({ ...archiveModal, status });
+    } catch (e) {
+      workspace.setError(String(e));
+    }
+  }, [workspace, archiveModal]);
+
   const confirmArchiveIssue = useCallback((issueKey: string) => {
     if (!archiveModal) return;
     const newConfirmed = new Set(archiveModal.confirmedIssues);
@@ -378,15 +413,21 @@ export function useWorkspaceActions(
     }
   }, [workspace, archiveModal, selectedWorktree, cleanupTerminalsForPath]);
 
-  const handleDeleteArchivedWorktree = useCallback(async () => {
+  const handleDeleteArchivedWorktree = useCallback(async (deleteRemote: boolean) => {
     if (!deleteConfirmWorktree) return;
     setDeletingArchived(true);
     try {
-      await workspace.deleteArchivedWorktree(deleteConfirmWorktree.name);
+      const result = await workspace.deleteArchivedWorktree(deleteConfirmWorktree.name, deleteRemote);
       if (selectedWorktree?.name === deleteConfirmWorktree.name) {
         setSelectedWorktree(null);
       }
       setDeleteConfirmWorktree(null);
+      const failed = result.projects.filter(p => p.error);
+      if (failed.length > 0) {
+        workspace.setError(i18next.t('app.deleteArchivedPartial', {
+          details: failed.map(p => `${p.project_name}: ${p.error}`).join('\n'),
+        }));
+      }
     } catch (e) {
       workspace.setError(String(e));
     } finally {
@@ -406,9 +447,40 @@ export function useWorkspaceActions(
     }
   }, [workspace, selectedWorktree]);
 
+  const handleRenameWorktree = useCallback(async (worktree: WorktreeListItem) => {
+    const input = window.prompt(i18next.t('sidebar.renamePrompt', { name: worktree.name }), worktree.name);
+    const newName = input?.trim();
+    if (!newName || newName === worktree.name) return;
+    const push = window.confirm(i18next.t('sidebar.renamePushConfirm'));
+    setRenamingWorktree(true);
+    try {
+      if (worktree.path) cleanupTerminalsForPath(worktree.path);
+      const result = await renameWorktreeBranch(worktree.name, newName, push);
+      await workspace.loadData();
+      if (selectedWorktree?.name === worktree.name) {
+        setSelectedWorktree({
+          ...worktree,
+          name: newName,
+          path: worktree.path.replace(/[^/\\]+$/, newName),
+          rel_path: worktree.rel_path.replace(/[^/\\]+$/, newName),
+        });
+      }
+      const failed = result.projects.filter(p => p.error);
+      if (failed.length > 0) {
+        workspace.setError(i18next.t('sidebar.renamePushFailed', {
+          details: failed.map(p => `${p.project_name}: ${p.error}`).join('\n'),
+        }));
+      }
+    } catch (e) {
+      workspace.setError(String(e));
+    } finally {
+      setRenamingWorktree(false);
+    }
+  }, [workspace, selectedWorktree, setSelectedWorktree, cleanupTerminalsForPath]);
+
   // Editor
-  const handleOpenInEditor = useCallback((path: string, editor?: EditorType) => {
-    workspace.openInEditor(path, editor || selectedEditor);
+  const handleOpenInEditor = useCallback((path: string, editor?: EditorType, file?: string, line?: number) => {
+    workspace.openInEditor(path, editor || selectedEditor, file, line);
   }, [workspace, selectedEditor]);
 
   // Other
@@ -509,13 +581,18 @@ export function useWorkspaceActions(
     setArchiveModal,
     openArchiveModal,
     confirmArchiveIssue,
+    refreshArchiveStatus,
     allArchiveIssuesConfirmed,
     handleArchiveWorktree,
     deleteConfirmWorktree,
     setDeleteConfirmWorktree,
     handleDeleteArchivedWorktree,
     handleRestoreWorktree,
+    renamingWorktree,
+    handleRenameWorktree,
 
+    editors,
+    refreshEditors,
     selectedEditor,
     setSelectedEditor,
     handleOpenInEditor,
@@ -8,6 +8,48 @@
  * A session ID is used in browser mode to simulate Tauri's per-window state.
  */
 
+import type {
+  ActionInfo,
+  AppPaths,
+  BaseUpdate,
+  CiStatus,
+  RunningProcess,
+  TerminalRecording,
+  CommandHistoryEntry,
+  RestoredTerminal,
+  PtyLimits,
+  RateLimitConfig,
+  CustomTlsConfig,
+  CloudflareTunnelConfig,
+  FrpTunnelConfig,
+  SshHost,
+  OutputSearchResult,
+  BulkArchiveResult,
+  BundleExportResult,
+  BundleImportResult,
+  ConflictResolution,
+  DirtyReport,
+  DiskUsageReport,
+  FileDiff,
+  ManifestImportResult,
+  MergeConflictState,
+  ProjectSyncResult,
+  QuickCommand,
+  QuickCommandRun,
+  RenameWorktreeResult,
+  RepairWorktreesResult,
+  SyncLinksResult,
+  WorkspaceManifest,
+  WorktreeListItem,
+  WorktreeListQuery,
+  WorktreeMeta,
+  WorktreeIssue,
+  WorktreePorts,
+  KilledProcess,
+  WorktreePage,
+  WorktreeSyncAdvice,
+} from '../types';
+
 // ---------------------------------------------------------------------------
 // Environment detection
 // ---------------------------------------------------------------------------
@@ -46,6 +88,15 @@ export function clearSessionId(): void {
   sessionStorage.removeItem('wm_session_id');
 }
 
+/** Whether the last session was dropped for being idle (the login page then says so). */
+export function wasSessionExpired(): boolean {
+  return sessionStorage.getItem('wm_session_expired') === '1';
+}
+
+export function clearSessionExpired(): void {
+  sessionStorage.removeItem('wm_session_expired');
+}
+
 // ---------------------------------------------------------------------------
 // HTTP base URL (browser mode)
 // ---------------------------------------------------------------------------
@@ -98,12 +149,21 @@ export async function callBackend<T = unknown>(
 
   if (!res.ok) {
     if (res.status === 401) {
+      // The server answers `{"error":"session_expired"}` when an idle session timed out
+      const text = await res.text();
+      if (text.includes('session_expired')) {
+        sessionStorage.setItem('wm_session_expired', '1');
+      }
       clearSessionId();
       window.location.replace(window.location.pathname || '/');
       throw new Error('Session expired');
     }
     const text = await res.text();
-    logError(new Error(text || `HTTP ${res.status}`));
+    const err: Error & { operationId?: string } = new Error(text || `HTTP ${res.status}`);
+    // Git operations name their transcript (see getOperationLog)
+    const operationId = res.headers.get('X-Operation-Id');
+    if (operationId) err.operationId = operationId;
+    logError(err);
   }
 
   // Some commands return empty 204
@@ -168,6 +228,11 @@ export async function getAppVersion(): Promise<string> {
   }
 }
 
+/** Resolved locations of config files, logs and workspace state (for integrations and support). */
+export async function getPaths(): Promise<AppPaths> {
+  return callBackend<AppPaths>('get_paths');
+}
+
 /** Check if this is the "main" window. */
 export async function isMainWindow(): Promise<boolean> {
   if (isTauri()) {
@@ -186,12 +251,27 @@ export interface ShareState {
   active: boolean;
   urls: string[];
   ngrok_url?: string;
+  /** Pluggable tunnel providers (ngrok, cloudflare, tailscale, frp) and their state */
+  tunnels?: { provider: string; url: string | null; status: 'stopped' | 'running' | 'closed' }[];
   wms_url?: string;
   wms_connected: boolean;
   wms_reconnecting: boolean;
   wms_reconnect_attempt: number;
   wms_next_retry_secs: number;
   workspace_path?: string;
+  profile_name?: string | null;
+}
+
+/** viewer: read-only; operator: terminals, git and worktree operations; admin: also workspace config and sharing */
+export type ShareRole = 'viewer' | 'operator' | 'admin';
+
+export interface ShareProfile {
+  name: string;
+  port: number;
+  bind_address: string | null;
+  role: ShareRole;
+  allowed_worktrees: string[];
+  tunnel: 'ngrok' | 'cloudflare' | 'tailscale' | 'frp' | 'wms' | null;
 }
 
 export interface WmsConfig {
@@ -204,6 +284,8 @@ export interface ShareInfo {
   workspace_name: string;
   workspace_path: string;
   current_worktree: string | null;
+  /** Login needs a 6-digit authenticator code besides the password */
+  totp_required?: boolean;
 }
 
 /** Start sharing the current workspace with a password. Returns the share URL. */
@@ -211,6 +293,26 @@ export async function startSharing(port: number, password: string): Promise<stri
   return callBackend<string>('start_sharing', { port, password });
 }
 
+/** List saved share profiles. */
+export async function getShareProfiles(): Promise<ShareProfile[]> {
+  return callBackend<ShareProfile[]>('get_share_profiles');
+}
+
+/** Create or replace (by name) a share profile. */
+export async function saveShareProfile(profile: ShareProfile): Promise<void> {
+  return callBackend<void>('save_share_profile', { profile });
+}
+
+/** Delete a share profile by name. */
+export async function deleteShareProfile(name: string): Promise<void> {
+  return callBackend<void>('delete_share_profile', { name });
+}
+
+/** Start sharing with a saved profile (and its tunnel, if any). Returns the tunnel or LAN URL. */
+export async function startSharingWithProfile(name: string, password: string): Promise<string> {
+  return callBackend<string>('start_sharing_with_profile', { name, password });
+}
+
 /** Start ngrok tunnel for the current sharing session. Returns the ngrok URL. */
 export async function startNgrokTunnel(): Promise<string> {
   return callBackend<string>('start_ngrok_tunnel');
@@ -221,6 +323,41 @@ export async function stopNgrokTunnel(): Promise<void> {
   return callBackend<void>('stop_ngrok_tunnel');
 }
 
+/** Start a Cloudflare tunnel (cloudflared) for the current sharing session. Returns its URL. */
+export async function startCloudflareTunnel(): Promise<string> {
+  return callBackend<string>('start_cloudflare_tunnel');
+}
+
+/** Stop the Cloudflare tunnel (LAN sharing continues). */
+export async function stopCloudflareTunnel(): Promise<void> {
+  return callBackend<void>('stop_cloudflare_tunnel');
+}
+
+/** Start an frp tunnel (frpc to the configured frps) for the current sharing session. Returns its URL. */
+export async function startFrpTunnel(): Promise<string> {
+  return callBackend<string>('start_frp_tunnel');
+}
+
+/** Stop the frp tunnel (LAN sharing continues). */
+export async function stopFrpTunnel(): Promise<void> {
+  return callBackend<void>('stop_frp_tunnel');
+}
+
+/** Tailnet URLs (MagicDNS name, tailnet IPs) of the share server; empty without Tailscale. */
+export async function getTailscaleUrls(): Promise<string[]> {
+  return callBackend<string[]>('get_tailscale_urls');
+}
+
+/** Publish the share server with Tailscale Funnel. Returns the public URL. */
+export async function startTailscaleFunnel(): Promise<string> {
+  return callBackend<string>('start_tailscale_funnel');
+}
+
+/** Turn Tailscale Funnel off (LAN and tailnet sharing continue). */
+export async function stopTailscaleFunnel(): Promise<void> {
+  return callBackend<void>('stop_tailscale_funnel');
+}
+
 /** Stop sharing (shuts down the HTTP server). */
 export async function stopSharing(): Promise<void> {
   return callBackend<void>('stop_sharing');
@@ -296,6 +433,9 @@ export interface ConnectedClient {
   authenticated_at: string;
   last_active: string;
   ws_connected: boolean;
+  /** Read-only: WS stays connected, mutating API calls are rejected */
+  quarantined: boolean;
+  role: ShareRole;
 }
 
 export async function getConnectedClients(): Promise<ConnectedClient[]> {
@@ -306,6 +446,95 @@ export async function kickClient(sessionId: string): Promise<void> {
   return callBackend('kick_client', { sessionId });
 }
 
+export async function quarantineClient(sessionId: string): Promise<void> {
+  return callBackend('quarantine_client', { sessionId });
+}
+
+export async function releaseClient(sessionId: string): Promise<void> {
+  return callBackend('release_client', { sessionId });
+}
+
+export async function setClientRole(sessionId: string, role: ShareRole): Promise<void> {
+  return callBackend('set_client_role', { sessionId, role });
+}
+
+/** Long-lived token for scripts / CI, sent as `Authorization: Bearer <token>` */
+export interface ApiToken {
+  id: string;
+  name: string;
+  role: ShareRole;
+  created_at: string;
+}
+
+export async function listApiTokens(): Promise<ApiToken[]> {
+  return callBackend<ApiToken[]>('list_api_tokens');
+}
+
+/** Returns the plain token; it is not stored and can't be shown again */
+export async function createApiToken(name: string, role: ShareRole): Promise<string> {
+  return callBackend<string>('create_api_token', { name, role });
+}
+
+export async function revokeApiToken(id: string): Promise<void> {
+  return callBackend('revoke_api_token', { id });
+}
+
+/** Desktop only: secret for GitHub / GitLab webhooks to /api/webhook/git, null when disabled */
+export async function getWebhookSecret(): Promise<string | null> {
+  return callBackend<string | null>('get_webhook_secret');
+}
+
+/** Generates a new secret; the previous one stops working */
+export async function rotateWebhookSecret(): Promise<string> {
+  return callBackend<string>('rotate_webhook_secret');
+}
+
+export async function disableWebhook(): Promise<void> {
+  return callBackend('disable_webhook');
+}
+
+export interface TotpEnrollment {
+  secret: string;
+  otpauth_url: string;
+}
+
+export async function getTotpEnabled(): Promise<boolean> {
+  return callBackend<boolean>('get_totp_enabled');
+}
+
+/** New secret for an authenticator app; TOTP turns on after `confirmTotp` */
+export async function enrollTotp(): Promise<TotpEnrollment> {
+  return callBackend<TotpEnrollment>('enroll_totp');
+}
+
+export async function confirmTotp(code: string): Promise<void> {
+  return callBackend('confirm_totp', { code });
+}
+
+export async function disableTotp(): Promise<void> {
+  return callBackend('disable_totp');
+}
+
+export interface ShareActivity {
+  session_id: string;
+  ip: string;
+  /** API command name, e.g. "switch_branch" */
+  command: string;
+  summary: string | null;
+  status: number;
+  duration_ms: number;
+  at: string;
+}
+
+/** Desktop only: recent mutating API calls from remote sessions, newest first. */
+export async function getShareActivity(): Promise<ShareActivity[]> {
+  return callBackend<ShareActivity[]>('get_share_activity');
+}
+
+export async function clearShareActivity(): Promise<void> {
+  return callBackend('clear_share_activity');
+}
+
 /** Browser mode: fetch info about the shared workspace from the HTTP server. */
 export async function getShareInfo(): Promise<ShareInfo> {
   const res = await fetch(`${getApiBase()}/get_share_info`);
@@ -317,7 +546,7 @@ export async function getShareInfo(): Promise<ShareInfo> {
 }
 
 /** Browser mode: authenticate with challenge-response protocol. */
-export async function authenticate(password: string): Promise<void> {
+export async function authenticate(password: string, totp?: string): Promise<void> {
   // Step 1: Request challenge (nonce + salt)
   const challengeRes = await fetch(`${getApiBase()}/auth/challenge`, {
     method: 'POST',
@@ -372,6 +601,7 @@ export async function authenticate(password: string): Promise<void> {
     body: JSON.stringify({
       proof: bytesToHex(new Uint8Array(proofBytes)),
       nonce,
+      totp: totp || undefined,
     }),
   });
 
@@ -412,6 +642,7 @@ export async function getTerminalState(
   active_terminal_tab: string | null;
   terminal_visible: boolean;
   client_id?: string;
+  tab_labels?: Record<string, string>;
 } | null> {
   return callBackend('get_terminal_state', { workspacePath, worktreeName });
 }
@@ -435,6 +666,113 @@ export async function broadcastTerminalState(
   });
 }
 
+export interface FocusState {
+  worktree_name: string | null;
+  project_name: string | null;
+  client_id?: string | null;
+  updated_at: string;
+}
+
+/** Get the presenter's cached focus for a workspace (used for initial follow sync) */
+export async function getFocusState(workspacePath: string): Promise<FocusState | null> {
+  return callBackend('get_focus_state', { workspacePath });
+}
+
+/** Broadcast the focused worktree/project (desktop only, no-op unless focus follow is enabled) */
+export async function broadcastFocus(
+  workspacePath: string,
+  worktreeName: string | null,
+  projectName: string | null,
+  clientId?: string
+): Promise<void> {
+  return callBackend('broadcast_focus', { workspacePath, worktreeName, projectName, clientId });
+}
+
+export async function getFocusFollowEnabled(): Promise<boolean> {
+  return callBackend<boolean>('get_focus_follow_enabled', {});
+}
+
+export async function setFocusFollowEnabled(enabled: boolean): Promise<void> {
+  return callBackend('set_focus_follow_enabled', { enabled });
+}
+
+export async function getScrollbackPersistEnabled(): Promise<boolean> {
+  return callBackend<boolean>('get_scrollback_persist_enabled', {});
+}
+
+export async function setScrollbackPersistEnabled(enabled: boolean): Promise<void> {
+  return callBackend('set_scrollback_persist_enabled', { enabled });
+}
+
+export async function getSessionRestoreEnabled(): Promise<boolean> {
+  return callBackend<boolean>('get_session_restore_enabled', {});
+}
+
+export async function setSessionRestoreEnabled(enabled: boolean): Promise<void> {
+  return callBackend('set_session_restore_enabled', { enabled });
+}
+
+export async function getPtyLimits(): Promise<PtyLimits> {
+  return callBackend<PtyLimits>('get_pty_limits', {});
+}
+
+export async function setPtyLimits(limits: PtyLimits): Promise<void> {
+  return callBackend('set_pty_limits', { limits });
+}
+
+export async function getRateLimitConfig(): Promise<RateLimitConfig> {
+  return callBackend<RateLimitConfig>('get_rate_limit_config', {});
+}
+
+export async function setRateLimitConfig(limits: RateLimitConfig): Promise<void> {
+  return callBackend('set_rate_limit_config', { limits });
+}
+
+export async function getSessionTtlMins(): Promise<number> {
+  return callBackend<number>('get_session_ttl_mins', {});
+}
+
+export async function setSessionTtlMins(mins: number): Promise<void> {
+  return callBackend('set_session_ttl_mins', { mins });
+}
+
+export async function getCustomTls(): Promise<CustomTlsConfig | null> {
+  return callBackend<CustomTlsConfig | null>('get_custom_tls', {});
+}
+
+export async function setCustomTls(customTls: CustomTlsConfig | null): Promise<void> {
+  return callBackend('set_custom_tls', { customTls });
+}
+
+export async function getCloudflareTunnelConfig(): Promise<CloudflareTunnelConfig> {
+  return callBackend<CloudflareTunnelConfig>('get_cloudflare_tunnel_config', {});
+}
+
+export async function setCloudflareTunnelConfig(tunnel: CloudflareTunnelConfig): Promise<void> {
+  return callBackend('set_cloudflare_tunnel_config', { tunnel });
+}
+
+export async function getFrpTunnelConfig(): Promise<FrpTunnelConfig> {
+  return callBackend<FrpTunnelConfig>('get_frp_tunnel_config', {});
+}
+
+export async function setFrpTunnelConfig(tunnel: FrpTunnelConfig): Promise<void> {
+  return callBackend('set_frp_tunnel_config', { tunnel });
+}
+
+export async function getSshHosts(): Promise<SshHost[]> {
+  return callBackend<SshHost[]>('get_ssh_hosts', {});
+}
+
+export async function setSshHosts(hosts: SshHost[]): Promise<void> {
+  return callBackend('set_ssh_hosts', { hosts });
+}
+
+/** Recreate the current workspace's terminals from the previous run (empty when restore is off) */
+export async function ptyRestoreSessions(): Promise<RestoredTerminal[]> {
+  return callBackend<RestoredTerminal[]>('pty_restore_sessions', {});
+}
+
 // ---------------------------------------------------------------------------
 // Git Operations API
 // ---------------------------------------------------------------------------
@@ -445,6 +783,16 @@ export interface BranchDiffStats {
   changed_files: number;
 }
 
+/** Amend the last commit (refused if it is already on origin); returns the new short commit id */
+export async function amendCommit(path: string, newMessage: string, includeStaged: boolean): Promise<string> {
+  return callBackend<string>('amend_commit', { path, newMessage, includeStaged });
+}
+
+/** Rename a worktree together with its branches; optionally push and drop the old remote branch */
+export async function renameWorktreeBranch(worktreeName: string, newName: string, push: boolean): Promise<RenameWorktreeResult> {
+  return callBackend<RenameWorktreeResult>('rename_worktree_branch', { worktreeName, newName, push });
+}
+
 /** Sync with base branch (pull from base branch) */
 export async function syncWithBaseBranch(path: string, baseBranch: string): Promise<string> {
   return callBackend<string>('sync_with_base_branch', { path, baseBranch });
@@ -455,9 +803,32 @@ export async function pushToRemote(path: string): Promise<string> {
   return callBackend<string>('push_to_remote', { path });
 }
 
-/** Merge current branch to test branch */
-export async function mergeToTestBranch(path: string, testBranch: string): Promise<string> {
-  return callBackend<string>('merge_to_test_branch', { path, testBranch });
+/**
+ * Merge current branch to test branch.
+ * With keepConflicts a conflicting merge stays in progress for resolveConflict / continueMerge / abortMerge.
+ */
+export async function mergeToTestBranch(path: string, testBranch: string, keepConflicts?: boolean): Promise<string> {
+  return callBackend<string>('merge_to_test_branch', { path, testBranch, keepConflicts: keepConflicts ?? null });
+}
+
+/** Conflicted files (with base/ours/theirs content) of the merge, rebase or cherry-pick in progress */
+export async function listMergeConflicts(path: string): Promise<MergeConflictState> {
+  return callBackend<MergeConflictState>('list_merge_conflicts', { path });
+}
+
+/** Resolve one conflicted file by taking a side, or stage it as edited */
+export async function resolveConflict(path: string, file: string, resolution: ConflictResolution): Promise<void> {
+  return callBackend<void>('resolve_conflict', { path, file, resolution });
+}
+
+/** Finish the merge once all conflicts are resolved (pushes and restores for merge-to-test) */
+export async function continueMerge(path: string): Promise<string> {
+  return callBackend<string>('continue_merge', { path });
+}
+
+/** Abort the merge in progress and restore the original branch */
+export async function abortMerge(path: string): Promise<string> {
+  return callBackend<string>('abort_merge', { path });
 }
 
 /** Merge current branch to base branch */
@@ -465,6 +836,19 @@ export async function mergeToBaseBranch(path: string, baseBranch: string): Promi
   return callBackend<string>('merge_to_base_branch', { path, baseBranch });
 }
 
+/**
+ * Unified diff of one file. Without baseRef: working-tree changes vs HEAD.
+ * With baseRef (e.g. "origin/uat"): merge base of HEAD and baseRef vs the working tree.
+ */
+export async function getFileDiff(path: string, file: string, baseRef?: string): Promise<FileDiff> {
+  return callBackend<FileDiff>('get_file_diff', { path, file, baseRef: baseRef ?? null });
+}
+
+/** Cherry-pick commits (applied in order) onto the current branch; aborts on conflict */
+export async function cherryPick(path: string, commitShas: string[]): Promise<string> {
+  return callBackend<string>('cherry_pick', { path, commitShas });
+}
+
 /** Get branch diff statistics */
 export async function getBranchDiffStats(path: string, baseBranch: string): Promise<BranchDiffStats> {
   return callBackend<BranchDiffStats>('get_branch_diff_stats', { path, baseBranch });
@@ -480,6 +864,11 @@ export async function createPullRequest(
   return callBackend<string>('create_pull_request', { path, baseBranch, title, body });
 }
 
+/** CI status of a branch head commit on GitHub (gh) or GitLab (glab); cached per commit */
+export async function getCiStatus(path: string, branch: string): Promise<CiStatus> {
+  return callBackend<CiStatus>('get_ci_status', { path, branch });
+}
+
 /** Fetch from remote origin (updates remote-tracking branches) */
 export async function fetchProjectRemote(path: string): Promise<void> {
   return callBackend<void>('fetch_project_remote', { path });
@@ -498,6 +887,373 @@ export async function getRemoteBranches(path: string): Promise<string[]> {
   return callBackend<string[]>('get_remote_branches', { path });
 }
 
+export interface OperationLogEntry {
+  command: string;
+  cwd: string | null;
+  exit_code: number | null;
+  success: boolean;
+  stdout: string;
+  stderr: string;
+  at: string;
+}
+
+export interface OperationLogSummary {
+  id: string;
+  operation: string;
+  started_at: string;
+  finished_at: string | null;
+  command_count: number;
+  failed_commands: number;
+}
+
+export interface OperationLog {
+  id: string;
+  operation: string;
+  started_at: string;
+  finished_at: string | null;
+  failed_commands: number;
+  entries: OperationLogEntry[];
+}
+
+/** List recent git operations (newest first) */
+export async function listOperationLogs(): Promise<OperationLogSummary[]> {
+  return callBackend<OperationLogSummary[]>('list_operation_logs', {});
+}
+
+/** Get the full command transcript of one operation */
+export async function getOperationLog(id: string): Promise<OperationLog> {
+  return callBackend<OperationLog>('get_operation_log', { id });
+}
+
+// ---------------------------------------------------------------------------
+// Sync advisor
+// ---------------------------------------------------------------------------
+
+/** Preview-merge the base branch into every project of a worktree and classify the result */
+export async function getSyncAdvice(worktreeName: string): Promise<WorktreeSyncAdvice> {
+  return callBackend<WorktreeSyncAdvice>('get_sync_advice', { worktreeName });
+}
+
+/** Sync only the projects currently classified as safe_to_sync */
+export async function syncSafeProjects(worktreeName: string): Promise<ProjectSyncResult[]> {
+  return callBackend<ProjectSyncResult[]>('sync_safe_projects', { worktreeName });
+}
+
+/** Sorted, paginated worktree listing; only the returned page is scanned unless sorting by status */
+export async function listWorktreesPage(query: WorktreeListQuery): Promise<WorktreePage> {
+  return callBackend<WorktreePage>('list_worktrees_page', { query });
+}
+
+/** Full status of a single worktree (pairs with a lightweight listing) */
+export async function getWorktree(name: string): Promise<WorktreeListItem> {
+  return callBackend<WorktreeListItem>('get_worktree', { name });
+}
+
+/** Git status of one worktree's projects (or just `project`), for refreshing a single card */
+export async function getWorktreeStatus(name: string, project?: string): Promise<import('../types').ProjectStatus[]> {
+  return callBackend<import('../types').ProjectStatus[]>('get_worktree_status', { name, project });
+}
+
+/** Update a worktree's description and ticket link; created_at is preserved */
+export async function setWorktreeMeta(name: string, description: string, ticketUrl: string): Promise<WorktreeMeta> {
+  return callBackend<WorktreeMeta>('set_worktree_meta', { name, description, ticketUrl });
+}
+
+/** Port range of a worktree (assigned on first use) with overlapping or already-bound ports */
+export async function getWorktreePorts(name: string): Promise<WorktreePorts> {
+  return callBackend<WorktreePorts>('get_worktree_ports', { name });
+}
+
+/** Whether the project has a devcontainer.json, and the state of its container */
+export async function getDevcontainerStatus(path: string): Promise<import('../types').DevcontainerStatus> {
+  return callBackend<import('../types').DevcontainerStatus>('get_devcontainer_status', { path });
+}
+
+/** Start the project's dev container (devcontainer up) and open it in VS Code */
+export async function openInDevcontainer(path: string): Promise<void> {
+  return callBackend('open_in_devcontainer', { path });
+}
+
+/** Compose file and containers of a worktree project's stack */
+export async function composeStatus(name: string, project: string): Promise<import('../types').ComposeStatus> {
+  return callBackend<import('../types').ComposeStatus>('compose_status', { name, project });
+}
+
+/** docker compose up -d, with the worktree's port variables */
+export async function composeUp(name: string, project: string): Promise<import('../types').ComposeStatus> {
+  return callBackend<import('../types').ComposeStatus>('compose_up', { name, project });
+}
+
+export async function composeDown(name: string, project: string): Promise<import('../types').ComposeStatus> {
+  return callBackend<import('../types').ComposeStatus>('compose_down', { name, project });
+}
+
+/** Ticket matched by the workspace's issue_trackers patterns, null when none match */
+export async function getWorktreeIssue(name: string): Promise<WorktreeIssue | null> {
+  return callBackend<WorktreeIssue | null>('get_worktree_issue', { name });
+}
+
+/** Terminate every process working inside a worktree (including external terminals): SIGTERM, then SIGKILL */
+export async function killWorktreeProcesses(path: string): Promise<KilledProcess[]> {
+  return callBackend<KilledProcess[]>('kill_worktree_processes', { path });
+}
+
+/** Replace a worktree's labels */
+export async function setWorktreeLabels(name: string, labels: string[]): Promise<WorktreeMeta> {
+  return callBackend<WorktreeMeta>('set_worktree_labels', { name, labels });
+}
+
+/** Replace the environment variables injected into a worktree's new terminals */
+export async function setWorktreeEnv(name: string, env: Record<string, string>): Promise<WorktreeMeta> {
+  return callBackend<WorktreeMeta>('set_worktree_env', { name, env });
+}
+
+/** Archive several worktrees; ones failing check_worktree_status are left untouched */
+export async function archiveWorktrees(names: string[]): Promise<BulkArchiveResult[]> {
+  return callBackend<BulkArchiveResult[]>('archive_worktrees', { names });
+}
+
+/** Fetch every project in the main workspace concurrently */
+export async function fetchAllProjects(): Promise<ProjectSyncResult[]> {
+  return callBackend<ProjectSyncResult[]>('fetch_all_projects');
+}
+
+/** Snapshot of all worktrees (active and archived) for migrating to another machine */
+export async function exportWorkspaceManifest(): Promise<WorkspaceManifest> {
+  return callBackend<WorkspaceManifest>('export_workspace_manifest');
+}
+
+/** Write per-project git bundles (branch vs base) and a manifest.json into `outputPath` (desktop only) */
+export async function exportWorktreeBundle(name: string, outputPath: string): Promise<BundleExportResult> {
+  return callBackend<BundleExportResult>('export_worktree_bundle', { name, outputPath });
+}
+
+/** Pick a bundle manifest.json and create its worktree from the bundles next to it (desktop only) */
+export async function importWorktreeBundle(): Promise<BundleImportResult | null> {
+  const { open } = await import('@tauri-apps/plugin-dialog');
+  const manifestPath = await open({ multiple: false, filters: [{ name: 'Manifest', extensions: ['json'] }] });
+  if (!manifestPath || typeof manifestPath !== 'string') return null;
+  return callBackend<BundleImportResult>('import_worktree', { manifestPath });
+}
+
+/** Recreate the manifest's active worktrees, preferring their pushed remote branches */
+export async function importWorkspaceManifest(manifest: WorkspaceManifest): Promise<ManifestImportResult[]> {
+  return callBackend<ManifestImportResult[]>('import_workspace_manifest', { manifest });
+}
+
+// ---------------------------------------------------------------------------
+// Command palette
+// ---------------------------------------------------------------------------
+
+/** All registered actions with enablement for the given selection */
+export async function listActions(worktreeName?: string, projectName?: string): Promise<ActionInfo[]> {
+  return callBackend<ActionInfo[]>('list_actions', { worktreeName, projectName });
+}
+
+// ---------------------------------------------------------------------------
+// Base branch updates
+// ---------------------------------------------------------------------------
+
+/** Worktree projects that fell behind origin/<base> past the threshold (last periodic check) */
+export async function getBaseUpdates(): Promise<BaseUpdate[]> {
+  return callBackend<BaseUpdate[]>('get_base_updates');
+}
+
+// ---------------------------------------------------------------------------
+// Running processes
+// ---------------------------------------------------------------------------
+
+/** Processes running in the current workspace's terminals, optionally for one worktree */
+export async function listRunningProcesses(worktreeName?: string): Promise<RunningProcess[]> {
+  return callBackend<RunningProcess[]>('list_running_processes', { worktreeName: worktreeName ?? null });
+}
+
+/** Case-insensitive search of a terminal's retained output, done server-side */
+export async function ptySearchOutput(sessionId: string, pattern: string, maxMatches?: number): Promise<OutputSearchResult> {
+  return callBackend<OutputSearchResult>('pty_search_output', { sessionId, pattern, maxMatches: maxMatches ?? null });
+}
+
+// ---------------------------------------------------------------------------
+// Terminal recordings
+// ---------------------------------------------------------------------------
+
+/** Start recording a terminal session to an asciicast file in its worktree */
+export async function ptyStartRecording(sessionId: string, title?: string): Promise<TerminalRecording> {
+  return callBackend<TerminalRecording>('pty_start_recording', { sessionId, title: title ?? null });
+}
+
+export async function ptyStopRecording(sessionId: string): Promise<TerminalRecording> {
+  return callBackend<TerminalRecording>('pty_stop_recording', { sessionId });
+}
+
+/** The session's active recording, null when it isn't being recorded */
+export async function ptyGetRecording(sessionId: string): Promise<TerminalRecording | null> {
+  return callBackend<TerminalRecording | null>('pty_get_recording', { sessionId });
+}
+
+/** Recordings of a worktree (main workspace when omitted), newest first */
+export async function listRecordings(worktreeName?: string): Promise<TerminalRecording[]> {
+  return callBackend<TerminalRecording[]>('list_recordings', { worktreeName: worktreeName ?? null });
+}
+
+/** Raw .cast content of a recording */
+export async function readRecording(worktreeName: string | null, fileName: string): Promise<string> {
+  return callBackend<string>('read_recording', { worktreeName, fileName });
+}
+
+/** Commands entered in a worktree's terminals (main workspace when omitted), fuzzy-filtered by query */
+export async function getCommandHistory(worktreeName?: string | null, query?: string, limit?: number): Promise<CommandHistoryEntry[]> {
+  return callBackend<CommandHistoryEntry[]>('get_command_history', {
+    worktreeName: worktreeName ?? null,
+    query: query || null,
+    limit: limit ?? null,
+  });
+}
+
+// ---------------------------------------------------------------------------
+// Disk usage
+// ---------------------------------------------------------------------------
+
+/** Re-checkout files whose only change is line endings; returns how many were fixed */
+export async function normalizeLineEndings(path: string): Promise<number> {
+  return callBackend<number>('normalize_line_endings', { path });
+}
+
+/** Revert tracked changes to HEAD (empty `files` = all); with dryRun only lists what would be reverted */
+export async function discardChanges(path: string, files: string[], dryRun = false): Promise<string[]> {
+  return callBackend<string[]>('discard_changes', { path, files, dryRun });
+}
+
+/** Remove untracked files (empty `paths` = whole project); with dryRun only lists what would be removed */
+export async function cleanUntracked(path: string, paths: string[], dryRun = false): Promise<string[]> {
+  return callBackend<string[]>('clean_untracked', { path, paths, dryRun });
+}
+
+/** Workspace and project quick commands; over HTTP only those with allow_remote */
+export async function listQuickCommands(): Promise<QuickCommand[]> {
+  return callBackend<QuickCommand[]>('list_quick_commands');
+}
+
+/** Run a configured quick command in the worktree's terminal (creating the PTY if needed) */
+export async function runQuickCommand(worktreeName: string, label: string, newSession = false, project?: string | null): Promise<QuickCommandRun> {
+  return callBackend<QuickCommandRun>('run_quick_command', { worktreeName, label, project: project ?? null, newSession });
+}
+
+/** Run a project's named task in a new terminal inside the worktree's project directory */
+export async function openTerminalPreset(worktreeName: string, project: string, preset: string): Promise<QuickCommandRun> {
+  return callBackend<QuickCommandRun>('open_terminal_preset', { worktreeName, project, preset });
+}
+
+export async function runTask(worktreeName: string, project: string, task: string): Promise<QuickCommandRun> {
+  return callBackend<QuickCommandRun>('run_task', { worktreeName, project, task });
+}
+
+/** Create linked items that are missing in existing worktrees (e.g. folders that appeared after an install) */
+export async function syncLinks(): Promise<SyncLinksResult[]> {
+  return callBackend<SyncLinksResult[]>('sync_links');
+}
+
+/** Prune stale worktree registrations and re-register worktree directories the main repos lost track of */
+export async function repairWorktrees(): Promise<RepairWorktreesResult[]> {
+  return callBackend<RepairWorktreesResult[]>('repair_worktrees');
+}
+
+/** Per-worktree disk usage; also triggers the quota warning when over the limit */
+export async function getDiskUsage(): Promise<DiskUsageReport> {
+  return callBackend<DiskUsageReport>('get_disk_usage');
+}
+
+// ---------------------------------------------------------------------------
+// Dirty-state guard
+// ---------------------------------------------------------------------------
+
+/** Consolidated uncommitted-changes report for main workspace projects */
+export async function getDirtyReport(
+  worktreeName?: string,
+  projectNames?: string[],
+): Promise<DirtyReport> {
+  return callBackend<DirtyReport>('get_dirty_report', { worktreeName, projectNames });
+}
+
+/**
+ * Ask the user to acknowledge a dirty report.
+ * Returns the fingerprint to pass as the ack, undefined when clean, or null when declined.
+ */
+export function confirmDirtyReport(report: DirtyReport): string | undefined | null {
+  if (report.projects.length === 0) return undefined;
+  const lines = report.projects.map(p =>
+    `- ${p.project_name}: ${p.staged_count} staged, ${p.modified_count} modified, ${p.untracked_count} untracked`,
+  );
+  const ok = window.confirm(`以下主工作区项目存在未提交的修改，确定继续吗？\n\n${lines.join('\n')}`);
+  return ok ? report.fingerprint : null;
+}
+
+// ---------------------------------------------------------------------------
+// Diff export
+// ---------------------------------------------------------------------------
+
+export interface DiffArchiveSummary {
+  file_name: string;
+  base_ref: string;
+  file_count: number;
+  binary_count: number;
+  deleted_files: string[];
+  size_bytes: number;
+}
+
+/**
+ * Export the files changed versus the base branch as a zip.
+ * Desktop: asks for a save location and writes the file there.
+ * Browser: downloads the zip directly (returns null, the summary isn't available).
+ */
+export async function exportDiffZip(path: string, baseBranch: string): Promise<DiffArchiveSummary | null> {
+  if (isTauri()) {
+    const { save } = await import('@tauri-apps/plugin-dialog');
+    const outputPath = await save({ filters: [{ name: 'Zip', extensions: ['zip'] }] });
+    if (!outputPath) return null;
+    return callBackend<DiffArchiveSummary>('export_diff_zip', { path, baseBranch, outputPath });
+  }
+
+  const res = await fetch(`${getApiBase()}/export_diff_zip`, {
+    method: 'POST',
+    headers: {
+      'Content-Type': 'application/json',
+      'X-Session-Id': getSessionId(),
+    },
+    body: JSON.stringify({ path, baseBranch }),
+  });
+  if (!res.ok) {
+    throw new Error((await res.text()) || `HTTP ${res.status}`);
+  }
+  const disposition = res.headers.get('content-disposition') || '';
+  const fileName = disposition.match(/filename="([^"]+)"/)?.[1] || 'diff.zip';
+  const url = URL.createObjectURL(await res.blob());
+  const a = document.createElement('a');
+  a.href = url;
+  a.download = fileName;
+  a.click();
+  URL.revokeObjectURL(url);
+  return null;
+}
+
+/** Download a folder or file of the shared workspace as a zip (browser mode only). */
+export async function downloadPath(path: string): Promise<void> {
+  const res = await fetch(`${getApiBase()}/download?path=${encodeURIComponent(path)}`, {
+    headers: { 'X-Session-Id': getSessionId() },
+  });
+  if (!res.ok) {
+    throw new Error((await res.text()) || `HTTP ${res.status}`);
+  }
+  const disposition = res.headers.get('content-disposition') || '';
+  const fileName = disposition.match(/filename="([^"]+)"/)?.[1] || 'download.zip';
+  const url = URL.createObjectURL(await res.blob());
+  const a = document.createElement('a');
+  a.href = url;
+  a.download = fileName;
+  a.click();
+  URL.revokeObjectURL(url);
+}
+
 // ---------------------------------------------------------------------------
 // Voice Recognition API (Dashscope)
 // ---------------------------------------------------------------------------
@@ -554,6 +1310,61 @@ export async function loadWorkspaceConfigByPath(path: string): Promise<import('.
   return callBackend<import('../types').WorkspaceConfig>('load_workspace_config_by_path', { path });
 }
 
+/** Desktop only: proxy + custom CA applied to git, ngrok and outbound HTTP */
+export async function getProxyConfig(): Promise<import('../types').ProxyConfig> {
+  return callBackend<import('../types').ProxyConfig>('get_proxy_config');
+}
+
+export async function setProxyConfig(config: import('../types').ProxyConfig): Promise<void> {
+  return callBackend('set_proxy_config', { config });
+}
+
+/** Desktop only: encrypt tokens, passwords and env values in config files (key in the OS keychain) */
+export async function getConfigEncryptionEnabled(): Promise<boolean> {
+  return callBackend<boolean>('get_config_encryption_enabled', {});
+}
+
+export async function setConfigEncryptionEnabled(enabled: boolean): Promise<void> {
+  return callBackend('set_config_encryption_enabled', { enabled });
+}
+
+/** Desktop only: Jira / Linear credentials used by getWorktreeIssue */
+export async function getIssueTrackerAuth(): Promise<import('../types').IssueTrackerAuth> {
+  return callBackend<import('../types').IssueTrackerAuth>('get_issue_tracker_auth', {});
+}
+
+export async function setIssueTrackerAuth(auth: import('../types').IssueTrackerAuth): Promise<void> {
+  return callBackend('set_issue_tracker_auth', { auth });
+}
+
+/** Desktop only: editors offered next to the built-in ones */
+export async function getCustomEditors(): Promise<import('../types').CustomEditor[]> {
+  return callBackend<import('../types').CustomEditor[]>('get_custom_editors', {});
+}
+
+export async function setCustomEditors(editors: import('../types').CustomEditor[]): Promise<void> {
+  return callBackend('set_custom_editors', { editors });
+}
+
+/** Desktop only: Slack / Discord channels notified of merges to test, archives and sharing */
+export async function getNotifiers(): Promise<import('../types').NotifierConfig[]> {
+  return callBackend<import('../types').NotifierConfig[]>('get_notifiers', {});
+}
+
+export async function setNotifiers(notifiers: import('../types').NotifierConfig[]): Promise<void> {
+  return callBackend('set_notifiers', { notifiers });
+}
+
+/** First-run environment check: git, gh CLI, credential helper, symlinks and disk space */
+export async function checkEnvironment(path?: string): Promise<import('../types').EnvironmentReport> {
+  return callBackend<import('../types').EnvironmentReport>('check_environment', { path: path ?? null });
+}
+
+/** Non-blocking checks on the current workspace config (missing project dirs, base branch not on origin) */
+export async function validateWorkspaceConfig(): Promise<import('../types').ConfigWarning[]> {
+  return callBackend<import('../types').ConfigWarning[]>('validate_workspace_config');
+}
+
 export async function saveWorkspaceConfigByPath(path: string, config: import('../types').WorkspaceConfig): Promise<void> {
   return callBackend<void>('save_workspace_config_by_path', { path, config });
 }
@@ -4,3 +4,39 @@ import { twMerge } from "tailwind-merge";
 export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs));
 }
+
+const LABEL_COLORS = [
+  'bg-sky-900/40 text-sky-300 border-sky-700/50',
+  'bg-emerald-900/40 text-emerald-300 border-emerald-700/50',
+  'bg-amber-900/40 text-amber-300 border-amber-700/50',
+  'bg-rose-900/40 text-rose-300 border-rose-700/50',
+  'bg-violet-900/40 text-violet-300 border-violet-700/50',
+  'bg-teal-900/40 text-teal-300 border-teal-700/50',
+  'bg-orange-900/40 text-orange-300 border-orange-700/50',
+  'bg-fuchsia-900/40 text-fuchsia-300 border-fuchsia-700/50',
+];
+
+/** Stable color classes for a worktree label, so the same label looks the same everywhere */
+export function labelColorClass(label: string): string {
+  let hash = 0;
+  for (const ch of label) hash = (hash * 31 + ch.charCodeAt(0)) | 0;
+  return LABEL_COLORS[Math.abs(hash) % LABEL_COLORS.length];
+}
+
+/** Environment variables as `KEY=value` lines */
+export function formatEnv(env?: Record<string, string>): string {
+  return Object.entries(env ?? {}).map(([key, value]) => `${key}=${value}`).join('\n');
+}
+
+/** Parse `KEY=value` lines; blank lines and `#` comments are skipped, the value keeps everything after the first `=` */
+export function parseEnv(text: string): Record<string, string> {
+  const env: Record<string, string> = {};
+  for (const line of text.split('\n')) {
+    const trimmed = line.trim();
+    if (!trimmed || trimmed.startsWith('#')) continue;
+    const eq = trimmed.indexOf('=');
+    if (eq === -1) env[trimmed] = '';
+    else env[trimmed.slice(0, eq).trim()] = trimmed.slice(eq + 1);
+  }
+  return env;
+}
@@ -2,10 +2,14 @@
  * WebSocket manager for browser mode.
  *
  * Multiplexes PTY output and lock state updates over a single WebSocket
- * connection to the Axum server's /ws endpoint.
+ * connection to the Axum server's /ws endpoint. When WebSockets never get
+ * through (e.g. killed by a corporate proxy), falls back to server-sent events
+ * from /api/events for locks, terminal state and PTY output, with terminal
+ * input posted to /api/pty_write.
  */
 
-import { getSessionId } from './backend';
+import { getSessionId, callBackend } from './backend';
+import type { RunningProcess } from '../types';
 
 type PtyCallback = (data: string) => void;
 type LockCallback = (locks: Record<string, string>) => void;
@@ -16,9 +20,56 @@ type TerminalStateCallback = (msg: {
   activeTerminalTab: string | null;
   terminalVisible: boolean;
   clientId?: string;
+  tabLabels?: Record<string, string>;
+}) => void;
+type FocusCallback = (msg: {
+  workspacePath: string;
+  worktreeName: string | null;
+  projectName: string | null;
+  clientId?: string | null;
+  updatedAt: string;
 }) => void;
 type VoiceEventCallback = (event: string, payload: Record<string, unknown>) => void;
+type WorktreeChangesCallback = (msg: {
+  worktreeName: string;
+  changes: { path: string; kind: 'created' | 'modified' | 'deleted' }[];
+  truncated: boolean;
+}) => void;
 type KickedCallback = (reason: string) => void;
+type WorktreeQueueCallback = (msg: {
+  worktreeName: string;
+  projectName: string;
+  ahead: number;
+}) => void;
+type WorkspaceRefreshProgressCallback = (msg: {
+  workspacePath: string;
+  scanned: number;
+  total: number;
+}) => void;
+type RunningProcessesCallback = (msg: {
+  workspacePath: string;
+  worktreeName: string;
+  started: RunningProcess[];
+  stopped: number[];
+  processes: RunningProcess[];
+}) => void;
+type GitRemoteUpdatedCallback = (msg: {
+  workspacePath: string;
+  projectName: string;
+  branch: string | null;
+  event: string;
+}) => void;
+type WorkspaceFsChangedCallback = (msg: {
+  workspacePath: string;
+  /** Which parts changed on disk: "worktrees" | "projects" | "config" | "git" */
+  changes: string[];
+}) => void;
+type WorktreeStatusChangedCallback = (msg: {
+  workspacePath: string;
+  worktreeName: string;
+  projectName: string;
+}) => void;
+type AppLogCallback = (msg: { tail?: string[]; line?: string; error?: string }) => void;
 type ConnectionStateCallback = (connected: boolean) => void;
 
 class WebSocketManager {
@@ -29,21 +80,43 @@ class WebSocketManager {
   private maxReconnectDelay = 10000;
   private sessionId: string | null = null;
 
+  // Server-sent events fallback
+  private static readonly maxWsFailures = 3;
+  private everConnected = false;
+  private wsFailures = 0;
+  private useEventStream = false;
+  private eventSource: EventSource | null = null;
+  private eventStreamTimer: ReturnType<typeof setTimeout> | null = null;
+  private replayPtySessions = new Set<string>();
+  private ptyWriteQueue: Promise<unknown> = Promise.resolve();
+
   // Callback registries
   private ptyCallbacks = new Map<string, PtyCallback>();
   private lockCallback: LockCallback | null = null;
   private terminalStateCallbacks: TerminalStateCallback[] = [];
   private voiceEventCallbacks: VoiceEventCallback[] = [];
+  private focusCallbacks: FocusCallback[] = [];
+  private worktreeChangesCallbacks: WorktreeChangesCallback[] = [];
+  private pendingWorktreeChangesSubscription: string | null = null;
   private kickedCallbacks: KickedCallback[] = [];
+  private worktreeQueueCallbacks: WorktreeQueueCallback[] = [];
+  private workspaceRefreshProgressCallbacks: WorkspaceRefreshProgressCallback[] = [];
+  private runningProcessesCallbacks: RunningProcessesCallback[] = [];
+  private appLogCallbacks: AppLogCallback[] = [];
+  private gitRemoteUpdatedCallbacks: GitRemoteUpdatedCallback[] = [];
+  private workspaceFsChangedCallbacks: WorkspaceFsChangedCallback[] = [];
+  private worktreeStatusChangedCallbacks: WorktreeStatusChangedCallback[] = [];
   private connectionStateCallbacks: ConnectionStateCallback[] = [];
 
   // Pending subscriptions to send after reconnect
   private pendingPtySubscriptions = new Set<string>();
   private pendingLockSubscription: string | null = null;
+  private pendingTerminalStateSubscription: { workspacePath: string; worktreeName: string } | null = null;
   private pendingVoiceSubscription = false;
+  private pendingAppLogLines: number | null = null;
 
   connect(sessionId: string) {
-    if (this.ws && this.connected) return;
+    if ((this.ws || this.eventSource) && this.connected) return;
     this.sessionId = sessionId;
     this.doConnect();
   }
@@ -70,6 +143,8 @@ class WebSocketManager {
 
     this.ws.onopen = () => {
       this.connected = true;
+      this.everConnected = true;
+      this.wsFailures = 0;
       this.reconnectDelay = 1000;
       this.notifyConnectionState(true);
       console.log('[ws] connected, re-subscribing', this.pendingPtySubscriptions.size, 'PTY sessions');
@@ -84,6 +159,12 @@ class WebSocketManager {
       if (this.pendingVoiceSubscription) {
         this.sendJson({ type: 'subscribe_voice_events' });
       }
+      if (this.pendingWorktreeChangesSubscription) {
+        this.sendJson({ type: 'subscribe_worktree_changes', worktreeName: this.pendingWorktreeChangesSubscription });
+      }
+      if (this.pendingAppLogLines !== null) {
+        this.sendJson({ type: 'subscribe_app_logs', lines: this.pendingAppLogLines });
+      }
     };
 
     this.ws.onmessage = (event) => {
@@ -113,6 +194,15 @@ class WebSocketManager {
         return;
       }
 
+      if (!this.everConnected && ++this.wsFailures >= WebSocketManager.maxWsFailures) {
+        console.warn('[ws] WebSocket unavailable, falling back to server-sent events');
+        this.useEventStream = true;
+        // Nothing was streamed yet, so every open terminal needs its replay buffer
+        this.replayPtySessions = new Set(this.pendingPtySubscriptions);
+        this.openEventStream();
+        return;
+      }
+
       this.scheduleReconnect();
     };
 
@@ -148,6 +238,20 @@ class WebSocketManager {
         }
         break;
       }
+      case 'focus_update': {
+        for (const cb of this.focusCallbacks) {
+          cb(msg);
+        }
+        break;
+      }
+      case 'worktree_changes': {
+        if (msg.worktreeName === this.pendingWorktreeChangesSubscription) {
+          for (const cb of this.worktreeChangesCallbacks) {
+            cb(msg);
+          }
+        }
+        break;
+      }
       case 'voice_event': {
         if (msg.event) {
           for (const cb of this.voiceEventCallbacks) {
@@ -156,6 +260,53 @@ class WebSocketManager {
         }
         break;
       }
+      case 'worktree_queue': {
+        for (const cb of this.worktreeQueueCallbacks) {
+          cb(msg);
+        }
+        break;
+      }
+      case 'workspace_refresh_progress': {
+        for (const cb of this.workspaceRefreshProgressCallbacks) {
+          cb(msg);
+        }
+        break;
+      }
+      case 'running_processes': {
+        for (const cb of this.runningProcessesCallbacks) {
+          cb(msg);
+        }
+        break;
+      }
+      case 'git_remote_updated': {
+        for (const cb of this.gitRemoteUpdatedCallbacks) {
+          cb(msg);
+        }
+        break;
+      }
+      case 'workspace_fs_changed': {
+        for (const cb of this.workspaceFsChangedCallbacks) {
+          cb(msg);
+        }
+        break;
+      }
+      case 'worktree_status_changed': {
+        for (const cb of this.worktreeStatusChangedCallbacks) {
+          cb(msg);
+        }
+        break;
+      }
+      case 'app_log_tail':
+      case 'app_log_line':
+      case 'app_log_error': {
+        const entry = msg.type === 'app_log_tail'
+          ? { tail: msg.lines || [] }
+          : msg.type === 'app_log_line' ? { line: String(msg.line ?? '') } : { error: msg.error };
+        for (const cb of this.appLogCallbacks) {
+          cb(entry);
+        }
+        break;
+      }
       case 'kicked': {
         const reason = msg.reason || '';
         for (const cb of this.kickedCallbacks) {
@@ -163,20 +314,97 @@ class WebSocketManager {
         }
         break;
       }
+      case 'session_expired': {
+        // The server dropped this idle session and closes the connection; back to the login page
+        import('./backend').then(({ clearSessionId }) => {
+          sessionStorage.setItem('wm_session_expired', '1');
+          clearSessionId();
+          window.location.reload();
+        });
+        break;
+      }
     }
   }
 
   private sendJson(obj: Record<string, unknown>) {
+    if (this.useEventStream) {
+      // Subscriptions are part of the stream URL; only terminal input has an HTTP equivalent.
+      // Writes are chained so keystrokes arrive in order.
+      if (obj.type === 'pty_write') {
+        this.ptyWriteQueue = this.ptyWriteQueue
+          .then(() => callBackend('pty_write', { sessionId: obj.sessionId, data: obj.data }))
+          .catch((e) => console.error('[sse] pty_write failed', e));
+      }
+      return;
+    }
     if (this.ws && this.connected) {
       this.ws.send(JSON.stringify(obj));
     }
   }
 
+  /**
+   * (Re)open the event stream for the current subscriptions. The server fixes
+   * subscriptions per stream, so any change reopens it.
+   */
+  private openEventStream() {
+    if (this.eventSource) {
+      this.eventSource.close();
+      this.eventSource = null;
+    }
+    if (!this.sessionId) return;
+
+    const params = new URLSearchParams({ session_id: this.sessionId });
+    const terminalState = this.pendingTerminalStateSubscription;
+    const workspacePath = terminalState?.workspacePath ?? this.pendingLockSubscription;
+    if (workspacePath) params.set('workspace_path', workspacePath);
+    if (terminalState) params.set('worktree_name', terminalState.worktreeName);
+    if (this.pendingPtySubscriptions.size > 0) params.set('pty', [...this.pendingPtySubscriptions].join(','));
+    if (this.replayPtySessions.size > 0) params.set('replay', [...this.replayPtySessions].join(','));
+    if (this.pendingAppLogLines !== null) params.set('logs', String(this.pendingAppLogLines));
+    this.replayPtySessions.clear();
+
+    const tunnelMatch = location.pathname.match(/^(\/t\/[^/]+)/);
+    const basePath = tunnelMatch ? tunnelMatch[1] : '';
+    const eventSource = new EventSource(`${basePath}/api/events?${params}`);
+    eventSource.onopen = () => {
+      this.connected = true;
+      this.notifyConnectionState(true);
+    };
+    eventSource.onmessage = (event) => {
+      try {
+        this.handleMessage(JSON.parse(event.data));
+      } catch {
+        // Ignore malformed messages
+      }
+    };
+    // EventSource reconnects on its own
+    eventSource.onerror = () => {
+      if (this.connected) {
+        this.connected = false;
+        this.notifyConnectionState(false);
+      }
+    };
+    this.eventSource = eventSource;
+  }
+
+  /** Reopen the event stream once a burst of subscription changes settles. */
+  private refreshEventStream() {
+    if (!this.useEventStream) return;
+    if (this.eventStreamTimer) clearTimeout(this.eventStreamTimer);
+    this.eventStreamTimer = setTimeout(() => {
+      this.eventStreamTimer = null;
+      this.openEventStream();
+    }, 50);
+  }
+
   private hasActiveSubscriptions(): boolean {
     return this.ptyCallbacks.size > 0
       || !!this.lockCallback
       || this.terminalStateCallbacks.length > 0
-      || this.voiceEventCallbacks.length > 0;
+      || this.voiceEventCallbacks.length > 0
+      || this.focusCallbacks.length > 0
+      || this.worktreeChangesCallbacks.length > 0
+      || this.appLogCallbacks.length > 0;
   }
 
   private scheduleReconnect() {
@@ -194,12 +422,16 @@ class WebSocketManager {
     this.pendingPtySubscriptions.add(sessionId);
     console.log('[ws] subscribePty:', sessionId, 'connected:', this.connected);
     this.sendJson({ type: 'pty_subscribe', sessionId });
+    if (this.useEventStream) this.replayPtySessions.add(sessionId);
+    this.refreshEventStream();
   }
 
   unsubscribePty(sessionId: string) {
     this.ptyCallbacks.delete(sessionId);
     this.pendingPtySubscriptions.delete(sessionId);
+    this.replayPtySessions.delete(sessionId);
     this.sendJson({ type: 'pty_unsubscribe', sessionId });
+    this.refreshEventStream();
   }
 
   writePty(sessionId: string, data: string) {
@@ -208,9 +440,14 @@ class WebSocketManager {
 
   subscribeTerminalState(workspacePath: string, worktreeName: string, callback: TerminalStateCallback) {
     this.terminalStateCallbacks.push(callback);
+    this.pendingTerminalStateSubscription = { workspacePath, worktreeName };
     this.sendJson({ type: 'subscribe_terminal_state', workspacePath, worktreeName });
+    this.refreshEventStream();
     return () => {
       this.terminalStateCallbacks = this.terminalStateCallbacks.filter(cb => cb !== callback);
+      if (this.terminalStateCallbacks.length === 0) {
+        this.pendingTerminalStateSubscription = null;
+      }
     };
   }
 
@@ -233,10 +470,62 @@ class WebSocketManager {
     });
   }
 
+  /** Follow the presenter's focused worktree/project (presentation mode) */
+  subscribeFocus(workspacePath: string, callback: FocusCallback) {
+    this.focusCallbacks.push(callback);
+    this.sendJson({ type: 'subscribe_focus', workspacePath });
+    return () => {
+      this.focusCallbacks = this.focusCallbacks.filter(cb => cb !== callback);
+      if (this.focusCallbacks.length === 0) {
+        this.sendJson({ type: 'unsubscribe_focus' });
+      }
+    };
+  }
+
+  /**
+   * Live file changes (debounced, gitignore-aware) for one worktree.
+   * One worktree per connection: subscribing to another replaces the previous one.
+   */
+  subscribeWorktreeChanges(worktreeName: string, callback: WorktreeChangesCallback) {
+    if (this.pendingWorktreeChangesSubscription !== worktreeName) {
+      this.worktreeChangesCallbacks = [];
+    }
+    this.worktreeChangesCallbacks.push(callback);
+    this.pendingWorktreeChangesSubscription = worktreeName;
+    this.sendJson({ type: 'subscribe_worktree_changes', worktreeName });
+    return () => {
+      this.worktreeChangesCallbacks = this.worktreeChangesCallbacks.filter(cb => cb !== callback);
+      if (this.worktreeChangesCallbacks.length === 0 && this.pendingWorktreeChangesSubscription === worktreeName) {
+        this.pendingWorktreeChangesSubscription = null;
+        this.sendJson({ type: 'unsubscribe_worktree_changes' });
+      }
+    };
+  }
+
+  /**
+   * Tail the host's app log (last `lines` lines), then follow new lines.
+   * Only the host and admin sessions are allowed; others get an error entry.
+   */
+  subscribeAppLogs(lines: number, callback: AppLogCallback) {
+    this.appLogCallbacks.push(callback);
+    this.pendingAppLogLines = lines;
+    this.sendJson({ type: 'subscribe_app_logs', lines });
+    this.refreshEventStream();
+    return () => {
+      this.appLogCallbacks = this.appLogCallbacks.filter(cb => cb !== callback);
+      if (this.appLogCallbacks.length === 0) {
+        this.pendingAppLogLines = null;
+        this.sendJson({ type: 'unsubscribe_app_logs' });
+        this.refreshEventStream();
+      }
+    };
+  }
+
   subscribeLocks(workspacePath: string, onUpdate: LockCallback) {
     this.lockCallback = onUpdate;
     this.pendingLockSubscription = workspacePath;
     this.sendJson({ type: 'subscribe_locks', workspacePath });
+    this.refreshEventStream();
   }
 
   unsubscribeLocks() {
@@ -263,6 +552,51 @@ class WebSocketManager {
     };
   }
 
+  onWorktreeQueue(callback: WorktreeQueueCallback): () => void {
+    this.worktreeQueueCallbacks.push(callback);
+    return () => {
+      this.worktreeQueueCallbacks = this.worktreeQueueCallbacks.filter(cb => cb !== callback);
+    };
+  }
+
+  onWorkspaceRefreshProgress(callback: WorkspaceRefreshProgressCallback): () => void {
+    this.workspaceRefreshProgressCallbacks.push(callback);
+    return () => {
+      this.workspaceRefreshProgressCallbacks = this.workspaceRefreshProgressCallbacks.filter(cb => cb !== callback);
+    };
+  }
+
+  onRunningProcesses(callback: RunningProcessesCallback): () => void {
+    this.runningProcessesCallbacks.push(callback);
+    return () => {
+      this.runningProcessesCallbacks = this.runningProcessesCallbacks.filter(cb => cb !== callback);
+    };
+  }
+
+  /** A webhook reported a push; the backend has already fetched the project */
+  onGitRemoteUpdated(callback: GitRemoteUpdatedCallback): () => void {
+    this.gitRemoteUpdatedCallbacks.push(callback);
+    return () => {
+      this.gitRemoteUpdatedCallbacks = this.gitRemoteUpdatedCallbacks.filter(cb => cb !== callback);
+    };
+  }
+
+  /** Worktree or project directories, HEAD/index files or the workspace config changed on disk */
+  onWorkspaceFsChanged(callback: WorkspaceFsChangedCallback): () => void {
+    this.workspaceFsChangedCallbacks.push(callback);
+    return () => {
+      this.workspaceFsChangedCallbacks = this.workspaceFsChangedCallbacks.filter(cb => cb !== callback);
+    };
+  }
+
+  /** HEAD or index of one worktree project changed; refresh that project only */
+  onWorktreeStatusChanged(callback: WorktreeStatusChangedCallback): () => void {
+    this.worktreeStatusChangedCallbacks.push(callback);
+    return () => {
+      this.worktreeStatusChangedCallbacks = this.worktreeStatusChangedCallbacks.filter(cb => cb !== callback);
+    };
+  }
+
   onConnectionStateChange(callback: ConnectionStateCallback): () => void {
     this.connectionStateCallbacks.push(callback);
     // Immediately notify current state
@@ -292,12 +626,21 @@ class WebSocketManager {
     this.lockCallback = null;
     this.pendingLockSubscription = null;
     this.terminalStateCallbacks = [];
+    this.pendingTerminalStateSubscription = null;
     this.voiceEventCallbacks = [];
     this.pendingVoiceSubscription = false;
     if (this.ws) {
       this.ws.close();
       this.ws = null;
     }
+    if (this.eventStreamTimer) {
+      clearTimeout(this.eventStreamTimer);
+      this.eventStreamTimer = null;
+    }
+    if (this.eventSource) {
+      this.eventSource.close();
+      this.eventSource = null;
+    }
     this.connected = false;
   }
 
@@ -10,6 +10,141 @@ export interface ProjectConfig {
   test_branch: string;
   merge_strategy: string;
   linked_folders: string[];
+  /** Files copied (not linked) from the main project on create / restore / sync, e.g. ".env.local" */
+  copy_rules?: CopyRule[];
+  /** gitignore-style patterns skipped by the status scanner, e.g. "coverage/" */
+  status_excludes?: string[];
+  /** Leave Git LFS pointer files instead of downloading objects (faster worktree creation) */
+  skip_lfs?: boolean;
+  /** When non-empty, worktrees only check out these directories (git sparse-checkout) */
+  sparse_paths?: string[];
+  /** Remote used for fetching the base branch, pushing and ahead/behind (defaults to "origin") */
+  remote?: string;
+  /** Project-level quick commands, run in this project's directory inside the worktree */
+  quick_commands?: QuickCommand[];
+  /** Named tasks (name → command), e.g. { dev: "pnpm dev" }; each run opens a new terminal */
+  tasks?: Record<string, string>;
+  /** Named terminals (e.g. "web dev server") opened from this project with a startup command */
+  terminal_presets?: TerminalPreset[];
+  /** Shell for terminals in this project's directory, overriding the workspace shell */
+  shell?: ShellConfig | null;
+  /** Environment variables for terminals in this project's directory */
+  env?: Record<string, string>;
+}
+
+/** A file or directory copied into each worktree, relative to the project directory */
+export interface CopyRule {
+  path: string;
+  /** What to do when the worktree already has the file; defaults to "skip" */
+  overwrite?: CopyOverwritePolicy;
+}
+
+export type CopyOverwritePolicy = 'skip' | 'if_newer' | 'always';
+
+/** A named terminal of a project: opened in a new tab labelled with its name, running `command` */
+export interface TerminalPreset {
+  name: string;
+  /** Startup command; empty just opens the terminal */
+  command?: string;
+  /** Subdirectory of the project directory to start in, e.g. "packages/web" */
+  cwd?: string | null;
+}
+
+/** Terminal shell: a program name looked up in PATH (or an absolute path) plus its arguments */
+export interface ShellConfig {
+  program: string;
+  args?: string[];
+}
+
+export type EnvironmentCheckStatus = 'ok' | 'warning' | 'error';
+
+export interface EnvironmentCheck {
+  /** git / gh / credential_helper / symlink / disk_space */
+  id: string;
+  status: EnvironmentCheckStatus;
+  detail: string;
+  fix: string | null;
+}
+
+export interface EnvironmentReport {
+  checks: EnvironmentCheck[];
+  /** No error-level check failed */
+  ready: boolean;
+}
+
+/** Terminal resource limits; 0 = unlimited */
+export interface PtyLimits {
+  max_sessions: number;
+  /** Close terminals without input or output for this many minutes */
+  idle_timeout_mins: number;
+}
+
+/** Token-bucket limits for remote clients, per logged-in session (per IP otherwise) */
+export interface RateLimitConfig {
+  enabled: boolean;
+  /** Sustained API calls per second; up to `api_burst` at once */
+  api_requests_per_sec: number;
+  api_burst: number;
+  /** Terminal input messages per second over the WebSocket */
+  pty_writes_per_sec: number;
+  pty_write_burst: number;
+}
+
+/** Cloudflare Tunnel via cloudflared; without a token a quick trycloudflare.com tunnel is used */
+export interface CloudflareTunnelConfig {
+  /** Show the Cloudflare toggle in the share bar */
+  enabled: boolean;
+  /** cloudflared executable, looked up on PATH when empty */
+  binary_path?: string | null;
+  /** Named tunnel token; its public hostname is routed in the Cloudflare dashboard */
+  token?: string | null;
+  hostname?: string | null;
+}
+
+/** frp tunnel via frpc and a self-hosted frps: TCP proxy when `remote_port` is set, else HTTP on `custom_domain` */
+export interface FrpTunnelConfig {
+  /** Show the frp toggle in the share bar */
+  enabled: boolean;
+  /** frpc executable, looked up on PATH when empty */
+  binary_path?: string | null;
+  server_addr: string;
+  server_port: number;
+  token?: string | null;
+  remote_port?: number | null;
+  custom_domain?: string | null;
+  /** Public URL shown for the tunnel; derived from the proxy when empty */
+  public_url?: string | null;
+}
+
+/** Certificate the share server uses instead of a self-signed one */
+export interface CustomTlsConfig {
+  /** PEM certificate chain, or a .p12 / .pfx bundle */
+  cert_path: string;
+  /** PEM private key; not needed for PKCS#12 */
+  key_path?: string | null;
+  /** PKCS#12 password */
+  password?: string | null;
+}
+
+/** Build server terminals can be opened on over SSH (uses the system ssh client and keys) */
+export interface SshHost {
+  name: string;
+  /** Hostname, IP or alias from ~/.ssh/config */
+  host: string;
+  user?: string | null;
+  port?: number | null;
+  identity_file?: string | null;
+  /** Remote directory new terminals start in; login directory when unset */
+  cwd?: string | null;
+}
+
+export interface ProxyConfig {
+  http_proxy: string | null;
+  https_proxy: string | null;
+  /** Comma-separated hosts/suffixes that bypass the proxy */
+  no_proxy: string | null;
+  /** PEM file with extra CA certificates (corporate TLS inspection) */
+  ca_cert_path: string | null;
 }
 
 export interface WorkspaceConfig {
@@ -17,12 +152,301 @@ export interface WorkspaceConfig {
   worktrees_dir: string;
   projects: ProjectConfig[];
   linked_workspace_items: string[];
+  /** Uncommitted files matching these patterns don't block archiving */
+  archive_ignore_patterns?: string[];
+  /** What to do when a link target is already a real directory (default "skip") */
+  existing_dir_policy?: ExistingDirPolicy;
+  /** Lock a worktree while a client types in its terminals; release after idle_secs */
+  auto_lock?: AutoLockConfig;
+  /** Warn when all worktrees together exceed this many MB (null = off) */
+  disk_quota_mb?: number | null;
+  /** Periodically fetch base branches and flag worktrees too far behind */
+  base_update?: BaseUpdateConfig;
+  /** One-click terminal commands run inside a worktree */
+  quick_commands?: QuickCommand[];
+  /** Lifecycle hooks, run in order for their event */
+  hooks?: HookConfig[];
+  /** Give each worktree its own block of dev-server ports */
+  port_allocation?: PortAllocationConfig;
+  /** Kill every process working inside a worktree before archiving or deleting it */
+  kill_processes_on_archive?: boolean;
+  /** Shell for terminals; the system default when unset or not installed */
+  shell?: ShellConfig | null;
+  /** Ticket key patterns matched against worktree / branch names, first match wins */
+  issue_trackers?: IssueTrackerConfig[];
+  /** URLs that receive a JSON POST after worktree lifecycle events */
+  webhooks?: WebhookConfig[];
+}
+
+export type IssueProvider = 'none' | 'jira' | 'linear';
+
+export interface IssueTrackerConfig {
+  /** Regex; the first capture group (or the whole match) is the ticket key */
+  pattern: string;
+  /** Ticket link, `{key}` is replaced with the key */
+  url_template: string;
+  /** 'jira' / 'linear' also fetch the ticket's title and status (default 'none') */
+  provider?: IssueProvider;
+  /** Jira API base, e.g. https://acme.atlassian.net; defaults to the link template's host */
+  api_base_url?: string | null;
+}
+
+export type NotifierProvider = 'slack' | 'discord';
+
+export type NotifyEvent = 'merge_to_test_succeeded' | 'merge_to_test_failed' | 'worktree_archived' | 'share_started';
+
+/** Desktop only: Slack / Discord incoming webhook that receives chat messages */
+export interface NotifierConfig {
+  provider: NotifierProvider;
+  webhook_url: string;
+  /** Only these events (empty = all) */
+  events?: NotifyEvent[];
+}
+
+/** Dev container of a project; the other fields are meaningless when config_path is null */
+export interface DevcontainerStatus {
+  config_path: string | null;
+  container_id: string | null;
+  /** Docker container state: running / exited / ... */
+  state: string | null;
+  /** Whether the devcontainer CLI is installed; without it VS Code builds the container */
+  cli_available: boolean;
+}
+
+/** A container of a worktree project's compose stack (`docker compose ps`) */
+export interface ComposeService {
+  name: string;
+  service: string;
+  /** running / exited / ... */
+  state: string;
+  /** e.g. "Up 5 minutes" */
+  status: string;
+}
+
+/** Compose stack of a worktree project; project_name is unique per worktree so stacks don't collide */
+export interface ComposeStatus {
+  compose_file: string | null;
+  project_name: string;
+  services: ComposeService[];
+}
+
+/** Desktop only: editor added next to the built-ins; a built-in with the same name is replaced */
+export interface CustomEditor {
+  name: string;
+  /** macOS: tried first via `open -a` */
+  app_name?: string | null;
+  /** CLI command, e.g. zed, subl, or a terminal emulator running nvim */
+  command?: string | null;
+  /** Argument template, `{path}` is replaced with the directory; empty = just the directory */
+  args?: string[];
+  /** Template when opening a file, with `{path}`, `{file}` and `{line}`; empty = just the file */
+  file_args?: string[];
+}
+
+/** Desktop only: credentials for fetching Jira / Linear ticket details */
+export interface IssueTrackerAuth {
+  /** Jira Cloud account email; without it jira_token is sent as a Bearer token (Server PAT) */
+  jira_email?: string | null;
+  jira_token?: string | null;
+  linear_api_key?: string | null;
+}
+
+/** Ticket found in a worktree's name or branch names */
+export interface WorktreeIssue {
+  key: string;
+  url: string;
+  provider: IssueProvider;
+  title: string | null;
+  status: string | null;
+  /** Why title / status couldn't be fetched; the link still works */
+  error: string | null;
+}
+
+/** Workspace config with its version; pass `etag` back as `baseEtag` when saving */
+export interface VersionedWorkspaceConfig {
+  config: WorkspaceConfig;
+  etag: string;
+}
+
+export interface QuickCommand {
+  label: string;
+  command: string;
+  /** Project directory to run in; null/absent = worktree root */
+  project?: string | null;
+  /** Whether clients connected through sharing may run it (admin role only; default true) */
+  allow_remote?: boolean;
+}
+
+/** on_archive / on_delete run before the operation and abort it on failure; the rest run afterwards */
+export type HookEvent = 'on_create' | 'on_archive' | 'on_restore' | 'on_delete' | 'on_deploy_to_main';
+
+/**
+ * A lifecycle hook. Commands get WM_HOOK_EVENT, WM_WORKSPACE_NAME/PATH, WM_WORKTREE_NAME/PATH
+ * and WM_PROJECTS (plus WM_PROJECT_NAME/PATH for per-project hooks) in their environment.
+ */
+export interface HookConfig {
+  event: HookEvent;
+  command: string;
+  /** Run once in each project directory instead of once in the worktree root */
+  per_project?: boolean;
+  /** With per_project: only these projects (empty = all) */
+  projects?: string[];
+  /** Defaults to 300 seconds */
+  timeout_secs?: number | null;
+}
+
+/**
+ * Outgoing webhook. The body is `{ event, timestamp, workspace: { name, path },
+ * worktree: { name, path }, projects }`; delivery failures are only logged.
+ */
+export interface WebhookConfig {
+  url: string;
+  /** Only these events (empty = all) */
+  events?: HookEvent[];
+  /** Signs the body: X-Worktree-Manager-Signature-256: sha256=<HMAC-SHA256 hex> */
+  secret?: string | null;
+}
+
+export interface HookRun {
+  event: HookEvent;
+  command: string;
+  project: string | null;
+  cwd: string;
+  success: boolean;
+  exit_code: number | null;
+  duration_ms: number;
+  /** Tail of stdout + stderr, or the error when the command couldn't run */
+  output: string;
+}
+
+export interface QuickCommandRun {
+  session_id: string;
+  /** Terminal tab key to activate (same form as project paths) */
+  terminal_path: string;
+}
+
+export interface BaseUpdateConfig {
+  enabled: boolean;
+  behind_threshold: number;
+  interval_mins: number;
+}
+
+export interface BaseUpdate {
+  worktree_name: string;
+  project_name: string;
+  /** Workspace-relative project path, pass to syncWithBaseBranch */
+  path: string;
+  base_branch: string;
+  behind: number;
+}
+
+/** CI result of a branch head commit (GitHub checks / GitLab pipeline) */
+export interface CiStatus {
+  state: 'success' | 'failure' | 'pending' | 'none';
+  commit: string;
+  total: number;
+  failed: number;
+  pending: number;
+  url: string | null;
+}
+
+/** A process started inside one of the workspace's terminals (dev server, task, ...) */
+export interface RunningProcess {
+  pid: number;
+  worktree_name: string;
+  session_id: string;
+  command: string;
+  /** Not sampled on Windows */
+  cpu_percent: number | null;
+  memory_kb: number | null;
+  uptime_secs: number | null;
+}
+
+/** A process ended by killWorktreeProcesses */
+export interface KilledProcess {
+  pid: number;
+  name: string;
+  command: string;
+  cwd: string;
+  /** Ignored SIGTERM and was killed after the grace period */
+  forced: boolean;
+  killed: boolean;
+}
+
+/** asciicast v2 recording of a terminal, stored in the worktree's .recordings directory */
+export interface TerminalRecording {
+  file_name: string;
+  path: string;
+  /** null for main-workspace terminals */
+  worktree_name: string | null;
+  title: string | null;
+  started_at: string | null;
+  duration_secs: number;
+  size_bytes: number;
+  /** Terminal session still writing this recording */
+  active_session_id: string | null;
+}
+
+export interface OutputMatch {
+  /** Line index from the start of the retained output */
+  line: number;
+  /** 0 = last line */
+  lines_from_end: number;
+  /** Line text with escape sequences stripped */
+  text: string;
+}
+
+export interface OutputSearchResult {
+  total_lines: number;
+  matches: OutputMatch[];
+  /** More lines matched; only the newest were returned */
+  truncated: boolean;
+}
+
+/** Terminal recreated from the previous app run */
+export interface RestoredTerminal {
+  session_id: string;
+  /** Terminal tab key (duplicated tabs carry a `#` suffix) */
+  terminal_path: string;
+  /** null for main-workspace terminals */
+  worktree_name: string | null;
+}
+
+/** A distinct command entered in a worktree's terminals */
+export interface CommandHistoryEntry {
+  command: string;
+  /** Working directory of the latest run */
+  cwd: string;
+  /** null for main-workspace terminals */
+  worktree_name: string | null;
+  last_run_at: string;
+  count: number;
+}
+
+export interface PortAllocationConfig {
+  enabled: boolean;
+  base_port: number;
+  ports_per_worktree: number;
+}
+
+export interface AutoLockConfig {
+  enabled: boolean;
+  idle_secs: number;
+}
+
+export interface ConfigWarning {
+  project_name: string | null;
+  message: string;
 }
 
+export type ExistingDirPolicy = 'skip' | 'replace' | 'merge';
+
 // Project status types
 export interface ProjectStatus {
   name: string;
   path: string;
+  /** Workspace-relative, '/'-separated; accepted anywhere a path argument is */
+  rel_path: string;
   current_branch: string;
   base_branch: string;
   test_branch: string;
@@ -31,11 +455,14 @@ export interface ProjectStatus {
   is_merged_to_test: boolean;
   ahead_of_base: number;
   behind_base: number;
+  /** Most modified files differ only in line endings (autocrlf mismatch) */
+  line_ending_mismatch?: boolean;
 }
 
 export interface MainProjectStatus {
   name: string;
   path: string;
+  rel_path: string;
   current_branch: string;
   has_uncommitted: boolean;
   uncommitted_count: number;
@@ -57,13 +484,167 @@ export interface MainWorkspaceStatus {
 export interface WorktreeListItem {
   name: string;
   path: string;
+  rel_path: string;
   is_archived: boolean;
   projects: ProjectStatus[];
+  // Only populated for archived entries (no git status is run for them)
+  size_bytes: number | null;
+  size_display: string | null;
+  archived_at: string | null;
+  /** Contents of .worktree-meta.json, null when the worktree has none */
+  meta: WorktreeMeta | null;
+}
+
+export type WorktreeSortBy = 'name' | 'last_commit' | 'status';
+
+/** Arguments of list_worktrees_page; every field is optional */
+export interface WorktreeListQuery {
+  include_archived?: boolean;
+  labels?: string[];
+  sort_by?: WorktreeSortBy;
+  offset?: number;
+  limit?: number;
+  /** Skip git status; fetch it per worktree with getWorktree when expanded */
+  lightweight?: boolean;
+}
+
+export interface WorktreePage {
+  items: WorktreeListItem[];
+  /** Matching worktrees before pagination */
+  total: number;
+  offset: number;
+}
+
+export interface WorktreeMeta {
+  description: string;
+  ticket_url: string;
+  /** Lowercased, deduplicated */
+  labels: string[];
+  created_at: string | null;
+  /** Assigned the first time a terminal or get_worktree_ports needs it */
+  ports?: PortRange | null;
+  /** Environment variables for the worktree's terminals, overriding project-level ones */
+  env?: Record<string, string>;
+}
+
+/** Ports [start, start + count) */
+export interface PortRange {
+  start: number;
+  count: number;
+}
+
+export interface PortConflict {
+  port: number;
+  /** Another worktree whose range contains this port */
+  worktree_name: string | null;
+  /** Something is already listening (possibly this worktree's own dev server) */
+  in_use: boolean;
+}
+
+export interface WorktreePorts {
+  worktree_name: string;
+  start: number;
+  end: number;
+  /** PORT each project's terminals get, in config order */
+  project_ports: Record<string, number>;
+  /** Variables exported to the worktree's terminals */
+  env: Record<string, string>;
+  conflicts: PortConflict[];
 }
 
 export interface CreateProjectRequest {
   name: string;
   base_branch: string;
+  /** Overrides the project's sparse_paths for this worktree */
+  sparse_paths?: string[];
+  /** Overrides the project's remote; new branches start from <remote>/<base_branch> */
+  remote?: string;
+}
+
+export type LinkStatus = 'created' | 'skipped' | 'failed';
+
+export interface LinkResult {
+  project: string | null;
+  item: string;
+  status: LinkStatus;
+  reason: string | null;
+}
+
+export interface SyncLinksResult {
+  worktree: string;
+  link_results: LinkResult[];
+}
+
+export interface UnregisteredWorktree {
+  worktree: string;
+  path: string;
+  reason: string;
+}
+
+export interface RepairWorktreesResult {
+  project_name: string;
+  /** Stale registrations removed by `git worktree prune` */
+  pruned: string[];
+  /** Worktree names registered again with the main repository */
+  reregistered: string[];
+  unresolved: UnregisteredWorktree[];
+  error: string | null;
+}
+
+export interface BranchDeleteResult {
+  project_name: string;
+  local_deleted: boolean;
+  remote_exists: boolean;
+  remote_deleted: boolean;
+  error: string | null;
+}
+
+export interface DeleteArchivedResult {
+  name: string;
+  branch: string;
+  delete_remote: boolean;
+  projects: BranchDeleteResult[];
+  hook_runs: HookRun[];
+}
+
+export interface WorkspacePaths {
+  root: string;
+  config: string;
+  worktrees_dir: string;
+  occupation_state: string;
+}
+
+export interface AppPaths {
+  global_config: string;
+  global_config_dir: string;
+  log_dir: string | null;
+  workspace: WorkspacePaths | null;
+}
+
+export interface BranchRenameProjectResult {
+  project_name: string;
+  renamed: boolean;
+  pushed: boolean;
+  old_remote_deleted: boolean;
+  error: string | null;
+}
+
+export interface RenameWorktreeResult {
+  old_name: string;
+  new_name: string;
+  projects: BranchRenameProjectResult[];
+}
+
+export interface CreateWorktreeResult {
+  path: string;
+  rel_path: string;
+  link_results: LinkResult[];
+  hook_runs: HookRun[];
+}
+
+export interface RestoreWorktreeResult {
+  link_results: LinkResult[];
+  hook_runs: HookRun[];
 }
 
 export interface AddProjectToWorktreeRequest {
@@ -78,12 +659,25 @@ export interface BranchStatus {
   branch_name: string;
   has_uncommitted: boolean;
   uncommitted_count: number;
+  staged_count: number;
+  modified_count: number;
+  untracked_count: number;
+  ignorable_count: number;
   is_pushed: boolean;
   unpushed_commits: number;
   has_merge_request: boolean;
   remote_url: string;
 }
 
+/** Per-worktree result of archive_worktrees */
+export interface BulkArchiveResult {
+  name: string;
+  outcome: 'archived' | 'blocked' | 'failed';
+  /** Why the worktree was blocked or failed */
+  reasons: string[];
+  warnings: string[];
+}
+
 export interface WorktreeArchiveStatus {
   name: string;
   can_archive: boolean;
@@ -92,8 +686,182 @@ export interface WorktreeArchiveStatus {
   projects: BranchStatus[];
 }
 
-// Editor types
-export type EditorType = 'vscode' | 'cursor' | 'antigravity' | 'idea';
+// Sync advisor types
+export type SyncAdvice = 'up_to_date' | 'safe_to_sync' | 'conflicts_expected' | 'blocked';
+
+export interface ProjectSyncAdvice {
+  project_name: string;
+  base_branch: string;
+  advice: SyncAdvice;
+  behind: number;
+  conflicted_files: string[];
+  reason: string | null;
+}
+
+export interface WorktreeSyncAdvice {
+  worktree_name: string;
+  projects: ProjectSyncAdvice[];
+}
+
+/** Result of refresh_workspace: fresh worktree list and main workspace status */
+export interface WorkspaceRefreshResult {
+  worktrees: WorktreeListItem[];
+  main_workspace: MainWorkspaceStatus;
+  elapsed_ms: number;
+}
+
+export interface ProjectSyncResult {
+  project_name: string;
+  success: boolean;
+  message: string;
+}
+
+// Workspace manifest (machine migration)
+export interface ManifestProject {
+  name: string;
+  branch: string;
+  base_branch: string;
+  has_uncommitted: boolean;
+  ahead_of_base: number;
+}
+
+export interface ManifestWorktree {
+  name: string;
+  is_archived: boolean;
+  projects: ManifestProject[];
+}
+
+export interface BundleProject {
+  name: string;
+  branch: string;
+  base_branch: string;
+  /** Commit the recipient must already have; null when the bundle holds full history */
+  base_commit: string | null;
+  head_commit: string;
+  commits: number;
+  /** Bundle file name next to manifest.json; null when there was nothing to bundle */
+  bundle: string | null;
+  has_uncommitted: boolean;
+  error: string | null;
+}
+
+export interface WorktreeBundleManifest {
+  version: number;
+  workspace_name: string;
+  worktree_name: string;
+  exported_at: string;
+  projects: BundleProject[];
+}
+
+export interface BundleExportResult {
+  output_path: string;
+  manifest: WorktreeBundleManifest;
+}
+
+export interface BundleImportResult {
+  worktree_name: string;
+  path: string;
+  projects: { name: string; commits: number; skipped: string | null }[];
+  link_results: LinkResult[];
+}
+
+export interface WorkspaceManifest {
+  version: number;
+  workspace_name: string;
+  exported_at: string;
+  worktrees: ManifestWorktree[];
+}
+
+export interface ManifestImportResult {
+  worktree_name: string;
+  created: boolean;
+  reason: string | null;
+}
+
+// File diff (unified hunks)
+export interface FileDiffLine {
+  /** '+' added, '-' removed, ' ' context */
+  origin: '+' | '-' | ' ';
+  old_lineno: number | null;
+  new_lineno: number | null;
+  content: string;
+}
+
+export interface FileDiffHunk {
+  header: string;
+  old_start: number;
+  old_lines: number;
+  new_start: number;
+  new_lines: number;
+  lines: FileDiffLine[];
+}
+
+export interface FileDiff {
+  file: string;
+  old_file: string | null;
+  is_binary: boolean;
+  truncated: boolean;
+  hunks: FileDiffHunk[];
+}
+
+// Disk usage / quota
+export interface WorktreeDiskUsage {
+  name: string;
+  is_archived: boolean;
+  size_bytes: number;
+  size_display: string;
+}
+
+export interface DiskUsageReport {
+  workspace_path: string;
+  total_bytes: number;
+  total_display: string;
+  quota_bytes: number | null;
+  over_quota: boolean;
+  /** Largest first */
+  worktrees: WorktreeDiskUsage[];
+  /** Active worktrees without uncommitted changes, largest first */
+  archive_candidates: string[];
+}
+
+// Command palette action registry
+export interface ActionArg {
+  /** Same name as the invoke / HTTP argument */
+  name: string;
+  kind: 'string' | 'boolean' | 'number' | 'string[]' | 'path' | 'object';
+  required: boolean;
+  description: string;
+}
+
+export interface ActionInfo {
+  /** Tauri command name, also the /api/<id> route */
+  id: string;
+  title: string;
+  category: string;
+  args: ActionArg[];
+  enabled: boolean;
+  disabled_reason: string | null;
+}
+
+// Merge conflicts
+export interface ConflictFile {
+  path: string;
+  /** null when added on both sides, or binary / too large */
+  base: string | null;
+  ours: string | null;
+  theirs: string | null;
+  is_binary: boolean;
+}
+
+export interface MergeConflictState {
+  operation: 'merge' | 'rebase' | 'cherry-pick' | null;
+  files: ConflictFile[];
+}
+
+export type ConflictResolution = 'ours' | 'theirs' | 'mark_resolved';
+
+// Editor types: a built-in id ('vscode' | 'cursor' | 'antigravity' | 'idea') or a custom editor's name
+export type EditorType = string;
 
 export interface EditorConfig {
   id: EditorType;
@@ -143,6 +911,21 @@ export interface ScannedFolder {
   is_recommended: boolean;
 }
 
+// Dirty-state guard for risky main workspace operations
+export interface DirtyProject {
+  project_name: string;
+  staged_count: number;
+  modified_count: number;
+  untracked_count: number;
+  files: string[];
+}
+
+export interface DirtyReport {
+  projects: DirtyProject[];
+  /** Pass back as dirtyAck / dirty_ack to confirm exactly this state */
+  fingerprint: string;
+}
+
 // Deploy to main workspace
 export interface MainWorkspaceOccupation {
   worktree_name: string;
@@ -154,4 +937,5 @@ export interface DeployToMainResult {
   success: boolean;
   switched_projects: string[];
   failed_projects: { project_name: string; error: string }[];
+  hook_runs: HookRun[];
 }